@@ -544,6 +544,23 @@ impl ActionLog {
         tracked_buffer.schedule_diff_update(ChangeAuthor::Agent, cx);
     }
 
+    /// Mark a buffer as edited by agent since the given version, for edits made on the agent's
+    /// behalf to buffers it may not have read, such as a language server rename.
+    pub fn buffer_edited_since(
+        &mut self,
+        buffer: Entity<Buffer>,
+        version: &clock::Global,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.tracked_buffers.contains_key(&buffer) {
+            let diff_base = buffer.read(cx).rope_for_version(version);
+            let tracked_buffer = self.track_buffer_internal(buffer.clone(), false, cx);
+            tracked_buffer.diff_base = diff_base.clone();
+            tracked_buffer.last_seen_base = diff_base;
+        }
+        self.buffer_edited(buffer, cx);
+    }
+
     pub fn will_delete_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
         let tracked_buffer = self.track_buffer_internal(buffer.clone(), false, cx);
         match tracked_buffer.status {
@@ -1088,6 +1105,45 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test]
+    async fn test_edits_since_version(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        // Edit the buffer without the action log knowing about it beforehand.
+        let version = buffer.read_with(cx, |buffer, _| buffer.version());
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(Point::new(1, 0)..Point::new(1, 3), "DEF")], None, cx)
+        });
+        action_log.update(cx, |log, cx| {
+            log.buffer_edited_since(buffer.clone(), &version, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            unreviewed_hunks(&action_log, cx),
+            vec![(
+                buffer.clone(),
+                vec![HunkStatus {
+                    range: Point::new(1, 0)..Point::new(2, 0),
+                    diff_status: DiffHunkStatusKind::Modified,
+                    old_text: "def\n".into(),
+                }],
+            )]
+        );
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_deletions(cx: &mut TestAppContext) {
         init_test(cx);
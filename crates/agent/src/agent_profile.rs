@@ -45,7 +45,11 @@ impl AgentProfile {
                 .map(|profile| profile.enable_all_context_servers)
                 .unwrap_or_default(),
             context_servers: base_profile
-                .map(|profile| profile.context_servers)
+                .as_ref()
+                .map(|profile| profile.context_servers.clone())
+                .unwrap_or_default(),
+            tool_permissions: base_profile
+                .map(|profile| profile.tool_permissions)
                 .unwrap_or_default(),
         };
 
@@ -107,7 +111,7 @@ impl AgentProfile {
 
 #[cfg(test)]
 mod tests {
-    use agent_settings::ContextServerPreset;
+    use agent_settings::{ContextServerPreset, ToolPermissions};
     use assistant_tool::ToolRegistry;
     use collections::IndexMap;
     use gpui::SharedString;
@@ -251,6 +255,7 @@ mod tests {
                     tools: IndexMap::default(),
                     enable_all_context_servers: false,
                     context_servers: IndexMap::from_iter([("mcp".into(), context_server_preset())]),
+                    tool_permissions: ToolPermissions::default(),
                 },
             );
             AgentSettings::override_global(agent_settings, cx);
@@ -1,4 +1,5 @@
 use super::*;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::Result;
 use gpui::{App, SharedString, Task};
 use std::future;
@@ -122,7 +123,13 @@ impl AgentTool for ToolRequiringPermission {
         event_stream: ToolCallEventStream,
         cx: &mut App,
     ) -> Task<Result<String>> {
-        let authorize = event_stream.authorize("Authorize?", cx);
+        let authorize = event_stream.authorize_with_rules(
+            "Authorize?",
+            ToolPermissionRequest::tool(Self::name()),
+            None,
+            ToolPermission::Ask,
+            cx,
+        );
         cx.foreground_executor().spawn(async move {
             authorize.await?;
             Ok("Allowed".to_string())
@@ -1,8 +1,9 @@
 use crate::{
-    ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
-    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
-    ListDirectoryTool, MovePathTool, NowTool, OpenTool, ReadFileTool, SystemPromptTemplate,
-    Template, Templates, TerminalTool, ThinkingTool, WebSearchTool,
+    CodeActionTool, ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel,
+    DbThread, DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool,
+    FindReferencesTool, GoToDefinitionTool, GrepTool, HoverTool, ListDirectoryTool, MovePathTool,
+    NowTool, OpenTool, ReadFileTool, RenameSymbolTool, SymbolsTool, SystemPromptTemplate, Template,
+    Templates, TerminalTool, ThinkingTool, WebSearchTool,
 };
 use acp_thread::{MentionUri, UserMessageId};
 use action_log::ActionLog;
@@ -10,7 +11,8 @@ use agent::thread::{GitState, ProjectSnapshot, WorktreeSnapshot};
 use agent_client_protocol as acp;
 use agent_settings::{
     AgentProfileId, AgentProfileSettings, AgentSettings, CompletionMode,
-    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT,
+    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT, ToolPermission,
+    ToolPermissionRequest,
 };
 use anyhow::{Context as _, Result, anyhow};
 use assistant_tool::adapt_schema_to_format;
@@ -37,13 +39,13 @@ use language_model::{
     LanguageModelToolUseId, Role, SelectedModel, StopReason, TokenUsage,
 };
 use project::{
-    Project,
+    Project, ProjectPath,
     git_store::{GitStore, RepositoryState},
 };
 use prompt_store::ProjectContext;
 use schemars::{JsonSchema, Schema};
 use serde::{Deserialize, Serialize};
-use settings::{Settings, update_settings_file};
+use settings::{Settings, SettingsLocation, update_settings_file};
 use smol::stream::StreamExt;
 use std::{
     collections::BTreeMap,
@@ -754,6 +756,7 @@ impl Thread {
             let tool_event_stream = ToolCallEventStream::new(
                 tool_use.id.clone(),
                 stream.clone(),
+                self.profile_id.clone(),
                 Some(self.project.read(cx).fs().clone()),
             );
             tool.replay(tool_use.input.clone(), output, tool_event_stream, cx)
@@ -1041,6 +1044,10 @@ impl Thread {
         cx: &mut Context<Self>,
     ) {
         let language_registry = self.project.read(cx).languages().clone();
+        self.add_tool(CodeActionTool::new(
+            self.project.clone(),
+            self.action_log.clone(),
+        ));
         self.add_tool(CopyPathTool::new(self.project.clone()));
         self.add_tool(CreateDirectoryTool::new(self.project.clone()));
         self.add_tool(DeletePathTool::new(
@@ -1055,7 +1062,10 @@ impl Thread {
         ));
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
         self.add_tool(FindPathTool::new(self.project.clone()));
+        self.add_tool(FindReferencesTool::new(self.project.clone()));
+        self.add_tool(GoToDefinitionTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
+        self.add_tool(HoverTool::new(self.project.clone()));
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
         self.add_tool(NowTool);
@@ -1064,6 +1074,11 @@ impl Thread {
             self.project.clone(),
             self.action_log.clone(),
         ));
+        self.add_tool(RenameSymbolTool::new(
+            self.project.clone(),
+            self.action_log.clone(),
+        ));
+        self.add_tool(SymbolsTool::new(self.project.clone()));
         self.add_tool(TerminalTool::new(self.project.clone(), environment));
         self.add_tool(ThinkingTool);
         self.add_tool(WebSearchTool);
@@ -1556,8 +1571,12 @@ impl Thread {
         };
 
         let fs = self.project.read(cx).fs().clone();
-        let tool_event_stream =
-            ToolCallEventStream::new(tool_use.id.clone(), event_stream.clone(), Some(fs));
+        let tool_event_stream = ToolCallEventStream::new(
+            tool_use.id.clone(),
+            event_stream.clone(),
+            self.profile_id.clone(),
+            Some(fs),
+        );
         tool_event_stream.update_fields(acp::ToolCallUpdateFields {
             status: Some(acp::ToolCallStatus::InProgress),
             ..Default::default()
@@ -2371,6 +2390,7 @@ impl ThreadEventStream {
 pub struct ToolCallEventStream {
     tool_use_id: LanguageModelToolUseId,
     stream: ThreadEventStream,
+    profile_id: AgentProfileId,
     fs: Option<Arc<dyn Fs>>,
 }
 
@@ -2379,7 +2399,12 @@ impl ToolCallEventStream {
     pub fn test() -> (Self, ToolCallEventStreamReceiver) {
         let (events_tx, events_rx) = mpsc::unbounded::<Result<ThreadEvent>>();
 
-        let stream = ToolCallEventStream::new("test_id".into(), ThreadEventStream(events_tx), None);
+        let stream = ToolCallEventStream::new(
+            "test_id".into(),
+            ThreadEventStream(events_tx),
+            AgentProfileId::default(),
+            None,
+        );
 
         (stream, ToolCallEventStreamReceiver(events_rx))
     }
@@ -2387,11 +2412,13 @@ impl ToolCallEventStream {
     fn new(
         tool_use_id: LanguageModelToolUseId,
         stream: ThreadEventStream,
+        profile_id: AgentProfileId,
         fs: Option<Arc<dyn Fs>>,
     ) -> Self {
         Self {
             tool_use_id,
             stream,
+            profile_id,
             fs,
         }
     }
@@ -2414,10 +2441,93 @@ impl ToolCallEventStream {
             .ok();
     }
 
-    pub fn authorize(&self, title: impl Into<String>, cx: &mut App) -> Task<Result<()>> {
-        if agent_settings::AgentSettings::get_global(cx).always_allow_tool_actions {
-            return Task::ready(Ok(()));
+    /// Authorizes a tool call according to the permission rules of the thread's profile
+    /// and of the user and project settings that apply to `location`.
+    ///
+    /// When no rule applies, the call is allowed if `always_allow_tool_actions` is set and
+    /// otherwise handled according to `fallback`. When asking for confirmation, the user
+    /// can choose to always allow calls matching patterns suggested by the request.
+    pub fn authorize_with_rules(
+        &self,
+        title: impl Into<String>,
+        request: ToolPermissionRequest,
+        location: Option<ProjectPath>,
+        fallback: ToolPermission,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let settings = AgentSettings::get(
+            location.as_ref().map(|location| SettingsLocation {
+                worktree_id: location.worktree_id,
+                path: &location.path,
+            }),
+            cx,
+        );
+        let permission = settings
+            .tool_permission(&self.profile_id, &request)
+            .unwrap_or(if settings.always_allow_tool_actions {
+                ToolPermission::Allow
+            } else {
+                fallback
+            });
+
+        match permission {
+            ToolPermission::Allow => Task::ready(Ok(())),
+            ToolPermission::Deny => Task::ready(Err(anyhow!(
+                "Permission to run tool denied by the agent's tool permission settings"
+            ))),
+            ToolPermission::Ask => self.request_authorization(title.into(), Some(request), cx),
+        }
+    }
+
+    fn request_authorization(
+        &self,
+        title: String,
+        request: Option<ToolPermissionRequest>,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let allow_patterns = request
+            .as_ref()
+            .map(|request| request.suggested_allow_patterns())
+            .unwrap_or_default();
+
+        let mut options = Vec::new();
+        if !allow_patterns.is_empty() {
+            let patterns = allow_patterns
+                .iter()
+                .map(|pattern| format!("`{pattern}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            options.push(acp::PermissionOption {
+                id: acp::PermissionOptionId("always_allow_pattern".into()),
+                name: format!("Always Allow {patterns}"),
+                kind: acp::PermissionOptionKind::AllowAlways,
+                meta: None,
+            });
         }
+        options.extend([
+            acp::PermissionOption {
+                id: acp::PermissionOptionId("always_allow".into()),
+                name: if allow_patterns.is_empty() {
+                    "Always Allow".into()
+                } else {
+                    "Always Allow All Tools".into()
+                },
+                kind: acp::PermissionOptionKind::AllowAlways,
+                meta: None,
+            },
+            acp::PermissionOption {
+                id: acp::PermissionOptionId("allow".into()),
+                name: "Allow".into(),
+                kind: acp::PermissionOptionKind::AllowOnce,
+                meta: None,
+            },
+            acp::PermissionOption {
+                id: acp::PermissionOptionId("deny".into()),
+                name: "Deny".into(),
+                kind: acp::PermissionOptionKind::RejectOnce,
+                meta: None,
+            },
+        ]);
 
         let (response_tx, response_rx) = oneshot::channel();
         self.stream
@@ -2428,36 +2538,42 @@ impl ToolCallEventStream {
                         meta: None,
                         id: acp::ToolCallId(self.tool_use_id.to_string().into()),
                         fields: acp::ToolCallUpdateFields {
-                            title: Some(title.into()),
+                            title: Some(title),
                             ..Default::default()
                         },
                     },
-                    options: vec![
-                        acp::PermissionOption {
-                            id: acp::PermissionOptionId("always_allow".into()),
-                            name: "Always Allow".into(),
-                            kind: acp::PermissionOptionKind::AllowAlways,
-                            meta: None,
-                        },
-                        acp::PermissionOption {
-                            id: acp::PermissionOptionId("allow".into()),
-                            name: "Allow".into(),
-                            kind: acp::PermissionOptionKind::AllowOnce,
-                            meta: None,
-                        },
-                        acp::PermissionOption {
-                            id: acp::PermissionOptionId("deny".into()),
-                            name: "Deny".into(),
-                            kind: acp::PermissionOptionKind::RejectOnce,
-                            meta: None,
-                        },
-                    ],
+                    options,
                     response: response_tx,
                 },
             )))
             .ok();
         let fs = self.fs.clone();
         cx.spawn(async move |cx| match response_rx.await?.0.as_ref() {
+            "always_allow_pattern" => {
+                if let Some(fs) = fs.clone()
+                    && let Some(request) = request
+                {
+                    cx.update(|cx| {
+                        update_settings_file(fs, cx, move |settings, _| {
+                            let allow = settings
+                                .project
+                                .agent_tool_permissions
+                                .get_or_insert_default()
+                                .entry(request.tool_name)
+                                .or_default()
+                                .allow
+                                .get_or_insert_default();
+                            for pattern in allow_patterns {
+                                if !allow.0.contains(&pattern) {
+                                    allow.0.push(pattern);
+                                }
+                            }
+                        });
+                    })?;
+                }
+
+                Ok(())
+            }
             "always_allow" => {
                 if let Some(fs) = fs.clone() {
                     cx.update(|cx| {
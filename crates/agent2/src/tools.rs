@@ -1,3 +1,4 @@
+mod code_action_tool;
 mod context_server_registry;
 mod copy_path_tool;
 mod create_directory_tool;
@@ -6,12 +7,18 @@ mod diagnostics_tool;
 mod edit_file_tool;
 mod fetch_tool;
 mod find_path_tool;
+mod find_references_tool;
+mod go_to_definition_tool;
 mod grep_tool;
+mod hover_tool;
 mod list_directory_tool;
+mod lsp_helpers;
 mod move_path_tool;
 mod now_tool;
 mod open_tool;
 mod read_file_tool;
+mod rename_symbol_tool;
+mod symbols_tool;
 mod terminal_tool;
 mod thinking_tool;
 mod web_search_tool;
@@ -19,6 +26,7 @@ mod web_search_tool;
 /// A list of all built in tool names, for use in deduplicating MCP tool names
 pub fn default_tool_names() -> impl Iterator<Item = &'static str> {
     [
+        CodeActionTool::name(),
         CopyPathTool::name(),
         CreateDirectoryTool::name(),
         DeletePathTool::name(),
@@ -26,12 +34,17 @@ pub fn default_tool_names() -> impl Iterator<Item = &'static str> {
         EditFileTool::name(),
         FetchTool::name(),
         FindPathTool::name(),
+        FindReferencesTool::name(),
+        GoToDefinitionTool::name(),
         GrepTool::name(),
+        HoverTool::name(),
         ListDirectoryTool::name(),
         MovePathTool::name(),
         NowTool::name(),
         OpenTool::name(),
         ReadFileTool::name(),
+        RenameSymbolTool::name(),
+        SymbolsTool::name(),
         TerminalTool::name(),
         ThinkingTool::name(),
         WebSearchTool::name(),
@@ -39,6 +52,7 @@ pub fn default_tool_names() -> impl Iterator<Item = &'static str> {
     .into_iter()
 }
 
+pub use code_action_tool::*;
 pub use context_server_registry::*;
 pub use copy_path_tool::*;
 pub use create_directory_tool::*;
@@ -47,12 +61,17 @@ pub use diagnostics_tool::*;
 pub use edit_file_tool::*;
 pub use fetch_tool::*;
 pub use find_path_tool::*;
+pub use find_references_tool::*;
+pub use go_to_definition_tool::*;
 pub use grep_tool::*;
+pub use hover_tool::*;
 pub use list_directory_tool::*;
 pub use move_path_tool::*;
 pub use now_tool::*;
 pub use open_tool::*;
 pub use read_file_tool::*;
+pub use rename_symbol_tool::*;
+pub use symbols_tool::*;
 pub use terminal_tool::*;
 pub use thinking_tool::*;
 pub use web_search_tool::*;
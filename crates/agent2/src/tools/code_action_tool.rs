@@ -0,0 +1,376 @@
+use crate::tools::lsp_helpers::{
+    authorize_unpreviewed_edits, authorize_workspace_edit, record_project_transaction,
+};
+use crate::{AgentTool, ToolCallEventStream};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{App, Entity, SharedString, Task};
+use language::Point;
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Lists or applies the code actions that the language server offers for a range of lines, such as quick fixes for diagnostics, adding missing imports, or extracting code into a function.
+///
+/// Call this tool without a title first to see which actions are available, then call it again with the exact title of the action to apply. Applied edits are saved and shown to the user for review.
+///
+/// <example>
+/// To list the code actions for line 8 of `project/src/main.rs`:
+/// {
+///     "path": "project/src/main.rs",
+///     "start_line": 8
+/// }
+///
+/// To apply one of them:
+/// {
+///     "path": "project/src/main.rs",
+///     "start_line": 8,
+///     "title": "Import `std::collections::HashMap`"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CodeActionToolInput {
+    /// The path of the file to get code actions for.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the range starts.
+    pub start_line: u32,
+    /// The 1-based line number where the range ends, inclusive. Defaults to `start_line`.
+    pub end_line: Option<u32>,
+    /// The title of the code action to apply, exactly as listed. When omitted, the available actions are listed instead.
+    pub title: Option<String>,
+}
+
+pub struct CodeActionTool {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+}
+
+impl CodeActionTool {
+    pub fn new(project: Entity<Project>, action_log: Entity<ActionLog>) -> Self {
+        Self {
+            project,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for CodeActionTool {
+    type Input = CodeActionToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "code_action"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(CodeActionToolInput {
+                title: Some(title), ..
+            }) => format!("Apply code action {}", MarkdownInlineCode(&title)).into(),
+            Ok(input) => format!("List code actions in {}", MarkdownInlineCode(&input.path)).into(),
+            Err(_) => "Code action".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let Some(project_path) = self.project.read(cx).find_project_path(&input.path, cx) else {
+            return Task::ready(Err(anyhow!("Path {} not found in project", input.path)));
+        };
+        let project = self.project.clone();
+        let action_log = self.action_log.clone();
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        cx.spawn(async move |cx| {
+            let buffer = open_buffer.await?;
+            let range = buffer.read_with(cx, |buffer, _| {
+                let max_row = buffer.max_point().row;
+                let start_row = input.start_line.saturating_sub(1).min(max_row);
+                let end_row = input
+                    .end_line
+                    .map_or(start_row, |line| line.saturating_sub(1))
+                    .clamp(start_row, max_row);
+                Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row))
+            })?;
+            let actions = project
+                .update(cx, |project, cx| {
+                    project.code_actions(&buffer, range, None, cx)
+                })?
+                .await?
+                .unwrap_or_default();
+
+            let Some(title) = input.title else {
+                if actions.is_empty() {
+                    return Ok(format!("No code actions available in {}", input.path));
+                }
+                let mut output = String::from("Available code actions:\n");
+                for action in &actions {
+                    writeln!(output, "- {}", action.lsp_action.title())?;
+                }
+                return Ok(output);
+            };
+
+            let action = actions
+                .into_iter()
+                .find(|action| action.lsp_action.title() == title)
+                .with_context(|| {
+                    format!(
+                        "No code action titled `{title}` is available. Call this tool without a title to list them."
+                    )
+                })?;
+            let auth_title = format!("Apply code action `{title}`");
+
+            // Edits are checked before they're applied. Commands make their edits as they
+            // run, and remote projects can't resolve actions up front, so both ask first.
+            let is_local = project.read_with(cx, |project, _| project.is_local())?;
+            let action = if is_local {
+                project
+                    .update(cx, |project, cx| {
+                        project.resolve_code_action(&buffer, action, cx)
+                    })?
+                    .await?
+            } else {
+                action
+            };
+            let edit = action
+                .lsp_action
+                .edit()
+                .filter(|edit| edit.changes.is_some() || edit.document_changes.is_some())
+                .filter(|_| is_local)
+                .cloned();
+            let transaction = if let Some(edit) = edit {
+                cx.update(|cx| {
+                    authorize_workspace_edit(&edit, &auth_title, &project, &event_stream, cx)
+                })?
+                .await?;
+                project
+                    .update(cx, |project, cx| {
+                        project.apply_workspace_edit(edit, action.server_id, true, cx)
+                    })?
+                    .await?
+            } else {
+                cx.update(|cx| {
+                    authorize_unpreviewed_edits(
+                        &auth_title,
+                        &input.path,
+                        &project,
+                        &event_stream,
+                        cx,
+                    )
+                })?
+                .await?;
+                project
+                    .update(cx, |project, cx| {
+                        project.apply_code_action(buffer, action, true, cx)
+                    })?
+                    .await?
+            };
+            if transaction.0.is_empty() {
+                return Ok(format!("Applied `{title}`, which didn't edit any files"));
+            }
+
+            let edited_files =
+                record_project_transaction(transaction, &project, &action_log, cx).await?;
+            Ok(format!("Applied `{title}`, editing:\n{edited_files}"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::lsp_helpers::{TEST_MAIN_RS, init_test_project};
+    use fs::Fs as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use settings::Settings as _;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_code_actions(cx: &mut TestAppContext) {
+        let (fs, project, fake_language_server, _handle) = init_test_project(
+            lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        fake_language_server.set_request_handler::<lsp::request::CodeActionRequest, _, _>(
+            |params, _| async move {
+                assert_eq!(
+                    params.range,
+                    lsp::Range::new(lsp::Position::new(6, 0), lsp::Position::new(6, 5))
+                );
+                Ok(Some(vec![
+                    lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                        title: "Replace with two".into(),
+                        edit: Some(lsp::WorkspaceEdit {
+                            changes: Some(
+                                [(
+                                    params.text_document.uri,
+                                    vec![lsp::TextEdit {
+                                        range: lsp::Range::new(
+                                            lsp::Position::new(6, 4),
+                                            lsp::Position::new(6, 5),
+                                        ),
+                                        new_text: "2".into(),
+                                    }],
+                                )]
+                                .into_iter()
+                                .collect(),
+                            ),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                        title: "Extract into constant".into(),
+                        ..Default::default()
+                    }),
+                ]))
+            },
+        );
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(CodeActionTool::new(project, action_log));
+        let run = |title: Option<&str>, cx: &mut TestAppContext| {
+            let (event_stream, _event_rx) = ToolCallEventStream::test();
+            cx.update(|cx| {
+                tool.clone().run(
+                    CodeActionToolInput {
+                        path: "root/src/main.rs".into(),
+                        start_line: 7,
+                        end_line: None,
+                        title: title.map(Into::into),
+                    },
+                    event_stream,
+                    cx,
+                )
+            })
+        };
+
+        assert_eq!(
+            run(None, cx).await.unwrap(),
+            "Available code actions:\n- Replace with two\n- Extract into constant\n"
+        );
+        assert!(run(Some("Inline function"), cx).await.is_err());
+        assert_eq!(
+            run(Some("Replace with two"), cx).await.unwrap(),
+            "Applied `Replace with two`, editing:\n- root/src/main.rs\n"
+        );
+        assert_eq!(
+            fs.load(path!("/root/src/main.rs").as_ref()).await.unwrap(),
+            TEST_MAIN_RS.replace("    1\n", "    2\n")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_code_action_follows_edit_permissions(cx: &mut TestAppContext) {
+        let (fs, project, fake_language_server, handle) = init_test_project(
+            lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        fake_language_server.set_request_handler::<lsp::request::CodeActionRequest, _, _>(
+            |params, _| async move {
+                let new_file = lsp::Uri::from_file_path(path!("/root/src/constants.rs")).unwrap();
+                Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                    lsp::CodeAction {
+                        title: "Move into constants".into(),
+                        edit: Some(lsp::WorkspaceEdit {
+                            document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(
+                                    lsp::CreateFile {
+                                        uri: new_file,
+                                        options: None,
+                                        annotation_id: None,
+                                    },
+                                )),
+                                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                                        uri: params.text_document.uri,
+                                        version: None,
+                                    },
+                                    edits: vec![lsp::OneOf::Left(lsp::TextEdit {
+                                        range: lsp::Range::new(
+                                            lsp::Position::new(6, 4),
+                                            lsp::Position::new(6, 5),
+                                        ),
+                                        new_text: "ONE".into(),
+                                    })],
+                                }),
+                            ])),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                )]))
+            },
+        );
+        cx.update(|cx| {
+            let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+            settings.tool_permissions =
+                agent_settings::ToolPermissions::from_content(&collections::IndexMap::from_iter([
+                    (
+                        "edit_file".into(),
+                        settings::ToolPermissionRulesContent {
+                            deny: Some(vec!["**/constants.rs".to_string()].into()),
+                            ..Default::default()
+                        },
+                    ),
+                ]));
+            agent_settings::AgentSettings::override_global(settings, cx);
+        });
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(CodeActionTool::new(project, action_log));
+        let (event_stream, mut event_rx) = ToolCallEventStream::test();
+        let result = cx
+            .update(|cx| {
+                tool.run(
+                    CodeActionToolInput {
+                        path: "root/src/main.rs".into(),
+                        start_line: 7,
+                        end_line: None,
+                        title: Some("Move into constants".into()),
+                    },
+                    event_stream,
+                    cx,
+                )
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert!(event_rx.try_next().is_err());
+        let buffer = handle.read_with(cx, |buffer, _| buffer.clone());
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            TEST_MAIN_RS
+        );
+        assert!(!fs.is_file(path!("/root/src/constants.rs").as_ref()).await);
+        assert_eq!(
+            fs.load(path!("/root/src/main.rs").as_ref()).await.unwrap(),
+            TEST_MAIN_RS
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{AgentToolOutput, AnyAgentTool, ToolCallEventStream};
 use agent_client_protocol::ToolKind;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::{Result, anyhow, bail};
 use collections::{BTreeMap, HashMap};
 use context_server::ContextServerId;
@@ -176,7 +177,13 @@ impl AnyAgentTool for ContextServerTool {
             return Task::ready(Err(anyhow!("Context server not found")));
         };
         let tool_name = self.tool.name.clone();
-        let authorize = event_stream.authorize(self.initial_title(input.clone(), cx), cx);
+        let authorize = event_stream.authorize_with_rules(
+            self.initial_title(input.clone(), cx),
+            ToolPermissionRequest::tool(tool_name.clone()),
+            None,
+            ToolPermission::Ask,
+            cx,
+        );
 
         cx.spawn(async move |_cx| {
             authorize.await?;
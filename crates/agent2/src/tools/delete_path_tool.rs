@@ -1,6 +1,7 @@
 use crate::{AgentTool, ToolCallEventStream};
 use action_log::ActionLog;
 use agent_client_protocol::ToolKind;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::{Context as _, Result, anyhow};
 use futures::{SinkExt, StreamExt, channel::mpsc};
 use gpui::{App, AppContext, Entity, SharedString, Task};
@@ -67,10 +68,11 @@ impl AgentTool for DeletePathTool {
     fn run(
         self: Arc<Self>,
         input: Self::Input,
-        _event_stream: ToolCallEventStream,
+        event_stream: ToolCallEventStream,
         cx: &mut App,
     ) -> Task<Result<Self::Output>> {
-        let path = input.path;
+        let path = input.path.clone();
+        let title = self.initial_title(Ok(input), cx);
         let Some(project_path) = self.project.read(cx).find_project_path(&path, cx) else {
             return Task::ready(Err(anyhow!(
                 "Couldn't delete {path} because that path isn't in this project."
@@ -87,6 +89,14 @@ impl AgentTool for DeletePathTool {
             )));
         };
 
+        let authorize = event_stream.authorize_with_rules(
+            title,
+            ToolPermissionRequest::paths(Self::name(), [&path]),
+            Some(project_path.clone()),
+            ToolPermission::Allow,
+            cx,
+        );
+
         let worktree_snapshot = worktree.read(cx).snapshot();
         let (mut paths_tx, mut paths_rx) = mpsc::channel(256);
         cx.background_spawn({
@@ -113,6 +123,8 @@ impl AgentTool for DeletePathTool {
         let project = self.project.clone();
         let action_log = self.action_log.clone();
         cx.spawn(async move |cx| {
+            authorize.await?;
+
             while let Some(path) = paths_rx.next().await {
                 if let Ok(buffer) = project
                     .update(cx, |project, cx| project.open_buffer(path, cx))?
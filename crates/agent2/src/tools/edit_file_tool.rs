@@ -1,6 +1,7 @@
 use crate::{AgentTool, Thread, ToolCallEventStream};
 use acp_thread::Diff;
 use agent_client_protocol::{self as acp, ToolCallLocation, ToolCallUpdateFields};
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::{Context as _, Result, anyhow};
 use assistant_tools::edit_agent::{EditAgent, EditAgentOutput, EditAgentOutputEvent, EditFormat};
 use cloud_llm_client::CompletionIntent;
@@ -15,7 +16,6 @@ use project::lsp_store::{FormatTrigger, LspFormatTarget};
 use project::{Project, ProjectPath};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
 use smol::stream::StreamExt as _;
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
@@ -144,51 +144,75 @@ impl EditFileTool {
         event_stream: &ToolCallEventStream,
         cx: &mut App,
     ) -> Task<Result<()>> {
-        if agent_settings::AgentSettings::get_global(cx).always_allow_tool_actions {
-            return Task::ready(Ok(()));
-        }
+        authorize_edits(
+            &input.display_description,
+            std::slice::from_ref(&input.path),
+            &self.project,
+            event_stream,
+            cx,
+        )
+    }
+}
 
-        // If any path component matches the local settings folder, then this could affect
-        // the editor in ways beyond the project source, so prompt.
-        let local_settings_folder = paths::local_settings_folder_name();
-        let path = Path::new(&input.path);
-        if path.components().any(|component| {
+/// Checks edits to `paths` against the `edit_file` permission rules, asking for confirmation
+/// when they touch settings or files outside the project. Other tools that edit files on the
+/// agent's behalf go through this too, so that the same rules apply to their edits.
+pub(crate) fn authorize_edits(
+    title: &str,
+    file_paths: &[String],
+    project: &Entity<Project>,
+    event_stream: &ToolCallEventStream,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    let project_paths = file_paths
+        .iter()
+        .map(|path| project.read(cx).find_project_path(path, cx))
+        .collect::<Vec<_>>();
+    let location = project_paths.iter().flatten().next().cloned();
+    let request = ToolPermissionRequest::paths(EditFileTool::name(), file_paths);
+
+    // If any path component matches the local settings folder, then this could affect
+    // the editor in ways beyond the project source, so prompt. Permission rules can't
+    // allow these edits, since the rules themselves live in settings files.
+    let local_settings_folder = paths::local_settings_folder_name();
+    if file_paths.iter().any(|path| {
+        Path::new(path).components().any(|component| {
             component.as_os_str() == <_ as AsRef<OsStr>>::as_ref(&local_settings_folder)
-        }) {
-            return event_stream.authorize(
-                format!("{} (local settings)", input.display_description),
-                cx,
-            );
-        }
-
-        // It's also possible that the global config dir is configured to be inside the project,
-        // so check for that edge case too.
-        // TODO this is broken when remoting
-        if let Ok(canonical_path) = std::fs::canonicalize(&input.path)
-            && canonical_path.starts_with(paths::config_dir())
-        {
-            return event_stream.authorize(
-                format!("{} (global settings)", input.display_description),
-                cx,
-            );
-        }
-
-        // Check if path is inside the global config directory
-        // First check if it's already inside project - if not, try to canonicalize
-        let Ok(project_path) = self.thread.read_with(cx, |thread, cx| {
-            thread.project().read(cx).find_project_path(&input.path, cx)
-        }) else {
-            return Task::ready(Err(anyhow!("thread was dropped")));
-        };
+        })
+    }) {
+        return event_stream.authorize_with_rules(
+            format!("{title} (local settings)"),
+            request.without_allow_rules(),
+            location,
+            ToolPermission::Ask,
+            cx,
+        );
+    }
 
-        // If the path is inside the project, and it's not one of the above edge cases,
-        // then no confirmation is necessary. Otherwise, confirmation is necessary.
-        if project_path.is_some() {
-            Task::ready(Ok(()))
-        } else {
-            event_stream.authorize(&input.display_description, cx)
-        }
+    // It's also possible that the global config dir is configured to be inside the project,
+    // so check for that edge case too.
+    // TODO this is broken when remoting
+    if file_paths.iter().any(|path| {
+        std::fs::canonicalize(path)
+            .is_ok_and(|canonical_path| canonical_path.starts_with(paths::config_dir()))
+    }) {
+        return event_stream.authorize_with_rules(
+            format!("{title} (global settings)"),
+            request.without_allow_rules(),
+            location,
+            ToolPermission::Ask,
+            cx,
+        );
     }
+
+    // If the paths are inside the project, and they're not one of the above edge cases,
+    // then no confirmation is necessary unless a rule says otherwise.
+    let fallback = if project_paths.iter().all(Option::is_some) {
+        ToolPermission::Allow
+    } else {
+        ToolPermission::Ask
+    };
+    event_stream.authorize_with_rules(title, request, location, fallback, cx)
 }
 
 impl AgentTool for EditFileTool {
@@ -564,7 +588,7 @@ mod tests {
     use language_model::fake_provider::FakeLanguageModel;
     use prompt_store::ProjectContext;
     use serde_json::json;
-    use settings::SettingsStore;
+    use settings::{Settings, SettingsStore};
     use util::{path, rel_path::rel_path};
 
     #[gpui::test]
@@ -1173,6 +1197,91 @@ mod tests {
         assert!(stream_rx.try_next().is_err());
     }
 
+    #[gpui::test]
+    async fn test_authorize_with_permission_rules(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = project::FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({})).await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let context_server_registry =
+            cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+        let language_registry = project.read_with(cx, |project, _cx| project.languages().clone());
+        let model = Arc::new(FakeLanguageModel::default());
+        let thread = cx.new(|cx| {
+            Thread::new(
+                project.clone(),
+                cx.new(|_cx| ProjectContext::default()),
+                context_server_registry,
+                Templates::new(),
+                Some(model.clone()),
+                cx,
+            )
+        });
+        let tool = Arc::new(EditFileTool::new(
+            project.clone(),
+            thread.downgrade(),
+            language_registry,
+        ));
+
+        cx.update(|cx| {
+            let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+            settings.tool_permissions =
+                agent_settings::ToolPermissions::from_content(&collections::IndexMap::from_iter([
+                    (
+                        "edit_file".into(),
+                        settings::ToolPermissionRulesContent {
+                            default: None,
+                            allow: Some(
+                                vec!["/tmp/**".to_string(), "**/.zed/**".to_string()].into(),
+                            ),
+                            ask: None,
+                            deny: Some(vec!["**/*.lock".to_string()].into()),
+                        },
+                    ),
+                ]));
+            agent_settings::AgentSettings::override_global(settings, cx);
+        });
+        let authorize = |path: &str, stream: &ToolCallEventStream, cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                tool.authorize(
+                    &EditFileToolInput {
+                        display_description: "edit".into(),
+                        path: path.into(),
+                        mode: EditFileMode::Edit,
+                    },
+                    stream,
+                    cx,
+                )
+            })
+        };
+
+        // Denied paths fail without asking, even inside the project.
+        let (stream_tx, mut stream_rx) = ToolCallEventStream::test();
+        assert!(authorize("root/Cargo.lock", &stream_tx, cx).await.is_err());
+        assert!(stream_rx.try_next().is_err());
+
+        // Allowed paths outside the project don't need confirmation.
+        let (stream_tx, mut stream_rx) = ToolCallEventStream::test();
+        authorize("/tmp/notes.txt", &stream_tx, cx).await.unwrap();
+        assert!(stream_rx.try_next().is_err());
+
+        // Other paths outside the project offer to allow their directory.
+        let (stream_tx, mut stream_rx) = ToolCallEventStream::test();
+        let _auth = authorize("/etc/hosts", &stream_tx, cx);
+        let event = stream_rx.expect_authorization().await;
+        assert_eq!(event.options[0].name, "Always Allow `/etc/**`");
+
+        // Allow rules don't apply to settings files.
+        let (stream_tx, mut stream_rx) = ToolCallEventStream::test();
+        let _auth = authorize("root/.zed/settings.json", &stream_tx, cx);
+        let event = stream_rx.expect_authorization().await;
+        assert_eq!(
+            event.tool_call.fields.title,
+            Some("edit (local settings)".into())
+        );
+        assert_eq!(event.options[0].name, "Always Allow");
+    }
+
     #[gpui::test]
     async fn test_authorize_global_config(cx: &mut TestAppContext) {
         init_test(cx);
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::{borrow::Cow, cell::RefCell};
 
 use agent_client_protocol as acp;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::{Context as _, Result, bail};
 use futures::AsyncReadExt as _;
 use gpui::{App, AppContext as _, Task};
@@ -143,7 +144,13 @@ impl AgentTool for FetchTool {
         event_stream: ToolCallEventStream,
         cx: &mut App,
     ) -> Task<Result<Self::Output>> {
-        let authorize = event_stream.authorize(input.url.clone(), cx);
+        let authorize = event_stream.authorize_with_rules(
+            input.url.clone(),
+            ToolPermissionRequest::url(Self::name(), input.url.clone()),
+            None,
+            ToolPermission::Ask,
+            cx,
+        );
 
         let text = cx.background_spawn({
             let http_client = self.http_client.clone();
@@ -0,0 +1,169 @@
+use crate::tools::lsp_helpers::{format_locations, locate_symbol};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Maximum number of references to include in the tool's output.
+const MAX_REFERENCES: usize = 200;
+
+/// Finds every reference to a symbol across the project, using the language server for the file that mentions it.
+///
+/// Prefer this tool to `grep` when you need to know all the places that use a function, type, or variable before changing it, since it ignores unrelated symbols that share the same name.
+///
+/// <example>
+/// To find all references to the `Config` struct defined on line 10 of `project/src/config.rs`:
+/// {
+///     "path": "project/src/config.rs",
+///     "line": 10,
+///     "symbol": "Config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesToolInput {
+    /// The path of a file that mentions the symbol.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The name of the symbol, exactly as it appears on that line.
+    pub symbol: String,
+}
+
+pub struct FindReferencesTool {
+    project: Entity<Project>,
+}
+
+impl FindReferencesTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for FindReferencesTool {
+    type Input = FindReferencesToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "find_references"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!("Find references to {}", MarkdownInlineCode(&input.symbol)).into()
+        } else {
+            "Find references".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let position = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let references = project
+                .update(cx, |project, cx| project.references(&buffer, position, cx))?
+                .await?
+                .unwrap_or_default();
+            if references.is_empty() {
+                return Ok(format!("No references found for `{}`", input.symbol));
+            }
+
+            let count = references.len();
+            let mut output = format!("Found {count} references to `{}`:\n", input.symbol);
+            output.push_str(&format_locations(
+                references.into_iter().take(MAX_REFERENCES),
+                cx,
+            )?);
+            if count > MAX_REFERENCES {
+                output.push_str(&format!(
+                    "\n{} more references omitted.",
+                    count - MAX_REFERENCES
+                ));
+            }
+            Ok(output)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::lsp_helpers::init_test_project;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_find_references(cx: &mut TestAppContext) {
+        let (_fs, project, fake_language_server, _handle) = init_test_project(
+            lsp::ServerCapabilities {
+                references_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        fake_language_server.set_request_handler::<lsp::request::References, _, _>(
+            |params, _| async move {
+                assert_eq!(
+                    params.text_document_position.position,
+                    lsp::Position::new(1, 16)
+                );
+                let uri = params.text_document_position.text_document.uri;
+                let location = |line, start, end| {
+                    lsp::Location::new(
+                        uri.clone(),
+                        lsp::Range::new(
+                            lsp::Position::new(line, start),
+                            lsp::Position::new(line, end),
+                        ),
+                    )
+                };
+                Ok(Some(vec![location(5, 3, 10), location(1, 16, 23)]))
+            },
+        );
+
+        let tool = Arc::new(FindReferencesTool::new(project));
+        let (event_stream, _event_rx) = ToolCallEventStream::test();
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    FindReferencesToolInput {
+                        path: "root/src/main.rs".into(),
+                        line: 2,
+                        symbol: "compute".into(),
+                    },
+                    event_stream,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output,
+            "Found 2 references to `compute`:\n\
+            - root/src/main.rs:6:4 `fn compute() -> u32 {`\n\
+            - root/src/main.rs:2:17 `let value = compute();`\n"
+        );
+    }
+}
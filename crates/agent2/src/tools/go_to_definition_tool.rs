@@ -0,0 +1,93 @@
+use crate::tools::lsp_helpers::{format_locations, locate_symbol};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Finds where a symbol is defined, using the language server for the file that references it.
+///
+/// Prefer this tool to `grep` for locating the definition of a function, type, or variable, since it understands imports, modules, and shadowing.
+///
+/// <example>
+/// To find the definition of `parse_config` called on line 42 of `project/src/main.rs`:
+/// {
+///     "path": "project/src/main.rs",
+///     "line": 42,
+///     "symbol": "parse_config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoToDefinitionToolInput {
+    /// The path of a file that mentions the symbol.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The name of the symbol, exactly as it appears on that line.
+    pub symbol: String,
+}
+
+pub struct GoToDefinitionTool {
+    project: Entity<Project>,
+}
+
+impl GoToDefinitionTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GoToDefinitionTool {
+    type Input = GoToDefinitionToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "go_to_definition"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!("Go to definition of {}", MarkdownInlineCode(&input.symbol)).into()
+        } else {
+            "Go to definition".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let position = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let definitions = project
+                .update(cx, |project, cx| project.definitions(&buffer, position, cx))?
+                .await?
+                .unwrap_or_default();
+            if definitions.is_empty() {
+                return Ok(format!("No definition found for `{}`", input.symbol));
+            }
+
+            let locations = format_locations(definitions.into_iter().map(|link| link.target), cx)?;
+            Ok(format!("Definitions of `{}`:\n{locations}", input.symbol))
+        })
+    }
+}
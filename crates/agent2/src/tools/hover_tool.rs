@@ -0,0 +1,115 @@
+use crate::tools::lsp_helpers::locate_symbol;
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use gpui::{App, Entity, SharedString, Task};
+use project::{HoverBlockKind, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::{MarkdownCodeBlock, MarkdownInlineCode};
+
+/// Shows what the language server knows about a symbol: its type or signature, and its documentation.
+///
+/// Use this tool to learn the inferred type of a variable or the signature of a function without reading its definition.
+///
+/// <example>
+/// To get the type of the `settings` variable on line 27 of `project/src/main.rs`:
+/// {
+///     "path": "project/src/main.rs",
+///     "line": 27,
+///     "symbol": "settings"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HoverToolInput {
+    /// The path of a file that mentions the symbol.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The name of the symbol, exactly as it appears on that line.
+    pub symbol: String,
+}
+
+pub struct HoverTool {
+    project: Entity<Project>,
+}
+
+impl HoverTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for HoverTool {
+    type Input = HoverToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "hover"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!("Get type info for {}", MarkdownInlineCode(&input.symbol)).into()
+        } else {
+            "Get type info".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let position = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let hovers = project
+                .update(cx, |project, cx| project.hover(&buffer, position, cx))?
+                .await
+                .unwrap_or_default();
+
+            let mut output = String::new();
+            for block in hovers.iter().flat_map(|hover| &hover.contents) {
+                if block.text.trim().is_empty() {
+                    continue;
+                }
+                if !output.is_empty() {
+                    output.push_str("\n\n");
+                }
+                match &block.kind {
+                    HoverBlockKind::Code { language } => output.push_str(
+                        &MarkdownCodeBlock {
+                            tag: language,
+                            text: &block.text,
+                        }
+                        .to_string(),
+                    ),
+                    HoverBlockKind::PlainText | HoverBlockKind::Markdown => {
+                        output.push_str(block.text.trim())
+                    }
+                }
+            }
+
+            if output.is_empty() {
+                Ok(format!("No information available for `{}`", input.symbol))
+            } else {
+                Ok(output)
+            }
+        })
+    }
+}
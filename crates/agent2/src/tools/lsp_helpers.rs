@@ -0,0 +1,336 @@
+use std::{fmt::Write, ops::Range};
+
+use action_log::ActionLog;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use gpui::{App, AsyncApp, Entity, Task};
+use language::{Buffer, BufferSnapshot, Point, ToPoint as _};
+use project::{Location, Project, ProjectTransaction};
+
+use crate::tools::edit_file_tool::authorize_edits;
+use crate::{AgentTool as _, EditFileTool, ToolCallEventStream};
+
+/// Opens the buffer at `path` and finds the position of `symbol` on the given
+/// 1-based `line`, which is where language server requests about it are made.
+pub(crate) fn locate_symbol(
+    project: &Entity<Project>,
+    path: &str,
+    line: u32,
+    symbol: &str,
+    cx: &mut App,
+) -> Task<Result<(Entity<Buffer>, Point)>> {
+    let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+        return Task::ready(Err(anyhow!("Path {path} not found in project")));
+    };
+    let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let path = path.to_string();
+    let symbol = symbol.to_string();
+    cx.spawn(async move |cx| {
+        let buffer = open_buffer.await?;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+        let row = line.checked_sub(1).context("Line numbers start at 1")?;
+        anyhow::ensure!(
+            row <= snapshot.max_point().row,
+            "Line {line} is past the end of {path}, which has {} lines",
+            snapshot.max_point().row + 1
+        );
+        let line_text = snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>();
+        let column = find_symbol_in_line(&line_text, &symbol).with_context(|| {
+            format!(
+                "Couldn't find `{symbol}` on line {line} of {path}, which reads: {}",
+                line_text.trim()
+            )
+        })?;
+        Ok((buffer, Point::new(row, column as u32)))
+    })
+}
+
+/// Finds the first occurrence of `symbol` in `line` that isn't part of a longer identifier.
+fn find_symbol_in_line(line: &str, symbol: &str) -> Option<usize> {
+    if symbol.is_empty() {
+        return None;
+    }
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(symbol)
+        .map(|(ix, _)| ix)
+        .find(|&ix| {
+            let before = line[..ix].chars().next_back();
+            let after = line[ix + symbol.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .or_else(|| line.find(symbol))
+}
+
+/// Describes locations in the project as a list of `path:line:column` entries
+/// followed by the text of their first line.
+pub(crate) fn format_locations(
+    locations: impl IntoIterator<Item = Location>,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let mut output = String::new();
+    for location in locations {
+        let (path, snapshot) = location.buffer.read_with(cx, |buffer, cx| {
+            (
+                buffer
+                    .file()
+                    .map(|file| file.full_path(cx).to_string_lossy().into_owned()),
+                buffer.snapshot(),
+            )
+        })?;
+        let range = location.range.to_point(&snapshot);
+        write_location(
+            &mut output,
+            path.as_deref().unwrap_or("untitled"),
+            &snapshot,
+            range,
+        )?;
+    }
+    Ok(output)
+}
+
+/// The files a language server's workspace edit touches, including ones it creates,
+/// renames or deletes. Files in the project are given by their path in the project, and
+/// others by their absolute path.
+fn workspace_edit_paths(
+    edit: &lsp::WorkspaceEdit,
+    project: &Entity<Project>,
+    cx: &App,
+) -> Vec<String> {
+    let mut uris = Vec::new();
+    if let Some(changes) = &edit.changes {
+        uris.extend(changes.keys());
+    }
+    match &edit.document_changes {
+        Some(lsp::DocumentChanges::Edits(edits)) => {
+            uris.extend(edits.iter().map(|edit| &edit.text_document.uri))
+        }
+        Some(lsp::DocumentChanges::Operations(operations)) => {
+            for operation in operations {
+                match operation {
+                    lsp::DocumentChangeOperation::Edit(edit) => uris.push(&edit.text_document.uri),
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
+                        uris.push(&op.uri)
+                    }
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(op)) => {
+                        uris.extend([&op.old_uri, &op.new_uri])
+                    }
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(op)) => {
+                        uris.push(&op.uri)
+                    }
+                }
+            }
+        }
+        None => {}
+    }
+
+    let project = project.read(cx);
+    let mut paths = uris
+        .into_iter()
+        .map(|uri| {
+            let Ok(abs_path) = uri.to_file_path() else {
+                return uri.to_string();
+            };
+            project
+                .find_project_path(&abs_path, cx)
+                .and_then(|project_path| {
+                    let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+                    Some(worktree.read(cx).full_path(&project_path.path))
+                })
+                .unwrap_or(abs_path)
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Checks a language server's workspace edit against the same permission rules as
+/// `edit_file`'s, before it's applied.
+pub(crate) fn authorize_workspace_edit(
+    edit: &lsp::WorkspaceEdit,
+    title: &str,
+    project: &Entity<Project>,
+    event_stream: &ToolCallEventStream,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    let paths = workspace_edit_paths(edit, project, cx);
+    authorize_edits(title, &paths, project, event_stream, cx)
+}
+
+/// Asks before a language server request whose edits can't be known until they're made,
+/// such as a code action's command or a rename in a remote project. Deny rules for
+/// `edit_file` still apply to the file the request is about.
+pub(crate) fn authorize_unpreviewed_edits(
+    title: &str,
+    path: &str,
+    project: &Entity<Project>,
+    event_stream: &ToolCallEventStream,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    let location = project.read(cx).find_project_path(path, cx);
+    event_stream.authorize_with_rules(
+        title,
+        ToolPermissionRequest::paths(EditFileTool::name(), [path]).without_allow_rules(),
+        location,
+        ToolPermission::Ask,
+        cx,
+    )
+}
+
+/// Records the edits a language server made on the agent's behalf in the action log, so that
+/// they can be reviewed like the agent's own edits, and saves the edited buffers. Returns a
+/// list of the edited files.
+pub(crate) async fn record_project_transaction(
+    transaction: ProjectTransaction,
+    project: &Entity<Project>,
+    action_log: &Entity<ActionLog>,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let mut paths = Vec::new();
+    for buffer in transaction.0.keys() {
+        buffer.read_with(cx, |buffer, cx| {
+            if let Some(file) = buffer.file() {
+                paths.push(file.full_path(cx).to_string_lossy().into_owned());
+            }
+        })?;
+    }
+    paths.sort();
+
+    action_log.update(cx, |action_log, cx| {
+        for (buffer, transaction) in &transaction.0 {
+            action_log.buffer_edited_since(buffer.clone(), &transaction.start, cx);
+        }
+    })?;
+
+    let buffers = transaction.0.into_keys().collect::<HashSet<_>>();
+    project
+        .update(cx, |project, cx| project.save_buffers(buffers, cx))?
+        .await?;
+
+    let mut output = String::new();
+    for path in paths {
+        writeln!(output, "- {path}")?;
+    }
+    Ok(output)
+}
+
+pub(crate) fn write_location(
+    output: &mut String,
+    path: &str,
+    snapshot: &BufferSnapshot,
+    range: Range<Point>,
+) -> std::fmt::Result {
+    let row = range.start.row;
+    let line_text = snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>();
+    writeln!(
+        output,
+        "- {path}:{}:{} `{}`",
+        row + 1,
+        range.start.column + 1,
+        line_text.trim()
+    )
+}
+
+/// Creates a project with a Rust file, `root/src/main.rs`, that is open and served by a fake
+/// language server with the given capabilities.
+#[cfg(test)]
+pub(crate) async fn init_test_project(
+    capabilities: lsp::ServerCapabilities,
+    cx: &mut gpui::TestAppContext,
+) -> (
+    std::sync::Arc<fs::FakeFs>,
+    Entity<Project>,
+    lsp::FakeLanguageServer,
+    project::lsp_store::OpenLspBufferHandle,
+) {
+    use futures::StreamExt as _;
+    use settings::Settings as _;
+    use util::path;
+
+    cx.update(|cx| {
+        let settings_store = settings::SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        language::init(cx);
+        agent_settings::AgentSettings::register(cx);
+        Project::init_settings(cx);
+    });
+
+    let fs = fs::FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/root"),
+        serde_json::json!({
+            "src": {
+                "main.rs": TEST_MAIN_RS,
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(std::sync::Arc::new(language::Language::new(
+        language::LanguageConfig {
+            name: "Rust".into(),
+            matcher: language::LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    )));
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "Rust",
+        language::FakeLspAdapter {
+            capabilities,
+            ..Default::default()
+        },
+    );
+
+    let (_, handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/root/src/main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+    (fs, project, fake_language_server, handle)
+}
+
+#[cfg(test)]
+pub(crate) const TEST_MAIN_RS: &str = indoc::indoc! {r#"
+    fn main() {
+        let value = compute();
+        println!("{value}");
+    }
+
+    fn compute() -> u32 {
+        1
+    }
+"#};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_symbol_in_line() {
+        assert_eq!(
+            find_symbol_in_line("let foo = foobar(foo);", "foo"),
+            Some(4)
+        );
+        assert_eq!(
+            find_symbol_in_line("let x = foobar(foo_1);", "foo"),
+            Some(8)
+        );
+        assert_eq!(find_symbol_in_line("fn bar() {}", "foo"), None);
+        assert_eq!(find_symbol_in_line("x.len()", "len"), Some(2));
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{AgentTool, ToolCallEventStream};
 use agent_client_protocol::ToolKind;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::{Context as _, Result, anyhow};
-use gpui::{App, AppContext, Entity, SharedString, Task};
+use gpui::{App, Entity, SharedString, Task};
 use project::Project;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -13,7 +14,7 @@ use util::markdown::MarkdownInlineCode;
 /// If the source and destination directories are the same, but the filename is different, this performs a rename. Otherwise, it performs a move.
 ///
 /// This tool should be used when it's desirable to move or rename a file or directory without changing its contents at all.
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct MovePathToolInput {
     /// The source path of the file or directory to move/rename.
     ///
@@ -89,29 +90,44 @@ impl AgentTool for MovePathTool {
     fn run(
         self: Arc<Self>,
         input: Self::Input,
-        _event_stream: ToolCallEventStream,
+        event_stream: ToolCallEventStream,
         cx: &mut App,
     ) -> Task<Result<Self::Output>> {
-        let rename_task = self.project.update(cx, |project, cx| {
-            match project
-                .find_project_path(&input.source_path, cx)
-                .and_then(|project_path| project.entry_for_path(&project_path, cx))
-            {
-                Some(entity) => match project.find_project_path(&input.destination_path, cx) {
-                    Some(project_path) => project.rename_entry(entity.id, project_path, cx),
-                    None => Task::ready(Err(anyhow!(
-                        "Destination path {} was outside the project.",
-                        input.destination_path
-                    ))),
-                },
-                None => Task::ready(Err(anyhow!(
-                    "Source path {} was not found in the project.",
-                    input.source_path
-                ))),
-            }
-        });
+        let project = self.project.read(cx);
+        let Some(source_id) = project
+            .find_project_path(&input.source_path, cx)
+            .and_then(|project_path| project.entry_for_path(&project_path, cx))
+            .map(|entry| entry.id)
+        else {
+            return Task::ready(Err(anyhow!(
+                "Source path {} was not found in the project.",
+                input.source_path
+            )));
+        };
+        let Some(destination) = project.find_project_path(&input.destination_path, cx) else {
+            return Task::ready(Err(anyhow!(
+                "Destination path {} was outside the project.",
+                input.destination_path
+            )));
+        };
 
-        cx.background_spawn(async move {
+        let authorize = event_stream.authorize_with_rules(
+            self.initial_title(Ok(input.clone()), cx),
+            ToolPermissionRequest::paths(
+                Self::name(),
+                [&input.source_path, &input.destination_path],
+            ),
+            Some(destination.clone()),
+            ToolPermission::Allow,
+            cx,
+        );
+
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            authorize.await?;
+            let rename_task = project.update(cx, |project, cx| {
+                project.rename_entry(source_id, destination, cx)
+            })?;
             let _ = rename_task.await.with_context(|| {
                 format!("Moving {} to {}", input.source_path, input.destination_path)
             })?;
@@ -1,5 +1,6 @@
 use crate::AgentTool;
 use agent_client_protocol::ToolKind;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::{Context as _, Result};
 use gpui::{App, AppContext, Entity, SharedString, Task};
 use project::Project;
@@ -65,7 +66,22 @@ impl AgentTool for OpenTool {
     ) -> Task<Result<Self::Output>> {
         // If path_or_url turns out to be a path in the project, make it absolute.
         let abs_path = to_absolute_path(&input.path_or_url, self.project.clone(), cx);
-        let authorize = event_stream.authorize(self.initial_title(Ok(input.clone()), cx), cx);
+        let project_path = self
+            .project
+            .read(cx)
+            .find_project_path(&input.path_or_url, cx);
+        let request = if abs_path.is_none() && input.path_or_url.contains("://") {
+            ToolPermissionRequest::url(Self::name(), input.path_or_url.clone())
+        } else {
+            ToolPermissionRequest::paths(Self::name(), [&input.path_or_url])
+        };
+        let authorize = event_stream.authorize_with_rules(
+            self.initial_title(Ok(input.clone()), cx),
+            request,
+            project_path,
+            ToolPermission::Ask,
+            cx,
+        );
         cx.background_spawn(async move {
             authorize.await?;
 
@@ -94,9 +110,10 @@ fn to_absolute_path(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ToolCallEventStream;
     use gpui::TestAppContext;
     use project::{FakeFs, Project};
-    use settings::SettingsStore;
+    use settings::{Settings, SettingsStore};
     use std::path::Path;
     use tempfile::TempDir;
 
@@ -159,11 +176,59 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_permission_rules(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", serde_json::json!({ "a.pdf": "" }))
+            .await;
+        let project = Project::test(fs, [Path::new("/root")], cx).await;
+        let tool = Arc::new(OpenTool::new(project));
+
+        cx.update(|cx| {
+            let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+            settings.tool_permissions =
+                agent_settings::ToolPermissions::from_content(&collections::IndexMap::from_iter([
+                    (
+                        "open".into(),
+                        settings::ToolPermissionRulesContent {
+                            default: None,
+                            allow: None,
+                            ask: None,
+                            deny: Some(
+                                vec!["https://example.com/*".to_string(), "**/*.pdf".to_string()]
+                                    .into(),
+                            ),
+                        },
+                    ),
+                ]));
+            agent_settings::AgentSettings::override_global(settings, cx);
+        });
+
+        for path_or_url in ["https://example.com/download", "root/a.pdf"] {
+            let (event_stream, mut event_rx) = ToolCallEventStream::test();
+            let result = cx
+                .update(|cx| {
+                    tool.clone().run(
+                        OpenToolInput {
+                            path_or_url: path_or_url.into(),
+                        },
+                        event_stream,
+                        cx,
+                    )
+                })
+                .await;
+            assert!(result.is_err(), "{path_or_url} should be denied");
+            assert!(event_rx.try_next().is_err());
+        }
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
             cx.set_global(settings_store);
             language::init(cx);
+            agent_settings::AgentSettings::register(cx);
             Project::init_settings(cx);
         });
     }
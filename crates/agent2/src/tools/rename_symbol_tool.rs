@@ -0,0 +1,280 @@
+use crate::tools::lsp_helpers::{
+    authorize_unpreviewed_edits, authorize_workspace_edit, locate_symbol,
+    record_project_transaction,
+};
+use crate::{AgentTool, ToolCallEventStream};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Renames a symbol and updates every reference to it across the project, using the language server for the file that mentions it.
+///
+/// Prefer this tool to editing files by hand when renaming functions, types, variables, or modules, since it only touches real references to the symbol. The edits are saved and shown to the user for review.
+///
+/// <example>
+/// To rename the `parse_config` function defined on line 12 of `project/src/config.rs` to `load_config`:
+/// {
+///     "path": "project/src/config.rs",
+///     "line": 12,
+///     "symbol": "parse_config",
+///     "new_name": "load_config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    /// The path of a file that mentions the symbol.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The current name of the symbol, exactly as it appears on that line.
+    pub symbol: String,
+    /// The new name for the symbol.
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+}
+
+impl RenameSymbolTool {
+    pub fn new(project: Entity<Project>, action_log: Entity<ActionLog>) -> Self {
+        Self {
+            project,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for RenameSymbolTool {
+    type Input = RenameSymbolToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "rename_symbol"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!(
+                "Rename {} to {}",
+                MarkdownInlineCode(&input.symbol),
+                MarkdownInlineCode(&input.new_name)
+            )
+            .into()
+        } else {
+            "Rename symbol".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let position = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        let action_log = self.action_log.clone();
+        cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let title = format!("Rename `{}` to `{}`", input.symbol, input.new_name);
+            let didnt_rename = || anyhow!("The language server didn't rename `{}`", input.symbol);
+
+            // The edit is checked before it's applied. Remote projects can't preview it, so
+            // they ask first.
+            let transaction = if project.read_with(cx, |project, _| project.is_local())? {
+                let (server_id, edit) = project
+                    .update(cx, |project, cx| {
+                        project.rename_edit(&buffer, position, input.new_name.clone(), cx)
+                    })?
+                    .await?
+                    .ok_or_else(didnt_rename)?;
+                cx.update(|cx| {
+                    authorize_workspace_edit(&edit, &title, &project, &event_stream, cx)
+                })?
+                .await?;
+                project
+                    .update(cx, |project, cx| {
+                        project.apply_workspace_edit(edit, server_id, true, cx)
+                    })?
+                    .await?
+            } else {
+                cx.update(|cx| {
+                    authorize_unpreviewed_edits(&title, &input.path, &project, &event_stream, cx)
+                })?
+                .await?;
+                project
+                    .update(cx, |project, cx| {
+                        project.perform_rename(buffer, position, input.new_name.clone(), cx)
+                    })?
+                    .await?
+            };
+            if transaction.0.is_empty() {
+                return Err(didnt_rename());
+            }
+
+            let edited_files =
+                record_project_transaction(transaction, &project, &action_log, cx).await?;
+            Ok(format!(
+                "Renamed `{}` to `{}` in:\n{edited_files}",
+                input.symbol, input.new_name
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::lsp_helpers::{TEST_MAIN_RS, init_test_project};
+    use fs::Fs as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use settings::Settings as _;
+    use util::path;
+
+    fn rename_edit(new_name: &str) -> lsp::WorkspaceEdit {
+        let edit = |line, start, end| lsp::TextEdit {
+            range: lsp::Range::new(
+                lsp::Position::new(line, start),
+                lsp::Position::new(line, end),
+            ),
+            new_text: new_name.to_string(),
+        };
+        lsp::WorkspaceEdit {
+            changes: Some(
+                [(
+                    lsp::Uri::from_file_path(path!("/root/src/main.rs")).unwrap(),
+                    vec![edit(1, 16, 23), edit(5, 3, 10)],
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol(cx: &mut TestAppContext) {
+        let (fs, project, fake_language_server, _handle) = init_test_project(
+            lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        fake_language_server.set_request_handler::<lsp::request::Rename, _, _>(
+            |params, _| async move {
+                assert_eq!(
+                    params.text_document_position.position,
+                    lsp::Position::new(5, 3)
+                );
+                Ok(Some(rename_edit(&params.new_name)))
+            },
+        );
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(RenameSymbolTool::new(project, action_log));
+        let (event_stream, _event_rx) = ToolCallEventStream::test();
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    RenameSymbolToolInput {
+                        path: "root/src/main.rs".into(),
+                        line: 6,
+                        symbol: "compute".into(),
+                        new_name: "load".into(),
+                    },
+                    event_stream,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output,
+            "Renamed `compute` to `load` in:\n- root/src/main.rs\n"
+        );
+        assert_eq!(
+            fs.load(path!("/root/src/main.rs").as_ref()).await.unwrap(),
+            TEST_MAIN_RS.replace("compute", "load")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_follows_edit_permissions(cx: &mut TestAppContext) {
+        let (fs, project, fake_language_server, handle) = init_test_project(
+            lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        fake_language_server.set_request_handler::<lsp::request::Rename, _, _>(
+            |params, _| async move { Ok(Some(rename_edit(&params.new_name))) },
+        );
+        cx.update(|cx| {
+            let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+            settings.tool_permissions =
+                agent_settings::ToolPermissions::from_content(&collections::IndexMap::from_iter([
+                    (
+                        "edit_file".into(),
+                        settings::ToolPermissionRulesContent {
+                            deny: Some(vec!["**/main.rs".to_string()].into()),
+                            ..Default::default()
+                        },
+                    ),
+                ]));
+            agent_settings::AgentSettings::override_global(settings, cx);
+        });
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(RenameSymbolTool::new(project, action_log));
+        let (event_stream, _event_rx) = ToolCallEventStream::test();
+        let result = cx
+            .update(|cx| {
+                tool.run(
+                    RenameSymbolToolInput {
+                        path: "root/src/main.rs".into(),
+                        line: 6,
+                        symbol: "compute".into(),
+                        new_name: "load".into(),
+                    },
+                    event_stream,
+                    cx,
+                )
+            })
+            .await;
+
+        assert!(result.is_err());
+        let buffer = handle.read_with(cx, |buffer, _| buffer.clone());
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            TEST_MAIN_RS
+        );
+        assert_eq!(
+            fs.load(path!("/root/src/main.rs").as_ref()).await.unwrap(),
+            TEST_MAIN_RS
+        );
+    }
+}
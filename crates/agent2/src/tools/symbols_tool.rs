@@ -0,0 +1,190 @@
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use gpui::{App, Entity, SharedString, Task};
+use project::{DocumentSymbol, Project, lsp_store::SymbolLocation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Maximum number of workspace symbols to include in the tool's output.
+const MAX_WORKSPACE_SYMBOLS: usize = 100;
+
+/// Lists symbols known to the project's language servers.
+///
+/// When a path is provided, returns the outline of that file: its types, functions, methods, and other symbols, nested as in the source, with their line ranges.
+/// When no path is provided, searches the symbols of the whole project for the given query.
+///
+/// <example>
+/// To get the outline of a file:
+/// {
+///     "path": "project/src/main.rs"
+/// }
+///
+/// To find symbols across the project whose name matches "Config":
+/// {
+///     "query": "Config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolsToolInput {
+    /// The path of the file whose symbols to list.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: Option<String>,
+    /// The name, or part of the name, of the symbols to search for across the project.
+    /// Used when no path is provided.
+    pub query: Option<String>,
+}
+
+pub struct SymbolsTool {
+    project: Entity<Project>,
+}
+
+impl SymbolsTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for SymbolsTool {
+    type Input = SymbolsToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "symbols"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(SymbolsToolInput {
+                path: Some(path), ..
+            }) if !path.is_empty() => {
+                format!("List symbols in {}", MarkdownInlineCode(&path)).into()
+            }
+            Ok(SymbolsToolInput {
+                query: Some(query), ..
+            }) => format!("Search symbols for {}", MarkdownInlineCode(&query)).into(),
+            _ => "List symbols".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        match (input.path, input.query) {
+            (Some(path), _) if !path.is_empty() => {
+                let Some(project_path) = self.project.read(cx).find_project_path(&path, cx) else {
+                    return Task::ready(Err(anyhow!("Path {path} not found in project")));
+                };
+                let project = self.project.clone();
+                let open_buffer =
+                    project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+                cx.spawn(async move |cx| {
+                    let buffer = open_buffer.await?;
+                    let symbols = project
+                        .update(cx, |project, cx| project.document_symbols(&buffer, cx))?
+                        .await?;
+                    if symbols.is_empty() {
+                        return Ok(format!("No symbols found in {path}"));
+                    }
+
+                    let mut output = String::new();
+                    write_document_symbols(&mut output, &symbols, 0)?;
+                    Ok(output)
+                })
+            }
+            (_, Some(query)) if !query.is_empty() => {
+                let project = self.project.clone();
+                let symbols = project.update(cx, |project, cx| project.symbols(&query, cx));
+                cx.spawn(async move |cx| {
+                    let symbols = symbols.await?;
+                    if symbols.is_empty() {
+                        return Ok(format!("No symbols found matching `{query}`"));
+                    }
+
+                    project.read_with(cx, |project, cx| {
+                        let mut output = String::new();
+                        for symbol in symbols.iter().take(MAX_WORKSPACE_SYMBOLS) {
+                            let path = match &symbol.path {
+                                SymbolLocation::InProject(project_path) => {
+                                    let Some(worktree) =
+                                        project.worktree_for_id(project_path.worktree_id, cx)
+                                    else {
+                                        continue;
+                                    };
+                                    worktree
+                                        .read(cx)
+                                        .root_name()
+                                        .join(&project_path.path)
+                                        .as_unix_str()
+                                        .to_string()
+                                }
+                                SymbolLocation::OutsideProject { abs_path, .. } => {
+                                    abs_path.to_string_lossy().into_owned()
+                                }
+                            };
+                            writeln!(
+                                output,
+                                "- {:?} `{}` {path}:{}",
+                                symbol.kind,
+                                symbol.name,
+                                symbol.range.start.0.row + 1
+                            )?;
+                        }
+                        if symbols.len() > MAX_WORKSPACE_SYMBOLS {
+                            writeln!(
+                                output,
+                                "\n{} more symbols omitted. Use a more specific query.",
+                                symbols.len() - MAX_WORKSPACE_SYMBOLS
+                            )?;
+                        }
+                        anyhow::Ok(output)
+                    })?
+                })
+            }
+            _ => Task::ready(Err(anyhow!(
+                "Either a path or a query needs to be provided"
+            ))),
+        }
+    }
+}
+
+fn write_document_symbols(
+    output: &mut String,
+    symbols: &[DocumentSymbol],
+    depth: usize,
+) -> std::fmt::Result {
+    for symbol in symbols {
+        let start_row = symbol.range.start.0.row + 1;
+        let end_row = symbol.range.end.0.row + 1;
+        write!(
+            output,
+            "{:indent$}- {:?} `{}` ",
+            "",
+            symbol.kind,
+            symbol.name,
+            indent = depth * 2
+        )?;
+        if start_row == end_row {
+            writeln!(output, "L{start_row}")?;
+        } else {
+            writeln!(output, "L{start_row}-{end_row}")?;
+        }
+        write_document_symbols(output, &symbol.children, depth + 1)?;
+    }
+    Ok(())
+}
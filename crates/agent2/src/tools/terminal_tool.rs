@@ -1,7 +1,8 @@
 use agent_client_protocol as acp;
+use agent_settings::{ToolPermission, ToolPermissionRequest};
 use anyhow::Result;
 use gpui::{App, Entity, SharedString, Task};
-use project::Project;
+use project::{Project, ProjectPath};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -97,7 +98,25 @@ impl AgentTool for TerminalTool {
             Err(err) => return Task::ready(Err(err)),
         };
 
-        let authorize = event_stream.authorize(self.initial_title(Ok(input.clone()), cx), cx);
+        // Without a directory in the project, check the command against the first
+        // worktree's settings rather than only the user's.
+        let project = self.project.read(cx);
+        let location = working_dir
+            .as_ref()
+            .and_then(|dir| project.find_project_path(dir, cx))
+            .or_else(|| {
+                project
+                    .visible_worktrees(cx)
+                    .next()
+                    .map(|worktree| ProjectPath::root_path(worktree.read(cx).id()))
+            });
+        let authorize = event_stream.authorize_with_rules(
+            self.initial_title(Ok(input.clone()), cx),
+            ToolPermissionRequest::command(Self::name(), &input.command),
+            location,
+            ToolPermission::Ask,
+            cx,
+        );
         cx.spawn(async move |cx| {
             authorize.await?;
 
@@ -11,7 +11,7 @@ use settings::{
 };
 use util::ResultExt as _;
 
-use crate::{AgentProfileId, AgentSettings};
+use crate::{AgentProfileId, AgentSettings, ToolPermissions};
 
 pub mod builtin_profiles {
     use super::AgentProfileId;
@@ -64,7 +64,11 @@ impl AgentProfile {
                 .map(|profile| profile.enable_all_context_servers)
                 .unwrap_or_default(),
             context_servers: base_profile
-                .map(|profile| profile.context_servers)
+                .as_ref()
+                .map(|profile| profile.context_servers.clone())
+                .unwrap_or_default(),
+            tool_permissions: base_profile
+                .map(|profile| profile.tool_permissions)
                 .unwrap_or_default(),
         };
 
@@ -96,6 +100,7 @@ pub struct AgentProfileSettings {
     pub tools: IndexMap<Arc<str>, bool>,
     pub enable_all_context_servers: bool,
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
+    pub tool_permissions: ToolPermissions,
 }
 
 impl AgentProfileSettings {
@@ -144,6 +149,7 @@ impl AgentProfileSettings {
                         )
                     })
                     .collect(),
+                tool_permissions: self.tool_permissions.to_content(),
             },
         );
 
@@ -162,6 +168,7 @@ impl From<AgentProfileContent> for AgentProfileSettings {
                 .into_iter()
                 .map(|(server_id, preset)| (server_id, preset.into()))
                 .collect(),
+            tool_permissions: ToolPermissions::from_content(&content.tool_permissions),
         }
     }
 }
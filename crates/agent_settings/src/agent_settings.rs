@@ -1,4 +1,5 @@
 mod agent_profile;
+mod tool_permissions;
 
 use std::sync::Arc;
 
@@ -14,6 +15,7 @@ use settings::{
 };
 
 pub use crate::agent_profile::*;
+pub use crate::tool_permissions::*;
 
 pub const SUMMARIZE_THREAD_PROMPT: &str =
     include_str!("../../agent/src/prompts/summarize_thread_prompt.txt");
@@ -40,6 +42,7 @@ pub struct AgentSettings {
     pub default_view: DefaultAgentView,
     pub profiles: IndexMap<AgentProfileId, AgentProfileSettings>,
     pub always_allow_tool_actions: bool,
+    pub tool_permissions: ToolPermissions,
     pub notify_when_agent_waiting: NotifyWhenAgentWaiting,
     pub play_sound_when_agent_done: bool,
     pub stream_edits: bool,
@@ -76,6 +79,21 @@ impl AgentSettings {
         return None;
     }
 
+    /// Decides whether a tool call may run according to the rules of the given profile
+    /// and the ones from user and project settings, returning `None` if no rule applies.
+    pub fn tool_permission(
+        &self,
+        profile_id: &AgentProfileId,
+        request: &ToolPermissionRequest,
+    ) -> Option<ToolPermission> {
+        let profile_rules = self
+            .profiles
+            .get(profile_id)
+            .and_then(|profile| profile.tool_permissions.rules_for_tool(&request.tool_name));
+        let rules = self.tool_permissions.rules_for_tool(&request.tool_name);
+        request.permission(profile_rules.into_iter().chain(rules))
+    }
+
     pub fn set_inline_assistant_model(&mut self, provider: String, model: String) {
         self.inline_assistant_model = Some(LanguageModelSelection {
             provider: provider.into(),
@@ -173,6 +191,12 @@ impl Settings for AgentSettings {
                 .map(|(key, val)| (AgentProfileId(key), val.into()))
                 .collect(),
             always_allow_tool_actions: agent.always_allow_tool_actions.unwrap(),
+            tool_permissions: content
+                .project
+                .agent_tool_permissions
+                .as_ref()
+                .map(ToolPermissions::from_content)
+                .unwrap_or_default(),
             notify_when_agent_waiting: agent.notify_when_agent_waiting.unwrap(),
             play_sound_when_agent_done: agent.play_sound_when_agent_done.unwrap(),
             stream_edits: agent.stream_edits.unwrap(),
@@ -0,0 +1,724 @@
+use std::sync::Arc;
+
+use collections::IndexMap;
+use globset::{Glob, GlobMatcher};
+use settings::{ExtendingVec, ToolPermissionRulesContent};
+
+pub use settings::ToolPermission;
+
+/// Permission rules for agent tool calls, keyed by tool name.
+#[derive(Clone, Debug, Default)]
+pub struct ToolPermissions {
+    rules: IndexMap<Arc<str>, ToolPermissionRules>,
+}
+
+impl ToolPermissions {
+    pub fn from_content(content: &IndexMap<Arc<str>, ToolPermissionRulesContent>) -> Self {
+        Self {
+            rules: content
+                .iter()
+                .map(|(tool_name, rules)| (tool_name.clone(), ToolPermissionRules::new(rules)))
+                .collect(),
+        }
+    }
+
+    pub fn to_content(&self) -> IndexMap<Arc<str>, ToolPermissionRulesContent> {
+        self.rules
+            .iter()
+            .map(|(tool_name, rules)| (tool_name.clone(), rules.to_content()))
+            .collect()
+    }
+
+    pub fn rules_for_tool(&self, tool_name: &str) -> Option<&ToolPermissionRules> {
+        self.rules.get(tool_name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ToolPermissionRules {
+    pub default: Option<ToolPermission>,
+    pub allow: Vec<ToolPermissionPattern>,
+    pub ask: Vec<ToolPermissionPattern>,
+    pub deny: Vec<ToolPermissionPattern>,
+}
+
+impl ToolPermissionRules {
+    fn new(content: &ToolPermissionRulesContent) -> Self {
+        fn patterns(patterns: &Option<ExtendingVec<String>>) -> Vec<ToolPermissionPattern> {
+            patterns
+                .iter()
+                .flat_map(|patterns| patterns.0.iter())
+                .filter_map(|source| match ToolPermissionPattern::new(source) {
+                    Ok(pattern) => Some(pattern),
+                    Err(error) => {
+                        log::error!("invalid tool permission pattern {source:?}: {error}");
+                        None
+                    }
+                })
+                .collect()
+        }
+
+        Self {
+            default: content.default,
+            allow: patterns(&content.allow),
+            ask: patterns(&content.ask),
+            deny: patterns(&content.deny),
+        }
+    }
+
+    fn to_content(&self) -> ToolPermissionRulesContent {
+        fn sources(patterns: &[ToolPermissionPattern]) -> Option<ExtendingVec<String>> {
+            (!patterns.is_empty()).then(|| {
+                ExtendingVec(
+                    patterns
+                        .iter()
+                        .map(|pattern| pattern.source.clone())
+                        .collect(),
+                )
+            })
+        }
+
+        ToolPermissionRulesContent {
+            default: self.default,
+            allow: sources(&self.allow),
+            ask: sources(&self.ask),
+            deny: sources(&self.deny),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ToolPermissionPattern {
+    source: String,
+    matcher: GlobMatcher,
+}
+
+impl ToolPermissionPattern {
+    pub fn new(source: &str) -> Result<Self, globset::Error> {
+        Ok(Self {
+            source: source.to_string(),
+            matcher: Glob::new(source)?.compile_matcher(),
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_match(&self, input: &str) -> bool {
+        self.matcher.is_match(input)
+    }
+}
+
+/// What kind of input a tool call is checked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToolPermissionInputKind {
+    /// Calls that are only checked by tool name, against the tool's default.
+    Tool,
+    Command,
+    Path,
+    Url,
+}
+
+/// A tool call to be checked against the configured permission rules.
+#[derive(Clone, Debug)]
+pub struct ToolPermissionRequest {
+    pub tool_name: Arc<str>,
+    pub kind: ToolPermissionInputKind,
+    /// The inputs matched against patterns. Every input has to be allowed
+    /// for the call to be allowed, while any denied input denies the call.
+    pub inputs: Vec<String>,
+    /// Whether allow patterns and an `allow` default may apply to this call. They
+    /// don't for commands whose effects can't be judged from their text.
+    pub allow_rules_apply: bool,
+}
+
+impl ToolPermissionRequest {
+    /// A request for a tool whose input isn't checked, so only the tool's default applies.
+    pub fn tool(tool_name: impl Into<Arc<str>>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            kind: ToolPermissionInputKind::Tool,
+            inputs: Vec::new(),
+            allow_rules_apply: true,
+        }
+    }
+
+    /// A request for a shell command, checked one sub-command at a time so
+    /// that allowing `cargo test*` doesn't allow `cargo test && rm -rf ~`.
+    pub fn command(tool_name: impl Into<Arc<str>>, command: &str) -> Self {
+        let ShellCommands {
+            commands,
+            is_opaque,
+        } = split_shell_command(command);
+        Self {
+            tool_name: tool_name.into(),
+            kind: ToolPermissionInputKind::Command,
+            inputs: commands,
+            allow_rules_apply: !is_opaque,
+        }
+    }
+
+    pub fn paths(
+        tool_name: impl Into<Arc<str>>,
+        paths: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            kind: ToolPermissionInputKind::Path,
+            inputs: paths
+                .into_iter()
+                .map(|path| path.as_ref().replace('\\', "/"))
+                .collect(),
+            allow_rules_apply: true,
+        }
+    }
+
+    pub fn url(tool_name: impl Into<Arc<str>>, url: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            kind: ToolPermissionInputKind::Url,
+            inputs: vec![url.into()],
+            allow_rules_apply: true,
+        }
+    }
+
+    /// Keeps allow rules from applying to this call, so that it can only be asked about or denied.
+    pub fn without_allow_rules(mut self) -> Self {
+        self.allow_rules_apply = false;
+        self
+    }
+
+    /// Decides whether the call may run, returning `None` if no rule applies to it.
+    pub fn permission<'a>(
+        &self,
+        rules: impl IntoIterator<Item = &'a ToolPermissionRules>,
+    ) -> Option<ToolPermission> {
+        let rules = rules.into_iter().collect::<Vec<_>>();
+        let matches = |patterns: fn(&ToolPermissionRules) -> &[ToolPermissionPattern],
+                       input: &str| {
+            rules.iter().any(|rules| {
+                patterns(rules)
+                    .iter()
+                    .any(|pattern| pattern.is_match(input))
+            })
+        };
+        // Deny and ask patterns also match commands as written without environment
+        // assignments or the program's directory, so `FOO=1 /bin/rm -rf /` can't slip past
+        // `rm -rf*`. Allow patterns only match the command as written.
+        let restricts = |patterns: fn(&ToolPermissionRules) -> &[ToolPermissionPattern],
+                         input: &str| {
+            matches(patterns, input)
+                || (self.kind == ToolPermissionInputKind::Command
+                    && normalize_command(input).is_some_and(|command| matches(patterns, &command)))
+        };
+
+        if self
+            .inputs
+            .iter()
+            .any(|input| restricts(|r| r.deny.as_slice(), input))
+        {
+            return Some(ToolPermission::Deny);
+        }
+        if self
+            .inputs
+            .iter()
+            .any(|input| restricts(|r| r.ask.as_slice(), input))
+        {
+            return Some(ToolPermission::Ask);
+        }
+        if self.allow_rules_apply
+            && !self.inputs.is_empty()
+            && self
+                .inputs
+                .iter()
+                .all(|input| matches(|r| r.allow.as_slice(), input))
+        {
+            return Some(ToolPermission::Allow);
+        }
+        rules
+            .iter()
+            .find_map(|rules| rules.default)
+            .filter(|default| self.allow_rules_apply || *default != ToolPermission::Allow)
+    }
+
+    /// Patterns that would allow this call if added to the allow list.
+    pub fn suggested_allow_patterns(&self) -> Vec<String> {
+        if !self.allow_rules_apply {
+            return Vec::new();
+        }
+
+        let mut patterns = Vec::new();
+        for input in &self.inputs {
+            let pattern = match self.kind {
+                ToolPermissionInputKind::Tool => None,
+                ToolPermissionInputKind::Command => suggested_command_pattern(input),
+                ToolPermissionInputKind::Path => suggested_path_pattern(input),
+                ToolPermissionInputKind::Url => suggested_url_pattern(input),
+            };
+            if let Some(pattern) = pattern
+                && !patterns.contains(&pattern)
+            {
+                patterns.push(pattern);
+            }
+        }
+        patterns
+    }
+}
+
+/// Programs that run their arguments as shell code.
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh", "fish"];
+
+/// The commands a shell command line runs.
+struct ShellCommands {
+    commands: Vec<String>,
+    /// Whether the command line does things that its split commands don't show, such as
+    /// substituting the output of other commands, grouping commands, writing to files or
+    /// running a string as shell code.
+    is_opaque: bool,
+}
+
+/// Splits a shell command into the commands it runs, on `;`, `&&`, `||`, `|`, `&` and newlines.
+fn split_shell_command(command: &str) -> ShellCommands {
+    let chars = command.chars().collect::<Vec<_>>();
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut is_opaque = false;
+    let mut ix = 0;
+    while let Some(&char) = chars.get(ix) {
+        let next = chars.get(ix + 1).copied();
+        ix += 1;
+        match (quote, char) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            // Single quotes keep everything literal, including backslashes.
+            (Some('\''), c) => current.push(c),
+            (Some(_), '\\') => {
+                current.push(char);
+                if let Some(next) = next {
+                    current.push(next);
+                    ix += 1;
+                }
+            }
+            (Some(_), c) => {
+                is_opaque |= c == '`' || (c == '$' && next == Some('('));
+                current.push(c);
+            }
+            (None, '\'' | '"') => {
+                quote = Some(char);
+                current.push(char);
+            }
+            (None, '\\') => {
+                current.push(char);
+                if let Some(next) = next {
+                    current.push(next);
+                    ix += 1;
+                }
+            }
+            // Command and process substitutions, subshells and command groups.
+            (None, '`' | '(' | ')') => {
+                is_opaque = true;
+                current.push(char);
+            }
+            (None, '{') if current.trim().is_empty() => {
+                is_opaque = true;
+                current.push(char);
+            }
+            (None, '>') => {
+                if !redirects_to_descriptor(&chars[ix..]) {
+                    is_opaque = true;
+                }
+                current.push(char);
+            }
+            // Redirections such as `2>&1` and `&>` don't separate commands.
+            (None, '&') if current.ends_with(['>', '<']) || next == Some('>') => current.push(char),
+            (None, ';' | '&' | '|' | '\n') => {
+                if next == Some(char) {
+                    ix += 1;
+                }
+                commands.push(std::mem::take(&mut current));
+            }
+            (None, c) => current.push(c),
+        }
+    }
+    commands.push(current);
+
+    let commands = commands
+        .into_iter()
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty())
+        .collect::<Vec<_>>();
+    is_opaque |= commands
+        .iter()
+        .any(|command| runs_shell_code(normalize_command(command).as_deref().unwrap_or(command)));
+    ShellCommands {
+        commands,
+        is_opaque,
+    }
+}
+
+/// Whether the output redirection starting after a `>` only points at a file descriptor
+/// (as in `2>&1` or `>&-`) or at `/dev/null`, rather than writing to a file.
+fn redirects_to_descriptor(rest: &[char]) -> bool {
+    if let ['&', next, ..] = rest {
+        return next.is_ascii_digit() || *next == '-';
+    }
+    let target = rest
+        .iter()
+        .skip_while(|c| c.is_whitespace())
+        .take_while(|c| !c.is_whitespace() && !matches!(c, ';' | '&' | '|'))
+        .collect::<String>();
+    target == "/dev/null"
+}
+
+/// Returns the command without leading `NAME=value` assignments and with the program's
+/// directory removed, as in `rm -rf /` for `FOO=1 /bin/rm -rf /`, or `None` if it has neither.
+fn normalize_command(command: &str) -> Option<String> {
+    let (start, program) = shell_words(command).find(|(_, word)| !is_assignment(word))?;
+    let name = program.rsplit('/').next().unwrap_or(program);
+    if start == 0 && name.len() == program.len() {
+        return None;
+    }
+    Some(format!("{name}{}", &command[start + program.len()..]))
+}
+
+/// Whether the word assigns a shell variable, like `FOO=1` or `PATH="$PATH:bin"`.
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        name.chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// The words of a single command along with their byte offsets, keeping quoted
+/// whitespace inside words.
+fn shell_words(command: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut chars = command.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        let (start, _) = *chars.peek()?;
+        let mut end = start;
+        let mut quote = None;
+        while let Some((ix, c)) = chars.next_if(|(_, c)| quote.is_some() || !c.is_whitespace()) {
+            end = ix + c.len_utf8();
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some('\''), _) => {}
+                (_, '\\') => {
+                    if let Some((ix, c)) = chars.next() {
+                        end = ix + c.len_utf8();
+                    }
+                }
+                (None, '\'' | '"') => quote = Some(c),
+                _ => {}
+            }
+        }
+        Some((start, &command[start..end]))
+    })
+}
+
+/// Whether the command runs a string as shell code, as `eval …` and `sh -c …` do.
+fn runs_shell_code(command: &str) -> bool {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        return false;
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    program == "eval"
+        || (SHELLS.contains(&program)
+            && words.any(|word| {
+                word.strip_prefix('-')
+                    .is_some_and(|flags| !flags.starts_with('-') && flags.contains('c'))
+            }))
+}
+
+/// Suggests a pattern covering the program and its subcommand, e.g. `cargo test*`.
+fn suggested_command_pattern(command: &str) -> Option<String> {
+    let mut words = command.split_whitespace();
+    let program = words.next()?;
+    let mut pattern = program.to_string();
+    if let Some(subcommand) = words.next()
+        && subcommand
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && !subcommand.starts_with('-')
+    {
+        pattern.push(' ');
+        pattern.push_str(subcommand);
+    }
+    pattern.push('*');
+    Some(escape_glob(&pattern))
+}
+
+/// Suggests a pattern covering the path's directory, e.g. `project/src/**`.
+fn suggested_path_pattern(path: &str) -> Option<String> {
+    let path = path.trim_end_matches('/');
+    let (directory, _) = path.rsplit_once('/')?;
+    Some(format!("{}/**", escape_glob(directory)))
+}
+
+/// Suggests a pattern covering the URL's host, e.g. `https://docs.rs/*`.
+fn suggested_url_pattern(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    Some(format!("{}://{}/*", escape_glob(scheme), escape_glob(host)))
+}
+
+fn escape_glob(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        if matches!(char, '*' | '?' | '[' | ']' | '{' | '}') {
+            escaped.push('[');
+            escaped.push(char);
+            escaped.push(']');
+        } else {
+            escaped.push(char);
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(
+        default: Option<ToolPermission>,
+        allow: &[&str],
+        ask: &[&str],
+        deny: &[&str],
+    ) -> ToolPermissionRules {
+        let patterns = |patterns: &[&str]| {
+            Some(ExtendingVec(
+                patterns.iter().map(|pattern| pattern.to_string()).collect(),
+            ))
+        };
+        ToolPermissionRules::new(&ToolPermissionRulesContent {
+            default,
+            allow: patterns(allow),
+            ask: patterns(ask),
+            deny: patterns(deny),
+        })
+    }
+
+    #[test]
+    fn test_command_permissions() {
+        let rules = rules(
+            None,
+            &["cargo test*", "ls*"],
+            &[],
+            &["rm -rf*", "git push*"],
+        );
+        let permission =
+            |command| ToolPermissionRequest::command("terminal", command).permission([&rules]);
+
+        assert_eq!(
+            permission("cargo test -p agent"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission("cargo test && ls -la"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission("cargo test 2>&1 | ls"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(permission("rm -rf target"), Some(ToolPermission::Deny));
+        assert_eq!(
+            permission("cargo test; git push origin"),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(permission("cargo test && cargo publish"), None);
+        assert_eq!(permission("cargo test $(cat args)"), None);
+        assert_eq!(permission("echo 'a && b'"), None);
+
+        let default_rules = rules(Some(ToolPermission::Allow), &[], &[], &["rm -rf*"]);
+        let permission = |command| {
+            ToolPermissionRequest::command("terminal", command).permission([&default_rules])
+        };
+        assert_eq!(permission("make"), Some(ToolPermission::Allow));
+        assert_eq!(permission("make $(cat target)"), None);
+    }
+
+    #[test]
+    fn test_command_prefixes() {
+        let restrict_rules = rules(
+            Some(ToolPermission::Allow),
+            &[],
+            &["git push*"],
+            &["rm -rf*"],
+        );
+        let permission = |command| {
+            ToolPermissionRequest::command("terminal", command).permission([&restrict_rules])
+        };
+
+        assert_eq!(permission("FOO=1 rm -rf /"), Some(ToolPermission::Deny));
+        assert_eq!(permission("/bin/rm -rf /"), Some(ToolPermission::Deny));
+        assert_eq!(
+            permission("A=1 B='x y' ./bin/../rm -rf /"),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission("cargo build && PATH=\"$PATH:bin\" /usr/bin/rm -rf ~"),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission("GIT_TRACE=1 git push origin"),
+            Some(ToolPermission::Ask)
+        );
+        assert_eq!(permission("FOO=1 sh -c 'rm -rf ~'"), None);
+
+        // Allow patterns only match the command as written.
+        let allow_rules = rules(None, &["rm target*"], &[], &[]);
+        let permission = |command| {
+            ToolPermissionRequest::command("terminal", command).permission([&allow_rules])
+        };
+        assert_eq!(permission("rm target/debug"), Some(ToolPermission::Allow));
+        assert_eq!(permission("./rm target/debug"), None);
+        assert_eq!(permission("FOO=1 rm target/debug"), None);
+    }
+
+    #[test]
+    fn test_tool_permissions() {
+        let request = ToolPermissionRequest::tool("open");
+        assert_eq!(
+            request.permission([&rules(None, &["*"], &["*"], &["*"])]),
+            None
+        );
+        assert_eq!(
+            request.permission([&rules(Some(ToolPermission::Deny), &["*"], &[], &[])]),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            request.permission([&rules(Some(ToolPermission::Allow), &[], &[], &[])]),
+            Some(ToolPermission::Allow)
+        );
+        assert!(request.suggested_allow_patterns().is_empty());
+    }
+
+    #[test]
+    fn test_command_quoting() {
+        let rules = rules(None, &["ls*", "echo*"], &[], &["rm -rf*"]);
+        let permission =
+            |command| ToolPermissionRequest::command("terminal", command).permission([&rules]);
+
+        assert_eq!(
+            permission(r"ls 'a\' && rm -rf ~"),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(r#"echo "a\" && b""#),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission("echo 'a; rm -rf ~'"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission("echo 'it''s $(date)'"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(permission(r#"echo "$(rm -rf ~)""#), None);
+        assert_eq!(permission(r#"echo "`date`""#), None);
+    }
+
+    #[test]
+    fn test_opaque_commands() {
+        let rules = rules(
+            Some(ToolPermission::Allow),
+            &["cargo test*", "ls*", "eval*", "sh*", "bash*"],
+            &[],
+            &[],
+        );
+        let permission =
+            |command| ToolPermissionRequest::command("terminal", command).permission([&rules]);
+
+        assert_eq!(
+            permission("cargo test 2>/dev/null"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(permission("ls >&-"), Some(ToolPermission::Allow));
+        assert_eq!(permission("bash script.sh"), Some(ToolPermission::Allow));
+
+        assert_eq!(permission("ls <(rm -rf ~)"), None);
+        assert_eq!(permission("ls >(cat)"), None);
+        assert_eq!(permission("ls > ~/.bashrc"), None);
+        assert_eq!(permission("ls >> ~/.bashrc"), None);
+        assert_eq!(permission("ls &> ~/.bashrc"), None);
+        assert_eq!(permission("ls >| ~/.bashrc"), None);
+        assert_eq!(permission("(cargo test)"), None);
+        assert_eq!(permission("{ cargo test; }"), None);
+        assert_eq!(permission("eval cargo test"), None);
+        assert_eq!(permission("sh -c 'rm -rf ~'"), None);
+        assert_eq!(permission("/bin/bash -xc 'rm -rf ~'"), None);
+        assert_eq!(permission("ls && bash -c 'rm -rf ~'"), None);
+    }
+
+    #[test]
+    fn test_path_permissions() {
+        let project_rules = rules(
+            Some(ToolPermission::Ask),
+            &["project/src/**"],
+            &["**/Cargo.toml"],
+            &[],
+        );
+        let profile_rules = rules(None, &[], &[], &["**/.env"]);
+        let permission = |paths: &[&str]| {
+            ToolPermissionRequest::paths("move_path", paths)
+                .permission([&profile_rules, &project_rules])
+        };
+
+        assert_eq!(
+            permission(&["project/src/main.rs"]),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission(&["project/src/main.rs", "project/lib.rs"]),
+            Some(ToolPermission::Ask)
+        );
+        assert_eq!(
+            permission(&["project/src/Cargo.toml"]),
+            Some(ToolPermission::Ask)
+        );
+        assert_eq!(
+            permission(&["project/src/main.rs", "project/src/.env"]),
+            Some(ToolPermission::Deny)
+        );
+    }
+
+    #[test]
+    fn test_suggested_allow_patterns() {
+        assert_eq!(
+            ToolPermissionRequest::command("terminal", "cargo test -p agent && ls -la")
+                .suggested_allow_patterns(),
+            vec!["cargo test*".to_string(), "ls*".to_string()]
+        );
+        assert_eq!(
+            ToolPermissionRequest::paths("delete_path", ["project/src/a.rs", "project/src/b.rs"])
+                .suggested_allow_patterns(),
+            vec!["project/src/**".to_string()]
+        );
+        assert_eq!(
+            ToolPermissionRequest::url("fetch", "https://docs.rs/gpui?search=App")
+                .suggested_allow_patterns(),
+            vec!["https://docs.rs/*".to_string()]
+        );
+
+        let rules = rules(None, &["cargo test*"], &[], &[]);
+        assert_eq!(
+            ToolPermissionRequest::command("terminal", "cargo test --workspace")
+                .permission([&rules]),
+            Some(ToolPermission::Allow)
+        );
+    }
+}
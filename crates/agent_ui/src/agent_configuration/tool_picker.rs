@@ -294,6 +294,7 @@ impl PickerDelegate for ToolPickerDelegate {
                                 )
                             })
                             .collect(),
+                        tool_permissions: default_profile.tool_permissions.to_content(),
                     });
 
                 if let Some(server_id) = server_id {
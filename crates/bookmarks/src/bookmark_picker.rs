@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity,
+    Window,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace};
+
+use crate::{BookmarkEntry, Toggle, bookmark_entries, open_bookmark};
+
+/// A modal for searching the bookmarks of the project by their label, path and line.
+pub struct BookmarkPicker {
+    picker: Entity<Picker<BookmarkPickerDelegate>>,
+}
+
+impl BookmarkPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &Toggle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let entries = bookmark_entries(workspace.project(), cx);
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            let delegate =
+                BookmarkPickerDelegate::new(cx.entity().downgrade(), workspace_handle, entries);
+            let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+            Self { picker }
+        });
+    }
+}
+
+impl Render for BookmarkPicker {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BookmarkPicker")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for BookmarkPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkPicker {}
+impl ModalView for BookmarkPicker {}
+
+pub struct BookmarkPickerDelegate {
+    bookmark_picker: WeakEntity<BookmarkPicker>,
+    workspace: WeakEntity<Workspace>,
+    entries: Vec<BookmarkEntry>,
+    candidates: Arc<[StringMatchCandidate]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarkPickerDelegate {
+    fn new(
+        bookmark_picker: WeakEntity<BookmarkPicker>,
+        workspace: WeakEntity<Workspace>,
+        entries: Vec<BookmarkEntry>,
+    ) -> Self {
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| {
+                StringMatchCandidate::new(ix, &format!("{} {}", entry.title(), entry.location()))
+            })
+            .collect();
+        Self {
+            bookmark_picker,
+            workspace,
+            entries,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarkPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search bookmarks…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(if self.entries.is_empty() {
+            "No bookmarks in this project".into()
+        } else {
+            "No matching bookmarks".into()
+        })
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string.clone(),
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.entries.get(mat.candidate_id))
+        {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_bookmark(workspace, entry, window, cx)
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmark_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let entry = self.entries.get(mat.candidate_id)?;
+        let title = entry.title();
+        // The candidate is the title and the location separated by a space, so split the
+        // matched positions between the two labels.
+        let location_start = title.len() + 1;
+        let (title_positions, location_positions): (Vec<_>, Vec<_>) = mat
+            .positions
+            .iter()
+            .copied()
+            .partition(|position| *position < location_start);
+        let location_positions = location_positions
+            .into_iter()
+            .map(|position| position - location_start)
+            .collect();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::Bookmark)
+                        .size(IconSize::Small)
+                        .color(Color::Accent),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(title, title_positions).truncate())
+                        .child(
+                            HighlightedLabel::new(entry.location(), location_positions)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                ),
+        )
+    }
+}
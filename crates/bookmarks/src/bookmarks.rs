@@ -0,0 +1,251 @@
+mod bookmark_picker;
+mod bookmarks_panel;
+mod bookmarks_panel_settings;
+
+use std::{path::Path, sync::Arc};
+
+use editor::{Editor, SelectionEffects, actions::SelectAll, scroll::Autoscroll};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, SharedString, Window,
+    actions,
+};
+use language::{Buffer, Point, ToPoint as _};
+use project::{Project, bookmark_store::BookmarkStore};
+use settings::Settings as _;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+pub use bookmark_picker::BookmarkPicker;
+pub use bookmarks_panel::BookmarksPanel;
+use bookmarks_panel_settings::BookmarksPanelSettings;
+
+actions!(
+    bookmarks,
+    [
+        /// Toggles a picker of all bookmarks in the project.
+        Toggle,
+        /// Toggles focus on the bookmarks panel.
+        ToggleFocus,
+        /// Edits the label of the bookmark at the cursor, adding a bookmark if there is none.
+        EditLabel,
+        /// Removes the selected bookmark.
+        RemoveSelected,
+        /// Removes all bookmarks in the project.
+        ClearAll,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    BookmarksPanelSettings::register(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<BookmarksPanel>(window, cx);
+            })
+            .register_action(BookmarkPicker::toggle)
+            .register_action(edit_label_at_cursor)
+            .register_action(|workspace, _: &ClearAll, _, cx| {
+                workspace
+                    .project()
+                    .read(cx)
+                    .bookmark_store()
+                    .update(cx, |bookmark_store, cx| bookmark_store.clear_bookmarks(cx));
+            });
+    })
+    .detach();
+}
+
+/// A bookmark, along with how it is described in the picker and the panel.
+#[derive(Clone)]
+struct BookmarkEntry {
+    abs_path: Arc<Path>,
+    buffer: Entity<Buffer>,
+    position: text::Anchor,
+    label: Option<SharedString>,
+    /// The path of the file, relative to the project.
+    path: SharedString,
+    row: u32,
+    /// The trimmed text of the bookmarked line.
+    line: SharedString,
+}
+
+impl BookmarkEntry {
+    /// The label of the bookmark, or the text of its line if it has none.
+    fn title(&self) -> SharedString {
+        self.label.clone().unwrap_or_else(|| self.line.clone())
+    }
+
+    fn location(&self) -> SharedString {
+        format!("{}:{}", self.path, self.row + 1).into()
+    }
+}
+
+fn bookmark_entries(project: &Entity<Project>, cx: &App) -> Vec<BookmarkEntry> {
+    project
+        .read(cx)
+        .bookmark_store()
+        .read(cx)
+        .all_bookmarks()
+        .map(|(abs_path, buffer, bookmark)| {
+            let buffer_ref = buffer.read(cx);
+            let row = bookmark.position.to_point(buffer_ref).row;
+            let path = buffer_ref
+                .file()
+                .map(|file| file.full_path(cx))
+                .unwrap_or_else(|| abs_path.to_path_buf());
+            let line = buffer_ref
+                .text_for_range(Point::new(row, 0)..Point::new(row, buffer_ref.line_len(row)))
+                .collect::<String>();
+            BookmarkEntry {
+                abs_path: abs_path.clone(),
+                buffer: buffer.clone(),
+                position: bookmark.position,
+                label: bookmark.label.clone(),
+                path: path.to_string_lossy().into_owned().into(),
+                row,
+                line: line.trim().to_string().into(),
+            }
+        })
+        .collect()
+}
+
+fn open_bookmark(
+    workspace: &mut Workspace,
+    entry: &BookmarkEntry,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let editor = workspace.open_project_item::<Editor>(
+        workspace.active_pane().clone(),
+        entry.buffer.clone(),
+        true,
+        true,
+        window,
+        cx,
+    );
+    let point = entry.position.to_point(entry.buffer.read(cx));
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()),
+            window,
+            cx,
+            |selections| selections.select_ranges([point..point]),
+        );
+    });
+}
+
+fn edit_label_at_cursor(
+    workspace: &mut Workspace,
+    _: &EditLabel,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+    else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        let buffer = editor.buffer().read(cx).buffer_for_anchor(head, cx)?;
+        Some((buffer, head.text_anchor))
+    }) else {
+        return;
+    };
+    let bookmark_store = workspace.project().read(cx).bookmark_store();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        BookmarkLabelEditor::new(bookmark_store, buffer, position, window, cx)
+    });
+}
+
+/// A modal for editing the label of a bookmark.
+struct BookmarkLabelEditor {
+    label_editor: Entity<Editor>,
+    bookmark_store: Entity<BookmarkStore>,
+    buffer: Entity<Buffer>,
+    position: text::Anchor,
+}
+
+impl BookmarkLabelEditor {
+    fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let row = position.to_point(buffer.read(cx)).row;
+        let label = bookmark_store
+            .read(cx)
+            .bookmarks_for_buffer(&buffer, cx)
+            .iter()
+            .find(|bookmark| bookmark.position.to_point(buffer.read(cx)).row == row)
+            .and_then(|bookmark| bookmark.label.clone());
+        let label_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark label…", window, cx);
+            if let Some(label) = label {
+                editor.set_text(label.to_string(), window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        Self {
+            label_editor,
+            bookmark_store,
+            buffer,
+            position,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let label = self.label_editor.read(cx).text(cx);
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_label(
+                self.buffer.clone(),
+                self.position,
+                Some(label.trim().to_string().into()),
+                cx,
+            );
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkLabelEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row = self.position.to_point(self.buffer.read(cx)).row;
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("BookmarkLabelEditor")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.label_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().child(
+                Label::new(format!("Label the bookmark on line {}", row + 1)).color(Color::Muted),
+            ))
+    }
+}
+
+impl Focusable for BookmarkLabelEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkLabelEditor {}
+impl ModalView for BookmarkLabelEditor {}
@@ -0,0 +1,418 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{
+    Action, AnyElement, App, AsyncWindowContext, ClickEvent, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, KeyContext, Pixels, Render, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, div, uniform_list,
+};
+use project::{Fs, Project};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use ui::{
+    IconButton, IconButtonShape, IconName, IconSize, Label, LabelSize, ListItem, Tab, Tooltip,
+    WithScrollbar, h_flex, prelude::*, v_flex,
+};
+use util::ResultExt;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::{
+    BookmarkEntry, BookmarkLabelEditor, ClearAll, EditLabel, RemoveSelected, ToggleFocus,
+    bookmark_entries, bookmarks_panel_settings::BookmarksPanelSettings, open_bookmark,
+};
+
+const BOOKMARKS_PANEL_KEY: &str = "BookmarksPanel";
+
+/// A panel listing the bookmarks of the project.
+pub struct BookmarksPanel {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    entries: Vec<BookmarkEntry>,
+    selected_ix: Option<usize>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedBookmarksPanel {
+    width: Option<Pixels>,
+}
+
+impl BookmarksPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(BOOKMARKS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedBookmarksPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let panel = Self::new(workspace, window, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|width| width.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new(|cx| {
+            let bookmark_store = project.read(cx).bookmark_store();
+            let mut old_dock_position = BookmarksPanelSettings::get_global(cx).dock;
+            let subscriptions = vec![
+                cx.observe(&bookmark_store, |this, _, cx| this.update_entries(cx)),
+                cx.observe_global_in::<SettingsStore>(window, move |_, _, cx| {
+                    let new_dock_position = BookmarksPanelSettings::get_global(cx).dock;
+                    if new_dock_position != old_dock_position {
+                        old_dock_position = new_dock_position;
+                        cx.emit(PanelEvent::ZoomOut);
+                    }
+                    cx.notify();
+                }),
+            ];
+
+            let mut this = Self {
+                project: project.clone(),
+                workspace: workspace_handle,
+                fs,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                width: None,
+                entries: Vec::new(),
+                selected_ix: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            };
+            this.update_entries(cx);
+            this
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        BOOKMARKS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedBookmarksPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        self.entries = bookmark_entries(&self.project, cx);
+        self.selected_ix = self
+            .selected_ix
+            .filter(|_| !self.entries.is_empty())
+            .map(|ix| ix.min(self.entries.len() - 1));
+        cx.notify();
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1) % self.entries.len()),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None | Some(0) => Some(self.entries.len() - 1),
+            Some(ix) => Some(ix - 1),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix {
+            self.open_entry(ix, window, cx);
+        }
+    }
+
+    fn open_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix).cloned() else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_bookmark(workspace, &entry, window, cx)
+            })
+            .log_err();
+    }
+
+    fn remove_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let (abs_path, position) = (entry.abs_path.clone(), entry.position);
+        self.project
+            .read(cx)
+            .bookmark_store()
+            .update(cx, |bookmark_store, cx| {
+                bookmark_store.remove_bookmark(&abs_path, position, cx)
+            });
+    }
+
+    fn remove_selected(&mut self, _: &RemoveSelected, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix {
+            self.remove_entry(ix, cx);
+        }
+    }
+
+    fn edit_label(&mut self, _: &EditLabel, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .cloned()
+        else {
+            return;
+        };
+        let bookmark_store = self.project.read(cx).bookmark_store();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    BookmarkLabelEditor::new(
+                        bookmark_store,
+                        entry.buffer,
+                        entry.position,
+                        window,
+                        cx,
+                    )
+                });
+            })
+            .log_err();
+    }
+
+    fn clear_all(&mut self, _: &ClearAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.project
+            .read(cx)
+            .bookmark_store()
+            .update(cx, |bookmark_store, cx| bookmark_store.clear_bookmarks(cx));
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let selected = self.selected_ix == Some(ix);
+
+        let remove_button = IconButton::new(("remove-bookmark", ix), IconName::Close)
+            .shape(IconButtonShape::Square)
+            .icon_size(IconSize::XSmall)
+            .tooltip(Tooltip::text("Remove Bookmark"))
+            .on_click(cx.listener(move |this, _, _, cx| this.remove_entry(ix, cx)));
+
+        ListItem::new(("bookmarks-panel-entry", ix))
+            .toggle_state(selected)
+            .start_slot(
+                Icon::new(IconName::Bookmark)
+                    .size(IconSize::Small)
+                    .color(Color::Accent),
+            )
+            .child(
+                v_flex()
+                    .child(Label::new(entry.title()).size(LabelSize::Small).truncate())
+                    .child(
+                        Label::new(entry.location())
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+            )
+            .end_hover_slot(remove_button)
+            .when(entry.label.is_some(), |this| {
+                this.tooltip(Tooltip::text(entry.line.clone()))
+            })
+            .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                this.select_ix(Some(ix), cx);
+                this.open_entry(ix, window, cx);
+            }))
+            .into_any_element()
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let count = self.entries.len();
+        let summary = match count {
+            0 => String::new(),
+            1 => "1 bookmark".to_string(),
+            count => format!("{count} bookmarks"),
+        };
+
+        h_flex()
+            .h(Tab::container_height(cx))
+            .px_2()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(summary)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                IconButton::new("clear-bookmarks", IconName::Trash)
+                    .icon_size(IconSize::Small)
+                    .disabled(count == 0)
+                    .tooltip(Tooltip::text("Remove All Bookmarks"))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.clear_all(&ClearAll, window, cx)),
+                    ),
+            )
+    }
+}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::new_with_defaults();
+        key_context.add("BookmarksPanel");
+
+        v_flex()
+            .id("bookmarks-panel")
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::remove_selected))
+            .on_action(cx.listener(Self::edit_label))
+            .on_action(cx.listener(Self::clear_all))
+            .child(self.render_header(cx))
+            .map(|this| {
+                if self.entries.is_empty() {
+                    this.child(
+                        v_flex().p_4().gap_2().child(
+                            Label::new("No bookmarks yet. Toggle a bookmark on a line of a file to add it here.")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        div()
+                            .flex_1()
+                            .size_full()
+                            .child(
+                                uniform_list(
+                                    "bookmarks-panel-entries",
+                                    self.entries.len(),
+                                    cx.processor(|this, range: Range<usize>, _, cx| {
+                                        range.map(|ix| this.render_entry(ix, cx)).collect()
+                                    }),
+                                )
+                                .track_scroll(self.scroll_handle.clone())
+                                .size_full(),
+                            )
+                            .vertical_scrollbar_for(self.scroll_handle.clone(), window, cx),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for BookmarksPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "BookmarksPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        BookmarksPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.bookmarks_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BookmarksPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        BookmarksPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Bookmark)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
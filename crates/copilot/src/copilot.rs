@@ -537,6 +537,10 @@ impl Copilot {
                 .update(|cx| {
                     let mut params = server.default_initialize_params(false, cx);
                     params.initialization_options = Some(editor_info_json);
+                    // Copilot's own requests are exchanged in UTF-16 positions.
+                    if let Some(general) = params.capabilities.general.as_mut() {
+                        general.position_encodings = Some(vec![lsp::PositionEncodingKind::UTF16]);
+                    }
                     server.initialize(params, configuration.into(), cx)
                 })?
                 .await?;
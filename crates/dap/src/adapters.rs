@@ -6,7 +6,7 @@ use collections::HashMap;
 pub use dap_types::{StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest};
 use fs::Fs;
 use futures::io::BufReader;
-use gpui::{AsyncApp, SharedString};
+use gpui::{AsyncApp, SharedString, Task};
 pub use http_client::{HttpClient, github::latest_github_release};
 use language::{LanguageName, LanguageToolchainStore};
 use node_runtime::NodeRuntime;
@@ -46,6 +46,16 @@ pub trait DapDelegate: Send + Sync + 'static {
     async fn which(&self, command: &OsStr) -> Option<PathBuf>;
     async fn read_text_file(&self, path: &RelPath) -> Result<String>;
     async fn shell_env(&self) -> collections::HashMap<String, String>;
+    /// Sends a `workspace/executeCommand` request to the named language server running for this worktree.
+    ///
+    /// Used by adapters whose debug server is hosted by a language server, such as java-debug in jdtls.
+    fn execute_language_server_command(
+        &self,
+        server_name: &str,
+        command: String,
+        arguments: Vec<serde_json::Value>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<serde_json::Value>>;
 }
 
 #[derive(
@@ -477,3 +487,102 @@ impl DebugAdapter for FakeAdapter {
         })
     }
 }
+
+/// A [`DapDelegate`] for testing adapters without a project, which resolves binaries
+/// and language server commands from fixed tables.
+#[cfg(any(test, feature = "test-support"))]
+pub struct FakeDapDelegate {
+    pub worktree_root: PathBuf,
+    pub fs: Arc<dyn Fs>,
+    /// The paths [`DapDelegate::which`] finds, by command name.
+    pub binaries: HashMap<String, PathBuf>,
+    /// The results of `workspace/executeCommand` requests, by command.
+    pub language_server_commands: HashMap<String, serde_json::Value>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl FakeDapDelegate {
+    pub fn new(worktree_root: impl Into<PathBuf>, fs: Arc<dyn Fs>) -> Self {
+        Self {
+            worktree_root: worktree_root.into(),
+            fs,
+            binaries: HashMap::default(),
+            language_server_commands: HashMap::default(),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+struct NoToolchains;
+
+#[cfg(any(test, feature = "test-support"))]
+#[async_trait(?Send)]
+impl LanguageToolchainStore for NoToolchains {
+    async fn active_toolchain(
+        self: Arc<Self>,
+        _: WorktreeId,
+        _: Arc<RelPath>,
+        _: LanguageName,
+        _: &mut AsyncApp,
+    ) -> Option<language::Toolchain> {
+        None
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+#[async_trait]
+impl DapDelegate for FakeDapDelegate {
+    fn worktree_id(&self) -> WorktreeId {
+        WorktreeId::from_usize(0)
+    }
+
+    fn worktree_root_path(&self) -> &Path {
+        &self.worktree_root
+    }
+
+    fn http_client(&self) -> Arc<dyn HttpClient> {
+        Arc::new(http_client::BlockedHttpClient)
+    }
+
+    fn node_runtime(&self) -> NodeRuntime {
+        NodeRuntime::unavailable()
+    }
+
+    fn toolchain_store(&self) -> Arc<dyn LanguageToolchainStore> {
+        Arc::new(NoToolchains)
+    }
+
+    fn fs(&self) -> Arc<dyn Fs> {
+        self.fs.clone()
+    }
+
+    fn output_to_console(&self, _: String) {}
+
+    async fn which(&self, command: &OsStr) -> Option<PathBuf> {
+        self.binaries.get(command.to_str()?).cloned()
+    }
+
+    async fn read_text_file(&self, path: &RelPath) -> Result<String> {
+        let path = self.worktree_root.join(path.as_std_path());
+        self.fs.load(&path).await
+    }
+
+    async fn shell_env(&self) -> collections::HashMap<String, String> {
+        collections::HashMap::default()
+    }
+
+    fn execute_language_server_command(
+        &self,
+        server_name: &str,
+        command: String,
+        _: Vec<serde_json::Value>,
+        _: &mut AsyncApp,
+    ) -> Task<Result<serde_json::Value>> {
+        Task::ready(
+            self.language_server_commands
+                .get(&command)
+                .cloned()
+                .with_context(|| format!("{server_name} doesn't support `{command}`")),
+        )
+    }
+}
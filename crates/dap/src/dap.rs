@@ -21,7 +21,7 @@ pub type VariableReference = u64;
 pub type StackFrameId = u64;
 
 #[cfg(any(test, feature = "test-support"))]
-pub use adapters::FakeAdapter;
+pub use adapters::{FakeAdapter, FakeDapDelegate};
 use task::{DebugScenario, TcpArgumentsTemplate};
 
 pub async fn configure_tcp_connection(
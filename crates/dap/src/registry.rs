@@ -29,6 +29,9 @@ pub trait DapLocator: Send + Sync {
 #[derive(Default)]
 struct DapRegistryState {
     adapters: BTreeMap<DebugAdapterName, Arc<dyn DebugAdapter>>,
+    /// Adapters replaced by another one with the same name, such as built-in adapters
+    /// that an extension also provides. They come back once the replacement is removed.
+    shadowed_adapters: BTreeMap<DebugAdapterName, Arc<dyn DebugAdapter>>,
     locators: FxHashMap<SharedString, Arc<dyn DapLocator>>,
 }
 
@@ -44,7 +47,10 @@ impl DapRegistry {
 
     pub fn add_adapter(&self, adapter: Arc<dyn DebugAdapter>) {
         let name = adapter.name();
-        let _previous_value = self.0.write().adapters.insert(name, adapter);
+        let mut state = self.0.write();
+        if let Some(previous) = state.adapters.insert(name.clone(), adapter) {
+            state.shadowed_adapters.entry(name).or_insert(previous);
+        }
     }
 
     pub fn add_locator(&self, locator: Arc<dyn DapLocator>) {
@@ -52,7 +58,12 @@ impl DapRegistry {
     }
 
     pub fn remove_adapter(&self, name: &str) {
-        self.0.write().adapters.remove(name);
+        let mut state = self.0.write();
+        if let Some(shadowed) = state.shadowed_adapters.remove(name) {
+            state.adapters.insert(shadowed.name(), shadowed);
+        } else {
+            state.adapters.remove(name);
+        }
     }
 
     pub fn remove_locator(&self, locator: &str) {
@@ -91,3 +102,28 @@ impl DapRegistry {
         self.0.read().adapters.keys().cloned().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FakeAdapter;
+
+    #[test]
+    fn test_removing_an_adapter_restores_the_one_it_replaced() {
+        let registry = DapRegistry::default();
+        let built_in: Arc<dyn DebugAdapter> = Arc::new(FakeAdapter::new());
+        let from_extension: Arc<dyn DebugAdapter> = Arc::new(FakeAdapter::new());
+
+        registry.add_adapter(built_in.clone());
+        registry.add_adapter(from_extension.clone());
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(Arc::ptr_eq(&adapter, &from_extension));
+
+        registry.remove_adapter(FakeAdapter::ADAPTER_NAME);
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(Arc::ptr_eq(&adapter, &built_in));
+
+        registry.remove_adapter(FakeAdapter::ADAPTER_NAME);
+        assert!(registry.adapter(FakeAdapter::ADAPTER_NAME).is_none());
+    }
+}
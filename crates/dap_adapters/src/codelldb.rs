@@ -93,6 +93,9 @@ impl DebugAdapter for CodeLldbDebugAdapter {
             "request": match zed_scenario.request {
                 DebugRequest::Launch(_) => "launch",
                 DebugRequest::Attach(_) => "attach",
+                // CodeLLDB loads core dumps through a `launch` request that creates
+                // the target from the dump instead of starting a process.
+                DebugRequest::CoreDump(_) => "launch",
             },
         });
         let map = configuration.as_object_mut().unwrap();
@@ -121,6 +124,37 @@ impl DebugAdapter for CodeLldbDebugAdapter {
                     map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
                 }
             }
+            DebugRequest::CoreDump(core_dump) => {
+                map.insert("program".into(), core_dump.program.clone().into());
+                map.insert("coreFile".into(), core_dump.core_file.clone().into());
+
+                let mut init_commands = Vec::new();
+                if let Some(sysroot) = core_dump.sysroot.as_ref() {
+                    init_commands.push(format!(
+                        "platform select --sysroot {} host",
+                        quote_lldb_argument(&sysroot.to_string_lossy())
+                    ));
+                }
+                for path in &core_dump.solib_search_paths {
+                    init_commands.push(format!(
+                        "settings append target.exec-search-paths {}",
+                        quote_lldb_argument(&path.to_string_lossy())
+                    ));
+                }
+                if !init_commands.is_empty() {
+                    map.insert("initCommands".into(), init_commands.into());
+                }
+                map.insert(
+                    "targetCreateCommands".into(),
+                    vec![format!(
+                        "target create {} --core {}",
+                        quote_lldb_argument(&core_dump.program),
+                        quote_lldb_argument(&core_dump.core_file)
+                    )]
+                    .into(),
+                );
+                map.insert("processCreateCommands".into(), Vec::<String>::new().into());
+            }
         }
 
         Ok(DebugScenario {
@@ -185,6 +219,10 @@ impl DebugAdapter for CodeLldbDebugAdapter {
                     "description": "Automatically stop debuggee after launch",
                     "default": false
                 },
+                "coreFile": {
+                    "type": "string",
+                    "description": "Core dump loaded by `targetCreateCommands`. Zed treats sessions with a core file as read-only"
+                },
                 "initCommands": {
                     "type": "array",
                     "description": "Initialization commands executed upon debugger startup",
@@ -406,3 +444,57 @@ impl DebugAdapter for CodeLldbDebugAdapter {
         })
     }
 }
+
+/// Quotes an argument for LLDB's command interpreter. Unlike Rust string literals, only
+/// backslashes, double quotes and backticks are escaped within double quotes.
+fn quote_lldb_argument(argument: &str) -> String {
+    let mut quoted = String::with_capacity(argument.len() + 2);
+    quoted.push('"');
+    for c in argument.chars() {
+        if matches!(c, '\\' | '"' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use task::CoreDumpRequest;
+
+    #[test]
+    fn test_quote_lldb_argument() {
+        assert_eq!(quote_lldb_argument("/tmp/app"), r#""/tmp/app""#);
+        assert_eq!(
+            quote_lldb_argument(r#"/tmp/my "app"/crash\core"#),
+            r#""/tmp/my \"app\"/crash\\core""#
+        );
+        // Other characters are passed through as is rather than escaped.
+        assert_eq!(quote_lldb_argument("/tmp/é\tapp"), "\"/tmp/é\tapp\"");
+    }
+
+    #[gpui::test]
+    async fn test_core_dump_config() {
+        let scenario = CodeLldbDebugAdapter::default()
+            .config_from_zed_format(ZedDebugConfig {
+                label: "core".into(),
+                adapter: "CodeLLDB".into(),
+                request: DebugRequest::CoreDump(CoreDumpRequest {
+                    program: "/work/my app".into(),
+                    core_file: "/work/core.1234".into(),
+                    sysroot: None,
+                    solib_search_paths: Vec::new(),
+                }),
+                stop_on_entry: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            scenario.config["targetCreateCommands"],
+            json!([r#"target create "/work/my app" --core "/work/core.1234""#])
+        );
+    }
+}
@@ -1,8 +1,12 @@
 mod codelldb;
+mod dotnet;
 mod gdb;
 mod go;
+mod java;
 mod javascript;
+mod php;
 mod python;
+mod ruby;
 
 use std::sync::Arc;
 
@@ -16,11 +20,15 @@ use dap::{
     },
     configure_tcp_connection,
 };
+use dotnet::DotNetDebugAdapter;
 use gdb::GdbDebugAdapter;
 use go::GoDebugAdapter;
 use gpui::{App, BorrowAppContext};
+use java::JavaDebugAdapter;
 use javascript::JsDebugAdapter;
+use php::PhpDebugAdapter;
 use python::PythonDebugAdapter;
+use ruby::RubyDebugAdapter;
 use serde_json::json;
 use task::{DebugScenario, ZedDebugConfig};
 
@@ -31,6 +39,10 @@ pub fn init(cx: &mut App) {
         registry.add_adapter(Arc::from(JsDebugAdapter::default()));
         registry.add_adapter(Arc::from(GoDebugAdapter::default()));
         registry.add_adapter(Arc::from(GdbDebugAdapter));
+        registry.add_adapter(Arc::from(RubyDebugAdapter));
+        registry.add_adapter(Arc::from(PhpDebugAdapter::default()));
+        registry.add_adapter(Arc::from(JavaDebugAdapter::default()));
+        registry.add_adapter(Arc::from(DotNetDebugAdapter::default()));
 
         #[cfg(any(test, feature = "test-support"))]
         {
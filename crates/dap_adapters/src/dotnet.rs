@@ -0,0 +1,346 @@
+use std::{collections::HashMap, ffi::OsStr, path::PathBuf, sync::OnceLock};
+
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use dap::{
+    StartDebuggingRequestArguments,
+    adapters::{DebugTaskDefinition, latest_github_release},
+};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use serde_json::Value;
+use task::{DebugRequest, DebugScenario, ZedDebugConfig};
+use util::ResultExt as _;
+
+use crate::*;
+
+#[derive(Debug, Default)]
+pub(crate) struct DotNetDebugAdapter {
+    checked: OnceLock<()>,
+}
+
+impl DotNetDebugAdapter {
+    const ADAPTER_NAME: &'static str = "netcoredbg";
+    const ADAPTER_REPO: &'static str = "Samsung/netcoredbg";
+
+    async fn fetch_latest_adapter_version(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+    ) -> Result<(AdapterVersion, adapters::DownloadedFileType)> {
+        let release =
+            latest_github_release(Self::ADAPTER_REPO, true, false, delegate.http_client()).await?;
+
+        let (asset_name, file_type) = match (std::env::consts::OS, std::env::consts::ARCH) {
+            ("linux", "x86_64") => (
+                "netcoredbg-linux-amd64.tar.gz",
+                adapters::DownloadedFileType::GzipTar,
+            ),
+            ("linux", "aarch64") => (
+                "netcoredbg-linux-arm64.tar.gz",
+                adapters::DownloadedFileType::GzipTar,
+            ),
+            // netcoredbg only publishes x86_64 builds for macOS, which run under Rosetta.
+            ("macos", _) => (
+                "netcoredbg-osx-amd64.tar.gz",
+                adapters::DownloadedFileType::GzipTar,
+            ),
+            ("windows", "x86_64") => ("netcoredbg-win64.zip", adapters::DownloadedFileType::Zip),
+            (os, arch) => bail!("netcoredbg has no release for {os} on {arch}"),
+        };
+
+        let version = AdapterVersion {
+            tag_name: release.tag_name,
+            url: release
+                .assets
+                .iter()
+                .find(|asset| asset.name == asset_name)
+                .with_context(|| format!("no asset found matching {asset_name:?}"))?
+                .browser_download_url
+                .clone(),
+        };
+        Ok((version, file_type))
+    }
+
+    async fn find_or_install_netcoredbg(&self, delegate: &Arc<dyn DapDelegate>) -> Result<PathBuf> {
+        if let Some(path) = delegate.which(OsStr::new("netcoredbg")).await {
+            return Ok(path);
+        }
+
+        if self.checked.set(()).is_ok() {
+            delegate.output_to_console(format!("Checking latest version of {}...", self.name()));
+            if let Some((version, file_type)) =
+                self.fetch_latest_adapter_version(delegate).await.log_err()
+            {
+                adapters::download_adapter_from_github(
+                    self.name(),
+                    version,
+                    file_type,
+                    delegate.as_ref(),
+                )
+                .await?;
+            }
+        }
+
+        let adapter_dir = paths::debug_adapters_dir().join(self.name().as_ref());
+        let file_name_prefix = format!("{}_", self.name());
+        let version_dir = util::fs::find_file_name_in_dir(adapter_dir.as_path(), |file_name| {
+            file_name.starts_with(&file_name_prefix)
+        })
+        .await
+        .context("Couldn't find the netcoredbg directory")?;
+
+        let binary_name = if cfg!(windows) {
+            "netcoredbg.exe"
+        } else {
+            "netcoredbg"
+        };
+        Ok(version_dir.join("netcoredbg").join(binary_name))
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for DotNetDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("C#").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = serde_json::Map::new();
+        config.insert("type".into(), "coreclr".into());
+
+        match &zed_scenario.request {
+            DebugRequest::Launch(launch) => {
+                config.insert("request".into(), "launch".into());
+                config.insert("program".into(), launch.program.clone().into());
+
+                if !launch.args.is_empty() {
+                    config.insert("args".into(), launch.args.clone().into());
+                }
+                if !launch.env.is_empty() {
+                    config.insert("env".into(), launch.env_json());
+                }
+                if let Some(cwd) = launch.cwd.as_ref() {
+                    config.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+                }
+                if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+                    config.insert("stopAtEntry".into(), stop_on_entry.into());
+                }
+            }
+            DebugRequest::Attach(attach) => {
+                config.insert("request".into(), "attach".into());
+                config.insert("processId".into(), attach.process_id.into());
+            }
+            DebugRequest::CoreDump(_) => {
+                bail!("{} doesn't support loading core dumps", self.name());
+            }
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config: Value::Object(config),
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a .NET program"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "program": {
+                                    "type": "string",
+                                    "description": "Path to the application dll, e.g. `bin/Debug/net8.0/App.dll`"
+                                },
+                                "args": {
+                                    "type": "array",
+                                    "description": "Command line arguments passed to the program",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "default": []
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the program"
+                                },
+                                "env": {
+                                    "type": "object",
+                                    "description": "Environment variables passed to the program",
+                                    "default": {}
+                                },
+                                "stopAtEntry": {
+                                    "type": "boolean",
+                                    "description": "Stop at the entry point of the program",
+                                    "default": false
+                                },
+                                "justMyCode": {
+                                    "type": "boolean",
+                                    "description": "Only step through user code",
+                                    "default": true
+                                },
+                                "enableStepFiltering": {
+                                    "type": "boolean",
+                                    "description": "Step over properties and operators",
+                                    "default": true
+                                }
+                            },
+                            "required": ["program"]
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to a running .NET process"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "processId": {
+                                    "type": ["string", "number"],
+                                    "description": "ID of the process to attach to"
+                                }
+                            },
+                            "required": ["processId"]
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let netcoredbg_path = if let Some(path) = user_installed_path {
+            path
+        } else {
+            self.find_or_install_netcoredbg(delegate).await?
+        };
+
+        let mut configuration = task_definition.config.clone();
+        let mut envs = HashMap::default();
+        if let Some(configuration) = configuration.as_object_mut() {
+            configuration
+                .entry("cwd")
+                .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+            // VS Code's `stopAtEntry` is the C# extension's spelling; accept the generic one too.
+            if let Some(stop_on_entry) = configuration.remove("stopOnEntry") {
+                configuration.entry("stopAtEntry").or_insert(stop_on_entry);
+            }
+
+            if let Some(env) = configuration.get("env").cloned()
+                && let Ok(env) = serde_json::from_value(env)
+            {
+                envs = env;
+            }
+        }
+
+        Ok(DebugAdapterBinary {
+            command: Some(netcoredbg_path.to_string_lossy().into_owned()),
+            arguments: user_args.unwrap_or_else(|| vec!["--interpreter=vscode".into()]),
+            envs,
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            connection: None,
+            request_args: StartDebuggingRequestArguments {
+                request: self.request_kind(&task_definition.config).await?,
+                configuration,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap::{FakeDapDelegate, StartDebuggingRequestArgumentsRequest};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_get_binary(cx: &mut TestAppContext) {
+        let delegate: Arc<dyn DapDelegate> = Arc::new(FakeDapDelegate::new(
+            path!("/project"),
+            FakeFs::new(cx.executor()),
+        ));
+        let task_definition = DebugTaskDefinition {
+            label: "run app".into(),
+            adapter: DotNetDebugAdapter::ADAPTER_NAME.into(),
+            config: json!({
+                "request": "launch",
+                "program": "bin/Debug/net8.0/App.dll",
+                "stopOnEntry": true,
+                "env": { "DOTNET_ENVIRONMENT": "Development" },
+            }),
+            tcp_connection: None,
+        };
+        let binary = DotNetDebugAdapter::default()
+            .get_binary(
+                &delegate,
+                &task_definition,
+                Some(PathBuf::from("/opt/netcoredbg/netcoredbg")),
+                None,
+                &mut cx.to_async(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            binary.command.as_deref(),
+            Some("/opt/netcoredbg/netcoredbg")
+        );
+        assert_eq!(binary.arguments, ["--interpreter=vscode"]);
+        assert_eq!(
+            binary.envs.get("DOTNET_ENVIRONMENT").map(String::as_str),
+            Some("Development")
+        );
+        assert_eq!(
+            binary.request_args.request,
+            StartDebuggingRequestArgumentsRequest::Launch
+        );
+        assert_eq!(
+            binary.request_args.configuration,
+            json!({
+                "request": "launch",
+                "program": "bin/Debug/net8.0/App.dll",
+                "stopAtEntry": true,
+                "env": { "DOTNET_ENVIRONMENT": "Development" },
+                "cwd": path!("/project"),
+            })
+        );
+    }
+}
@@ -13,6 +13,57 @@ pub(crate) struct GdbDebugAdapter;
 
 impl GdbDebugAdapter {
     const ADAPTER_NAME: &'static str = "GDB";
+
+    /// GDB's DAP interpreter doesn't accept the shared library settings needed for core
+    /// dumps taken on another machine, so they are removed from the request and set on
+    /// the command line before the dump is loaded.
+    fn take_shared_library_arguments(
+        configuration: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Vec<String>> {
+        let mut arguments = Vec::new();
+        if let Some(sysroot) = configuration.remove("sysroot") {
+            let sysroot = sysroot.as_str().context("`sysroot` must be a path")?;
+            arguments.push("-iex".into());
+            arguments.push(format!("set sysroot {sysroot}"));
+        }
+        if let Some(search_path) = configuration.remove("solibSearchPath") {
+            let search_path = search_path
+                .as_array()
+                .context("`solibSearchPath` must be a list of paths")?
+                .iter()
+                .filter_map(|path| path.as_str())
+                .collect::<Vec<_>>()
+                .join(":");
+            arguments.push("-iex".into());
+            arguments.push(format!("set solib-search-path {search_path}"));
+        }
+        Ok(arguments)
+    }
+
+    /// GDB's attach request only connects to a live process or a remote target, so core
+    /// dumps are loaded on the command line instead. The session then starts with a launch
+    /// request that has no program, which GDB answers without running anything.
+    fn take_core_dump_arguments(
+        configuration: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Vec<String>> {
+        let Some(core_file) = configuration.get("coreFile").filter(|core| !core.is_null()) else {
+            return Ok(Vec::new());
+        };
+        let core_file = core_file
+            .as_str()
+            .context("`coreFile` must be a path")?
+            .to_owned();
+        let program = configuration
+            .remove("program")
+            .context("core dumps need the `program` that produced them")?;
+        let program = program.as_str().context("`program` must be a path")?;
+
+        configuration.insert("request".into(), "launch".into());
+        Ok(vec![
+            format!("--se={program}"),
+            format!("--core={core_file}"),
+        ])
+    }
 }
 
 #[async_trait(?Send)]
@@ -52,6 +103,30 @@ impl DebugAdapter for GdbDebugAdapter {
                     obj.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
                 }
             }
+
+            dap::DebugRequest::CoreDump(core_dump) => {
+                obj.insert("request".into(), "attach".into());
+                obj.insert("program".into(), core_dump.program.clone().into());
+                obj.insert("coreFile".into(), core_dump.core_file.clone().into());
+
+                if let Some(sysroot) = core_dump.sysroot.as_ref() {
+                    obj.insert(
+                        "sysroot".into(),
+                        sysroot.to_string_lossy().into_owned().into(),
+                    );
+                }
+                if !core_dump.solib_search_paths.is_empty() {
+                    obj.insert(
+                        "solibSearchPath".into(),
+                        core_dump
+                            .solib_search_paths
+                            .iter()
+                            .map(|path| path.to_string_lossy().into_owned())
+                            .collect::<Vec<_>>()
+                            .into(),
+                    );
+                }
+            }
         }
 
         Ok(DebugScenario {
@@ -149,6 +224,46 @@ impl DebugAdapter for GdbDebugAdapter {
                             "required": ["pid"]
                         }
                     ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to inspect a core dump"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "program": {
+                                    "type": "string",
+                                    "description": "The program that produced the core dump. This corresponds to the GDB 'file' command."
+                                },
+                                "coreFile": {
+                                    "type": "string",
+                                    "description": "The core dump to load. This corresponds to GDB's '--core' option. The session is read-only: the program can't be resumed."
+                                },
+                                "sysroot": {
+                                    "type": "string",
+                                    "description": "The directory containing the target's shared libraries, for dumps taken on another machine. This corresponds to GDB's 'set sysroot'."
+                                },
+                                "solibSearchPath": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Additional directories to search for shared libraries. This corresponds to GDB's 'set solib-search-path'."
+                                }
+                            },
+                            "required": ["program", "coreFile"]
+                        }
+                    ]
                 }
             ]
         })
@@ -179,22 +294,94 @@ impl DebugAdapter for GdbDebugAdapter {
         let gdb_path = user_setting_path.unwrap_or(gdb_path?);
 
         let mut configuration = config.config.clone();
+        let mut arguments = user_args.unwrap_or_else(|| vec!["-i=dap".into()]);
         if let Some(configuration) = configuration.as_object_mut() {
             configuration
                 .entry("cwd")
                 .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+
+            arguments.extend(Self::take_shared_library_arguments(configuration)?);
+            arguments.extend(Self::take_core_dump_arguments(configuration)?);
         }
 
         Ok(DebugAdapterBinary {
             command: Some(gdb_path),
-            arguments: user_args.unwrap_or_else(|| vec!["-i=dap".into()]),
+            arguments,
             envs: HashMap::default(),
             cwd: Some(delegate.worktree_root_path().to_path_buf()),
             connection: None,
             request_args: StartDebuggingRequestArguments {
-                request: self.request_kind(&config.config).await?,
+                request: self.request_kind(&configuration).await?,
                 configuration,
             },
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use task::{CoreDumpRequest, DebugRequest};
+
+    #[gpui::test]
+    async fn test_core_dump_config() {
+        let scenario = GdbDebugAdapter
+            .config_from_zed_format(ZedDebugConfig {
+                label: "core".into(),
+                adapter: GdbDebugAdapter::ADAPTER_NAME.into(),
+                request: DebugRequest::CoreDump(CoreDumpRequest {
+                    program: "/work/app".into(),
+                    core_file: "/work/core.1234".into(),
+                    sysroot: Some(PathBuf::from("/sysroots/arm64")),
+                    solib_search_paths: vec![PathBuf::from("/work/lib"), PathBuf::from("/opt/lib")],
+                }),
+                stop_on_entry: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "attach",
+                "program": "/work/app",
+                "coreFile": "/work/core.1234",
+                "sysroot": "/sysroots/arm64",
+                "solibSearchPath": ["/work/lib", "/opt/lib"],
+            })
+        );
+
+        let mut configuration = scenario.config.as_object().unwrap().clone();
+        assert_eq!(
+            GdbDebugAdapter::take_shared_library_arguments(&mut configuration).unwrap(),
+            [
+                "-iex",
+                "set sysroot /sysroots/arm64",
+                "-iex",
+                "set solib-search-path /work/lib:/opt/lib",
+            ]
+        );
+        assert_eq!(
+            GdbDebugAdapter::take_core_dump_arguments(&mut configuration).unwrap(),
+            ["--se=/work/app", "--core=/work/core.1234"]
+        );
+        assert_eq!(
+            serde_json::Value::Object(configuration),
+            json!({
+                "request": "launch",
+                "coreFile": "/work/core.1234",
+            })
+        );
+
+        let mut configuration = json!({ "request": "launch", "program": "/work/app" })
+            .as_object()
+            .unwrap()
+            .clone();
+        assert!(
+            GdbDebugAdapter::take_core_dump_arguments(&mut configuration)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(configuration["program"], "/work/app");
+    }
+}
@@ -370,6 +370,9 @@ impl DebugAdapter for GoDebugAdapter {
                     "processId": attach_config.process_id,
                 })
             }
+            dap::DebugRequest::CoreDump(_) => {
+                bail!("{} doesn't support loading core dumps", self.name());
+            }
             dap::DebugRequest::Launch(launch_config) => {
                 let mode = if launch_config.program != "." {
                     "exec"
@@ -0,0 +1,480 @@
+use std::{net::Ipv4Addr, path::PathBuf, sync::OnceLock};
+
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use dap::{
+    StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
+    adapters::DebugTaskDefinition,
+};
+use futures::AsyncReadExt as _;
+use gpui::{AsyncApp, SharedString, http_client::AsyncBody};
+use language::LanguageName;
+use serde_json::Value;
+use smol::fs::File;
+use task::{DebugRequest, DebugScenario, ZedDebugConfig};
+use util::ResultExt as _;
+
+use crate::*;
+
+/// java-debug doesn't run on its own: it's a plugin of the Eclipse JDT language
+/// server, which starts a debug server on request and reports its port.
+#[derive(Debug, Default)]
+pub(crate) struct JavaDebugAdapter {
+    checked: OnceLock<()>,
+}
+
+impl JavaDebugAdapter {
+    const ADAPTER_NAME: &'static str = "Java";
+    const LANGUAGE_SERVER_NAME: &'static str = "jdtls";
+    const MAVEN_ARTIFACT_URL: &'static str =
+        "https://repo1.maven.org/maven2/com/microsoft/java/com.microsoft.java.debug.plugin";
+
+    /// Downloads the latest java-debug plugin, so that it can be listed in the
+    /// `bundles` jdtls is initialized with.
+    async fn fetch_latest_plugin(&self, delegate: &Arc<dyn DapDelegate>) -> Result<PathBuf> {
+        let http_client = delegate.http_client();
+        let mut response = http_client
+            .get(
+                &format!("{}/maven-metadata.xml", Self::MAVEN_ARTIFACT_URL),
+                AsyncBody::empty(),
+                true,
+            )
+            .await?;
+        anyhow::ensure!(
+            response.status().is_success(),
+            "fetching java-debug versions failed with status {}",
+            response.status()
+        );
+        let mut metadata = String::new();
+        response.body_mut().read_to_string(&mut metadata).await?;
+        let version = metadata
+            .split_once("<release>")
+            .and_then(|(_, rest)| rest.split_once("</release>"))
+            .map(|(version, _)| version.trim().to_owned())
+            .context("no release found in java-debug maven metadata")?;
+
+        let adapter_dir = paths::debug_adapters_dir().join(Self::ADAPTER_NAME);
+        let jar_path = adapter_dir.join(format!("com.microsoft.java.debug.plugin-{version}.jar"));
+        if delegate.fs().is_file(&jar_path).await {
+            return Ok(jar_path);
+        }
+
+        delegate.fs().create_dir(&adapter_dir).await?;
+        let url = format!(
+            "{}/{version}/com.microsoft.java.debug.plugin-{version}.jar",
+            Self::MAVEN_ARTIFACT_URL
+        );
+        delegate.output_to_console(format!("Downloading from {url}..."));
+        let mut response = http_client.get(&url, AsyncBody::empty(), true).await?;
+        anyhow::ensure!(
+            response.status().is_success(),
+            "download failed with status {}",
+            response.status()
+        );
+
+        // Keep a single version of the plugin around.
+        util::fs::remove_matching(&adapter_dir, |entry| {
+            entry
+                .extension()
+                .is_some_and(|extension| extension == "jar")
+        })
+        .await;
+        let mut file = File::create(&jar_path).await?;
+        futures::io::copy(response.body_mut(), &mut file).await?;
+        delegate.output_to_console("Download complete".to_owned());
+
+        Ok(jar_path)
+    }
+
+    async fn plugin_path(&self, delegate: &Arc<dyn DapDelegate>) -> Option<PathBuf> {
+        if self.checked.set(()).is_ok()
+            && let Some(path) = self.fetch_latest_plugin(delegate).await.log_err()
+        {
+            return Some(path);
+        }
+
+        let adapter_dir = paths::debug_adapters_dir().join(Self::ADAPTER_NAME);
+        util::fs::find_file_name_in_dir(&adapter_dir, |file_name| file_name.ends_with(".jar")).await
+    }
+
+    async fn execute_command(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        command: &str,
+        arguments: Vec<Value>,
+        cx: &mut AsyncApp,
+    ) -> Result<Value> {
+        delegate
+            .execute_language_server_command(
+                Self::LANGUAGE_SERVER_NAME,
+                command.to_owned(),
+                arguments,
+                cx,
+            )
+            .await
+            .with_context(|| format!("running `{command}` in {}", Self::LANGUAGE_SERVER_NAME))
+    }
+
+    /// Fills in the class path and Java runtime of a launch configuration from
+    /// the project model jdtls has built.
+    async fn resolve_launch_configuration(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        configuration: &mut serde_json::Map<String, Value>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let main_class = configuration
+            .get("mainClass")
+            .cloned()
+            .context("Java launch configurations need a `mainClass`")?;
+        let project_name = configuration
+            .get("projectName")
+            .cloned()
+            .unwrap_or(Value::Null);
+
+        if !configuration.contains_key("classPaths") && !configuration.contains_key("modulePaths") {
+            let resolved = self
+                .execute_command(
+                    delegate,
+                    "vscode.java.resolveClasspath",
+                    vec![main_class.clone(), project_name.clone()],
+                    cx,
+                )
+                .await?;
+            if let Value::Array(mut paths) = resolved
+                && paths.len() == 2
+            {
+                let class_paths = paths.pop().unwrap_or_default();
+                let module_paths = paths.pop().unwrap_or_default();
+                configuration.insert("modulePaths".into(), module_paths);
+                configuration.insert("classPaths".into(), class_paths);
+            }
+        }
+
+        if !configuration.contains_key("javaExec") {
+            let java_exec = self
+                .execute_command(
+                    delegate,
+                    "vscode.java.resolveJavaExecutable",
+                    vec![main_class, project_name],
+                    cx,
+                )
+                .await?;
+            if java_exec.is_string() {
+                configuration.insert("javaExec".into(), java_exec);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for JavaDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("Java").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = serde_json::Map::new();
+        config.insert("type".into(), "java".into());
+
+        match &zed_scenario.request {
+            DebugRequest::Launch(launch) => {
+                config.insert("request".into(), "launch".into());
+                config.insert("mainClass".into(), launch.program.clone().into());
+
+                if !launch.args.is_empty() {
+                    config.insert("args".into(), launch.args.clone().into());
+                }
+                if !launch.env.is_empty() {
+                    config.insert("env".into(), launch.env_json());
+                }
+                if let Some(cwd) = launch.cwd.as_ref() {
+                    config.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+                }
+                if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+                    config.insert("stopOnEntry".into(), stop_on_entry.into());
+                }
+            }
+            DebugRequest::Attach(_) => {
+                bail!(
+                    "{} attaches over JDWP rather than to a process id; set `hostName` and `port` in debug.json instead",
+                    self.name()
+                );
+            }
+            DebugRequest::CoreDump(_) => {
+                bail!("{} doesn't support loading core dumps", self.name());
+            }
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config: Value::Object(config),
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a Java program"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "mainClass": {
+                                    "type": "string",
+                                    "description": "The fully qualified name of the class containing `main`"
+                                },
+                                "projectName": {
+                                    "type": "string",
+                                    "description": "The project the main class belongs to, when several projects define it"
+                                },
+                                "args": {
+                                    "type": ["array", "string"],
+                                    "description": "Command line arguments passed to the program",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "default": []
+                                },
+                                "vmArgs": {
+                                    "type": ["array", "string"],
+                                    "description": "Extra options passed to the JVM",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "default": []
+                                },
+                                "classPaths": {
+                                    "type": "array",
+                                    "description": "The class path of the program. Resolved by jdtls when omitted",
+                                    "items": {
+                                        "type": "string"
+                                    }
+                                },
+                                "modulePaths": {
+                                    "type": "array",
+                                    "description": "The module path of the program. Resolved by jdtls when omitted",
+                                    "items": {
+                                        "type": "string"
+                                    }
+                                },
+                                "javaExec": {
+                                    "type": "string",
+                                    "description": "Path to the java executable. Resolved by jdtls when omitted"
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the program"
+                                },
+                                "env": {
+                                    "type": "object",
+                                    "description": "Environment variables passed to the program",
+                                    "default": {}
+                                },
+                                "stopOnEntry": {
+                                    "type": "boolean",
+                                    "description": "Stop at the start of `main`",
+                                    "default": false
+                                }
+                            },
+                            "required": ["mainClass"]
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to a JVM started with a JDWP agent"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "hostName": {
+                                    "type": "string",
+                                    "description": "The host the JDWP agent listens on",
+                                    "default": "localhost"
+                                },
+                                "port": {
+                                    "type": "number",
+                                    "description": "The port the JDWP agent listens on",
+                                    "default": 5005
+                                },
+                                "timeout": {
+                                    "type": "number",
+                                    "description": "Milliseconds to wait for the JVM before giving up",
+                                    "default": 30000
+                                }
+                            },
+                            "required": ["hostName", "port"]
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        _: Option<PathBuf>,
+        _: Option<Vec<String>>,
+        cx: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let plugin_path = self.plugin_path(delegate).await;
+        let request = self.request_kind(&task_definition.config).await?;
+
+        let mut configuration = task_definition.config.clone();
+        let configuration_map = configuration
+            .as_object_mut()
+            .context("Java debug configuration is not a valid json object")?;
+        configuration_map
+            .entry("cwd")
+            .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+
+        let port = self
+            .execute_command(delegate, "vscode.java.startDebugSession", Vec::new(), cx)
+            .await
+            .map_err(|error| {
+                let bundle_hint = plugin_path
+                    .as_ref()
+                    .map(|path| format!(" and that {path:?} is listed in `lsp.jdtls.initialization_options.bundles`"))
+                    .unwrap_or_default();
+                error.context(format!(
+                    "java-debug runs inside the Java language server: make sure jdtls is running for this project{bundle_hint}"
+                ))
+            })?
+            .as_u64()
+            .and_then(|port| u16::try_from(port).ok())
+            .context("jdtls didn't report the port of the debug server")?;
+
+        if request == StartDebuggingRequestArgumentsRequest::Launch {
+            self.resolve_launch_configuration(delegate, configuration_map, cx)
+                .await?;
+        }
+
+        Ok(DebugAdapterBinary {
+            command: None,
+            arguments: Vec::new(),
+            envs: HashMap::default(),
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            connection: Some(adapters::TcpArguments {
+                host: Ipv4Addr::LOCALHOST,
+                port,
+                timeout: task_definition
+                    .tcp_connection
+                    .as_ref()
+                    .and_then(|connection| connection.timeout),
+            }),
+            request_args: StartDebuggingRequestArguments {
+                request,
+                configuration,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap::FakeDapDelegate;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_get_binary(cx: &mut TestAppContext) {
+        let mut delegate = FakeDapDelegate::new(path!("/project"), FakeFs::new(cx.executor()));
+        delegate.language_server_commands.extend([
+            ("vscode.java.startDebugSession".to_string(), json!(5005)),
+            (
+                "vscode.java.resolveClasspath".to_string(),
+                json!([["/project/modules"], ["/project/target/classes"]]),
+            ),
+            (
+                "vscode.java.resolveJavaExecutable".to_string(),
+                json!("/usr/lib/jvm/bin/java"),
+            ),
+        ]);
+        let delegate: Arc<dyn DapDelegate> = Arc::new(delegate);
+        let task_definition = DebugTaskDefinition {
+            label: "run Main".into(),
+            adapter: JavaDebugAdapter::ADAPTER_NAME.into(),
+            config: json!({
+                "request": "launch",
+                "mainClass": "com.example.Main",
+            }),
+            tcp_connection: None,
+        };
+        let binary = JavaDebugAdapter::default()
+            .get_binary(&delegate, &task_definition, None, None, &mut cx.to_async())
+            .await
+            .unwrap();
+
+        // The debug server runs inside jdtls, so there is nothing to spawn.
+        assert_eq!(binary.command, None);
+        let connection = binary.connection.unwrap();
+        assert_eq!(connection.host, Ipv4Addr::LOCALHOST);
+        assert_eq!(connection.port, 5005);
+        assert_eq!(
+            binary.request_args.configuration,
+            json!({
+                "request": "launch",
+                "mainClass": "com.example.Main",
+                "cwd": path!("/project"),
+                "modulePaths": ["/project/modules"],
+                "classPaths": ["/project/target/classes"],
+                "javaExec": "/usr/lib/jvm/bin/java",
+            })
+        );
+    }
+
+    #[gpui::test]
+    async fn test_get_binary_without_jdtls(cx: &mut TestAppContext) {
+        let delegate: Arc<dyn DapDelegate> = Arc::new(FakeDapDelegate::new(
+            path!("/project"),
+            FakeFs::new(cx.executor()),
+        ));
+        let task_definition = DebugTaskDefinition {
+            label: "attach".into(),
+            adapter: JavaDebugAdapter::ADAPTER_NAME.into(),
+            config: json!({ "request": "attach", "port": 8000 }),
+            tcp_connection: None,
+        };
+        let error = JavaDebugAdapter::default()
+            .get_binary(&delegate, &task_definition, None, None, &mut cx.to_async())
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("make sure jdtls is running"));
+    }
+}
@@ -1,5 +1,5 @@
 use adapters::latest_github_release;
-use anyhow::Context as _;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use dap::{StartDebuggingRequestArguments, adapters::DebugTaskDefinition};
 use gpui::AsyncApp;
@@ -185,6 +185,9 @@ impl DebugAdapter for JsDebugAdapter {
             "request": match zed_scenario.request {
                 DebugRequest::Launch(_) => "launch",
                 DebugRequest::Attach(_) => "attach",
+                DebugRequest::CoreDump(_) => {
+                    bail!("{} doesn't support loading core dumps", self.name())
+                }
             },
         });
 
@@ -193,6 +196,7 @@ impl DebugAdapter for JsDebugAdapter {
             DebugRequest::Attach(attach) => {
                 map.insert("processId".into(), attach.process_id.into());
             }
+            DebugRequest::CoreDump(_) => {}
             DebugRequest::Launch(launch) => {
                 if launch.program.starts_with("http://") {
                     map.insert("url".into(), launch.program.clone().into());
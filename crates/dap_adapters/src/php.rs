@@ -0,0 +1,334 @@
+use std::{collections::HashMap, path::PathBuf, sync::OnceLock};
+
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use dap::{
+    StartDebuggingRequestArguments,
+    adapters::{DebugTaskDefinition, latest_github_release},
+};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use serde_json::Value;
+use task::{DebugRequest, DebugScenario, ZedDebugConfig};
+use util::ResultExt as _;
+
+use crate::*;
+
+#[derive(Debug, Default)]
+pub(crate) struct PhpDebugAdapter {
+    checked: OnceLock<()>,
+}
+
+impl PhpDebugAdapter {
+    const ADAPTER_NAME: &'static str = "Xdebug";
+    const ADAPTER_REPO: &'static str = "xdebug/vscode-php-debug";
+    const ADAPTER_PATH: &'static str = "extension/out/phpDebug.js";
+    const DEFAULT_XDEBUG_PORT: u16 = 9003;
+
+    async fn fetch_latest_adapter_version(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+    ) -> Result<AdapterVersion> {
+        let release =
+            latest_github_release(Self::ADAPTER_REPO, true, false, delegate.http_client()).await?;
+
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name.starts_with("php-debug-") && asset.name.ends_with(".vsix"))
+            .context("no php-debug vsix found in the latest release")?;
+
+        Ok(AdapterVersion {
+            tag_name: release.tag_name.clone(),
+            url: asset.browser_download_url.clone(),
+        })
+    }
+
+    /// Runs php-debug, which is a Node.js program, as a debug server for Xdebug to
+    /// connect to.
+    async fn node_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        node_path: PathBuf,
+        adapter_path: PathBuf,
+        user_args: Option<Vec<String>>,
+    ) -> Result<DebugAdapterBinary> {
+        let tcp_connection = task_definition.tcp_connection.clone().unwrap_or_default();
+        let (host, port, timeout) = crate::configure_tcp_connection(tcp_connection).await?;
+
+        let mut configuration = task_definition.config.clone();
+        let mut envs = HashMap::default();
+        if let Some(configuration) = configuration.as_object_mut() {
+            configuration
+                .entry("cwd")
+                .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+            configuration
+                .entry("port")
+                .or_insert(Self::DEFAULT_XDEBUG_PORT.into());
+
+            if let Some(env) = configuration.get("env").cloned()
+                && let Ok(env) = serde_json::from_value(env)
+            {
+                envs = env;
+            }
+        }
+
+        let mut arguments = vec![
+            adapter_path.to_string_lossy().into_owned(),
+            format!("--server={port}"),
+        ];
+        arguments.extend(user_args.unwrap_or_default());
+
+        Ok(DebugAdapterBinary {
+            command: Some(node_path.to_string_lossy().into_owned()),
+            arguments,
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            envs,
+            connection: Some(adapters::TcpArguments {
+                host,
+                port,
+                timeout,
+            }),
+            request_args: StartDebuggingRequestArguments {
+                request: self.request_kind(&task_definition.config).await?,
+                configuration,
+            },
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for PhpDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("PHP").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = serde_json::Map::new();
+
+        match &zed_scenario.request {
+            DebugRequest::Launch(launch) => {
+                config.insert("request".into(), "launch".into());
+                config.insert("program".into(), launch.program.clone().into());
+                config.insert("port".into(), Self::DEFAULT_XDEBUG_PORT.into());
+
+                if !launch.args.is_empty() {
+                    config.insert("args".into(), launch.args.clone().into());
+                }
+                if !launch.env.is_empty() {
+                    config.insert("env".into(), launch.env_json());
+                }
+                if let Some(cwd) = launch.cwd.as_ref() {
+                    config.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+                }
+            }
+            DebugRequest::Attach(_) => {
+                bail!(
+                    "{} can't attach to a process; use a launch configuration without a `program` to listen for Xdebug connections instead",
+                    self.name()
+                );
+            }
+            DebugRequest::CoreDump(_) => {
+                bail!("{} doesn't support loading core dumps", self.name());
+            }
+        }
+
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            config.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config: Value::Object(config),
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "properties": {
+                "request": {
+                    "type": "string",
+                    "enum": ["launch"],
+                    "description": "Launch a PHP script, or listen for incoming Xdebug connections when no `program` is given"
+                },
+                "program": {
+                    "type": "string",
+                    "description": "Absolute path to the PHP script to run. Leave empty to only listen for Xdebug connections, e.g. from a web server"
+                },
+                "args": {
+                    "type": "array",
+                    "description": "Command line arguments passed to the script",
+                    "items": {
+                        "type": "string"
+                    },
+                    "default": []
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory of the script"
+                },
+                "env": {
+                    "type": "object",
+                    "description": "Environment variables passed to the script",
+                    "default": {}
+                },
+                "runtimeExecutable": {
+                    "type": "string",
+                    "description": "Path to the PHP binary used to run `program`",
+                    "default": "php"
+                },
+                "runtimeArgs": {
+                    "type": "array",
+                    "description": "Extra arguments passed to the PHP binary",
+                    "items": {
+                        "type": "string"
+                    },
+                    "default": []
+                },
+                "hostname": {
+                    "type": "string",
+                    "description": "Address to listen on for Xdebug connections",
+                    "default": "::"
+                },
+                "port": {
+                    "type": "number",
+                    "description": "Port to listen on for Xdebug connections. Xdebug 3 connects to 9003 by default",
+                    "default": 9003
+                },
+                "pathMappings": {
+                    "type": "object",
+                    "description": "Maps server paths to local paths, e.g. when PHP runs in a container",
+                    "default": {}
+                },
+                "stopOnEntry": {
+                    "type": "boolean",
+                    "description": "Break at the first line of the script",
+                    "default": false
+                },
+                "log": {
+                    "type": "boolean",
+                    "description": "Log the communication between the adapter and Xdebug to the debug console",
+                    "default": false
+                },
+                "xdebugSettings": {
+                    "type": "object",
+                    "description": "Xdebug feature settings, such as `max_children` or `max_depth`",
+                    "default": {}
+                }
+            },
+            "required": ["request"]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let adapter_path = if let Some(user_installed_path) = user_installed_path {
+            user_installed_path
+        } else {
+            if self.checked.set(()).is_ok() {
+                delegate
+                    .output_to_console(format!("Checking latest version of {}...", self.name()));
+                if let Some(version) = self.fetch_latest_adapter_version(delegate).await.log_err() {
+                    adapters::download_adapter_from_github(
+                        self.name(),
+                        version,
+                        adapters::DownloadedFileType::Vsix,
+                        delegate.as_ref(),
+                    )
+                    .await?;
+                }
+            }
+
+            let adapter_dir = paths::debug_adapters_dir().join(self.name().as_ref());
+            let file_name_prefix = format!("{}_", self.name());
+            util::fs::find_file_name_in_dir(adapter_dir.as_path(), |file_name| {
+                file_name.starts_with(&file_name_prefix)
+            })
+            .await
+            .context("Couldn't find the php-debug adapter directory")?
+            .join(Self::ADAPTER_PATH)
+        };
+        let node_path = delegate.node_runtime().binary_path().await?;
+
+        self.node_binary(
+            delegate,
+            task_definition,
+            node_path,
+            adapter_path,
+            user_args,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap::FakeDapDelegate;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_node_binary(cx: &mut TestAppContext) {
+        let delegate: Arc<dyn DapDelegate> = Arc::new(FakeDapDelegate::new(
+            path!("/project"),
+            FakeFs::new(cx.executor()),
+        ));
+        let task_definition = DebugTaskDefinition {
+            label: "listen for Xdebug".into(),
+            adapter: PhpDebugAdapter::ADAPTER_NAME.into(),
+            config: json!({
+                "request": "launch",
+                "env": { "XDEBUG_MODE": "debug" },
+            }),
+            tcp_connection: None,
+        };
+        let binary = PhpDebugAdapter::default()
+            .node_binary(
+                &delegate,
+                &task_definition,
+                PathBuf::from("/usr/bin/node"),
+                PathBuf::from("/adapters/phpDebug.js"),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(binary.command.as_deref(), Some("/usr/bin/node"));
+        let port = binary.connection.as_ref().unwrap().port;
+        assert_eq!(
+            binary.arguments,
+            [
+                "/adapters/phpDebug.js".to_string(),
+                format!("--server={port}"),
+            ]
+        );
+        assert_eq!(
+            binary.envs.get("XDEBUG_MODE").map(String::as_str),
+            Some("debug")
+        );
+        assert_eq!(
+            binary.request_args.configuration["port"],
+            json!(PhpDebugAdapter::DEFAULT_XDEBUG_PORT)
+        );
+        assert_eq!(
+            binary.request_args.configuration["cwd"],
+            json!(path!("/project"))
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::*;
-use anyhow::Context as _;
+use anyhow::{Context as _, bail};
 use dap::{DebugRequest, StartDebuggingRequestArguments, adapters::DebugTaskDefinition};
 use fs::RemoveOptions;
 use futures::{StreamExt, TryStreamExt};
@@ -336,6 +336,9 @@ impl DebugAdapter for PythonDebugAdapter {
             "request": match zed_scenario.request {
                 DebugRequest::Launch(_) => "launch",
                 DebugRequest::Attach(_) => "attach",
+                DebugRequest::CoreDump(_) => {
+                    bail!("{} doesn't support loading core dumps", self.name())
+                }
             },
             "subProcess": true,
             "redirectOutput": true,
@@ -346,6 +349,7 @@ impl DebugAdapter for PythonDebugAdapter {
             DebugRequest::Attach(attach) => {
                 map.insert("processId".into(), attach.process_id.into());
             }
+            DebugRequest::CoreDump(_) => {}
             DebugRequest::Launch(launch) => {
                 map.insert("program".into(), launch.program.clone().into());
                 map.insert("args".into(), launch.args.clone().into());
@@ -0,0 +1,396 @@
+use std::{collections::HashMap, ffi::OsStr, path::PathBuf};
+
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use dap::{
+    StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
+    adapters::DebugTaskDefinition,
+};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use serde_json::Value;
+use task::{DebugRequest, DebugScenario, ZedDebugConfig};
+
+use crate::*;
+
+#[derive(Default)]
+pub(crate) struct RubyDebugAdapter;
+
+impl RubyDebugAdapter {
+    const ADAPTER_NAME: &'static str = "rdbg";
+    const GEM_NAME: &'static str = "debug";
+
+    /// Finds `rdbg` on the user's `PATH`, installing the `debug` gem into Zed's
+    /// adapter directory as a fallback.
+    async fn find_or_install_rdbg(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+    ) -> Result<(PathBuf, HashMap<String, String>)> {
+        if let Some(rdbg) = delegate.which(OsStr::new("rdbg")).await {
+            return Ok((rdbg, HashMap::default()));
+        }
+
+        let gem_home = paths::debug_adapters_dir().join(Self::ADAPTER_NAME);
+        let envs = HashMap::from_iter([(
+            "GEM_HOME".to_owned(),
+            gem_home.to_string_lossy().into_owned(),
+        )]);
+        let rdbg = gem_home.join("bin").join("rdbg");
+        if delegate.fs().is_file(&rdbg).await {
+            return Ok((rdbg, envs));
+        }
+
+        let gem = delegate
+            .which(OsStr::new("gem"))
+            .await
+            .context("Ruby not found in path. Please install Ruby first, then rdbg will be installed automatically.")?;
+
+        delegate.output_to_console(format!("Installing the `{}` gem...", Self::GEM_NAME));
+        let install_output = util::command::new_smol_command(&gem)
+            .args(["install", "--no-document", "--install-dir"])
+            .arg(&gem_home)
+            .arg(Self::GEM_NAME)
+            .output()
+            .await?;
+
+        if !install_output.status.success() {
+            bail!(
+                "failed to install rdbg via `gem install`. stdout: {:?}, stderr: {:?}\n Please try installing it manually using 'gem install debug'",
+                String::from_utf8_lossy(&install_output.stdout),
+                String::from_utf8_lossy(&install_output.stderr)
+            );
+        }
+
+        Ok((rdbg, envs))
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for RubyDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("Ruby").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = serde_json::Map::new();
+
+        match &zed_scenario.request {
+            DebugRequest::Launch(launch) => {
+                config.insert("request".into(), "launch".into());
+                config.insert("script".into(), launch.program.clone().into());
+
+                if !launch.args.is_empty() {
+                    config.insert("args".into(), launch.args.clone().into());
+                }
+                if !launch.env.is_empty() {
+                    config.insert("env".into(), launch.env_json());
+                }
+                if let Some(cwd) = launch.cwd.as_ref() {
+                    config.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+                }
+            }
+            DebugRequest::Attach(_) => {
+                bail!(
+                    "{} attaches to programs started with `rdbg --open`; set `tcp_connection` in debug.json instead",
+                    self.name()
+                );
+            }
+            DebugRequest::CoreDump(_) => {
+                bail!("{} doesn't support loading core dumps", self.name());
+            }
+        }
+
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            config.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config: Value::Object(config),
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a new Ruby program under rdbg"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "script": {
+                                    "type": "string",
+                                    "description": "The Ruby script to debug"
+                                },
+                                "command": {
+                                    "type": "string",
+                                    "description": "A command to run instead of `ruby`, e.g. `bundle exec rspec`. When set, `script` is passed to it as the first argument"
+                                },
+                                "args": {
+                                    "type": ["array", "string"],
+                                    "description": "Command line arguments passed to the script",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "default": []
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the program"
+                                },
+                                "env": {
+                                    "type": "object",
+                                    "description": "Environment variables passed to the program",
+                                    "default": {}
+                                },
+                                "useBundler": {
+                                    "type": "boolean",
+                                    "description": "Run the program with `bundle exec`",
+                                    "default": false
+                                },
+                                "stopOnEntry": {
+                                    "type": "boolean",
+                                    "description": "Stop at the first line of the program",
+                                    "default": false
+                                }
+                            }
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to a program started with `rdbg --open`. The address is taken from `tcp_connection`"
+                                }
+                            }
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let mut configuration = task_definition.config.clone();
+        let request = self.request_kind(&configuration).await?;
+
+        // Attaching connects to a program that already runs under `rdbg --open`.
+        if request == StartDebuggingRequestArgumentsRequest::Attach {
+            let tcp_connection = task_definition.tcp_connection.clone().unwrap_or_default();
+            let (host, port, timeout) = crate::configure_tcp_connection(tcp_connection).await?;
+            return Ok(DebugAdapterBinary {
+                command: None,
+                arguments: Vec::new(),
+                envs: HashMap::default(),
+                cwd: Some(delegate.worktree_root_path().to_path_buf()),
+                connection: Some(adapters::TcpArguments {
+                    host,
+                    port,
+                    timeout,
+                }),
+                request_args: StartDebuggingRequestArguments {
+                    request,
+                    configuration,
+                },
+            });
+        }
+
+        let (rdbg_path, mut envs) = if let Some(path) = user_installed_path {
+            (path, HashMap::default())
+        } else {
+            self.find_or_install_rdbg(delegate).await?
+        };
+
+        let tcp_connection = task_definition.tcp_connection.clone().unwrap_or_default();
+        let (host, port, timeout) = crate::configure_tcp_connection(tcp_connection).await?;
+
+        let configuration_map = configuration
+            .as_object_mut()
+            .context("rdbg configuration is not a valid json object")?;
+        configuration_map
+            .entry("cwd")
+            .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+
+        if let Some(Value::Object(env)) = configuration_map.get("env") {
+            envs.extend(
+                env.iter()
+                    .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_owned()))),
+            );
+        }
+
+        // rdbg runs the debuggee itself, so the program and its arguments are
+        // passed on its command line rather than in the launch request.
+        let mut debuggee = Vec::new();
+        let use_bundler = configuration_map
+            .get("useBundler")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if let Some(command) = configuration_map.get("command").and_then(Value::as_str) {
+            debuggee.extend(shlex::split(command).context("invalid rdbg `command`")?);
+        } else if use_bundler {
+            debuggee.extend(["bundle".to_owned(), "exec".to_owned(), "ruby".to_owned()]);
+        }
+        if let Some(script) = configuration_map.get("script").and_then(Value::as_str) {
+            debuggee.push(script.to_owned());
+        }
+        match configuration_map.get("args") {
+            Some(Value::Array(args)) => debuggee.extend(
+                args.iter()
+                    .filter_map(|arg| arg.as_str().map(str::to_owned)),
+            ),
+            Some(Value::String(args)) => {
+                debuggee.extend(shlex::split(args).context("invalid rdbg `args`")?)
+            }
+            _ => {}
+        }
+        if debuggee.is_empty() {
+            bail!("rdbg launch configurations need a `script` or a `command`");
+        }
+
+        let stop_on_entry = configuration_map
+            .get("stopOnEntry")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let mut arguments = user_args.unwrap_or_default();
+        arguments.extend([
+            "--open".to_owned(),
+            "--host".to_owned(),
+            host.to_string(),
+            "--port".to_owned(),
+            port.to_string(),
+        ]);
+        if !stop_on_entry {
+            arguments.push("--nonstop".to_owned());
+        }
+        if configuration_map.contains_key("command") || use_bundler {
+            arguments.push("--command".to_owned());
+        }
+        arguments.push("--".to_owned());
+        arguments.extend(debuggee);
+
+        let cwd = configuration_map
+            .get("cwd")
+            .and_then(Value::as_str)
+            .map(PathBuf::from);
+
+        Ok(DebugAdapterBinary {
+            command: Some(rdbg_path.to_string_lossy().into_owned()),
+            arguments,
+            envs,
+            cwd,
+            connection: Some(adapters::TcpArguments {
+                host,
+                port,
+                timeout,
+            }),
+            request_args: StartDebuggingRequestArguments {
+                request,
+                configuration,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap::FakeDapDelegate;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_get_binary(cx: &mut TestAppContext) {
+        let delegate: Arc<dyn DapDelegate> = Arc::new(FakeDapDelegate::new(
+            path!("/project"),
+            FakeFs::new(cx.executor()),
+        ));
+        let launch = DebugTaskDefinition {
+            label: "run main.rb".into(),
+            adapter: RubyDebugAdapter::ADAPTER_NAME.into(),
+            config: json!({
+                "request": "launch",
+                "script": "main.rb",
+                "args": ["--verbose"],
+            }),
+            tcp_connection: None,
+        };
+        let binary = RubyDebugAdapter
+            .get_binary(
+                &delegate,
+                &launch,
+                Some(PathBuf::from("/gems/bin/rdbg")),
+                None,
+                &mut cx.to_async(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(binary.command.as_deref(), Some("/gems/bin/rdbg"));
+        assert_eq!(binary.cwd, Some(PathBuf::from(path!("/project"))));
+        let connection = binary.connection.unwrap();
+        let (host, port) = (connection.host.to_string(), connection.port.to_string());
+        assert_eq!(
+            binary.arguments,
+            [
+                "--open",
+                "--host",
+                host.as_str(),
+                "--port",
+                port.as_str(),
+                "--nonstop",
+                "--",
+                "main.rb",
+                "--verbose",
+            ]
+        );
+        assert_eq!(
+            binary.request_args.request,
+            StartDebuggingRequestArgumentsRequest::Launch
+        );
+
+        // Attaching connects to a program started with `rdbg --open`, so nothing is spawned.
+        let attach = DebugTaskDefinition {
+            config: json!({ "request": "attach" }),
+            ..launch
+        };
+        let binary = RubyDebugAdapter
+            .get_binary(&delegate, &attach, None, None, &mut cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(binary.command, None);
+        assert!(binary.connection.is_some());
+    }
+}
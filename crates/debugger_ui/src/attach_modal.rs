@@ -228,6 +228,10 @@ impl PickerDelegate for AttachModalDelegate {
                 debug_panic!("Debugger attach modal used on launch debug config");
                 return;
             }
+            DebugRequest::CoreDump(_) => {
+                debug_panic!("Debugger attach modal used on core dump debug config");
+                return;
+            }
         }
 
         let workspace = self.workspace.clone();
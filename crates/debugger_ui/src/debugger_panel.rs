@@ -5,9 +5,10 @@ use crate::session::running::breakpoint_list::BreakpointList;
 
 use crate::{
     ClearAllBreakpoints, Continue, CopyDebugAdapterArguments, Detach, FocusBreakpointList,
-    FocusConsole, FocusFrames, FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables,
-    NewProcessModal, NewProcessMode, Pause, RerunSession, StepInto, StepOut, StepOver, Stop,
-    ToggleExpandItem, ToggleSessionPicker, ToggleThreadPicker, persistence, spawn_task_or_modal,
+    FocusConsole, FocusDisassembly, FocusFrames, FocusLoadedSources, FocusModules, FocusTerminal,
+    FocusVariables, NewProcessModal, NewProcessMode, Pause, RerunSession, StepInto, StepOut,
+    StepOver, Stop, ToggleExpandItem, ToggleSessionPicker, ToggleThreadPicker, persistence,
+    spawn_task_or_modal,
 };
 use anyhow::{Context as _, Result, anyhow};
 use collections::IndexMap;
@@ -675,6 +676,13 @@ impl DebugPanel {
                                     let capabilities = running_state.read(cx).capabilities(cx);
                                     let supports_detach =
                                         running_state.read(cx).session().read(cx).is_attached();
+                                    // Post-mortem sessions show a stopped program that can't be resumed.
+                                    let can_resume = thread_status == ThreadStatus::Stopped
+                                        && !running_state
+                                            .read(cx)
+                                            .session()
+                                            .read(cx)
+                                            .is_post_mortem();
 
                                     this.map(|this| {
                                         if thread_status == ThreadStatus::Running {
@@ -714,7 +722,7 @@ impl DebugPanel {
                                                     running_state,
                                                     |this, _, _window, cx| this.continue_thread(cx),
                                                 ))
-                                                .disabled(thread_status != ThreadStatus::Stopped)
+                                                .disabled(!can_resume)
                                                 .tooltip({
                                                     let focus_handle = focus_handle.clone();
                                                     move |window, cx| {
@@ -739,7 +747,7 @@ impl DebugPanel {
                                                     this.step_over(cx);
                                                 },
                                             ))
-                                            .disabled(thread_status != ThreadStatus::Stopped)
+                                            .disabled(!can_resume)
                                             .tooltip({
                                                 let focus_handle = focus_handle.clone();
                                                 move |window, cx| {
@@ -765,7 +773,7 @@ impl DebugPanel {
                                                 this.step_in(cx);
                                             },
                                         ))
-                                        .disabled(thread_status != ThreadStatus::Stopped)
+                                        .disabled(!can_resume)
                                         .tooltip({
                                             let focus_handle = focus_handle.clone();
                                             move |window, cx| {
@@ -788,7 +796,7 @@ impl DebugPanel {
                                                     this.step_out(cx);
                                                 },
                                             ))
-                                            .disabled(thread_status != ThreadStatus::Stopped)
+                                            .disabled(!can_resume)
                                             .tooltip({
                                                 let focus_handle = focus_handle.clone();
                                                 move |window, cx| {
@@ -1615,6 +1623,15 @@ impl Render for DebugPanel {
                     .ok();
                 }
             })
+            .on_action({
+                let this = this.clone();
+                move |_: &FocusDisassembly, window, cx| {
+                    this.update(cx, |this, cx| {
+                        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+                    })
+                    .ok();
+                }
+            })
             .on_action({
                 let this = this.clone();
                 move |_: &ToggleThreadPicker, window, cx| {
@@ -54,6 +54,10 @@ actions!(
         StepOut,
         /// Steps back to the previous statement.
         StepBack,
+        /// Steps into the next machine instruction, following calls.
+        StepIntoInstruction,
+        /// Steps over the next machine instruction.
+        StepOverInstruction,
         /// Stops the debugging session.
         Stop,
         /// Toggles whether to ignore all breakpoints.
@@ -74,6 +78,8 @@ actions!(
         FocusLoadedSources,
         /// Focuses on the terminal panel.
         FocusTerminal,
+        /// Focuses on the disassembly panel.
+        FocusDisassembly,
         /// Shows the stack trace for the current thread.
         ShowStackTrace,
         /// Toggles the thread picker dropdown.
@@ -166,7 +172,10 @@ pub fn init(cx: &mut App) {
 
                 let caps = running_state.capabilities(cx);
                 let supports_step_back = caps.supports_step_back.unwrap_or_default();
+                let supports_stepping_granularity =
+                    caps.supports_stepping_granularity.unwrap_or_default();
                 let supports_detach = running_state.session().read(cx).is_attached();
+                let is_post_mortem = running_state.session().read(cx).is_post_mortem();
                 let status = running_state.thread_status(cx);
 
                 let active_item = active_item.downgrade();
@@ -178,72 +187,102 @@ pub fn init(cx: &mut App) {
                             .ok();
                     })
                 })
-                .when(status == Some(ThreadStatus::Stopped), |div| {
-                    div.on_action({
-                        let active_item = active_item.clone();
-                        move |_: &StepInto, _, cx| {
-                            active_item.update(cx, |item, cx| item.step_in(cx)).ok();
-                        }
-                    })
-                    .on_action({
-                        let active_item = active_item.clone();
-                        move |_: &StepOver, _, cx| {
-                            active_item.update(cx, |item, cx| item.step_over(cx)).ok();
-                        }
-                    })
-                    .on_action({
-                        let active_item = active_item.clone();
-                        move |_: &StepOut, _, cx| {
-                            active_item.update(cx, |item, cx| item.step_out(cx)).ok();
-                        }
-                    })
-                    .when(supports_step_back, |div| {
-                        let active_item = active_item.clone();
-                        div.on_action(move |_: &StepBack, _, cx| {
-                            active_item.update(cx, |item, cx| item.step_back(cx)).ok();
+                .when(
+                    status == Some(ThreadStatus::Stopped) && !is_post_mortem,
+                    |div| {
+                        div.on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepInto, _, cx| {
+                                active_item.update(cx, |item, cx| item.step_in(cx)).ok();
+                            }
                         })
-                    })
-                    .on_action({
-                        let active_item = active_item.clone();
-                        move |_: &Continue, _, cx| {
-                            active_item
-                                .update(cx, |item, cx| item.continue_thread(cx))
-                                .ok();
-                        }
-                    })
-                    .on_action(cx.listener(
-                        |workspace, _: &ShowStackTrace, window, cx| {
-                            let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) else {
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepOver, _, cx| {
+                                active_item.update(cx, |item, cx| item.step_over(cx)).ok();
+                            }
+                        })
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepOut, _, cx| {
+                                active_item.update(cx, |item, cx| item.step_out(cx)).ok();
+                            }
+                        })
+                        .when(supports_step_back, |div| {
+                            let active_item = active_item.clone();
+                            div.on_action(move |_: &StepBack, _, cx| {
+                                active_item.update(cx, |item, cx| item.step_back(cx)).ok();
+                            })
+                        })
+                        .when(supports_stepping_granularity, |div| {
+                            div.on_action({
+                                let active_item = active_item.clone();
+                                move |_: &StepIntoInstruction, _, cx| {
+                                    active_item
+                                        .update(cx, |item, cx| {
+                                            item.step_in_with_granularity(
+                                                dap::SteppingGranularity::Instruction,
+                                                cx,
+                                            )
+                                        })
+                                        .ok();
+                                }
+                            })
+                            .on_action({
+                                let active_item = active_item.clone();
+                                move |_: &StepOverInstruction, _, cx| {
+                                    active_item
+                                        .update(cx, |item, cx| {
+                                            item.step_over_with_granularity(
+                                                dap::SteppingGranularity::Instruction,
+                                                cx,
+                                            )
+                                        })
+                                        .ok();
+                                }
+                            })
+                        })
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &Continue, _, cx| {
+                                active_item
+                                    .update(cx, |item, cx| item.continue_thread(cx))
+                                    .ok();
+                            }
+                        })
+                    },
+                )
+                .when(status == Some(ThreadStatus::Stopped), |div| {
+                    div.on_action(cx.listener(|workspace, _: &ShowStackTrace, window, cx| {
+                        let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) else {
+                            return;
+                        };
+
+                        if let Some(existing) = workspace.item_of_type::<StackTraceView>(cx) {
+                            let is_active = workspace
+                                .active_item(cx)
+                                .is_some_and(|item| item.item_id() == existing.item_id());
+                            workspace.activate_item(&existing, true, !is_active, window, cx);
+                        } else {
+                            let Some(active_session) = debug_panel.read(cx).active_session() else {
                                 return;
                             };
 
-                            if let Some(existing) = workspace.item_of_type::<StackTraceView>(cx) {
-                                let is_active = workspace
-                                    .active_item(cx)
-                                    .is_some_and(|item| item.item_id() == existing.item_id());
-                                workspace.activate_item(&existing, true, !is_active, window, cx);
-                            } else {
-                                let Some(active_session) = debug_panel.read(cx).active_session()
-                                else {
-                                    return;
-                                };
-
-                                let project = workspace.project();
+                            let project = workspace.project();
 
-                                let stack_trace_view = active_session.update(cx, |session, cx| {
-                                    session.stack_trace_view(project, window, cx).clone()
-                                });
+                            let stack_trace_view = active_session.update(cx, |session, cx| {
+                                session.stack_trace_view(project, window, cx).clone()
+                            });
 
-                                workspace.add_item_to_active_pane(
-                                    Box::new(stack_trace_view),
-                                    None,
-                                    true,
-                                    window,
-                                    cx,
-                                );
-                            }
-                        },
-                    ))
+                            workspace.add_item_to_active_pane(
+                                Box::new(stack_trace_view),
+                                None,
+                                true,
+                                window,
+                                cx,
+                            );
+                        }
+                    }))
                 })
                 .when(supports_detach, |div| {
                     let active_item = active_item.clone();
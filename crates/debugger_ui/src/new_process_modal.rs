@@ -16,17 +16,20 @@ use editor::{Editor, EditorElement, EditorStyle};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     Action, App, AppContext, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
-    KeyContext, Render, Subscription, Task, TextStyle, WeakEntity,
+    KeyContext, PathPromptOptions, Render, Subscription, Task, TextStyle, WeakEntity,
 };
 use itertools::Itertools as _;
 use picker::{Picker, PickerDelegate, highlighted_match_with_paths::HighlightedMatch};
-use project::{DebugScenarioContext, Project, TaskContexts, TaskSourceKind, task_store::TaskStore};
+use project::{
+    DebugScenarioContext, DirectoryLister, Project, TaskContexts, TaskSourceKind,
+    task_store::TaskStore,
+};
 use settings::Settings;
 use task::{DebugScenario, RevealTarget, VariableName, ZedDebugConfig};
 use theme::ThemeSettings;
 use ui::{
     ActiveTheme, Button, ButtonCommon, ButtonSize, CheckboxWithLabel, Clickable, Color, Context,
-    ContextMenu, Disableable, DropdownMenu, FluentBuilder, Icon, IconName, IconSize,
+    ContextMenu, Disableable, DropdownMenu, FluentBuilder, Icon, IconButton, IconName, IconSize,
     IconWithIndicator, Indicator, InteractiveElement, IntoElement, KeyBinding, Label,
     LabelCommon as _, LabelSize, ListItem, ListItemSpacing, ParentElement, RenderOnce,
     SharedString, Styled, StyledExt, ToggleButton, ToggleState, Toggleable, Tooltip, Window, div,
@@ -44,6 +47,7 @@ pub(super) struct NewProcessModal {
     debug_picker: Entity<Picker<DebugDelegate>>,
     attach_mode: Entity<AttachMode>,
     configure_mode: Entity<ConfigureMode>,
+    core_dump_mode: Entity<CoreDumpMode>,
     task_mode: TaskMode,
     debugger: Option<DebugAdapterName>,
     _subscriptions: [Subscription; 3],
@@ -64,6 +68,14 @@ fn suggested_label(request: &DebugRequest, debugger: &str) -> SharedString {
             config.process_id.unwrap_or(u32::MAX)
         )
         .into(),
+        DebugRequest::CoreDump(config) => {
+            let core_file_name = Path::new(&config.core_file)
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_else(|| Cow::Borrowed(&config.core_file));
+
+            format!("core: {} ({debugger})", core_file_name).into()
+        }
     }
 }
 
@@ -100,6 +112,7 @@ impl NewProcessModal {
                     });
 
                     let configure_mode = ConfigureMode::new(window, cx);
+                    let core_dump_mode = CoreDumpMode::new(workspace_handle.clone(), window, cx);
 
                     let task_overrides = Some(TaskOverrides { reveal_target });
 
@@ -254,6 +267,7 @@ impl NewProcessModal {
                         debug_picker,
                         attach_mode,
                         configure_mode,
+                        core_dump_mode,
                         task_mode,
                         debugger: None,
                         mode,
@@ -285,6 +299,9 @@ impl NewProcessModal {
             NewProcessMode::Launch => self.configure_mode.update(cx, |this, cx| {
                 this.clone().render(dap_menu, window, cx).into_any_element()
             }),
+            NewProcessMode::CoreDump => self.core_dump_mode.update(cx, |this, cx| {
+                this.render(dap_menu, window, cx).into_any_element()
+            }),
             NewProcessMode::Debug => v_flex()
                 .w(rems(34.))
                 .child(self.debug_picker.clone())
@@ -297,6 +314,7 @@ impl NewProcessModal {
             NewProcessMode::Task => self.task_mode.task_modal.focus_handle(cx),
             NewProcessMode::Attach => self.attach_mode.read(cx).attach_picker.focus_handle(cx),
             NewProcessMode::Launch => self.configure_mode.read(cx).program.focus_handle(cx),
+            NewProcessMode::CoreDump => self.core_dump_mode.read(cx).program.focus_handle(cx),
             NewProcessMode::Debug => self.debug_picker.focus_handle(cx),
         }
     }
@@ -309,6 +327,9 @@ impl NewProcessModal {
             NewProcessMode::Attach => {
                 DebugRequest::Attach(self.attach_mode.read(cx).debug_request())
             }
+            NewProcessMode::CoreDump => {
+                DebugRequest::CoreDump(self.core_dump_mode.read(cx).debug_request(cx))
+            }
             _ => return Task::ready(None),
         };
         let label = suggested_label(&request, debugger);
@@ -330,7 +351,12 @@ impl NewProcessModal {
             .global::<DapRegistry>()
             .adapter(&session_scenario.adapter);
 
-        cx.spawn(async move |_| adapter?.config_from_zed_format(session_scenario).await.ok())
+        cx.spawn(async move |_| {
+            adapter?
+                .config_from_zed_format(session_scenario)
+                .await
+                .log_err()
+        })
     }
 
     fn start_new_session(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -509,6 +535,7 @@ pub(crate) enum NewProcessMode {
     Task,
     Launch,
     Attach,
+    CoreDump,
     Debug,
 }
 
@@ -519,6 +546,7 @@ impl std::fmt::Display for NewProcessMode {
             NewProcessMode::Debug => "Debug",
             NewProcessMode::Attach => "Attach",
             NewProcessMode::Launch => "Launch",
+            NewProcessMode::CoreDump => "Core Dump",
         };
 
         write!(f, "{}", mode)
@@ -594,7 +622,8 @@ impl Render for NewProcessModal {
                     NewProcessMode::Task => NewProcessMode::Debug,
                     NewProcessMode::Debug => NewProcessMode::Attach,
                     NewProcessMode::Attach => NewProcessMode::Launch,
-                    NewProcessMode::Launch => NewProcessMode::Task,
+                    NewProcessMode::Launch => NewProcessMode::CoreDump,
+                    NewProcessMode::CoreDump => NewProcessMode::Task,
                 };
 
                 this.mode_focus_handle(cx).focus(window);
@@ -602,10 +631,11 @@ impl Render for NewProcessModal {
             .on_action(
                 cx.listener(|this, _: &pane::ActivatePreviousItem, window, cx| {
                     this.mode = match this.mode {
-                        NewProcessMode::Task => NewProcessMode::Launch,
+                        NewProcessMode::Task => NewProcessMode::CoreDump,
                         NewProcessMode::Debug => NewProcessMode::Task,
                         NewProcessMode::Attach => NewProcessMode::Debug,
                         NewProcessMode::Launch => NewProcessMode::Attach,
+                        NewProcessMode::CoreDump => NewProcessMode::Launch,
                     };
 
                     this.mode_focus_handle(cx).focus(window);
@@ -683,6 +713,24 @@ impl Render for NewProcessModal {
                             cx.notify();
                         }))
                         .tooltip(Tooltip::text("Launch a new process with a debugger"))
+                        .middle(),
+                    )
+                    .child(
+                        ToggleButton::new(
+                            "debugger-session-ui-core-dump-button",
+                            NewProcessMode::CoreDump.to_string(),
+                        )
+                        .size(ButtonSize::Default)
+                        .toggle_state(matches!(self.mode, NewProcessMode::CoreDump))
+                        .style(ui::ButtonStyle::Subtle)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.mode = NewProcessMode::CoreDump;
+                            this.mode_focus_handle(cx).focus(window);
+                            cx.notify();
+                        }))
+                        .tooltip(Tooltip::text(
+                            "Inspect a crashed program from its core dump",
+                        ))
                         .last(),
                     ),
             )
@@ -766,6 +814,27 @@ impl Render for NewProcessModal {
                                     .child(div().child(self.adapter_drop_down_menu(window, cx))),
                             )
                     }),
+                    NewProcessMode::CoreDump => el.child({
+                        let disabled = self.debugger.is_none()
+                            || !self.core_dump_mode.read(cx).is_complete(cx);
+                        container
+                            .child(
+                                h_flex().child(
+                                    Button::new("edit-core-dump-debug", "Edit in debug.json")
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.save_debug_scenario(window, cx);
+                                        }))
+                                        .disabled(disabled),
+                                ),
+                            )
+                            .child(
+                                Button::new("debugger-load-core-dump", "Load")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.start_new_session(window, cx)
+                                    }))
+                                    .disabled(disabled),
+                            )
+                    }),
                     NewProcessMode::Debug => el,
                     NewProcessMode::Task => el,
                 }
@@ -932,6 +1001,205 @@ impl ConfigureMode {
     }
 }
 
+pub(super) struct CoreDumpMode {
+    workspace: WeakEntity<Workspace>,
+    program: Entity<Editor>,
+    core_file: Entity<Editor>,
+    sysroot: Entity<Editor>,
+    solib_search_paths: Entity<Editor>,
+}
+
+impl CoreDumpMode {
+    pub(super) fn new(
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let editor_with_placeholder = |placeholder: &str, window: &mut Window, cx: &mut App| {
+            let editor = cx.new(|cx| Editor::single_line(window, cx));
+            editor.update(cx, |this, cx| {
+                this.set_placeholder_text(placeholder, window, cx);
+            });
+            editor
+        };
+
+        let program = editor_with_placeholder("Ex: target/debug/server", window, cx);
+        let core_file = editor_with_placeholder("Ex: /var/crash/core.server.1234", window, cx);
+        let sysroot =
+            editor_with_placeholder("Optional: libraries of the crashed machine", window, cx);
+        let solib_search_paths =
+            editor_with_placeholder("Optional: additional library directories", window, cx);
+
+        cx.new(|_| Self {
+            workspace,
+            program,
+            core_file,
+            sysroot,
+            solib_search_paths,
+        })
+    }
+
+    fn is_complete(&self, cx: &App) -> bool {
+        !self.program.read(cx).is_empty(cx) && !self.core_file.read(cx).is_empty(cx)
+    }
+
+    pub(super) fn debug_request(&self, cx: &App) -> task::CoreDumpRequest {
+        let sysroot = self.sysroot.read(cx).text(cx);
+        let solib_search_paths = self.solib_search_paths.read(cx).text(cx);
+
+        task::CoreDumpRequest {
+            program: self.program.read(cx).text(cx),
+            core_file: self.core_file.read(cx).text(cx),
+            sysroot: (!sysroot.is_empty()).then(|| PathBuf::from(sysroot)),
+            solib_search_paths: std::env::split_paths(&solib_search_paths)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect(),
+        }
+    }
+
+    fn browse(
+        &mut self,
+        editor: Entity<Editor>,
+        directories: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(paths) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                let lister = DirectoryLister::Project(workspace.project().clone());
+                workspace.prompt_for_open_path(
+                    PathPromptOptions {
+                        files: !directories,
+                        directories,
+                        multiple: false,
+                        prompt: None,
+                    },
+                    lister,
+                    window,
+                    cx,
+                )
+            })
+            .log_err()
+        else {
+            return;
+        };
+
+        let is_search_path = editor == self.solib_search_paths;
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(path) = paths
+                .await
+                .log_err()
+                .flatten()
+                .and_then(|paths| paths.into_iter().next())
+            else {
+                return;
+            };
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    // Search paths accumulate, everything else is a single path.
+                    let text = if is_search_path && !editor.is_empty(cx) {
+                        std::env::join_paths(
+                            std::env::split_paths(&editor.text(cx)).chain(Some(path.clone())),
+                        )
+                        .map(|paths| paths.to_string_lossy().into_owned())
+                        .unwrap_or_else(|_| path.to_string_lossy().into_owned())
+                    } else {
+                        path.to_string_lossy().into_owned()
+                    };
+                    editor.set_text(text, window, cx);
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    fn render_path_field(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        editor: &Entity<Editor>,
+        directories: bool,
+        window: &mut Window,
+        cx: &mut ui::Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .gap_0p5()
+            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(div().flex_1().child(render_editor(editor, window, cx)))
+                    .child(
+                        IconButton::new(id, IconName::Folder)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Browse…"))
+                            .on_click(cx.listener({
+                                let editor = editor.clone();
+                                move |this, _, window, cx| {
+                                    this.browse(editor.clone(), directories, window, cx)
+                                }
+                            })),
+                    ),
+            )
+    }
+
+    fn render(
+        &mut self,
+        adapter_menu: DropdownMenu,
+        window: &mut Window,
+        cx: &mut ui::Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .p_2()
+            .w_full()
+            .gap_2()
+            .track_focus(&self.program.focus_handle(cx))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new("Debugger")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(adapter_menu),
+            )
+            .child(self.render_path_field(
+                "core-dump-browse-program",
+                "Program",
+                &self.program,
+                false,
+                window,
+                cx,
+            ))
+            .child(self.render_path_field(
+                "core-dump-browse-core-file",
+                "Core File",
+                &self.core_file,
+                false,
+                window,
+                cx,
+            ))
+            .child(self.render_path_field(
+                "core-dump-browse-sysroot",
+                "Sysroot",
+                &self.sysroot,
+                true,
+                window,
+                cx,
+            ))
+            .child(self.render_path_field(
+                "core-dump-browse-solib-search-paths",
+                "Shared Library Search Paths",
+                &self.solib_search_paths,
+                true,
+                window,
+                cx,
+            ))
+    }
+}
+
 #[derive(Clone)]
 pub(super) struct AttachMode {
     pub(super) definition: ZedDebugConfig,
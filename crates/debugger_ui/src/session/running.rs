@@ -1,5 +1,6 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
 pub(crate) mod memory_view;
 pub(crate) mod module_list;
@@ -11,7 +12,7 @@ use crate::{
     ToggleExpandItem,
     new_process_modal::resolve_path,
     persistence::{self, DebuggerPaneItem, SerializedLayout},
-    session::running::memory_view::MemoryView,
+    session::running::{disassembly_view::DisassemblyView, memory_view::MemoryView},
 };
 
 use anyhow::{Context as _, Result, anyhow};
@@ -19,7 +20,7 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
@@ -79,6 +80,7 @@ pub struct RunningState {
     pub(crate) scenario: Option<DebugScenario>,
     pub(crate) scenario_context: Option<DebugScenarioContext>,
     memory_view: Entity<MemoryView>,
+    disassembly_view: Entity<DisassemblyView>,
 }
 
 impl RunningState {
@@ -717,6 +719,14 @@ impl RunningState {
                 cx,
             )
         });
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                project.downgrade(),
+                stack_frame_list.clone(),
+                cx,
+            )
+        });
         let variable_list = cx.new(|cx| {
             VariableList::new(
                 session.clone(),
@@ -801,6 +811,9 @@ impl RunningState {
                         {
                             this.remove_pane_item(DebuggerPaneItem::LoadedSources, window, cx);
                         }
+                        if !capabilities.supports_disassemble_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
                     }
                     SessionEvent::RunInTerminal { request, sender } => this
                         .handle_run_in_terminal(request, sender.clone(), window, cx)
@@ -839,6 +852,7 @@ impl RunningState {
                 &loaded_source_list,
                 &debug_terminal,
                 &memory_view,
+                &disassembly_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -868,6 +882,7 @@ impl RunningState {
 
         Self {
             memory_view,
+            disassembly_view,
             session,
             workspace,
             focus_handle,
@@ -1288,6 +1303,12 @@ impl RunningState {
                 item_kind,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                cx,
+            )),
         }
     }
 
@@ -1472,6 +1493,11 @@ impl RunningState {
         &self.module_list
     }
 
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
     pub(crate) fn activate_item(
         &mut self,
         item: DebuggerPaneItem,
@@ -1567,24 +1593,38 @@ impl RunningState {
     }
 
     pub fn step_over(&mut self, cx: &mut Context<Self>) {
+        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        self.step_over_with_granularity(granularity, cx);
+    }
+
+    pub(crate) fn step_over_with_granularity(
+        &mut self,
+        granularity: SteppingGranularity,
+        cx: &mut Context<Self>,
+    ) {
         let Some(thread_id) = self.thread_id else {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
-
         self.session().update(cx, |state, cx| {
             state.step_over(thread_id, granularity, cx);
         });
     }
 
     pub(crate) fn step_in(&mut self, cx: &mut Context<Self>) {
+        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        self.step_in_with_granularity(granularity, cx);
+    }
+
+    pub(crate) fn step_in_with_granularity(
+        &mut self,
+        granularity: SteppingGranularity,
+        cx: &mut Context<Self>,
+    ) {
         let Some(thread_id) = self.thread_id else {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
-
         self.session().update(cx, |state, cx| {
             state.step_in(thread_id, granularity, cx);
         });
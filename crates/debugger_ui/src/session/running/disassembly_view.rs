@@ -0,0 +1,444 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use collections::HashMap;
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::{Buffer, Point};
+use project::{
+    Project,
+    debugger::session::{Session, SessionEvent},
+};
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use util::ResultExt;
+
+use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
+
+/// How many instructions to show before the instruction pointer.
+const INSTRUCTIONS_BEFORE: u64 = 64;
+/// How many instructions to show from the instruction pointer onwards.
+const INSTRUCTIONS_AFTER: u64 = 128;
+
+#[derive(Debug, Clone)]
+pub(crate) enum DisassemblyRow {
+    /// The source line that the following instructions were generated from.
+    Source {
+        path: Arc<Path>,
+        line: u64,
+        text: Option<SharedString>,
+    },
+    Instruction(dap::DisassembledInstruction),
+}
+
+/// Shows the instructions around the instruction pointer of the selected stack frame,
+/// interleaved with the source lines they belong to.
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    project: WeakEntity<Project>,
+    stack_frame_list: Entity<StackFrameList>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    instruction_pointer: Option<String>,
+    rows: Vec<DisassemblyRow>,
+    source_buffers: HashMap<Arc<Path>, Option<Entity<Buffer>>>,
+    scroll_to_instruction_pointer: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        project: WeakEntity<Project>,
+        stack_frame_list: Entity<StackFrameList>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_) => {
+                    this.scroll_to_instruction_pointer = true;
+                    this.refresh(cx);
+                }
+                SessionEvent::Disassembly | SessionEvent::StackTrace => this.refresh(cx),
+                _ => {}
+            }),
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(_) => {
+                    this.scroll_to_instruction_pointer = true;
+                    this.refresh(cx);
+                }
+                StackFrameListEvent::BuiltEntries => this.refresh(cx),
+            }),
+        ];
+
+        let mut this = Self {
+            session,
+            project,
+            stack_frame_list,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            instruction_pointer: None,
+            rows: Vec::new(),
+            source_buffers: HashMap::default(),
+            scroll_to_instruction_pointer: true,
+            _subscriptions,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    #[cfg(test)]
+    pub(crate) fn rows(&self) -> &[DisassemblyRow] {
+        &self.rows
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.instruction_pointer = self
+            .stack_frame_list
+            .update(cx, |list, cx| list.opened_stack_frame(cx))
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference);
+
+        let instructions = self.instruction_pointer.clone().and_then(|pointer| {
+            self.session.update(cx, |session, cx| {
+                session.disassemble(
+                    pointer,
+                    -(INSTRUCTIONS_BEFORE as i64),
+                    INSTRUCTIONS_BEFORE + INSTRUCTIONS_AFTER,
+                    cx,
+                )
+            })
+        });
+        let Some(instructions) = instructions else {
+            if self.instruction_pointer.is_none() {
+                self.rows.clear();
+            }
+            cx.notify();
+            return;
+        };
+
+        self.rows = self.build_rows(&instructions, cx);
+        if self.scroll_to_instruction_pointer
+            && let Some(ix) = self.instruction_pointer_ix()
+        {
+            self.scroll_to_instruction_pointer = false;
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn build_rows(
+        &mut self,
+        instructions: &[dap::DisassembledInstruction],
+        cx: &mut Context<Self>,
+    ) -> Vec<DisassemblyRow> {
+        let mut rows = Vec::with_capacity(instructions.len());
+        let mut path: Option<Arc<Path>> = None;
+        let mut last_location = None;
+        for instruction in instructions {
+            // Adapters may omit the location when it's the same as the previous
+            // instruction's.
+            if let Some(source_path) = instruction
+                .location
+                .as_ref()
+                .and_then(|source| source.path.as_deref())
+            {
+                path = Some(Arc::from(Path::new(source_path)));
+            }
+
+            if let Some((path, line)) = path.clone().zip(instruction.line) {
+                let location = (path.clone(), line);
+                if last_location.as_ref() != Some(&location) {
+                    rows.push(DisassemblyRow::Source {
+                        text: self.source_line(&path, line, cx),
+                        path,
+                        line,
+                    });
+                    last_location = Some(location);
+                }
+            }
+
+            rows.push(DisassemblyRow::Instruction(instruction.clone()));
+        }
+        rows
+    }
+
+    /// Returns the text of the given one-based line, loading the file in the background
+    /// the first time it's needed.
+    fn source_line(
+        &mut self,
+        path: &Arc<Path>,
+        line: u64,
+        cx: &mut Context<Self>,
+    ) -> Option<SharedString> {
+        let Some(buffer) = self.source_buffers.get(path) else {
+            self.source_buffers.insert(path.clone(), None);
+            let Some(task) = self
+                .project
+                .update(cx, |project, cx| open_source_buffer(project, path, cx))
+                .log_err()
+            else {
+                return None;
+            };
+            let path = path.clone();
+            cx.spawn(async move |this, cx| {
+                let buffer = task.await.log_err();
+                this.update(cx, |this, cx| {
+                    this.source_buffers.insert(path, buffer);
+                    this.refresh(cx);
+                })
+                .ok();
+            })
+            .detach();
+            return None;
+        };
+
+        let buffer = buffer.as_ref()?.read(cx);
+        let row = u32::try_from(line.checked_sub(1)?).ok()?;
+        if row > buffer.max_point().row {
+            return None;
+        }
+        let range = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+        Some(
+            buffer
+                .text_for_range(range)
+                .collect::<String>()
+                .trim()
+                .to_string()
+                .into(),
+        )
+    }
+
+    fn instruction_pointer_ix(&self) -> Option<usize> {
+        let pointer = self.instruction_pointer.as_deref()?;
+        self.rows.iter().position(|row| match row {
+            DisassemblyRow::Instruction(instruction) => same_address(&instruction.address, pointer),
+            DisassemblyRow::Source { .. } => false,
+        })
+    }
+
+    fn render_row(&mut self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        match &self.rows[ix] {
+            DisassemblyRow::Source { path, line, text } => {
+                let file_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                h_flex()
+                    .id(("disassembly-source", ix))
+                    .w_full()
+                    .gap_2()
+                    .pl_6()
+                    .tooltip(Tooltip::text(format!("{}:{line}", path.display())))
+                    .child(
+                        Label::new(format!("{file_name}:{line}"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when_some(text.clone(), |this, text| {
+                        this.child(
+                            Label::new(text)
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    })
+                    .into_any_element()
+            }
+            DisassemblyRow::Instruction(instruction) => {
+                let is_current = self
+                    .instruction_pointer
+                    .as_deref()
+                    .is_some_and(|pointer| same_address(&instruction.address, pointer));
+                let session = self.session.read(cx);
+                let supports_breakpoints = session
+                    .capabilities()
+                    .supports_instruction_breakpoints
+                    .unwrap_or_default();
+                let has_breakpoint = session.has_instruction_breakpoint(&instruction.address);
+                let address = instruction.address.clone();
+
+                h_flex()
+                    .id(("disassembly-instruction", ix))
+                    .w_full()
+                    .gap_2()
+                    .when(is_current, |this| {
+                        this.bg(cx.theme().colors().editor_debugger_active_line_background)
+                    })
+                    .child(
+                        div()
+                            .id(("disassembly-breakpoint", ix))
+                            .flex_none()
+                            .w_4()
+                            .h_full()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .when(has_breakpoint, |this| {
+                                this.child(
+                                    Icon::new(IconName::DebugBreakpoint)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Debugger),
+                                )
+                            })
+                            .when(supports_breakpoints, |this| {
+                                this.cursor_pointer()
+                                    .tooltip(Tooltip::text(if has_breakpoint {
+                                        "Remove Instruction Breakpoint"
+                                    } else {
+                                        "Set Instruction Breakpoint"
+                                    }))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.session.update(cx, |session, cx| {
+                                            session
+                                                .toggle_instruction_breakpoint(address.clone(), cx)
+                                        });
+                                        cx.notify();
+                                    }))
+                            }),
+                    )
+                    .child(
+                        Label::new(instruction.address.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                        this.child(
+                            div().w_32().flex_none().child(
+                                Label::new(bytes)
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                        )
+                    })
+                    .child(
+                        Label::new(instruction.instruction.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .when(is_current, |this| this.color(Color::Debugger)),
+                    )
+                    .when_some(instruction.symbol.clone(), |this, symbol| {
+                        this.child(
+                            Label::new(format!("<{symbol}>"))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    })
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+/// Opens a source file referenced by the disassembly without adding a worktree for it.
+///
+/// Files outside the project are only readable on local projects, where they're loaded
+/// into a standalone buffer.
+fn open_source_buffer(
+    project: &mut Project,
+    path: &Arc<Path>,
+    cx: &mut Context<Project>,
+) -> Task<Result<Entity<Buffer>>> {
+    if let Some(project_path) = project.find_project_path(path.as_ref(), cx) {
+        return project.open_buffer(project_path, cx);
+    }
+    if !project.is_local() {
+        return Task::ready(Err(anyhow!(
+            "{} is not in the remote project",
+            path.display()
+        )));
+    }
+
+    let fs = project.fs().clone();
+    let path = path.clone();
+    cx.spawn(async move |project, cx| {
+        let text = fs.load(&path).await?;
+        project.update(cx, |project, cx| {
+            project.create_local_buffer(&text, None, false, cx)
+        })
+    })
+}
+
+/// Compares two DAP memory references, which adapters may format differently
+/// (e.g. with or without leading zeroes).
+fn same_address(a: &str, b: &str) -> bool {
+    match (parse_address(a), parse_address(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn parse_address(address: &str) -> Option<u64> {
+    let address = address.trim();
+    if let Some(hex) = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16).ok()
+    } else {
+        address.parse().ok()
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.rows.is_empty() {
+            div()
+                .p_2()
+                .child(
+                    Label::new(if self.instruction_pointer.is_none() {
+                        "The selected stack frame has no instruction pointer"
+                    } else {
+                        "Loading disassembly…"
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .into_any_element()
+        } else {
+            uniform_list(
+                "disassembly-view",
+                self.rows.len(),
+                cx.processor(|this, range: Range<usize>, _window, cx| {
+                    range.map(|ix| this.render_row(ix, cx)).collect()
+                }),
+            )
+            .track_scroll(self.scroll_handle.clone())
+            .size_full()
+            .into_any_element()
+        };
+
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .child(content)
+            .vertical_scrollbar_for(self.scroll_handle.clone(), window, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_address() {
+        assert!(same_address("0x0000000100003f80", "0x100003F80"));
+        assert!(same_address("4294983552", "0x100003f80"));
+        assert!(!same_address("0x100003f80", "0x100003f84"));
+        assert!(same_address("main+4", "main+4"));
+    }
+}
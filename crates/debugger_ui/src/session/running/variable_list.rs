@@ -628,15 +628,20 @@ impl VariableList {
     ) {
         let (supports_set_variable, supports_data_breakpoints, supports_go_to_memory) =
             self.session.read_with(cx, |session, _| {
+                // A core dump can't be modified or resumed, so neither editing values
+                // nor watching them for changes makes sense.
+                let is_post_mortem = session.is_post_mortem();
                 (
-                    session
-                        .capabilities()
-                        .supports_set_variable
-                        .unwrap_or_default(),
-                    session
-                        .capabilities()
-                        .supports_data_breakpoints
-                        .unwrap_or_default(),
+                    !is_post_mortem
+                        && session
+                            .capabilities()
+                            .supports_set_variable
+                            .unwrap_or_default(),
+                    !is_post_mortem
+                        && session
+                            .capabilities()
+                            .supports_data_breakpoints
+                            .unwrap_or_default(),
                     session
                         .capabilities()
                         .supports_read_memory_request
@@ -1093,6 +1098,7 @@ impl VariableList {
                         this.text_color(cx.theme().colors().text_muted)
                             .when(
                                 !self.disabled
+                                    && !self.session.read(cx).is_post_mortem()
                                     && self
                                         .session
                                         .read(cx)
@@ -0,0 +1,198 @@
+use crate::{
+    persistence::DebuggerPaneItem,
+    session::running::disassembly_view::DisassemblyRow,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame,
+    requests::{Disassemble, Initialize, Scopes, SetInstructionBreakpoints, StackTrace, Threads},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use util::path;
+
+fn instruction(address: &str, text: &str, line: Option<u64>) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: text.into(),
+        symbol: None,
+        location: line.map(|_| dap::Source {
+            name: Some("main.c".into()),
+            path: Some(path!("/project/main.c").into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+        line,
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    int a = 1;\n    return a;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 2,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1004".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Disassemble, _>(move |_, args| {
+        assert_eq!(args.memory_reference, "0x1004");
+        assert_eq!(args.instruction_offset, Some(-64));
+        Ok(dap::DisassembleResponse {
+            instructions: vec![
+                instruction("0x0000000000001000", "push rbp", Some(1)),
+                instruction("0x0000000000001004", "mov dword ptr [rbp - 4], 1", Some(2)),
+                instruction(
+                    "0x000000000000100b",
+                    "mov eax, dword ptr [rbp - 4]",
+                    Some(3),
+                ),
+                // Adapters may omit the location when it's the same as the previous
+                // instruction's.
+                dap::DisassembledInstruction {
+                    location: None,
+                    ..instruction("0x000000000000100e", "pop rbp", Some(3))
+                },
+            ],
+        })
+    });
+    let instruction_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let instruction_breakpoints = instruction_breakpoints.clone();
+        move |_, args| {
+            *instruction_breakpoints.lock().unwrap() = args
+                .breakpoints
+                .iter()
+                .map(|breakpoint| breakpoint.instruction_reference.clone())
+                .collect();
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+    running_state.update_in(cx, |this, window, cx| {
+        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+        cx.refresh_windows();
+    });
+    cx.run_until_parked();
+
+    let disassembly_view = running_state.update(cx, |state, _| state.disassembly_view().clone());
+    disassembly_view.update(cx, |view, _| {
+        let rows = view
+            .rows()
+            .iter()
+            .map(|row| match row {
+                DisassemblyRow::Source { line, text, .. } => {
+                    format!("{line}: {}", text.as_deref().unwrap_or_default())
+                }
+                DisassemblyRow::Instruction(instruction) => {
+                    format!("    {}", instruction.instruction)
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                "1: int main() {",
+                "    push rbp",
+                "2: int a = 1;",
+                "    mov dword ptr [rbp - 4], 1",
+                "3: return a;",
+                "    mov eax, dword ptr [rbp - 4]",
+                "    pop rbp",
+            ]
+        );
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x000000000000100b".into(), cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        *instruction_breakpoints.lock().unwrap(),
+        ["0x000000000000100b"]
+    );
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x000000000000100b".into(), cx);
+    });
+    cx.run_until_parked();
+    assert!(instruction_breakpoints.lock().unwrap().is_empty());
+}
@@ -5,7 +5,10 @@ use project::{FakeFs, Fs as _, Project};
 use serde_json::json;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use task::{DebugRequest, DebugScenario, LaunchRequest, TaskContext, VariableName, ZedDebugConfig};
+use task::{
+    CoreDumpRequest, DebugRequest, DebugScenario, LaunchRequest, TaskContext, VariableName,
+    ZedDebugConfig,
+};
 use text::Point;
 use util::path;
 
@@ -353,6 +356,10 @@ async fn test_dap_adapter_config_conversion_and_validation(cx: &mut TestAppConte
         "JavaScript",
         "Delve",
         "GDB",
+        "rdbg",
+        "Xdebug",
+        "Java",
+        "netcoredbg",
         "fake-adapter",
     ];
 
@@ -432,3 +439,55 @@ async fn test_dap_adapter_config_conversion_and_validation(cx: &mut TestAppConte
         expected_adapters
     );
 }
+
+#[gpui::test]
+async fn test_core_dump_config_conversion(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let zed_config = ZedDebugConfig {
+        label: "test_core_dump".into(),
+        adapter: "test_adapter".into(),
+        request: DebugRequest::CoreDump(CoreDumpRequest {
+            program: "target/debug/server".into(),
+            core_file: "/var/crash/core.1234".into(),
+            sysroot: Some("/srv/sysroot".into()),
+            solib_search_paths: vec!["/srv/sysroot/usr/lib".into()],
+        }),
+        stop_on_entry: None,
+    };
+
+    for adapter_name in ["GDB", "CodeLLDB", "Debugpy", "JavaScript", "Delve"] {
+        let adapter = cx
+            .update(|cx| DapRegistry::global(cx).adapter(adapter_name))
+            .unwrap_or_else(|| panic!("Adapter {} should exist", adapter_name));
+
+        let mut adapter_specific_config = zed_config.clone();
+        adapter_specific_config.adapter = adapter_name.into();
+        let debug_scenario = adapter
+            .config_from_zed_format(adapter_specific_config)
+            .await;
+
+        match adapter_name {
+            "GDB" | "CodeLLDB" => {
+                let debug_scenario = debug_scenario.unwrap_or_else(|_| {
+                    panic!("Adapter {} should support core dumps", adapter_name)
+                });
+                assert_eq!(
+                    debug_scenario.config["coreFile"],
+                    json!("/var/crash/core.1234"),
+                    "Adapter {} should mark the session as post-mortem",
+                    adapter_name
+                );
+                assert_eq!(
+                    debug_scenario.config["program"],
+                    json!("target/debug/server")
+                );
+            }
+            _ => assert!(
+                debug_scenario.is_err(),
+                "Adapter {} should reject core dumps",
+                adapter_name
+            ),
+        }
+    }
+}
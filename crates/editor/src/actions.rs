@@ -318,6 +318,30 @@ pub struct GoToPreviousDiagnostic {
     pub severity: GoToDiagnosticSeverityFilter,
 }
 
+/// Plays a keyboard macro: the last recorded one, or a saved one when a name is given.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct PlayMacro {
+    /// The name of a macro saved in `macros.json` to play instead of the last recorded one.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// How many times to play the macro. Defaults to once.
+    #[serde(default)]
+    pub times: Option<u32>,
+}
+
+/// Plays a keyboard macro once on every line of the selections, with a cursor at the start of
+/// each line.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct PlayMacroOnEachLine {
+    /// The name of a macro saved in `macros.json` to play instead of the last recorded one.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
 actions!(
     debugger,
     [
@@ -504,6 +528,10 @@ actions!(
         GoToNextDocumentHighlight,
         /// Goes to the previous document highlight.
         GoToPreviousDocumentHighlight,
+        /// Goes to the next bookmark in the editor.
+        GoToNextBookmark,
+        /// Goes to the previous bookmark in the editor.
+        GoToPreviousBookmark,
         /// Scrolls down by half a page.
         HalfPageDown,
         /// Scrolls up by half a page.
@@ -605,6 +633,10 @@ actions!(
         PageUp,
         /// Pastes from clipboard.
         Paste,
+        /// Shows the definition of the symbol at cursor inline, below the current line.
+        PeekDefinition,
+        /// Shows the references to the symbol at cursor inline, below the current line.
+        PeekReferences,
         /// Navigates to the previous edit prediction.
         PreviousEditPrediction,
         /// Redoes the last undone edit.
@@ -625,6 +657,8 @@ actions!(
         Rewrap,
         /// Runs flycheck diagnostics.
         RunFlycheck,
+        /// Saves the last recorded keyboard macro to `macros.json` under a name.
+        SaveMacro,
         /// Scrolls the cursor to the bottom of the viewport.
         ScrollCursorBottom,
         /// Scrolls the cursor to the center of the viewport.
@@ -705,8 +739,12 @@ actions!(
         SortLinesCaseInsensitive,
         /// Sorts selected lines case-sensitively.
         SortLinesCaseSensitive,
+        /// Starts recording a keyboard macro.
+        StartRecordingMacro,
         /// Stops the language server for the current file.
         StopLanguageServer,
+        /// Stops recording the keyboard macro.
+        StopRecordingMacro,
         /// Switches between source and header files.
         SwitchSourceHeader,
         /// Inserts a tab character or indents.
@@ -715,6 +753,8 @@ actions!(
         Backtab,
         /// Toggles a breakpoint at the current line.
         ToggleBreakpoint,
+        /// Toggles a bookmark at the current line.
+        ToggleBookmark,
         /// Toggles the case of selected text.
         ToggleCase,
         /// Disables the breakpoint at the current line.
@@ -745,6 +785,8 @@ actions!(
         ToggleLineNumbers,
         /// Toggles the minimap display.
         ToggleMinimap,
+        /// Starts or stops recording a keyboard macro.
+        ToggleMacroRecording,
         /// Swaps the start and end of the current selection.
         SwapSelectionEnds,
         /// Sets a mark at the current position.
@@ -0,0 +1,205 @@
+use std::ops::Range;
+
+use collections::HashMap;
+use gpui::{App, ClickEvent, Context, Entity, Window};
+use language::{Point, ToOffset as _};
+use multi_buffer::{Anchor, MultiBufferRow, ToPoint as _};
+use project::bookmark_store::{Bookmark, BookmarkStore};
+use text::Bias;
+use ui::{ButtonStyle, IconButton, IconName, IconSize, Tooltip, prelude::*};
+
+use crate::{
+    Direction, DisplayPoint, DisplayRow, Editor, EditorSnapshot, HideMouseCursorOrigin,
+    SelectionEffects,
+    actions::{GoToNextBookmark, GoToPreviousBookmark, ToggleBookmark},
+    display_map::ToDisplayPoint as _,
+    scroll::Autoscroll,
+};
+
+impl Editor {
+    pub fn bookmark_store(&self) -> Option<Entity<BookmarkStore>> {
+        self.bookmark_store.clone()
+    }
+
+    pub fn toggle_bookmark(
+        &mut self,
+        _: &ToggleBookmark,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut rows = self
+            .selections
+            .disjoint_anchors_arc()
+            .iter()
+            .map(|selection| selection.head().to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
+
+        for row in rows {
+            self.toggle_bookmark_at_anchor(snapshot.anchor_after(Point::new(row, 0)), cx);
+        }
+    }
+
+    pub fn toggle_bookmark_at_anchor(&mut self, position: Anchor, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer_for_anchor(position, cx) else {
+            return;
+        };
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.toggle_bookmark(buffer, position.text_anchor, cx);
+        });
+    }
+
+    pub fn go_to_next_bookmark(
+        &mut self,
+        _: &GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Next, window, cx);
+    }
+
+    pub fn go_to_previous_bookmark(
+        &mut self,
+        _: &GoToPreviousBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Prev, window, cx);
+    }
+
+    /// Moves the cursor to the nearest bookmarked line in `direction`, wrapping around the ends
+    /// of the editor.
+    fn go_to_bookmark(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut rows = self
+            .bookmarks_in_range(Point::zero()..snapshot.max_point(), cx)
+            .into_iter()
+            .map(|(anchor, _)| anchor.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
+
+        let head_row = self
+            .selections
+            .newest_anchor()
+            .head()
+            .to_point(&snapshot)
+            .row;
+        let target_row = match direction {
+            Direction::Next => rows.iter().find(|row| **row > head_row).or(rows.first()),
+            Direction::Prev => rows
+                .iter()
+                .rev()
+                .find(|row| **row < head_row)
+                .or(rows.last()),
+        };
+        let Some(&row) = target_row else {
+            return;
+        };
+
+        self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
+        let destination = Point::new(row, snapshot.indent_size_for_line(MultiBufferRow(row)).len);
+        self.unfold_ranges(&[destination..destination], false, false, cx);
+        self.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()),
+            window,
+            cx,
+            |selections| selections.select_ranges([destination..destination]),
+        );
+    }
+
+    /// Returns the bookmarks within `range` of the multibuffer, in the order of their excerpts.
+    fn bookmarks_in_range(&self, range: Range<Point>, cx: &App) -> Vec<(Anchor, Bookmark)> {
+        let Some(bookmark_store) = self.bookmark_store.as_ref() else {
+            return Vec::new();
+        };
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+
+        let mut bookmarks = Vec::new();
+        for (buffer_snapshot, range, excerpt_id) in snapshot.range_to_buffer_ranges(range) {
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            for bookmark in bookmark_store.read(cx).bookmarks_for_buffer(&buffer, cx) {
+                let offset = bookmark.position.to_offset(buffer_snapshot);
+                if range.start <= offset && offset <= range.end {
+                    let anchor = Anchor::in_buffer(
+                        excerpt_id,
+                        buffer_snapshot.remote_id(),
+                        bookmark.position,
+                    );
+                    bookmarks.push((anchor, bookmark.clone()));
+                }
+            }
+        }
+        bookmarks
+    }
+
+    pub(crate) fn active_bookmarks(
+        &self,
+        range: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &App,
+    ) -> HashMap<DisplayRow, (Anchor, Bookmark)> {
+        let range = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left)
+            ..snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+        self.bookmarks_in_range(range, cx)
+            .into_iter()
+            .map(|(anchor, bookmark)| {
+                let row = anchor
+                    .to_point(snapshot.buffer_snapshot())
+                    .to_display_point(snapshot)
+                    .row();
+                (row, (anchor, bookmark))
+            })
+            .collect()
+    }
+
+    pub(crate) fn render_bookmark(
+        &self,
+        position: Anchor,
+        row: DisplayRow,
+        bookmark: &Bookmark,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let focus_handle = self.focus_handle.clone();
+        let label = bookmark.label.clone();
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(Color::Accent)
+            .style(ButtonStyle::Transparent)
+            .on_click(cx.listener(move |editor, _: &ClickEvent, window, cx| {
+                window.focus(&editor.focus_handle(cx));
+                editor.toggle_bookmark_at_anchor(position, cx);
+            }))
+            .tooltip(move |window, cx| match &label {
+                Some(label) => Tooltip::with_meta_in(
+                    label.clone(),
+                    Some(&ToggleBookmark),
+                    "Click to remove the bookmark",
+                    &focus_handle,
+                    window,
+                    cx,
+                ),
+                None => Tooltip::for_action_in(
+                    "Remove Bookmark",
+                    &ToggleBookmark,
+                    &focus_handle,
+                    window,
+                    cx,
+                ),
+            })
+    }
+}
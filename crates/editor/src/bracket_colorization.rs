@@ -0,0 +1,208 @@
+use std::{cmp::Reverse, ops::Range};
+
+use gpui::{App, Context, HighlightStyle, Hsla};
+use language::{BracketMatch, BufferSnapshot};
+use multi_buffer::{Anchor, MultiBufferRow, ToOffset as _, ToPoint as _};
+use text::Point;
+use theme::{ActiveTheme as _, ThemeColors};
+
+use crate::{DisplayPoint, DisplayRow, Editor, EditorSnapshot};
+
+enum RainbowBrackets {}
+
+/// The number of theme colors that nested brackets cycle through.
+const RAINBOW_BRACKET_COLORS: usize = 6;
+
+/// A guide drawn between the brackets of the scope enclosing the newest cursor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BracketGuide {
+    /// The first row between the brackets.
+    pub start_row: MultiBufferRow,
+    /// The last row between the brackets.
+    pub end_row: MultiBufferRow,
+    /// The position whose column the guide is aligned with.
+    pub position: Point,
+    pub depth: usize,
+}
+
+pub(crate) fn rainbow_bracket_color(depth: usize, colors: &ThemeColors) -> Hsla {
+    match depth % RAINBOW_BRACKET_COLORS {
+        0 => colors.editor_rainbow_bracket_1,
+        1 => colors.editor_rainbow_bracket_2,
+        2 => colors.editor_rainbow_bracket_3,
+        3 => colors.editor_rainbow_bracket_4,
+        4 => colors.editor_rainbow_bracket_5,
+        _ => colors.editor_rainbow_bracket_6,
+    }
+}
+
+impl Editor {
+    /// Colors the brackets in the visible part of the editor by their nesting depth.
+    pub(crate) fn refresh_bracket_colors(&mut self, cx: &mut Context<Self>) {
+        if !self.mode.is_full() {
+            return;
+        }
+
+        let mut ranges_by_depth = vec![Vec::new(); RAINBOW_BRACKET_COLORS];
+        for (range, depth) in self.rainbow_brackets(cx) {
+            ranges_by_depth[depth % RAINBOW_BRACKET_COLORS].push(range);
+        }
+
+        for (depth, ranges) in ranges_by_depth.into_iter().enumerate() {
+            let color = rainbow_bracket_color(depth, cx.theme().colors());
+            self.highlight_text_key::<RainbowBrackets>(
+                depth,
+                ranges,
+                HighlightStyle {
+                    color: Some(color),
+                    ..HighlightStyle::default()
+                },
+                cx,
+            );
+        }
+    }
+
+    /// Returns the visible brackets of languages with `colorize_brackets` enabled,
+    /// along with their nesting depth.
+    pub(crate) fn rainbow_brackets(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Vec<(Range<Anchor>, usize)> {
+        // Folds and soft wraps make visible rows differ from buffer rows, so the visible range
+        // is computed in display rows.
+        let display_snapshot = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let snapshot = display_snapshot.buffer_snapshot();
+        let scroll_top = self
+            .scroll_manager
+            .anchor()
+            .scroll_position(&display_snapshot)
+            .y;
+        let start_row =
+            DisplayRow(scroll_top.floor() as u32).min(display_snapshot.max_point().row());
+        let end_row =
+            DisplayRow((scroll_top + self.visible_line_count().unwrap_or(0.)).ceil() as u32)
+                .min(display_snapshot.max_point().row());
+        let visible_start = DisplayPoint::new(start_row, 0).to_point(&display_snapshot);
+        let visible_end = DisplayPoint::new(end_row, display_snapshot.line_len(end_row))
+            .to_point(&display_snapshot);
+
+        let mut brackets = Vec::new();
+        for (buffer, range, excerpt_id) in
+            snapshot.range_to_buffer_ranges(visible_start..visible_end)
+        {
+            if range.is_empty() || !buffer.settings_at(range.start, cx).colorize_brackets {
+                continue;
+            }
+            for (pair, depth) in colorized_bracket_pairs(buffer, range.clone()) {
+                for bracket in [pair.open_range, pair.close_range] {
+                    if bracket.start < range.start || bracket.end > range.end {
+                        continue;
+                    }
+                    if let Some(start) =
+                        snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_after(bracket.start))
+                        && let Some(end) = snapshot
+                            .anchor_in_excerpt(excerpt_id, buffer.anchor_before(bracket.end))
+                    {
+                        brackets.push((start..end, depth));
+                    }
+                }
+            }
+        }
+        brackets
+    }
+}
+
+impl EditorSnapshot {
+    /// Returns the guide for the innermost bracket pair enclosing the newest cursor
+    /// with rows between its brackets, if its language has `show_bracket_guides` enabled.
+    pub(crate) fn bracket_guide(&self, editor: &Editor, cx: &App) -> Option<BracketGuide> {
+        let snapshot = self.buffer_snapshot();
+        let head = editor.selections.newest_anchor().head().to_offset(snapshot);
+        if !snapshot.language_settings_at(head, cx).show_bracket_guides {
+            return None;
+        }
+
+        let mut excerpt = snapshot.excerpt_containing(head..head)?;
+        let buffer = excerpt.buffer();
+        let buffer_head = excerpt.map_offset_to_buffer(head);
+        let (pair, depth) = colorized_bracket_pairs(buffer, buffer_head..buffer_head)
+            .into_iter()
+            .filter(|(pair, _)| {
+                pair.open_range.end <= buffer_head
+                    && pair.close_range.start >= buffer_head
+                    && buffer.offset_to_point(pair.close_range.start).row
+                        > buffer.offset_to_point(pair.open_range.end).row + 1
+                    && excerpt.contains_buffer_range(pair.open_range.start..pair.close_range.end)
+            })
+            .max_by_key(|(_, depth)| *depth)?;
+
+        let open = excerpt
+            .map_range_from_buffer(pair.open_range)
+            .start
+            .to_point(snapshot);
+        let close = excerpt
+            .map_range_from_buffer(pair.close_range)
+            .start
+            .to_point(snapshot);
+
+        // Align the guide with the start of the line that opens the scope, unless
+        // the closing bracket is outdented further.
+        let open_indent = snapshot.indent_size_for_line(MultiBufferRow(open.row)).len;
+        let position = if close.column < open_indent {
+            close
+        } else {
+            Point::new(open.row, open_indent)
+        };
+
+        Some(BracketGuide {
+            start_row: MultiBufferRow(open.row + 1),
+            end_row: MultiBufferRow(close.row - 1),
+            position,
+            depth,
+        })
+    }
+}
+
+/// Returns the bracket pairs overlapping `range` that are listed in the language's
+/// bracket config, along with their nesting depth.
+///
+/// Pairs with identical open and close text, such as quotes, are skipped as their
+/// nesting is ambiguous.
+fn colorized_bracket_pairs(
+    buffer: &BufferSnapshot,
+    range: Range<usize>,
+) -> Vec<(BracketMatch, usize)> {
+    let mut pairs = buffer
+        .bracket_ranges(range)
+        .filter(|pair| {
+            let Some(scope) = buffer.language_scope_at(pair.open_range.start) else {
+                return false;
+            };
+            scope.brackets().any(|(bracket_pair, _)| {
+                bracket_pair.start != bracket_pair.end
+                    && bracket_pair.start.len() == pair.open_range.len()
+                    && bracket_pair.end.len() == pair.close_range.len()
+                    && buffer.contains_str_at(pair.open_range.start, &bracket_pair.start)
+                    && buffer.contains_str_at(pair.close_range.start, &bracket_pair.end)
+            })
+        })
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|pair| (pair.open_range.start, Reverse(pair.close_range.end)));
+    pairs.dedup();
+
+    let mut enclosing_ends = Vec::new();
+    pairs
+        .into_iter()
+        .map(|pair| {
+            while enclosing_ends
+                .last()
+                .is_some_and(|end| *end <= pair.open_range.start)
+            {
+                enclosing_ends.pop();
+            }
+            let depth = enclosing_ends.len();
+            enclosing_ends.push(pair.close_range.end);
+            (pair, depth)
+        })
+        .collect()
+}
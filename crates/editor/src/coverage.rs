@@ -0,0 +1,100 @@
+use std::{any::TypeId, ops::Range};
+
+use gpui::{App, Context, Hsla};
+use language::Point;
+use multi_buffer::{Anchor, ExcerptId};
+use project::{ProjectPath, coverage_store::LineCoverage, project_settings::ProjectSettings};
+use settings::Settings as _;
+use text::ToPoint as _;
+use theme::ActiveTheme as _;
+
+use crate::Editor;
+
+enum CoveredLines {}
+enum UncoveredLines {}
+enum PartiallyCoveredLines {}
+
+impl Editor {
+    /// Highlights the lines of the visible excerpts that coverage reports mention in the gutter.
+    pub(crate) fn refresh_coverage_highlights(&mut self, cx: &mut Context<Self>) {
+        let mut covered = Vec::new();
+        let mut uncovered = Vec::new();
+        let mut partial = Vec::new();
+        if ProjectSettings::get_global(cx).coverage.gutter
+            && let Some(project) = self.project.as_ref()
+        {
+            let coverage_store = project.read(cx).coverage_store();
+            let coverage_store = coverage_store.read(cx);
+            let snapshot = self.buffer.read(cx).snapshot(cx);
+            let mut previous_line = None::<(ExcerptId, u32, LineCoverage)>;
+            for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+                let Some(coverage) = buffer_snapshot.file().and_then(|file| {
+                    coverage_store.coverage_for_path(&ProjectPath::from_file(file.as_ref(), cx))
+                }) else {
+                    continue;
+                };
+                let context = excerpt_range.context.start.to_point(buffer_snapshot)
+                    ..excerpt_range.context.end.to_point(buffer_snapshot);
+                let max_row = buffer_snapshot.max_point().row;
+                for (&row, &line_coverage) in
+                    coverage.lines.range(context.start.row..=context.end.row)
+                {
+                    if row > max_row {
+                        break;
+                    }
+                    let Some(end) = snapshot.anchor_in_excerpt(
+                        excerpt_id,
+                        buffer_snapshot
+                            .anchor_before(Point::new(row, buffer_snapshot.line_len(row))),
+                    ) else {
+                        continue;
+                    };
+                    let ranges: &mut Vec<Range<Anchor>> = match line_coverage {
+                        LineCoverage::Covered => &mut covered,
+                        LineCoverage::Uncovered => &mut uncovered,
+                        LineCoverage::Partial => &mut partial,
+                    };
+                    // Extend the previous highlight over consecutive lines with the same coverage.
+                    if previous_line == Some((excerpt_id, row.wrapping_sub(1), line_coverage))
+                        && let Some(range) = ranges.last_mut()
+                    {
+                        range.end = end;
+                    } else if let Some(start) = snapshot.anchor_in_excerpt(
+                        excerpt_id,
+                        buffer_snapshot.anchor_after(Point::new(row, 0)),
+                    ) {
+                        ranges.push(start..end);
+                    }
+                    previous_line = Some((excerpt_id, row, line_coverage));
+                }
+            }
+        }
+
+        self.set_coverage_highlights::<CoveredLines>(covered, |cx| cx.theme().status().created, cx);
+        self.set_coverage_highlights::<UncoveredLines>(
+            uncovered,
+            |cx| cx.theme().status().deleted,
+            cx,
+        );
+        self.set_coverage_highlights::<PartiallyCoveredLines>(
+            partial,
+            |cx| cx.theme().status().modified,
+            cx,
+        );
+    }
+
+    fn set_coverage_highlights<T: 'static>(
+        &mut self,
+        ranges: Vec<Range<Anchor>>,
+        color_fetcher: fn(&App) -> Hsla,
+        cx: &mut Context<Self>,
+    ) {
+        if ranges.is_empty() {
+            if self.gutter_highlights.contains_key(&TypeId::of::<T>()) {
+                self.clear_gutter_highlights::<T>(cx);
+            }
+        } else {
+            self.highlight_gutter::<T>(ranges, color_fetcher, cx);
+        }
+    }
+}
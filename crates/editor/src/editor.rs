@@ -14,8 +14,11 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 mod blink_manager;
+mod bookmarks;
+mod bracket_colorization;
 mod clangd_ext;
 pub mod code_context_menus;
+mod coverage;
 pub mod display_map;
 mod editor_settings;
 mod element;
@@ -27,16 +30,19 @@ mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
 mod jsx_tag_auto_close;
+mod keyboard_macros;
 mod linked_editing_ranges;
 mod lsp_colors;
 mod lsp_ext;
 mod mouse_context_menu;
 pub mod movement;
+mod peek_view;
 mod persistence;
 mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod spell_check;
 pub mod tasks;
 
 #[cfg(test)]
@@ -46,6 +52,7 @@ mod edit_prediction_tests;
 #[cfg(test)]
 mod editor_tests;
 mod signature_help;
+mod sticky_scroll;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
 
@@ -127,7 +134,7 @@ use language::{
         self, InlayHintSettings, LspInsertMode, RewrapBehavior, WordsCompletionMode,
         all_language_settings, language_settings,
     },
-    point_from_lsp, point_to_lsp, text_diff_with_options,
+    point_from_lsp_with_encoding, point_to_lsp, text_diff_with_options,
 };
 use linked_editing_ranges::refresh_linked_ranges;
 use lsp::{
@@ -143,12 +150,15 @@ use multi_buffer::{
     ToOffsetUtf16,
 };
 use parking_lot::Mutex;
+use peek_view::PeekState;
 use persistence::DB;
 use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
     CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint,
     Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectPath,
     ProjectTransaction, TaskSourceKind,
+    bookmark_store::BookmarkStore,
+    coverage_store::CoverageStoreEvent,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -159,6 +169,7 @@ use project::{
     git_store::{GitStoreEvent, RepositoryEvent},
     lsp_store::{CompletionDocumentation, FormatTrigger, LspFormatTarget, OpenLspBufferHandle},
     project_settings::{DiagnosticSeverity, GoToDiagnosticSeverityFilter, ProjectSettings},
+    test_results::TestStatus,
 };
 use rand::seq::SliceRandom;
 use rpc::{ErrorCode, ErrorExt, proto::PeerId};
@@ -170,6 +181,7 @@ use serde::{Deserialize, Serialize};
 use settings::{GitGutterSetting, Settings, SettingsLocation, SettingsStore, update_settings_file};
 use smallvec::{SmallVec, smallvec};
 use snippet::Snippet;
+use spell_check::SpellCheckCodeActionProvider;
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
@@ -347,6 +359,7 @@ pub fn init_settings(cx: &mut App) {
 
 pub fn init(cx: &mut App) {
     init_settings(cx);
+    keyboard_macros::init(cx);
 
     cx.set_global(GlobalBlameRenderer(Arc::new(())));
 
@@ -1095,6 +1108,7 @@ pub struct Editor {
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
     pending_rename: Option<RenameState>,
+    peek: Option<PeekState>,
     searchable: bool,
     cursor_shape: CursorShape,
     current_line_highlight: Option<CurrentLineHighlight>,
@@ -1164,6 +1178,7 @@ pub struct Editor {
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
+    bookmark_store: Option<Entity<BookmarkStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
@@ -1983,6 +1998,12 @@ impl Editor {
                     },
                 ));
             };
+            project_subscriptions
+                .push(cx.observe(&project.read(cx).test_results(), |_, _, cx| cx.notify()));
+            project_subscriptions.push(cx.subscribe(
+                &project.read(cx).coverage_store(),
+                |editor, _, _: &CoverageStoreEvent, cx| editor.refresh_coverage_highlights(cx),
+            ));
 
             project_subscriptions.push(cx.subscribe_in(
                 &project.read(cx).breakpoint_store(),
@@ -2056,6 +2077,10 @@ impl Editor {
             (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).breakpoint_store()),
             _ => None,
         };
+        let bookmark_store = match (&mode, project.as_ref()) {
+            (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).bookmark_store()),
+            _ => None,
+        };
 
         let mut code_action_providers = Vec::new();
         let mut load_uncommitted_diff = None;
@@ -2070,6 +2095,9 @@ impl Editor {
                 )
                 .shared(),
             );
+            code_action_providers.push(Rc::new(SpellCheckCodeActionProvider::new(
+                project.read(cx).spell_check_store(),
+            )) as Rc<_>);
             code_action_providers.push(Rc::new(project) as Rc<_>);
         }
 
@@ -2149,6 +2177,7 @@ impl Editor {
             document_highlights_task: None,
             linked_editing_range_task: None,
             pending_rename: None,
+            peek: None,
             searchable: !is_minimap,
             cursor_shape: EditorSettings::get_global(cx)
                 .cursor_shape
@@ -2214,6 +2243,7 @@ impl Editor {
             tasks: BTreeMap::default(),
 
             breakpoint_store,
+            bookmark_store,
             gutter_breakpoint_indicator: (None, None),
             hovered_diff_hunk_row: None,
             _subscriptions: (!is_minimap)
@@ -2284,7 +2314,15 @@ impl Editor {
                     cx.notify();
                 }));
         }
+        if let Some(bookmarks) = editor.bookmark_store.as_ref() {
+            editor
+                ._subscriptions
+                .push(cx.observe(bookmarks, |_, _, cx| {
+                    cx.notify();
+                }));
+        }
         editor.tasks_update_task = Some(editor.refresh_runnables(window, cx));
+        editor.refresh_coverage_highlights(cx);
         editor._subscriptions.extend(project_subscriptions);
 
         editor._subscriptions.push(cx.subscribe_in(
@@ -3945,6 +3983,10 @@ impl Editor {
             return true;
         }
 
+        if self.dismiss_peek(window, cx) {
+            return true;
+        }
+
         if hide_hover(self, cx) {
             return true;
         }
@@ -8568,38 +8610,42 @@ impl Editor {
         is_active: bool,
         row: DisplayRow,
         breakpoint: Option<(Anchor, Breakpoint, Option<BreakpointSessionState>)>,
+        test_status: Option<TestStatus>,
         cx: &mut Context<Self>,
     ) -> IconButton {
-        let color = Color::Muted;
         let position = breakpoint.as_ref().map(|(anchor, _, _)| *anchor);
+        let (icon, color) = match test_status {
+            None => (ui::IconName::PlayOutlined, Color::Muted),
+            Some(TestStatus::Running) => (ui::IconName::LoadCircle, Color::Accent),
+            Some(TestStatus::Passed) => (ui::IconName::Check, Color::Success),
+            Some(TestStatus::Failed) => (ui::IconName::XCircle, Color::Error),
+            Some(TestStatus::Skipped) => (ui::IconName::Dash, Color::Muted),
+        };
 
-        IconButton::new(
-            ("run_indicator", row.0 as usize),
-            ui::IconName::PlayOutlined,
-        )
-        .shape(ui::IconButtonShape::Square)
-        .icon_size(IconSize::XSmall)
-        .icon_color(color)
-        .toggle_state(is_active)
-        .on_click(cx.listener(move |editor, e: &ClickEvent, window, cx| {
-            let quick_launch = match e {
-                ClickEvent::Keyboard(_) => true,
-                ClickEvent::Mouse(e) => e.down.button == MouseButton::Left,
-            };
+        IconButton::new(("run_indicator", row.0 as usize), icon)
+            .shape(ui::IconButtonShape::Square)
+            .icon_size(IconSize::XSmall)
+            .icon_color(color)
+            .toggle_state(is_active)
+            .on_click(cx.listener(move |editor, e: &ClickEvent, window, cx| {
+                let quick_launch = match e {
+                    ClickEvent::Keyboard(_) => true,
+                    ClickEvent::Mouse(e) => e.down.button == MouseButton::Left,
+                };
 
-            window.focus(&editor.focus_handle(cx));
-            editor.toggle_code_actions(
-                &ToggleCodeActions {
-                    deployed_from: Some(CodeActionSource::RunMenu(row)),
-                    quick_launch,
-                },
-                window,
-                cx,
-            );
-        }))
-        .on_right_click(cx.listener(move |editor, event: &ClickEvent, window, cx| {
-            editor.set_breakpoint_context_menu(row, position, event.position(), window, cx);
-        }))
+                window.focus(&editor.focus_handle(cx));
+                editor.toggle_code_actions(
+                    &ToggleCodeActions {
+                        deployed_from: Some(CodeActionSource::RunMenu(row)),
+                        quick_launch,
+                    },
+                    window,
+                    cx,
+                );
+            }))
+            .on_right_click(cx.listener(move |editor, event: &ClickEvent, window, cx| {
+                editor.set_breakpoint_context_menu(row, position, event.position(), window, cx);
+            }))
     }
 
     pub fn context_menu_visible(&self) -> bool {
@@ -9340,6 +9386,7 @@ impl Editor {
             Some(provider) => match provider.provider.name() {
                 "copilot" => IconName::Copilot,
                 "supermaven" => IconName::Supermaven,
+                "local" => IconName::Ai,
                 _ => IconName::ZedPredict,
             },
             None => IconName::ZedPredict,
@@ -12857,10 +12904,11 @@ impl Editor {
 
     pub fn move_left(&mut self, _: &MoveLeft, window: &mut Window, cx: &mut Context<Self>) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
+        let text_layout_details = self.text_layout_details(window);
         self.change_selections(Default::default(), window, cx, |s| {
             s.move_with(|map, selection| {
                 let cursor = if selection.is_empty() {
-                    movement::left(map, selection.start)
+                    movement::visual_left(map, selection.start, &text_layout_details)
                 } else {
                     selection.start
                 };
@@ -12871,17 +12919,24 @@ impl Editor {
 
     pub fn select_left(&mut self, _: &SelectLeft, window: &mut Window, cx: &mut Context<Self>) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
+        let text_layout_details = self.text_layout_details(window);
         self.change_selections(Default::default(), window, cx, |s| {
-            s.move_heads_with(|map, head, _| (movement::left(map, head), SelectionGoal::None));
+            s.move_heads_with(|map, head, _| {
+                (
+                    movement::visual_left(map, head, &text_layout_details),
+                    SelectionGoal::None,
+                )
+            });
         })
     }
 
     pub fn move_right(&mut self, _: &MoveRight, window: &mut Window, cx: &mut Context<Self>) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
+        let text_layout_details = self.text_layout_details(window);
         self.change_selections(Default::default(), window, cx, |s| {
             s.move_with(|map, selection| {
                 let cursor = if selection.is_empty() {
-                    movement::right(map, selection.end)
+                    movement::visual_right(map, selection.end, &text_layout_details)
                 } else {
                     selection.end
                 };
@@ -12892,8 +12947,14 @@ impl Editor {
 
     pub fn select_right(&mut self, _: &SelectRight, window: &mut Window, cx: &mut Context<Self>) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
+        let text_layout_details = self.text_layout_details(window);
         self.change_selections(Default::default(), window, cx, |s| {
-            s.move_heads_with(|map, head, _| (movement::right(map, head), SelectionGoal::None));
+            s.move_heads_with(|map, head, _| {
+                (
+                    movement::visual_right(map, head, &text_layout_details),
+                    SelectionGoal::None,
+                )
+            });
         });
     }
 
@@ -16513,7 +16574,8 @@ impl Editor {
             .into_iter()
             .filter_map(|def| match def {
                 HoverLink::Text(link) => Some(Task::ready(anyhow::Ok(Some(link.target)))),
-                HoverLink::InlayHint(lsp_location, server_id) => {
+                HoverLink::InlayHint(lsp_location, server_id)
+                | HoverLink::DocumentLink(lsp_location, server_id) => {
                     let computation =
                         self.compute_target_location(lsp_location, server_id, window, cx);
                     Some(cx.background_spawn(computation))
@@ -16688,18 +16750,36 @@ impl Editor {
         };
 
         cx.spawn_in(window, async move |editor, cx| {
-            let location_task = editor.update(cx, |_, cx| {
-                project.update(cx, |project, cx| {
+            let (location_task, encoding) = editor.update(cx, |_, cx| {
+                let encoding = project
+                    .read(cx)
+                    .lsp_store()
+                    .read(cx)
+                    .language_server_position_encoding(server_id);
+                let location_task = project.update(cx, |project, cx| {
                     project.open_local_buffer_via_lsp(lsp_location.uri.clone(), server_id, cx)
-                })
+                });
+                (location_task, encoding)
             })?;
             let location = Some({
                 let target_buffer_handle = location_task.await.context("open local buffer")?;
                 let range = target_buffer_handle.read_with(cx, |target_buffer, _| {
-                    let target_start = target_buffer
-                        .clip_point_utf16(point_from_lsp(lsp_location.range.start), Bias::Left);
-                    let target_end = target_buffer
-                        .clip_point_utf16(point_from_lsp(lsp_location.range.end), Bias::Left);
+                    let target_start = target_buffer.clip_point_utf16(
+                        point_from_lsp_with_encoding(
+                            lsp_location.range.start,
+                            encoding,
+                            target_buffer,
+                        ),
+                        Bias::Left,
+                    );
+                    let target_end = target_buffer.clip_point_utf16(
+                        point_from_lsp_with_encoding(
+                            lsp_location.range.end,
+                            encoding,
+                            target_buffer,
+                        ),
+                        Bias::Left,
+                    );
                     target_buffer.anchor_after(target_start)
                         ..target_buffer.anchor_before(target_end)
                 })?;
@@ -19157,6 +19237,11 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn clear_soft_wrap_mode_override(&mut self, cx: &mut Context<Self>) {
+        self.soft_wrap_mode_override = None;
+        cx.notify();
+    }
+
     pub fn set_hard_wrap(&mut self, hard_wrap: Option<usize>, cx: &mut Context<Self>) {
         self.hard_wrap = hard_wrap;
         cx.notify();
@@ -20791,6 +20876,8 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_coverage_highlights(cx);
+                self.refresh_bracket_colors(cx);
             }
             multi_buffer::Event::ExcerptsRemoved {
                 ids,
@@ -20819,10 +20906,13 @@ impl Editor {
             }
             multi_buffer::Event::ExcerptsExpanded { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_coverage_highlights(cx);
+                self.refresh_bracket_colors(cx);
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+                self.refresh_bracket_colors(cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
@@ -20838,9 +20928,13 @@ impl Editor {
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
-            multi_buffer::Event::FileHandleChanged
-            | multi_buffer::Event::Reloaded
-            | multi_buffer::Event::BufferDiffChanged => cx.emit(EditorEvent::TitleChanged),
+            multi_buffer::Event::FileHandleChanged => {
+                self.refresh_coverage_highlights(cx);
+                cx.emit(EditorEvent::TitleChanged)
+            }
+            multi_buffer::Event::Reloaded | multi_buffer::Event::BufferDiffChanged => {
+                cx.emit(EditorEvent::TitleChanged)
+            }
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.update_diagnostics_state(window, cx);
             }
@@ -20901,6 +20995,8 @@ impl Editor {
             self.set_max_diagnostics_severity(new_severity, cx);
         }
         self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+        self.refresh_coverage_highlights(cx);
+        self.refresh_bracket_colors(cx);
         self.update_edit_prediction_settings(cx);
         self.refresh_edit_prediction(true, false, window, cx);
         self.refresh_inline_values(cx);
@@ -22624,6 +22720,19 @@ pub trait SemanticsProvider {
         cx: &mut App,
     ) -> Option<Task<Result<Option<Vec<LocationLink>>>>>;
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<project::DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        link: project::DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<project::DocumentLink>>>;
+
     fn range_for_rename(
         &self,
         buffer: &Entity<Buffer>,
@@ -23074,6 +23183,25 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<project::DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        link: project::DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<project::DocumentLink>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(link, buffer, cx)
+        }))
+    }
+
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
         self.update(cx, |project, cx| {
             if project
@@ -55,6 +55,7 @@ pub struct EditorSettings {
     pub diagnostics_max_severity: Option<DiagnosticSeverity>,
     pub inline_code_actions: bool,
     pub drag_and_drop_selection: DragAndDropSelection,
+    pub sticky_scroll: StickyScroll,
     pub lsp_document_colors: DocumentColorsRenderMode,
     pub minimum_contrast_for_highlights: f32,
 }
@@ -152,6 +153,20 @@ pub struct DragAndDropSelection {
     pub delay: u64,
 }
 
+/// Pin the scopes enclosing the top of the viewport while scrolling.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct StickyScroll {
+    /// Whether to show the enclosing scopes at the top of the editor.
+    ///
+    /// Default: false
+    pub enabled: bool,
+
+    /// The maximum number of nested scopes to pin at once.
+    ///
+    /// Default: 5
+    pub max_depth: u32,
+}
+
 /// Default options for buffer and project search items.
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct SearchSettings {
@@ -185,6 +200,7 @@ impl Settings for EditorSettings {
         let toolbar = editor.toolbar.unwrap();
         let search = editor.search.unwrap();
         let drag_and_drop_selection = editor.drag_and_drop_selection.unwrap();
+        let sticky_scroll = editor.sticky_scroll.unwrap();
         Self {
             cursor_blink: editor.cursor_blink.unwrap(),
             cursor_shape: editor.cursor_shape.map(Into::into),
@@ -266,6 +282,10 @@ impl Settings for EditorSettings {
                 enabled: drag_and_drop_selection.enabled.unwrap(),
                 delay: drag_and_drop_selection.delay.unwrap(),
             },
+            sticky_scroll: StickyScroll {
+                enabled: sticky_scroll.enabled.unwrap(),
+                max_depth: sticky_scroll.max_depth.unwrap(),
+            },
             lsp_document_colors: editor.lsp_document_colors.unwrap(),
             minimum_contrast_for_highlights: editor.minimum_contrast_for_highlights.unwrap(),
         }
@@ -473,5 +493,15 @@ impl Settings for EditorSettings {
         if minimap != settings::MinimapContent::default() {
             current.editor.minimap = Some(minimap)
         }
+
+        let mut sticky_scroll = settings::StickyScrollContent::default();
+        vscode.bool_setting("editor.stickyScroll.enabled", &mut sticky_scroll.enabled);
+        vscode.u32_setting(
+            "editor.stickyScroll.maxLineCount",
+            &mut sticky_scroll.max_depth,
+        );
+        if sticky_scroll != settings::StickyScrollContent::default() {
+            current.editor.sticky_scroll = Some(sticky_scroll)
+        }
     }
 }
@@ -1394,6 +1394,64 @@ fn test_move_cursor(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_move_cursor_visually_in_rtl_text(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    // "abc שלום" followed by a second line. The Hebrew word occupies columns 4..12 and
+    // is displayed from right to left.
+    let editor = cx.add_window(|window, cx| {
+        let buffer = MultiBuffer::build_simple("abc \u{5e9}\u{5dc}\u{5d5}\u{5dd}\nxyz", cx);
+        build_editor(buffer, window, cx)
+    });
+
+    _ = editor.update(cx, |editor, window, cx| {
+        let cursor_columns_moving_right = (0..10)
+            .map(|_| {
+                editor.move_right(&MoveRight, window, cx);
+                let head = editor.selections.newest_display(cx).head();
+                (head.row().0, head.column())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cursor_columns_moving_right,
+            [
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (0, 12),
+                (0, 10),
+                (0, 8),
+                (0, 6),
+                (0, 4),
+                (1, 0),
+                (1, 1)
+            ]
+        );
+
+        editor.move_left(&MoveLeft, window, cx);
+        editor.move_left(&MoveLeft, window, cx);
+        assert_eq!(
+            editor.selections.display_ranges(cx),
+            &[DisplayPoint::new(DisplayRow(0), 12)..DisplayPoint::new(DisplayRow(0), 12)]
+        );
+
+        // Selecting visually from the right edge of the Hebrew word extends the
+        // selection over its logical start.
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(0), 8)..DisplayPoint::new(DisplayRow(0), 8)
+            ]);
+        });
+        editor.select_right(&SelectRight, window, cx);
+        editor.select_right(&SelectRight, window, cx);
+        assert_eq!(
+            editor.selections.display_ranges(cx),
+            &[DisplayPoint::new(DisplayRow(0), 8)..DisplayPoint::new(DisplayRow(0), 4)]
+        );
+    });
+}
+
 #[gpui::test]
 fn test_move_cursor_multibyte(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -8777,6 +8835,215 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_sticky_scopes(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(indoc! {r#"
+            (impl_item "impl" @context type: (_) @name) @item
+            (function_item "fn" @context name: (_) @name) @item
+        "#})
+        .unwrap(),
+    );
+
+    let text = indoc! {"
+        struct Foo;
+
+        impl Foo {
+            fn one() {
+                let a = 1;
+                let b = 2;
+            }
+
+            fn two() {
+                let c = 3;
+                let d = 4;
+                let e = 5;
+            }
+        }
+    "};
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    editor.update_in(cx, |editor, window, cx| {
+        let snapshot = editor.snapshot(window, cx);
+        let theme = theme::SyntaxTheme::default();
+        let scopes = |top: u32, reserved_rows: u32, max_depth: usize| {
+            snapshot
+                .sticky_scopes(DisplayRow(top), reserved_rows, max_depth, &theme)
+                .into_iter()
+                .map(|scope| (scope.item.text, scope.start.row))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(scopes(0, 0, 5), vec![]);
+        // A scope starting at the top row is visible, so it isn't pinned.
+        assert_eq!(scopes(2, 0, 5), vec![]);
+        // The pinned `impl` covers the `fn` line, so the `fn` gets pinned too.
+        assert_eq!(
+            scopes(3, 0, 5),
+            vec![("impl Foo".to_string(), 2), ("fn one".to_string(), 3)]
+        );
+        assert_eq!(
+            scopes(4, 0, 5),
+            vec![("impl Foo".to_string(), 2), ("fn one".to_string(), 3)]
+        );
+        // Only the scopes still enclosing the row below the pinned ones are kept.
+        assert_eq!(scopes(5, 0, 5), vec![("impl Foo".to_string(), 2)]);
+        assert_eq!(
+            scopes(9, 0, 5),
+            vec![("impl Foo".to_string(), 2), ("fn two".to_string(), 8)]
+        );
+        assert_eq!(scopes(9, 0, 1), vec![("impl Foo".to_string(), 2)]);
+        assert_eq!(scopes(9, 0, 0), vec![]);
+        assert_eq!(
+            scopes(7, 2, 5),
+            vec![("impl Foo".to_string(), 2), ("fn two".to_string(), 8)]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_rainbow_brackets(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.colorize_brackets = Some(true);
+        settings.defaults.show_bracket_guides = Some(true);
+    });
+
+    let bracket_pair = |start: &str, end: &str| BracketPair {
+        start: start.to_string(),
+        end: end.to_string(),
+        close: true,
+        surround: true,
+        newline: true,
+    };
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                brackets: BracketPairConfig {
+                    pairs: vec![
+                        bracket_pair("(", ")"),
+                        bracket_pair("{", "}"),
+                        bracket_pair("\"", "\""),
+                    ],
+                    ..BracketPairConfig::default()
+                },
+                ..LanguageConfig::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_brackets_query(indoc! {r#"
+            ("(" @open ")" @close)
+            ("{" @open "}" @close)
+            ("[" @open "]" @close)
+            ("\"" @open "\"" @close)
+        "#})
+        .unwrap(),
+    );
+
+    let text = indoc! {r#"
+        fn main() {
+            let s = "(";
+            foo(bar(1), [2]);
+            if true {
+                baz();
+            }
+        }
+    "#};
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+    cx.run_until_parked();
+
+    editor.update_in(cx, |editor, window, cx| {
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        let brackets = editor
+            .rainbow_brackets(cx)
+            .into_iter()
+            .map(|(range, depth)| {
+                let start = range.start.to_point(&snapshot);
+                let text = snapshot.text_for_range(range).collect::<String>();
+                ((start.row, start.column), text, depth)
+            })
+            .sorted()
+            .collect::<Vec<_>>();
+        // Quotes and pairs missing from the bracket config are not colored.
+        assert_eq!(
+            brackets,
+            vec![
+                ((0, 7), "(".to_string(), 0),
+                ((0, 8), ")".to_string(), 0),
+                ((0, 10), "{".to_string(), 0),
+                ((2, 7), "(".to_string(), 1),
+                ((2, 11), "(".to_string(), 2),
+                ((2, 13), ")".to_string(), 2),
+                ((2, 19), ")".to_string(), 1),
+                ((3, 12), "{".to_string(), 1),
+                ((4, 11), "(".to_string(), 2),
+                ((4, 12), ")".to_string(), 2),
+                ((5, 4), "}".to_string(), 1),
+                ((6, 0), "}".to_string(), 0),
+            ]
+        );
+
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(4, 8)..Point::new(4, 8)])
+        });
+        let snapshot = editor.snapshot(window, cx);
+        assert_eq!(
+            snapshot.bracket_guide(editor, cx),
+            Some(crate::bracket_colorization::BracketGuide {
+                start_row: MultiBufferRow(4),
+                end_row: MultiBufferRow(4),
+                position: Point::new(3, 4),
+                depth: 1,
+            })
+        );
+
+        // Pairs without rows between their brackets are skipped.
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(2, 9)..Point::new(2, 9)])
+        });
+        let snapshot = editor.snapshot(window, cx);
+        assert_eq!(
+            snapshot.bracket_guide(editor, cx),
+            Some(crate::bracket_colorization::BracketGuide {
+                start_row: MultiBufferRow(1),
+                end_row: MultiBufferRow(5),
+                position: Point::new(0, 0),
+                depth: 0,
+            })
+        );
+
+        // The visible range is measured in display rows, so brackets below a fold are colored.
+        editor.set_visible_line_count(2., window, cx);
+        editor.fold_ranges(vec![Point::new(0, 11)..Point::new(5, 5)], false, window, cx);
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        assert!(
+            editor
+                .rainbow_brackets(cx)
+                .iter()
+                .any(|(range, _)| range.start.to_point(&snapshot) == Point::new(6, 0))
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_select_larger_syntax_node_for_cursor_at_end(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -21515,6 +21782,69 @@ async fn test_goto_definition_no_fallback(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_peek_references(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            references_provider: Some(lsp::OneOf::Left(true)),
+            ..lsp::ServerCapabilities::default()
+        },
+        cx,
+    )
+    .await;
+
+    let original_state = r#"fn one() {
+            let mut a = two();
+        }
+
+        fn ˇtwo() {}"#
+        .unindent();
+    cx.set_state(&original_state);
+    cx.lsp
+        .set_request_handler::<lsp::request::References, _, _>(move |params, _| async move {
+            Ok(Some(vec![
+                lsp::Location {
+                    uri: params.text_document_position.text_document.uri.clone(),
+                    range: lsp::Range::new(lsp::Position::new(1, 16), lsp::Position::new(1, 19)),
+                },
+                lsp::Location {
+                    uri: params.text_document_position.text_document.uri,
+                    range: lsp::Range::new(lsp::Position::new(4, 3), lsp::Position::new(4, 6)),
+                },
+            ]))
+        });
+
+    let navigated = cx
+        .update_editor(|editor, window, cx| editor.peek_references(&PeekReferences, window, cx))
+        .await
+        .expect("Failed to peek references");
+    assert_eq!(navigated, Navigated::Yes);
+    cx.assert_editor_state(&original_state);
+
+    let editors = cx.update_workspace(|workspace, _, cx| {
+        workspace.items_of_type::<Editor>(cx).collect::<Vec<_>>()
+    });
+    cx.update_editor(|editor, window, cx| {
+        assert_eq!(editors.len(), 1, "Peeking should not open a new editor");
+        let peek_editor = editor
+            .peek_editor()
+            .expect("Should have opened a peek view");
+        assert!(peek_editor.focus_handle(cx).is_focused(window));
+        assert_eq!(
+            peek_editor.read(cx).text(cx),
+            "fn one() {\n    let mut a = two();\n}\n\nfn two() {}"
+        );
+    });
+
+    cx.update_editor(|editor, window, cx| editor.cancel(&Cancel, window, cx));
+    cx.update_editor(|editor, window, cx| {
+        assert!(editor.peek_editor().is_none());
+        assert!(editor.focus_handle(cx).is_focused(window));
+    });
+    cx.assert_editor_state(&original_state);
+}
+
 #[gpui::test]
 async fn test_find_all_references_editor_reuse(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -23027,6 +23357,181 @@ async fn test_breakpoint_enabling_and_disabling(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_bookmarks(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("main.rs")), cx)
+        })
+        .await
+        .unwrap();
+
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer, cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+    let bookmarked_rows = |cx: &mut VisualTestContext| {
+        editor.update(cx, |editor, cx| {
+            editor
+                .bookmark_store()
+                .unwrap()
+                .read(cx)
+                .all_serialized_bookmarks(cx)
+                .into_values()
+                .flatten()
+                .map(|bookmark| bookmark.row)
+                .collect::<Vec<_>>()
+        })
+    };
+    let cursor_row = |cx: &mut VisualTestContext| {
+        editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head().row
+        })
+    };
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        editor.move_to_end(&MoveToEnd, window, cx);
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmarked_rows(cx), vec![0, 3]);
+
+    // Bookmarks follow the lines they were set on.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.move_to_beginning(&MoveToBeginning, window, cx);
+        editor.handle_input("// header\n", window, cx);
+    });
+    assert_eq!(bookmarked_rows(cx), vec![1, 4]);
+
+    // Navigation wraps around the ends of the editor.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.move_to_beginning(&MoveToBeginning, window, cx);
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+    });
+    assert_eq!(cursor_row(cx), 1);
+    editor.update_in(cx, |editor, window, cx| {
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+    });
+    assert_eq!(cursor_row(cx), 4);
+    editor.update_in(cx, |editor, window, cx| {
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+    });
+    assert_eq!(cursor_row(cx), 1);
+    editor.update_in(cx, |editor, window, cx| {
+        editor.go_to_previous_bookmark(&actions::GoToPreviousBookmark, window, cx);
+    });
+    assert_eq!(cursor_row(cx), 4);
+
+    // Toggling a bookmarked line removes its bookmark.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmarked_rows(cx), vec![1]);
+}
+
+#[gpui::test]
+async fn test_keyboard_macros(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        let default_key_bindings = settings::KeymapFile::load_asset_allow_partial_failure(
+            "keymaps/default-linux.json",
+            cx,
+        )
+        .unwrap();
+        cx.bind_keys(default_key_bindings);
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        ˇone
+        two
+        three
+        four
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.start_recording_macro(&StartRecordingMacro, window, cx);
+    });
+    cx.simulate_input("- ");
+    cx.simulate_keystrokes("end");
+    cx.simulate_input(";");
+    cx.simulate_keystrokes("down home");
+    cx.update_editor(|editor, window, cx| {
+        editor.stop_recording_macro(&StopRecordingMacro, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        - one;
+        ˇtwo
+        three
+        four
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.play_macro(
+            &PlayMacro {
+                name: None,
+                times: Some(2),
+            },
+            window,
+            cx,
+        );
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        - one;
+        - two;
+        - three;
+        ˇfour
+    "});
+
+    // The whole playback is undone at once.
+    cx.update_editor(|editor, window, cx| editor.undo(&Undo, window, cx));
+    cx.assert_editor_state(indoc! {"
+        - one;
+        ˇtwo
+        three
+        four
+    "});
+
+    // Playing on each line runs the macro with a cursor at the start of every selected line.
+    cx.set_state(indoc! {"
+        «one
+        two
+        threeˇ»
+        four
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.play_macro_on_each_line(&PlayMacroOnEachLine { name: None }, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        - one;
+        ˇ- two;
+        ˇ- three;
+        ˇfour
+    "});
+}
+
 #[gpui::test]
 async fn test_rename_with_duplicate_edits(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -10,6 +10,7 @@ use crate::{
     PageUp, PhantomBreakpointIndicator, Point, RowExt, RowRangeExt, SelectPhase,
     SelectedTextHighlight, Selection, SelectionDragState, SoftWrap, StickyHeaderExcerpt, ToPoint,
     ToggleFold, ToggleFoldAll,
+    bracket_colorization::rainbow_bracket_color,
     code_context_menus::{CodeActionsMenu, MENU_ASIDE_MAX_WIDTH, MENU_ASIDE_MIN_WIDTH, MENU_GAP},
     display_map::{
         Block, BlockContext, BlockStyle, ChunkRendererId, DisplaySnapshot, EditorMargins,
@@ -42,16 +43,16 @@ use git::{
     status::FileStatus,
 };
 use gpui::{
-    Action, Along, AnyElement, App, AppContext, AvailableSpace, Axis as ScrollbarAxis, BorderStyle,
-    Bounds, ClickEvent, ClipboardItem, ContentMask, Context, Corner, Corners, CursorStyle,
-    DispatchPhase, Edges, Element, ElementInputHandler, Entity, Focusable as _, FontId,
-    GlobalElementId, Hitbox, HitboxBehavior, Hsla, InteractiveElement, IntoElement, IsZero,
-    KeybindingKeystroke, Length, Modifiers, ModifiersChangedEvent, MouseButton, MouseClickEvent,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta,
-    ScrollHandle, ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement,
-    Style, Styled, TextRun, TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill,
-    linear_color_stop, linear_gradient, outline, point, px, quad, relative, size, solid_background,
-    transparent_black,
+    AccessibilityAction, AccessibilityActionData, AccessibilityNode, AccessibilityRole, Action,
+    Along, AnyElement, App, AppContext, AvailableSpace, Axis as ScrollbarAxis, BorderStyle, Bounds,
+    ClickEvent, ClipboardItem, ContentMask, Context, Corner, Corners, CursorStyle, DispatchPhase,
+    Edges, Element, ElementInputHandler, Entity, Focusable as _, FontId, GlobalElementId, Hitbox,
+    HitboxBehavior, Hsla, InteractiveElement, IntoElement, IsZero, KeybindingKeystroke, Length,
+    Modifiers, ModifiersChangedEvent, MouseButton, MouseClickEvent, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta, ScrollHandle, ScrollWheelEvent,
+    ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled, StyledText, TextRun,
+    TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill, linear_color_stop,
+    linear_gradient, outline, point, px, quad, relative, size, solid_background, transparent_black,
 };
 use itertools::Itertools;
 use language::{IndentGuideSettings, language_settings::ShowWhitespaceSetting};
@@ -63,6 +64,7 @@ use multi_buffer::{
 
 use project::{
     Entry, ProjectPath,
+    bookmark_store::Bookmark,
     debugger::breakpoint_store::{Breakpoint, BreakpointSessionState},
     project_settings::ProjectSettings,
 };
@@ -428,6 +430,16 @@ impl EditorElement {
                 .go_to_type_definition_split(action, window, cx)
                 .detach_and_log_err(cx);
         });
+        register_action(editor, window, |editor, action, window, cx| {
+            editor
+                .peek_definition(action, window, cx)
+                .detach_and_log_err(cx);
+        });
+        register_action(editor, window, |editor, action, window, cx| {
+            editor
+                .peek_references(action, window, cx)
+                .detach_and_log_err(cx);
+        });
         register_action(editor, window, Editor::open_url);
         register_action(editor, window, Editor::open_selected_filename);
         register_action(editor, window, Editor::fold);
@@ -596,6 +608,15 @@ impl EditorElement {
         register_action(editor, window, Editor::edit_log_breakpoint);
         register_action(editor, window, Editor::enable_breakpoint);
         register_action(editor, window, Editor::disable_breakpoint);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::go_to_next_bookmark);
+        register_action(editor, window, Editor::go_to_previous_bookmark);
+        register_action(editor, window, Editor::start_recording_macro);
+        register_action(editor, window, Editor::stop_recording_macro);
+        register_action(editor, window, Editor::toggle_macro_recording);
+        register_action(editor, window, Editor::play_macro);
+        register_action(editor, window, Editor::play_macro_on_each_line);
+        register_action(editor, window, Editor::save_macro);
         if editor.read(cx).enable_wrap_selections_in_tag(cx) {
             register_action(editor, window, Editor::wrap_selections_in_tag);
         }
@@ -2747,6 +2768,41 @@ impl EditorElement {
         )
     }
 
+    fn layout_bracket_guide(
+        &self,
+        content_origin: gpui::Point<Pixels>,
+        text_origin: gpui::Point<Pixels>,
+        scroll_pixel_position: gpui::Point<ScrollPixelOffset>,
+        line_height: Pixels,
+        snapshot: &EditorSnapshot,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<BracketGuideLayout> {
+        let guide = snapshot.bracket_guide(self.editor.read(cx), cx)?;
+        let column = guide.position.to_display_point(snapshot).column();
+        let start_x = Pixels::from(
+            ScrollOffset::from(content_origin.x + self.column_pixels(column as usize, window))
+                - scroll_pixel_position.x,
+        );
+        if start_x < text_origin.x {
+            return None;
+        }
+
+        let (offset_y, length) = Self::calculate_indent_guide_bounds(
+            guide.start_row..guide.end_row,
+            line_height,
+            snapshot,
+        );
+        let start_y =
+            Pixels::from(ScrollOffset::from(content_origin.y) + offset_y - scroll_pixel_position.y);
+
+        Some(BracketGuideLayout {
+            origin: point(start_x, start_y),
+            length,
+            color: rainbow_bracket_color(guide.depth, cx.theme().colors()),
+        })
+    }
+
     fn layout_wrap_guides(
         &self,
         em_advance: Pixels,
@@ -2907,6 +2963,61 @@ impl EditorElement {
         })
     }
 
+    fn layout_bookmarks(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_position: gpui::Point<ScrollOffset>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
+        snapshot: &EditorSnapshot,
+        bookmarks: HashMap<DisplayRow, (Anchor, Bookmark)>,
+        row_infos: &[RowInfo],
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            bookmarks
+                .into_iter()
+                .filter_map(|(display_row, (anchor, bookmark))| {
+                    if !range.contains(&display_row)
+                        || row_infos
+                            .get((display_row.0.saturating_sub(range.start.0)) as usize)
+                            .is_some_and(|row_info| {
+                                row_info.expand_info.is_some()
+                                    || row_info
+                                        .diff_status
+                                        .is_some_and(|status| status.is_deleted())
+                            })
+                    {
+                        return None;
+                    }
+
+                    let row =
+                        MultiBufferRow(DisplayPoint::new(display_row, 0).to_point(snapshot).row);
+                    if snapshot.is_line_folded(row) {
+                        return None;
+                    }
+
+                    let button = editor.render_bookmark(anchor, display_row, &bookmark, cx);
+                    let button = prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_position,
+                        gutter_hitbox,
+                        display_hunks,
+                        window,
+                        cx,
+                    );
+                    Some(button)
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_run_indicators(
         &self,
@@ -2919,6 +3030,7 @@ impl EditorElement {
         display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
         snapshot: &EditorSnapshot,
         breakpoints: &mut HashMap<DisplayRow, (Anchor, Breakpoint, Option<BreakpointSessionState>)>,
+        bookmarks: &mut HashMap<DisplayRow, (Anchor, Bookmark)>,
         window: &mut Window,
         cx: &mut App,
     ) -> Vec<AnyElement> {
@@ -2993,11 +3105,24 @@ impl EditorElement {
                         return None;
                     }
 
+                    let test_status = editor.project().and_then(|project| {
+                        let (buffer_snapshot, line) = snapshot
+                            .buffer_snapshot()
+                            .buffer_line_for_row(multibuffer_row)?;
+                        project
+                            .read(cx)
+                            .test_results()
+                            .read(cx)
+                            .status_for_row(buffer_snapshot, line.start.row)
+                    });
+                    // Run indicators take the place of bookmarks on their rows.
+                    bookmarks.remove(&display_row);
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
                         display_row,
                         breakpoints.remove(&display_row),
+                        test_status,
                         cx,
                     );
 
@@ -4442,6 +4567,115 @@ impl EditorElement {
         header
     }
 
+    fn layout_sticky_scopes(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<ScrollOffset>,
+        reserved_rows: u32,
+        line_height: Pixels,
+        em_width: Pixels,
+        right_margin: Pixels,
+        gutter_dimensions: &GutterDimensions,
+        hitbox: &Hitbox,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<AnyElement> {
+        let sticky_scroll = EditorSettings::get_global(cx).sticky_scroll;
+        if !sticky_scroll.enabled || !snapshot.mode.is_full() {
+            return None;
+        }
+
+        let scopes = snapshot.sticky_scopes(
+            DisplayRow(scroll_position.y as u32),
+            reserved_rows,
+            sticky_scroll.max_depth as usize,
+            &self.style.syntax,
+        );
+        if scopes.is_empty() {
+            return None;
+        }
+
+        let colors = cx.theme().colors();
+        let text_style = self.style.text.clone();
+        let show_line_numbers = snapshot
+            .show_line_numbers
+            .unwrap_or_else(|| EditorSettings::get_global(cx).gutter.line_numbers);
+        let gutter_width = gutter_dimensions.full_width();
+        let available_width = hitbox.bounds.size.width - right_margin;
+
+        let rows = scopes.into_iter().enumerate().map(|(ix, scope)| {
+            let start = scope.item.range.start;
+            let line_number = snapshot
+                .buffer_snapshot()
+                .buffer_for_excerpt(start.excerpt_id)
+                .map(|buffer| language::ToPoint::to_point(&start.text_anchor, buffer).row + 1)
+                .unwrap_or(scope.start.row + 1);
+            let text = StyledText::new(scope.item.text.clone())
+                .with_default_highlights(&text_style, scope.item.highlight_ranges.clone());
+            let line_offset_from_top = reserved_rows + ix as u32;
+
+            h_flex()
+                .id(("sticky-scope", ix))
+                .w(available_width)
+                .h(line_height)
+                .bg(colors.editor_background)
+                .hover(|style| style.bg(colors.editor_active_line_background))
+                .cursor_pointer()
+                .child(
+                    h_flex()
+                        .flex_none()
+                        .w(gutter_width)
+                        .pr(gutter_dimensions.right_padding)
+                        .justify_end()
+                        .when(show_line_numbers, |el| {
+                            el.child(
+                                div()
+                                    .text_color(colors.editor_line_number)
+                                    .child(line_number.to_string()),
+                            )
+                        }),
+                )
+                .child(
+                    div()
+                        .pl(gutter_dimensions.margin + em_width * scope.start.column as f32)
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .child(text),
+                )
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .on_click(window.listener_for(&self.editor, {
+                    move |editor, e: &ClickEvent, window, cx| {
+                        editor.go_to_sticky_scope(
+                            &scope,
+                            line_offset_from_top,
+                            e.modifiers().secondary(),
+                            window,
+                            cx,
+                        );
+                    }
+                }))
+        });
+
+        let mut element = v_flex()
+            .w(available_width)
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .children(rows)
+            .into_any_element();
+
+        let origin = point(
+            hitbox.origin.x,
+            hitbox.origin.y + line_height * reserved_rows as f32,
+        );
+        let size = size(
+            AvailableSpace::Definite(available_width),
+            AvailableSpace::MinContent,
+        );
+        element.prepaint_as_root(origin, size, window, cx);
+
+        Some(element)
+    }
+
     fn layout_cursor_popovers(
         &self,
         line_height: Pixels,
@@ -5723,6 +5957,18 @@ impl EditorElement {
         })
     }
 
+    fn paint_bracket_guide(&mut self, layout: &EditorLayout, window: &mut Window) {
+        if let Some(bracket_guide) = &layout.bracket_guide {
+            window.paint_quad(fill(
+                Bounds {
+                    origin: bracket_guide.origin,
+                    size: size(px(1.), bracket_guide.length),
+                },
+                bracket_guide.color,
+            ));
+        }
+    }
+
     fn paint_indent_guides(
         &mut self,
         layout: &mut EditorLayout,
@@ -6049,6 +6295,10 @@ impl EditorElement {
                 }
             });
 
+            for bookmark in layout.bookmarks.iter_mut() {
+                bookmark.paint(window, cx);
+            }
+
             for breakpoint in layout.breakpoints.iter_mut() {
                 breakpoint.paint(window, cx);
             }
@@ -6821,6 +7071,68 @@ impl EditorElement {
                     ..cmp::min(range.end.row().next_row(), end_row)
             };
 
+            let line_layouts = &layout.position_map.line_layouts;
+            if row_range
+                .iter_rows()
+                .any(|row| line_layouts[row.minus(start_row) as usize].has_rtl())
+            {
+                // Right-to-left text makes a range visually discontiguous within a line,
+                // so each of its extents is painted on its own.
+                for row in row_range.iter_rows() {
+                    let line_layout = &line_layouts[row.minus(start_row) as usize];
+                    let start_column = if row == range.start.row() {
+                        range.start.column() as usize
+                    } else {
+                        0
+                    };
+                    let end_column = if row == range.end.row() {
+                        range.end.column() as usize
+                    } else {
+                        line_layout.len
+                    };
+                    let mut x_ranges =
+                        line_layout.x_ranges_for_index_range(start_column..end_column);
+                    if row != range.end.row() {
+                        let line_end = line_layout.width..line_layout.width + line_end_overshoot;
+                        match x_ranges.last_mut() {
+                            Some(last) if last.end == line_end.start => last.end = line_end.end,
+                            _ => x_ranges.push(line_end),
+                        }
+                    }
+
+                    let start_y = layout.content_origin.y
+                        + Pixels::from(
+                            (row.as_f64() - layout.position_map.scroll_position.y)
+                                * ScrollOffset::from(layout.position_map.line_height),
+                        );
+                    let to_content_x = |x: Pixels| {
+                        layout.content_origin.x
+                            + Pixels::from(
+                                ScrollPixelOffset::from(x)
+                                    - layout.position_map.scroll_pixel_position.x,
+                            )
+                    };
+                    for x_range in x_ranges {
+                        HighlightedRange {
+                            color,
+                            line_height: layout.position_map.line_height,
+                            corner_radius,
+                            start_y,
+                            lines: vec![HighlightedRangeLine {
+                                start_x: to_content_x(x_range.start),
+                                end_x: to_content_x(x_range.end),
+                            }],
+                        }
+                        .paint(
+                            fill,
+                            layout.position_map.text_hitbox.bounds,
+                            window,
+                        );
+                    }
+                }
+                return;
+            }
+
             let highlighted_range = HighlightedRange {
                 color,
                 line_height: layout.position_map.line_height,
@@ -7143,6 +7455,78 @@ impl EditorElement {
         }
     }
 
+    /// Describes the editor to assistive technologies. Only the visible lines are
+    /// exposed as the value, so that large buffers stay cheap to describe, and the
+    /// contents are left out entirely while no assistive technology is active.
+    fn accessibility_node(
+        &self,
+        layout: &EditorLayout,
+        window: &Window,
+        cx: &mut App,
+    ) -> AccessibilityNode {
+        if layout.mode.is_minimap() {
+            return AccessibilityNode::new(AccessibilityRole::Group).label("Minimap");
+        }
+
+        let role = if layout.mode.is_single_line() {
+            AccessibilityRole::TextInput
+        } else {
+            AccessibilityRole::MultilineTextInput
+        };
+        let mut node = AccessibilityNode::new(role).focus_handle(&self.editor.focus_handle(cx));
+        if !window.is_accessibility_active() {
+            return node;
+        }
+        let snapshot = &layout.position_map.snapshot;
+        if let Some(placeholder) = snapshot.placeholder_text() {
+            node = node.description(placeholder);
+        }
+        // Redacted text must not be read out loud either.
+        if !layout.redacted_ranges.is_empty() {
+            return node;
+        }
+
+        let visible_rows = &layout.visible_display_row_range;
+        let visible_start = DisplayPoint::new(visible_rows.start, 0)
+            .to_offset(&snapshot.display_snapshot, Bias::Left);
+        let visible_end = snapshot
+            .clip_point(DisplayPoint::new(visible_rows.end, 0), Bias::Left)
+            .min(snapshot.max_point())
+            .to_offset(&snapshot.display_snapshot, Bias::Right);
+        let text = snapshot
+            .buffer_snapshot
+            .text_for_range(visible_start..visible_end)
+            .collect::<String>();
+
+        let selection = self
+            .editor
+            .update(cx, |editor, cx| editor.selections.newest::<usize>(cx));
+        let clamp = |offset: usize| offset.clamp(visible_start, visible_end) - visible_start;
+        let selection = if selection.reversed {
+            clamp(selection.end)..clamp(selection.start)
+        } else {
+            clamp(selection.start)..clamp(selection.end)
+        };
+
+        let editor = self.editor.downgrade();
+        node.value(text).text_selection(selection).on_action(
+            AccessibilityAction::SetTextSelection,
+            move |request, window, cx| {
+                let Some(AccessibilityActionData::TextSelection(range)) = &request.data else {
+                    return;
+                };
+                let range = range.start + visible_start..range.end + visible_start;
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.change_selections(Default::default(), window, cx, |s| {
+                            s.select_ranges([range])
+                        });
+                    })
+                    .ok();
+            },
+        )
+    }
+
     fn paint_scroll_wheel_listener(
         &mut self,
         layout: &EditorLayout,
@@ -8241,6 +8625,57 @@ impl LineWithInvisibles {
         fragment_start_x
     }
 
+    /// The horizontal extents covered by the given range of columns, ordered from left
+    /// to right. There can be several of them when the line contains right-to-left text.
+    pub fn x_ranges_for_index_range(&self, range: Range<usize>) -> SmallVec<[Range<Pixels>; 1]> {
+        let mut x_ranges: SmallVec<[Range<Pixels>; 1]> = SmallVec::new();
+        let mut push_x_range = |x_range: Range<Pixels>| match x_ranges.last_mut() {
+            Some(last) if last.end == x_range.start => last.end = x_range.end,
+            _ => x_ranges.push(x_range),
+        };
+
+        let mut fragment_start_x = Pixels::ZERO;
+        let mut fragment_start_index = 0;
+        for fragment in &self.fragments {
+            let (fragment_len, fragment_width) = match fragment {
+                LineFragment::Text(shaped_line) => (shaped_line.len, shaped_line.width),
+                LineFragment::Element { len, size, .. } => (*len, size.width),
+            };
+            let start = range.start.max(fragment_start_index);
+            let end = range.end.min(fragment_start_index + fragment_len);
+            if start < end {
+                match fragment {
+                    LineFragment::Text(shaped_line) => {
+                        for x_range in shaped_line.x_ranges_for_index_range(
+                            start - fragment_start_index..end - fragment_start_index,
+                        ) {
+                            push_x_range(
+                                fragment_start_x + x_range.start..fragment_start_x + x_range.end,
+                            );
+                        }
+                    }
+                    LineFragment::Element { .. } => {
+                        push_x_range(fragment_start_x..fragment_start_x + fragment_width)
+                    }
+                }
+            }
+            fragment_start_x += fragment_width;
+            fragment_start_index += fragment_len;
+        }
+
+        if x_ranges.is_empty() {
+            let x = self.x_for_index(range.start);
+            x_ranges.push(x..x);
+        }
+        x_ranges
+    }
+
+    pub fn has_rtl(&self) -> bool {
+        self.fragments.iter().any(
+            |fragment| matches!(fragment, LineFragment::Text(shaped_line) if shaped_line.has_rtl()),
+        )
+    }
+
     pub fn index_for_x(&self, x: Pixels) -> Option<usize> {
         let mut fragment_start_x = Pixels::ZERO;
         let mut fragment_start_index = 0;
@@ -8271,6 +8706,39 @@ impl LineWithInvisibles {
         None
     }
 
+    pub fn closest_index_for_x(&self, x: Pixels) -> usize {
+        let mut fragment_start_x = Pixels::ZERO;
+        let mut fragment_start_index = 0;
+
+        for fragment in &self.fragments {
+            match fragment {
+                LineFragment::Text(shaped_line) => {
+                    let fragment_end_x = fragment_start_x + shaped_line.width;
+                    if x < fragment_end_x {
+                        return fragment_start_index
+                            + shaped_line.closest_index_for_x(x - fragment_start_x);
+                    }
+                    fragment_start_x = fragment_end_x;
+                    fragment_start_index += shaped_line.len;
+                }
+                LineFragment::Element { len, size, .. } => {
+                    let fragment_end_x = fragment_start_x + size.width;
+                    if x < fragment_end_x {
+                        return if x - fragment_start_x < size.width / 2. {
+                            fragment_start_index
+                        } else {
+                            fragment_start_index + len
+                        };
+                    }
+                    fragment_start_index += len;
+                    fragment_start_x = fragment_end_x;
+                }
+            }
+        }
+
+        fragment_start_index
+    }
+
     pub fn font_id_for_index(&self, index: usize) -> Option<FontId> {
         let mut fragment_start_index = 0;
 
@@ -8821,6 +9289,10 @@ impl Element for EditorElement {
                     let mut breakpoint_rows = self.editor.update(cx, |editor, cx| {
                         editor.active_breakpoints(start_row..end_row, window, cx)
                     });
+                    let mut bookmark_rows =
+                        self.editor
+                            .read(cx)
+                            .active_bookmarks(start_row..end_row, &snapshot, cx);
                     for (display_row, (_, bp, state)) in &breakpoint_rows {
                         if bp.is_enabled() && state.is_none_or(|s| s.verified) {
                             active_rows.entry(*display_row).or_default().breakpoint = true;
@@ -9065,6 +9537,30 @@ impl Element for EditorElement {
                         })
                     });
 
+                    let sticky_scopes = (!is_minimap)
+                        .then(|| {
+                            let reserved_rows = if sticky_buffer_header.is_some() {
+                                FILE_HEADER_HEIGHT
+                            } else {
+                                0
+                            };
+                            window.with_element_namespace("sticky_scopes", |window| {
+                                self.layout_sticky_scopes(
+                                    &snapshot,
+                                    scroll_position,
+                                    reserved_rows,
+                                    line_height,
+                                    em_width,
+                                    right_margin,
+                                    &gutter_dimensions,
+                                    &hitbox,
+                                    window,
+                                    cx,
+                                )
+                            })
+                        })
+                        .flatten();
+
                     let start_buffer_row =
                         MultiBufferRow(start_anchor.to_point(&snapshot.buffer_snapshot()).row);
                     let end_buffer_row =
@@ -9112,6 +9608,15 @@ impl Element for EditorElement {
                         window,
                         cx,
                     );
+                    let bracket_guide = self.layout_bracket_guide(
+                        content_origin,
+                        text_hitbox.origin,
+                        scroll_pixel_position,
+                        line_height,
+                        &snapshot,
+                        window,
+                        cx,
+                    );
 
                     let crease_trailers =
                         window.with_element_namespace("crease_trailers", |window| {
@@ -9345,6 +9850,7 @@ impl Element for EditorElement {
                             &display_hunks,
                             &snapshot,
                             &mut breakpoint_rows,
+                            &mut bookmark_rows,
                             window,
                             cx,
                         )
@@ -9355,6 +9861,22 @@ impl Element for EditorElement {
                     let show_breakpoints = snapshot
                         .show_breakpoints
                         .unwrap_or(gutter_settings.breakpoints);
+                    if show_breakpoints {
+                        bookmark_rows.retain(|row, _| !breakpoint_rows.contains_key(row));
+                    }
+                    let bookmarks = self.layout_bookmarks(
+                        line_height,
+                        start_row..end_row,
+                        scroll_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &display_hunks,
+                        &snapshot,
+                        bookmark_rows,
+                        &row_infos,
+                        window,
+                        cx,
+                    );
                     let breakpoints = if show_breakpoints {
                         self.layout_breakpoints(
                             line_height,
@@ -9543,6 +10065,7 @@ impl Element for EditorElement {
                         visible_display_row_range: start_row..end_row,
                         wrap_guides,
                         indent_guides,
+                        bracket_guide,
                         hitbox,
                         gutter_hitbox,
                         display_hunks,
@@ -9570,11 +10093,13 @@ impl Element for EditorElement {
                         mouse_context_menu,
                         test_indicators,
                         breakpoints,
+                        bookmarks,
                         crease_toggles,
                         crease_trailers,
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                         expand_toggles,
                     }
                 })
@@ -9614,42 +10139,52 @@ impl Element for EditorElement {
             ..Default::default()
         };
         let rem_size = self.rem_size(cx);
-        window.with_rem_size(rem_size, |window| {
-            window.with_text_style(Some(text_style), |window| {
-                window.with_content_mask(Some(ContentMask { bounds }), |window| {
-                    self.paint_mouse_listeners(layout, window, cx);
-                    self.paint_background(layout, window, cx);
-                    self.paint_indent_guides(layout, window, cx);
+        let accessibility_node = self.accessibility_node(layout, window, cx);
+        window.with_accessibility_node(None, bounds, accessibility_node, |window| {
+            window.with_rem_size(rem_size, |window| {
+                window.with_text_style(Some(text_style), |window| {
+                    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                        self.paint_mouse_listeners(layout, window, cx);
+                        self.paint_background(layout, window, cx);
+                        self.paint_indent_guides(layout, window, cx);
+                        self.paint_bracket_guide(layout, window);
+
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_blamed_display_rows(layout, window, cx);
+                            self.paint_line_numbers(layout, window, cx);
+                        }
 
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_blamed_display_rows(layout, window, cx);
-                        self.paint_line_numbers(layout, window, cx);
-                    }
+                        self.paint_text(layout, window, cx);
 
-                    self.paint_text(layout, window, cx);
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_gutter_highlights(layout, window, cx);
+                            self.paint_gutter_indicators(layout, window, cx);
+                        }
 
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_gutter_highlights(layout, window, cx);
-                        self.paint_gutter_indicators(layout, window, cx);
-                    }
+                        if !layout.blocks.is_empty() {
+                            window.with_element_namespace("blocks", |window| {
+                                self.paint_blocks(layout, window, cx);
+                            });
+                        }
+
+                        window.with_element_namespace("sticky_scopes", |window| {
+                            if let Some(mut sticky_scopes) = layout.sticky_scopes.take() {
+                                sticky_scopes.paint(window, cx)
+                            }
+                        });
 
-                    if !layout.blocks.is_empty() {
                         window.with_element_namespace("blocks", |window| {
-                            self.paint_blocks(layout, window, cx);
+                            if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
+                                sticky_header.paint(window, cx)
+                            }
                         });
-                    }
 
-                    window.with_element_namespace("blocks", |window| {
-                        if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
-                            sticky_header.paint(window, cx)
-                        }
+                        self.paint_minimap(layout, window, cx);
+                        self.paint_scrollbars(layout, window, cx);
+                        self.paint_edit_prediction_popover(layout, window, cx);
+                        self.paint_mouse_context_menu(layout, window, cx);
                     });
-
-                    self.paint_minimap(layout, window, cx);
-                    self.paint_scrollbars(layout, window, cx);
-                    self.paint_edit_prediction_popover(layout, window, cx);
-                    self.paint_mouse_context_menu(layout, window, cx);
-                });
+                })
             })
         })
     }
@@ -9725,6 +10260,7 @@ pub struct EditorLayout {
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
+    bracket_guide: Option<BracketGuideLayout>,
     visible_display_row_range: Range<DisplayRow>,
     active_rows: BTreeMap<DisplayRow, LineHighlightSpec>,
     highlighted_rows: BTreeMap<DisplayRow, LineHighlight>,
@@ -9744,6 +10280,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     test_indicators: Vec<AnyElement>,
     breakpoints: Vec<AnyElement>,
+    bookmarks: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     expand_toggles: Vec<Option<(AnyElement, gpui::Point<Pixels>)>>,
     diff_hunk_controls: Vec<AnyElement>,
@@ -9753,6 +10290,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Option<AnyElement>,
     document_colors: Option<(DocumentColorsRenderMode, Vec<(Range<DisplayPoint>, Hsla)>)>,
 }
 
@@ -10255,7 +10793,11 @@ impl PositionMap {
             .line_layouts
             .get(row as usize - scroll_position.y as usize)
         {
-            if let Some(ix) = line.index_for_x(x) {
+            if line.has_rtl() && x < line.width {
+                // Glyphs in right-to-left text aren't laid out in logical order, so snap
+                // to the caret position that is visually closest.
+                (line.closest_index_for_x(x) as u32, px(0.))
+            } else if let Some(ix) = line.index_for_x(x) {
                 (ix as u32, px(0.))
             } else {
                 (line.len as u32, px(0.).max(x - line.width))
@@ -10326,6 +10868,12 @@ pub struct IndentGuideLayout {
     settings: IndentGuideSettings,
 }
 
+struct BracketGuideLayout {
+    origin: gpui::Point<Pixels>,
+    length: Pixels,
+    color: Hsla,
+}
+
 pub struct CursorLayout {
     origin: gpui::Point<Pixels>,
     block_width: Pixels,
@@ -10698,7 +11246,7 @@ fn compute_auto_height_layout(
 mod tests {
     use super::*;
     use crate::{
-        Editor, MultiBuffer, SelectionEffects,
+        Editor, MoveToEnd, MultiBuffer, SelectAll, SelectionEffects,
         display_map::{BlockPlacement, BlockProperties},
         editor_tests::{init_test, update_test_language_settings},
     };
@@ -10773,6 +11321,93 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_accessibility_node_contents(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple("one\ntwo\nthree", cx);
+            Editor::new(EditorMode::full(), buffer, None, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        cx.run_until_parked();
+        let editor_value = |cx: &mut VisualTestContext| {
+            cx.update(|window, _| {
+                let tree = window.accessibility_tree();
+                let (_, node) = tree
+                    .iter()
+                    .find(|(_, node)| node.role == AccessibilityRole::MultilineTextInput)
+                    .expect("the editor should be in the accessibility tree");
+                node.value.clone()
+            })
+        };
+
+        // The contents are only described while an assistive technology is active.
+        assert_eq!(editor_value(cx), None);
+        cx.simulate_accessibility_activation(true);
+        assert_eq!(editor_value(cx), Some("one\ntwo\nthree".into()));
+        cx.simulate_accessibility_activation(false);
+        assert_eq!(editor_value(cx), None);
+    }
+
+    #[gpui::test]
+    async fn test_render_selections(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple("one\ntwo\nthree", cx);
+            Editor::new(EditorMode::full(), buffer, None, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+
+        // Samples the middle of the last line, "three", which is selected below.
+        let sample = |cx: &mut VisualTestContext| {
+            let image = cx.render_window();
+            let (position_map, scale_factor) = cx.update(|window, cx| {
+                let position_map = editor.read(cx).last_position_map.clone().unwrap();
+                (position_map, window.scale_factor())
+            });
+            let origin = position_map.text_hitbox.origin;
+            let x = (origin.x + position_map.em_advance * 3.) * scale_factor;
+            let y = (origin.y + position_map.line_height * 2.5) * scale_factor;
+            (
+                image.clone(),
+                image.get_pixel(f32::from(x) as u32, f32::from(y) as u32).0,
+            )
+        };
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.move_to_end(&MoveToEnd, window, cx)
+        });
+        let (image, unselected) = sample(cx);
+        // Rendering is deterministic.
+        assert_eq!(image, cx.render_window());
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.select_all(&SelectAll, window, cx)
+        });
+        let (_, selected) = sample(cx);
+        let selection = cx.update(|_, cx| {
+            let background = cx.theme().colors().editor_background.to_rgb();
+            let selection = cx.theme().players().local().selection.to_rgb();
+            let alpha = selection.a;
+            [
+                selection.r * alpha + background.r * (1. - alpha),
+                selection.g * alpha + background.g * (1. - alpha),
+                selection.b * alpha + background.b * (1. - alpha),
+            ]
+            .map(|channel| (channel * 255.).round() as u8)
+        });
+        assert_ne!(unselected, selected);
+        for (actual, expected) in selected[..3].iter().zip(selection) {
+            assert!(
+                actual.abs_diff(expected) <= 1,
+                "selection rendered as {selected:?}, expected {selection:?}"
+            );
+        }
+    }
+
     #[gpui::test]
     fn test_shape_line_numbers(cx: &mut TestAppContext) {
         init_test(cx, |_| {});
@@ -10991,6 +11626,46 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_point_for_position_in_rtl_text(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        // "abc שלום", where the Hebrew word occupies columns 4..12 and is displayed from
+        // right to left.
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple("abc \u{5e9}\u{5dc}\u{5d5}\u{5dd}", cx);
+            Editor::new(EditorMode::full(), buffer, None, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style),
+        );
+
+        let position_map = &state.position_map;
+        let line = &position_map.line_layouts[0];
+        assert!(line.has_rtl());
+        let column_at = |x: Pixels| {
+            position_map
+                .point_for_position(
+                    position_map.text_hitbox.bounds.origin
+                        + point(x, position_map.line_height / 2.),
+                )
+                .previous_valid
+                .column()
+        };
+
+        // The end of the line is displayed at the left edge of the Hebrew word, and its
+        // first character at the right edge.
+        let hebrew_x_range = line.x_ranges_for_index_range(4..12)[0].clone();
+        assert_eq!(column_at(hebrew_x_range.start + px(1.)), 12);
+        assert_eq!(column_at(hebrew_x_range.end - px(1.)), 4);
+        assert_eq!(column_at(line.x_for_index(2) + px(1.)), 2);
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
@@ -1,7 +1,7 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToDefinitionSplit, GoToTypeDefinition, GoToTypeDefinitionSplit, GotoDefinitionKind, InlayId,
-    Navigated, PointForPosition, SelectPhase,
+    Navigated, PointForPosition, SelectPhase, SemanticsProvider,
     editor_settings::GoToDefinitionFallback,
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
@@ -11,11 +11,11 @@ use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink, Project,
-    ResolveState, ResolvedPath,
+    DocumentLink, HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip,
+    LocationLink, Project, ResolveState, ResolvedPath,
 };
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, rc::Rc, str::FromStr as _};
 use theme::ActiveTheme as _;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
@@ -69,6 +69,7 @@ pub enum HoverLink {
     File(ResolvedPath),
     Text(LocationLink),
     InlayHint(lsp::Location, LanguageServerId),
+    DocumentLink(lsp::Location, LanguageServerId),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -539,7 +540,18 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    if let Some((link_range, link)) =
+                        find_document_link(provider.as_ref(), &buffer, buffer_position, cx).await
+                    {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.read_with(cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -659,6 +671,71 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+/// Finds the language server's document link at the given position, resolving its
+/// target if the server deferred that.
+async fn find_document_link(
+    provider: Option<&Rc<dyn SemanticsProvider>>,
+    buffer: &Entity<language::Buffer>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let provider = provider?;
+    let links = cx
+        .update(|_, cx| provider.document_links(buffer, cx))
+        .ok()??
+        .await
+        .log_err()?;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot()).ok()?;
+    let link = links.into_iter().find(|link| {
+        link.range.start.cmp(&position, &snapshot).is_le()
+            && link.range.end.cmp(&position, &snapshot).is_ge()
+    })?;
+    let link = if link.resolved {
+        link
+    } else {
+        cx.update(|_, cx| provider.resolve_document_link(link, buffer.clone(), cx))
+            .ok()??
+            .await
+            .log_err()?
+    };
+    Some((link.range.clone(), hover_link_for_document_link(&link)?))
+}
+
+/// Turns a document link target into a hover link. `file` URIs are opened in Zed, at
+/// the position given by an `#L<line>,<column>` fragment if there is one; other
+/// schemes are opened externally.
+fn hover_link_for_document_link(link: &DocumentLink) -> Option<HoverLink> {
+    let target = link.target()?.to_string();
+    if !target.starts_with("file:") {
+        return Some(HoverLink::Url(target));
+    }
+
+    let (uri, fragment) = match target.split_once('#') {
+        Some((uri, fragment)) => (uri, Some(fragment)),
+        None => (target.as_str(), None),
+    };
+    let position = fragment
+        .and_then(|fragment| fragment.strip_prefix('L'))
+        .map(|line_and_column| {
+            let mut parts = line_and_column.splitn(2, ',');
+            let line = parts.next().and_then(|line| line.parse::<u32>().ok());
+            let column = parts.next().and_then(|column| column.parse::<u32>().ok());
+            lsp::Position::new(
+                line.unwrap_or(1).saturating_sub(1),
+                column.unwrap_or(1).saturating_sub(1),
+            )
+        })
+        .unwrap_or_default();
+
+    Some(HoverLink::DocumentLink(
+        lsp::Location {
+            uri: lsp::Uri::from_str(uri).log_err()?,
+            range: lsp::Range::new(position, position),
+        },
+        link.server_id,
+    ))
+}
+
 pub(crate) fn find_url(
     buffer: &Entity<language::Buffer>,
     position: text::Anchor,
@@ -931,7 +1008,12 @@ mod tests {
     use gpui::Modifiers;
     use indoc::indoc;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
+    use serde_json::json;
     use settings::InlayHintSettingsContent;
+    use std::sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+    };
     use util::{assert_set_eq, path};
     use workspace::item::Item;
 
@@ -1508,6 +1590,92 @@ mod tests {
         assert_eq!(cx.opened_url(), Some("https://zed.dev/releases".into()));
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            // See docs/rustˇ.md
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            // See «docs/rust.md»
+        "});
+
+        let link_request_count = Arc::new(AtomicUsize::new(0));
+        let mut link_requests =
+            cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>({
+                let link_request_count = link_request_count.clone();
+                move |_, _, _| {
+                    link_request_count.fetch_add(1, atomic::Ordering::Release);
+                    async move {
+                        Ok(Some(vec![lsp::DocumentLink {
+                            range: link_range,
+                            target: None,
+                            tooltip: None,
+                            data: Some(json!({ "id": 1 })),
+                        }]))
+                    }
+                }
+            });
+        let mut resolve_requests = cx
+            .set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+                move |_, link, _| async move {
+                    assert_eq!(link.data, Some(json!({ "id": 1 })));
+                    Ok(lsp::DocumentLink {
+                        target: Some("https://zed.dev/docs/languages/rust".parse().unwrap()),
+                        ..link
+                    })
+                },
+            );
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            // See docs/ruˇst.md
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        link_requests.next().await;
+        resolve_requests.next().await;
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            // See «docs/rust.mdˇ»
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(
+            cx.opened_url(),
+            Some("https://zed.dev/docs/languages/rust".into())
+        );
+        assert_eq!(link_request_count.load(atomic::Ordering::Acquire), 1);
+
+        // Hovering the link again reuses the links of the unchanged buffer.
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::none());
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        resolve_requests.next().await;
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            // See «docs/rust.mdˇ»
+        "});
+        assert_eq!(link_request_count.load(atomic::Ordering::Acquire), 1);
+
+        // Editing the buffer requests them again.
+        cx.update_editor(|editor, window, cx| editor.insert(" ", window, cx));
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::none());
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        link_requests.next().await;
+        cx.background_executor.run_until_parked();
+        assert_eq!(link_request_count.load(atomic::Ordering::Acquire), 2);
+    }
+
     #[gpui::test]
     async fn test_surrounding_filename(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -0,0 +1,484 @@
+use std::{collections::BTreeMap, ops::Range, sync::Arc, time::Instant};
+
+use anyhow::{Context as _, Result, anyhow};
+use fs::Fs;
+use gpui::{
+    Action, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Global,
+    KeystrokeEvent, Task, Window,
+};
+use language::Point;
+use serde::{Deserialize, Serialize};
+use ui::prelude::*;
+use workspace::{ModalView, notifications::NotifyTaskExt as _};
+
+use crate::{
+    Editor, EditorEvent, SelectionEffects,
+    actions::{
+        PlayMacro, PlayMacroOnEachLine, SaveMacro, ShowCharacterPalette, StartRecordingMacro,
+        StopRecordingMacro, ToggleMacroRecording,
+    },
+};
+
+/// A single recorded step of a keyboard macro.
+enum MacroStep {
+    Action {
+        action: Box<dyn Action>,
+        /// The JSON arguments the action was bound with, used when saving the macro.
+        input: Option<SharedString>,
+    },
+    Insertion {
+        text: Arc<str>,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+/// How a macro step is stored in `macros.json`: either an action name, an action name with its
+/// arguments (as in keymaps), or an object with the inserted text.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SerializedMacroStep {
+    Action(String),
+    ActionWithInput(String, serde_json::Value),
+    Insertion {
+        insert: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+#[derive(Default)]
+struct KeyboardMacros {
+    recording: Option<Vec<MacroStep>>,
+    last_recorded: Option<Arc<[MacroStep]>>,
+    replaying: bool,
+}
+
+impl Global for KeyboardMacros {}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.set_global(KeyboardMacros::default());
+
+    cx.observe_keystrokes(|event, _, cx| {
+        if cx.global::<KeyboardMacros>().recording.is_some() {
+            record_keystroke_action(event, cx);
+        }
+    })
+    .detach();
+
+    cx.observe_new(|editor: &mut Editor, _, cx| {
+        if !editor.mode.is_full() {
+            return;
+        }
+        cx.subscribe_self(|_, event: &EditorEvent, cx| {
+            if let EditorEvent::InputHandled {
+                text,
+                utf16_range_to_replace,
+            } = event
+            {
+                KeyboardMacros::record(
+                    MacroStep::Insertion {
+                        text: text.clone(),
+                        utf16_range_to_replace: utf16_range_to_replace.clone(),
+                    },
+                    cx,
+                );
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn record_keystroke_action(event: &KeystrokeEvent, cx: &mut App) {
+    let Some(action) = event.action.as_ref() else {
+        return;
+    };
+    // Only record actions handled by a full editor, so that typing in pickers, the search bar
+    // or other panels doesn't end up in the macro.
+    let in_full_editor = event.context_stack.last().is_some_and(|context| {
+        context.contains("Editor") && context.get("mode").is_some_and(|mode| mode == "full")
+    });
+    if !in_full_editor || !should_record(action.as_ref()) {
+        return;
+    }
+    let input = cx
+        .key_bindings()
+        .borrow()
+        .bindings_for_action(action.as_ref())
+        .find_map(|binding| binding.action_input());
+    KeyboardMacros::record(
+        MacroStep::Action {
+            action: action.boxed_clone(),
+            input,
+        },
+        cx,
+    );
+}
+
+fn should_record(action: &dyn Action) -> bool {
+    let action = action.as_any();
+    // Recording macro actions would make macros replay themselves, and the character palette
+    // would be left open on playback.
+    !(action.is::<StartRecordingMacro>()
+        || action.is::<StopRecordingMacro>()
+        || action.is::<ToggleMacroRecording>()
+        || action.is::<PlayMacro>()
+        || action.is::<PlayMacroOnEachLine>()
+        || action.is::<SaveMacro>()
+        || action.is::<ShowCharacterPalette>())
+}
+
+impl KeyboardMacros {
+    fn record(step: MacroStep, cx: &mut App) {
+        let macros = cx.global_mut::<KeyboardMacros>();
+        if macros.replaying {
+            return;
+        }
+        if let Some(recording) = macros.recording.as_mut() {
+            recording.push(step);
+        }
+    }
+}
+
+impl MacroStep {
+    fn serialize(&self, cx: &App) -> Result<SerializedMacroStep> {
+        match self {
+            MacroStep::Action { action, input } => {
+                if let Some(input) = input {
+                    return Ok(SerializedMacroStep::ActionWithInput(
+                        action.name().to_string(),
+                        serde_json::from_str(input)?,
+                    ));
+                }
+                let rebuilt = cx.build_action(action.name(), None);
+                if rebuilt.is_ok_and(|rebuilt| rebuilt.partial_eq(action.as_ref())) {
+                    Ok(SerializedMacroStep::Action(action.name().to_string()))
+                } else {
+                    Err(anyhow!(
+                        "the arguments of `{}` in the macro can't be saved",
+                        action.name()
+                    ))
+                }
+            }
+            MacroStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Ok(SerializedMacroStep::Insertion {
+                insert: text.to_string(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            }),
+        }
+    }
+
+    fn deserialize(step: SerializedMacroStep, cx: &App) -> Result<Self> {
+        Ok(match step {
+            SerializedMacroStep::Action(name) => MacroStep::Action {
+                action: cx.build_action(&name, None)?,
+                input: None,
+            },
+            SerializedMacroStep::ActionWithInput(name, input) => MacroStep::Action {
+                input: Some(input.to_string().into()),
+                action: cx.build_action(&name, Some(input))?,
+            },
+            SerializedMacroStep::Insertion {
+                insert,
+                utf16_range_to_replace,
+            } => MacroStep::Insertion {
+                text: insert.into(),
+                utf16_range_to_replace,
+            },
+        })
+    }
+}
+
+async fn load_saved_macros(fs: &dyn Fs) -> Result<BTreeMap<String, Vec<SerializedMacroStep>>> {
+    let path = paths::keyboard_macros_file();
+    if !fs.is_file(path).await {
+        return Ok(BTreeMap::default());
+    }
+    let content = fs.load(path).await?;
+    if content.trim().is_empty() {
+        return Ok(BTreeMap::default());
+    }
+    settings::parse_json_with_comments(&content)
+        .with_context(|| format!("parsing {}", path.display()))
+}
+
+fn save_macro(name: String, steps: &[MacroStep], fs: Arc<dyn Fs>, cx: &App) -> Task<Result<()>> {
+    let steps = steps
+        .iter()
+        .map(|step| step.serialize(cx))
+        .collect::<Result<Vec<_>>>();
+    cx.background_spawn(async move {
+        let steps = steps?;
+        let mut macros = load_saved_macros(fs.as_ref()).await?;
+        macros.insert(name, steps);
+        fs.create_dir(paths::config_dir()).await?;
+        fs.atomic_write(
+            paths::keyboard_macros_file().clone(),
+            serde_json::to_string_pretty(&macros)?,
+        )
+        .await
+    })
+}
+
+impl Editor {
+    pub fn is_recording_macro(cx: &App) -> bool {
+        cx.try_global::<KeyboardMacros>()
+            .is_some_and(|macros| macros.recording.is_some())
+    }
+
+    pub fn start_recording_macro(
+        &mut self,
+        _: &StartRecordingMacro,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.default_global::<KeyboardMacros>().recording = Some(Vec::new());
+    }
+
+    pub fn stop_recording_macro(
+        &mut self,
+        _: &StopRecordingMacro,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let macros = cx.default_global::<KeyboardMacros>();
+        if let Some(recording) = macros.recording.take()
+            && !recording.is_empty()
+        {
+            macros.last_recorded = Some(recording.into());
+        }
+    }
+
+    pub fn toggle_macro_recording(
+        &mut self,
+        _: &ToggleMacroRecording,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if Self::is_recording_macro(cx) {
+            self.stop_recording_macro(&StopRecordingMacro, window, cx);
+        } else {
+            self.start_recording_macro(&StartRecordingMacro, window, cx);
+        }
+    }
+
+    pub fn play_macro(&mut self, action: &PlayMacro, window: &mut Window, cx: &mut Context<Self>) {
+        let times = action.times.unwrap_or(1);
+        self.play_macro_internal(action.name.clone(), times, false, window, cx);
+    }
+
+    pub fn play_macro_on_each_line(
+        &mut self,
+        action: &PlayMacroOnEachLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.play_macro_internal(action.name.clone(), 1, true, window, cx);
+    }
+
+    pub fn save_macro(&mut self, _: &SaveMacro, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(steps) = cx
+            .try_global::<KeyboardMacros>()
+            .and_then(|macros| macros.last_recorded.clone())
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let fs = workspace.app_state().fs.clone();
+            workspace.toggle_modal(window, cx, |window, cx| {
+                MacroNameEditor::new(steps, fs, window, cx)
+            });
+        });
+    }
+
+    fn play_macro_internal(
+        &mut self,
+        name: Option<String>,
+        times: u32,
+        on_each_line: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let steps = match name {
+            None => Task::ready(
+                cx.try_global::<KeyboardMacros>()
+                    .and_then(|macros| macros.last_recorded.clone())
+                    .context("no keyboard macro has been recorded"),
+            ),
+            Some(name) => {
+                let Some(project) = self.project.as_ref() else {
+                    return;
+                };
+                let fs = project.read(cx).fs().clone();
+                cx.spawn(async move |_, cx| {
+                    let mut macros = load_saved_macros(fs.as_ref()).await?;
+                    let steps = macros
+                        .remove(&name)
+                        .with_context(|| format!("no keyboard macro named `{name}`"))?;
+                    cx.update(|cx| {
+                        steps
+                            .into_iter()
+                            .map(|step| MacroStep::deserialize(step, cx))
+                            .collect::<Result<Arc<[_]>>>()
+                    })?
+                })
+            }
+        };
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let steps = steps.await?;
+            editor.update_in(cx, |editor, window, cx| {
+                if on_each_line {
+                    editor.place_cursors_at_line_starts(window, cx);
+                }
+                editor.replay_macro(steps, times, window, cx);
+            })
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    /// Replaces the selections with a cursor at the start of every line they span.
+    fn place_cursors_at_line_starts(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut cursors = Vec::new();
+        for selection in self.selections.all::<Point>(cx) {
+            let mut end_row = selection.end.row;
+            if selection.end.column == 0 && end_row > selection.start.row {
+                end_row -= 1;
+            }
+            for row in selection.start.row..=end_row {
+                let point = Point::new(row, 0);
+                cursors.push(point..point);
+            }
+        }
+        cursors.dedup();
+        self.change_selections(SelectionEffects::default(), window, cx, |selections| {
+            selections.select_ranges(cursors)
+        });
+    }
+
+    /// Replays the steps of a macro in this editor `times` times, as a single undoable
+    /// transaction. Actions apply to every cursor, as they would when typed.
+    fn replay_macro(
+        &mut self,
+        steps: Arc<[MacroStep]>,
+        times: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if cx.default_global::<KeyboardMacros>().replaying {
+            return;
+        }
+        let focus_handle = self.focus_handle.clone();
+        let editor = cx.entity();
+        // Actions are dispatched to the editor, so wait until it isn't being updated anymore.
+        window.defer(cx, move |window, cx| {
+            cx.global_mut::<KeyboardMacros>().replaying = true;
+            editor.update(cx, |editor, cx| {
+                editor.start_transaction_at(Instant::now(), window, cx);
+            });
+            for _ in 0..times {
+                for step in steps.iter() {
+                    match step {
+                        MacroStep::Action { action, .. } => {
+                            focus_handle.dispatch_action(action.as_ref(), window, cx);
+                        }
+                        MacroStep::Insertion {
+                            text,
+                            utf16_range_to_replace,
+                        } => editor.update(cx, |editor, cx| {
+                            editor.replay_insert_event(
+                                text,
+                                utf16_range_to_replace.clone(),
+                                window,
+                                cx,
+                            );
+                        }),
+                    }
+                }
+            }
+            editor.update(cx, |editor, cx| {
+                editor.end_transaction_at(Instant::now(), cx);
+            });
+            cx.global_mut::<KeyboardMacros>().replaying = false;
+        });
+    }
+}
+
+/// A modal for naming the last recorded macro before saving it to `macros.json`.
+struct MacroNameEditor {
+    name_editor: Entity<Editor>,
+    steps: Arc<[MacroStep]>,
+    fs: Arc<dyn Fs>,
+}
+
+impl MacroNameEditor {
+    fn new(
+        steps: Arc<[MacroStep]>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Macro name…", window, cx);
+            editor
+        });
+        Self {
+            name_editor,
+            steps,
+            fs,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        save_macro(name, &self.steps, self.fs.clone(), cx).detach_and_notify_err(window, cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for MacroNameEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("MacroNameEditor")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new("Save the last recorded macro").color(Color::Muted)),
+            )
+    }
+}
+
+impl Focusable for MacroNameEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for MacroNameEditor {}
+impl ModalView for MacroNameEditor {}
@@ -73,6 +73,70 @@ pub fn right(map: &DisplaySnapshot, mut point: DisplayPoint) -> DisplayPoint {
     map.clip_point(point, Bias::Right)
 }
 
+/// Returns the position visually to the left of the current point, wrapping to the
+/// previous line at the left edge of the line. This only differs from [`left`] in
+/// lines containing right-to-left text.
+pub fn visual_left(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    text_layout_details: &TextLayoutDetails,
+) -> DisplayPoint {
+    if !row_has_rtl(map, point.row()) {
+        return left(map, point);
+    }
+    let line = map.layout_row(point.row(), text_layout_details);
+    if !line.has_rtl() {
+        return left(map, point);
+    }
+
+    match line.index_left_of(point.column() as usize) {
+        Some(column) => map.clip_point(DisplayPoint::new(point.row(), column as u32), Bias::Left),
+        None if point.row().0 > 0 => left(map, DisplayPoint::new(point.row(), 0)),
+        None => point,
+    }
+}
+
+/// Returns the position visually to the right of the current point, wrapping to the
+/// next line at the right edge of the line. This only differs from [`right`] in
+/// lines containing right-to-left text.
+pub fn visual_right(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    text_layout_details: &TextLayoutDetails,
+) -> DisplayPoint {
+    if !row_has_rtl(map, point.row()) {
+        return right(map, point);
+    }
+    let line = map.layout_row(point.row(), text_layout_details);
+    if !line.has_rtl() {
+        return right(map, point);
+    }
+
+    match line.index_right_of(point.column() as usize) {
+        Some(column) => map.clip_point(DisplayPoint::new(point.row(), column as u32), Bias::Right),
+        None if point.row() < map.max_point().row() => right(
+            map,
+            DisplayPoint::new(point.row(), map.line_len(point.row())),
+        ),
+        None => point,
+    }
+}
+
+/// Whether the display row contains right-to-left text, which is much cheaper to check
+/// than laying out the row.
+fn row_has_rtl(map: &DisplaySnapshot, row: DisplayRow) -> bool {
+    for chunk in map.text_chunks(row) {
+        let line_end = chunk.find('\n');
+        if gpui::contains_rtl(&chunk[..line_end.unwrap_or(chunk.len())]) {
+            return true;
+        }
+        if line_end.is_some() {
+            break;
+        }
+    }
+    false
+}
+
 /// Returns a column to the right of the current point, not performing any wrapping
 /// if that point is already at the end of line.
 pub fn saturating_right(map: &DisplaySnapshot, mut point: DisplayPoint) -> DisplayPoint {
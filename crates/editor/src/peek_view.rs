@@ -0,0 +1,452 @@
+use std::{cmp::Reverse, ops::Range, sync::Arc};
+
+use anyhow::Result;
+use gpui::{
+    App, Context, Entity, FocusHandle, Focusable, Render, Subscription, Task, WeakEntity, Window,
+};
+use language::{Buffer, OffsetRangeExt as _, Point};
+use multi_buffer::{Anchor, MultiBuffer, PathKey};
+use project::{Location, LocationLink, Project};
+use ui::{ListItem, Tooltip, prelude::*};
+
+use crate::{
+    Cancel, Editor, EditorEvent, GotoDefinitionKind, Navigated, PeekDefinition, PeekReferences,
+    SelectionEffects,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    hover_links::{self, HoverLink},
+    multibuffer_context_lines,
+    scroll::Autoscroll,
+};
+
+/// The height of the peek view, in lines of the host editor.
+const PEEK_VIEW_HEIGHT: u32 = 16;
+/// Definitions usually need more surrounding code to make sense than references do.
+const PEEK_DEFINITION_CONTEXT_LINES: u32 = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PeekKind {
+    Definition,
+    References,
+}
+
+impl PeekKind {
+    fn title(self) -> &'static str {
+        match self {
+            PeekKind::Definition => "Definition",
+            PeekKind::References => "References",
+        }
+    }
+}
+
+pub(crate) struct PeekState {
+    editor: Entity<Editor>,
+    block_id: CustomBlockId,
+}
+
+struct PeekLocation {
+    /// The location in its own buffer, used to navigate to it.
+    location: Location,
+    /// The location within the peek view's multibuffer.
+    range: Range<Anchor>,
+    label: SharedString,
+    line: SharedString,
+}
+
+/// An embedded editor showing the excerpts around peeked locations,
+/// rendered as a block below the line it was invoked from.
+pub(crate) struct PeekView {
+    host: WeakEntity<Editor>,
+    kind: PeekKind,
+    editor: Entity<Editor>,
+    locations: Vec<PeekLocation>,
+    selected_index: usize,
+    _subscription: Subscription,
+}
+
+impl Editor {
+    pub fn peek_definition(
+        &mut self,
+        _: &PeekDefinition,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Navigated>> {
+        let Some(provider) = self.semantics_provider.clone() else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let head = self.selections.newest::<usize>(cx).head();
+        let Some((buffer, head)) = self.buffer.read(cx).text_anchor_for_position(head, cx) else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let Some(definitions) = provider.definitions(&buffer, head, GotoDefinitionKind::Symbol, cx)
+        else {
+            return Task::ready(Ok(Navigated::No));
+        };
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let Some(definitions) = definitions.await? else {
+                return Ok(Navigated::No);
+            };
+            editor.update_in(cx, |editor, window, cx| {
+                let locations = definitions
+                    .into_iter()
+                    .filter(|link| hover_links::exclude_link_to_position(&buffer, &head, link, cx))
+                    .map(|link| link.target)
+                    .collect();
+                editor.show_peek(PeekKind::Definition, locations, window, cx)
+            })
+        })
+    }
+
+    pub fn peek_references(
+        &mut self,
+        _: &PeekReferences,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Navigated>> {
+        let Some(project) = self.project.clone() else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let head = self.selections.newest::<usize>(cx).head();
+        let Some((buffer, head)) = self.buffer.read(cx).text_anchor_for_position(head, cx) else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let references = project.update(cx, |project, cx| project.references(&buffer, head, cx));
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let Some(locations) = references.await? else {
+                return Ok(Navigated::No);
+            };
+            editor.update_in(cx, |editor, window, cx| {
+                editor.show_peek(PeekKind::References, locations, window, cx)
+            })
+        })
+    }
+
+    fn show_peek(
+        &mut self,
+        kind: PeekKind,
+        locations: Vec<Location>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Navigated {
+        self.dismiss_peek(window, cx);
+        if locations.is_empty() {
+            return Navigated::No;
+        }
+
+        let Some(project) = self.project.clone() else {
+            return Navigated::No;
+        };
+        let host = cx.entity().downgrade();
+        let view = cx.new(|cx| PeekView::new(host, kind, locations, project, window, cx));
+        let peek_editor = view.read(cx).editor.clone();
+        cx.subscribe(&peek_editor, |_, _, event: &EditorEvent, cx| {
+            if event == &EditorEvent::Focused {
+                cx.emit(EditorEvent::FocusedIn)
+            }
+        })
+        .detach();
+
+        let position = self.selections.newest_anchor().head();
+        let block_id = self.insert_blocks(
+            [BlockProperties {
+                style: BlockStyle::Flex,
+                placement: BlockPlacement::Below(position),
+                height: Some(PEEK_VIEW_HEIGHT),
+                render: Arc::new({
+                    let view = view.clone();
+                    move |cx: &mut BlockContext| {
+                        div()
+                            .id(cx.block_id)
+                            .size_full()
+                            .pl(cx.margins.gutter.full_width())
+                            .pr(cx.margins.right)
+                            .block_mouse_except_scroll()
+                            .child(view.clone())
+                            .into_any_element()
+                    }
+                }),
+                priority: 0,
+            }],
+            Some(Autoscroll::fit()),
+            cx,
+        )[0];
+        self.peek = Some(PeekState {
+            editor: peek_editor.clone(),
+            block_id,
+        });
+        window.focus(&peek_editor.focus_handle(cx));
+        cx.notify();
+        Navigated::Yes
+    }
+
+    /// The embedded editor of the open peek view, if any.
+    pub fn peek_editor(&self) -> Option<&Entity<Editor>> {
+        self.peek.as_ref().map(|peek| &peek.editor)
+    }
+
+    /// Closes the peek view, if one is open, returning focus to this editor.
+    pub fn dismiss_peek(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let Some(peek) = self.peek.take() else {
+            return false;
+        };
+        if peek.editor.focus_handle(cx).contains_focused(window, cx) {
+            window.focus(&self.focus_handle);
+        }
+        self.remove_blocks([peek.block_id].into_iter().collect(), None, cx);
+        cx.notify();
+        true
+    }
+}
+
+impl PeekView {
+    fn new(
+        host: WeakEntity<Editor>,
+        kind: PeekKind,
+        locations: Vec<Location>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let context_lines = match kind {
+            PeekKind::Definition => PEEK_DEFINITION_CONTEXT_LINES,
+            PeekKind::References => multibuffer_context_lines(cx),
+        };
+
+        let mut by_buffer: Vec<(Entity<Buffer>, Vec<Location>)> = Vec::new();
+        for location in locations {
+            match by_buffer
+                .iter_mut()
+                .find(|(buffer, _)| *buffer == location.buffer)
+            {
+                Some((_, locations)) => locations.push(location),
+                None => by_buffer.push((location.buffer.clone(), vec![location])),
+            }
+        }
+
+        let capability = project.read(cx).capability();
+        let mut peek_locations = Vec::new();
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::new(capability);
+            for (buffer, locations) in by_buffer {
+                let snapshot = buffer.read(cx).snapshot();
+                let mut locations = locations
+                    .into_iter()
+                    .map(|location| (location.range.to_point(&snapshot), location))
+                    .collect::<Vec<_>>();
+                locations.sort_by_key(|(range, _)| (range.start, Reverse(range.end)));
+                locations.dedup_by_key(|(range, _)| range.clone());
+                let ranges = locations
+                    .iter()
+                    .map(|(range, _)| range.clone())
+                    .collect::<Vec<_>>();
+                let (anchor_ranges, _) = multibuffer.set_excerpts_for_path(
+                    PathKey::for_buffer(&buffer, cx),
+                    buffer.clone(),
+                    ranges,
+                    context_lines,
+                    cx,
+                );
+                let file_name = buffer
+                    .read(cx)
+                    .file()
+                    .map(|file| file.file_name(cx).to_string())
+                    .unwrap_or_else(|| "untitled".to_string());
+                for ((point_range, location), range) in locations.into_iter().zip(anchor_ranges) {
+                    let row = point_range.start.row;
+                    let line = snapshot
+                        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                        .collect::<String>();
+                    peek_locations.push(PeekLocation {
+                        location,
+                        range,
+                        label: format!("{file_name}:{}", row + 1).into(),
+                        line: line.trim().to_string().into(),
+                    });
+                }
+            }
+            multibuffer
+        });
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+            editor.highlight_background::<Self>(
+                &peek_locations
+                    .iter()
+                    .map(|location| location.range.clone())
+                    .collect::<Vec<_>>(),
+                |theme| theme.colors().editor_highlighted_line_background,
+                cx,
+            );
+            editor
+        });
+        let _subscription = cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            if let EditorEvent::TitleChanged | EditorEvent::DirtyChanged = event {
+                cx.notify();
+            }
+        });
+
+        let mut this = Self {
+            host,
+            kind,
+            editor,
+            locations: peek_locations,
+            selected_index: 0,
+            _subscription,
+        };
+        this.select(0, window, cx);
+        this
+    }
+
+    fn select(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(location) = self.locations.get(index) else {
+            return;
+        };
+        self.selected_index = index;
+        let range = location.range.clone();
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_anchor_ranges([range.start..range.start]),
+            );
+        });
+        cx.notify();
+    }
+
+    fn open_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(location) = self.locations.get(self.selected_index) else {
+            return;
+        };
+        let link = HoverLink::Text(LocationLink {
+            origin: None,
+            target: location.location.clone(),
+        });
+        self.host
+            .update(cx, |host, cx| {
+                host.dismiss_peek(window, cx);
+                host.navigate_to_hover_links(None, vec![link], false, window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn dismiss(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.host
+            .update(cx, |host, cx| host.dismiss_peek(window, cx))
+            .ok();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match self.kind {
+            PeekKind::Definition => self.kind.title().to_string(),
+            PeekKind::References => {
+                format!("{} ({})", self.kind.title(), self.locations.len())
+            }
+        };
+        let selected_label = self
+            .locations
+            .get(self.selected_index)
+            .map(|location| location.label.clone());
+
+        h_flex()
+            .flex_none()
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_subheader_background)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(title).size(LabelSize::Small))
+                    .when_some(selected_label, |el, label| {
+                        el.child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("peek-open", IconName::ArrowUpRight)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Open Location"))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.open_selected(window, cx)),
+                            ),
+                    )
+                    .child(
+                        IconButton::new("peek-close", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Close"))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| {
+                                    this.dismiss(&Cancel, window, cx)
+                                }),
+                            ),
+                    ),
+            )
+    }
+
+    fn render_locations(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("peek-locations")
+            .flex_none()
+            .w_1_4()
+            .h_full()
+            .overflow_y_scroll()
+            .border_l_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().panel_background)
+            .children(self.locations.iter().enumerate().map(|(ix, location)| {
+                ListItem::new(ix)
+                    .toggle_state(ix == self.selected_index)
+                    .on_click(cx.listener(move |this, _, window, cx| this.select(ix, window, cx)))
+                    .child(
+                        v_flex()
+                            .overflow_hidden()
+                            .child(Label::new(location.label.clone()).size(LabelSize::Small))
+                            .child(
+                                Label::new(location.line.clone())
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                    )
+            }))
+    }
+}
+
+impl Focusable for PeekView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for PeekView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("PeekView")
+            .on_action(cx.listener(Self::dismiss))
+            .size_full()
+            .border_t_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .child(div().flex_1().h_full().child(self.editor.clone()))
+                    .when(self.locations.len() > 1, |el| {
+                        el.child(self.render_locations(cx))
+                    }),
+            )
+    }
+}
@@ -495,6 +495,23 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
         self.0.definitions(&buffer, position, kind, cx)
     }
 
+    fn document_links(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::DocumentLink>>>> {
+        None
+    }
+
+    fn resolve_document_link(
+        &self,
+        _: project::DocumentLink,
+        _: Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<project::DocumentLink>>> {
+        None
+    }
+
     fn range_for_rename(
         &self,
         _: &Entity<Buffer>,
@@ -499,6 +499,7 @@ impl Editor {
                     .update_in(cx, |editor, window, cx| {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
                         editor.refresh_colors(false, None, window, cx);
+                        editor.refresh_bracket_colors(cx);
                     })
                     .ok()
             })
@@ -615,6 +616,7 @@ impl Editor {
 
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
         self.refresh_colors(false, None, window, cx);
+        self.refresh_bracket_colors(cx);
         editor_was_scrolled
     }
 
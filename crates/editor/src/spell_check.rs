@@ -0,0 +1,152 @@
+use crate::CodeActionProvider;
+use anyhow::{Context as _, Result};
+use gpui::{App, Entity, Task, Window};
+use language::Buffer;
+use multi_buffer::ExcerptId;
+use project::{
+    CodeAction, LspAction, ProjectTransaction,
+    spell_check_store::{SPELL_CHECK_DIAGNOSTIC_SOURCE, SpellCheckStore},
+};
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, sync::Arc};
+use text::ToOffset as _;
+
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Serialize, Deserialize)]
+enum SpellCheckAction {
+    Replace(String),
+    AddToUserDictionary(String),
+    AddToProjectWords(String),
+}
+
+/// Offers to correct misspelled words, or to accept them from then on.
+pub(crate) struct SpellCheckCodeActionProvider {
+    spell_check_store: Entity<SpellCheckStore>,
+}
+
+impl SpellCheckCodeActionProvider {
+    pub(crate) fn new(spell_check_store: Entity<SpellCheckStore>) -> Self {
+        Self { spell_check_store }
+    }
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        "spell_check".into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let snapshot = buffer.read(cx).snapshot();
+        let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+        let spell_check_store = self.spell_check_store.read(cx);
+        let server_id = spell_check_store.server_id();
+        let mut actions = Vec::new();
+        for entry in snapshot.diagnostics_in_range::<_, usize>(range, false) {
+            if entry.diagnostic.source.as_deref() != Some(SPELL_CHECK_DIAGNOSTIC_SOURCE) {
+                continue;
+            }
+            let word = snapshot
+                .text_for_range(entry.range.clone())
+                .collect::<String>();
+            let range =
+                snapshot.anchor_before(entry.range.start)..snapshot.anchor_after(entry.range.end);
+            let suggestions = spell_check_store.suggestions(&word, MAX_SUGGESTIONS);
+            actions.extend(
+                suggestions
+                    .into_iter()
+                    .map(|suggestion| {
+                        (
+                            format!("Change to '{suggestion}'"),
+                            SpellCheckAction::Replace(suggestion),
+                        )
+                    })
+                    .chain([
+                        (
+                            format!("Add '{word}' to user dictionary"),
+                            SpellCheckAction::AddToUserDictionary(word.clone()),
+                        ),
+                        (
+                            format!("Add '{word}' to project word list"),
+                            SpellCheckAction::AddToProjectWords(word),
+                        ),
+                    ])
+                    .map(|(title, action)| CodeAction {
+                        server_id,
+                        range: range.clone(),
+                        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                            title,
+                            kind: Some(lsp::CodeActionKind::QUICKFIX),
+                            data: serde_json::to_value(action).ok(),
+                            ..Default::default()
+                        })),
+                        resolved: true,
+                    }),
+            );
+        }
+        Task::ready(Ok(actions))
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        push_to_history: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let LspAction::Action(lsp_action) = action.lsp_action else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        let spell_check_action = match lsp_action
+            .data
+            .context("missing spell check action")
+            .and_then(|data| Ok(serde_json::from_value::<SpellCheckAction>(data)?))
+        {
+            Ok(spell_check_action) => spell_check_action,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let add_word = match spell_check_action {
+            SpellCheckAction::Replace(replacement) => {
+                let transaction = buffer.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range, replacement)], None, cx);
+                    buffer.end_transaction(cx).and_then(|transaction_id| {
+                        if push_to_history {
+                            buffer.finalize_last_transaction();
+                            buffer.get_transaction(transaction_id).cloned()
+                        } else {
+                            buffer.forget_transaction(transaction_id)
+                        }
+                    })
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer, transaction);
+                }
+                return Task::ready(Ok(project_transaction));
+            }
+            SpellCheckAction::AddToUserDictionary(word) => self
+                .spell_check_store
+                .update(cx, |store, cx| store.add_to_user_dictionary(word, cx)),
+            SpellCheckAction::AddToProjectWords(word) => {
+                self.spell_check_store.update(cx, |store, cx| {
+                    store.add_to_project_words(word, &buffer, cx)
+                })
+            }
+        };
+        cx.background_spawn(async move {
+            add_word.await?;
+            Ok(ProjectTransaction::default())
+        })
+    }
+}
@@ -0,0 +1,118 @@
+use gpui::{Context, Window};
+use language::{OutlineItem, Point};
+use multi_buffer::{Anchor, ToPoint as _};
+use text::Bias;
+use theme::SyntaxTheme;
+
+use crate::{
+    DisplayPoint, DisplayRow, Editor, EditorSnapshot, JumpData, SelectionEffects,
+    scroll::Autoscroll,
+};
+
+/// A scope that encloses the top of the viewport and is pinned there while scrolling.
+#[derive(Clone, Debug)]
+pub(crate) struct StickyScope {
+    pub item: OutlineItem<Anchor>,
+    /// The first row of the scope, clipped to its excerpt.
+    pub start: Point,
+}
+
+impl EditorSnapshot {
+    /// Returns the outline items enclosing the first row that is not covered by
+    /// the pinned scopes themselves, outermost first.
+    ///
+    /// `reserved_rows` is the number of rows already covered at the top of the
+    /// viewport, e.g. by the sticky buffer header of a multibuffer.
+    pub(crate) fn sticky_scopes(
+        &self,
+        scroll_top: DisplayRow,
+        reserved_rows: u32,
+        max_depth: usize,
+        theme: &SyntaxTheme,
+    ) -> Vec<StickyScope> {
+        let buffer = self.buffer_snapshot();
+        let max_row = self.max_point().row();
+        let mut scopes = Vec::new();
+        if max_depth == 0 {
+            return scopes;
+        }
+
+        // Each pinned scope covers a row, which can reveal more enclosing scopes
+        // below it, so keep probing until the number of scopes settles.
+        for _ in 0..=max_depth {
+            let probe_row = DisplayRow(scroll_top.0 + reserved_rows + scopes.len() as u32);
+            if probe_row > max_row {
+                break;
+            }
+            let probe = self.display_point_to_point(DisplayPoint::new(probe_row, 0), Bias::Left);
+            let Some((_, items)) = buffer.symbols_containing(probe, Some(theme)) else {
+                break;
+            };
+            let enclosing = items
+                .into_iter()
+                .filter_map(|item| {
+                    let start = item.range.start.to_point(buffer);
+                    let end = item.range.end.to_point(buffer);
+                    (start.row < probe.row && end.row >= probe.row)
+                        .then_some(StickyScope { item, start })
+                })
+                .take(max_depth)
+                .collect::<Vec<_>>();
+            let settled = enclosing.len() <= scopes.len();
+            scopes = enclosing;
+            if settled {
+                break;
+            }
+        }
+        scopes
+    }
+}
+
+impl Editor {
+    /// Navigates to a pinned scope, keeping it below the scopes that enclose it.
+    ///
+    /// In multibuffers, scopes that start outside of their excerpt are opened in
+    /// their own buffer instead.
+    pub(crate) fn go_to_sticky_scope(
+        &mut self,
+        scope: &StickyScope,
+        line_offset_from_top: u32,
+        split: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let start = scope.item.range.start;
+        let starts_in_excerpt = buffer
+            .context_range_for_excerpt(start.excerpt_id)
+            .zip(buffer.buffer_for_excerpt(start.excerpt_id))
+            .is_some_and(|(context, excerpt_buffer)| {
+                context
+                    .start
+                    .cmp(&start.text_anchor, excerpt_buffer)
+                    .is_le()
+            });
+
+        if buffer.is_singleton() || (starts_in_excerpt && !split) {
+            self.change_selections(
+                SelectionEffects::scroll(Autoscroll::top_relative(line_offset_from_top as usize)),
+                window,
+                cx,
+                |selections| selections.select_ranges([scope.start..scope.start]),
+            );
+        } else if let Some(excerpt_buffer) = buffer.buffer_for_excerpt(start.excerpt_id) {
+            let position = language::ToPoint::to_point(&start.text_anchor, excerpt_buffer);
+            self.open_excerpts_common(
+                Some(JumpData::MultiBufferPoint {
+                    excerpt_id: start.excerpt_id,
+                    position,
+                    anchor: start.text_anchor,
+                    line_offset_from_top,
+                }),
+                split,
+                window,
+                cx,
+            );
+        }
+    }
+}
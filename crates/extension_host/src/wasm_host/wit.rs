@@ -954,7 +954,7 @@ impl Extension {
     ) -> Result<Result<DebugScenario, String>> {
         match self {
             Extension::V0_6_0(ext) => {
-                let config = config.into();
+                let config = config.try_into()?;
                 let dap_binary = ext
                     .call_dap_config_to_scenario(store, &config)
                     .await?
@@ -148,11 +148,15 @@ impl TryFrom<extension::DebugTaskDefinition> for DebugTaskDefinition {
     }
 }
 
-impl From<task::DebugRequest> for DebugRequest {
-    fn from(value: task::DebugRequest) -> Self {
+impl TryFrom<task::DebugRequest> for DebugRequest {
+    type Error = anyhow::Error;
+    fn try_from(value: task::DebugRequest) -> Result<Self, Self::Error> {
         match value {
-            task::DebugRequest::Launch(launch_request) => Self::Launch(launch_request.into()),
-            task::DebugRequest::Attach(attach_request) => Self::Attach(attach_request.into()),
+            task::DebugRequest::Launch(launch_request) => Ok(Self::Launch(launch_request.into())),
+            task::DebugRequest::Attach(attach_request) => Ok(Self::Attach(attach_request.into())),
+            task::DebugRequest::CoreDump(_) => {
+                bail!("Extension debug adapters don't support loading core dumps")
+            }
         }
     }
 }
@@ -203,14 +207,15 @@ impl From<AttachRequest> for task::AttachRequest {
     }
 }
 
-impl From<ZedDebugConfig> for DebugConfig {
-    fn from(value: ZedDebugConfig) -> Self {
-        Self {
+impl TryFrom<ZedDebugConfig> for DebugConfig {
+    type Error = anyhow::Error;
+    fn try_from(value: ZedDebugConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
             label: value.label.into(),
             adapter: value.adapter.into(),
-            request: value.request.into(),
+            request: value.request.try_into()?,
             stop_on_entry: value.stop_on_entry,
-        }
+        })
     }
 }
 impl TryFrom<DebugAdapterBinary> for extension::DebugAdapterBinary {
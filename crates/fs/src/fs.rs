@@ -115,6 +115,9 @@ pub trait Fs: Send + Sync {
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
+    /// Overwrites the bytes of an existing file starting at `offset`, leaving the rest of its
+    /// content untouched.
+    async fn write_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -257,6 +260,10 @@ pub struct RealFs {
 
 pub trait FileHandle: Send + Sync + std::fmt::Debug {
     fn current_path(&self, fs: &Arc<dyn Fs>) -> Result<PathBuf>;
+
+    /// Reads the bytes starting at `offset` into `buf`, returning how many were read. Less than
+    /// the length of `buf` is only read at the end of the file.
+    fn read_at(&self, fs: &Arc<dyn Fs>, offset: u64, buf: &mut [u8]) -> Result<usize>;
 }
 
 impl FileHandle for std::fs::File {
@@ -322,6 +329,22 @@ impl FileHandle for std::fs::File {
     fn current_path(&self, _: &Arc<dyn Fs>) -> Result<PathBuf> {
         anyhow::bail!("unimplemented")
     }
+
+    fn read_at(&self, _: &Arc<dyn Fs>, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            let position = offset + read as u64;
+            #[cfg(unix)]
+            let count = std::os::unix::fs::FileExt::read_at(self, &mut buf[read..], position)?;
+            #[cfg(windows)]
+            let count = std::os::windows::fs::FileExt::seek_read(self, &mut buf[read..], position)?;
+            if count == 0 {
+                break;
+            }
+            read += count;
+        }
+        Ok(read)
+    }
 }
 
 pub struct RealWatcher {}
@@ -639,6 +662,20 @@ impl Fs for RealFs {
         Ok(())
     }
 
+    async fn write_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()> {
+        use smol::io::AsyncSeekExt as _;
+
+        let mut file = smol::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .await
+            .with_context(|| format!("opening {path:?} for writing"))?;
+        file.seek(io::SeekFrom::Start(offset)).await?;
+        file.write_all(content).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         Ok(smol::fs::canonicalize(path)
             .await
@@ -1956,6 +1993,7 @@ impl Watcher for FakeWatcher {
 #[derive(Debug)]
 struct FakeHandle {
     inode: u64,
+    path: PathBuf,
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -1972,6 +2010,21 @@ impl FileHandle for FakeHandle {
         }
         anyhow::bail!("fake fd target not found")
     }
+
+    fn read_at(&self, fs: &Arc<dyn Fs>, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let fs = fs.as_fake();
+        let mut state = fs.state.lock();
+        let path = state
+            .moves
+            .get(&self.inode)
+            .cloned()
+            .unwrap_or_else(|| self.path.clone());
+        let content = state.entry(&path)?.file_content(&path)?;
+        let start = content.len().min(offset as usize);
+        let count = buf.len().min(content.len() - start);
+        buf[..count].copy_from_slice(&content[start..start + count]);
+        Ok(count)
+    }
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -2259,7 +2312,10 @@ impl Fs for FakeFs {
             FakeFsEntry::Dir { inode, .. } => *inode,
             _ => unreachable!(),
         };
-        Ok(Arc::new(FakeHandle { inode }))
+        Ok(Arc::new(FakeHandle {
+            inode,
+            path: normalize_path(path),
+        }))
     }
 
     async fn load(&self, path: &Path) -> Result<String> {
@@ -2302,6 +2358,18 @@ impl Fs for FakeFs {
         Ok(())
     }
 
+    async fn write_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()> {
+        let path = normalize_path(path);
+        let mut data = self.load_internal(&path).await?;
+        let start = offset as usize;
+        if data.len() < start + content.len() {
+            data.resize(start + content.len(), 0);
+        }
+        data[start..start + content.len()].copy_from_slice(content);
+        self.write_file_internal(path, data, false)?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         let path = normalize_path(path);
         self.simulate_random_delay().await;
@@ -3127,4 +3195,27 @@ mod tests {
         let content = std::fs::read_to_string(&file_to_be_replaced).unwrap();
         assert_eq!(content, "Hello");
     }
+
+    #[gpui::test]
+    async fn test_realfs_write_at_and_read_at(executor: BackgroundExecutor) {
+        let fs: Arc<dyn Fs> = Arc::new(RealFs {
+            bundled_git_binary_path: None,
+            executor,
+        });
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("file.bin");
+        std::fs::write(&file_path, b"\x00\x01\x02\x03\x04\x05").unwrap();
+
+        smol::block_on(fs.write_at(&file_path, 2, b"\xaa\xbb")).unwrap();
+        assert_eq!(
+            std::fs::read(&file_path).unwrap(),
+            b"\x00\x01\xaa\xbb\x04\x05"
+        );
+
+        let handle = smol::block_on(fs.open_handle(&file_path)).unwrap();
+        let mut buf = [0; 4];
+        assert_eq!(handle.read_at(&fs, 3, &mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"\xbb\x04\x05");
+        assert_eq!(handle.read_at(&fs, 10, &mut buf).unwrap(), 0);
+    }
 }
@@ -92,6 +92,8 @@ actions!(
         Init,
         /// Opens all modified files in the editor.
         OpenModifiedFiles,
+        /// Toggles between unified and side-by-side rendering of the active diff.
+        ToggleSplitDiff,
         /// Clones a repository.
         Clone,
     ]
@@ -1,14 +1,19 @@
+use crate::split_diff::SplitDiff;
 use anyhow::{Context as _, Result};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer, SelectionEffects, multibuffer_context_lines};
-use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath};
+use git::{
+    ToggleSplitDiff,
+    repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath},
+};
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, Render, WeakEntity, Window,
+    FocusHandle, Focusable, InteractiveElement as _, IntoElement, ParentElement as _, Render,
+    Styled as _, WeakEntity, Window, div, prelude::FluentBuilder as _,
 };
 use language::{
-    Anchor, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _,
-    Point, Rope, TextBuffer,
+    Anchor, Buffer, BufferId, Capability, DiskState, File, LanguageRegistry, LineEnding,
+    OffsetRangeExt as _, Point, Rope, TextBuffer,
 };
 use multi_buffer::PathKey;
 use project::{Project, WorktreeId, git_store::Repository};
@@ -30,6 +35,8 @@ pub struct CommitView {
     commit: CommitDetails,
     editor: Entity<Editor>,
     multibuffer: Entity<MultiBuffer>,
+    metadata_buffer_id: Option<BufferId>,
+    split_diff: Option<Entity<SplitDiff>>,
 }
 
 struct GitBlob {
@@ -207,10 +214,36 @@ impl CommitView {
         })
         .detach();
 
-        Self {
+        let split_diff = SplitDiff::for_default_style(&editor, window, cx);
+        let this = Self {
             commit,
             editor,
             multibuffer,
+            metadata_buffer_id,
+            split_diff,
+        };
+        this.hide_metadata_header_in_split_diff(cx);
+        this
+    }
+
+    fn toggle_split_diff(
+        &mut self,
+        _: &ToggleSplitDiff,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        SplitDiff::toggle(&mut self.split_diff, &self.editor, window, cx);
+        self.hide_metadata_header_in_split_diff(cx);
+        cx.notify();
+    }
+
+    fn hide_metadata_header_in_split_diff(&self, cx: &mut App) {
+        if let Some((split_diff, buffer_id)) = self.split_diff.as_ref().zip(self.metadata_buffer_id)
+        {
+            let base_editor = split_diff.read(cx).base_editor().clone();
+            base_editor.update(cx, |base_editor, cx| {
+                base_editor.disable_header_for_buffer(buffer_id, cx);
+            });
         }
     }
 }
@@ -544,7 +577,13 @@ impl Item for CommitView {
 }
 
 impl Render for CommitView {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-        self.editor.clone()
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .on_action(cx.listener(Self::toggle_split_diff))
+            .map(|this| match &self.split_diff {
+                Some(split_diff) => this.child(split_diff.clone()),
+                None => this.child(self.editor.clone()),
+            })
     }
 }
@@ -1,12 +1,15 @@
 //! FileDiffView provides a UI for displaying differences between two buffers.
 
+use crate::split_diff::SplitDiff;
 use anyhow::Result;
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer};
 use futures::{FutureExt, select_biased};
+use git::ToggleSplitDiff;
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, Render, Task, Window,
+    FocusHandle, Focusable, InteractiveElement as _, IntoElement, ParentElement as _, Render,
+    Styled as _, Task, Window, div, prelude::FluentBuilder as _,
 };
 use language::Buffer;
 use project::Project;
@@ -27,6 +30,7 @@ use workspace::{
 
 pub struct FileDiffView {
     editor: Entity<Editor>,
+    split_diff: Option<Entity<SplitDiff>>,
     old_buffer: Entity<Buffer>,
     new_buffer: Entity<Buffer>,
     buffer_changes_tx: watch::Sender<()>,
@@ -102,6 +106,7 @@ impl FileDiffView {
             );
             editor
         });
+        let split_diff = SplitDiff::for_default_style(&editor, window, cx);
 
         let (buffer_changes_tx, mut buffer_changes_rx) = watch::channel(());
 
@@ -119,6 +124,7 @@ impl FileDiffView {
 
         Self {
             editor,
+            split_diff,
             buffer_changes_tx,
             old_buffer,
             new_buffer,
@@ -162,6 +168,16 @@ impl FileDiffView {
             }),
         }
     }
+
+    fn toggle_split_diff(
+        &mut self,
+        _: &ToggleSplitDiff,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        SplitDiff::toggle(&mut self.split_diff, &self.editor, window, cx);
+        cx.notify();
+    }
 }
 
 async fn build_buffer_diff(
@@ -349,8 +365,14 @@ impl Item for FileDiffView {
 }
 
 impl Render for FileDiffView {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-        self.editor.clone()
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .on_action(cx.listener(Self::toggle_split_diff))
+            .map(|this| match &self.split_diff {
+                Some(split_diff) => this.child(split_diff.clone()),
+                None => this.child(self.editor.clone()),
+            })
     }
 }
 
@@ -44,6 +44,7 @@ pub mod picker_prompt;
 pub mod project_diff;
 pub(crate) mod remote_output;
 pub mod repository_selector;
+mod split_diff;
 pub mod stash_picker;
 pub mod text_diff_view;
 
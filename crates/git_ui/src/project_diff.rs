@@ -3,6 +3,7 @@ use crate::{
     git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
     git_panel_settings::GitPanelSettings,
     remote_button::{render_publish_button, render_push_button},
+    split_diff::SplitDiff,
 };
 use anyhow::Result;
 use buffer_diff::{BufferDiff, DiffHunkSecondaryStatus};
@@ -15,7 +16,7 @@ use editor::{
 };
 use futures::StreamExt;
 use git::{
-    Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext,
+    Commit, StageAll, StageAndNext, ToggleSplitDiff, ToggleStaged, UnstageAll, UnstageAndNext,
     repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::FileStatus,
 };
@@ -56,6 +57,7 @@ pub struct ProjectDiff {
     project: Entity<Project>,
     multibuffer: Entity<MultiBuffer>,
     editor: Entity<Editor>,
+    split_diff: Option<Entity<SplitDiff>>,
     git_store: Entity<GitStore>,
     workspace: WeakEntity<Workspace>,
     focus_handle: FocusHandle,
@@ -170,6 +172,7 @@ impl ProjectDiff {
         });
         cx.subscribe_in(&editor, window, Self::handle_editor_event)
             .detach();
+        let split_diff = SplitDiff::for_default_style(&editor, window, cx);
 
         let git_store = project.read(cx).git_store().clone();
         let git_store_subscription = cx.subscribe_in(
@@ -216,6 +219,7 @@ impl ProjectDiff {
             workspace: workspace.downgrade(),
             focus_handle,
             editor,
+            split_diff,
             multibuffer,
             pending_scroll: None,
             update_needed: send,
@@ -337,9 +341,20 @@ impl ProjectDiff {
             selection,
             stage_all,
             unstage_all,
+            split: self.split_diff.is_some(),
         }
     }
 
+    fn toggle_split_diff(
+        &mut self,
+        _: &ToggleSplitDiff,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        SplitDiff::toggle(&mut self.split_diff, &self.editor, window, cx);
+        cx.notify();
+    }
+
     fn handle_editor_event(
         &mut self,
         editor: &Entity<Editor>,
@@ -720,6 +735,7 @@ impl Render for ProjectDiff {
         div()
             .track_focus(&self.focus_handle)
             .key_context(if is_empty { "EmptyPane" } else { "GitDiff" })
+            .on_action(cx.listener(Self::toggle_split_diff))
             .bg(cx.theme().colors().editor_background)
             .flex()
             .items_center()
@@ -773,7 +789,10 @@ impl Render for ProjectDiff {
                         ),
                 )
             })
-            .when(!is_empty, |el| el.child(self.editor.clone()))
+            .when(!is_empty, |el| match &self.split_diff {
+                Some(split_diff) => el.child(split_diff.clone()),
+                None => el.child(self.editor.clone()),
+            })
     }
 }
 
@@ -911,6 +930,7 @@ struct ButtonStates {
     selection: bool,
     stage_all: bool,
     unstage_all: bool,
+    split: bool,
 }
 
 impl Render for ProjectDiffToolbar {
@@ -997,6 +1017,19 @@ impl Render for ProjectDiffToolbar {
                             .on_click(cx.listener(|this, _, window, cx| {
                                 this.dispatch_action(&GoToHunk, window, cx)
                             })),
+                    )
+                    .child(
+                        IconButton::new("split", IconName::Split)
+                            .shape(ui::IconButtonShape::Square)
+                            .toggle_state(button_states.split)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Toggle split diff",
+                                &ToggleSplitDiff,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.dispatch_action(&ToggleSplitDiff, window, cx)
+                            })),
                     ),
             )
             .child(vertical_divider())
@@ -0,0 +1,893 @@
+//! SplitDiff renders a diff editor as two synchronized panes: the base text on
+//! the left and the current text on the right.
+//!
+//! The right pane is the diff item's own editor with its hunks collapsed. The
+//! left pane mirrors each of its excerpts over a read-only copy of the diff's
+//! base text. Filler blocks are inserted on whichever side is shorter so that
+//! corresponding lines share a display row, which lets scrolling be mirrored
+//! by copying the scroll position.
+
+use buffer_diff::BufferDiffSnapshot;
+use collections::HashMap;
+use editor::{
+    Editor, EditorEvent, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
+    RowHighlightOptions, SelectionEffects,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+};
+use git::{Restore, StageAndNext, ToggleStaged, UnstageAndNext};
+use gpui::{
+    App, AppContext as _, Context, Entity, FocusHandle, Focusable, IntoElement, Render,
+    Subscription, Task, Window,
+};
+use language::{
+    Anchor, AnchorRangeExt as _, Bias, Buffer, BufferId, BufferSnapshot, Capability, Point,
+    ToOffset as _, language_settings::SoftWrap,
+};
+use project::project_settings::ProjectSettings;
+use settings::{DiffViewStyle, Settings as _};
+use std::{ops::Range, sync::Arc};
+use theme::ActiveTheme as _;
+use ui::prelude::*;
+
+/// Hunks spanning more rows than this on either side are not word-diffed.
+const MAX_WORD_DIFF_ROWS: u32 = 8;
+
+pub struct SplitDiff {
+    editor: Entity<Editor>,
+    base_editor: Entity<Editor>,
+    base_multibuffer: Entity<MultiBuffer>,
+    base_buffers: HashMap<BufferId, BaseBuffer>,
+    excerpts: Vec<ExcerptPair>,
+    blocks: Vec<CustomBlockId>,
+    base_blocks: Vec<CustomBlockId>,
+    _sync_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// A read-only copy of a buffer diff's base text, displayed in the left pane.
+struct BaseBuffer {
+    buffer: Entity<Buffer>,
+    base_text_id: BufferId,
+    base_text_version: clock::Global,
+}
+
+/// An excerpt of the primary editor along with its counterpart in the left pane.
+struct ExcerptPair {
+    excerpt_id: ExcerptId,
+    base_excerpt_id: ExcerptId,
+    base_buffer: Entity<Buffer>,
+    base_range: Range<Point>,
+    rows: Range<u32>,
+    base_rows: Range<u32>,
+    /// All hunks of the buffer up to the end of the excerpt, in buffer rows.
+    hunks: Vec<HunkRows>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct HunkRows {
+    rows: Range<u32>,
+    base_rows: Range<u32>,
+}
+
+enum SplitDiffRows {}
+enum SplitDiffWords {}
+
+impl SplitDiff {
+    /// Splits the given diff editor if the `git.diff_view_style` setting asks for it.
+    pub(crate) fn for_default_style(
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Entity<Self>> {
+        (ProjectSettings::get_global(cx).git.diff_view_style == DiffViewStyle::Split)
+            .then(|| cx.new(|cx| Self::new(editor.clone(), window, cx)))
+    }
+
+    /// Switches a diff editor between unified and split rendering.
+    pub(crate) fn toggle(
+        split: &mut Option<Entity<Self>>,
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        if let Some(split) = split.take() {
+            split.update(cx, |split, cx| split.unsplit(cx));
+        } else {
+            *split = Some(cx.new(|cx| Self::new(editor.clone(), window, cx)));
+        }
+    }
+
+    pub fn new(editor: Entity<Editor>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let multibuffer = editor.read(cx).buffer().clone();
+        let base_multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
+        // The base text buffers borrow the files of the buffers they mirror so
+        // that headers show the right paths, so they must never be registered
+        // with the project's language servers.
+        let base_editor = cx.new(|cx| {
+            let mut base_editor =
+                Editor::for_multibuffer(base_multibuffer.clone(), None, window, cx);
+            base_editor.set_read_only(true);
+            base_editor.disable_diagnostics(cx);
+            base_editor.disable_expand_excerpt_buttons(cx);
+            base_editor.set_show_git_diff_gutter(false, cx);
+            base_editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            base_editor
+        });
+        editor.update(cx, |editor, cx| {
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor.buffer().update(cx, |multibuffer, cx| {
+                multibuffer.set_all_diff_hunks_collapsed(cx);
+            });
+        });
+
+        let subscriptions = vec![
+            cx.subscribe(&multibuffer, |this, _, event, cx| match event {
+                multi_buffer::Event::ExcerptsAdded { .. }
+                | multi_buffer::Event::ExcerptsRemoved { .. }
+                | multi_buffer::Event::ExcerptsExpanded { .. }
+                | multi_buffer::Event::ExcerptsEdited { .. }
+                | multi_buffer::Event::Edited { .. }
+                | multi_buffer::Event::Reloaded
+                | multi_buffer::Event::BufferDiffChanged => this.schedule_sync(cx),
+                _ => {}
+            }),
+            cx.subscribe_in(&editor, window, |this, _, event, window, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(false, window, cx);
+                }
+            }),
+            cx.subscribe_in(&base_editor, window, |this, _, event, window, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(true, window, cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            editor,
+            base_editor,
+            base_multibuffer,
+            base_buffers: HashMap::default(),
+            excerpts: Vec::new(),
+            blocks: Vec::new(),
+            base_blocks: Vec::new(),
+            _sync_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.sync(cx);
+        this.sync_scroll_position(false, window, cx);
+        this
+    }
+
+    pub(crate) fn base_editor(&self) -> &Entity<Editor> {
+        &self.base_editor
+    }
+
+    /// Restores the primary editor to unified rendering.
+    fn unsplit(&mut self, cx: &mut Context<Self>) {
+        let blocks = self.blocks.drain(..).collect();
+        self.editor.update(cx, |editor, cx| {
+            editor.remove_blocks(blocks, None, cx);
+            editor.clear_row_highlights::<SplitDiffRows>();
+            editor.clear_background_highlights::<SplitDiffWords>(cx);
+            editor.clear_soft_wrap_mode_override(cx);
+            editor.set_expand_all_diff_hunks(cx);
+        });
+    }
+
+    fn schedule_sync(&mut self, cx: &mut Context<Self>) {
+        self._sync_task = cx.spawn(async move |this, cx| {
+            this.update(cx, |this, cx| this.sync(cx)).ok();
+        });
+    }
+
+    fn sync(&mut self, cx: &mut Context<Self>) {
+        let snapshot = self.editor.read(cx).buffer().read(cx).snapshot(cx);
+        let hide_headers = snapshot.is_singleton();
+
+        let mut excerpts = Vec::new();
+        let mut base_text_changed = false;
+        for (excerpt_id, buffer, range) in snapshot.excerpts() {
+            let context = range.context.to_point(buffer);
+            let rows = context.start.row..context.end.row + 1;
+            let pair = if let Some(diff) = snapshot.diff_for_buffer_id(buffer.remote_id()) {
+                let base_text = diff.base_text();
+                let hunks = diff
+                    .hunks_intersecting_range(Anchor::MIN..range.context.end, buffer)
+                    .map(|hunk| HunkRows {
+                        rows: row_range(hunk.range.clone()),
+                        base_rows: row_range(
+                            base_text.offset_to_point(hunk.diff_base_byte_range.start)
+                                ..base_text.offset_to_point(hunk.diff_base_byte_range.end),
+                        ),
+                    })
+                    .collect::<Vec<_>>();
+                let max_base_row = base_text.max_point().row;
+                let base_start = map_row(rows.start, &hunks, false, Bias::Left).min(max_base_row);
+                let base_end = map_row(rows.end, &hunks, false, Bias::Right)
+                    .clamp(base_start, max_base_row + 1);
+                let base_range = if base_end > base_start {
+                    Point::new(base_start, 0)
+                        ..Point::new(base_end - 1, base_text.line_len(base_end - 1))
+                } else {
+                    Point::new(base_start, 0)..Point::new(base_start, 0)
+                };
+                let (base_buffer, changed) = self.base_buffer_for(buffer, diff, hide_headers, cx);
+                base_text_changed |= changed;
+                ExcerptPair {
+                    excerpt_id,
+                    base_excerpt_id: ExcerptId::min(),
+                    base_buffer,
+                    base_range,
+                    rows,
+                    base_rows: base_start..base_end,
+                    hunks,
+                }
+            } else {
+                let Some(buffer) = self
+                    .editor
+                    .read(cx)
+                    .buffer()
+                    .read(cx)
+                    .buffer(buffer.remote_id())
+                else {
+                    continue;
+                };
+                ExcerptPair {
+                    excerpt_id,
+                    base_excerpt_id: ExcerptId::min(),
+                    base_buffer: buffer,
+                    base_range: context,
+                    rows: rows.clone(),
+                    base_rows: rows,
+                    hunks: Vec::new(),
+                }
+            };
+            excerpts.push(pair);
+        }
+
+        let layout_changed = base_text_changed
+            || excerpts.len() != self.excerpts.len()
+            || excerpts.iter().zip(&self.excerpts).any(|(new, old)| {
+                new.excerpt_id != old.excerpt_id
+                    || new.base_buffer != old.base_buffer
+                    || new.base_range != old.base_range
+            });
+        if layout_changed {
+            self.base_multibuffer.update(cx, |base_multibuffer, cx| {
+                base_multibuffer.clear(cx);
+                for pair in &mut excerpts {
+                    if let Some(base_excerpt_id) = base_multibuffer
+                        .push_excerpts(
+                            pair.base_buffer.clone(),
+                            [ExcerptRange::new(pair.base_range.clone())],
+                            cx,
+                        )
+                        .pop()
+                    {
+                        pair.base_excerpt_id = base_excerpt_id;
+                    }
+                }
+            });
+        } else {
+            for (new, old) in excerpts.iter_mut().zip(&self.excerpts) {
+                new.base_excerpt_id = old.base_excerpt_id;
+            }
+        }
+        self.excerpts = excerpts;
+        self.refresh_decorations(&snapshot, cx);
+    }
+
+    /// Returns the left pane's copy of a buffer's base text, updating it when
+    /// the diff's base text has changed.
+    fn base_buffer_for(
+        &mut self,
+        buffer: &BufferSnapshot,
+        diff: &BufferDiffSnapshot,
+        hide_header: bool,
+        cx: &mut Context<Self>,
+    ) -> (Entity<Buffer>, bool) {
+        let base_text = diff.base_text();
+        if let Some(base_buffer) = self.base_buffers.get(&buffer.remote_id())
+            && base_buffer.base_text_id == base_text.remote_id()
+            && base_buffer.base_text_version == *base_text.version()
+        {
+            return (base_buffer.buffer.clone(), false);
+        }
+
+        let language_registry = self
+            .editor
+            .read(cx)
+            .project()
+            .map(|project| project.read(cx).languages().clone());
+        let base_buffer = if let Some(base_buffer) = self.base_buffers.get(&buffer.remote_id()) {
+            base_buffer.buffer.clone()
+        } else {
+            let base_buffer = cx.new(|cx| {
+                let mut base_buffer = Buffer::local("", cx);
+                base_buffer.set_capability(Capability::ReadOnly, cx);
+                if let Some(file) = buffer.file() {
+                    base_buffer.file_updated(file.clone(), cx);
+                }
+                base_buffer
+            });
+            if hide_header {
+                let base_buffer_id = base_buffer.read(cx).remote_id();
+                self.base_editor.update(cx, |base_editor, cx| {
+                    base_editor.disable_header_for_buffer(base_buffer_id, cx);
+                });
+            }
+            base_buffer
+        };
+        base_buffer.update(cx, |base_buffer, cx| {
+            if let Some(language_registry) = language_registry {
+                base_buffer.set_language_registry(language_registry);
+            }
+            base_buffer.set_text(base_text.text(), cx);
+            base_buffer.set_language(base_text.language().cloned(), cx);
+        });
+        self.base_buffers.insert(
+            buffer.remote_id(),
+            BaseBuffer {
+                buffer: base_buffer.clone(),
+                base_text_id: base_text.remote_id(),
+                base_text_version: base_text.version().clone(),
+            },
+        );
+        (base_buffer, true)
+    }
+
+    /// Recomputes the filler blocks and hunk highlights of both panes.
+    fn refresh_decorations(&mut self, snapshot: &MultiBufferSnapshot, cx: &mut Context<Self>) {
+        let base_snapshot = self.base_multibuffer.read(cx).snapshot(cx);
+
+        let mut blocks = Vec::new();
+        let mut base_blocks = Vec::new();
+        let mut row_highlights = Vec::new();
+        let mut base_row_highlights = Vec::new();
+        let mut word_highlights = Vec::new();
+        let mut base_word_highlights = Vec::new();
+        for pair in &self.excerpts {
+            let Some(buffer) = snapshot.buffer_for_excerpt(pair.excerpt_id) else {
+                continue;
+            };
+            let base_buffer = pair.base_buffer.read(cx).snapshot();
+            let anchor = |point: Point| {
+                snapshot.anchor_in_excerpt(pair.excerpt_id, buffer.anchor_before(point))
+            };
+            let base_anchor = |point: Point| {
+                base_snapshot
+                    .anchor_in_excerpt(pair.base_excerpt_id, base_buffer.anchor_before(point))
+            };
+
+            let hunks = pair
+                .hunks
+                .iter()
+                .skip_while(|hunk| {
+                    hunk.rows.start < pair.rows.start && hunk.rows.end <= pair.rows.start
+                })
+                .map(|hunk| HunkRows {
+                    rows: clamp_rows(&hunk.rows, &pair.rows),
+                    base_rows: clamp_rows(&hunk.base_rows, &pair.base_rows),
+                })
+                .collect::<Vec<_>>();
+
+            let (fillers, base_fillers) = filler_blocks(&pair.rows, &pair.base_rows, &hunks);
+            for (placement, height) in fillers {
+                if let Some(position) = anchor(Point::new(*placement.start(), 0)) {
+                    blocks.push(filler_block(placement.map(|_| position), height));
+                }
+            }
+            for (placement, height) in base_fillers {
+                if let Some(position) = base_anchor(Point::new(*placement.start(), 0)) {
+                    base_blocks.push(filler_block(placement.map(|_| position), height));
+                }
+            }
+
+            for hunk in &hunks {
+                if let Some(range) = rows_to_range(&hunk.rows, buffer)
+                    .and_then(|range| Some(anchor(range.start)?..anchor(range.end)?))
+                {
+                    row_highlights.push(range);
+                }
+                if let Some(range) = rows_to_range(&hunk.base_rows, &base_buffer)
+                    .and_then(|range| Some(base_anchor(range.start)?..base_anchor(range.end)?))
+                {
+                    base_row_highlights.push(range);
+                }
+
+                if hunk.rows.is_empty()
+                    || hunk.base_rows.is_empty()
+                    || hunk.rows.end - hunk.rows.start > MAX_WORD_DIFF_ROWS
+                    || hunk.base_rows.end - hunk.base_rows.start > MAX_WORD_DIFF_ROWS
+                {
+                    continue;
+                }
+                let Some((range, base_range)) = rows_to_range(&hunk.rows, buffer)
+                    .zip(rows_to_range(&hunk.base_rows, &base_buffer))
+                else {
+                    continue;
+                };
+                let offset = range.start.to_offset(buffer);
+                let base_offset = base_range.start.to_offset(&base_buffer);
+                let text = buffer.text_for_range(range).collect::<String>();
+                let base_text = base_buffer.text_for_range(base_range).collect::<String>();
+                for (word_range, base_word_range) in word_diff(&base_text, &text) {
+                    if !word_range.is_empty()
+                        && let Some(start) =
+                            anchor(buffer.offset_to_point(offset + word_range.start))
+                        && let Some(end) = anchor(buffer.offset_to_point(offset + word_range.end))
+                    {
+                        word_highlights.push(start..end);
+                    }
+                    if !base_word_range.is_empty()
+                        && let Some(start) = base_anchor(
+                            base_buffer.offset_to_point(base_offset + base_word_range.start),
+                        )
+                        && let Some(end) = base_anchor(
+                            base_buffer.offset_to_point(base_offset + base_word_range.end),
+                        )
+                    {
+                        base_word_highlights.push(start..end);
+                    }
+                }
+            }
+        }
+
+        let added_background = cx.theme().status().created_background;
+        let deleted_background = cx.theme().status().deleted_background;
+        let options = RowHighlightOptions {
+            include_gutter: true,
+            ..Default::default()
+        };
+
+        let old_blocks = self.blocks.drain(..).collect();
+        self.blocks = self.editor.update(cx, |editor, cx| {
+            editor.remove_blocks(old_blocks, None, cx);
+            editor.clear_row_highlights::<SplitDiffRows>();
+            for range in row_highlights {
+                editor.highlight_rows::<SplitDiffRows>(range, added_background, options, cx);
+            }
+            editor.highlight_background::<SplitDiffWords>(
+                &word_highlights,
+                |theme| theme.colors().version_control_added.opacity(0.3),
+                cx,
+            );
+            editor.insert_blocks(blocks, None, cx)
+        });
+
+        let old_base_blocks = self.base_blocks.drain(..).collect();
+        self.base_blocks = self.base_editor.update(cx, |base_editor, cx| {
+            base_editor.remove_blocks(old_base_blocks, None, cx);
+            base_editor.clear_row_highlights::<SplitDiffRows>();
+            for range in base_row_highlights {
+                base_editor.highlight_rows::<SplitDiffRows>(range, deleted_background, options, cx);
+            }
+            base_editor.highlight_background::<SplitDiffWords>(
+                &base_word_highlights,
+                |theme| theme.colors().version_control_deleted.opacity(0.3),
+                cx,
+            );
+            base_editor.insert_blocks(base_blocks, None, cx)
+        });
+    }
+
+    /// Copies the scroll position of one pane onto the other.
+    fn sync_scroll_position(
+        &mut self,
+        from_base: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (source, target) = if from_base {
+            (&self.base_editor, &self.editor)
+        } else {
+            (&self.editor, &self.base_editor)
+        };
+        let position = source.update(cx, |editor, cx| editor.scroll_position(cx));
+        target.update(cx, |editor, cx| {
+            if editor.scroll_position(cx) != position {
+                editor.set_scroll_position(position, window, cx);
+            }
+        });
+    }
+
+    /// Runs a hunk action on the primary editor for the hunks selected in the
+    /// left pane. Actions dispatched from the right pane are left untouched.
+    fn forward_from_base(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        action: impl FnOnce(&mut Editor, &mut Window, &mut Context<Editor>),
+    ) {
+        if !self
+            .base_editor
+            .focus_handle(cx)
+            .contains_focused(window, cx)
+        {
+            return;
+        }
+        cx.stop_propagation();
+
+        let snapshot = self.editor.read(cx).buffer().read(cx).snapshot(cx);
+        let base_snapshot = self.base_multibuffer.read(cx).snapshot(cx);
+        let selections = self.base_editor.update(cx, |base_editor, cx| {
+            base_editor.selections.all::<Point>(cx)
+        });
+        let ranges = selections
+            .into_iter()
+            .filter_map(|selection| {
+                let (_, start, base_excerpt_id) =
+                    base_snapshot.point_to_buffer_point(selection.start)?;
+                let (_, end, _) = base_snapshot.point_to_buffer_point(selection.end)?;
+                let pair = self
+                    .excerpts
+                    .iter()
+                    .find(|pair| pair.base_excerpt_id == base_excerpt_id)?;
+                let buffer = snapshot.buffer_for_excerpt(pair.excerpt_id)?;
+                let start_row = map_row(start.row, &pair.hunks, true, Bias::Left)
+                    .clamp(pair.rows.start, pair.rows.end - 1);
+                let end_row = map_row(end.row, &pair.hunks, true, Bias::Left)
+                    .clamp(start_row, pair.rows.end - 1);
+                let start = buffer.anchor_before(Point::new(start_row, 0));
+                let end = buffer.anchor_after(Point::new(end_row, buffer.line_len(end_row)));
+                Some(
+                    snapshot.anchor_in_excerpt(pair.excerpt_id, start)?
+                        ..snapshot.anchor_in_excerpt(pair.excerpt_id, end)?,
+                )
+            })
+            .collect::<Vec<_>>();
+        if ranges.is_empty() {
+            return;
+        }
+
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |selections| {
+                selections.select_anchor_ranges(ranges)
+            });
+            action(editor, window, cx);
+        });
+    }
+
+    fn toggle_staged(
+        &mut self,
+        action: &ToggleStaged,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.forward_from_base(window, cx, |editor, window, cx| {
+            editor.toggle_staged_selected_diff_hunks(action, window, cx)
+        });
+    }
+
+    fn stage_and_next(
+        &mut self,
+        action: &StageAndNext,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.forward_from_base(window, cx, |editor, window, cx| {
+            editor.stage_and_next(action, window, cx)
+        });
+    }
+
+    fn unstage_and_next(
+        &mut self,
+        action: &UnstageAndNext,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.forward_from_base(window, cx, |editor, window, cx| {
+            editor.unstage_and_next(action, window, cx)
+        });
+    }
+
+    fn restore(&mut self, action: &Restore, window: &mut Window, cx: &mut Context<Self>) {
+        self.forward_from_base(window, cx, |editor, window, cx| {
+            editor.git_restore(action, window, cx)
+        });
+    }
+}
+
+/// Converts a point range into the range of rows it spans, counting a
+/// trailing partial line.
+fn row_range(range: Range<Point>) -> Range<u32> {
+    let end = if range.end.column > 0 {
+        range.end.row + 1
+    } else {
+        range.end.row
+    };
+    range.start.row..end.max(range.start.row)
+}
+
+fn clamp_rows(rows: &Range<u32>, bounds: &Range<u32>) -> Range<u32> {
+    let start = rows.start.clamp(bounds.start, bounds.end);
+    start..rows.end.clamp(start, bounds.end)
+}
+
+fn rows_to_range(rows: &Range<u32>, buffer: &BufferSnapshot) -> Option<Range<Point>> {
+    if rows.is_empty() || rows.start > buffer.max_point().row {
+        return None;
+    }
+    let end_row = (rows.end - 1).min(buffer.max_point().row);
+    Some(Point::new(rows.start, 0)..Point::new(end_row, buffer.line_len(end_row)))
+}
+
+fn filler_block(
+    placement: BlockPlacement<editor::Anchor>,
+    height: u32,
+) -> BlockProperties<editor::Anchor> {
+    BlockProperties {
+        placement,
+        height: Some(height),
+        style: BlockStyle::Fixed,
+        render: Arc::new(|cx: &mut BlockContext| {
+            div()
+                .size_full()
+                .bg(cx.theme().colors().editor_subheader_background.opacity(0.5))
+                .into_any_element()
+        }),
+        priority: 0,
+    }
+}
+
+/// Maps a row on one side of a diff to the corresponding row on the other side.
+///
+/// Rows inside a hunk map to the start or end of the hunk's counterpart,
+/// depending on `bias`.
+fn map_row(row: u32, hunks: &[HunkRows], from_base: bool, bias: Bias) -> u32 {
+    let mut delta = 0i64;
+    for hunk in hunks {
+        let (from, to) = if from_base {
+            (&hunk.base_rows, &hunk.rows)
+        } else {
+            (&hunk.rows, &hunk.base_rows)
+        };
+        if row <= from.start {
+            break;
+        }
+        if row < from.end {
+            return match bias {
+                Bias::Left => to.start,
+                Bias::Right => to.end,
+            };
+        }
+        delta = to.end as i64 - from.end as i64;
+    }
+    (row as i64 + delta).max(0) as u32
+}
+
+/// Computes the filler blocks needed to align an excerpt with its base text
+/// counterpart, returned as `(placement, height)` pairs for each side.
+///
+/// Both row ranges are treated as showing at least one row, as an excerpt over
+/// an empty range still occupies a line.
+fn filler_blocks(
+    rows: &Range<u32>,
+    base_rows: &Range<u32>,
+    hunks: &[HunkRows],
+) -> (
+    Vec<(BlockPlacement<u32>, u32)>,
+    Vec<(BlockPlacement<u32>, u32)>,
+) {
+    let rows = rows.start..rows.end.max(rows.start + 1);
+    let base_rows = base_rows.start..base_rows.end.max(base_rows.start + 1);
+
+    let mut fillers = Vec::new();
+    let mut base_fillers = Vec::new();
+    let mut row = rows.start;
+    let mut base_row = base_rows.start;
+    let boundaries = hunks
+        .iter()
+        .flat_map(|hunk| {
+            [
+                (hunk.rows.start, hunk.base_rows.start),
+                (hunk.rows.end, hunk.base_rows.end),
+            ]
+        })
+        .chain([(rows.end, base_rows.end)]);
+    for (end, base_end) in boundaries {
+        let end = end.clamp(row, rows.end);
+        let base_end = base_end.clamp(base_row, base_rows.end);
+        let (len, base_len) = (end - row, base_end - base_row);
+        if len > base_len {
+            base_fillers.push((
+                filler_placement(base_row..base_end, &base_rows),
+                len - base_len,
+            ));
+        } else if base_len > len {
+            fillers.push((filler_placement(row..end, &rows), base_len - len));
+        }
+        row = end;
+        base_row = base_end;
+    }
+    (fillers, base_fillers)
+}
+
+fn filler_placement(segment: Range<u32>, rows: &Range<u32>) -> BlockPlacement<u32> {
+    if !segment.is_empty() {
+        BlockPlacement::Below(segment.end - 1)
+    } else if segment.start < rows.end {
+        BlockPlacement::Above(segment.start)
+    } else {
+        BlockPlacement::Below(rows.end - 1)
+    }
+}
+
+/// Returns the changed byte ranges of a small hunk as `(new, old)` pairs.
+fn word_diff(old_text: &str, new_text: &str) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut delta = 0isize;
+    language::text_diff(old_text, new_text)
+        .into_iter()
+        .map(|(old_range, replacement)| {
+            let start = (old_range.start as isize + delta) as usize;
+            delta += replacement.len() as isize - old_range.len() as isize;
+            (start..start + replacement.len(), old_range)
+        })
+        .collect()
+}
+
+impl Focusable for SplitDiff {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for SplitDiff {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .capture_action(cx.listener(Self::toggle_staged))
+            .capture_action(cx.listener(Self::stage_and_next))
+            .capture_action(cx.listener(Self::unstage_and_next))
+            .capture_action(cx.listener(Self::restore))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .h_full()
+                    .child(self.base_editor.clone()),
+            )
+            .child(div().w_px().h_full().bg(cx.theme().colors().border_variant))
+            .child(div().flex_1().min_w_0().h_full().child(self.editor.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer_diff::BufferDiff;
+    use gpui::{TestAppContext, VisualTestContext};
+    use settings::SettingsStore;
+    use std::any::TypeId;
+
+    fn hunk(rows: Range<u32>, base_rows: Range<u32>) -> HunkRows {
+        HunkRows { rows, base_rows }
+    }
+
+    #[test]
+    fn test_map_row() {
+        // Row 2 was modified into rows 2..4, and base rows 6..8 were deleted.
+        let hunks = [hunk(2..4, 2..3), hunk(7..7, 6..8)];
+
+        assert_eq!(map_row(0, &hunks, false, Bias::Left), 0);
+        assert_eq!(map_row(2, &hunks, false, Bias::Left), 2);
+        assert_eq!(map_row(3, &hunks, false, Bias::Left), 2);
+        assert_eq!(map_row(3, &hunks, false, Bias::Right), 3);
+        assert_eq!(map_row(4, &hunks, false, Bias::Left), 3);
+        assert_eq!(map_row(7, &hunks, false, Bias::Left), 6);
+        assert_eq!(map_row(8, &hunks, false, Bias::Left), 9);
+
+        assert_eq!(map_row(3, &hunks, true, Bias::Left), 4);
+        assert_eq!(map_row(6, &hunks, true, Bias::Left), 7);
+        assert_eq!(map_row(7, &hunks, true, Bias::Left), 7);
+        assert_eq!(map_row(7, &hunks, true, Bias::Right), 7);
+        assert_eq!(map_row(8, &hunks, true, Bias::Left), 7);
+        assert_eq!(map_row(10, &hunks, true, Bias::Left), 9);
+    }
+
+    #[test]
+    fn test_filler_blocks() {
+        let hunks = [hunk(2..4, 2..3), hunk(7..7, 6..8)];
+        assert_eq!(
+            filler_blocks(&(0..10), &(0..11), &hunks),
+            (
+                vec![(BlockPlacement::Above(7), 2)],
+                vec![(BlockPlacement::Below(2), 1)],
+            )
+        );
+
+        // A newly added file is compared against an empty base text.
+        assert_eq!(
+            filler_blocks(&(0..3), &(0..0), &[hunk(0..3, 0..0)]),
+            (
+                vec![(BlockPlacement::Below(2), 1)],
+                vec![(BlockPlacement::Above(0), 3)],
+            )
+        );
+
+        // Deletions at the end of an excerpt are padded below its last row.
+        assert_eq!(
+            filler_blocks(&(0..2), &(0..4), &[hunk(2..2, 2..4)]),
+            (vec![(BlockPlacement::Below(1), 2)], vec![])
+        );
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            project::Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_split_diff_aligns_hunks(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let buffer = cx.new(|cx| Buffer::local("one\nTWO\nextra\nthree\n", cx));
+        let diff =
+            cx.new(|cx| BufferDiff::new_with_base_text("one\ntwo\nthree\nfour\n", &buffer, cx));
+        let (split_diff, cx) = cx.add_window_view(|window, cx| {
+            let multibuffer = cx.new(|cx| {
+                let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+                multibuffer.add_diff(diff.clone(), cx);
+                multibuffer
+            });
+            let editor = cx.new(|cx| Editor::for_multibuffer(multibuffer, None, window, cx));
+            SplitDiff::new(editor, window, cx)
+        });
+        cx.run_until_parked();
+
+        let (editor, base_editor) = split_diff.read_with(cx, |split_diff, _| {
+            (split_diff.editor.clone(), split_diff.base_editor.clone())
+        });
+        let pane_state = |editor: &Entity<Editor>, cx: &mut VisualTestContext| {
+            editor.update_in(cx, |editor, window, cx| {
+                let highlighted_rows = editor
+                    .highlighted_display_rows(window, cx)
+                    .into_iter()
+                    .filter(|(_, highlight)| {
+                        highlight.type_id == Some(TypeId::of::<SplitDiffRows>())
+                    })
+                    .map(|(row, _)| row.0)
+                    .collect::<Vec<_>>();
+                (editor.display_text(cx), highlighted_rows)
+            })
+        };
+
+        // Fillers pad the modified hunk on the left and the deleted line on the
+        // right, so that the unchanged lines share display rows.
+        let (text, highlighted_rows) = pane_state(&editor, cx);
+        let (base_text, base_highlighted_rows) = pane_state(&base_editor, cx);
+        assert_eq!(text, "one\nTWO\nextra\nthree\n\n");
+        assert_eq!(base_text, "one\ntwo\n\nthree\nfour\n");
+        assert_eq!(highlighted_rows, [1, 2]);
+        assert_eq!(base_highlighted_rows, [1, 4]);
+
+        // Edits to the buffer realign the panes.
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        diff.update(cx, |diff, cx| {
+            diff.recalculate_diff_sync(buffer.read(cx).text_snapshot(), cx)
+        });
+        cx.run_until_parked();
+        let (text, highlighted_rows) = pane_state(&editor, cx);
+        let (base_text, base_highlighted_rows) = pane_state(&base_editor, cx);
+        assert_eq!(text, "zero\none\nTWO\nextra\nthree\n\n");
+        assert_eq!(base_text, "\none\ntwo\n\nthree\nfour\n");
+        assert_eq!(highlighted_rows, [0, 2, 3]);
+        assert_eq!(base_highlighted_rows, [2, 5]);
+    }
+
+    #[test]
+    fn test_word_diff() {
+        assert_eq!(
+            word_diff("let foo = 1;\n", "let bar = 1;\n"),
+            vec![(4..7, 4..7)]
+        );
+    }
+}
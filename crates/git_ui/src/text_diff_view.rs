@@ -1,12 +1,15 @@
 //! TextDiffView currently provides a UI for displaying differences between the clipboard and selected text.
 
+use crate::split_diff::SplitDiff;
 use anyhow::Result;
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer, ToPoint, actions::DiffClipboardWithSelectionData};
 use futures::{FutureExt, select_biased};
+use git::ToggleSplitDiff;
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, Render, Task, Window,
+    FocusHandle, Focusable, InteractiveElement as _, IntoElement, ParentElement as _, Render,
+    Styled as _, Task, Window, div, prelude::FluentBuilder as _,
 };
 use language::{self, Buffer, Point};
 use project::Project;
@@ -29,6 +32,7 @@ use workspace::{
 
 pub struct TextDiffView {
     diff_editor: Entity<Editor>,
+    split_diff: Option<Entity<SplitDiff>>,
     title: SharedString,
     path: Option<SharedString>,
     buffer_changes_tx: watch::Sender<()>,
@@ -165,6 +169,7 @@ impl TextDiffView {
             );
             editor
         });
+        let split_diff = SplitDiff::for_default_style(&diff_editor, window, cx);
 
         let (buffer_changes_tx, mut buffer_changes_rx) = watch::channel(());
 
@@ -203,6 +208,7 @@ impl TextDiffView {
 
         Self {
             diff_editor,
+            split_diff,
             title: format!("Clipboard ↔ {selection_location_title}").into(),
             path: Some(format!("Clipboard ↔ {selection_location_path}").into()),
             buffer_changes_tx,
@@ -228,6 +234,16 @@ impl TextDiffView {
             }),
         }
     }
+
+    fn toggle_split_diff(
+        &mut self,
+        _: &ToggleSplitDiff,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        SplitDiff::toggle(&mut self.split_diff, &self.diff_editor, window, cx);
+        cx.notify();
+    }
 }
 
 fn build_clipboard_buffer(
@@ -438,8 +454,14 @@ pub fn selection_location_text(editor: &Editor, cx: &App) -> Option<String> {
 }
 
 impl Render for TextDiffView {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-        self.diff_editor.clone()
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .on_action(cx.listener(Self::toggle_split_diff))
+            .map(|this| match &self.split_diff {
+                Some(split_diff) => this.child(split_diff.clone()),
+                None => this.child(self.diff_editor.clone()),
+            })
     }
 }
 
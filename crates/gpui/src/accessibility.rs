@@ -0,0 +1,625 @@
+use std::{
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Range,
+    rc::Rc,
+};
+
+use collections::FxHashMap;
+use smallvec::SmallVec;
+
+use crate::{App, Bounds, FocusHandle, FocusId, GlobalElementId, Pixels, SharedString, Window};
+
+/// What kind of control an element is, as reported to assistive technologies
+/// such as screen readers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AccessibilityRole {
+    /// The root of a window's accessibility tree.
+    Window,
+    /// A container that groups related elements.
+    #[default]
+    Group,
+    /// A button that performs an action when clicked.
+    Button,
+    /// A button that toggles between checked and unchecked.
+    CheckBox,
+    /// A button that toggles between on and off.
+    Switch,
+    /// One of a set of mutually exclusive options.
+    RadioButton,
+    /// A tab within a [`AccessibilityRole::TabList`].
+    Tab,
+    /// A set of tabs, only one of which is selected at a time.
+    TabList,
+    /// The content shown for the selected tab.
+    TabPanel,
+    /// A list of items.
+    List,
+    /// An item within a [`AccessibilityRole::List`].
+    ListItem,
+    /// A hierarchical list of items.
+    Tree,
+    /// An item within a [`AccessibilityRole::Tree`].
+    TreeItem,
+    /// A menu of commands.
+    Menu,
+    /// A command within a [`AccessibilityRole::Menu`].
+    MenuItem,
+    /// A bar of controls.
+    Toolbar,
+    /// A piece of static text.
+    Label,
+    /// The heading of a section.
+    Heading,
+    /// A link to a resource.
+    Link,
+    /// An image or icon.
+    Image,
+    /// A single line of editable text.
+    TextInput,
+    /// Multiple lines of editable text, such as a code editor.
+    MultilineTextInput,
+    /// A region whose content can be scrolled.
+    ScrollView,
+    /// A window-like region that asks for the user's attention.
+    Dialog,
+    /// Additional information about another element, shown on hover.
+    Tooltip,
+}
+
+/// An action that assistive technologies can request an element to perform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityAction {
+    /// Activate the element, as if it was clicked.
+    Click,
+    /// Move keyboard focus to the element.
+    Focus,
+    /// Expand the element, such as a tree item or a disclosure.
+    Expand,
+    /// Collapse the element, such as a tree item or a disclosure.
+    Collapse,
+    /// Scroll the element's ancestors so that it becomes visible.
+    ScrollIntoView,
+    /// Open the element's context menu.
+    ShowContextMenu,
+    /// Replace the element's value with [`AccessibilityActionData::Value`].
+    SetValue,
+    /// Select the range in the element's value given by [`AccessibilityActionData::TextSelection`].
+    SetTextSelection,
+}
+
+/// The data that accompanies some [`AccessibilityAction`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessibilityActionData {
+    /// The new value for [`AccessibilityAction::SetValue`].
+    Value(SharedString),
+    /// The new selection for [`AccessibilityAction::SetTextSelection`], as a range
+    /// of UTF-8 bytes in the element's value.
+    TextSelection(Range<usize>),
+}
+
+/// A request from assistive technologies to perform an action on a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessibilityActionRequest {
+    /// The node to perform the action on.
+    pub target: AccessibilityNodeId,
+    /// The action to perform.
+    pub action: AccessibilityAction,
+    /// The data for the action, if it requires any.
+    pub data: Option<AccessibilityActionData>,
+}
+
+/// Identifies a node in a window's accessibility tree. Elements with an id keep the same
+/// node id across frames, so that assistive technologies can track them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccessibilityNodeId(pub u64);
+
+impl AccessibilityNodeId {
+    /// The id of the node representing the window itself.
+    pub const ROOT: Self = Self(0);
+
+    fn derive(seed: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        match hasher.finish() {
+            0 => Self(1),
+            id => Self(id),
+        }
+    }
+}
+
+pub(crate) type AccessibilityActionListener =
+    Rc<dyn Fn(&AccessibilityActionRequest, &mut Window, &mut App)>;
+
+/// Describes an element to assistive technologies. Add one to the tree with
+/// [`Window::with_accessibility_node`], or use [`crate::InteractiveElement::role`] on a div.
+#[derive(Clone, Default)]
+pub struct AccessibilityNode {
+    /// What kind of control this is.
+    pub role: AccessibilityRole,
+    /// The name of the element, such as a button's text.
+    pub label: Option<SharedString>,
+    /// The current value of the element, such as the contents of a text input.
+    pub value: Option<SharedString>,
+    /// A longer description of the element, such as its tooltip.
+    pub description: Option<SharedString>,
+    /// Whether a checkbox, switch or toggle button is checked.
+    pub checked: Option<bool>,
+    /// Whether a tab, list item or tree item is selected.
+    pub selected: Option<bool>,
+    /// Whether a tree item or disclosure is expanded.
+    pub expanded: Option<bool>,
+    /// Whether the element is disabled.
+    pub disabled: bool,
+    /// The selected range in [`AccessibilityNode::value`], in UTF-8 bytes. An empty
+    /// range is the position of the caret.
+    pub text_selection: Option<Range<usize>>,
+    /// The actions this element supports, in addition to [`AccessibilityAction::Focus`]
+    /// when it has a focus handle.
+    pub actions: SmallVec<[AccessibilityAction; 2]>,
+    pub(crate) bounds: Bounds<Pixels>,
+    pub(crate) focus_handle: Option<FocusHandle>,
+    pub(crate) action_listener: Option<AccessibilityActionListener>,
+}
+
+impl AccessibilityNode {
+    /// Creates a node with the given role.
+    pub fn new(role: AccessibilityRole) -> Self {
+        Self {
+            role,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the name of the element.
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the current value of the element.
+    pub fn value(mut self, value: impl Into<SharedString>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Sets a longer description of the element.
+    pub fn description(mut self, description: impl Into<SharedString>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets whether the element is checked.
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    /// Sets whether the element is selected.
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    /// Sets whether the element is expanded.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    /// Sets whether the element is disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Sets the selected range in the element's value, in UTF-8 bytes.
+    pub fn text_selection(mut self, selection: Range<usize>) -> Self {
+        self.text_selection = Some(selection);
+        self
+    }
+
+    /// Associates the element with a focus handle, so that assistive technologies can
+    /// follow and move keyboard focus.
+    pub fn focus_handle(mut self, focus_handle: &FocusHandle) -> Self {
+        self.focus_handle = Some(focus_handle.clone());
+        self
+    }
+
+    /// Declares that the element supports the given action, and calls the listener
+    /// when assistive technologies request any of the element's actions.
+    /// [`AccessibilityAction::Focus`] is handled automatically for elements with a focus handle.
+    pub fn on_action(
+        mut self,
+        action: AccessibilityAction,
+        listener: impl Fn(&AccessibilityActionRequest, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+        let previous = self.action_listener.take();
+        self.action_listener = Some(Rc::new(move |request, window, cx| {
+            if request.action == action {
+                listener(request, window, cx);
+            } else if let Some(previous) = previous.as_ref() {
+                previous(request, window, cx);
+            }
+        }));
+        self
+    }
+
+    /// The bounds of the element in the window.
+    pub fn bounds(&self) -> Bounds<Pixels> {
+        self.bounds
+    }
+
+    /// Whether assistive technologies can move keyboard focus to this element.
+    pub fn is_focusable(&self) -> bool {
+        self.focus_handle.is_some()
+    }
+
+    /// Whether the element supports the given action.
+    pub fn supports_action(&self, action: AccessibilityAction) -> bool {
+        self.actions.contains(&action)
+            || (action == AccessibilityAction::Focus && self.focus_handle.is_some())
+    }
+}
+
+impl fmt::Debug for AccessibilityNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessibilityNode")
+            .field("role", &self.role)
+            .field("label", &self.label)
+            .field("value", &self.value)
+            .field("checked", &self.checked)
+            .field("selected", &self.selected)
+            .field("expanded", &self.expanded)
+            .field("disabled", &self.disabled)
+            .field("text_selection", &self.text_selection)
+            .field("actions", &self.actions)
+            .field("bounds", &self.bounds)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum AccessibilityOperation {
+    Push(AccessibilityNodeId, AccessibilityNode),
+    Pop,
+}
+
+#[derive(Debug)]
+struct AccessibilityEntry {
+    node: AccessibilityNode,
+    parent: Option<AccessibilityNodeId>,
+    children: Vec<AccessibilityNodeId>,
+}
+
+/// The accessibility tree of a window, built while its elements are painted.
+pub struct AccessibilityTree {
+    pub(crate) insertion_history: Vec<AccessibilityOperation>,
+    entries: FxHashMap<AccessibilityNodeId, AccessibilityEntry>,
+    by_focus_id: FxHashMap<FocusId, AccessibilityNodeId>,
+    stack: Vec<AccessibilityNodeId>,
+    focused: Option<FocusId>,
+}
+
+impl Default for AccessibilityTree {
+    fn default() -> Self {
+        let mut entries = FxHashMap::default();
+        entries.insert(
+            AccessibilityNodeId::ROOT,
+            AccessibilityEntry {
+                node: AccessibilityNode::new(AccessibilityRole::Window),
+                parent: None,
+                children: Vec::new(),
+            },
+        );
+        Self {
+            insertion_history: Vec::new(),
+            entries,
+            by_focus_id: FxHashMap::default(),
+            stack: vec![AccessibilityNodeId::ROOT],
+            focused: None,
+        }
+    }
+}
+
+impl fmt::Debug for AccessibilityTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn fmt_node(
+            tree: &AccessibilityTree,
+            id: AccessibilityNodeId,
+            depth: usize,
+            f: &mut fmt::Formatter<'_>,
+        ) -> fmt::Result {
+            let node = tree.node(id).expect("child ids always refer to a node");
+            write!(f, "{:indent$}{:?}", "", node.role, indent = depth * 2)?;
+            if let Some(label) = &node.label {
+                write!(f, " {label:?}")?;
+            }
+            if let Some(value) = &node.value {
+                write!(f, " value={value:?}")?;
+            }
+            writeln!(f)?;
+            for child in tree.children(id) {
+                fmt_node(tree, *child, depth + 1, f)?;
+            }
+            Ok(())
+        }
+
+        fmt_node(self, AccessibilityNodeId::ROOT, 0, f)
+    }
+}
+
+impl AccessibilityTree {
+    pub(crate) fn push(
+        &mut self,
+        global_id: Option<&GlobalElementId>,
+        node: AccessibilityNode,
+    ) -> AccessibilityNodeId {
+        let parent = self.current_parent();
+        let id = match global_id {
+            Some(global_id) => AccessibilityNodeId::derive(global_id),
+            None => AccessibilityNodeId::derive((parent, self.children(parent).len())),
+        };
+        self.insert(id, node)
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.insertion_history.push(AccessibilityOperation::Pop);
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    fn insert(
+        &mut self,
+        mut id: AccessibilityNodeId,
+        node: AccessibilityNode,
+    ) -> AccessibilityNodeId {
+        // Two elements can share an id when one of them was replayed from the previous frame.
+        let mut attempt = 0;
+        while self.entries.contains_key(&id) {
+            attempt += 1;
+            id = AccessibilityNodeId::derive((id, attempt));
+        }
+
+        self.insertion_history
+            .push(AccessibilityOperation::Push(id, node.clone()));
+
+        let parent = self.current_parent();
+        if let Some(focus_handle) = node.focus_handle.as_ref() {
+            self.by_focus_id.insert(focus_handle.id, id);
+        }
+        self.entries.insert(
+            id,
+            AccessibilityEntry {
+                node,
+                parent: Some(parent),
+                children: Vec::new(),
+            },
+        );
+        if let Some(parent) = self.entries.get_mut(&parent) {
+            parent.children.push(id);
+        }
+        self.stack.push(id);
+        id
+    }
+
+    pub(crate) fn replay(&mut self, operations: &[AccessibilityOperation]) {
+        for operation in operations {
+            match operation {
+                AccessibilityOperation::Push(id, node) => {
+                    self.insert(*id, node.clone());
+                }
+                AccessibilityOperation::Pop => self.pop(),
+            }
+        }
+    }
+
+    pub(crate) fn paint_index(&self) -> usize {
+        self.insertion_history.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub(crate) fn set_focus(&mut self, focus: Option<FocusId>) {
+        self.focused = focus;
+    }
+
+    fn current_parent(&self) -> AccessibilityNodeId {
+        self.stack
+            .last()
+            .copied()
+            .unwrap_or(AccessibilityNodeId::ROOT)
+    }
+
+    /// Returns the node with the given id.
+    pub fn node(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        self.entries.get(&id).map(|entry| &entry.node)
+    }
+
+    /// Returns the ids of the children of the given node, in paint order.
+    pub fn children(&self, id: AccessibilityNodeId) -> &[AccessibilityNodeId] {
+        self.entries
+            .get(&id)
+            .map_or(&[], |entry| entry.children.as_slice())
+    }
+
+    /// Returns the id of the parent of the given node.
+    pub fn parent(&self, id: AccessibilityNodeId) -> Option<AccessibilityNodeId> {
+        self.entries.get(&id)?.parent
+    }
+
+    /// Returns the node that has keyboard focus, or the root if no node does.
+    pub fn focus(&self) -> AccessibilityNodeId {
+        self.focused
+            .and_then(|focus_id| self.by_focus_id.get(&focus_id))
+            .copied()
+            .unwrap_or(AccessibilityNodeId::ROOT)
+    }
+
+    /// Iterates over all nodes in the tree, starting with the root, depth-first in paint order.
+    pub fn iter(&self) -> impl Iterator<Item = (AccessibilityNodeId, &AccessibilityNode)> {
+        let mut stack = vec![AccessibilityNodeId::ROOT];
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self.children(id).iter().rev());
+            Some((id, self.node(id)?))
+        })
+    }
+
+    /// Returns the first node with the given role and label.
+    pub fn find(
+        &self,
+        role: AccessibilityRole,
+        label: &str,
+    ) -> Option<(AccessibilityNodeId, &AccessibilityNode)> {
+        self.iter()
+            .find(|(_, node)| node.role == role && node.label.as_deref() == Some(label))
+    }
+
+    /// Returns the number of nodes in the tree, including the root.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the tree only contains the root.
+    pub fn is_empty(&self) -> bool {
+        self.entries.len() == 1
+    }
+}
+
+impl Window {
+    /// Adds a node to the accessibility tree, and calls the given function with that node as
+    /// the parent of any nodes added by child elements. Nodes of elements with an id keep the
+    /// same [`AccessibilityNodeId`] across frames. This method should only be called as part
+    /// of the paint phase of element drawing.
+    pub fn with_accessibility_node<R>(
+        &mut self,
+        global_id: Option<&GlobalElementId>,
+        bounds: Bounds<Pixels>,
+        mut node: AccessibilityNode,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        self.invalidator.debug_assert_paint();
+        node.bounds = bounds;
+        self.next_frame.accessibility.push(global_id, node);
+        let result = f(self);
+        self.next_frame.accessibility.pop();
+        result
+    }
+
+    /// Returns whether an assistive technology, such as a screen reader, is observing this
+    /// window. Elements whose accessibility nodes are costly to describe should only fill
+    /// them in while this is true; the window is redrawn from scratch when it changes.
+    pub fn is_accessibility_active(&self) -> bool {
+        self.accessibility_active
+    }
+
+    /// Returns the accessibility tree of the most recently rendered frame.
+    pub fn accessibility_tree(&self) -> &AccessibilityTree {
+        &self.rendered_frame.accessibility
+    }
+
+    pub(crate) fn dispatch_accessibility_action(
+        &mut self,
+        request: AccessibilityActionRequest,
+        cx: &mut App,
+    ) {
+        let Some(node) = self.rendered_frame.accessibility.node(request.target) else {
+            return;
+        };
+        let focus_handle = node.focus_handle.clone();
+        let listener = node.action_listener.clone();
+
+        if request.action == AccessibilityAction::Focus
+            && let Some(focus_handle) = focus_handle
+        {
+            self.focus(&focus_handle);
+        } else if let Some(listener) = listener {
+            listener(&request, self, cx);
+        }
+        self.refresh();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AccessibilityAction, AccessibilityActionRequest, AccessibilityNodeId, AccessibilityRole,
+        Context, FocusHandle, InteractiveElement, IntoElement, ParentElement, Render,
+        StatefulInteractiveElement, Styled, TestAppContext, Window, div,
+    };
+
+    struct Toolbar {
+        clicks: usize,
+        focus_handle: FocusHandle,
+    }
+
+    impl Render for Toolbar {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .role(AccessibilityRole::Toolbar)
+                .accessibility_label("Actions")
+                .child(
+                    div()
+                        .id("save")
+                        .size_10()
+                        .role(AccessibilityRole::Button)
+                        .accessibility_label("Save")
+                        .track_focus(&self.focus_handle)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.clicks += 1;
+                            cx.notify();
+                        })),
+                )
+                .child(div().id("unlabeled").size_10())
+        }
+    }
+
+    #[gpui::test]
+    fn test_accessibility_tree(cx: &mut TestAppContext) {
+        let (toolbar, cx) = cx.add_window_view(|_, cx| Toolbar {
+            clicks: 0,
+            focus_handle: cx.focus_handle(),
+        });
+
+        let (save_id, toolbar_id) = cx.update(|window, _| {
+            let tree = window.accessibility_tree();
+            assert_eq!(tree.len(), 3);
+            let (toolbar_id, _) = tree.find(AccessibilityRole::Toolbar, "Actions").unwrap();
+            assert_eq!(tree.parent(toolbar_id), Some(AccessibilityNodeId::ROOT));
+            let (save_id, save) = tree.find(AccessibilityRole::Button, "Save").unwrap();
+            assert_eq!(tree.children(toolbar_id), [save_id]);
+            assert!(save.supports_action(AccessibilityAction::Click));
+            assert!(save.supports_action(AccessibilityAction::Focus));
+            assert_eq!(tree.focus(), AccessibilityNodeId::ROOT);
+            (save_id, toolbar_id)
+        });
+
+        cx.simulate_accessibility_action(AccessibilityActionRequest {
+            target: save_id,
+            action: AccessibilityAction::Click,
+            data: None,
+        });
+        assert_eq!(toolbar.read_with(cx, |toolbar, _| toolbar.clicks), 1);
+
+        cx.simulate_accessibility_action(AccessibilityActionRequest {
+            target: save_id,
+            action: AccessibilityAction::Focus,
+            data: None,
+        });
+        cx.update(|window, cx| {
+            assert!(toolbar.read(cx).focus_handle.is_focused(window));
+            let tree = window.accessibility_tree();
+            // Ids are stable across frames.
+            assert_eq!(tree.focus(), save_id);
+            assert_eq!(tree.parent(save_id), Some(toolbar_id));
+        });
+    }
+}
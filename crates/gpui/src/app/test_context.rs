@@ -1,16 +1,20 @@
 use crate::{
-    Action, AnyView, AnyWindowHandle, App, AppCell, AppContext, AsyncApp, AvailableSpace,
-    BackgroundExecutor, BorrowAppContext, Bounds, Capslock, ClipboardItem, DrawPhase, Drawable,
-    Element, Empty, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Modifiers,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels,
-    Platform, Point, Render, Result, Size, Task, TestDispatcher, TestPlatform,
-    TestScreenCaptureSource, TestWindow, TextSystem, VisualContext, Window, WindowBounds,
-    WindowHandle, WindowOptions,
+    AccessibilityActionRequest, Action, AnyView, AnyWindowHandle, App, AppCell, AppContext,
+    AssetSource, AsyncApp, AvailableSpace, BackgroundExecutor, BorrowAppContext, Bounds, Capslock,
+    ClipboardItem, DrawPhase, Drawable, Element, Empty, EventEmitter, ForegroundExecutor, Global,
+    InputEvent, Keystroke, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, NoopTextSystem, Pixels, Platform, PlatformTextSystem, Point,
+    Render, Result, Size, SvgRenderer, Task, TestDispatcher, TestPlatform, TestScreenCaptureSource,
+    TestWindow, TextSystem, VisualContext, Window, WindowBounds, WindowHandle, WindowOptions,
+    current_platform,
 };
 use anyhow::{anyhow, bail};
 use futures::{Stream, StreamExt, channel::oneshot};
+use image::RgbaImage;
 use rand::{SeedableRng, rngs::StdRng};
-use std::{cell::RefCell, future::Future, ops::Deref, rc::Rc, sync::Arc, time::Duration};
+use std::{
+    cell::RefCell, future::Future, ops::Deref, path::PathBuf, rc::Rc, sync::Arc, time::Duration,
+};
 
 /// A TestAppContext is provided to tests created with `#[gpui::test]`, it provides
 /// an implementation of `Context` with additional methods that are useful in tests.
@@ -122,10 +126,33 @@ impl AppContext for TestAppContext {
 impl TestAppContext {
     /// Creates a new `TestAppContext`. Usually you can rely on `#[gpui::test]` to do this for you.
     pub fn build(dispatcher: TestDispatcher, fn_name: Option<&'static str>) -> Self {
+        Self::build_with_text_system(dispatcher, fn_name, Arc::new(NoopTextSystem))
+    }
+
+    /// Creates a new `TestAppContext` that shapes and rasterizes text with the text
+    /// system of the current operating system, so that [`TestAppContext::render_window`]
+    /// produces images that include text. Layout then depends on the fonts available
+    /// on the machine running the test.
+    pub fn build_with_platform_text_system(
+        dispatcher: TestDispatcher,
+        fn_name: Option<&'static str>,
+    ) -> Self {
+        Self::build_with_text_system(dispatcher, fn_name, current_platform(true).text_system())
+    }
+
+    fn build_with_text_system(
+        dispatcher: TestDispatcher,
+        fn_name: Option<&'static str>,
+        platform_text_system: Arc<dyn PlatformTextSystem>,
+    ) -> Self {
         let arc_dispatcher = Arc::new(dispatcher.clone());
         let background_executor = BackgroundExecutor::new(arc_dispatcher.clone());
         let foreground_executor = ForegroundExecutor::new(arc_dispatcher);
-        let platform = TestPlatform::new(background_executor.clone(), foreground_executor.clone());
+        let platform = TestPlatform::with_text_system(
+            background_executor.clone(),
+            foreground_executor.clone(),
+            platform_text_system,
+        );
         let asset_source = Arc::new(());
         let http_client = http_client::FakeHttpClient::with_404_response();
         let text_system = Arc::new(TextSystem::new(platform.text_system()));
@@ -148,6 +175,15 @@ impl TestAppContext {
         Self::build(dispatcher, None)
     }
 
+    /// Replaces the app's asset source, which is empty by default, so that rendered
+    /// windows can include icons and other bundled images.
+    pub fn set_asset_source(&mut self, asset_source: impl AssetSource) {
+        let mut app = self.app.borrow_mut();
+        let asset_source = Arc::new(asset_source);
+        app.asset_source = asset_source.clone();
+        app.svg_renderer = SvgRenderer::new(asset_source);
+    }
+
     /// The name of the test function that created this `TestAppContext`
     pub fn test_function_name(&self) -> Option<&'static str> {
         self.fn_name
@@ -448,6 +484,68 @@ impl TestAppContext {
         .unwrap();
     }
 
+    /// Rasterizes the current contents of the given window on the CPU, at the test
+    /// window's scale factor. This doesn't need a GPU, so it works on any CI machine.
+    ///
+    /// Text is only rendered when the app uses a real text system, see
+    /// [`TestAppContext::build_with_platform_text_system`].
+    pub fn render_window(&mut self, window: AnyWindowHandle) -> RgbaImage {
+        self.run_until_parked();
+        let test_window = self.test_window(window);
+        self.update_window(window, |_, window, _| {
+            test_window.render(&window.rendered_frame.scene)
+        })
+        .unwrap()
+    }
+
+    /// Renders the given window and compares it with the `<name>.png` snapshot in the
+    /// `test_snapshots` directory of the crate under test.
+    ///
+    /// Missing snapshots are recorded, unless running on CI, and existing ones are
+    /// overwritten when the `UPDATE_SNAPSHOTS` environment variable is set. On a
+    /// mismatch, the rendered image is saved as `<name>.actual.png` for inspection.
+    #[track_caller]
+    pub fn assert_window_snapshot(&mut self, window: AnyWindowHandle, name: &str) {
+        let image = self.render_window(window);
+        let directory = PathBuf::from(
+            std::env::var("CARGO_MANIFEST_DIR").expect("snapshot tests must be run through cargo"),
+        )
+        .join("test_snapshots");
+        let snapshot_path = directory.join(format!("{name}.png"));
+        let actual_path = directory.join(format!("{name}.actual.png"));
+
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() || !snapshot_path.exists() {
+            if std::env::var_os("CI").is_some() && !snapshot_path.exists() {
+                panic!("missing snapshot {snapshot_path:?}, run the test locally to record it");
+            }
+            std::fs::create_dir_all(&directory).unwrap();
+            image.save(&snapshot_path).unwrap();
+            return;
+        }
+
+        let expected = image::open(&snapshot_path)
+            .unwrap_or_else(|error| panic!("failed to load snapshot {snapshot_path:?}: {error}"))
+            .into_rgba8();
+        if expected.dimensions() != image.dimensions() {
+            image.save(&actual_path).unwrap();
+            panic!(
+                "snapshot {name} has size {:?}, but the window rendered at {:?}, see {actual_path:?}",
+                expected.dimensions(),
+                image.dimensions(),
+            );
+        }
+        let differing_pixels = expected
+            .pixels()
+            .zip(image.pixels())
+            .filter(|(expected, actual)| expected != actual)
+            .count();
+        if differing_pixels > 0 {
+            image.save(&actual_path).unwrap();
+            panic!("{differing_pixels} pixels differ from snapshot {name}, see {actual_path:?}");
+        }
+        std::fs::remove_file(actual_path).ok();
+    }
+
     /// Returns the `TestWindow` backing the given handle.
     pub(crate) fn test_window(&self, window: AnyWindowHandle) -> TestWindow {
         self.app
@@ -805,6 +903,33 @@ impl VisualTestContext {
         self.simulate_window_resize(self.window, size)
     }
 
+    /// Rasterizes the current contents of the window, see [`TestAppContext::render_window`].
+    pub fn render_window(&mut self) -> RgbaImage {
+        self.cx.render_window(self.window)
+    }
+
+    /// Compares the window with a PNG snapshot, see [`TestAppContext::assert_window_snapshot`].
+    #[track_caller]
+    pub fn assert_window_snapshot(&mut self, name: &str) {
+        self.cx.assert_window_snapshot(self.window, name)
+    }
+
+    /// Simulates an assistive technology, such as a screen reader, requesting an action
+    /// on a node of the window's accessibility tree.
+    pub fn simulate_accessibility_action(&mut self, request: AccessibilityActionRequest) {
+        self.test_window(self.window)
+            .simulate_accessibility_action(request);
+        self.background_executor.run_until_parked();
+    }
+
+    /// Simulates an assistive technology starting or stopping to observe the window.
+    pub fn simulate_accessibility_activation(&mut self, active: bool) {
+        self.test_window(self.window)
+            .simulate_accessibility_activation(active);
+        self.update(|window, _| window.refresh());
+        self.background_executor.run_until_parked();
+    }
+
     /// debug_bounds returns the bounds of the element with the given selector.
     pub fn debug_bounds(&mut self, selector: &'static str) -> Option<Bounds<Pixels>> {
         self.update(|window, _| window.rendered_frame.debug_bounds.get(selector).copied())
@@ -16,7 +16,8 @@
 //! constructed by combining these two systems into an all-in-one element.
 
 use crate::{
-    AbsoluteLength, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, App, Bounds, ClickEvent,
+    AbsoluteLength, AccessibilityAction, AccessibilityActionRequest, AccessibilityNode,
+    AccessibilityRole, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, App, Bounds, ClickEvent,
     DispatchPhase, Element, ElementId, Entity, FocusHandle, Global, GlobalElementId, Hitbox,
     HitboxBehavior, HitboxId, InspectorElementId, IntoElement, IsZero, KeyContext, KeyDownEvent,
     KeyUpEvent, KeyboardButton, KeyboardClickEvent, LayoutId, ModifiersChangedEvent, MouseButton,
@@ -644,6 +645,70 @@ pub trait InteractiveElement: Sized {
         self
     }
 
+    /// Expose this element to assistive technologies, such as screen readers, with the
+    /// given role. Only elements with a role appear in the window's accessibility tree.
+    fn role(mut self, role: AccessibilityRole) -> Self {
+        self.interactivity().accessibility_node().role = role;
+        self
+    }
+
+    /// Set the name that assistive technologies announce for this element, such as a
+    /// button's text. Elements with a label but no role are exposed as a group.
+    fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.interactivity().accessibility_node().label = Some(label.into());
+        self
+    }
+
+    /// Set the value that assistive technologies announce for this element, such as the
+    /// contents of a text input.
+    fn accessibility_value(mut self, value: impl Into<SharedString>) -> Self {
+        self.interactivity().accessibility_node().value = Some(value.into());
+        self
+    }
+
+    /// Set a longer description of this element for assistive technologies.
+    fn accessibility_description(mut self, description: impl Into<SharedString>) -> Self {
+        self.interactivity().accessibility_node().description = Some(description.into());
+        self
+    }
+
+    /// Report whether this element, such as a checkbox, is checked to assistive technologies.
+    fn accessibility_checked(mut self, checked: bool) -> Self {
+        self.interactivity().accessibility_node().checked = Some(checked);
+        self
+    }
+
+    /// Report whether this element, such as a tab, is selected to assistive technologies.
+    fn accessibility_selected(mut self, selected: bool) -> Self {
+        self.interactivity().accessibility_node().selected = Some(selected);
+        self
+    }
+
+    /// Report whether this element, such as a tree item, is expanded to assistive technologies.
+    fn accessibility_expanded(mut self, expanded: bool) -> Self {
+        self.interactivity().accessibility_node().expanded = Some(expanded);
+        self
+    }
+
+    /// Report whether this element is disabled to assistive technologies.
+    fn accessibility_disabled(mut self, disabled: bool) -> Self {
+        self.interactivity().accessibility_node().disabled = disabled;
+        self
+    }
+
+    /// Handle the given action when assistive technologies request it on this element.
+    /// [`AccessibilityAction::Click`] is handled automatically by invoking the element's
+    /// click listeners, and [`AccessibilityAction::Focus`] by focusing its focus handle.
+    fn on_accessibility_action(
+        mut self,
+        action: AccessibilityAction,
+        listener: impl Fn(&AccessibilityActionRequest, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        let node = self.interactivity().accessibility_node();
+        *node = mem::take(node).on_action(action, listener);
+        self
+    }
+
     /// Set the keymap context for this element. This will be used to determine
     /// which action to dispatch from the keymap.
     fn key_context<C, E>(mut self, key_context: C) -> Self
@@ -1505,6 +1570,7 @@ pub struct Interactivity {
     pub(crate) hitbox_behavior: HitboxBehavior,
     pub(crate) tab_index: Option<isize>,
     pub(crate) tab_group: bool,
+    pub(crate) accessibility: Option<Box<AccessibilityNode>>,
 
     #[cfg(any(feature = "inspector", debug_assertions))]
     pub(crate) source_location: Option<&'static core::panic::Location<'static>>,
@@ -1798,6 +1864,12 @@ impl Interactivity {
                     window.next_frame.tab_stops.insert(focus_handle);
                 }
 
+                let accessibility_node = self.take_accessibility_node(bounds);
+                let has_accessibility_node = accessibility_node.is_some();
+                if let Some(node) = accessibility_node {
+                    window.next_frame.accessibility.push(global_id, node);
+                }
+
                 window.with_element_opacity(style.opacity, |window| {
                     style.paint(bounds, window, cx, |window: &mut Window, cx: &mut App| {
                         window.with_text_style(style.text_style().cloned(), |window| {
@@ -1863,11 +1935,40 @@ impl Interactivity {
                     });
                 });
 
+                if has_accessibility_node {
+                    window.next_frame.accessibility.pop();
+                }
+
                 ((), element_state)
             },
         );
     }
 
+    pub(crate) fn accessibility_node(&mut self) -> &mut AccessibilityNode {
+        self.accessibility.get_or_insert_default()
+    }
+
+    fn take_accessibility_node(&mut self, bounds: Bounds<Pixels>) -> Option<AccessibilityNode> {
+        let mut node = *self.accessibility.take()?;
+        node.bounds = bounds;
+        if node.focus_handle.is_none() {
+            node.focus_handle = self.tracked_focus_handle.clone();
+        }
+        if !self.click_listeners.is_empty() && !node.actions.contains(&AccessibilityAction::Click) {
+            let click_listeners = self.click_listeners.clone();
+            node = node.on_action(AccessibilityAction::Click, move |_, window, cx| {
+                let click_event = ClickEvent::Keyboard(KeyboardClickEvent {
+                    button: KeyboardButton::Enter,
+                    bounds,
+                });
+                for listener in &click_listeners {
+                    listener(&click_event, window, cx);
+                }
+            });
+        }
+        Some(node)
+    }
+
     #[cfg(debug_assertions)]
     fn paint_debug_info(
         &self,
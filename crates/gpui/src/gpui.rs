@@ -70,6 +70,7 @@
 extern crate self as gpui;
 
 #[macro_use]
+mod accessibility;
 mod action;
 mod app;
 
@@ -128,6 +129,7 @@ mod seal {
     pub trait Sealed {}
 }
 
+pub use accessibility::*;
 pub use action::*;
 pub use anyhow::Result;
 pub use app::*;
@@ -36,12 +36,13 @@ mod windows;
 pub(crate) mod scap_screen_capture;
 
 use crate::{
-    Action, AnyWindowHandle, App, AsyncWindowContext, BackgroundExecutor, Bounds,
-    DEFAULT_WINDOW_SIZE, DevicePixels, DispatchEventResult, Font, FontId, FontMetrics, FontRun,
-    ForegroundExecutor, GlyphId, GpuSpecs, ImageSource, Keymap, LineLayout, Pixels, PlatformInput,
-    Point, RenderGlyphParams, RenderImage, RenderImageParams, RenderSvgParams, Scene, ShapedGlyph,
-    ShapedRun, SharedString, Size, SvgRenderer, SvgSize, SystemWindowTab, Task, TaskLabel, Window,
-    WindowControlArea, hash, point, px, size,
+    AccessibilityActionRequest, AccessibilityTree, Action, AnyWindowHandle, App,
+    AsyncWindowContext, BackgroundExecutor, Bounds, DEFAULT_WINDOW_SIZE, DevicePixels,
+    DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, GpuSpecs,
+    ImageSource, Keymap, LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImage,
+    RenderImageParams, RenderSvgParams, Scene, ShapedGlyph, ShapedRun, SharedString, Size,
+    SvgRenderer, SvgSize, SystemWindowTab, Task, TaskLabel, Window, WindowControlArea, hash, point,
+    px, size, visual_runs,
 };
 use anyhow::Result;
 use async_task::Runnable;
@@ -550,6 +551,13 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
 
     fn update_ime_position(&self, _bounds: Bounds<Pixels>);
 
+    // Accessibility methods
+    fn update_accessibility_tree(&self, _tree: &AccessibilityTree) {}
+    fn on_accessibility_action(&self, _callback: Box<dyn FnMut(AccessibilityActionRequest)>) {}
+    fn is_accessibility_active(&self) -> bool {
+        false
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     fn as_test(&mut self) -> Option<&mut TestWindow> {
         None
@@ -674,8 +682,27 @@ impl PlatformTextSystem for NoopTextSystem {
                 .unwrap()
                 .width
             / metrics.units_per_em as f32;
+        let bidi_runs = visual_runs(text);
+        let visual_chars = if bidi_runs.is_empty() {
+            text.char_indices().collect::<Vec<_>>()
+        } else {
+            bidi_runs
+                .iter()
+                .flat_map(|bidi_run| {
+                    let chars = text[bidi_run.range.clone()]
+                        .char_indices()
+                        .map(|(ix, c)| (bidi_run.range.start + ix, c))
+                        .collect::<Vec<_>>();
+                    if bidi_run.rtl {
+                        chars.into_iter().rev().collect()
+                    } else {
+                        chars
+                    }
+                })
+                .collect()
+        };
         let mut glyphs = Vec::new();
-        for (ix, c) in text.char_indices() {
+        for (ix, c) in visual_chars {
             if let Some(glyph) = self.glyph_for_char(FontId(0), c) {
                 glyphs.push(ShapedGlyph {
                     id: glyph,
@@ -709,6 +736,7 @@ impl PlatformTextSystem for NoopTextSystem {
             descent: font_size * (metrics.descent / metrics.units_per_em as f32),
             runs,
             len: text.len(),
+            bidi_runs,
         }
     }
 }
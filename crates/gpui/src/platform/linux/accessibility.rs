@@ -0,0 +1,423 @@
+//! Exposes GPUI's accessibility tree to AT-SPI, so that screen readers such as Orca can
+//! navigate Zed's UI.
+//!
+//! This module uses the [accesskit_unix] crate, which runs the AT-SPI connection on its
+//! own thread. Action requests and activation are forwarded to the main thread over a
+//! channel.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use accesskit::{
+    Action, ActionData, ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, Node,
+    NodeId, Rect, Role, TextPosition, TextSelection, Toggled, Tree, TreeUpdate,
+};
+use collections::HashMap;
+
+use crate::{
+    AccessibilityAction, AccessibilityActionData, AccessibilityActionRequest, AccessibilityNode,
+    AccessibilityNodeId, AccessibilityRole, AccessibilityTree, Bounds, ForegroundExecutor, Pixels,
+    Task,
+};
+
+enum Event {
+    Activated,
+    Deactivated,
+    Action(ActionRequest),
+}
+
+struct Handler(flume::Sender<Event>);
+
+impl ActivationHandler for Handler {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        // The tree is only available on the main thread, so ask it for a frame instead.
+        self.0.send(Event::Activated).ok();
+        None
+    }
+}
+
+impl ActionHandler for Handler {
+    fn do_action(&mut self, request: ActionRequest) {
+        self.0.send(Event::Action(request)).ok();
+    }
+}
+
+impl DeactivationHandler for Handler {
+    fn deactivate_accessibility(&mut self) {
+        self.0.send(Event::Deactivated).ok();
+    }
+}
+
+/// The text of each text input in the last tree sent to AT-SPI, used to translate
+/// text positions in action requests back into byte offsets.
+type TextInputs = HashMap<NodeId, (AccessibilityNodeId, String)>;
+
+pub(crate) struct LinuxAccessibility {
+    adapter: RefCell<accesskit_unix::Adapter>,
+    action_callback: Rc<RefCell<Option<Box<dyn FnMut(AccessibilityActionRequest)>>>>,
+    text_inputs: Rc<RefCell<TextInputs>>,
+    active: Rc<Cell<bool>>,
+    _events: Task<()>,
+}
+
+impl LinuxAccessibility {
+    /// Creates the adapter for a window. `request_frame` is called when a screen reader
+    /// starts or stops observing the window, and should draw it so that the tree gets sent.
+    pub fn new(executor: &ForegroundExecutor, request_frame: impl Fn() + 'static) -> Self {
+        let (sender, receiver) = flume::unbounded();
+        let adapter = accesskit_unix::Adapter::new(
+            Handler(sender.clone()),
+            Handler(sender.clone()),
+            Handler(sender),
+        );
+
+        let action_callback: Rc<RefCell<Option<Box<dyn FnMut(AccessibilityActionRequest)>>>> =
+            Rc::default();
+        let text_inputs: Rc<RefCell<TextInputs>> = Rc::default();
+        let active = Rc::new(Cell::new(false));
+        let events = executor.spawn({
+            let action_callback = action_callback.clone();
+            let text_inputs = text_inputs.clone();
+            let active = active.clone();
+            async move {
+                while let Ok(event) = receiver.recv_async().await {
+                    match event {
+                        Event::Activated => {
+                            active.set(true);
+                            request_frame();
+                        }
+                        Event::Deactivated => {
+                            active.set(false);
+                            request_frame();
+                        }
+                        Event::Action(request) => {
+                            let Some(request) =
+                                from_accesskit_request(request, &text_inputs.borrow())
+                            else {
+                                continue;
+                            };
+                            let callback = action_callback.borrow_mut().take();
+                            if let Some(mut callback) = callback {
+                                callback(request);
+                                action_callback.borrow_mut().get_or_insert(callback);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            adapter: RefCell::new(adapter),
+            action_callback,
+            text_inputs,
+            active,
+            _events: events,
+        }
+    }
+
+    pub fn on_action(&self, callback: Box<dyn FnMut(AccessibilityActionRequest)>) {
+        *self.action_callback.borrow_mut() = Some(callback);
+    }
+
+    /// Returns whether a screen reader is observing the window.
+    pub fn is_active(&self) -> bool {
+        self.active.get()
+    }
+
+    /// Sends the tree to AT-SPI. The tree is only converted while a screen reader is active.
+    pub fn update(&self, tree: &AccessibilityTree, scale_factor: f32) {
+        self.adapter.borrow_mut().update_if_active(|| {
+            let (update, text_inputs) = to_tree_update(tree, scale_factor);
+            *self.text_inputs.borrow_mut() = text_inputs;
+            update
+        });
+    }
+
+    pub fn set_focused(&self, focused: bool) {
+        self.adapter.borrow_mut().update_window_focus_state(focused);
+    }
+
+    /// Tells AT-SPI where the window is on screen, in device pixels.
+    pub fn set_window_bounds(&self, outer: Bounds<i32>, inner: Bounds<i32>) {
+        self.adapter
+            .borrow_mut()
+            .set_root_window_bounds(to_rect(outer.map(f64::from)), to_rect(inner.map(f64::from)));
+    }
+}
+
+fn to_rect(bounds: Bounds<f64>) -> Rect {
+    Rect::new(
+        bounds.origin.x,
+        bounds.origin.y,
+        bounds.origin.x + bounds.size.width,
+        bounds.origin.y + bounds.size.height,
+    )
+}
+
+fn to_device_rect(bounds: Bounds<Pixels>, scale_factor: f32) -> Rect {
+    to_rect(bounds.map(|pixels| f64::from(pixels.0 * scale_factor)))
+}
+
+fn node_id(id: AccessibilityNodeId) -> NodeId {
+    NodeId(id.0)
+}
+
+/// Text inputs expose their value through a single text run child, so that screen readers
+/// can read the text around the caret.
+fn text_run_id(id: AccessibilityNodeId) -> NodeId {
+    NodeId(!id.0)
+}
+
+fn to_tree_update(tree: &AccessibilityTree, scale_factor: f32) -> (TreeUpdate, TextInputs) {
+    let mut nodes = Vec::with_capacity(tree.len());
+    let mut text_inputs = TextInputs::default();
+
+    for (id, node) in tree.iter() {
+        let mut accesskit_node = Node::new(to_accesskit_role(node.role));
+        let bounds = to_device_rect(node.bounds(), scale_factor);
+        if id != AccessibilityNodeId::ROOT {
+            accesskit_node.set_bounds(bounds);
+        }
+        if let Some(label) = node.label.as_ref() {
+            accesskit_node.set_label(label.as_ref());
+        }
+        if let Some(description) = node.description.as_ref() {
+            accesskit_node.set_description(description.as_ref());
+        }
+        if let Some(checked) = node.checked {
+            accesskit_node.set_toggled(if checked {
+                Toggled::True
+            } else {
+                Toggled::False
+            });
+        }
+        if let Some(selected) = node.selected {
+            accesskit_node.set_selected(selected);
+        }
+        if let Some(expanded) = node.expanded {
+            accesskit_node.set_expanded(expanded);
+        }
+        if node.disabled {
+            accesskit_node.set_disabled();
+        }
+        for action in &node.actions {
+            accesskit_node.add_action(to_accesskit_action(*action));
+        }
+        if node.is_focusable() {
+            accesskit_node.add_action(Action::Focus);
+        }
+
+        let mut children = tree
+            .children(id)
+            .iter()
+            .copied()
+            .map(node_id)
+            .collect::<Vec<_>>();
+        if is_text_input(node) {
+            let value = node.value.as_deref().unwrap_or_default();
+            let run_id = text_run_id(id);
+            children.insert(0, run_id);
+            nodes.push((run_id, text_run(value, bounds)));
+            if let Some(selection) = node.text_selection.as_ref() {
+                accesskit_node.set_text_selection(TextSelection {
+                    anchor: TextPosition {
+                        node: run_id,
+                        character_index: character_index(value, selection.start),
+                    },
+                    focus: TextPosition {
+                        node: run_id,
+                        character_index: character_index(value, selection.end),
+                    },
+                });
+            }
+            text_inputs.insert(run_id, (id, value.to_string()));
+            text_inputs.insert(node_id(id), (id, value.to_string()));
+        } else if let Some(value) = node.value.as_ref() {
+            accesskit_node.set_value(value.as_ref());
+        }
+        accesskit_node.set_children(children);
+        nodes.push((node_id(id), accesskit_node));
+    }
+
+    let mut accesskit_tree = Tree::new(node_id(AccessibilityNodeId::ROOT));
+    accesskit_tree.toolkit_name = Some("GPUI".to_string());
+    accesskit_tree.toolkit_version = Some(env!("CARGO_PKG_VERSION").to_string());
+
+    (
+        TreeUpdate {
+            nodes,
+            tree: Some(accesskit_tree),
+            focus: node_id(tree.focus()),
+        },
+        text_inputs,
+    )
+}
+
+fn is_text_input(node: &AccessibilityNode) -> bool {
+    matches!(
+        node.role,
+        AccessibilityRole::TextInput | AccessibilityRole::MultilineTextInput
+    )
+}
+
+fn text_run(value: &str, bounds: Rect) -> Node {
+    let mut node = Node::new(Role::TextRun);
+    node.set_bounds(bounds);
+    node.set_value(value);
+    node.set_character_lengths(
+        value
+            .chars()
+            .map(|c| c.len_utf8() as u8)
+            .collect::<Vec<_>>(),
+    );
+    node
+}
+
+fn character_index(text: &str, byte_offset: usize) -> usize {
+    text.char_indices()
+        .take_while(|(ix, _)| *ix < byte_offset)
+        .count()
+}
+
+fn byte_offset(text: &str, character_index: usize) -> usize {
+    text.char_indices()
+        .nth(character_index)
+        .map_or(text.len(), |(ix, _)| ix)
+}
+
+fn from_accesskit_request(
+    request: ActionRequest,
+    text_inputs: &TextInputs,
+) -> Option<AccessibilityActionRequest> {
+    let action = match request.action {
+        Action::Click => AccessibilityAction::Click,
+        Action::Focus => AccessibilityAction::Focus,
+        Action::Expand => AccessibilityAction::Expand,
+        Action::Collapse => AccessibilityAction::Collapse,
+        Action::ScrollIntoView => AccessibilityAction::ScrollIntoView,
+        Action::ShowContextMenu => AccessibilityAction::ShowContextMenu,
+        Action::SetValue => AccessibilityAction::SetValue,
+        Action::SetTextSelection => AccessibilityAction::SetTextSelection,
+        _ => return None,
+    };
+
+    // Requests on a text run are performed by the text input that owns it.
+    let (target, text) = match text_inputs.get(&request.target) {
+        Some((target, text)) => (*target, Some(text)),
+        None => (AccessibilityNodeId(request.target.0), None),
+    };
+
+    let data = match request.data {
+        Some(ActionData::Value(value)) => {
+            Some(AccessibilityActionData::Value(String::from(value).into()))
+        }
+        Some(ActionData::SetTextSelection(selection)) => {
+            let text = text?;
+            let anchor = byte_offset(text, selection.anchor.character_index);
+            let focus = byte_offset(text, selection.focus.character_index);
+            Some(AccessibilityActionData::TextSelection(
+                anchor.min(focus)..anchor.max(focus),
+            ))
+        }
+        _ => None,
+    };
+
+    Some(AccessibilityActionRequest {
+        target,
+        action,
+        data,
+    })
+}
+
+fn to_accesskit_action(action: AccessibilityAction) -> Action {
+    match action {
+        AccessibilityAction::Click => Action::Click,
+        AccessibilityAction::Focus => Action::Focus,
+        AccessibilityAction::Expand => Action::Expand,
+        AccessibilityAction::Collapse => Action::Collapse,
+        AccessibilityAction::ScrollIntoView => Action::ScrollIntoView,
+        AccessibilityAction::ShowContextMenu => Action::ShowContextMenu,
+        AccessibilityAction::SetValue => Action::SetValue,
+        AccessibilityAction::SetTextSelection => Action::SetTextSelection,
+    }
+}
+
+fn to_accesskit_role(role: AccessibilityRole) -> Role {
+    match role {
+        AccessibilityRole::Window => Role::Window,
+        AccessibilityRole::Group => Role::Group,
+        AccessibilityRole::Button => Role::Button,
+        AccessibilityRole::CheckBox => Role::CheckBox,
+        AccessibilityRole::Switch => Role::Switch,
+        AccessibilityRole::RadioButton => Role::RadioButton,
+        AccessibilityRole::Tab => Role::Tab,
+        AccessibilityRole::TabList => Role::TabList,
+        AccessibilityRole::TabPanel => Role::TabPanel,
+        AccessibilityRole::List => Role::List,
+        AccessibilityRole::ListItem => Role::ListItem,
+        AccessibilityRole::Tree => Role::Tree,
+        AccessibilityRole::TreeItem => Role::TreeItem,
+        AccessibilityRole::Menu => Role::Menu,
+        AccessibilityRole::MenuItem => Role::MenuItem,
+        AccessibilityRole::Toolbar => Role::Toolbar,
+        AccessibilityRole::Label => Role::Label,
+        AccessibilityRole::Heading => Role::Heading,
+        AccessibilityRole::Link => Role::Link,
+        AccessibilityRole::Image => Role::Image,
+        AccessibilityRole::TextInput => Role::TextInput,
+        AccessibilityRole::MultilineTextInput => Role::MultilineTextInput,
+        AccessibilityRole::ScrollView => Role::ScrollView,
+        AccessibilityRole::Dialog => Role::Dialog,
+        AccessibilityRole::Tooltip => Role::Tooltip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_positions() {
+        let text = "héllo";
+        assert_eq!(character_index(text, 0), 0);
+        assert_eq!(character_index(text, 3), 2);
+        assert_eq!(character_index(text, text.len()), 5);
+        assert_eq!(byte_offset(text, 2), 3);
+        assert_eq!(byte_offset(text, 5), text.len());
+    }
+
+    #[test]
+    fn test_text_selection_request() {
+        let mut text_inputs = TextInputs::default();
+        text_inputs.insert(NodeId(!7), (AccessibilityNodeId(7), "héllo".to_string()));
+        let request = from_accesskit_request(
+            ActionRequest {
+                action: Action::SetTextSelection,
+                target: NodeId(!7),
+                data: Some(ActionData::SetTextSelection(TextSelection {
+                    anchor: TextPosition {
+                        node: NodeId(!7),
+                        character_index: 4,
+                    },
+                    focus: TextPosition {
+                        node: NodeId(!7),
+                        character_index: 1,
+                    },
+                })),
+            },
+            &text_inputs,
+        )
+        .unwrap();
+        assert_eq!(
+            request,
+            AccessibilityActionRequest {
+                target: AccessibilityNodeId(7),
+                action: AccessibilityAction::SetTextSelection,
+                data: Some(AccessibilityActionData::TextSelection(1..5)),
+            }
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
-    Bounds, DevicePixels, Font, FontFeatures, FontId, FontMetrics, FontRun, FontStyle, FontWeight,
-    GlyphId, LineLayout, Pixels, PlatformTextSystem, Point, RenderGlyphParams, SUBPIXEL_VARIANTS_X,
-    SUBPIXEL_VARIANTS_Y, ShapedGlyph, ShapedRun, SharedString, Size, point, size,
+    BidiRun, Bounds, DevicePixels, Font, FontFeatures, FontId, FontMetrics, FontRun, FontStyle,
+    FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point, RenderGlyphParams,
+    SUBPIXEL_VARIANTS_X, SUBPIXEL_VARIANTS_Y, ShapedGlyph, ShapedRun, SharedString, Size, point,
+    size,
 };
 use anyhow::{Context as _, Ok, Result};
 use collections::HashMap;
@@ -412,7 +413,22 @@ impl CosmicTextSystemState {
         let layout = layout_lines.first().unwrap();
 
         let mut runs: Vec<ShapedRun> = Vec::new();
+        let mut bidi_runs: SmallVec<[BidiRun; 1]> = SmallVec::new();
         for glyph in &layout.glyphs {
+            // cosmic-text has already reordered the glyphs visually, so directional
+            // runs can be recovered from their embedding levels.
+            let rtl = glyph.level.is_rtl();
+            match bidi_runs.last_mut() {
+                Some(bidi_run) if bidi_run.rtl == rtl => {
+                    bidi_run.range.start = bidi_run.range.start.min(glyph.start);
+                    bidi_run.range.end = bidi_run.range.end.max(glyph.end);
+                }
+                _ => bidi_runs.push(BidiRun {
+                    range: glyph.start..glyph.end,
+                    rtl,
+                }),
+            }
+
             let mut font_id = FontId(glyph.metadata);
             let mut loaded_font = self.loaded_font(font_id);
             if loaded_font.font.id() != glyph.font_id {
@@ -453,6 +469,11 @@ impl CosmicTextSystemState {
             descent: layout.max_descent.into(),
             runs,
             len: text.len(),
+            bidi_runs: if bidi_runs.iter().any(|bidi_run| bidi_run.rtl) {
+                bidi_runs
+            } else {
+                SmallVec::new()
+            },
         }
     }
 }
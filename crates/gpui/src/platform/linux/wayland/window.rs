@@ -23,17 +23,21 @@ use wayland_protocols_plasma::blur::client::org_kde_kwin_blur;
 
 use crate::scene::Scene;
 use crate::{
-    AnyWindowHandle, Bounds, Decorations, Globals, GpuSpecs, Modifiers, Output, Pixels,
-    PlatformDisplay, PlatformInput, Point, PromptButton, PromptLevel, RequestFrameOptions,
-    ResizeEdge, Size, Tiling, WaylandClientStatePtr, WindowAppearance, WindowBackgroundAppearance,
-    WindowBounds, WindowControlArea, WindowControls, WindowDecorations, WindowParams, px, size,
+    AccessibilityActionRequest, AccessibilityTree, AnyWindowHandle, Bounds, Decorations, Globals,
+    GpuSpecs, Modifiers, Output, Pixels, PlatformDisplay, PlatformInput, Point, PromptButton,
+    PromptLevel, RequestFrameOptions, ResizeEdge, Size, Tiling, WaylandClientStatePtr,
+    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowControlArea, WindowControls,
+    WindowDecorations, WindowParams, px, size,
 };
 use crate::{
     Capslock,
     platform::{
         PlatformAtlas, PlatformInputHandler, PlatformWindow,
         blade::{BladeContext, BladeRenderer, BladeSurfaceConfig},
-        linux::wayland::{display::WaylandDisplay, serial::SerialKind},
+        linux::{
+            LinuxAccessibility,
+            wayland::{display::WaylandDisplay, serial::SerialKind},
+        },
     },
 };
 
@@ -117,6 +121,7 @@ pub struct WaylandWindowState {
 pub struct WaylandWindowStatePtr {
     state: Rc<RefCell<WaylandWindowState>>,
     callbacks: Rc<RefCell<Callbacks>>,
+    accessibility: Rc<LinuxAccessibility>,
 }
 
 impl WaylandWindowState {
@@ -304,6 +309,20 @@ impl WaylandWindow {
             .as_ref()
             .map(|viewporter| viewporter.get_viewport(&surface, &globals.qh, ()));
 
+        let callbacks = Rc::new(RefCell::new(Callbacks::default()));
+        let accessibility = Rc::new(LinuxAccessibility::new(&globals.executor, {
+            let callbacks = Rc::downgrade(&callbacks);
+            move || {
+                if let Some(callbacks) = callbacks.upgrade()
+                    && let Some(request_frame) = callbacks.borrow_mut().request_frame.as_mut()
+                {
+                    request_frame(RequestFrameOptions {
+                        require_presentation: false,
+                        force_render: true,
+                    });
+                }
+            }
+        }));
         let this = Self(WaylandWindowStatePtr {
             state: Rc::new(RefCell::new(WaylandWindowState::new(
                 handle,
@@ -318,7 +337,8 @@ impl WaylandWindow {
                 gpu_context,
                 params,
             )?)),
-            callbacks: Rc::new(RefCell::new(Callbacks::default())),
+            callbacks,
+            accessibility,
         });
 
         // Kick things off
@@ -724,6 +744,7 @@ impl WaylandWindowStatePtr {
     }
 
     pub fn set_focused(&self, focus: bool) {
+        self.accessibility.set_focused(focus);
         self.state.borrow_mut().active = focus;
         if let Some(ref mut fun) = self.callbacks.borrow_mut().active_status_change {
             fun(focus);
@@ -1082,6 +1103,19 @@ impl PlatformWindow for WaylandWindow {
         state.client.update_ime_position(bounds);
     }
 
+    fn update_accessibility_tree(&self, tree: &AccessibilityTree) {
+        let scale = self.borrow().scale;
+        self.0.accessibility.update(tree, scale);
+    }
+
+    fn on_accessibility_action(&self, callback: Box<dyn FnMut(AccessibilityActionRequest)>) {
+        self.0.accessibility.on_action(callback);
+    }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.0.accessibility.is_active()
+    }
+
     fn gpu_specs(&self) -> Option<GpuSpecs> {
         self.borrow().renderer.gpu_specs().into()
     }
@@ -3,10 +3,11 @@ use x11rb::connection::RequestConnection;
 
 use crate::platform::blade::{BladeContext, BladeRenderer, BladeSurfaceConfig};
 use crate::{
-    AnyWindowHandle, Bounds, Decorations, DevicePixels, ForegroundExecutor, GpuSpecs, Modifiers,
-    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
-    Point, PromptButton, PromptLevel, RequestFrameOptions, ResizeEdge, ScaledPixels, Scene, Size,
-    Tiling, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowControlArea,
+    AccessibilityActionRequest, AccessibilityTree, AnyWindowHandle, Bounds, Decorations,
+    DevicePixels, ForegroundExecutor, GpuSpecs, LinuxAccessibility, Modifiers, Pixels,
+    PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point,
+    PromptButton, PromptLevel, RequestFrameOptions, ResizeEdge, ScaledPixels, Scene, Size, Tiling,
+    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowControlArea,
     WindowDecorations, WindowKind, WindowParams, X11ClientStatePtr, px, size,
 };
 
@@ -283,6 +284,7 @@ impl X11WindowState {
 pub(crate) struct X11WindowStatePtr {
     pub state: Rc<RefCell<X11WindowState>>,
     pub(crate) callbacks: Rc<RefCell<Callbacks>>,
+    accessibility: Rc<LinuxAccessibility>,
     xcb: Rc<XCBConnection>,
     pub(crate) x_window: xproto::Window,
 }
@@ -738,6 +740,20 @@ impl X11Window {
         scale_factor: f32,
         appearance: WindowAppearance,
     ) -> anyhow::Result<Self> {
+        let callbacks = Rc::new(RefCell::new(Callbacks::default()));
+        let accessibility = Rc::new(LinuxAccessibility::new(&executor, {
+            let callbacks = Rc::downgrade(&callbacks);
+            move || {
+                if let Some(callbacks) = callbacks.upgrade()
+                    && let Some(request_frame) = callbacks.borrow_mut().request_frame.as_mut()
+                {
+                    request_frame(RequestFrameOptions {
+                        require_presentation: false,
+                        force_render: true,
+                    });
+                }
+            }
+        }));
         let ptr = X11WindowStatePtr {
             state: Rc::new(RefCell::new(X11WindowState::new(
                 handle,
@@ -753,7 +769,8 @@ impl X11Window {
                 scale_factor,
                 appearance,
             )?)),
-            callbacks: Rc::new(RefCell::new(Callbacks::default())),
+            callbacks,
+            accessibility,
             xcb: xcb.clone(),
             x_window,
         };
@@ -1083,6 +1100,7 @@ impl X11WindowStatePtr {
     }
 
     pub fn set_active(&self, focus: bool) {
+        self.accessibility.set_focused(focus);
         if let Some(ref mut fun) = self.callbacks.borrow_mut().active_status_change {
             fun(focus);
         }
@@ -1626,6 +1644,24 @@ impl PlatformWindow for X11Window {
         client.update_ime_position(bounds);
     }
 
+    fn update_accessibility_tree(&self, tree: &AccessibilityTree) {
+        let (bounds, scale_factor) = {
+            let state = self.0.state.borrow();
+            (state.bounds, state.scale_factor)
+        };
+        let bounds = bounds.map(|pixels| (pixels.0 * scale_factor) as i32);
+        self.0.accessibility.set_window_bounds(bounds, bounds);
+        self.0.accessibility.update(tree, scale_factor);
+    }
+
+    fn on_accessibility_action(&self, callback: Box<dyn FnMut(AccessibilityActionRequest)>) {
+        self.0.accessibility.on_action(callback);
+    }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.0.accessibility.is_active()
+    }
+
     fn gpu_specs(&self) -> Option<GpuSpecs> {
         self.0.state.borrow().renderer.gpu_specs().into()
     }
@@ -2,7 +2,7 @@ use crate::{
     Bounds, DevicePixels, Font, FontFallbacks, FontFeatures, FontId, FontMetrics, FontRun,
     FontStyle, FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point,
     RenderGlyphParams, Result, SUBPIXEL_VARIANTS_X, ShapedGlyph, ShapedRun, SharedString, Size,
-    point, px, size, swap_rgba_pa_to_bgra,
+    point, px, size, swap_rgba_pa_to_bgra, visual_runs_for_glyphs,
 };
 use anyhow::anyhow;
 use cocoa::appkit::CGFloat;
@@ -504,6 +504,7 @@ impl MacTextSystemState {
             runs.push(ShapedRun { font_id, glyphs });
         }
         let typographic_bounds = line.get_typographic_bounds();
+        let bidi_runs = visual_runs_for_glyphs(text, &runs);
         LineLayout {
             runs,
             font_size,
@@ -511,6 +512,7 @@ impl MacTextSystemState {
             ascent: typographic_bounds.ascent.into(),
             descent: typographic_bounds.descent.into(),
             len: text.len(),
+            bidi_runs,
         }
     }
 }
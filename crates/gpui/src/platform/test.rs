@@ -1,11 +1,13 @@
 mod dispatcher;
 mod display;
 mod platform;
+mod software_renderer;
 mod window;
 
 pub use dispatcher::*;
 pub(crate) use display::*;
 pub(crate) use platform::*;
+pub(crate) use software_renderer::*;
 pub(crate) use window::*;
 
 pub use platform::{TestScreenCaptureSource, TestScreenCaptureStream};
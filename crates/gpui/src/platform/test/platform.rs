@@ -92,6 +92,16 @@ pub(crate) struct TestPrompts {
 
 impl TestPlatform {
     pub fn new(executor: BackgroundExecutor, foreground_executor: ForegroundExecutor) -> Rc<Self> {
+        Self::with_text_system(executor, foreground_executor, Arc::new(NoopTextSystem))
+    }
+
+    /// Creates a test platform that shapes and rasterizes text with the given text
+    /// system, instead of one that renders no glyphs.
+    pub fn with_text_system(
+        executor: BackgroundExecutor,
+        foreground_executor: ForegroundExecutor,
+        text_system: Arc<dyn PlatformTextSystem>,
+    ) -> Rc<Self> {
         #[cfg(target_os = "windows")]
         let bitmap_factory = unsafe {
             windows::Win32::System::Ole::OleInitialize(None)
@@ -102,8 +112,6 @@ impl TestPlatform {
             )
         };
 
-        let text_system = Arc::new(NoopTextSystem);
-
         Rc::new_cyclic(|weak| TestPlatform {
             background_executor: executor,
             foreground_executor,
@@ -0,0 +1,680 @@
+use crate::{
+    AtlasTextureKind, AtlasTile, Background, BackgroundTag, Bounds, ContentMask, Corners,
+    DevicePixels, MonochromeSprite, Path, Point, PolychromeSprite, PrimitiveBatch, Quad, Rgba,
+    ScaledPixels, Scene, Shadow, Size, TransformationMatrix, Underline, point,
+};
+use image::RgbaImage;
+use std::f32::consts::PI;
+
+/// Provides the pixels of the atlas tiles referenced by a scene's sprites.
+pub(crate) trait TileSource {
+    /// Returns the size and bytes of the tile: one alpha byte per pixel for monochrome
+    /// tiles, and premultiplied BGRA for polychrome ones.
+    fn tile_pixels(&self, tile: &AtlasTile) -> Option<(Size<DevicePixels>, &[u8])>;
+}
+
+/// Rasterizes a [`Scene`] on the CPU, so that windows can be rendered without a GPU.
+///
+/// The output closely follows the blade shaders, but isn't pixel-identical to them:
+/// everything is blended in sRGB space, dashed borders are drawn solid, and surfaces
+/// are skipped.
+pub(crate) struct SoftwareRenderer {
+    size: Size<DevicePixels>,
+    /// Premultiplied RGBA.
+    pixels: Vec<[f32; 4]>,
+}
+
+/// Subsamples per axis used to anti-alias path triangles.
+const PATH_SUBSAMPLES: usize = 4;
+
+impl SoftwareRenderer {
+    pub fn new(size: Size<DevicePixels>) -> Self {
+        let len = size.width.0.max(0) as usize * size.height.0.max(0) as usize;
+        Self {
+            size,
+            pixels: vec![[0.; 4]; len],
+        }
+    }
+
+    pub fn render(mut self, scene: &Scene, tiles: &dyn TileSource) -> RgbaImage {
+        for batch in scene.batches() {
+            match batch {
+                PrimitiveBatch::Shadows(shadows) => {
+                    for shadow in shadows {
+                        self.draw_shadow(shadow);
+                    }
+                }
+                PrimitiveBatch::Quads(quads) => {
+                    for quad in quads {
+                        self.draw_quad(quad);
+                    }
+                }
+                PrimitiveBatch::Paths(paths) => {
+                    for path in paths {
+                        self.draw_path(path);
+                    }
+                }
+                PrimitiveBatch::Underlines(underlines) => {
+                    for underline in underlines {
+                        self.draw_underline(underline);
+                    }
+                }
+                PrimitiveBatch::MonochromeSprites { sprites, .. } => {
+                    for sprite in sprites {
+                        self.draw_monochrome_sprite(sprite, tiles);
+                    }
+                }
+                PrimitiveBatch::PolychromeSprites { sprites, .. } => {
+                    for sprite in sprites {
+                        self.draw_polychrome_sprite(sprite, tiles);
+                    }
+                }
+                PrimitiveBatch::Surfaces(_) => {}
+            }
+        }
+        self.into_image()
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let mut image = RgbaImage::new(self.size.width.0 as u32, self.size.height.0 as u32);
+        for (pixel, [r, g, b, a]) in image.pixels_mut().zip(self.pixels) {
+            let unpremultiply = if a > 0. { 1. / a } else { 0. };
+            let to_byte = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+            pixel.0 = [
+                to_byte(r * unpremultiply),
+                to_byte(g * unpremultiply),
+                to_byte(b * unpremultiply),
+                to_byte(a),
+            ];
+        }
+        image
+    }
+
+    /// Calls `f` with the center of every pixel within `bounds` and the content mask, and
+    /// blends the premultiplied color it returns over the pixel.
+    fn fill(
+        &mut self,
+        bounds: Bounds<ScaledPixels>,
+        content_mask: &ContentMask<ScaledPixels>,
+        mut f: impl FnMut(Point<f32>) -> Option<[f32; 4]>,
+    ) {
+        let bounds = bounds.intersect(&content_mask.bounds);
+        let left = (bounds.origin.x.0.floor().max(0.)) as i32;
+        let top = (bounds.origin.y.0.floor().max(0.)) as i32;
+        let right = (bounds.bottom_right().x.0.ceil() as i32).min(self.size.width.0);
+        let bottom = (bounds.bottom_right().y.0.ceil() as i32).min(self.size.height.0);
+        for y in top..bottom {
+            for x in left..right {
+                let center = point(x as f32 + 0.5, y as f32 + 0.5);
+                if !contains(&content_mask.bounds, center) {
+                    continue;
+                }
+                if let Some(source) = f(center) {
+                    let destination = &mut self.pixels[(y * self.size.width.0 + x) as usize];
+                    let inverse_alpha = 1. - source[3];
+                    for channel in 0..4 {
+                        destination[channel] =
+                            source[channel] + destination[channel] * inverse_alpha;
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_quad(&mut self, quad: &Quad) {
+        let bounds = quad.bounds;
+        let border_widths = quad.border_widths;
+        let has_border = border_widths.top.0 > 0.
+            || border_widths.right.0 > 0.
+            || border_widths.bottom.0 > 0.
+            || border_widths.left.0 > 0.;
+        let inner_bounds = Bounds::from_corners(
+            point(
+                bounds.origin.x + border_widths.left,
+                bounds.origin.y + border_widths.top,
+            ),
+            point(
+                bounds.bottom_right().x - border_widths.right,
+                bounds.bottom_right().y - border_widths.bottom,
+            ),
+        );
+        let inner_radii = Corners {
+            top_left: inner_radius(
+                quad.corner_radii.top_left,
+                border_widths.top,
+                border_widths.left,
+            ),
+            top_right: inner_radius(
+                quad.corner_radii.top_right,
+                border_widths.top,
+                border_widths.right,
+            ),
+            bottom_right: inner_radius(
+                quad.corner_radii.bottom_right,
+                border_widths.bottom,
+                border_widths.right,
+            ),
+            bottom_left: inner_radius(
+                quad.corner_radii.bottom_left,
+                border_widths.bottom,
+                border_widths.left,
+            ),
+        };
+        let border_color = premultiplied(quad.border_color.to_rgb());
+
+        self.fill(bounds, &quad.content_mask, |position| {
+            let coverage = coverage(rounded_rect_distance(position, bounds, &quad.corner_radii));
+            if coverage <= 0. {
+                return None;
+            }
+
+            let background = background_color(&quad.background, position, bounds);
+            let color = if has_border {
+                let inside_border = coverage_of(rounded_rect_distance_checked(
+                    position,
+                    inner_bounds,
+                    &inner_radii,
+                ));
+                over(scale(border_color, 1. - inside_border), background)
+            } else {
+                background
+            };
+            Some(scale(color, coverage))
+        });
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let sigma = shadow.blur_radius.0;
+        let bounds = shadow.bounds;
+        let margin = ScaledPixels(sigma * 3.);
+        let blurred_bounds = bounds.dilate(margin);
+        let color = premultiplied(shadow.color.to_rgb());
+        let half_size = point(bounds.size.width.0 / 2., bounds.size.height.0 / 2.);
+        let center = point(
+            bounds.origin.x.0 + half_size.x,
+            bounds.origin.y.0 + half_size.y,
+        );
+
+        self.fill(blurred_bounds, &shadow.content_mask, |position| {
+            let center_to_point = point(position.x - center.x, position.y - center.y);
+            let corner_radius = pick_corner_radius(center_to_point, &shadow.corner_radii);
+            let alpha = if sigma <= 0. {
+                coverage(rounded_rect_distance(
+                    position,
+                    bounds,
+                    &shadow.corner_radii,
+                ))
+            } else {
+                // Integrate the blur along y numerically, as the shader does.
+                let low = center_to_point.y - half_size.y;
+                let high = center_to_point.y + half_size.y;
+                let start = (-3. * sigma).clamp(low, high);
+                let end = (3. * sigma).clamp(low, high);
+                let step = (end - start) / 4.;
+                let mut y = start + step * 0.5;
+                let mut alpha = 0.;
+                for _ in 0..4 {
+                    let blur = blur_along_x(
+                        center_to_point.x,
+                        center_to_point.y - y,
+                        sigma,
+                        corner_radius,
+                        half_size,
+                    );
+                    alpha += blur * gaussian(y, sigma) * step;
+                    y += step;
+                }
+                alpha
+            };
+            (alpha > 0.).then(|| scale(color, alpha))
+        });
+    }
+
+    fn draw_path(&mut self, path: &Path<ScaledPixels>) {
+        let triangles = path
+            .vertices
+            .chunks_exact(3)
+            .map(|vertices| {
+                [
+                    (vertices[0].xy_position, vertices[0].st_position),
+                    (vertices[1].xy_position, vertices[1].st_position),
+                    (vertices[2].xy_position, vertices[2].st_position),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let bounds = path.bounds;
+
+        self.fill(bounds, &path.content_mask, |position| {
+            let mut covered = 0;
+            for sample_y in 0..PATH_SUBSAMPLES {
+                for sample_x in 0..PATH_SUBSAMPLES {
+                    let sample = point(
+                        position.x - 0.5 + (sample_x as f32 + 0.5) / PATH_SUBSAMPLES as f32,
+                        position.y - 0.5 + (sample_y as f32 + 0.5) / PATH_SUBSAMPLES as f32,
+                    );
+                    if triangles
+                        .iter()
+                        .any(|triangle| triangle_contains(triangle, sample))
+                    {
+                        covered += 1;
+                    }
+                }
+            }
+            if covered == 0 {
+                return None;
+            }
+            let coverage = covered as f32 / (PATH_SUBSAMPLES * PATH_SUBSAMPLES) as f32;
+            Some(scale(
+                background_color(&path.color, position, bounds),
+                coverage,
+            ))
+        });
+    }
+
+    fn draw_underline(&mut self, underline: &Underline) {
+        const WAVE_FREQUENCY: f32 = 2.0;
+        const WAVE_HEIGHT_RATIO: f32 = 0.8;
+
+        let color = premultiplied(underline.color.to_rgb());
+        let bounds = underline.bounds;
+        let wavy = underline.wavy & 0xFF != 0;
+        let thickness = underline.thickness.0;
+        let height = bounds.size.height.0;
+
+        self.fill(bounds, &underline.content_mask, |position| {
+            if !wavy {
+                return Some(color);
+            }
+
+            let st = point(
+                (position.x - bounds.origin.x.0) / height,
+                (position.y - bounds.origin.y.0) / height - 0.5,
+            );
+            let frequency = PI * WAVE_FREQUENCY * thickness / height;
+            let amplitude = thickness * WAVE_HEIGHT_RATIO / height;
+            let sine = (st.x * frequency).sin() * amplitude;
+            let slope = (st.x * frequency).cos() * amplitude * frequency;
+            let distance = (st.y - sine) / (1. + slope * slope).sqrt() * height;
+            let half_thickness = thickness * 0.5;
+            let alpha =
+                (0.5 - (-(distance + half_thickness)).max(distance - half_thickness)).clamp(0., 1.);
+            (alpha > 0.).then(|| scale(color, alpha))
+        });
+    }
+
+    fn draw_monochrome_sprite(&mut self, sprite: &MonochromeSprite, tiles: &dyn TileSource) {
+        let Some((tile_size, bytes)) = tiles.tile_pixels(&sprite.tile) else {
+            return;
+        };
+        if sprite.tile.texture_id.kind != AtlasTextureKind::Monochrome {
+            return;
+        }
+        let color = premultiplied(sprite.color.to_rgb());
+        let inverse = invert(&sprite.transformation);
+        let bounds = transformed_bounds(sprite.bounds, &sprite.transformation);
+
+        self.fill(bounds, &sprite.content_mask, |position| {
+            let position = inverse.map_or(position, |inverse| apply(&inverse, position));
+            let (x, y) = sample_coordinates(position, sprite.bounds, tile_size)?;
+            let alpha = *bytes.get(y * tile_size.width.0 as usize + x)? as f32 / 255.;
+            (alpha > 0.).then(|| scale(color, alpha))
+        });
+    }
+
+    fn draw_polychrome_sprite(&mut self, sprite: &PolychromeSprite, tiles: &dyn TileSource) {
+        let Some((tile_size, bytes)) = tiles.tile_pixels(&sprite.tile) else {
+            return;
+        };
+        let monochrome = sprite.tile.texture_id.kind == AtlasTextureKind::Monochrome;
+
+        self.fill(sprite.bounds, &sprite.content_mask, |position| {
+            let coverage = coverage(rounded_rect_distance(
+                position,
+                sprite.bounds,
+                &sprite.corner_radii,
+            ));
+            if coverage <= 0. {
+                return None;
+            }
+            let (x, y) = sample_coordinates(position, sprite.bounds, tile_size)?;
+            let offset = y * tile_size.width.0 as usize + x;
+            let mut color = if monochrome {
+                let alpha = *bytes.get(offset)? as f32 / 255.;
+                [alpha; 4]
+            } else {
+                let bgra = bytes.get(offset * 4..offset * 4 + 4)?;
+                [
+                    bgra[2] as f32 / 255.,
+                    bgra[1] as f32 / 255.,
+                    bgra[0] as f32 / 255.,
+                    bgra[3] as f32 / 255.,
+                ]
+            };
+            if sprite.grayscale {
+                let luminance = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+                color = [luminance, luminance, luminance, color[3]];
+            }
+            Some(scale(color, sprite.opacity * coverage))
+        });
+    }
+}
+
+fn contains(bounds: &Bounds<ScaledPixels>, position: Point<f32>) -> bool {
+    position.x >= bounds.origin.x.0
+        && position.y >= bounds.origin.y.0
+        && position.x < bounds.bottom_right().x.0
+        && position.y < bounds.bottom_right().y.0
+}
+
+fn premultiplied(color: Rgba) -> [f32; 4] {
+    [
+        color.r * color.a,
+        color.g * color.a,
+        color.b * color.a,
+        color.a,
+    ]
+}
+
+fn scale(color: [f32; 4], factor: f32) -> [f32; 4] {
+    color.map(|channel| channel * factor)
+}
+
+fn over(top: [f32; 4], bottom: [f32; 4]) -> [f32; 4] {
+    let inverse_alpha = 1. - top[3];
+    [
+        top[0] + bottom[0] * inverse_alpha,
+        top[1] + bottom[1] * inverse_alpha,
+        top[2] + bottom[2] * inverse_alpha,
+        top[3] + bottom[3] * inverse_alpha,
+    ]
+}
+
+/// Converts a signed distance to an edge into the fraction of the pixel it covers.
+fn coverage(distance: f32) -> f32 {
+    (0.5 - distance).clamp(0., 1.)
+}
+
+fn coverage_of(distance: Option<f32>) -> f32 {
+    distance.map_or(0., coverage)
+}
+
+fn inner_radius(
+    radius: ScaledPixels,
+    width_a: ScaledPixels,
+    width_b: ScaledPixels,
+) -> ScaledPixels {
+    ScaledPixels((radius.0 - width_a.0.max(width_b.0)).max(0.))
+}
+
+fn pick_corner_radius(center_to_point: Point<f32>, radii: &Corners<ScaledPixels>) -> f32 {
+    match (center_to_point.x < 0., center_to_point.y < 0.) {
+        (true, true) => radii.top_left.0,
+        (true, false) => radii.bottom_left.0,
+        (false, true) => radii.top_right.0,
+        (false, false) => radii.bottom_right.0,
+    }
+}
+
+/// The signed distance from the position to the edge of a rounded rectangle, negative
+/// inside of it. Returns a large positive distance for empty rectangles.
+fn rounded_rect_distance(
+    position: Point<f32>,
+    bounds: Bounds<ScaledPixels>,
+    radii: &Corners<ScaledPixels>,
+) -> f32 {
+    rounded_rect_distance_checked(position, bounds, radii).unwrap_or(f32::MAX)
+}
+
+fn rounded_rect_distance_checked(
+    position: Point<f32>,
+    bounds: Bounds<ScaledPixels>,
+    radii: &Corners<ScaledPixels>,
+) -> Option<f32> {
+    if bounds.size.width.0 <= 0. || bounds.size.height.0 <= 0. {
+        return None;
+    }
+    let half_size = point(bounds.size.width.0 / 2., bounds.size.height.0 / 2.);
+    let center_to_point = point(
+        position.x - (bounds.origin.x.0 + half_size.x),
+        position.y - (bounds.origin.y.0 + half_size.y),
+    );
+    let radius = pick_corner_radius(center_to_point, radii)
+        .min(half_size.x)
+        .min(half_size.y);
+    let corner_to_point = point(
+        center_to_point.x.abs() - half_size.x + radius,
+        center_to_point.y.abs() - half_size.y + radius,
+    );
+    let outside = point(corner_to_point.x.max(0.), corner_to_point.y.max(0.));
+    let outside_distance = (outside.x * outside.x + outside.y * outside.y).sqrt();
+    let inside_distance = corner_to_point.x.max(corner_to_point.y).min(0.);
+    Some(outside_distance + inside_distance - radius)
+}
+
+fn gaussian(x: f32, sigma: f32) -> f32 {
+    (-(x * x) / (2. * sigma * sigma)).exp() / ((2. * PI).sqrt() * sigma)
+}
+
+/// Approximates the error function, needed for the gaussian integral.
+fn erf(value: f32) -> f32 {
+    let sign = value.signum();
+    let a = value.abs();
+    let r1 = 1. + (0.278393 + (0.230389 + (0.000972 + 0.078108 * a) * a) * a) * a;
+    let r2 = r1 * r1;
+    sign - sign / (r2 * r2)
+}
+
+fn blur_along_x(x: f32, y: f32, sigma: f32, corner: f32, half_size: Point<f32>) -> f32 {
+    let delta = (half_size.y - corner - y.abs()).min(0.);
+    let curved = half_size.x - corner + (corner * corner - delta * delta).max(0.).sqrt();
+    let factor = 0.5f32.sqrt() / sigma;
+    let low = 0.5 + 0.5 * erf((x - curved) * factor);
+    let high = 0.5 + 0.5 * erf((x + curved) * factor);
+    high - low
+}
+
+fn background_color(
+    background: &Background,
+    position: Point<f32>,
+    bounds: Bounds<ScaledPixels>,
+) -> [f32; 4] {
+    match background.tag {
+        BackgroundTag::LinearGradient => {
+            let radians = (background.gradient_angle_or_pattern_height % 360. - 90.) * PI / 180.;
+            let mut direction = point(radians.cos(), radians.sin());
+            let size = point(bounds.size.width.0, bounds.size.height.0);
+            if size.x > size.y {
+                direction.y *= size.y / size.x;
+            } else {
+                direction.x *= size.x / size.y;
+            }
+
+            let half_size = point(size.x / 2., size.y / 2.);
+            let center_to_point = point(
+                position.x - bounds.origin.x.0 - half_size.x,
+                position.y - bounds.origin.y.0 - half_size.y,
+            );
+            let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+            let mut t =
+                (center_to_point.x * direction.x + center_to_point.y * direction.y) / length;
+            t = if direction.x.abs() > direction.y.abs() {
+                (t + half_size.x) / size.x
+            } else {
+                (t + half_size.y) / size.y
+            };
+            let [stop0, stop1] = &background.colors;
+            t = ((t - stop0.percentage) / (stop1.percentage - stop0.percentage)).clamp(0., 1.);
+
+            let color0 = premultiplied(stop0.color.to_rgb());
+            let color1 = premultiplied(stop1.color.to_rgb());
+            [0, 1, 2, 3].map(|channel| color0[channel] + (color1[channel] - color0[channel]) * t)
+        }
+        BackgroundTag::Solid | BackgroundTag::PatternSlash => {
+            premultiplied(background.solid.to_rgb())
+        }
+    }
+}
+
+fn triangle_contains(
+    triangle: &[(Point<ScaledPixels>, Point<f32>); 3],
+    sample: Point<f32>,
+) -> bool {
+    let [(a, st_a), (b, st_b), (c, st_c)] = triangle;
+    let (a, b, c) = (
+        point(a.x.0, a.y.0),
+        point(b.x.0, b.y.0),
+        point(c.x.0, c.y.0),
+    );
+    let area = (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y);
+    if area == 0. {
+        return false;
+    }
+    let weight_a =
+        ((b.x - sample.x) * (c.y - sample.y) - (c.x - sample.x) * (b.y - sample.y)) / area;
+    let weight_b =
+        ((c.x - sample.x) * (a.y - sample.y) - (a.x - sample.x) * (c.y - sample.y)) / area;
+    let weight_c = 1. - weight_a - weight_b;
+    if weight_a < 0. || weight_b < 0. || weight_c < 0. {
+        return false;
+    }
+
+    // Quadratic curves are drawn as triangles whose texture coordinates satisfy
+    // s² - t <= 0 on the inside of the curve.
+    let s = st_a.x * weight_a + st_b.x * weight_b + st_c.x * weight_c;
+    let t = st_a.y * weight_a + st_b.y * weight_b + st_c.y * weight_c;
+    s * s - t <= 0.
+}
+
+fn sample_coordinates(
+    position: Point<f32>,
+    bounds: Bounds<ScaledPixels>,
+    tile_size: Size<DevicePixels>,
+) -> Option<(usize, usize)> {
+    let u = (position.x - bounds.origin.x.0) / bounds.size.width.0;
+    let v = (position.y - bounds.origin.y.0) / bounds.size.height.0;
+    if !(0. ..1.).contains(&u) || !(0. ..1.).contains(&v) {
+        return None;
+    }
+    Some((
+        (u * tile_size.width.0 as f32) as usize,
+        (v * tile_size.height.0 as f32) as usize,
+    ))
+}
+
+fn apply(transformation: &TransformationMatrix, position: Point<f32>) -> Point<f32> {
+    let [[a, b], [c, d]] = transformation.rotation_scale;
+    let [tx, ty] = transformation.translation;
+    point(
+        a * position.x + b * position.y + tx,
+        c * position.x + d * position.y + ty,
+    )
+}
+
+fn invert(transformation: &TransformationMatrix) -> Option<TransformationMatrix> {
+    if *transformation == TransformationMatrix::unit() {
+        return None;
+    }
+    let [[a, b], [c, d]] = transformation.rotation_scale;
+    let determinant = a * d - b * c;
+    if determinant == 0. {
+        return None;
+    }
+    let rotation_scale = [
+        [d / determinant, -b / determinant],
+        [-c / determinant, a / determinant],
+    ];
+    let [tx, ty] = transformation.translation;
+    Some(TransformationMatrix {
+        translation: [
+            -(rotation_scale[0][0] * tx + rotation_scale[0][1] * ty),
+            -(rotation_scale[1][0] * tx + rotation_scale[1][1] * ty),
+        ],
+        rotation_scale,
+    })
+}
+
+fn transformed_bounds(
+    bounds: Bounds<ScaledPixels>,
+    transformation: &TransformationMatrix,
+) -> Bounds<ScaledPixels> {
+    if *transformation == TransformationMatrix::unit() {
+        return bounds;
+    }
+    let corners = [
+        bounds.origin,
+        bounds.top_right(),
+        bounds.bottom_left(),
+        bounds.bottom_right(),
+    ]
+    .map(|corner| apply(transformation, point(corner.x.0, corner.y.0)));
+    let min_x = corners
+        .iter()
+        .map(|corner| corner.x)
+        .fold(f32::MAX, f32::min);
+    let min_y = corners
+        .iter()
+        .map(|corner| corner.y)
+        .fold(f32::MAX, f32::min);
+    let max_x = corners
+        .iter()
+        .map(|corner| corner.x)
+        .fold(f32::MIN, f32::max);
+    let max_y = corners
+        .iter()
+        .map(|corner| corner.y)
+        .fold(f32::MIN, f32::max);
+    Bounds::from_corners(
+        point(ScaledPixels(min_x), ScaledPixels(min_y)),
+        point(ScaledPixels(max_x), ScaledPixels(max_y)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Context, IntoElement, ParentElement, Render, Styled, TestAppContext, Window, div, px, rgb,
+    };
+
+    struct Swatches;
+
+    impl Render for Swatches {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .bg(rgb(0xffffff))
+                .child(
+                    div()
+                        .absolute()
+                        .top(px(10.))
+                        .left(px(10.))
+                        .size(px(20.))
+                        .bg(rgb(0xff0000)),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .top(px(10.))
+                        .left(px(40.))
+                        .size(px(20.))
+                        .rounded(px(10.))
+                        .border_2()
+                        .border_color(rgb(0x0000ff))
+                        .bg(rgb(0x00ff00)),
+                )
+        }
+    }
+
+    #[crate::test]
+    fn test_render_quads(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| Swatches);
+        let image = cx.render_window();
+
+        // The test window has a scale factor of 2.
+        assert_eq!(image.get_pixel(2, 2).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(40, 40).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(100, 40).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(100, 22).0, [0, 0, 255, 255]);
+        // Outside the rounded corner, the background shows through.
+        assert_eq!(image.get_pixel(81, 21).0, [255, 255, 255, 255]);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{
-    AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DispatchEventResult, GpuSpecs,
-    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
-    Point, PromptButton, RequestFrameOptions, Size, TestPlatform, TileId, WindowAppearance,
+    AccessibilityActionRequest, AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds,
+    DevicePixels, DispatchEventResult, GpuSpecs, Pixels, PlatformAtlas, PlatformDisplay,
+    PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptButton, RequestFrameOptions,
+    Scene, Size, SoftwareRenderer, TestPlatform, TileId, TileSource, WindowAppearance,
     WindowBackgroundAppearance, WindowBounds, WindowControlArea, WindowParams,
 };
 use collections::HashMap;
+use image::RgbaImage;
 use parking_lot::Mutex;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use std::{
@@ -19,7 +21,7 @@ pub(crate) struct TestWindowState {
     pub(crate) title: Option<String>,
     pub(crate) edited: bool,
     platform: Weak<TestPlatform>,
-    sprite_atlas: Arc<dyn PlatformAtlas>,
+    sprite_atlas: Arc<TestAtlas>,
     pub(crate) should_close_handler: Option<Box<dyn FnMut() -> bool>>,
     hit_test_window_control_callback: Option<Box<dyn FnMut() -> Option<WindowControlArea>>>,
     input_callback: Option<Box<dyn FnMut(PlatformInput) -> DispatchEventResult>>,
@@ -27,6 +29,8 @@ pub(crate) struct TestWindowState {
     hover_status_change_callback: Option<Box<dyn FnMut(bool)>>,
     resize_callback: Option<Box<dyn FnMut(Size<Pixels>, f32)>>,
     moved_callback: Option<Box<dyn FnMut()>>,
+    accessibility_action_callback: Option<Box<dyn FnMut(AccessibilityActionRequest)>>,
+    accessibility_active: bool,
     input_handler: Option<PlatformInputHandler>,
     is_fullscreen: bool,
 }
@@ -72,6 +76,8 @@ impl TestWindow {
             hover_status_change_callback: None,
             resize_callback: None,
             moved_callback: None,
+            accessibility_action_callback: None,
+            accessibility_active: false,
             input_handler: None,
             is_fullscreen: false,
         })))
@@ -109,6 +115,29 @@ impl TestWindow {
         self.0.lock().input_callback = Some(callback);
         !result.propagate
     }
+
+    /// Rasterizes the given scene on the CPU, at the window's size and scale factor.
+    pub(crate) fn render(&self, scene: &Scene) -> RgbaImage {
+        let size = self.content_size().scale(self.scale_factor());
+        let size = size.map(|pixels| DevicePixels(pixels.0.ceil() as i32));
+        let atlas = self.0.lock().sprite_atlas.clone();
+        let state = atlas.0.lock();
+        SoftwareRenderer::new(size).render(scene, &*state)
+    }
+
+    pub fn simulate_accessibility_action(&mut self, request: AccessibilityActionRequest) {
+        let mut lock = self.0.lock();
+        let Some(mut callback) = lock.accessibility_action_callback.take() else {
+            return;
+        };
+        drop(lock);
+        callback(request);
+        self.0.lock().accessibility_action_callback = Some(callback);
+    }
+
+    pub fn simulate_accessibility_activation(&mut self, active: bool) {
+        self.0.lock().accessibility_active = active;
+    }
 }
 
 impl PlatformWindow for TestWindow {
@@ -291,6 +320,14 @@ impl PlatformWindow for TestWindow {
 
     fn update_ime_position(&self, _bounds: Bounds<Pixels>) {}
 
+    fn on_accessibility_action(&self, callback: Box<dyn FnMut(AccessibilityActionRequest)>) {
+        self.0.lock().accessibility_action_callback = Some(callback);
+    }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.0.lock().accessibility_active
+    }
+
     fn gpu_specs(&self) -> Option<GpuSpecs> {
         None
     }
@@ -299,6 +336,8 @@ impl PlatformWindow for TestWindow {
 pub(crate) struct TestAtlasState {
     next_id: u32,
     tiles: HashMap<AtlasKey, AtlasTile>,
+    /// The pixels of each tile, keyed by texture index, kept for software rendering.
+    pixels: HashMap<u32, (Size<DevicePixels>, Vec<u8>)>,
 }
 
 pub(crate) struct TestAtlas(Mutex<TestAtlasState>);
@@ -308,10 +347,18 @@ impl TestAtlas {
         TestAtlas(Mutex::new(TestAtlasState {
             next_id: 0,
             tiles: HashMap::default(),
+            pixels: HashMap::default(),
         }))
     }
 }
 
+impl TileSource for TestAtlasState {
+    fn tile_pixels(&self, tile: &AtlasTile) -> Option<(Size<DevicePixels>, &[u8])> {
+        let (size, bytes) = self.pixels.get(&tile.texture_id.index)?;
+        Some((*size, bytes.as_slice()))
+    }
+}
+
 impl PlatformAtlas for TestAtlas {
     fn get_or_insert_with<'a>(
         &self,
@@ -326,7 +373,7 @@ impl PlatformAtlas for TestAtlas {
         }
         drop(state);
 
-        let Some((size, _)) = build()? else {
+        let Some((size, bytes)) = build()? else {
             return Ok(None);
         };
 
@@ -335,13 +382,14 @@ impl PlatformAtlas for TestAtlas {
         let texture_id = state.next_id;
         state.next_id += 1;
         let tile_id = state.next_id;
+        state.pixels.insert(texture_id, (size, bytes.into_owned()));
 
         state.tiles.insert(
             key.clone(),
             crate::AtlasTile {
                 texture_id: AtlasTextureId {
                     index: texture_id,
-                    kind: crate::AtlasTextureKind::Monochrome,
+                    kind: key.texture_kind(),
                 },
                 tile_id: TileId(tile_id),
                 padding: 0,
@@ -357,6 +405,8 @@ impl PlatformAtlas for TestAtlas {
 
     fn remove(&self, key: &AtlasKey) {
         let mut state = self.0.lock();
-        state.tiles.remove(key);
+        if let Some(tile) = state.tiles.remove(key) {
+            state.pixels.remove(&tile.texture_id.index);
+        }
     }
 }
@@ -656,6 +656,7 @@ impl DirectWriteState {
                 0.0,
             )?;
             let width = px(renderer_context.width);
+            let bidi_runs = visual_runs_for_glyphs(text, &runs);
 
             Ok(LineLayout {
                 font_size,
@@ -664,6 +665,7 @@ impl DirectWriteState {
                 descent,
                 runs,
                 len: text.len(),
+                bidi_runs,
             })
         }
     }
@@ -0,0 +1,161 @@
+use crate::ShapedRun;
+use smallvec::SmallVec;
+use std::ops::Range;
+use unicode_bidi::{BidiClass, BidiInfo, bidi_class};
+
+/// A run of text with a single direction, as determined by the Unicode Bidirectional
+/// Algorithm.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BidiRun {
+    /// The logical range of the run within its line, in utf-8 bytes.
+    pub range: Range<usize>,
+    /// Whether the run is laid out from right to left.
+    pub rtl: bool,
+}
+
+/// Splits a line of text into directional runs, in visual order from left to right.
+///
+/// Returns no runs when the line contains no right-to-left text, which lets callers
+/// keep their simpler logical-order code paths for the common case.
+pub fn visual_runs(text: &str) -> SmallVec<[BidiRun; 1]> {
+    let mut runs = SmallVec::new();
+    if !contains_rtl(text) {
+        return runs;
+    }
+
+    let info = BidiInfo::new(text, None);
+    for paragraph in &info.paragraphs {
+        let (levels, level_runs) = info.visual_runs(paragraph, paragraph.range.clone());
+        for range in level_runs {
+            runs.push(BidiRun {
+                rtl: levels[range.start].is_rtl(),
+                range,
+            });
+        }
+    }
+    runs
+}
+
+/// Like [`visual_runs`], but for text systems that may not reorder right-to-left glyphs.
+///
+/// Returns no runs unless the shaped glyphs, from left to right, visit the directional
+/// runs in visual order, so that lines the text system laid out logically keep the
+/// logical-order code paths.
+pub fn visual_runs_for_glyphs(text: &str, runs: &[ShapedRun]) -> SmallVec<[BidiRun; 1]> {
+    let bidi_runs = visual_runs(text);
+    if bidi_runs.is_empty() {
+        return bidi_runs;
+    }
+
+    let mut glyphs = runs
+        .iter()
+        .flat_map(|run| run.glyphs.iter())
+        .collect::<SmallVec<[_; 32]>>();
+    glyphs.sort_by_key(|glyph| glyph.position.x);
+
+    let mut bidi_run_ix = 0;
+    let mut prev_index = None;
+    for glyph in glyphs {
+        while !bidi_runs[bidi_run_ix].range.contains(&glyph.index) {
+            bidi_run_ix += 1;
+            prev_index = None;
+            if bidi_run_ix == bidi_runs.len() {
+                return SmallVec::new();
+            }
+        }
+        let rtl = bidi_runs[bidi_run_ix].rtl;
+        if prev_index.is_some_and(|prev_index| {
+            glyph.index != prev_index && (glyph.index < prev_index) != rtl
+        }) {
+            return SmallVec::new();
+        }
+        prev_index = Some(glyph.index);
+    }
+    bidi_runs
+}
+
+/// Whether the text contains any strongly right-to-left characters.
+pub fn contains_rtl(text: &str) -> bool {
+    !text.is_ascii()
+        && text.chars().any(|c| {
+            matches!(
+                bidi_class(c),
+                BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visual_runs() {
+        assert!(visual_runs("fn main() {}").is_empty());
+        assert!(visual_runs("héllo wörld").is_empty());
+
+        // "abc שלום def"
+        let text = "abc \u{5e9}\u{5dc}\u{5d5}\u{5dd} def";
+        let hebrew_start = 4;
+        let hebrew_end = hebrew_start + 8;
+        assert_eq!(
+            visual_runs(text).as_slice(),
+            &[
+                BidiRun {
+                    range: 0..hebrew_start,
+                    rtl: false,
+                },
+                BidiRun {
+                    range: hebrew_start..hebrew_end,
+                    rtl: true,
+                },
+                BidiRun {
+                    range: hebrew_end..text.len(),
+                    rtl: false,
+                },
+            ]
+        );
+
+        // A line that starts with Arabic has a right-to-left base direction, so the
+        // trailing Latin text is displayed to the left of it.
+        let text = "\u{645}\u{631}\u{62d}\u{628}\u{627} abc";
+        let runs = visual_runs(text);
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].range, 11..text.len());
+        assert!(!runs[0].rtl);
+        assert_eq!(runs[1].range, 0..11);
+        assert!(runs[1].rtl);
+    }
+
+    #[test]
+    fn test_visual_runs_for_glyphs() {
+        use crate::{FontId, GlyphId, ShapedGlyph, point, px};
+
+        // "ab שׁל", one glyph per character.
+        let text = "ab \u{5e9}\u{5dc}";
+        let shaped_runs = |indices: &[usize]| {
+            vec![ShapedRun {
+                font_id: FontId(0),
+                glyphs: indices
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, &index)| ShapedGlyph {
+                        id: GlyphId(0),
+                        position: point(px(ix as f32 * 10.), px(0.)),
+                        index,
+                        is_emoji: false,
+                    })
+                    .collect(),
+            }]
+        };
+
+        let reordered = shaped_runs(&[0, 1, 2, 5, 3]);
+        assert_eq!(
+            visual_runs_for_glyphs(text, &reordered).as_slice(),
+            visual_runs(text).as_slice()
+        );
+
+        let logical = shaped_runs(&[0, 1, 2, 3, 5]);
+        assert!(visual_runs_for_glyphs(text, &logical).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{FontId, GlyphId, Pixels, PlatformTextSystem, Point, SharedString, Size, point, px};
 use collections::FxHashMap;
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
-use smallvec::SmallVec;
+use smallvec::{SmallVec, smallvec};
 use std::{
     borrow::Borrow,
     hash::{Hash, Hasher},
@@ -9,7 +9,7 @@ use std::{
     sync::Arc,
 };
 
-use super::LineWrapper;
+use super::{BidiRun, LineWrapper};
 
 /// A laid out and styled line of text
 #[derive(Default, Debug)]
@@ -26,6 +26,9 @@ pub struct LineLayout {
     pub runs: Vec<ShapedRun>,
     /// The length of the line in utf-8 bytes
     pub len: usize,
+    /// The directional runs of the line in visual order, with glyphs laid out
+    /// accordingly. Empty when the line contains no right-to-left text.
+    pub bidi_runs: SmallVec<[BidiRun; 1]>,
 }
 
 /// A run of text that has been shaped .
@@ -37,6 +40,15 @@ pub struct ShapedRun {
     pub glyphs: Vec<ShapedGlyph>,
 }
 
+/// A caret position in a line, see [`LineLayout::carets`].
+#[derive(Clone, Copy, Debug)]
+struct Caret {
+    index: usize,
+    x: Pixels,
+    bidi_run_ix: usize,
+    canonical: bool,
+}
+
 /// A single glyph, ready to paint.
 #[derive(Clone, Debug)]
 pub struct ShapedGlyph {
@@ -58,6 +70,14 @@ impl LineLayout {
     pub fn index_for_x(&self, x: Pixels) -> Option<usize> {
         if x >= self.width {
             None
+        } else if self.has_rtl() {
+            Some(
+                self.visual_glyphs()
+                    .iter()
+                    .rev()
+                    .find(|glyph| glyph.position.x <= x)
+                    .map_or(0, |glyph| glyph.index),
+            )
         } else {
             for run in self.runs.iter().rev() {
                 for glyph in run.glyphs.iter().rev() {
@@ -73,6 +93,15 @@ impl LineLayout {
     /// closest_index_for_x returns the character boundary closest to the given x coordinate
     /// (e.g. to handle aligning up/down arrow keys)
     pub fn closest_index_for_x(&self, x: Pixels) -> usize {
+        if self.has_rtl() {
+            return self
+                .carets()
+                .iter()
+                .filter(|caret| caret.canonical)
+                .min_by_key(|caret| (caret.x - x).abs())
+                .map_or(0, |caret| caret.index);
+        }
+
         let mut prev_index = 0;
         let mut prev_x = px(0.);
 
@@ -103,6 +132,11 @@ impl LineLayout {
 
     /// The x position of the character at the given index
     pub fn x_for_index(&self, index: usize) -> Pixels {
+        if self.has_rtl() {
+            let carets = self.carets();
+            return carets[Self::caret_ix_for_index(&carets, index)].x;
+        }
+
         for run in &self.runs {
             for glyph in &run.glyphs {
                 if glyph.index >= index {
@@ -113,6 +147,174 @@ impl LineLayout {
         self.width
     }
 
+    /// Whether the line contains right-to-left text, in which case its glyphs are not
+    /// in logical order.
+    pub fn has_rtl(&self) -> bool {
+        !self.bidi_runs.is_empty()
+    }
+
+    /// The index of the caret position visually to the left of the given index, or
+    /// `None` if the index is already at the left edge of the line.
+    pub fn index_left_of(&self, index: usize) -> Option<usize> {
+        let carets = self.carets();
+        let caret_ix = Self::caret_ix_for_index(&carets, index);
+        let x = carets[caret_ix].x;
+        carets[..caret_ix]
+            .iter()
+            .rev()
+            .find(|caret| caret.canonical && caret.x != x)
+            .map(|caret| caret.index)
+    }
+
+    /// The index of the caret position visually to the right of the given index, or
+    /// `None` if the index is already at the right edge of the line.
+    pub fn index_right_of(&self, index: usize) -> Option<usize> {
+        let carets = self.carets();
+        let caret_ix = Self::caret_ix_for_index(&carets, index);
+        let x = carets[caret_ix].x;
+        carets[caret_ix + 1..]
+            .iter()
+            .find(|caret| caret.canonical && caret.x != x)
+            .map(|caret| caret.index)
+    }
+
+    /// The horizontal extents covered by the given range of the line, ordered from
+    /// left to right. A range spanning text of both directions is visually
+    /// discontiguous, so it can cover more than one extent.
+    pub fn x_ranges_for_index_range(&self, range: Range<usize>) -> SmallVec<[Range<Pixels>; 1]> {
+        if !self.has_rtl() {
+            return smallvec![self.x_for_index(range.start)..self.x_for_index(range.end)];
+        }
+
+        let carets = self.carets();
+        let mut x_ranges: SmallVec<[Range<Pixels>; 1]> = SmallVec::new();
+        for (bidi_run_ix, bidi_run) in self.bidi_runs.iter().enumerate() {
+            let start = range.start.max(bidi_run.range.start);
+            let end = range.end.min(bidi_run.range.end);
+            if start >= end {
+                continue;
+            }
+
+            let run_carets = carets
+                .iter()
+                .filter(|caret| caret.bidi_run_ix == bidi_run_ix)
+                .collect::<SmallVec<[_; 32]>>();
+            let x_for = |index: usize| {
+                run_carets
+                    .iter()
+                    .min_by_key(|caret| caret.index.abs_diff(index))
+                    .map_or(Pixels::ZERO, |caret| caret.x)
+            };
+            let (start_x, end_x) = (x_for(start), x_for(end));
+            let x_range = start_x.min(end_x)..start_x.max(end_x);
+            match x_ranges.last_mut() {
+                Some(last) if last.end == x_range.start => last.end = x_range.end,
+                _ => x_ranges.push(x_range),
+            }
+        }
+        x_ranges
+    }
+
+    /// Every caret position in the line, ordered from left to right. Positions at the
+    /// boundary of two directional runs appear once for each run, but only one of
+    /// them is canonical, i.e. where the caret is displayed for that index.
+    fn carets(&self) -> SmallVec<[Caret; 32]> {
+        let glyphs = self.visual_glyphs();
+        let right_edge = |glyph_ix: usize| {
+            glyphs
+                .get(glyph_ix + 1)
+                .map_or(self.width, |glyph| glyph.position.x)
+        };
+
+        let ltr_line = [BidiRun {
+            range: 0..self.len,
+            rtl: false,
+        }];
+        let bidi_runs = if self.has_rtl() {
+            self.bidi_runs.as_slice()
+        } else {
+            &ltr_line
+        };
+
+        let mut carets = SmallVec::new();
+        let mut glyph_ix = 0;
+        let mut x = Pixels::ZERO;
+        for (bidi_run_ix, bidi_run) in bidi_runs.iter().enumerate() {
+            let caret = |index, x| Caret {
+                index,
+                x,
+                bidi_run_ix,
+                canonical: false,
+            };
+            if bidi_run.rtl {
+                carets.push(caret(bidi_run.range.end, x));
+            }
+            while let Some(glyph) = glyphs.get(glyph_ix)
+                && bidi_run.range.contains(&glyph.index)
+            {
+                let glyph_x = if bidi_run.rtl {
+                    right_edge(glyph_ix)
+                } else {
+                    glyph.position.x
+                };
+                // Glyphs from the same cluster share an index. The caret goes
+                // before the whole cluster, which is its rightmost edge in
+                // right-to-left text.
+                match carets.last_mut() {
+                    Some(last) if last.index == glyph.index && last.bidi_run_ix == bidi_run_ix => {
+                        if bidi_run.rtl {
+                            last.x = glyph_x;
+                        }
+                    }
+                    _ => carets.push(caret(glyph.index, glyph_x)),
+                }
+                x = right_edge(glyph_ix);
+                glyph_ix += 1;
+            }
+            if !bidi_run.rtl {
+                carets.push(caret(bidi_run.range.end, x));
+            }
+        }
+        // An index on the boundary of two directional runs is displayed with the run of
+        // the character that follows it.
+        for caret in &mut carets {
+            let is_run_end = bidi_runs[caret.bidi_run_ix].range.end == caret.index;
+            caret.canonical = !is_run_end
+                || !bidi_runs.iter().any(|bidi_run| {
+                    bidi_run.range.start == caret.index && !bidi_run.range.is_empty()
+                });
+        }
+        carets
+    }
+
+    /// Every glyph in the line, ordered from left to right. Text systems don't
+    /// necessarily return the runs of a bidirectional line in visual order.
+    fn visual_glyphs(&self) -> SmallVec<[&ShapedGlyph; 32]> {
+        let mut glyphs = self
+            .runs
+            .iter()
+            .flat_map(|run| run.glyphs.iter())
+            .collect::<SmallVec<[_; 32]>>();
+        glyphs.sort_by_key(|glyph| glyph.position.x);
+        glyphs
+    }
+
+    /// Picks the caret where the given index is displayed, falling back to the
+    /// closest one when the index falls within a cluster.
+    fn caret_ix_for_index(carets: &[Caret], index: usize) -> usize {
+        carets
+            .iter()
+            .position(|caret| caret.canonical && caret.index == index)
+            .or_else(|| {
+                carets
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, caret)| caret.index.abs_diff(index))
+                    .map(|(caret_ix, _)| caret_ix)
+            })
+            .unwrap_or(0)
+    }
+
     /// The corresponding Font at the given index
     pub fn font_id_for_index(&self, index: usize) -> Option<FontId> {
         for run in &self.runs {
@@ -683,3 +885,107 @@ impl AsCacheKeyRef for CacheKeyRef<'_> {
         *self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TestAppContext, TestDispatcher};
+    use rand::prelude::*;
+
+    fn layout_line(text: &str) -> LineLayout {
+        let dispatcher = TestDispatcher::new(StdRng::seed_from_u64(0));
+        let cx = TestAppContext::build(dispatcher, None);
+        cx.text_system()
+            .platform_text_system
+            .layout_line(text, px(16.), &[])
+    }
+
+    #[test]
+    fn test_bidi_carets() {
+        // "abc שלום", where the Hebrew word occupies bytes 4..12.
+        let line = layout_line("abc \u{5e9}\u{5dc}\u{5d5}\u{5dd}");
+        assert!(line.has_rtl());
+        let glyphs = &line.runs[0].glyphs;
+        let slot_x = |slot: usize| {
+            glyphs
+                .get(slot)
+                .map_or(line.width, |glyph| glyph.position.x)
+        };
+
+        // The Hebrew glyphs are laid out from right to left.
+        let glyph_indices = glyphs.iter().map(|glyph| glyph.index).collect::<Vec<_>>();
+        assert_eq!(glyph_indices, [0, 1, 2, 3, 10, 8, 6, 4]);
+
+        assert_eq!(line.x_for_index(2), slot_x(2));
+        // The first Hebrew character is displayed at the right edge of the line...
+        assert_eq!(line.x_for_index(4), slot_x(8));
+        assert_eq!(line.x_for_index(6), slot_x(7));
+        // ...and the end of the line sits at the left edge of the Hebrew word.
+        assert_eq!(line.x_for_index(12), slot_x(4));
+        assert_eq!(line.closest_index_for_x(slot_x(4)), 12);
+        assert_eq!(line.closest_index_for_x(slot_x(7) + px(1.)), 6);
+
+        let mut index = 0;
+        let mut visual_order = vec![index];
+        while let Some(next) = line.index_right_of(index) {
+            index = next;
+            visual_order.push(index);
+        }
+        assert_eq!(visual_order, [0, 1, 2, 3, 12, 10, 8, 6, 4]);
+
+        let mut visual_order = vec![index];
+        while let Some(next) = line.index_left_of(index) {
+            index = next;
+            visual_order.push(index);
+        }
+        assert_eq!(visual_order, [4, 6, 8, 10, 12, 3, 2, 1, 0]);
+
+        // Selecting "c ש" covers two disjoint extents.
+        assert_eq!(
+            line.x_ranges_for_index_range(2..6).as_slice(),
+            &[slot_x(2)..slot_x(4), slot_x(7)..slot_x(8)]
+        );
+
+        assert_eq!(line.index_for_x(slot_x(2) + px(1.)), Some(2));
+        assert_eq!(line.index_for_x(slot_x(4) + px(1.)), Some(10));
+        assert_eq!(line.index_for_x(slot_x(7) + px(1.)), Some(4));
+        assert_eq!(line.index_for_x(line.width), None);
+    }
+
+    #[test]
+    fn test_bidi_runs_out_of_visual_order() {
+        // Text systems may return each directional run as a separate shaped run, in
+        // logical order.
+        let mut line = layout_line("abc \u{5e9}\u{5dc}\u{5d5}\u{5dd}");
+        let glyphs = line.runs[0].glyphs.clone();
+        let hebrew_x = glyphs[4].position.x;
+        line.runs = vec![
+            ShapedRun {
+                font_id: line.runs[0].font_id,
+                glyphs: glyphs[4..].to_vec(),
+            },
+            ShapedRun {
+                font_id: line.runs[0].font_id,
+                glyphs: glyphs[..4].to_vec(),
+            },
+        ];
+
+        assert_eq!(line.index_for_x(px(1.)), Some(0));
+        assert_eq!(line.index_for_x(hebrew_x + px(1.)), Some(10));
+        assert_eq!(line.x_for_index(12), hebrew_x);
+        assert_eq!(line.index_right_of(3), Some(12));
+    }
+
+    #[test]
+    fn test_ltr_carets() {
+        let line = layout_line("abc");
+        assert!(!line.has_rtl());
+        assert_eq!(line.index_right_of(1), Some(2));
+        assert_eq!(line.index_right_of(3), None);
+        assert_eq!(line.index_left_of(0), None);
+        assert_eq!(
+            line.x_ranges_for_index_range(1..3).as_slice(),
+            &[line.x_for_index(1)..line.width]
+        );
+    }
+}
@@ -0,0 +1,416 @@
+use std::{collections::BTreeMap, ops::Range, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::{FileHandle, Fs, MTime, Metadata};
+
+/// How many bytes are read from disk at once when displaying the file.
+pub const PAGE_SIZE: u64 = 64 * 1024;
+/// Upper bound on the number of pages kept in memory, so that scrolling through huge files does
+/// not end up loading all of them.
+const MAX_CACHED_PAGES: usize = 64;
+/// How many bytes are scanned at once when searching.
+const SEARCH_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// A single byte overwrite, recorded for undo and redo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ByteEdit {
+    offset: u64,
+    old_value: u8,
+    new_value: u8,
+}
+
+/// Reads pages of a file without holding on to the [`HexBuffer`], so that reads can happen on a
+/// background thread.
+#[derive(Clone)]
+pub struct PageReader {
+    fs: Arc<dyn Fs>,
+    handle: Arc<dyn FileHandle>,
+    len: u64,
+}
+
+impl PageReader {
+    pub fn read_page(&self, page: u64) -> Result<Arc<[u8]>> {
+        let start = page * PAGE_SIZE;
+        let end = (start + PAGE_SIZE).min(self.len);
+        Ok(self.read_range(start..end, &BTreeMap::default())?.into())
+    }
+
+    /// Finds the first occurrence of `pattern` at or after `start`, wrapping around to the
+    /// beginning of the file. Pending `patches` are taken into account.
+    pub fn find(
+        &self,
+        pattern: &[u8],
+        start: u64,
+        patches: &BTreeMap<u64, u8>,
+    ) -> Result<Option<u64>> {
+        if pattern.is_empty() || pattern.len() as u64 > self.len {
+            return Ok(None);
+        }
+        let start = start.min(self.len);
+        if let Some(offset) = self.find_in_range(pattern, start..self.len, patches)? {
+            return Ok(Some(offset));
+        }
+        self.find_in_range(pattern, 0..start, patches)
+    }
+
+    /// Finds the first occurrence of `pattern` that starts within `range`.
+    fn find_in_range(
+        &self,
+        pattern: &[u8],
+        range: Range<u64>,
+        patches: &BTreeMap<u64, u8>,
+    ) -> Result<Option<u64>> {
+        let mut chunk_start = range.start;
+        while chunk_start < range.end {
+            let chunk_end = (chunk_start + SEARCH_CHUNK_SIZE).min(range.end);
+            // Read a little past the chunk so that matches spanning two chunks are found.
+            let read_end = (chunk_end + pattern.len() as u64 - 1).min(self.len);
+            let bytes = self.read_range(chunk_start..read_end, patches)?;
+            if let Some(ix) = bytes
+                .windows(pattern.len())
+                .position(|window| window == pattern)
+            {
+                return Ok(Some(chunk_start + ix as u64));
+            }
+            chunk_start = chunk_end;
+        }
+        Ok(None)
+    }
+
+    fn read_range(&self, range: Range<u64>, patches: &BTreeMap<u64, u8>) -> Result<Vec<u8>> {
+        let mut bytes = vec![0; (range.end - range.start) as usize];
+        let read = self.handle.read_at(&self.fs, range.start, &mut bytes)?;
+        bytes.truncate(read);
+        for (offset, value) in patches.range(range.start..range.start + read as u64) {
+            bytes[(offset - range.start) as usize] = *value;
+        }
+        Ok(bytes)
+    }
+}
+
+/// The contents of a file opened in the hex editor.
+///
+/// Bytes are read lazily in pages of [`PAGE_SIZE`] bytes, and overwrites are kept as patches on
+/// top of them until the buffer is saved.
+pub struct HexBuffer {
+    reader: PageReader,
+    pages: HashMap<u64, Arc<[u8]>>,
+    patches: BTreeMap<u64, u8>,
+    undo_stack: Vec<ByteEdit>,
+    redo_stack: Vec<ByteEdit>,
+    /// The depth of the undo stack when the buffer was last saved, or `None` if that state can no
+    /// longer be reached by undoing or redoing.
+    saved_undo_depth: Option<usize>,
+    /// The modification time of the file when it was opened or last saved.
+    mtime: MTime,
+}
+
+impl HexBuffer {
+    pub async fn open(fs: Arc<dyn Fs>, abs_path: &Path) -> Result<Self> {
+        let metadata = fs
+            .metadata(abs_path)
+            .await?
+            .with_context(|| format!("{abs_path:?} does not exist"))?;
+        anyhow::ensure!(!metadata.is_dir, "{abs_path:?} is a directory");
+        let handle = fs.open_handle(abs_path).await?;
+        Ok(Self {
+            reader: PageReader {
+                fs,
+                handle,
+                len: metadata.len,
+            },
+            pages: HashMap::default(),
+            patches: BTreeMap::default(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            saved_undo_depth: Some(0),
+            mtime: metadata.mtime,
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.reader.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reader.len == 0
+    }
+
+    pub fn reader(&self) -> PageReader {
+        self.reader.clone()
+    }
+
+    pub fn patches(&self) -> &BTreeMap<u64, u8> {
+        &self.patches
+    }
+
+    /// Returns the byte at `offset`, or `None` if it is past the end of the file or its page has
+    /// not been loaded yet.
+    pub fn byte(&self, offset: u64) -> Option<u8> {
+        if offset >= self.reader.len {
+            return None;
+        }
+        if let Some(value) = self.patches.get(&offset) {
+            return Some(*value);
+        }
+        let page = self.pages.get(&(offset / PAGE_SIZE))?;
+        page.get((offset % PAGE_SIZE) as usize).copied()
+    }
+
+    /// Returns the pages covering `range` that have not been loaded yet.
+    pub fn missing_pages(&self, range: Range<u64>) -> impl Iterator<Item = u64> + '_ {
+        let end = range.end.min(self.reader.len);
+        let pages = if range.start < end {
+            range.start / PAGE_SIZE..(end - 1) / PAGE_SIZE + 1
+        } else {
+            0..0
+        };
+        pages.filter(|page| !self.pages.contains_key(page))
+    }
+
+    pub fn insert_page(&mut self, page: u64, bytes: Arc<[u8]>) {
+        if self.pages.len() >= MAX_CACHED_PAGES {
+            // Evict the page furthest away from the one being loaded, as it is the least likely
+            // to be displayed again soon.
+            if let Some(furthest) = self
+                .pages
+                .keys()
+                .copied()
+                .max_by_key(|cached| cached.abs_diff(page))
+            {
+                self.pages.remove(&furthest);
+            }
+        }
+        self.pages.insert(page, bytes);
+    }
+
+    /// Overwrites the byte at `offset`. Returns `false` if the byte could not be edited, because
+    /// it is past the end of the file or has not been loaded yet.
+    pub fn set_byte(&mut self, offset: u64, new_value: u8) -> bool {
+        let Some(old_value) = self.byte(offset) else {
+            return false;
+        };
+        if old_value == new_value {
+            return true;
+        }
+        if self
+            .saved_undo_depth
+            .is_some_and(|depth| depth > self.undo_stack.len())
+        {
+            self.saved_undo_depth = None;
+        }
+        let edit = ByteEdit {
+            offset,
+            old_value,
+            new_value,
+        };
+        self.patches.insert(offset, new_value);
+        self.undo_stack.push(edit);
+        self.redo_stack.clear();
+        true
+    }
+
+    /// Reverts the last edit, returning the offset it touched.
+    pub fn undo(&mut self) -> Option<u64> {
+        let edit = self.undo_stack.pop()?;
+        self.patches.insert(edit.offset, edit.old_value);
+        self.redo_stack.push(edit);
+        Some(edit.offset)
+    }
+
+    /// Reapplies the last undone edit, returning the offset it touched.
+    pub fn redo(&mut self) -> Option<u64> {
+        let edit = self.redo_stack.pop()?;
+        self.patches.insert(edit.offset, edit.new_value);
+        self.undo_stack.push(edit);
+        Some(edit.offset)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saved_undo_depth != Some(self.undo_stack.len())
+    }
+
+    /// Returns the patched bytes grouped into contiguous runs, each of which can be written back
+    /// to the file with a single [`Fs::write_at`].
+    pub fn patched_runs(&self) -> Vec<(u64, Vec<u8>)> {
+        let mut runs: Vec<(u64, Vec<u8>)> = Vec::new();
+        for (offset, value) in &self.patches {
+            match runs.last_mut() {
+                Some((start, bytes)) if *start + bytes.len() as u64 == *offset => {
+                    bytes.push(*value);
+                }
+                _ => runs.push((*offset, vec![*value])),
+            }
+        }
+        runs
+    }
+
+    pub fn undo_depth(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// Marks the state at `undo_depth` as the one on disk, which was last modified at `mtime`.
+    /// The depth is taken before writing, so that edits made while a save is underway still
+    /// count as unsaved.
+    pub fn did_save(&mut self, undo_depth: usize, mtime: MTime) {
+        self.saved_undo_depth = Some(undo_depth);
+        self.mtime = mtime;
+    }
+
+    /// Whether another program modified the file since it was opened or last saved, in which
+    /// case writing the patches would mix the two versions.
+    pub fn has_changed_on_disk(&self, metadata: &Metadata) -> bool {
+        metadata.mtime != self.mtime || metadata.len != self.reader.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    async fn open_buffer(cx: &mut TestAppContext, content: Vec<u8>) -> (Arc<FakeFs>, HexBuffer) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/root"), json!({})).await;
+        fs.insert_file(path!("/root/firmware.bin"), content).await;
+        let buffer = HexBuffer::open(fs.clone(), Path::new(path!("/root/firmware.bin")))
+            .await
+            .unwrap();
+        (fs, buffer)
+    }
+
+    fn load_all_pages(buffer: &mut HexBuffer) {
+        let pages = buffer.missing_pages(0..buffer.len()).collect::<Vec<_>>();
+        for page in pages {
+            let bytes = buffer.reader().read_page(page).unwrap();
+            buffer.insert_page(page, bytes);
+        }
+    }
+
+    #[gpui::test]
+    async fn test_paged_reading(cx: &mut TestAppContext) {
+        let content = (0..PAGE_SIZE * 2 + 10)
+            .map(|ix| (ix % 251) as u8)
+            .collect::<Vec<_>>();
+        let (_, mut buffer) = open_buffer(cx, content.clone()).await;
+
+        assert_eq!(buffer.len(), content.len() as u64);
+        assert_eq!(buffer.byte(0), None);
+        assert_eq!(
+            buffer
+                .missing_pages(PAGE_SIZE - 1..PAGE_SIZE + 1)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        let page = buffer.reader().read_page(2).unwrap();
+        assert_eq!(page.len(), 10);
+        buffer.insert_page(2, page);
+        assert_eq!(
+            buffer.byte(PAGE_SIZE * 2 + 3),
+            Some(content[PAGE_SIZE as usize * 2 + 3])
+        );
+        assert_eq!(buffer.byte(buffer.len()), None);
+        assert_eq!(
+            buffer
+                .missing_pages(PAGE_SIZE * 2..buffer.len() + 100)
+                .count(),
+            0
+        );
+    }
+
+    #[gpui::test]
+    async fn test_edit_undo_redo_and_save(cx: &mut TestAppContext) {
+        let (fs, mut buffer) = open_buffer(cx, b"\x00\x01\x02\x03\x04".to_vec()).await;
+        assert!(!buffer.set_byte(1, 0xff), "unloaded bytes can't be edited");
+
+        load_all_pages(&mut buffer);
+        assert!(buffer.set_byte(1, 0xaa));
+        assert!(buffer.set_byte(2, 0xbb));
+        assert!(buffer.set_byte(4, 0xcc));
+        assert!(buffer.is_dirty());
+        assert_eq!(
+            buffer.patched_runs(),
+            vec![(1, vec![0xaa, 0xbb]), (4, vec![0xcc])]
+        );
+
+        assert_eq!(buffer.undo(), Some(4));
+        assert_eq!(buffer.byte(4), Some(0x04));
+        assert_eq!(buffer.redo(), Some(4));
+        assert_eq!(buffer.byte(4), Some(0xcc));
+
+        for (offset, bytes) in buffer.patched_runs() {
+            fs.write_at(Path::new(path!("/root/firmware.bin")), offset, &bytes)
+                .await
+                .unwrap();
+        }
+        let metadata = fs
+            .metadata(Path::new(path!("/root/firmware.bin")))
+            .await
+            .unwrap()
+            .unwrap();
+        buffer.did_save(buffer.undo_depth(), metadata.mtime);
+        assert!(!buffer.is_dirty());
+        assert!(!buffer.has_changed_on_disk(&metadata));
+        assert_eq!(
+            fs.read_file_sync(path!("/root/firmware.bin")).unwrap(),
+            b"\x00\xaa\xbb\x03\xcc"
+        );
+
+        // Undoing past the save point makes the buffer dirty again, redoing makes it clean.
+        buffer.undo();
+        assert!(buffer.is_dirty());
+        buffer.redo();
+        assert!(!buffer.is_dirty());
+
+        // Once a new edit replaces the undone ones, the saved state can't be reached anymore.
+        buffer.undo();
+        buffer.set_byte(0, 0x11);
+        buffer.undo();
+        assert!(buffer.is_dirty());
+    }
+
+    #[gpui::test]
+    async fn test_changed_on_disk(cx: &mut TestAppContext) {
+        let (fs, buffer) = open_buffer(cx, b"\x00\x01\x02".to_vec()).await;
+        let path = Path::new(path!("/root/firmware.bin"));
+        let metadata = fs.metadata(path).await.unwrap().unwrap();
+        assert!(!buffer.has_changed_on_disk(&metadata));
+
+        fs.write_at(path, 1, b"\xff").await.unwrap();
+        let metadata = fs.metadata(path).await.unwrap().unwrap();
+        assert!(buffer.has_changed_on_disk(&metadata));
+    }
+
+    #[gpui::test]
+    async fn test_search(cx: &mut TestAppContext) {
+        let mut content = vec![0; (SEARCH_CHUNK_SIZE + 8) as usize];
+        let boundary = SEARCH_CHUNK_SIZE as usize - 2;
+        content[boundary..boundary + 4].copy_from_slice(b"\xde\xad\xbe\xef");
+        content[4..8].copy_from_slice(b"ELF!");
+        let (_, mut buffer) = open_buffer(cx, content).await;
+        let reader = buffer.reader();
+
+        assert_eq!(
+            reader
+                .find(b"\xde\xad\xbe\xef", 0, buffer.patches())
+                .unwrap(),
+            Some(boundary as u64)
+        );
+        assert_eq!(
+            reader.find(b"ELF!", 100, buffer.patches()).unwrap(),
+            Some(4),
+            "search wraps around to the start of the file"
+        );
+        assert_eq!(reader.find(b"missing", 0, buffer.patches()).unwrap(), None);
+
+        load_all_pages(&mut buffer);
+        buffer.set_byte(5, b'X');
+        assert_eq!(reader.find(b"ELF!", 0, buffer.patches()).unwrap(), None);
+        assert_eq!(reader.find(b"EXF!", 0, buffer.patches()).unwrap(), Some(4));
+    }
+}
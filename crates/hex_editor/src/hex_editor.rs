@@ -0,0 +1,941 @@
+mod hex_buffer;
+
+use std::{
+    ops::Range,
+    path::Path,
+    sync::{Arc, LazyLock},
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use editor::{Editor, EditorElement, EditorStyle};
+use file_icons::FileIcons;
+use fs::Fs;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, KeyDownEvent,
+    MouseButton, Render, ScrollStrategy, SharedString, Task, TextStyle, UniformList,
+    UniformListScrollHandle, actions, uniform_list,
+};
+use project::Project;
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::{WithScrollbar, prelude::*};
+use util::{paths::PathExt, size::format_file_size};
+use workspace::{
+    ItemSettings, Workspace,
+    invalid_buffer_view::InvalidBufferView,
+    item::{Item, ItemEvent, SaveOptions},
+    notifications::NotifyTaskExt,
+};
+use zed_actions::hex_editor::ReopenAsHex;
+
+pub use crate::hex_buffer::*;
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor up one row.
+        MoveUp,
+        /// Moves the cursor down one row.
+        MoveDown,
+        /// Moves the cursor up one page.
+        MovePageUp,
+        /// Moves the cursor down one page.
+        MovePageDown,
+        /// Moves the cursor to the first byte of its row.
+        MoveToRowStart,
+        /// Moves the cursor to the last byte of its row.
+        MoveToRowEnd,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Switches typing between the hex and the ASCII columns.
+        ToggleColumn,
+        /// Undoes the last byte overwrite.
+        Undo,
+        /// Redoes the last undone byte overwrite.
+        Redo,
+        /// Opens the query bar to jump to a byte offset.
+        GoToOffset,
+        /// Opens the query bar to search for a byte pattern or a string.
+        Search,
+        /// Moves the cursor to the next match of the last search.
+        SelectNextMatch,
+    ]
+);
+
+const BYTES_PER_ROW: u64 = 16;
+const ROWS_PER_PAGE: u64 = 32;
+
+static HEX_BYTES: LazyLock<[SharedString; 256]> =
+    LazyLock::new(|| std::array::from_fn(|byte| SharedString::from(format!("{byte:02X}"))));
+static UNLOADED_BYTE: SharedString = SharedString::new_static("··");
+static EMPTY_BYTE: SharedString = SharedString::new_static("  ");
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(reopen_as_hex);
+    })
+    .detach();
+}
+
+fn reopen_as_hex(
+    workspace: &mut Workspace,
+    _: &ReopenAsHex,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(active_item) = workspace.active_item(cx) else {
+        return;
+    };
+    if active_item.downcast::<HexEditor>().is_some() {
+        return;
+    }
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        workspace.show_error(
+            &anyhow::anyhow!("The hex editor is only available for local projects"),
+            cx,
+        );
+        return;
+    }
+    let abs_path: Arc<Path> =
+        if let Some(invalid_buffer_view) = active_item.downcast::<InvalidBufferView>() {
+            invalid_buffer_view.read(cx).abs_path.clone()
+        } else {
+            let Some(abs_path) = active_item
+                .project_path(cx)
+                .and_then(|project_path| project.absolute_path(&project_path, cx))
+            else {
+                return;
+            };
+            abs_path.into()
+        };
+
+    let pane = workspace.active_pane().clone();
+    if let Some(existing) = pane
+        .read(cx)
+        .items_of_type::<HexEditor>()
+        .find(|hex_editor| hex_editor.read(cx).abs_path == abs_path)
+    {
+        workspace.activate_item(&existing, true, true, window, cx);
+        return;
+    }
+
+    let open_hex_editor = HexEditor::open(abs_path, project.fs().clone(), window, cx);
+    cx.spawn_in(window, async move |_, cx| {
+        let hex_editor = open_hex_editor.await?;
+        pane.update_in(cx, |pane, window, cx| {
+            let index = pane
+                .index_for_item(active_item.as_ref())
+                .map(|index| index + 1);
+            pane.add_item(Box::new(hex_editor), true, true, index, window, cx);
+            // Replace the item the file was previously shown in, unless that would discard edits.
+            if !active_item.is_dirty(cx) {
+                pane.remove_item(active_item.item_id(), false, false, window, cx);
+            }
+        })
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QueryMode {
+    GoToOffset,
+    Search,
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    Saved,
+    Reloaded,
+    Conflicted,
+}
+
+/// An editor showing the raw bytes of a file as offset, hex and ASCII columns.
+///
+/// Bytes can only be overwritten, never inserted or removed, so that offsets within the file stay
+/// stable while editing.
+pub struct HexEditor {
+    abs_path: Arc<Path>,
+    fs: Arc<dyn Fs>,
+    buffer: HexBuffer,
+    cursor: u64,
+    /// Whether the next hex digit typed replaces the low nibble of the byte under the cursor.
+    low_nibble: bool,
+    column: Column,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    query_editor: Entity<Editor>,
+    query_mode: Option<QueryMode>,
+    last_match: Option<Range<u64>>,
+    message: Option<SharedString>,
+    /// Whether saving found that the file was changed on disk by another program. The next save
+    /// overwrites it, which the workspace confirms first.
+    has_conflict: bool,
+    loading_pages: HashMap<u64, Task<()>>,
+    _search_task: Option<Task<()>>,
+}
+
+impl HexEditor {
+    pub fn open(
+        abs_path: Arc<Path>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        window.spawn(cx, async move |cx| {
+            let buffer = HexBuffer::open(fs.clone(), &abs_path).await?;
+            cx.update(|window, cx| cx.new(|cx| Self::new(abs_path, fs, buffer, window, cx)))
+        })
+    }
+
+    fn new(
+        abs_path: Arc<Path>,
+        fs: Arc<dyn Fs>,
+        buffer: HexBuffer,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            abs_path,
+            fs,
+            buffer,
+            cursor: 0,
+            low_nibble: false,
+            column: Column::Hex,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            query_editor: cx.new(|cx| Editor::single_line(window, cx)),
+            query_mode: None,
+            last_match: None,
+            message: None,
+            has_conflict: false,
+            loading_pages: HashMap::default(),
+            _search_task: None,
+        }
+    }
+
+    pub fn abs_path(&self) -> &Arc<Path> {
+        &self.abs_path
+    }
+
+    fn load_pages(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+        let pages = self
+            .buffer
+            .missing_pages(range)
+            .filter(|page| !self.loading_pages.contains_key(page))
+            .collect::<Vec<_>>();
+        for page in pages {
+            let reader = self.buffer.reader();
+            let task = cx.spawn(async move |this, cx| {
+                let bytes = cx
+                    .background_spawn(async move { reader.read_page(page) })
+                    .await;
+                this.update(cx, |this, cx| {
+                    this.loading_pages.remove(&page);
+                    match bytes {
+                        Ok(bytes) => this.buffer.insert_page(page, bytes),
+                        Err(error) => {
+                            this.message = Some(format!("Failed to read file: {error}").into())
+                        }
+                    }
+                    cx.notify();
+                })
+                .ok();
+            });
+            self.loading_pages.insert(page, task);
+        }
+    }
+
+    fn move_cursor_to(&mut self, offset: u64, cx: &mut Context<Self>) {
+        self.cursor = offset.min(self.buffer.len().saturating_sub(1));
+        self.low_nibble = false;
+        if !self.buffer.is_empty() {
+            self.scroll_handle
+                .scroll_to_item((self.cursor / BYTES_PER_ROW) as usize, ScrollStrategy::Top);
+        }
+        cx.notify();
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        if self.low_nibble {
+            self.low_nibble = false;
+            cx.notify();
+        } else {
+            self.move_cursor_to(self.cursor.saturating_sub(1), cx);
+        }
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(self.cursor + 1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor >= BYTES_PER_ROW {
+            self.move_cursor_to(self.cursor - BYTES_PER_ROW, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor + BYTES_PER_ROW < self.buffer.len() {
+            self.move_cursor_to(self.cursor + BYTES_PER_ROW, cx);
+        }
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor % BYTES_PER_ROW;
+        let target = self.cursor.saturating_sub(BYTES_PER_ROW * ROWS_PER_PAGE);
+        self.move_cursor_to(target.max(offset), cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(self.cursor + BYTES_PER_ROW * ROWS_PER_PAGE, cx);
+    }
+
+    fn move_to_row_start(&mut self, _: &MoveToRowStart, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(self.cursor - self.cursor % BYTES_PER_ROW, cx);
+    }
+
+    fn move_to_row_end(&mut self, _: &MoveToRowEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(
+            self.cursor - self.cursor % BYTES_PER_ROW + BYTES_PER_ROW - 1,
+            cx,
+        );
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(0, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(self.buffer.len(), cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.low_nibble = false;
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(offset) = self.buffer.undo() {
+            self.move_cursor_to(offset, cx);
+            cx.emit(HexEditorEvent::Edited);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(offset) = self.buffer.redo() {
+            self.move_cursor_to(offset, cx);
+            cx.emit(HexEditorEvent::Edited);
+        }
+    }
+
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.focus_handle.is_focused(window) {
+            return;
+        }
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(key_char) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        let mut chars = key_char.chars();
+        let (Some(character), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+
+        match self.column {
+            Column::Hex => {
+                let Some(nibble) = character.to_digit(16) else {
+                    return;
+                };
+                self.write_nibble(nibble as u8, cx);
+            }
+            Column::Ascii => {
+                if !character.is_ascii_graphic() && character != ' ' {
+                    return;
+                }
+                if self.write_byte(character as u8, cx) {
+                    self.move_cursor_to(self.cursor + 1, cx);
+                }
+            }
+        }
+        cx.stop_propagation();
+    }
+
+    fn write_nibble(&mut self, nibble: u8, cx: &mut Context<Self>) {
+        let Some(current) = self.buffer.byte(self.cursor) else {
+            return;
+        };
+        let value = if self.low_nibble {
+            (current & 0xF0) | nibble
+        } else {
+            (nibble << 4) | (current & 0x0F)
+        };
+        if !self.write_byte(value, cx) {
+            return;
+        }
+        if self.low_nibble {
+            self.move_cursor_to(self.cursor + 1, cx);
+        } else {
+            self.low_nibble = true;
+        }
+    }
+
+    fn write_byte(&mut self, value: u8, cx: &mut Context<Self>) -> bool {
+        if self.cursor >= self.buffer.len() {
+            self.message = Some("Bytes can only be overwritten, not appended".into());
+            cx.notify();
+            return false;
+        }
+        if !self.buffer.set_byte(self.cursor, value) {
+            return false;
+        }
+        self.message = None;
+        cx.emit(HexEditorEvent::Edited);
+        cx.notify();
+        true
+    }
+
+    fn go_to_offset(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.deploy_query_bar(QueryMode::GoToOffset, window, cx);
+    }
+
+    fn search(&mut self, _: &Search, window: &mut Window, cx: &mut Context<Self>) {
+        self.deploy_query_bar(QueryMode::Search, window, cx);
+    }
+
+    fn deploy_query_bar(&mut self, mode: QueryMode, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_mode != Some(mode) {
+            let placeholder = match mode {
+                QueryMode::GoToOffset => "Go to offset (decimal, or hex with 0x)",
+                QueryMode::Search => "Search for hex bytes (DE AD BE EF) or text (\"text\")",
+            };
+            self.query_editor.update(cx, |editor, cx| {
+                editor.clear(window, cx);
+                editor.set_placeholder_text(placeholder, window, cx);
+            });
+            self.query_mode = Some(mode);
+        }
+        self.query_editor.focus_handle(cx).focus(window);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(mode) = self.query_mode else {
+            return;
+        };
+        let query = self.query_editor.read(cx).text(cx);
+        match mode {
+            QueryMode::GoToOffset => match parse_offset(&query) {
+                Some(offset) if offset < self.buffer.len().max(1) => {
+                    self.message = None;
+                    self.move_cursor_to(offset, cx);
+                }
+                Some(_) => {
+                    self.message = Some("Offset is past the end of the file".into());
+                    cx.notify();
+                }
+                None => {
+                    self.message = Some(format!("Invalid offset: {query}").into());
+                    cx.notify();
+                }
+            },
+            QueryMode::Search => self.select_next_match(&SelectNextMatch, window, cx),
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_mode.take().is_some() {
+            window.focus(&self.focus_handle);
+        } else {
+            self.last_match = None;
+            self.message = None;
+        }
+        cx.notify();
+    }
+
+    fn select_next_match(&mut self, _: &SelectNextMatch, _: &mut Window, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let Some(pattern) = parse_pattern(&query) else {
+            return;
+        };
+        let start = match &self.last_match {
+            Some(last_match) if last_match.start == self.cursor => self.cursor + 1,
+            _ => self.cursor,
+        };
+        let reader = self.buffer.reader();
+        let patches = self.buffer.patches().clone();
+        self._search_task = Some(cx.spawn(async move |this, cx| {
+            let pattern_len = pattern.len() as u64;
+            let result = cx
+                .background_spawn(async move { reader.find(&pattern, start, &patches) })
+                .await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(Some(offset)) => {
+                        this.message = None;
+                        this.last_match = Some(offset..offset + pattern_len);
+                        this.move_cursor_to(offset, cx);
+                    }
+                    Ok(None) => {
+                        this.last_match = None;
+                        this.message = Some("No matches".into());
+                    }
+                    Err(error) => this.message = Some(format!("Search failed: {error}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn render_rows(&self, cx: &Context<Self>) -> UniformList {
+        let row_count = self.buffer.len().div_ceil(BYTES_PER_ROW) as usize;
+        uniform_list(
+            "hex-editor-rows",
+            row_count,
+            cx.processor(|this, range: Range<usize>, _, cx| {
+                this.load_pages(
+                    range.start as u64 * BYTES_PER_ROW..range.end as u64 * BYTES_PER_ROW,
+                    cx,
+                );
+                range.map(|row| this.render_row(row as u64, cx)).collect()
+            }),
+        )
+        .track_scroll(self.scroll_handle.clone())
+    }
+
+    fn render_row(&self, row: u64, cx: &Context<Self>) -> AnyElement {
+        let row_start = row * BYTES_PER_ROW;
+        let offsets = row_start..row_start + BYTES_PER_ROW;
+        let hex_cells = offsets.clone().map(|offset| {
+            let label = if offset >= self.buffer.len() {
+                EMPTY_BYTE.clone()
+            } else {
+                self.buffer
+                    .byte(offset)
+                    .map(|byte| HEX_BYTES[byte as usize].clone())
+                    .unwrap_or_else(|| UNLOADED_BYTE.clone())
+            };
+            self.render_cell(offset, Column::Hex, label, cx)
+                .when(offset % BYTES_PER_ROW == BYTES_PER_ROW / 2 - 1, |cell| {
+                    cell.mr_2()
+                })
+        });
+        let ascii_cells = offsets.map(|offset| {
+            let label = match self.buffer.byte(offset) {
+                Some(byte) if byte.is_ascii_graphic() || byte == b' ' => {
+                    SharedString::from((byte as char).to_string())
+                }
+                Some(_) => ".".into(),
+                None => " ".into(),
+            };
+            self.render_cell(offset, Column::Ascii, label, cx)
+        });
+
+        h_flex()
+            .id(("hex-editor-row", row_start))
+            .gap_4()
+            .px_2()
+            .child(
+                Label::new(format!("{row_start:08X}"))
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(h_flex().children(hex_cells))
+            .child(h_flex().children(ascii_cells))
+            .into_any_element()
+    }
+
+    fn render_cell(
+        &self,
+        offset: u64,
+        column: Column,
+        label: SharedString,
+        cx: &Context<Self>,
+    ) -> Div {
+        let colors = cx.theme().colors();
+        let is_cursor = offset == self.cursor && offset < self.buffer.len();
+        let is_match = self
+            .last_match
+            .as_ref()
+            .is_some_and(|last_match| last_match.contains(&offset));
+        let is_patched = self.buffer.patches().contains_key(&offset);
+        let selection = cx.theme().players().local().selection;
+        let cursor = cx.theme().players().local().cursor;
+
+        div()
+            .when(column == Column::Hex, |cell| cell.px_0p5())
+            .when(is_match, |cell| cell.bg(colors.search_match_background))
+            .when(is_cursor, |cell| {
+                if column == self.column {
+                    cell.bg(selection)
+                } else {
+                    cell.border_b_1().border_color(cursor)
+                }
+            })
+            .when(offset < self.buffer.len(), |cell| {
+                cell.on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _, window, cx| {
+                        this.column = column;
+                        this.move_cursor_to(offset, cx);
+                        window.focus(&this.focus_handle);
+                    }),
+                )
+            })
+            .child(
+                Label::new(label)
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .when(is_patched, |label| label.color(Color::Modified))
+                    .when(label_is_unloaded(offset, &self.buffer), |label| {
+                        label.color(Color::Muted)
+                    }),
+            )
+    }
+
+    fn render_query_bar(&self, mode: QueryMode, cx: &Context<Self>) -> impl IntoElement {
+        let icon = match mode {
+            QueryMode::GoToOffset => IconName::ArrowRight,
+            QueryMode::Search => IconName::MagnifyingGlass,
+        };
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.buffer_font.family.clone(),
+            font_features: settings.buffer_font.features.clone(),
+            font_size: TextSize::Small.rems(cx).into(),
+            font_weight: settings.buffer_font.weight,
+            ..Default::default()
+        };
+        h_flex()
+            .m_1()
+            .px_2()
+            .py_0p5()
+            .gap_2()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border_focused)
+            .bg(cx.theme().colors().editor_background)
+            .child(Icon::new(icon).size(IconSize::XSmall).color(Color::Muted))
+            .child(EditorElement::new(
+                &self.query_editor,
+                EditorStyle {
+                    background: cx.theme().colors().editor_background,
+                    local_player: cx.theme().players().local(),
+                    text: text_style,
+                    ..Default::default()
+                },
+            ))
+    }
+
+    fn render_status_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let position = if self.buffer.is_empty() {
+            "Empty file".to_string()
+        } else {
+            let value = self
+                .buffer
+                .byte(self.cursor)
+                .map(|byte| format!(" · Value 0x{byte:02X} ({byte})"))
+                .unwrap_or_default();
+            format!(
+                "Offset 0x{:08X} ({}){value} · {}",
+                self.cursor,
+                self.cursor,
+                format_file_size(self.buffer.len(), false),
+            )
+        };
+        h_flex()
+            .px_2()
+            .py_0p5()
+            .gap_3()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(position)
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(self.message.clone().map(|message| {
+                Label::new(message)
+                    .size(LabelSize::Small)
+                    .color(Color::Warning)
+            }))
+    }
+}
+
+fn label_is_unloaded(offset: u64, buffer: &HexBuffer) -> bool {
+    offset < buffer.len() && buffer.byte(offset).is_none()
+}
+
+/// Parses an offset given either in decimal or as a `0x`-prefixed hex number.
+fn parse_offset(query: &str) -> Option<u64> {
+    parse_int::parse::<u64>(query.trim()).ok()
+}
+
+/// Parses a search query into the bytes to look for.
+///
+/// Queries made of hex digit pairs, optionally separated by whitespace, are searched for as raw
+/// bytes. Anything else, or text wrapped in double quotes, is searched for as UTF-8 text.
+fn parse_pattern(query: &str) -> Option<Vec<u8>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    if let Some(text) = query
+        .strip_prefix('"')
+        .and_then(|query| query.strip_suffix('"'))
+        .filter(|text| !text.is_empty())
+    {
+        return Some(text.as_bytes().to_vec());
+    }
+    let digits = query
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect::<String>();
+    if digits.len() % 2 == 0
+        && digits
+            .chars()
+            .all(|character| character.is_ascii_hexdigit())
+    {
+        let bytes = (0..digits.len())
+            .step_by(2)
+            .map(|ix| u8::from_str_radix(&digits[ix..ix + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        return Some(bytes);
+    }
+    Some(query.as_bytes().to_vec())
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::Saved | HexEditorEvent::Reloaded | HexEditorEvent::Conflicted => {
+                f(ItemEvent::UpdateTab)
+            }
+        }
+    }
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        self.abs_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+            .into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(
+            self.abs_path
+                .compact()
+                .to_string_lossy()
+                .into_owned()
+                .into(),
+        )
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&self.abs_path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Hex Editor Opened")
+    }
+
+    fn is_dirty(&self, _: &App) -> bool {
+        self.buffer.is_dirty()
+    }
+
+    fn has_conflict(&self, _: &App) -> bool {
+        self.has_conflict
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let runs = self.buffer.patched_runs();
+        let undo_depth = self.buffer.undo_depth();
+        let overwrite = self.has_conflict;
+        cx.spawn(async move |this, cx| {
+            let metadata = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{abs_path:?} no longer exists"))?;
+            let has_changed =
+                this.read_with(cx, |this, _| this.buffer.has_changed_on_disk(&metadata))?;
+            if has_changed && !overwrite {
+                this.update(cx, |this, cx| {
+                    this.has_conflict = true;
+                    this.message = Some(
+                        "The file changed on disk. Reload it, or save again to overwrite it."
+                            .into(),
+                    );
+                    cx.emit(HexEditorEvent::Conflicted);
+                    cx.notify();
+                })?;
+                anyhow::bail!("{abs_path:?} changed on disk since it was opened");
+            }
+
+            for (offset, bytes) in runs {
+                fs.write_at(&abs_path, offset, &bytes).await?;
+            }
+            let metadata = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{abs_path:?} no longer exists"))?;
+            this.update(cx, |this, cx| {
+                this.buffer.did_save(undo_depth, metadata.mtime);
+                this.has_conflict = false;
+                this.message = None;
+                cx.emit(HexEditorEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let buffer = HexBuffer::open(fs, &abs_path).await?;
+            this.update(cx, |this, cx| {
+                this.buffer = buffer;
+                this.loading_pages.clear();
+                this.last_match = None;
+                this.message = None;
+                this.has_conflict = false;
+                this.move_cursor_to(this.cursor, cx);
+                cx.emit(HexEditorEvent::Reloaded);
+            })
+        })
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("hex-editor")
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_row_start))
+            .on_action(cx.listener(Self::move_to_row_end))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::go_to_offset))
+            .on_action(cx.listener(Self::search))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::select_next_match))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .when_some(self.query_mode, |this, mode| {
+                this.child(self.render_query_bar(mode, cx))
+            })
+            .child(
+                div()
+                    .flex_1()
+                    .size_full()
+                    .py_1()
+                    .child(self.render_rows(cx).size_full())
+                    .vertical_scrollbar_for(self.scroll_handle.clone(), window, cx),
+            )
+            .child(self.render_status_bar(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("1024"), Some(1024));
+        assert_eq!(parse_offset(" 0x1F "), Some(0x1f));
+        assert_eq!(parse_offset("0x"), None);
+        assert_eq!(parse_offset("offset"), None);
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(
+            parse_pattern("DE AD be ef"),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert_eq!(parse_pattern("cafe"), Some(vec![0xca, 0xfe]));
+        assert_eq!(parse_pattern("\"cafe\""), Some(b"cafe".to_vec()));
+        assert_eq!(parse_pattern("ELF"), Some(b"ELF".to_vec()));
+        assert_eq!(parse_pattern("abc"), Some(b"abc".to_vec()));
+        assert_eq!(parse_pattern("  "), None);
+    }
+}
@@ -2471,6 +2471,118 @@ pub fn range_from_lsp(range: lsp::Range) -> Range<Unclipped<PointUtf16>> {
     start..end
 }
 
+/// Converts a point to a position whose column is measured in the server's encoding.
+pub fn point_to_lsp_with_encoding(
+    point: PointUtf16,
+    encoding: lsp::PositionEncoding,
+    snapshot: &text::BufferSnapshot,
+) -> lsp::Position {
+    match encoding {
+        lsp::PositionEncoding::Utf16 => point_to_lsp(point),
+        lsp::PositionEncoding::Utf8 => {
+            let point = snapshot.unclipped_point_utf16_to_point(Unclipped(point));
+            lsp::Position::new(point.row, point.column)
+        }
+        lsp::PositionEncoding::Utf32 => {
+            let point = snapshot.unclipped_point_utf16_to_point(Unclipped(point));
+            let column = snapshot
+                .text_for_range(Point::new(point.row, 0)..point)
+                .map(|chunk| chunk.chars().count())
+                .sum::<usize>();
+            lsp::Position::new(point.row, column as u32)
+        }
+    }
+}
+
+/// Converts a position whose column is measured in the server's encoding to a point.
+/// Like [`point_from_lsp`], columns past the end of their line are preserved so that
+/// callers can detect them by clipping.
+pub fn point_from_lsp_with_encoding(
+    position: lsp::Position,
+    encoding: lsp::PositionEncoding,
+    snapshot: &text::BufferSnapshot,
+) -> Unclipped<PointUtf16> {
+    point_from_lsp_in_text(position, encoding, snapshot.as_rope())
+}
+
+/// Like [`point_from_lsp_with_encoding`], for positions in text that isn't open in a buffer,
+/// such as a file on disk.
+pub fn point_from_lsp_in_text(
+    position: lsp::Position,
+    encoding: lsp::PositionEncoding,
+    text: &Rope,
+) -> Unclipped<PointUtf16> {
+    if encoding == lsp::PositionEncoding::Utf16 || position.line > text.max_point().row {
+        return point_from_lsp(position);
+    }
+
+    let row = position.line;
+    let (column, overflow) = match encoding {
+        lsp::PositionEncoding::Utf8 => {
+            let line_len = text.line_len(row);
+            let point = text.clip_point(
+                Point::new(row, position.character.min(line_len)),
+                Bias::Left,
+            );
+            (
+                text.point_to_point_utf16(point).column,
+                position.character.saturating_sub(line_len),
+            )
+        }
+        lsp::PositionEncoding::Utf16 | lsp::PositionEncoding::Utf32 => {
+            let mut column = 0;
+            let mut remaining = position.character;
+            for ch in text
+                .chars_at(text.point_to_offset(Point::new(row, 0)))
+                .take_while(|ch| *ch != '\n')
+                .take(position.character as usize)
+            {
+                column += ch.len_utf16() as u32;
+                remaining -= 1;
+            }
+            (column, remaining)
+        }
+    };
+    Unclipped(PointUtf16::new(row, column + overflow))
+}
+
+pub fn range_to_lsp_with_encoding(
+    range: Range<PointUtf16>,
+    encoding: lsp::PositionEncoding,
+    snapshot: &text::BufferSnapshot,
+) -> Result<lsp::Range> {
+    let range = range_to_lsp(range)?;
+    if encoding == lsp::PositionEncoding::Utf16 {
+        return Ok(range);
+    }
+    Ok(lsp::Range {
+        start: point_to_lsp_with_encoding(point_from_lsp(range.start).0, encoding, snapshot),
+        end: point_to_lsp_with_encoding(point_from_lsp(range.end).0, encoding, snapshot),
+    })
+}
+
+pub fn range_from_lsp_with_encoding(
+    range: lsp::Range,
+    encoding: lsp::PositionEncoding,
+    snapshot: &text::BufferSnapshot,
+) -> Range<Unclipped<PointUtf16>> {
+    range_from_lsp_in_text(range, encoding, snapshot.as_rope())
+}
+
+pub fn range_from_lsp_in_text(
+    range: lsp::Range,
+    encoding: lsp::PositionEncoding,
+    text: &Rope,
+) -> Range<Unclipped<PointUtf16>> {
+    let mut start = point_from_lsp_in_text(range.start, encoding, text);
+    let mut end = point_from_lsp_in_text(range.end, encoding, text);
+    if start > end {
+        log::warn!("range_from_lsp called with inverted range {start:?}-{end:?}");
+        mem::swap(&mut start, &mut end);
+    }
+    start..end
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2708,4 +2820,58 @@ mod tests {
             assert_eq!(config.tab_size, 0);
         }
     }
+
+    #[test]
+    fn test_lsp_position_encodings() {
+        // "🦀" is 4 bytes, 2 UTF-16 code units and 1 scalar value; "é" is 2 bytes.
+        let buffer = text::Buffer::new(0, text::BufferId::new(1).unwrap(), "a🦀é = 1;\n🦀\n");
+        let snapshot = buffer.snapshot();
+        let after_e = PointUtf16::new(0, 4);
+
+        let cases = [
+            (lsp::PositionEncoding::Utf8, 7),
+            (lsp::PositionEncoding::Utf16, 4),
+            (lsp::PositionEncoding::Utf32, 3),
+        ];
+        for (encoding, column) in cases {
+            let position = lsp::Position::new(0, column);
+            assert_eq!(
+                point_to_lsp_with_encoding(after_e, encoding, &snapshot),
+                position,
+                "{encoding:?}"
+            );
+            assert_eq!(
+                point_from_lsp_with_encoding(position, encoding, &snapshot),
+                Unclipped(after_e),
+                "{encoding:?}"
+            );
+        }
+
+        // Columns past the end of a line stay past it, so that callers can clip them.
+        assert_eq!(
+            point_from_lsp_with_encoding(
+                lsp::Position::new(1, 6),
+                lsp::PositionEncoding::Utf8,
+                &snapshot
+            ),
+            Unclipped(PointUtf16::new(1, 4))
+        );
+        assert_eq!(
+            point_from_lsp_with_encoding(
+                lsp::Position::new(1, 3),
+                lsp::PositionEncoding::Utf32,
+                &snapshot
+            ),
+            Unclipped(PointUtf16::new(1, 4))
+        );
+
+        assert_eq!(
+            range_from_lsp_with_encoding(
+                lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(1, 4)),
+                lsp::PositionEncoding::Utf8,
+                &snapshot
+            ),
+            Unclipped(PointUtf16::new(1, 0))..Unclipped(PointUtf16::new(1, 2))
+        );
+    }
 }
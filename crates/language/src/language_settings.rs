@@ -12,12 +12,13 @@ use itertools::{Either, Itertools};
 
 pub use settings::{
     CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FormatOnSave,
-    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
-    RewrapBehavior, SelectedFormatter, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
+    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LocalEditPredictionApi,
+    LspInsertMode, RewrapBehavior, SelectedFormatter, ShowWhitespaceSetting, SoftWrap,
+    WordsCompletionMode,
 };
 use settings::{ExtendingVec, Settings, SettingsContent, SettingsLocation, SettingsStore};
 use shellexpand;
-use std::{borrow::Cow, num::NonZeroU32, path::Path, sync::Arc};
+use std::{borrow::Cow, num::NonZeroU32, path::Path, sync::Arc, time::Duration};
 
 /// Initializes the language settings.
 pub fn init(cx: &mut App) {
@@ -87,6 +88,10 @@ pub struct LanguageSettings {
     pub wrap_guides: Vec<usize>,
     /// Indent guide related settings.
     pub indent_guides: IndentGuideSettings,
+    /// Whether to color brackets by their nesting depth.
+    pub colorize_brackets: bool,
+    /// Whether to draw a guide between the brackets of the scope enclosing the cursor.
+    pub show_bracket_guides: bool,
     /// Whether or not to perform a buffer format before saving.
     pub format_on_save: FormatOnSave,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
@@ -377,6 +382,8 @@ pub struct EditPredictionSettings {
     pub mode: settings::EditPredictionsMode,
     /// Settings specific to GitHub Copilot.
     pub copilot: CopilotSettings,
+    /// Settings specific to the `local` provider.
+    pub local: LocalEditPredictionSettings,
     /// Whether edit predictions are enabled in the assistant panel.
     /// This setting has no effect if globally disabled.
     pub enabled_in_text_threads: bool,
@@ -412,6 +419,24 @@ pub struct CopilotSettings {
     pub enterprise_uri: Option<String>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct LocalEditPredictionSettings {
+    /// The API used to request completions.
+    pub api: LocalEditPredictionApi,
+    /// The URL of the completion server.
+    pub api_url: String,
+    /// The model to request completions from.
+    pub model: String,
+    /// A fill-in-the-middle prompt template with `{prefix}` and `{suffix}` placeholders.
+    pub fim_template: Option<String>,
+    /// The maximum number of tokens to generate for a prediction.
+    pub max_output_tokens: u32,
+    /// The maximum number of bytes around the cursor to include in the prompt.
+    pub max_context_bytes: usize,
+    /// How long to wait after typing stops before requesting a prediction.
+    pub debounce: Duration,
+}
+
 impl AllLanguageSettings {
     /// Returns the [`LanguageSettings`] for the language with the specified name.
     pub fn language<'a>(
@@ -525,6 +550,8 @@ impl settings::Settings for AllLanguageSettings {
                     coloring: indent_guides.coloring.unwrap(),
                     background_coloring: indent_guides.background_coloring.unwrap(),
                 },
+                colorize_brackets: settings.colorize_brackets.unwrap(),
+                show_bracket_guides: settings.show_bracket_guides.unwrap(),
                 format_on_save: settings.format_on_save.unwrap(),
                 remove_trailing_whitespace_on_save: settings
                     .remove_trailing_whitespace_on_save
@@ -622,6 +649,17 @@ impl settings::Settings for AllLanguageSettings {
             enterprise_uri: copilot.enterprise_uri,
         };
 
+        let local = edit_predictions.local.unwrap();
+        let local_settings = LocalEditPredictionSettings {
+            api: local.api.unwrap(),
+            api_url: local.api_url.unwrap(),
+            model: local.model.unwrap(),
+            fim_template: local.fim_template,
+            max_output_tokens: local.max_output_tokens.unwrap(),
+            max_context_bytes: local.max_context_bytes.unwrap(),
+            debounce: Duration::from_millis(local.debounce_ms.unwrap()),
+        };
+
         let enabled_in_text_threads = edit_predictions.enabled_in_text_threads.unwrap();
 
         let mut file_types: FxHashMap<Arc<str>, GlobSet> = FxHashMap::default();
@@ -655,6 +693,7 @@ impl settings::Settings for AllLanguageSettings {
                     .collect(),
                 mode: edit_predictions_mode,
                 copilot: copilot_settings,
+                local: local_settings,
                 enabled_in_text_threads,
             },
             defaults: default_language_settings,
@@ -695,6 +734,17 @@ impl settings::Settings for AllLanguageSettings {
         if let Some(b) = vscode.read_bool("editor.guides.indentation") {
             d.indent_guides.get_or_insert_default().enabled = Some(b);
         }
+        vscode.bool_setting(
+            "editor.bracketPairColorization.enabled",
+            &mut d.colorize_brackets,
+        );
+        if let Some(guides) = vscode.read_value("editor.guides.bracketPairs") {
+            d.show_bracket_guides = match guides {
+                serde_json::Value::Bool(b) => Some(*b),
+                serde_json::Value::String(s) => Some(s != "false"),
+                _ => None,
+            };
+        }
 
         if let Some(b) = vscode.read_bool("editor.guides.formatOnSave") {
             d.format_on_save = Some(if b {
@@ -0,0 +1,345 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    AnyElement, App, Context, DismissEvent, Entity, EventEmitter, Focusable, Render, Task,
+    WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use settings::Settings as _;
+use ui::{HighlightedLabel, IconButton, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace, WorkspaceSettings, notifications::NotifyTaskExt as _};
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(
+            |workspace, _: &zed_actions::layout_selector::Toggle, window, cx| {
+                toggle_layout_selector(workspace, window, cx);
+            },
+        );
+    })
+    .detach();
+}
+
+fn toggle_layout_selector(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let workspace_handle = cx.entity().downgrade();
+    let saved_layouts = workspace.saved_layout_names();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        let delegate = LayoutSelectorDelegate::new(
+            cx.entity().downgrade(),
+            workspace_handle,
+            saved_layouts,
+            cx,
+        );
+        LayoutSelector::new(delegate, window, cx)
+    });
+}
+
+pub struct LayoutSelector {
+    picker: Entity<Picker<LayoutSelectorDelegate>>,
+}
+
+impl ModalView for LayoutSelector {}
+
+impl EventEmitter<DismissEvent> for LayoutSelector {}
+
+impl Focusable for LayoutSelector {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for LayoutSelector {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(22.)).child(self.picker.clone())
+    }
+}
+
+impl LayoutSelector {
+    fn new(delegate: LayoutSelectorDelegate, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum LayoutEntry {
+    /// A layout saved in the workspace.
+    Saved(String),
+    /// A layout declared in the `layouts` setting.
+    Configured(String),
+}
+
+impl LayoutEntry {
+    fn name(&self) -> &str {
+        match self {
+            LayoutEntry::Saved(name) | LayoutEntry::Configured(name) => name,
+        }
+    }
+}
+
+pub struct LayoutSelectorDelegate {
+    layouts: Vec<LayoutEntry>,
+    matches: Vec<StringMatch>,
+    /// The name the current layout can be saved under, when the query doesn't name
+    /// a layout saved in the workspace.
+    new_layout_name: Option<String>,
+    selected_index: usize,
+    selector: WeakEntity<LayoutSelector>,
+    workspace: WeakEntity<Workspace>,
+}
+
+impl LayoutSelectorDelegate {
+    fn new(
+        selector: WeakEntity<LayoutSelector>,
+        workspace: WeakEntity<Workspace>,
+        saved_layouts: Vec<String>,
+        cx: &App,
+    ) -> Self {
+        let mut configured_layouts = WorkspaceSettings::get_global(cx)
+            .layouts
+            .keys()
+            .filter(|name| !saved_layouts.contains(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        configured_layouts.sort();
+
+        let layouts = saved_layouts
+            .into_iter()
+            .map(LayoutEntry::Saved)
+            .chain(configured_layouts.into_iter().map(LayoutEntry::Configured))
+            .collect::<Vec<_>>();
+        let matches = layouts
+            .iter()
+            .enumerate()
+            .map(|(ix, layout)| StringMatch {
+                candidate_id: ix,
+                score: 0.0,
+                positions: Vec::new(),
+                string: layout.name().to_string(),
+            })
+            .collect();
+
+        Self {
+            layouts,
+            matches,
+            new_layout_name: None,
+            selected_index: 0,
+            selector,
+            workspace,
+        }
+    }
+
+    /// The offset of the layout matches, which follow the entry for saving a new layout.
+    fn matches_offset(&self) -> usize {
+        if self.new_layout_name.is_some() { 1 } else { 0 }
+    }
+
+    fn layout_at(&self, ix: usize) -> Option<&LayoutEntry> {
+        let mat = self.matches.get(ix.checked_sub(self.matches_offset())?)?;
+        self.layouts.get(mat.candidate_id)
+    }
+
+    fn delete_layout(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(LayoutEntry::Saved(name)) = self.layout_at(ix).cloned() else {
+            return;
+        };
+        let Some(delete) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.delete_layout(name.clone(), cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        delete.detach_and_notify_err(window, cx);
+
+        self.layouts
+            .retain(|layout| layout != &LayoutEntry::Saved(name.clone()));
+        if WorkspaceSettings::get_global(cx)
+            .layouts
+            .contains_key(&name)
+        {
+            self.layouts.push(LayoutEntry::Configured(name));
+        }
+        cx.spawn_in(window, async move |picker, cx| {
+            picker.update_in(cx, |picker, window, cx| {
+                picker.update_matches(picker.query(cx), window, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dismiss(&self, cx: &mut Context<Picker<Self>>) {
+        self.selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+}
+
+impl PickerDelegate for LayoutSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut Window, _: &mut App) -> Arc<str> {
+        "Load a layout, or type a name to save the current one...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches_offset() + self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .layouts
+            .iter()
+            .enumerate()
+            .map(|(id, layout)| StringMatchCandidate::new(id, layout.name()))
+            .collect::<Vec<_>>();
+        let query = query.trim().to_string();
+        let new_layout_name = (!query.is_empty()
+            && !self
+                .layouts
+                .iter()
+                .any(|layout| layout == &LayoutEntry::Saved(query.clone())))
+        .then(|| query.clone());
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.new_layout_name = new_layout_name;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.match_count().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let task = if let Some(name) = self
+            .new_layout_name
+            .clone()
+            .filter(|_| self.selected_index == 0)
+        {
+            self.workspace
+                .update(cx, |workspace, cx| workspace.save_layout(name, window, cx))
+        } else {
+            match self.layout_at(self.selected_index).cloned() {
+                Some(LayoutEntry::Saved(name)) if secondary => self
+                    .workspace
+                    .update(cx, |workspace, cx| workspace.save_layout(name, window, cx)),
+                Some(layout) => self.workspace.update(cx, |workspace, cx| {
+                    workspace.load_layout(layout.name(), window, cx)
+                }),
+                None => return,
+            }
+        };
+        if let Some(task) = task.log_err() {
+            task.detach_and_notify_err(window, cx);
+        }
+        self.dismiss(cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.dismiss(cx);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        if let Some(name) = self.new_layout_name.as_ref().filter(|_| ix == 0) {
+            return Some(
+                ListItem::new(ix)
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(selected)
+                    .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new(format!("Save current layout as \"{name}\""))),
+            );
+        }
+
+        let mat = self.matches.get(ix - self.matches_offset())?;
+        let layout = self.layouts.get(mat.candidate_id)?;
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected)
+            .child(HighlightedLabel::new(
+                mat.string.clone(),
+                mat.positions.clone(),
+            ));
+
+        Some(match layout {
+            LayoutEntry::Saved(_) => {
+                let delete_button = IconButton::new(("delete-layout", ix), IconName::Trash)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |picker, _, window, cx| {
+                        cx.stop_propagation();
+                        window.prevent_default();
+                        picker.delegate.delete_layout(ix, window, cx);
+                    }))
+                    .tooltip(Tooltip::text("Delete Layout"))
+                    .into_any_element();
+                if selected {
+                    item.end_slot::<AnyElement>(delete_button)
+                } else {
+                    item.end_hover_slot::<AnyElement>(delete_button)
+                }
+            }
+            LayoutEntry::Configured(_) => item.end_slot::<Label>(
+                Label::new("settings")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            ),
+        })
+    }
+}
@@ -0,0 +1,368 @@
+use anyhow::{Context as _, Result};
+use edit_prediction::{Direction, EditPrediction, EditPredictionProvider};
+use edit_prediction_context::{EditPredictionExcerpt, EditPredictionExcerptOptions};
+use gpui::{App, Context, Entity, EntityId, Task};
+use http_client::HttpClient;
+use language::{
+    Anchor, Buffer, BufferSnapshot, ToOffset, ToPoint,
+    language_settings::{
+        LocalEditPredictionApi, LocalEditPredictionSettings, all_language_settings,
+    },
+};
+use std::sync::Arc;
+use util::ResultExt as _;
+
+const PREFIX_PLACEHOLDER: &str = "{prefix}";
+const SUFFIX_PLACEHOLDER: &str = "{suffix}";
+
+/// Requests fill-in-the-middle completions from a self-hosted model server,
+/// such as Ollama or llama.cpp, and shows them as edit predictions.
+pub struct LocalEditPredictionProvider {
+    http_client: Arc<dyn HttpClient>,
+    pending_refresh: Option<Task<Result<()>>>,
+    current_completion: Option<CurrentCompletion>,
+}
+
+struct CurrentCompletion {
+    buffer_id: EntityId,
+    position: Anchor,
+    text: String,
+}
+
+impl CurrentCompletion {
+    /// Returns the part of the completion that is still to be inserted, given the
+    /// text typed since it was requested. Returns `None` once the user has
+    /// diverged from it.
+    fn remaining_text(&self, buffer: &Buffer, cursor_position: Anchor) -> Option<&str> {
+        if !self.position.is_valid(buffer) {
+            return None;
+        }
+        let start = self.position.to_offset(buffer);
+        let cursor = cursor_position.to_offset(buffer);
+        if cursor < start || cursor - start > self.text.len() {
+            return None;
+        }
+
+        let typed = buffer.text_for_range(start..cursor).collect::<String>();
+        let remaining = self.text.strip_prefix(&typed)?;
+        (!remaining.trim().is_empty()).then_some(remaining)
+    }
+}
+
+/// The buffer text before and after the cursor that the model completes between.
+#[derive(Debug, PartialEq)]
+struct FimPrompt {
+    prefix: String,
+    suffix: String,
+}
+
+impl FimPrompt {
+    fn new(snapshot: &BufferSnapshot, cursor_position: Anchor, max_bytes: usize) -> Option<Self> {
+        let cursor_offset = cursor_position.to_offset(snapshot);
+        let excerpt = EditPredictionExcerpt::select_from_buffer(
+            cursor_position.to_point(snapshot),
+            snapshot,
+            &EditPredictionExcerptOptions {
+                max_bytes,
+                min_bytes: max_bytes / 4,
+                target_before_cursor_over_total_bytes: 0.66,
+            },
+            None,
+        )?;
+
+        Some(Self {
+            prefix: snapshot
+                .text_for_range(excerpt.range.start.min(cursor_offset)..cursor_offset)
+                .collect(),
+            suffix: snapshot
+                .text_for_range(cursor_offset..excerpt.range.end.max(cursor_offset))
+                .collect(),
+        })
+    }
+
+    /// Substitutes the prefix and suffix into a template in a single pass, so
+    /// placeholder-like text in the buffer is left alone.
+    fn render(&self, template: &str) -> String {
+        let mut rendered =
+            String::with_capacity(template.len() + self.prefix.len() + self.suffix.len());
+        let mut rest = template;
+        loop {
+            let next_placeholder = [
+                (PREFIX_PLACEHOLDER, &self.prefix),
+                (SUFFIX_PLACEHOLDER, &self.suffix),
+            ]
+            .into_iter()
+            .filter_map(|(placeholder, value)| Some((rest.find(placeholder)?, placeholder, value)))
+            .min_by_key(|(ix, _, _)| *ix);
+
+            let Some((ix, placeholder, value)) = next_placeholder else {
+                rendered.push_str(rest);
+                return rendered;
+            };
+            rendered.push_str(&rest[..ix]);
+            rendered.push_str(value);
+            rest = &rest[ix + placeholder.len()..];
+        }
+    }
+}
+
+/// Drops trailing whitespace and anything from the point where the model starts
+/// reproducing the text after the cursor.
+fn trim_completion(completion: &str, suffix: &str) -> String {
+    let completion = completion.trim_end();
+    let Some(next_line) = suffix.lines().map(str::trim).find(|line| !line.is_empty()) else {
+        return completion.to_string();
+    };
+
+    let mut offset = 0;
+    for line in completion.split_inclusive('\n') {
+        if offset > 0 && line.trim() == next_line {
+            return completion[..offset].trim_end().to_string();
+        }
+        offset += line.len();
+    }
+    completion.to_string()
+}
+
+async fn request_completion(
+    http_client: &dyn HttpClient,
+    settings: &LocalEditPredictionSettings,
+    prompt: &FimPrompt,
+) -> Result<String> {
+    let (raw_prompt, suffix) = match &settings.fim_template {
+        Some(template) => (prompt.render(template), None),
+        None => (prompt.prefix.clone(), Some(prompt.suffix.clone())),
+    };
+
+    match settings.api {
+        LocalEditPredictionApi::Ollama => {
+            let response = ollama::generate(
+                http_client,
+                &settings.api_url,
+                None,
+                ollama::GenerateRequest {
+                    model: settings.model.clone(),
+                    raw: suffix.is_none(),
+                    prompt: raw_prompt,
+                    suffix,
+                    stream: false,
+                    keep_alive: ollama::KeepAlive::default(),
+                    options: Some(ollama::ChatOptions {
+                        num_predict: Some(settings.max_output_tokens as isize),
+                        temperature: Some(0.),
+                        ..Default::default()
+                    }),
+                },
+            )
+            .await?;
+            Ok(response.response)
+        }
+        LocalEditPredictionApi::OpenAiCompatible => {
+            let response = open_ai::complete_text(
+                http_client,
+                &settings.api_url,
+                None,
+                open_ai::TextCompletionRequest {
+                    model: settings.model.clone(),
+                    prompt: raw_prompt,
+                    suffix,
+                    max_tokens: settings.max_output_tokens,
+                    temperature: 0.,
+                    stop: Vec::new(),
+                    stream: false,
+                },
+            )
+            .await?;
+            let choice = response
+                .choices
+                .into_iter()
+                .next()
+                .context("completion response contained no choices")?;
+            Ok(choice.text)
+        }
+    }
+}
+
+impl LocalEditPredictionProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            pending_refresh: None,
+            current_completion: None,
+        }
+    }
+}
+
+impl EditPredictionProvider for LocalEditPredictionProvider {
+    fn name() -> &'static str {
+        "local"
+    }
+
+    fn display_name() -> &'static str {
+        "Local Model"
+    }
+
+    fn show_completions_in_menu() -> bool {
+        true
+    }
+
+    fn show_tab_accept_marker() -> bool {
+        true
+    }
+
+    fn supports_jump_to_edit() -> bool {
+        false
+    }
+
+    fn is_enabled(&self, _buffer: &Entity<Buffer>, _cursor_position: Anchor, _cx: &App) -> bool {
+        true
+    }
+
+    fn is_refreshing(&self) -> bool {
+        self.pending_refresh.is_some()
+    }
+
+    fn refresh(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cursor_position: Anchor,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        // Keep the current prediction while the user types through it.
+        if self.current_completion.as_ref().is_some_and(|completion| {
+            completion.buffer_id == buffer.entity_id()
+                && completion
+                    .remaining_text(buffer.read(cx), cursor_position)
+                    .is_some()
+        }) {
+            return;
+        }
+
+        let settings = all_language_settings(None, cx)
+            .edit_predictions
+            .local
+            .clone();
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_id = buffer.entity_id();
+        let http_client = self.http_client.clone();
+
+        // Replacing the pending task drops the in-flight request, cancelling it.
+        self.pending_refresh = Some(cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor().timer(settings.debounce).await;
+            }
+
+            let prompt = cx
+                .background_spawn({
+                    let max_bytes = settings.max_context_bytes;
+                    async move { FimPrompt::new(&snapshot, cursor_position, max_bytes) }
+                })
+                .await;
+
+            let completion = match prompt {
+                Some(prompt) => request_completion(http_client.as_ref(), &settings, &prompt)
+                    .await
+                    .log_err()
+                    .map(|text| trim_completion(&text, &prompt.suffix))
+                    .filter(|text| !text.trim().is_empty()),
+                None => None,
+            };
+
+            this.update(cx, |this, cx| {
+                this.pending_refresh = None;
+                this.current_completion = completion.map(|text| CurrentCompletion {
+                    buffer_id,
+                    position: cursor_position,
+                    text,
+                });
+                cx.notify();
+            })
+        }));
+    }
+
+    fn cycle(
+        &mut self,
+        _buffer: Entity<Buffer>,
+        _cursor_position: Anchor,
+        _direction: Direction,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn accept(&mut self, _cx: &mut Context<Self>) {
+        self.pending_refresh = None;
+        self.current_completion = None;
+    }
+
+    fn discard(&mut self, _cx: &mut Context<Self>) {
+        self.pending_refresh = None;
+        self.current_completion = None;
+    }
+
+    fn suggest(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cursor_position: Anchor,
+        cx: &mut Context<Self>,
+    ) -> Option<EditPrediction> {
+        let completion = self.current_completion.as_ref()?;
+        if completion.buffer_id != buffer.entity_id() {
+            return None;
+        }
+
+        let buffer = buffer.read(cx);
+        let text = completion.remaining_text(buffer, cursor_position)?;
+        let position = cursor_position.bias_right(buffer);
+        Some(EditPrediction::Local {
+            id: None,
+            edits: vec![(position..position, text.to_string())],
+            edit_preview: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use indoc::indoc;
+
+    #[test]
+    fn test_render_fim_template() {
+        let prompt = FimPrompt {
+            prefix: "let template = \"{suffix}\";\nfn main() {".into(),
+            suffix: "}\n".into(),
+        };
+        assert_eq!(
+            prompt.render("<|fim_prefix|>{prefix}<|fim_suffix|>{suffix}<|fim_middle|>"),
+            "<|fim_prefix|>let template = \"{suffix}\";\nfn main() {<|fim_suffix|>}\n<|fim_middle|>"
+        );
+    }
+
+    #[test]
+    fn test_trim_completion() {
+        let suffix = "\n    }\n}\n";
+        assert_eq!(
+            trim_completion("\n        x += 1;\n    }\n}\n", suffix),
+            "\n        x += 1;"
+        );
+        assert_eq!(trim_completion("x + 1  \n\n", ""), "x + 1");
+    }
+
+    #[gpui::test]
+    fn test_fim_prompt(cx: &mut TestAppContext) {
+        let text = indoc! {"
+            fn main() {
+                let x = 1;
+            }
+        "};
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let cursor = snapshot.anchor_after(text.find("1;").unwrap());
+
+        let prompt = FimPrompt::new(&snapshot, cursor, 4096).unwrap();
+        assert_eq!(prompt.prefix, "fn main() {\n    let x = ");
+        assert_eq!(prompt.suffix, "1;\n}\n");
+    }
+}
@@ -80,6 +80,29 @@ pub struct LanguageServerBinaryOptions {
     pub pre_release: bool,
 }
 
+/// The unit a language server counts the `character` of a [`Position`] in, as negotiated
+/// through the `positionEncoding` server capability.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PositionEncoding {
+    /// Bytes of UTF-8.
+    Utf8,
+    /// UTF-16 code units. Servers that don't report an encoding use this.
+    #[default]
+    Utf16,
+    /// Unicode scalar values.
+    Utf32,
+}
+
+impl PositionEncoding {
+    pub fn from_kind(kind: Option<&PositionEncodingKind>) -> Self {
+        match kind {
+            Some(kind) if *kind == PositionEncodingKind::UTF8 => Self::Utf8,
+            Some(kind) if *kind == PositionEncodingKind::UTF32 => Self::Utf32,
+            _ => Self::Utf16,
+        }
+    }
+}
+
 /// A running language server process.
 pub struct LanguageServer {
     server_id: LanguageServerId,
@@ -655,7 +678,13 @@ impl LanguageServer {
             initialization_options: None,
             capabilities: ClientCapabilities {
                 general: Some(GeneralClientCapabilities {
-                    position_encodings: Some(vec![PositionEncodingKind::UTF16]),
+                    // In order of preference: UTF-8 columns map directly onto our buffers,
+                    // UTF-16 is what every server is required to support.
+                    position_encodings: Some(vec![
+                        PositionEncodingKind::UTF8,
+                        PositionEncodingKind::UTF16,
+                        PositionEncodingKind::UTF32,
+                    ]),
                     ..GeneralClientCapabilities::default()
                 }),
                 workspace: Some(WorkspaceClientCapabilities {
@@ -838,6 +867,10 @@ impl LanguageServer {
                     code_lens: Some(CodeLensClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
+                    document_link: Some(DocumentLinkClientCapabilities {
+                        dynamic_registration: Some(true),
+                        tooltip_support: Some(true),
+                    }),
                     document_symbol: Some(DocumentSymbolClientCapabilities {
                         hierarchical_document_symbol_support: Some(true),
                         dynamic_registration: Some(true),
@@ -1147,6 +1180,11 @@ impl LanguageServer {
         }
     }
 
+    /// The encoding the server selected for the columns of positions it sends and receives.
+    pub fn position_encoding(&self) -> PositionEncoding {
+        PositionEncoding::from_kind(self.capabilities.read().position_encoding.as_ref())
+    }
+
     pub fn update_capabilities(&self, update: impl FnOnce(&mut ServerCapabilities)) {
         update(self.capabilities.write().deref_mut());
     }
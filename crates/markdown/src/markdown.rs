@@ -22,12 +22,15 @@ use gpui::{
     AnyElement, App, BorderStyle, Bounds, ClipboardItem, CursorStyle, DispatchPhase, Edges, Entity,
     FocusHandle, Focusable, FontStyle, FontWeight, GlobalElementId, Hitbox, Hsla, Image,
     ImageFormat, KeyContext, Length, MouseDownEvent, MouseEvent, MouseMoveEvent, MouseUpEvent,
-    Point, Stateful, StrikethroughStyle, StyleRefinement, StyledText, Task, TextLayout, TextRun,
-    TextStyle, TextStyleRefinement, actions, img, point, quad,
+    Point, RenderImage, Stateful, StrikethroughStyle, StyleRefinement, StyledText, Task,
+    TextLayout, TextRun, TextStyle, TextStyleRefinement, actions, img, point, quad,
 };
 use language::{Language, LanguageRegistry, Rope};
 use parser::CodeBlockMetadata;
-use parser::{MarkdownEvent, MarkdownTag, MarkdownTagEnd, parse_links_only, parse_markdown};
+use parser::{
+    MarkdownEvent, MarkdownTag, MarkdownTagEnd, parse_links_only, parse_markdown,
+    parse_markdown_with_math,
+};
 use pulldown_cmark::Alignment;
 use sum_tree::TreeMap;
 use theme::SyntaxTheme;
@@ -101,6 +104,7 @@ pub struct Markdown {
     autoscroll_request: Option<usize>,
     parsed_markdown: ParsedMarkdown,
     images_by_source_offset: HashMap<usize, Arc<Image>>,
+    diagrams_by_source_offset: HashMap<usize, Arc<MermaidDiagram>>,
     should_reparse: bool,
     pending_parse: Option<Task<()>>,
     focus_handle: FocusHandle,
@@ -112,6 +116,7 @@ pub struct Markdown {
 
 struct Options {
     parse_links_only: bool,
+    parse_math: bool,
 }
 
 pub enum CodeBlockRenderer {
@@ -158,6 +163,45 @@ impl Markdown {
         language_registry: Option<Arc<LanguageRegistry>>,
         fallback_code_block_language: Option<LanguageName>,
         cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new_with_options(
+            source,
+            language_registry,
+            fallback_code_block_language,
+            Options {
+                parse_links_only: false,
+                parse_math: false,
+            },
+            cx,
+        )
+    }
+
+    /// Like [`Markdown::new`], but also renders `$...$` and `$$...$$` as math. Dollar signs
+    /// are common in other text, so only content that is expected to contain math opts in.
+    pub fn new_with_math(
+        source: SharedString,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        fallback_code_block_language: Option<LanguageName>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new_with_options(
+            source,
+            language_registry,
+            fallback_code_block_language,
+            Options {
+                parse_links_only: false,
+                parse_math: true,
+            },
+            cx,
+        )
+    }
+
+    fn new_with_options(
+        source: SharedString,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        fallback_code_block_language: Option<LanguageName>,
+        options: Options,
+        cx: &mut Context<Self>,
     ) -> Self {
         let focus_handle = cx.focus_handle();
         let mut this = Self {
@@ -167,41 +211,36 @@ impl Markdown {
             autoscroll_request: None,
             should_reparse: false,
             images_by_source_offset: Default::default(),
+            diagrams_by_source_offset: Default::default(),
             parsed_markdown: ParsedMarkdown::default(),
             pending_parse: None,
             focus_handle,
             language_registry,
             fallback_code_block_language,
-            options: Options {
-                parse_links_only: false,
-            },
+            options,
             copied_code_blocks: HashSet::default(),
         };
+        cx.on_release(|this, cx| {
+            for diagram in this.diagrams_by_source_offset.values() {
+                diagram.release_image(cx);
+            }
+        })
+        .detach();
         this.parse(cx);
         this
     }
 
     pub fn new_text(source: SharedString, cx: &mut Context<Self>) -> Self {
-        let focus_handle = cx.focus_handle();
-        let mut this = Self {
+        Self::new_with_options(
             source,
-            selection: Selection::default(),
-            pressed_link: None,
-            autoscroll_request: None,
-            should_reparse: false,
-            parsed_markdown: ParsedMarkdown::default(),
-            images_by_source_offset: Default::default(),
-            pending_parse: None,
-            focus_handle,
-            language_registry: None,
-            fallback_code_block_language: None,
-            options: Options {
+            None,
+            None,
+            Options {
                 parse_links_only: true,
+                parse_math: false,
             },
-            copied_code_blocks: HashSet::default(),
-        };
-        this.parse(cx);
-        this
+            cx,
+        )
     }
 
     pub fn is_parsing(&self) -> bool {
@@ -299,6 +338,7 @@ impl Markdown {
     fn start_background_parse(&self, cx: &Context<Self>) -> Task<()> {
         let source = self.source.clone();
         let should_parse_links_only = self.options.parse_links_only;
+        let should_parse_math = self.options.parse_math;
         let language_registry = self.language_registry.clone();
         let fallback = self.fallback_code_block_language.clone();
 
@@ -312,11 +352,17 @@ impl Markdown {
                         languages_by_path: TreeMap::default(),
                     },
                     Default::default(),
+                    Default::default(),
                 );
             }
 
-            let (events, language_names, paths) = parse_markdown(&source);
+            let (events, language_names, paths) = if should_parse_math {
+                parse_markdown_with_math(&source)
+            } else {
+                parse_markdown(&source)
+            };
             let mut images_by_source_offset = HashMap::default();
+            let mut diagrams_by_source_offset = HashMap::default();
             let mut languages_by_name = TreeMap::default();
             let mut languages_by_path = TreeMap::default();
             if let Some(registry) = language_registry.as_ref() {
@@ -344,6 +390,23 @@ impl Markdown {
             }
 
             for (range, event) in &events {
+                if let MarkdownEvent::Start(MarkdownTag::CodeBlock {
+                    kind: CodeBlockKind::FencedLang(language),
+                    metadata,
+                }) = event
+                    && language.as_ref() == "mermaid"
+                {
+                    // Diagrams that fail to parse are shown as code blocks.
+                    if let Some(diagram) =
+                        mermaid::Diagram::parse(&source[metadata.content_range.clone()])
+                            .log_with_level(Level::Debug)
+                    {
+                        diagrams_by_source_offset
+                            .insert(range.start, Arc::new(MermaidDiagram::new(diagram)));
+                    }
+                    continue;
+                }
+
                 if let MarkdownEvent::Start(MarkdownTag::Image { dest_url, .. }) = event
                     && let Some(data_url) = dest_url.strip_prefix("data:")
                 {
@@ -376,15 +439,33 @@ impl Markdown {
                     languages_by_path,
                 },
                 images_by_source_offset,
+                diagrams_by_source_offset,
             )
         });
 
         cx.spawn(async move |this, cx| {
-            let (parsed, images_by_source_offset) = parsed.await;
+            let (parsed, images_by_source_offset, diagrams_by_source_offset) = parsed.await;
 
             this.update(cx, |this, cx| {
                 this.parsed_markdown = parsed;
                 this.images_by_source_offset = images_by_source_offset;
+                // Keep the images of diagrams that didn't change, so that streamed content
+                // doesn't draw them again on every update.
+                let mut old_diagrams = mem::take(&mut this.diagrams_by_source_offset);
+                this.diagrams_by_source_offset = diagrams_by_source_offset
+                    .into_iter()
+                    .map(|(offset, diagram)| match old_diagrams.remove(&offset) {
+                        Some(old_diagram) if old_diagram == diagram => (offset, old_diagram),
+                        Some(old_diagram) => {
+                            old_diagram.release_image(cx);
+                            (offset, diagram)
+                        }
+                        None => (offset, diagram),
+                    })
+                    .collect();
+                for old_diagram in old_diagrams.into_values() {
+                    old_diagram.release_image(cx);
+                }
                 this.pending_parse.take();
                 if this.should_reparse {
                     this.parse(cx);
@@ -757,6 +838,7 @@ impl Element for MarkdownElement {
         let markdown = self.markdown.read(cx);
         let parsed_markdown = &markdown.parsed_markdown;
         let images = &markdown.images_by_source_offset;
+        let diagrams = &markdown.diagrams_by_source_offset;
         let markdown_end = if let Some(last) = parsed_markdown.events.last() {
             last.0.end
         } else {
@@ -765,6 +847,7 @@ impl Element for MarkdownElement {
 
         let mut current_code_block_metadata = None;
         let mut current_img_block_range: Option<Range<usize>> = None;
+        let mut current_diagram_range: Option<Range<usize>> = None;
         for (range, event) in parsed_markdown.events.iter() {
             // Skip alt text for images that rendered
             if let Some(current_img_block_range) = &current_img_block_range
@@ -773,6 +856,14 @@ impl Element for MarkdownElement {
                 continue;
             }
 
+            // Skip the source of diagrams that rendered
+            if let Some(diagram_range) = &current_diagram_range {
+                if range.start >= diagram_range.start && range.end <= diagram_range.end {
+                    continue;
+                }
+                current_diagram_range = None;
+            }
+
             match event {
                 MarkdownEvent::Start(tag) => {
                     match tag {
@@ -825,6 +916,17 @@ impl Element for MarkdownElement {
                                 markdown_end,
                             );
                         }
+                        MarkdownTag::CodeBlock { .. } if diagrams.contains_key(&range.start) => {
+                            current_diagram_range = Some(range.clone());
+                            let theme = mermaid_theme(&builder.text_style(), window, cx);
+                            let image = diagrams[&range.start].image(&theme, window, cx).log_err();
+                            builder.push_div(
+                                div().mb_2().children(image.map(img)),
+                                range,
+                                markdown_end,
+                            );
+                            builder.pop_div();
+                        }
                         MarkdownTag::CodeBlock { kind, metadata } => {
                             let language = match kind {
                                 CodeBlockKind::Fenced => None,
@@ -1213,6 +1315,36 @@ impl Element for MarkdownElement {
                     );
                     builder.pop_div()
                 }
+                MarkdownEvent::InlineMath => {
+                    builder.push_text_style(TextStyleRefinement {
+                        font_style: Some(FontStyle::Italic),
+                        ..Default::default()
+                    });
+                    builder.push_text(
+                        &math::inline_text(&parsed_markdown.source[range.clone()]),
+                        range.clone(),
+                    );
+                    builder.pop_text_style();
+                }
+                MarkdownEvent::DisplayMath => {
+                    let text_style = builder.text_style();
+                    builder.push_div(
+                        div()
+                            .flex()
+                            .justify_center()
+                            .my_2()
+                            .text_size(text_style.font_size)
+                            .font_family(text_style.font_family.clone())
+                            .text_color(text_style.color)
+                            .child(
+                                math::math(parsed_markdown.source[range.clone()].to_string())
+                                    .display(true),
+                            ),
+                        range,
+                        markdown_end,
+                    );
+                    builder.pop_div();
+                }
                 MarkdownEvent::SoftBreak => builder.push_text(" ", range.clone()),
                 MarkdownEvent::HardBreak => builder.push_text("\n", range.clone()),
                 _ => log::error!("unsupported markdown event {:?}", event),
@@ -1281,6 +1413,99 @@ impl Element for MarkdownElement {
     }
 }
 
+/// A parsed Mermaid diagram along with the image it was last drawn to, so that it's only
+/// drawn again when the theme changes.
+///
+/// The image is uploaded to the windows it's shown in, so its owner has to call
+/// [`MermaidDiagram::release_image`] once the diagram isn't shown anymore.
+#[derive(Debug)]
+pub struct MermaidDiagram {
+    diagram: mermaid::Diagram,
+    image: std::sync::Mutex<Option<(mermaid::Theme, Arc<RenderImage>)>>,
+}
+
+impl MermaidDiagram {
+    pub fn new(diagram: mermaid::Diagram) -> Self {
+        Self {
+            diagram,
+            image: Default::default(),
+        }
+    }
+
+    pub fn diagram(&self) -> &mermaid::Diagram {
+        &self.diagram
+    }
+
+    /// Returns the diagram drawn with the given theme. When the theme changed since the
+    /// diagram was last drawn, the previous image is released from the windows.
+    pub fn image(
+        &self,
+        theme: &mermaid::Theme,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> anyhow::Result<Arc<RenderImage>> {
+        let mut image = self.image.lock().unwrap_or_else(|error| error.into_inner());
+        if let Some((image_theme, image)) = image.as_ref()
+            && image_theme == theme
+        {
+            return Ok(image.clone());
+        }
+        let svg = self.diagram.to_svg(theme);
+        let new_image = Image::from_bytes(ImageFormat::Svg, svg.into_bytes())
+            .to_image_data(cx.svg_renderer())?;
+        if let Some((_, old_image)) = image.replace((theme.clone(), new_image.clone())) {
+            cx.drop_image(old_image, Some(window));
+        }
+        Ok(new_image)
+    }
+
+    /// Releases the image the diagram was last drawn to from all windows.
+    pub fn release_image(&self, cx: &mut App) {
+        let image = self
+            .image
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .take();
+        if let Some((_, image)) = image {
+            cx.drop_image(image, None);
+        }
+    }
+}
+
+impl PartialEq for MermaidDiagram {
+    fn eq(&self, other: &Self) -> bool {
+        self.diagram == other.diagram
+    }
+}
+
+/// The colors and font used to draw Mermaid diagrams, matching the current theme.
+pub fn mermaid_theme(text_style: &TextStyle, window: &Window, cx: &App) -> mermaid::Theme {
+    fn css_color(color: Hsla) -> String {
+        let color = gpui::Rgba::from(color);
+        format!(
+            "rgba({}, {}, {}, {})",
+            (color.r * 255.).round() as u8,
+            (color.g * 255.).round() as u8,
+            (color.b * 255.).round() as u8,
+            color.a
+        )
+    }
+
+    let colors = cx.theme().colors();
+    let status = cx.theme().status();
+    mermaid::Theme {
+        font_family: format!("{}, sans-serif", text_style.font_family),
+        font_size: text_style.font_size.to_pixels(window.rem_size()).into(),
+        text: css_color(colors.text),
+        line: css_color(colors.text_muted),
+        node_fill: css_color(colors.element_background),
+        node_border: css_color(colors.border),
+        note_fill: css_color(status.warning_background),
+        note_border: css_color(status.warning_border),
+        label_background: css_color(colors.editor_background),
+    }
+}
+
 fn apply_heading_style(
     mut heading: Div,
     level: pulldown_cmark::HeadingLevel,
@@ -1826,7 +2051,7 @@ impl RenderedText {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::{TestAppContext, size};
+    use gpui::{TestAppContext, VisualTestContext, size};
 
     #[gpui::test]
     fn test_mappings(cx: &mut TestAppContext) {
@@ -1908,6 +2133,40 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_mermaid_diagram_image_cache(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let diagram = MermaidDiagram::new(mermaid::Diagram::parse("graph TD\nA --> B").unwrap());
+        let theme = mermaid::Theme {
+            font_family: "Zed Plex Sans, sans-serif".into(),
+            font_size: 14.,
+            text: "black".into(),
+            line: "gray".into(),
+            node_fill: "white".into(),
+            node_border: "gray".into(),
+            note_fill: "yellow".into(),
+            note_border: "orange".into(),
+            label_background: "white".into(),
+        };
+
+        let image = |theme: &mermaid::Theme, cx: &mut VisualTestContext| {
+            cx.update(|window, cx| diagram.image(theme, window, cx).unwrap())
+        };
+        let light_image = image(&theme, cx);
+        assert!(Arc::ptr_eq(&light_image, &image(&theme, cx)));
+
+        let dark_theme = mermaid::Theme {
+            text: "white".into(),
+            ..theme.clone()
+        };
+        let dark_image = image(&dark_theme, cx);
+        assert!(!Arc::ptr_eq(&light_image, &dark_image));
+        assert!(Arc::ptr_eq(&dark_image, &image(&dark_theme, cx)));
+
+        cx.update(|_, cx| diagram.release_image(cx));
+        assert!(!Arc::ptr_eq(&dark_image, &image(&dark_theme, cx)));
+    }
+
     #[track_caller]
     fn assert_mappings(rendered: &RenderedText, expected: Vec<Vec<(usize, usize)>>) {
         assert_eq!(rendered.lines.len(), expected.len(), "line count mismatch");
@@ -26,15 +26,35 @@ pub fn parse_markdown(
     Vec<(Range<usize>, MarkdownEvent)>,
     HashSet<SharedString>,
     HashSet<Arc<str>>,
+) {
+    parse_markdown_with_options(text, PARSE_OPTIONS)
+}
+
+/// Like [`parse_markdown`], but also parses `$...$` and `$$...$$` as math.
+pub fn parse_markdown_with_math(
+    text: &str,
+) -> (
+    Vec<(Range<usize>, MarkdownEvent)>,
+    HashSet<SharedString>,
+    HashSet<Arc<str>>,
+) {
+    parse_markdown_with_options(text, PARSE_OPTIONS.union(Options::ENABLE_MATH))
+}
+
+fn parse_markdown_with_options(
+    text: &str,
+    options: Options,
+) -> (
+    Vec<(Range<usize>, MarkdownEvent)>,
+    HashSet<SharedString>,
+    HashSet<Arc<str>>,
 ) {
     let mut events = Vec::new();
     let mut language_names = HashSet::default();
     let mut language_paths = HashSet::default();
     let mut within_link = false;
     let mut within_metadata = false;
-    let mut parser = Parser::new_ext(text, PARSE_OPTIONS)
-        .into_offset_iter()
-        .peekable();
+    let mut parser = Parser::new_ext(text, options).into_offset_iter().peekable();
     while let Some((pulldown_event, range)) = parser.next() {
         if within_metadata {
             if let pulldown_cmark::Event::End(pulldown_cmark::TagEnd::MetadataBlock { .. }) =
@@ -321,7 +341,18 @@ pub fn parse_markdown(
             pulldown_cmark::Event::TaskListMarker(checked) => {
                 events.push((range, MarkdownEvent::TaskListMarker(checked)))
             }
-            pulldown_cmark::Event::InlineMath(_) | pulldown_cmark::Event::DisplayMath(_) => {}
+            pulldown_cmark::Event::InlineMath(_) => {
+                let content_range = extract_math_content_range(&text[range.clone()]);
+                let content_range =
+                    content_range.start + range.start..content_range.end + range.start;
+                events.push((content_range, MarkdownEvent::InlineMath))
+            }
+            pulldown_cmark::Event::DisplayMath(_) => {
+                let content_range = extract_math_content_range(&text[range.clone()]);
+                let content_range =
+                    content_range.start + range.start..content_range.end + range.start;
+                events.push((content_range, MarkdownEvent::DisplayMath))
+            }
         }
     }
     (events, language_names, language_paths)
@@ -397,6 +428,10 @@ pub enum MarkdownEvent {
     Rule,
     /// A task list marker, rendered as a checkbox in HTML. Contains a true when it is checked.
     TaskListMarker(bool),
+    /// An inline math expression, such as `$x^2$`, whose range excludes the `$` delimiters.
+    InlineMath,
+    /// A display math expression, such as `$$x^2$$`, whose range excludes the `$$` delimiters.
+    DisplayMath,
 }
 
 /// Tags for elements that can contain other elements.
@@ -501,18 +536,26 @@ pub struct CodeBlockMetadata {
 }
 
 fn extract_code_content_range(text: &str) -> Range<usize> {
+    extract_delimited_content_range(text, '`')
+}
+
+fn extract_math_content_range(text: &str) -> Range<usize> {
+    extract_delimited_content_range(text, '$')
+}
+
+fn extract_delimited_content_range(text: &str, delimiter: char) -> Range<usize> {
     let text_len = text.len();
     if text_len == 0 {
         return 0..0;
     }
 
-    let start_ticks = text.chars().take_while(|&c| c == '`').count();
+    let start_ticks = text.chars().take_while(|&c| c == delimiter).count();
 
     if start_ticks == 0 || start_ticks > text_len {
         return 0..text_len;
     }
 
-    let end_ticks = text.chars().rev().take_while(|&c| c == '`').count();
+    let end_ticks = text.chars().rev().take_while(|&c| c == delimiter).count();
 
     if end_ticks != start_ticks || text_len < start_ticks + end_ticks {
         return 0..text_len;
@@ -546,9 +589,8 @@ mod tests {
     use super::MarkdownTag::*;
     use super::*;
 
-    const UNWANTED_OPTIONS: Options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
-        .union(Options::ENABLE_MATH)
-        .union(Options::ENABLE_DEFINITION_LIST);
+    const UNWANTED_OPTIONS: Options =
+        Options::ENABLE_YAML_STYLE_METADATA_BLOCKS.union(Options::ENABLE_DEFINITION_LIST);
 
     #[test]
     fn all_options_considered() {
@@ -728,6 +770,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_math() {
+        assert_eq!(
+            parse_markdown_with_math("a $x^2$ b\n\n$$\ny\n$$").0,
+            vec![
+                (0..10, Start(Paragraph)),
+                (0..2, Text),
+                (3..6, InlineMath),
+                (7..9, Text),
+                (0..10, End(MarkdownTagEnd::Paragraph)),
+                (11..18, Start(Paragraph)),
+                (13..16, DisplayMath),
+                (11..18, End(MarkdownTagEnd::Paragraph)),
+            ]
+        );
+
+        // Dollar signs are left alone unless math is enabled.
+        assert!(
+            !parse_markdown("a $x^2$ b\n\n$$\ny\n$$")
+                .0
+                .iter()
+                .any(|(_, event)| matches!(event, InlineMath | DisplayMath))
+        );
+    }
+
     #[test]
     fn test_extract_code_content_range() {
         let input = "```let x = 5;```";
@@ -1,9 +1,9 @@
 use gpui::{
-    DefiniteLength, FontStyle, FontWeight, HighlightStyle, Hsla, SharedString, StrikethroughStyle,
-    UnderlineStyle, px,
+    App, DefiniteLength, FontStyle, FontWeight, HighlightStyle, Hsla, SharedString,
+    StrikethroughStyle, UnderlineStyle, px,
 };
 use language::HighlightId;
-use std::{fmt::Display, ops::Range, path::PathBuf};
+use std::{fmt::Display, ops::Range, path::PathBuf, sync::Arc};
 
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -13,6 +13,8 @@ pub enum ParsedMarkdownElement {
     Table(ParsedMarkdownTable),
     BlockQuote(ParsedMarkdownBlockQuote),
     CodeBlock(ParsedMarkdownCodeBlock),
+    /// A ```` ```mermaid ```` code block whose diagram could be parsed.
+    MermaidDiagram(ParsedMarkdownMermaidDiagram),
     /// A paragraph of text and other inline elements.
     Paragraph(MarkdownParagraph),
     HorizontalRule(Range<usize>),
@@ -20,6 +22,23 @@ pub enum ParsedMarkdownElement {
 }
 
 impl ParsedMarkdownElement {
+    fn release_diagram_images(&self, cx: &mut App) {
+        match self {
+            Self::MermaidDiagram(diagram) => diagram.diagram.release_image(cx),
+            Self::ListItem(list_item) => {
+                for child in &list_item.content {
+                    child.release_diagram_images(cx);
+                }
+            }
+            Self::BlockQuote(block_quote) => {
+                for child in &block_quote.children {
+                    child.release_diagram_images(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
     pub fn source_range(&self) -> Option<Range<usize>> {
         Some(match self {
             Self::Heading(heading) => heading.source_range.clone(),
@@ -27,9 +46,11 @@ impl ParsedMarkdownElement {
             Self::Table(table) => table.source_range.clone(),
             Self::BlockQuote(block_quote) => block_quote.source_range.clone(),
             Self::CodeBlock(code_block) => code_block.source_range.clone(),
+            Self::MermaidDiagram(diagram) => diagram.source_range.clone(),
             Self::Paragraph(text) => match text.get(0)? {
                 MarkdownParagraphChunk::Text(t) => t.source_range.clone(),
                 MarkdownParagraphChunk::Image(image) => image.source_range.clone(),
+                MarkdownParagraphChunk::Math(math) => math.source_range.clone(),
             },
             Self::HorizontalRule(range) => range.clone(),
             Self::Image(image) => image.source_range.clone(),
@@ -48,6 +69,8 @@ pub type MarkdownParagraph = Vec<MarkdownParagraphChunk>;
 pub enum MarkdownParagraphChunk {
     Text(ParsedMarkdownText),
     Image(Image),
+    /// Display math, such as `$$x^2$$`. Inline math is converted to text.
+    Math(ParsedMarkdownMath),
 }
 
 #[derive(Debug)]
@@ -56,6 +79,15 @@ pub struct ParsedMarkdown {
     pub children: Vec<ParsedMarkdownElement>,
 }
 
+impl ParsedMarkdown {
+    /// Releases the images that the Mermaid diagrams of the document were drawn to.
+    pub fn release_diagram_images(&self, cx: &mut App) {
+        for child in &self.children {
+            child.release_diagram_images(cx);
+        }
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ParsedMarkdownListItem {
@@ -83,6 +115,21 @@ pub struct ParsedMarkdownCodeBlock {
     pub highlights: Option<Vec<(Range<usize>, HighlightId)>>,
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMermaidDiagram {
+    pub source_range: Range<usize>,
+    pub diagram: Arc<markdown::MermaidDiagram>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMath {
+    pub source_range: Range<usize>,
+    /// The TeX source of the expression, without its `$$` delimiters.
+    pub contents: SharedString,
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ParsedMarkdownHeading {
@@ -125,6 +125,8 @@ impl<'a> MarkdownParser<'a> {
             | Event::Html(_)
             | Event::InlineHtml(_)
             | Event::FootnoteReference(_)
+            | Event::InlineMath(_)
+            | Event::DisplayMath(_)
             | Event::Start(Tag::Link { .. })
             | Event::Start(Tag::Emphasis)
             | Event::Start(Tag::Strong)
@@ -196,6 +198,16 @@ impl<'a> MarkdownParser<'a> {
                     self.cursor += 1;
 
                     let code_block = self.parse_code_block(language).await?;
+                    if code_block.language.as_deref() == Some("mermaid")
+                        && let Ok(diagram) = mermaid::Diagram::parse(&code_block.contents)
+                    {
+                        return Some(vec![ParsedMarkdownElement::MermaidDiagram(
+                            ParsedMarkdownMermaidDiagram {
+                                source_range: code_block.source_range,
+                                diagram: Arc::new(markdown::MermaidDiagram::new(diagram)),
+                            },
+                        )]);
+                    }
                     Some(vec![ParsedMarkdownElement::CodeBlock(code_block)])
                 }
                 Tag::HtmlBlock => {
@@ -342,6 +354,37 @@ impl<'a> MarkdownParser<'a> {
                         }
                     }
                 }
+                Event::InlineMath(t) => {
+                    text.push_str(&math::inline_text(t));
+                    highlights.push((
+                        prev_len..text.len(),
+                        MarkdownHighlight::Style(MarkdownHighlightStyle {
+                            italic: true,
+                            weight: if bold_depth > 0 {
+                                FontWeight::BOLD
+                            } else {
+                                FontWeight::default()
+                            },
+                            ..Default::default()
+                        }),
+                    ));
+                }
+                Event::DisplayMath(t) => {
+                    if !text.is_empty() {
+                        markdown_text_like.push(MarkdownParagraphChunk::Text(ParsedMarkdownText {
+                            source_range: source_range.clone(),
+                            contents: std::mem::take(&mut text),
+                            highlights: std::mem::take(&mut highlights),
+                            region_ranges: std::mem::take(&mut region_ranges),
+                            regions: std::mem::take(&mut regions),
+                        }));
+                    }
+                    let (_, math_range) = self.current().unwrap();
+                    markdown_text_like.push(MarkdownParagraphChunk::Math(ParsedMarkdownMath {
+                        source_range: math_range.clone(),
+                        contents: t.to_string().into(),
+                    }));
+                }
                 Event::Code(t) => {
                     text.push_str(t.as_ref());
                     region_ranges.push(prev_len..text.len());
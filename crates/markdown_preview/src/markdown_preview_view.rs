@@ -217,6 +217,12 @@ impl MarkdownPreviewView {
                 mode,
             };
 
+            cx.on_release(|this, cx| {
+                if let Some(contents) = &this.contents {
+                    contents.release_diagram_images(cx);
+                }
+            })
+            .detach();
             this.set_editor(active_editor, window, cx);
 
             if mode == MarkdownPreviewMode::Follow {
@@ -341,7 +347,9 @@ impl MarkdownPreviewView {
             let contents = parsing_task.await;
             view.update(cx, move |view, cx| {
                 let markdown_blocks_count = contents.children.len();
-                view.contents = Some(contents);
+                if let Some(old_contents) = view.contents.replace(contents) {
+                    old_contents.release_diagram_images(cx);
+                }
                 let scroll_top = view.list_state.logical_scroll_top();
                 view.list_state.reset(markdown_blocks_count);
                 view.list_state.scroll_to(scroll_top);
@@ -1,8 +1,9 @@
 use crate::markdown_elements::{
     HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
     ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
-    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
-    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownMath,
+    ParsedMarkdownMermaidDiagram, ParsedMarkdownTable, ParsedMarkdownTableAlignment,
+    ParsedMarkdownTableRow,
 };
 use fs::normalize_path;
 use gpui::{
@@ -59,6 +60,7 @@ pub struct RenderContext {
     code_block_background_color: Hsla,
     code_span_background_color: Hsla,
     syntax_theme: Arc<SyntaxTheme>,
+    mermaid_theme: mermaid::Theme,
     indent: usize,
     checkbox_clicked_callback: Option<CheckboxClickedCallback>,
 }
@@ -85,6 +87,7 @@ impl RenderContext {
             buffer_text_style,
             text_style: window.text_style(),
             syntax_theme: theme.syntax().clone(),
+            mermaid_theme: markdown::mermaid_theme(&window.text_style(), window, cx),
             border_color: theme.colors().border,
             element_background_color: theme.colors().element_background,
             text_color: theme.colors().text,
@@ -167,6 +170,7 @@ pub fn render_markdown_block(block: &ParsedMarkdownElement, cx: &mut RenderConte
         Table(table) => render_markdown_table(table, cx),
         BlockQuote(block_quote) => render_markdown_block_quote(block_quote, cx),
         CodeBlock(code_block) => render_markdown_code_block(code_block, cx),
+        MermaidDiagram(diagram) => render_markdown_mermaid_diagram(diagram, cx),
         HorizontalRule(_) => render_markdown_rule(cx),
         Image(image) => render_markdown_image(image, cx),
     }
@@ -459,6 +463,7 @@ fn paragraph_len(paragraphs: &MarkdownParagraph) -> usize {
             MarkdownParagraphChunk::Text(text) => text.contents.len(),
             // TODO: Scale column width based on image size
             MarkdownParagraphChunk::Image(_) => 1,
+            MarkdownParagraphChunk::Math(math) => math.contents.len(),
         })
         .sum()
 }
@@ -647,6 +652,20 @@ fn render_markdown_code_block(
         .into_any()
 }
 
+fn render_markdown_mermaid_diagram(
+    parsed: &ParsedMarkdownMermaidDiagram,
+    cx: &mut RenderContext,
+) -> AnyElement {
+    let diagram = parsed.diagram.clone();
+    let theme = cx.mermaid_theme.clone();
+    let image = ImageSource::Custom(Arc::new(move |window: &mut Window, cx: &mut App| {
+        Some(diagram.image(&theme, window, cx).map_err(Into::into))
+    }));
+    cx.with_common_p(div())
+        .child(img(image).max_w_full())
+        .into_any()
+}
+
 fn render_markdown_paragraph(parsed: &MarkdownParagraph, cx: &mut RenderContext) -> AnyElement {
     cx.with_common_p(div())
         .children(render_markdown_text(parsed, cx))
@@ -750,12 +769,26 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
             MarkdownParagraphChunk::Image(image) => {
                 any_element.push(render_markdown_image(image, cx));
             }
+
+            MarkdownParagraphChunk::Math(math) => {
+                any_element.push(render_markdown_math(math, cx));
+            }
         }
     }
 
     any_element
 }
 
+fn render_markdown_math(parsed: &ParsedMarkdownMath, cx: &mut RenderContext) -> AnyElement {
+    div()
+        .flex()
+        .justify_center()
+        .py(cx.scaled_rems(0.5))
+        .text_color(cx.text_color)
+        .child(math::math(parsed.contents.clone()).display(true))
+        .into_any()
+}
+
 fn render_markdown_rule(cx: &mut RenderContext) -> AnyElement {
     let rule = div().w_full().h(cx.scaled_rems(0.125)).bg(cx.border_color);
     div().py(cx.scaled_rems(0.5)).child(rule).into_any()
@@ -0,0 +1,214 @@
+use gpui::{
+    App, Bounds, Element, ElementId, Font, GlobalElementId, Hsla, InspectorElementId, IntoElement,
+    LayoutId, PathBuilder, Pixels, SharedString, Style, TextRun, Window, WindowTextSystem, fill,
+    point, px, size,
+};
+use std::sync::Arc;
+use util::ResultExt as _;
+
+use crate::{
+    FontMetrics, GlyphStyle, LayoutItem, MathLayout, MathNode, TextMetrics, layout, parse,
+};
+
+/// Creates an element that lays out and paints a TeX math expression, using the
+/// current text style. Expressions that fail to parse are shown as their source.
+pub fn math(source: impl Into<SharedString>) -> MathElement {
+    MathElement {
+        source: source.into(),
+        display: false,
+        color: None,
+    }
+}
+
+pub struct MathElement {
+    source: SharedString,
+    display: bool,
+    color: Option<Hsla>,
+}
+
+impl MathElement {
+    /// Sets the expression in display style, as for `$$...$$` blocks.
+    pub fn display(mut self, display: bool) -> Self {
+        self.display = display;
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<Hsla>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+}
+
+pub struct MathLayoutState {
+    layout: MathLayout,
+    font: Font,
+    color: Hsla,
+}
+
+impl IntoElement for MathElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for MathElement {
+    type RequestLayoutState = MathLayoutState;
+    type PrepaintState = ();
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let text_style = window.text_style();
+        let font_size = text_style.font_size.to_pixels(window.rem_size());
+        let font = text_style.font();
+        let node = parse(&self.source).unwrap_or_else(|_| MathNode::Text(self.source.to_string()));
+        let mut metrics = TextSystemMetrics {
+            text_system: window.text_system().clone(),
+            font: font.clone(),
+        };
+        let layout = layout(&node, self.display, font_size.into(), &mut metrics);
+
+        let mut style = Style::default();
+        style.size.width = px(layout.width).into();
+        style.size.height = px(layout.height()).into();
+        let layout_id = window.request_layout(style, None, cx);
+        (
+            layout_id,
+            MathLayoutState {
+                layout,
+                font,
+                color: self.color.unwrap_or(text_style.color),
+            },
+        )
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) {
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        state: &mut Self::RequestLayoutState,
+        _prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let baseline = point(bounds.origin.x, bounds.origin.y + px(state.layout.ascent));
+        let to_window = |point: gpui::Point<f32>| baseline + gpui::point(px(point.x), px(point.y));
+
+        for item in &state.layout.items {
+            match item {
+                LayoutItem::Glyph {
+                    text,
+                    origin,
+                    font_size,
+                    style,
+                } => {
+                    let text = SharedString::from(text.clone());
+                    let run = TextRun {
+                        len: text.len(),
+                        font: styled_font(&state.font, *style),
+                        color: state.color,
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    };
+                    let line = window
+                        .text_system()
+                        .shape_line(text, px(*font_size), &[run], None);
+                    let line_height = line.ascent + line.descent;
+                    let origin = to_window(*origin) - gpui::point(px(0.), line.ascent);
+                    line.paint(origin, line_height, window, cx).log_err();
+                }
+                LayoutItem::Rule {
+                    origin,
+                    width,
+                    height,
+                } => {
+                    window.paint_quad(fill(
+                        Bounds::new(to_window(*origin), size(px(*width), px(*height))),
+                        state.color,
+                    ));
+                }
+                LayoutItem::Stroke { points, width } => {
+                    let mut builder = PathBuilder::stroke(px(*width));
+                    let mut points = points.iter().map(|point| to_window(*point));
+                    let Some(start) = points.next() else {
+                        continue;
+                    };
+                    builder.move_to(start);
+                    for point in points {
+                        builder.line_to(point);
+                    }
+                    if let Some(path) = builder.build().log_err() {
+                        window.paint_path(path, state.color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn styled_font(font: &Font, style: GlyphStyle) -> Font {
+    let mut font = font.clone();
+    if style.italic {
+        font = font.italic();
+    }
+    if style.bold {
+        font = font.bold();
+    }
+    font
+}
+
+/// Measures text with a window's text system.
+struct TextSystemMetrics {
+    text_system: Arc<WindowTextSystem>,
+    font: Font,
+}
+
+impl FontMetrics for TextSystemMetrics {
+    fn measure(&mut self, text: &str, style: GlyphStyle, font_size: f32) -> TextMetrics {
+        let run = TextRun {
+            len: text.len(),
+            font: styled_font(&self.font, style),
+            color: Hsla::default(),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        let line = self
+            .text_system
+            .layout_line(text, px(font_size), &[run], None);
+        // Fonts report the ascent and descent of their tallest glyphs, which
+        // would leave fractions and scripts too loosely spaced.
+        TextMetrics {
+            width: line.width.into(),
+            ascent: f32::from(line.ascent).min(font_size * 0.78),
+            descent: f32::from(line.descent).min(font_size * 0.22),
+        }
+    }
+}
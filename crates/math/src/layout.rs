@@ -0,0 +1,970 @@
+use std::f32::consts::PI;
+
+use gpui::{Point, point};
+
+use crate::parser::{Accent, MathNode, MathVariant, MatrixAlignment, OperatorClass};
+
+/// The size of a run of text, as reported by a [`FontMetrics`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextMetrics {
+    pub width: f32,
+    pub ascent: f32,
+    pub descent: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GlyphStyle {
+    pub italic: bool,
+    pub bold: bool,
+}
+
+/// Measures text for laying out math, so that layout doesn't depend on a window.
+pub trait FontMetrics {
+    fn measure(&mut self, text: &str, style: GlyphStyle, font_size: f32) -> TextMetrics;
+}
+
+/// An item to paint, positioned relative to the left end of the layout's baseline,
+/// with `y` growing downwards.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutItem {
+    /// A run of text whose baseline starts at `origin`.
+    Glyph {
+        text: String,
+        origin: Point<f32>,
+        font_size: f32,
+        style: GlyphStyle,
+    },
+    /// A filled rectangle, such as a fraction bar.
+    Rule {
+        origin: Point<f32>,
+        width: f32,
+        height: f32,
+    },
+    /// A stroked polyline, such as a radical sign or a stretched delimiter.
+    Stroke { points: Vec<Point<f32>>, width: f32 },
+}
+
+impl LayoutItem {
+    fn translate(&mut self, delta: Point<f32>) {
+        match self {
+            LayoutItem::Glyph { origin, .. } | LayoutItem::Rule { origin, .. } => {
+                *origin = *origin + delta
+            }
+            LayoutItem::Stroke { points, .. } => {
+                for point in points {
+                    *point = *point + delta;
+                }
+            }
+        }
+    }
+}
+
+/// The result of laying out a math expression.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MathLayout {
+    pub width: f32,
+    /// The distance from the baseline to the top of the layout.
+    pub ascent: f32,
+    /// The distance from the baseline to the bottom of the layout.
+    pub descent: f32,
+    pub items: Vec<LayoutItem>,
+}
+
+impl MathLayout {
+    pub fn height(&self) -> f32 {
+        self.ascent + self.descent
+    }
+
+    /// Adds the items of `other`, with its baseline starting at `origin`,
+    /// growing this layout's ascent and descent to fit.
+    fn append(&mut self, other: MathLayout, origin: Point<f32>) {
+        self.ascent = self.ascent.max(other.ascent - origin.y);
+        self.descent = self.descent.max(other.descent + origin.y);
+        self.width = self.width.max(origin.x + other.width);
+        self.items.extend(other.items.into_iter().map(|mut item| {
+            item.translate(origin);
+            item
+        }));
+    }
+}
+
+/// Lays out a parsed math expression. Display math, as in `$$...$$`, sets large
+/// operators bigger and places their limits above and below them.
+pub fn layout(
+    node: &MathNode,
+    display: bool,
+    font_size: f32,
+    metrics: &mut dyn FontMetrics,
+) -> MathLayout {
+    let style = Style {
+        font_size,
+        base_font_size: font_size,
+        display,
+        script: false,
+    };
+    Layouter { metrics }.layout_node(node, style)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Style {
+    font_size: f32,
+    base_font_size: f32,
+    display: bool,
+    script: bool,
+}
+
+impl Style {
+    fn script(self) -> Self {
+        Self {
+            font_size: (self.font_size * 0.7).max(self.base_font_size * 0.5),
+            display: false,
+            script: true,
+            ..self
+        }
+    }
+
+    /// The style of a fraction's numerator and denominator.
+    fn fraction(self) -> Self {
+        if self.display {
+            Self {
+                display: false,
+                ..self
+            }
+        } else {
+            self.script()
+        }
+    }
+
+    fn em(&self, ems: f32) -> f32 {
+        self.font_size * ems
+    }
+
+    /// The height of the math axis above the baseline, on which fraction bars
+    /// and operators are centered.
+    fn axis(&self) -> f32 {
+        self.em(0.25)
+    }
+
+    fn rule_thickness(&self) -> f32 {
+        self.em(0.05).max(1.)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AtomClass {
+    Ordinary,
+    LargeOperator,
+    Binary,
+    Relation,
+    Open,
+    Close,
+    Punctuation,
+}
+
+fn atom_class(node: &MathNode) -> AtomClass {
+    match node {
+        MathNode::Operator { text, class } => match class {
+            OperatorClass::Binary => AtomClass::Binary,
+            OperatorClass::Relation => AtomClass::Relation,
+            OperatorClass::Punctuation => AtomClass::Punctuation,
+            OperatorClass::Fence if matches!(text.as_str(), "(" | "[" | "{" | "⟨" | "⌊" | "⌈") => {
+                AtomClass::Open
+            }
+            OperatorClass::Fence if matches!(text.as_str(), ")" | "]" | "}" | "⟩" | "⌋" | "⌉") => {
+                AtomClass::Close
+            }
+            OperatorClass::Fence | OperatorClass::Ordinary => AtomClass::Ordinary,
+        },
+        MathNode::LargeOperator { .. } | MathNode::Function { .. } => AtomClass::LargeOperator,
+        MathNode::Scripts { base, .. } => atom_class(base),
+        _ => AtomClass::Ordinary,
+    }
+}
+
+/// The space between two adjacent atoms in a row, in ems.
+fn atom_spacing(left: AtomClass, right: AtomClass, style: Style) -> f32 {
+    use AtomClass::*;
+    match (left, right) {
+        (Ordinary | LargeOperator | Close, LargeOperator) | (LargeOperator, Ordinary) => 3. / 18.,
+        _ if style.script => 0.,
+        (Binary, _) | (_, Binary) => 4. / 18.,
+        (Relation, Relation) => 0.,
+        (Relation, _) | (_, Relation) => 5. / 18.,
+        (Punctuation, _) => 3. / 18.,
+        _ => 0.,
+    }
+}
+
+struct Layouter<'a> {
+    metrics: &'a mut dyn FontMetrics,
+}
+
+impl Layouter<'_> {
+    fn layout_node(&mut self, node: &MathNode, style: Style) -> MathLayout {
+        match node {
+            MathNode::Identifier { text, variant } => self.glyph(
+                text,
+                GlyphStyle {
+                    italic: *variant == MathVariant::Italic,
+                    bold: *variant == MathVariant::Bold,
+                },
+                style.font_size,
+            ),
+            MathNode::Number(text)
+            | MathNode::Text(text)
+            | MathNode::Function { name: text, .. } => {
+                self.glyph(text, GlyphStyle::default(), style.font_size)
+            }
+            MathNode::Operator { text, .. } => {
+                self.glyph(text, GlyphStyle::default(), style.font_size)
+            }
+            MathNode::LargeOperator { text, .. } => self.layout_large_operator(text, style),
+            MathNode::Row(nodes) => self.layout_row(nodes, style),
+            MathNode::Fraction {
+                numerator,
+                denominator,
+                bar,
+            } => self.layout_fraction(numerator, denominator, *bar, style),
+            MathNode::Root { index, radicand } => {
+                self.layout_root(index.as_deref(), radicand, style)
+            }
+            MathNode::Scripts {
+                base,
+                subscript,
+                superscript,
+            } => self.layout_scripts(base, subscript.as_deref(), superscript.as_deref(), style),
+            MathNode::Delimited { open, close, body } => {
+                let body = self.layout_node(body, style);
+                self.layout_delimited(open, close, body, style)
+            }
+            MathNode::Matrix { rows, alignment } => self.layout_matrix(rows, *alignment, style),
+            MathNode::Accent { accent, body } => self.layout_accent(*accent, body, style),
+            MathNode::Space(ems) => MathLayout {
+                width: style.em(*ems),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn glyph(&mut self, text: &str, glyph_style: GlyphStyle, font_size: f32) -> MathLayout {
+        let metrics = self.metrics.measure(text, glyph_style, font_size);
+        MathLayout {
+            width: metrics.width,
+            ascent: metrics.ascent,
+            descent: metrics.descent,
+            items: vec![LayoutItem::Glyph {
+                text: text.to_string(),
+                origin: point(0., 0.),
+                font_size,
+                style: glyph_style,
+            }],
+        }
+    }
+
+    fn layout_row(&mut self, nodes: &[MathNode], style: Style) -> MathLayout {
+        let mut classes = nodes.iter().map(atom_class).collect::<Vec<_>>();
+        // A binary operator without an operand on both sides, like a leading
+        // minus sign, is spaced like an ordinary symbol.
+        for ix in 0..classes.len() {
+            if classes[ix] != AtomClass::Binary {
+                continue;
+            }
+            let previous = ix.checked_sub(1).map(|ix| classes[ix]);
+            let next = classes.get(ix + 1).copied();
+            if matches!(
+                previous,
+                None | Some(
+                    AtomClass::Binary
+                        | AtomClass::Relation
+                        | AtomClass::Open
+                        | AtomClass::Punctuation
+                        | AtomClass::LargeOperator
+                )
+            ) || matches!(
+                next,
+                None | Some(AtomClass::Relation | AtomClass::Close | AtomClass::Punctuation)
+            ) {
+                classes[ix] = AtomClass::Ordinary;
+            }
+        }
+
+        let mut layout = MathLayout::default();
+        let mut x = 0.;
+        let mut previous_class = None;
+        for (node, class) in nodes.iter().zip(classes) {
+            if let Some(previous_class) = previous_class {
+                x += style.em(atom_spacing(previous_class, class, style));
+            }
+            let child = self.layout_node(node, style);
+            let width = child.width;
+            layout.append(child, point(x, 0.));
+            x += width;
+            if !matches!(node, MathNode::Space(_)) {
+                previous_class = Some(class);
+            }
+        }
+        layout.width = x;
+        layout
+    }
+
+    fn layout_large_operator(&mut self, text: &str, style: Style) -> MathLayout {
+        let scale = if style.display { 1.6 } else { 1.15 };
+        let mut layout = self.glyph(text, GlyphStyle::default(), style.font_size * scale);
+        // Center the operator on the math axis, assuming its ink sits between the
+        // baseline and roughly the height of a capital letter.
+        let shift = style.em(0.35 * scale) - style.axis();
+        for item in &mut layout.items {
+            item.translate(point(0., shift));
+        }
+        layout.ascent -= shift;
+        layout.descent += shift;
+        layout
+    }
+
+    fn layout_fraction(
+        &mut self,
+        numerator: &MathNode,
+        denominator: &MathNode,
+        bar: bool,
+        style: Style,
+    ) -> MathLayout {
+        let child_style = style.fraction();
+        let numerator = self.layout_node(numerator, child_style);
+        let denominator = self.layout_node(denominator, child_style);
+
+        let thickness = style.rule_thickness();
+        let gap = if bar {
+            style.em(if style.display { 0.15 } else { 0.1 })
+        } else {
+            style.em(0.3)
+        };
+        let padding = style.em(0.12);
+        let width = numerator.width.max(denominator.width) + 2. * padding;
+        let bar_top = -style.axis() - thickness / 2.;
+        let bar_bottom = bar_top + thickness;
+        let numerator_baseline = bar_top - gap - numerator.descent;
+        let denominator_baseline = bar_bottom + gap + denominator.ascent;
+
+        let mut layout = MathLayout::default();
+        let numerator_x = (width - numerator.width) / 2.;
+        let denominator_x = (width - denominator.width) / 2.;
+        layout.append(numerator, point(numerator_x, numerator_baseline));
+        layout.append(denominator, point(denominator_x, denominator_baseline));
+        if bar {
+            layout.items.push(LayoutItem::Rule {
+                origin: point(padding / 2., bar_top),
+                width: width - padding,
+                height: thickness,
+            });
+        }
+        layout.width = width;
+        layout
+    }
+
+    fn layout_root(
+        &mut self,
+        index: Option<&MathNode>,
+        radicand: &MathNode,
+        style: Style,
+    ) -> MathLayout {
+        let radicand = self.layout_node(radicand, style);
+        let thickness = style.rule_thickness();
+        let gap = style.em(0.12);
+        let top = -(radicand.ascent + gap + thickness / 2.);
+        let bottom = radicand.descent + style.em(0.05);
+        let height = bottom - top;
+        let sign_width = (style.em(0.5) + 0.1 * (height - style.font_size)).min(style.em(0.8));
+        let at_height = |fraction: f32| top + height * fraction;
+
+        let mut layout = MathLayout::default();
+        let mut offset = 0.;
+        if let Some(index) = index {
+            let index_style = style.script().script();
+            let index = self.layout_node(index, index_style);
+            let index_right = sign_width * 0.45;
+            offset = (index.width - index_right).max(0.);
+            let origin = point(
+                offset + index_right - index.width,
+                at_height(0.5) - style.em(0.05),
+            );
+            layout.append(index, origin);
+        }
+
+        layout.items.push(LayoutItem::Stroke {
+            points: vec![
+                point(offset, at_height(0.6)),
+                point(offset + sign_width * 0.2, at_height(0.5)),
+                point(offset + sign_width * 0.5, bottom),
+                point(offset + sign_width, top),
+                point(offset + sign_width + radicand.width + style.em(0.1), top),
+            ],
+            width: thickness,
+        });
+        let radicand_x = offset + sign_width + style.em(0.05);
+        let radicand_width = radicand.width;
+        layout.append(radicand, point(radicand_x, 0.));
+        layout.ascent = layout.ascent.max(-top + thickness / 2.);
+        layout.descent = layout.descent.max(bottom + thickness / 2.);
+        layout.width = radicand_x + radicand_width + style.em(0.1);
+        layout
+    }
+
+    fn layout_scripts(
+        &mut self,
+        base: &MathNode,
+        subscript: Option<&MathNode>,
+        superscript: Option<&MathNode>,
+        style: Style,
+    ) -> MathLayout {
+        let limits = style.display
+            && matches!(
+                base,
+                MathNode::LargeOperator { limits: true, .. }
+                    | MathNode::Function { limits: true, .. }
+            );
+        let is_glyph = matches!(
+            base,
+            MathNode::Identifier { .. }
+                | MathNode::Number(_)
+                | MathNode::Operator { .. }
+                | MathNode::Text(_)
+                | MathNode::Function { .. }
+        );
+        let italic_correction = if matches!(
+            base,
+            MathNode::Identifier {
+                variant: MathVariant::Italic,
+                ..
+            }
+        ) {
+            style.em(0.05)
+        } else {
+            0.
+        };
+
+        let base = self.layout_node(base, style);
+        let script_style = style.script();
+        let superscript = superscript.map(|node| self.layout_node(node, script_style));
+        let subscript = subscript.map(|node| self.layout_node(node, script_style));
+
+        let mut layout = MathLayout::default();
+        if limits {
+            let gap = style.em(0.1);
+            let width = base
+                .width
+                .max(superscript.as_ref().map_or(0., |layout| layout.width))
+                .max(subscript.as_ref().map_or(0., |layout| layout.width));
+            let base_ascent = base.ascent;
+            let base_descent = base.descent;
+            let base_x = (width - base.width) / 2.;
+            layout.append(base, point(base_x, 0.));
+            if let Some(superscript) = superscript {
+                let baseline = -(base_ascent + gap + superscript.descent);
+                let x = (width - superscript.width) / 2.;
+                layout.append(superscript, point(x, baseline));
+            }
+            if let Some(subscript) = subscript {
+                let baseline = base_descent + gap + subscript.ascent;
+                let x = (width - subscript.width) / 2.;
+                layout.append(subscript, point(x, baseline));
+            }
+            layout.width = width;
+            return layout;
+        }
+
+        let (mut superscript_shift, mut subscript_shift) = if is_glyph {
+            (style.em(0.42), style.em(0.2))
+        } else {
+            (
+                style.em(0.42).max(base.ascent - style.em(0.3)),
+                style.em(0.2).max(base.descent + style.em(0.1)),
+            )
+        };
+        if let Some(superscript) = &superscript {
+            superscript_shift = superscript_shift.max(superscript.descent + style.em(0.1));
+        }
+        if let Some((superscript, subscript)) = superscript.as_ref().zip(subscript.as_ref()) {
+            let gap =
+                (superscript_shift - superscript.descent) - (subscript.ascent - subscript_shift);
+            let min_gap = style.em(0.05);
+            if gap < min_gap {
+                subscript_shift += min_gap - gap;
+            }
+        }
+
+        let base_width = base.width;
+        layout.append(base, point(0., 0.));
+        let mut width = base_width;
+        if let Some(superscript) = superscript {
+            let x = base_width + italic_correction;
+            width = width.max(x + superscript.width);
+            layout.append(superscript, point(x, -superscript_shift));
+        }
+        if let Some(subscript) = subscript {
+            width = width.max(base_width + subscript.width);
+            layout.append(subscript, point(base_width, subscript_shift));
+        }
+        layout.width = width + style.em(0.05);
+        layout
+    }
+
+    fn layout_delimited(
+        &mut self,
+        open: &str,
+        close: &str,
+        body: MathLayout,
+        style: Style,
+    ) -> MathLayout {
+        let axis = style.axis();
+        let half_height = (body.ascent - axis).max(body.descent + axis);
+        let height = (2. * half_height + style.em(0.1)).max(style.font_size);
+
+        let mut layout = MathLayout::default();
+        let mut x = 0.;
+        if !open.is_empty() {
+            let delimiter = self.layout_delimiter(open, height, style);
+            x += delimiter.width;
+            layout.append(delimiter, point(0., 0.));
+        }
+        let body_width = body.width;
+        layout.append(body, point(x, 0.));
+        x += body_width;
+        if !close.is_empty() {
+            let delimiter = self.layout_delimiter(close, height, style);
+            let width = delimiter.width;
+            layout.append(delimiter, point(x, 0.));
+            x += width;
+        }
+        layout.width = x;
+        layout
+    }
+
+    /// Lays out a delimiter that spans `height`, centered on the math axis. Short
+    /// delimiters use the font's glyph, while taller ones are drawn as strokes.
+    fn layout_delimiter(&mut self, delimiter: &str, height: f32, style: Style) -> MathLayout {
+        if height <= style.em(1.2) {
+            return self.glyph(delimiter, GlyphStyle::default(), style.font_size);
+        }
+
+        let thickness = style.rule_thickness() * 1.2;
+        let width = match delimiter {
+            "{" | "}" => style.em(0.5),
+            "|" | "‖" => style.em(0.3),
+            _ => style.em(0.4),
+        };
+        let top = -style.axis() - height / 2.;
+        let bottom = top + height;
+        let middle = (top + bottom) / 2.;
+
+        let (shape, mirrored) = match delimiter {
+            "(" => (DelimiterShape::Parenthesis, false),
+            ")" => (DelimiterShape::Parenthesis, true),
+            "[" => (DelimiterShape::Bracket, false),
+            "]" => (DelimiterShape::Bracket, true),
+            "{" => (DelimiterShape::Brace, false),
+            "}" => (DelimiterShape::Brace, true),
+            "⟨" => (DelimiterShape::Angle, false),
+            "⟩" => (DelimiterShape::Angle, true),
+            "⌊" => (DelimiterShape::Floor, false),
+            "⌋" => (DelimiterShape::Floor, true),
+            "⌈" => (DelimiterShape::Ceiling, false),
+            "⌉" => (DelimiterShape::Ceiling, true),
+            "‖" => (DelimiterShape::DoubleBar, false),
+            "/" => (DelimiterShape::Slash, false),
+            _ => (DelimiterShape::Bar, false),
+        };
+
+        let strokes = match shape {
+            DelimiterShape::Parenthesis => {
+                let segments = 16;
+                vec![
+                    (0..=segments)
+                        .map(|ix| {
+                            let t = ix as f32 / segments as f32;
+                            (0.8 - 0.55 * (PI * t).sin(), top + height * t)
+                        })
+                        .collect::<Vec<_>>(),
+                ]
+            }
+            DelimiterShape::Bracket => {
+                vec![vec![(0.8, top), (0.3, top), (0.3, bottom), (0.8, bottom)]]
+            }
+            DelimiterShape::Brace => {
+                let tip = height * 0.08;
+                vec![vec![
+                    (0.8, top),
+                    (0.55, top + tip),
+                    (0.55, middle - tip),
+                    (0.2, middle),
+                    (0.55, middle + tip),
+                    (0.55, bottom - tip),
+                    (0.8, bottom),
+                ]]
+            }
+            DelimiterShape::Angle => vec![vec![(0.8, top), (0.2, middle), (0.8, bottom)]],
+            DelimiterShape::Floor => vec![vec![(0.3, top), (0.3, bottom), (0.8, bottom)]],
+            DelimiterShape::Ceiling => vec![vec![(0.8, top), (0.3, top), (0.3, bottom)]],
+            DelimiterShape::Bar => vec![vec![(0.5, top), (0.5, bottom)]],
+            DelimiterShape::DoubleBar => {
+                vec![
+                    vec![(0.3, top), (0.3, bottom)],
+                    vec![(0.7, top), (0.7, bottom)],
+                ]
+            }
+            DelimiterShape::Slash => vec![vec![(0.9, top), (0.1, bottom)]],
+        };
+
+        MathLayout {
+            width,
+            ascent: -top,
+            descent: bottom,
+            items: strokes
+                .into_iter()
+                .map(|stroke| LayoutItem::Stroke {
+                    points: stroke
+                        .into_iter()
+                        .map(|(x, y)| {
+                            let x = if mirrored { 1. - x } else { x };
+                            point(x * width, y)
+                        })
+                        .collect(),
+                    width: thickness,
+                })
+                .collect(),
+        }
+    }
+
+    fn layout_matrix(
+        &mut self,
+        rows: &[Vec<MathNode>],
+        alignment: MatrixAlignment,
+        style: Style,
+    ) -> MathLayout {
+        let cell_style = Style {
+            display: alignment == MatrixAlignment::RightLeft && style.display,
+            ..style
+        };
+        let cells = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| self.layout_node(cell, cell_style))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let column_count = cells.iter().map(Vec::len).max().unwrap_or(0);
+        let mut column_widths = vec![0f32; column_count];
+        for row in &cells {
+            for (ix, cell) in row.iter().enumerate() {
+                column_widths[ix] = column_widths[ix].max(cell.width);
+            }
+        }
+        let column_gap = |ix: usize| match alignment {
+            MatrixAlignment::RightLeft if ix % 2 == 0 => 0.,
+            MatrixAlignment::RightLeft => style.em(2.),
+            _ => style.em(1.),
+        };
+        let row_gap = style.em(0.3);
+        let row_metrics = cells
+            .iter()
+            .map(|row| {
+                row.iter().fold(
+                    (style.em(0.7), style.em(0.25)),
+                    |(ascent, descent), cell| (ascent.max(cell.ascent), descent.max(cell.descent)),
+                )
+            })
+            .collect::<Vec<_>>();
+        let total_height = row_metrics
+            .iter()
+            .map(|(ascent, descent)| ascent + descent)
+            .sum::<f32>()
+            + row_gap * rows.len().saturating_sub(1) as f32;
+
+        let mut layout = MathLayout::default();
+        let mut y = -style.axis() - total_height / 2.;
+        for (row, (ascent, descent)) in cells.into_iter().zip(row_metrics) {
+            let baseline = y + ascent;
+            let mut x = 0.;
+            for (ix, cell) in row.into_iter().enumerate() {
+                let slack = column_widths[ix] - cell.width;
+                let offset = match alignment {
+                    MatrixAlignment::Center => slack / 2.,
+                    MatrixAlignment::Left => 0.,
+                    MatrixAlignment::RightLeft if ix % 2 == 0 => slack,
+                    MatrixAlignment::RightLeft => 0.,
+                };
+                layout.append(cell, point(x + offset, baseline));
+                x += column_widths[ix] + column_gap(ix);
+            }
+            y = baseline + descent + row_gap;
+        }
+        layout.width = column_widths.iter().sum::<f32>()
+            + (0..column_count.saturating_sub(1))
+                .map(column_gap)
+                .sum::<f32>();
+        layout.ascent = layout.ascent.max(style.axis() + total_height / 2.);
+        layout.descent = layout.descent.max(total_height / 2. - style.axis());
+        layout
+    }
+
+    fn layout_accent(&mut self, accent: Accent, body: &MathNode, style: Style) -> MathLayout {
+        let body = self.layout_node(body, style);
+        let thickness = style.rule_thickness();
+        let width = body.width;
+        let above = -(body.ascent + style.em(0.08));
+        let mut items = Vec::new();
+        let mut extra_ascent = 0.;
+        let mut extra_descent = 0.;
+        match accent {
+            Accent::Hat => {
+                let accent_width = width.min(style.em(0.5)).max(width * 0.6);
+                let left = (width - accent_width) / 2.;
+                let height = style.em(0.12);
+                items.push(LayoutItem::Stroke {
+                    points: vec![
+                        point(left, above),
+                        point(width / 2., above - height),
+                        point(left + accent_width, above),
+                    ],
+                    width: thickness,
+                });
+                extra_ascent = style.em(0.08) + height + thickness;
+            }
+            Accent::Tilde => {
+                let height = style.em(0.06);
+                let segments = 12;
+                items.push(LayoutItem::Stroke {
+                    points: (0..=segments)
+                        .map(|ix| {
+                            let t = ix as f32 / segments as f32;
+                            point(
+                                width * (0.1 + 0.8 * t),
+                                above - height - height * (2. * PI * t).sin(),
+                            )
+                        })
+                        .collect(),
+                    width: thickness,
+                });
+                extra_ascent = style.em(0.08) + 2. * height + thickness;
+            }
+            Accent::Bar => {
+                items.push(LayoutItem::Rule {
+                    origin: point(0., above - thickness),
+                    width,
+                    height: thickness,
+                });
+                extra_ascent = style.em(0.08) + thickness;
+            }
+            Accent::Vector => {
+                let y = above - style.em(0.08);
+                let head = style.em(0.12);
+                items.push(LayoutItem::Stroke {
+                    points: vec![point(0., y), point(width, y)],
+                    width: thickness,
+                });
+                items.push(LayoutItem::Stroke {
+                    points: vec![
+                        point(width - head, y - head * 0.6),
+                        point(width, y),
+                        point(width - head, y + head * 0.6),
+                    ],
+                    width: thickness,
+                });
+                extra_ascent = style.em(0.16) + head * 0.6 + thickness;
+            }
+            Accent::Dot | Accent::DoubleDot => {
+                let size = style.em(0.1);
+                let centers = if accent == Accent::Dot {
+                    vec![width / 2.]
+                } else {
+                    vec![width / 2. - size * 1.25, width / 2. + size * 1.25]
+                };
+                for center in centers {
+                    items.push(LayoutItem::Rule {
+                        origin: point(center - size / 2., above - size),
+                        width: size,
+                        height: size,
+                    });
+                }
+                extra_ascent = style.em(0.08) + size;
+            }
+            Accent::Underline => {
+                let y = body.descent + style.em(0.08);
+                items.push(LayoutItem::Rule {
+                    origin: point(0., y),
+                    width,
+                    height: thickness,
+                });
+                extra_descent = style.em(0.08) + thickness;
+            }
+        }
+
+        let mut layout = MathLayout {
+            width,
+            ascent: body.ascent + extra_ascent,
+            descent: body.descent + extra_descent,
+            items,
+        };
+        layout.append(body, point(0., 0.));
+        layout
+    }
+}
+
+enum DelimiterShape {
+    Parenthesis,
+    Bracket,
+    Brace,
+    Angle,
+    Floor,
+    Ceiling,
+    Bar,
+    DoubleBar,
+    Slash,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    /// Measures every character as half an em wide, with a fixed ascent and descent.
+    struct FixedMetrics;
+
+    impl FontMetrics for FixedMetrics {
+        fn measure(&mut self, text: &str, _: GlyphStyle, font_size: f32) -> TextMetrics {
+            TextMetrics {
+                width: text.chars().count() as f32 * font_size * 0.5,
+                ascent: font_size * 0.75,
+                descent: font_size * 0.25,
+            }
+        }
+    }
+
+    fn layout_source(source: &str, display: bool) -> MathLayout {
+        layout(&parse(source).unwrap(), display, 20., &mut FixedMetrics)
+    }
+
+    fn glyphs(layout: &MathLayout) -> Vec<(&str, Point<f32>, f32)> {
+        layout
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                LayoutItem::Glyph {
+                    text,
+                    origin,
+                    font_size,
+                    ..
+                } => Some((text.as_str(), *origin, *font_size)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[track_caller]
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_row_spacing() {
+        let layout = layout_source("a+b=-c", false);
+        // Binary and relation operators are surrounded by space, except for a
+        // leading minus sign, which is set next to its operand.
+        let binary = 20. * 4. / 18.;
+        let relation = 20. * 5. / 18.;
+        let expected = [
+            ("a", 0.),
+            ("+", 10. + binary),
+            ("b", 20. + 2. * binary),
+            ("=", 30. + 2. * binary + relation),
+            ("−", 40. + 2. * binary + 2. * relation),
+            ("c", 50. + 2. * binary + 2. * relation),
+        ];
+        let glyphs = glyphs(&layout);
+        assert_eq!(glyphs.len(), expected.len());
+        for ((text, origin, _), (expected_text, expected_x)) in glyphs.into_iter().zip(expected) {
+            assert_eq!(text, expected_text);
+            assert_close(origin.x, expected_x);
+        }
+        assert_close(layout.width, 60. + 2. * binary + 2. * relation);
+    }
+
+    #[test]
+    fn test_fraction_layout() {
+        let layout = layout_source("\\frac{1}{2}", true);
+        let glyphs = glyphs(&layout);
+        assert_eq!(glyphs.len(), 2);
+        let (_, numerator, _) = glyphs[0];
+        let (_, denominator, _) = glyphs[1];
+        assert!(numerator.y < -5.);
+        assert!(denominator.y > 0.);
+        assert_close(numerator.x, denominator.x);
+
+        let bar = layout
+            .items
+            .iter()
+            .find_map(|item| match item {
+                LayoutItem::Rule { origin, .. } => Some(*origin),
+                _ => None,
+            })
+            .unwrap();
+        // The bar is centered on the math axis, between the numerator and denominator.
+        assert!(numerator.y + 5. < bar.y && bar.y < denominator.y - 15.);
+        assert!(layout.ascent > 15. && layout.descent > 15.);
+    }
+
+    #[test]
+    fn test_scripts_and_limits() {
+        let inline = layout_source("\\sum_{i}^{n} x_i^2", false);
+        let display = layout_source("\\sum_{i}^{n} x_i^2", true);
+
+        let inline_glyphs = glyphs(&inline);
+        let display_glyphs = glyphs(&display);
+        let position = |glyphs: &[(&str, Point<f32>, f32)], text: &str| {
+            glyphs
+                .iter()
+                .find(|(glyph, _, _)| *glyph == text)
+                .map(|(_, origin, size)| (*origin, *size))
+                .unwrap()
+        };
+
+        let (sum, _) = position(&inline_glyphs, "∑");
+        let (upper, upper_size) = position(&inline_glyphs, "n");
+        assert!(upper.x > sum.x, "inline limits are set as scripts");
+        assert_close(upper_size, 14.);
+
+        let (sum, sum_size) = position(&display_glyphs, "∑");
+        let (upper, _) = position(&display_glyphs, "n");
+        let (lower, _) = position(&display_glyphs, "i");
+        assert_close(sum_size, 32.);
+        assert!(
+            upper.x > sum.x && upper.x < sum.x + 16.,
+            "display limits are centered"
+        );
+        assert!(upper.y < sum.y - 20. && lower.y > sum.y);
+    }
+
+    #[test]
+    fn test_stretched_delimiters() {
+        let short = layout_source("\\left( x \\right)", false);
+        assert!(
+            short
+                .items
+                .iter()
+                .all(|item| matches!(item, LayoutItem::Glyph { .. }))
+        );
+
+        let tall = layout_source("\\left( \\frac{a}{b} \\right)", true);
+        let strokes = tall
+            .items
+            .iter()
+            .filter(|item| matches!(item, LayoutItem::Stroke { .. }))
+            .count();
+        assert_eq!(strokes, 2);
+        assert!(tall.height() > 40.);
+    }
+}
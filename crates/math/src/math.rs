@@ -0,0 +1,23 @@
+//! Parsing, layout and rendering for the subset of TeX used by math in Markdown,
+//! such as `$x^2$` and `$$\frac{a}{b}$$`.
+
+mod element;
+mod layout;
+mod parser;
+mod symbols;
+mod unicode;
+
+pub use element::{MathElement, math};
+pub use layout::{FontMetrics, GlyphStyle, LayoutItem, MathLayout, TextMetrics, layout};
+pub use parser::{
+    Accent, MathNode, MathVariant, MatrixAlignment, OperatorClass, ParseError, parse,
+};
+pub use unicode::to_unicode;
+
+/// Converts the source of an inline math expression to plain text, falling back
+/// to the source itself when it can't be parsed.
+pub fn inline_text(source: &str) -> String {
+    parse(source)
+        .map(|node| to_unicode(&node))
+        .unwrap_or_else(|_| source.to_string())
+}
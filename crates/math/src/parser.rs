@@ -0,0 +1,749 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use crate::symbols::{self, Symbol};
+
+/// A node in the tree produced by parsing a TeX math expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MathNode {
+    /// A letter or symbol set in the given variant, e.g. `x` or `\alpha`.
+    Identifier {
+        text: String,
+        variant: MathVariant,
+    },
+    /// A run of digits, e.g. `3.14`.
+    Number(String),
+    /// An operator, relation or punctuation mark, e.g. `+`, `\leq` or `,`.
+    Operator {
+        text: String,
+        class: OperatorClass,
+    },
+    /// Upright text, e.g. `\text{if }`.
+    Text(String),
+    /// A function name set in upright text, e.g. `\sin` or `\lim`.
+    Function {
+        name: String,
+        limits: bool,
+    },
+    /// A large operator such as `\sum` or `\int`.
+    LargeOperator {
+        text: String,
+        limits: bool,
+    },
+    /// A sequence of nodes laid out horizontally.
+    Row(Vec<MathNode>),
+    Fraction {
+        numerator: Box<MathNode>,
+        denominator: Box<MathNode>,
+        /// Whether a fraction bar is drawn, which isn't the case for `\binom`.
+        bar: bool,
+    },
+    Root {
+        index: Option<Box<MathNode>>,
+        radicand: Box<MathNode>,
+    },
+    Scripts {
+        base: Box<MathNode>,
+        subscript: Option<Box<MathNode>>,
+        superscript: Option<Box<MathNode>>,
+    },
+    /// A body surrounded by delimiters that stretch to its height, e.g. `\left( x \right)`.
+    /// An empty delimiter is omitted, as with `\left.`.
+    Delimited {
+        open: String,
+        close: String,
+        body: Box<MathNode>,
+    },
+    /// A grid of cells, e.g. `\begin{pmatrix} a & b \\ c & d \end{pmatrix}`.
+    Matrix {
+        rows: Vec<Vec<MathNode>>,
+        alignment: MatrixAlignment,
+    },
+    Accent {
+        accent: Accent,
+        body: Box<MathNode>,
+    },
+    /// Horizontal space, measured in ems.
+    Space(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathVariant {
+    Italic,
+    Upright,
+    Bold,
+}
+
+/// Determines the spacing around an operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperatorClass {
+    /// A binary operator, such as `+` or `\times`.
+    Binary,
+    /// A relation, such as `=` or `\to`.
+    Relation,
+    /// Punctuation, such as `,`, followed by a thin space.
+    Punctuation,
+    /// An opening or closing fence that isn't stretched, such as `(`.
+    Fence,
+    /// Any other symbol, such as `\infty`, with no extra spacing.
+    Ordinary,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixAlignment {
+    /// Every column is centered, as in `matrix`.
+    Center,
+    /// Every column is aligned to the left, as in `cases`.
+    Left,
+    /// Columns alternate between right and left alignment, as in `aligned`.
+    RightLeft,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Accent {
+    Hat,
+    Tilde,
+    Bar,
+    Vector,
+    Dot,
+    DoubleDot,
+    Underline,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub offset: usize,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a TeX math expression, such as the contents of `$...$` or `$$...$$`.
+pub fn parse(source: &str) -> Result<MathNode, ParseError> {
+    let mut parser = Parser {
+        source,
+        chars: source.char_indices().peekable(),
+    };
+    let row = parser.parse_row(Terminator::EndOfInput)?;
+    Ok(row)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Terminator {
+    EndOfInput,
+    Brace,
+    Right,
+    Environment,
+    Bracket,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.source.len(), |(offset, _)| *offset)
+    }
+
+    fn error<T>(&mut self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError {
+            message: message.into(),
+            offset: self.offset(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            _ => self.error(format!("expected '{expected}'")),
+        }
+    }
+
+    fn parse_row(&mut self, terminator: Terminator) -> Result<MathNode, ParseError> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let Some(&(_, c)) = self.chars.peek() else {
+                if terminator == Terminator::EndOfInput {
+                    break;
+                }
+                return self.error("unexpected end of input");
+            };
+            match c {
+                '}' if terminator == Terminator::Brace => {
+                    self.chars.next();
+                    break;
+                }
+                ']' if terminator == Terminator::Bracket => {
+                    self.chars.next();
+                    break;
+                }
+                '}' => return self.error("unmatched '}'"),
+                '&' if terminator == Terminator::Environment => break,
+                '\\' if self.at_row_end(terminator) => break,
+                '^' | '_' => {
+                    let base = nodes.pop().unwrap_or_else(|| MathNode::Row(Vec::new()));
+                    let node = self.parse_scripts(base)?;
+                    nodes.push(node);
+                }
+                '\'' => {
+                    self.chars.next();
+                    let base = nodes.pop().unwrap_or_else(|| MathNode::Row(Vec::new()));
+                    nodes.push(attach_prime(base));
+                }
+                _ => {
+                    if let Some(node) = self.parse_atom(terminator)? {
+                        nodes.push(node);
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.pop().unwrap()
+        } else {
+            MathNode::Row(nodes)
+        })
+    }
+
+    /// Whether the next command ends the current row: a `\right`, an `\end`
+    /// or a `\\` row separator, depending on what's being parsed.
+    fn at_row_end(&mut self, terminator: Terminator) -> bool {
+        let rest = &self.source[self.offset()..];
+        match terminator {
+            Terminator::Right => rest.starts_with("\\right") && !is_command_prefix(rest, 6),
+            Terminator::Environment => {
+                rest.starts_with("\\\\") || rest.starts_with("\\end") && !is_command_prefix(rest, 4)
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_scripts(&mut self, base: MathNode) -> Result<MathNode, ParseError> {
+        let (base, mut subscript, mut superscript) = match base {
+            MathNode::Scripts {
+                base,
+                subscript,
+                superscript,
+            } => (*base, subscript, superscript),
+            base => (base, None, None),
+        };
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some((_, '^')) => {
+                    self.chars.next();
+                    if superscript.is_some() {
+                        return self.error("double superscript");
+                    }
+                    superscript = Some(Box::new(self.parse_argument()?));
+                }
+                Some((_, '_')) => {
+                    self.chars.next();
+                    if subscript.is_some() {
+                        return self.error("double subscript");
+                    }
+                    subscript = Some(Box::new(self.parse_argument()?));
+                }
+                _ => break,
+            }
+        }
+        Ok(MathNode::Scripts {
+            base: Box::new(base),
+            subscript,
+            superscript,
+        })
+    }
+
+    /// Parses a single argument, which is either a braced group or a single token.
+    fn parse_argument(&mut self) -> Result<MathNode, ParseError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some((_, '{')) => {
+                self.chars.next();
+                self.parse_row(Terminator::Brace)
+            }
+            Some(_) => match self.parse_atom(Terminator::EndOfInput)? {
+                Some(node) => Ok(node),
+                None => self.error("expected an argument"),
+            },
+            None => self.error("expected an argument"),
+        }
+    }
+
+    /// Parses the raw text of a braced argument, as for `\text{...}`.
+    fn parse_text_argument(&mut self) -> Result<String, ParseError> {
+        self.expect('{')?;
+        let mut text = String::new();
+        let mut depth = 0;
+        loop {
+            match self.chars.next() {
+                Some((_, '{')) => {
+                    depth += 1;
+                    text.push('{');
+                }
+                Some((_, '}')) if depth == 0 => break,
+                Some((_, '}')) => {
+                    depth -= 1;
+                    text.push('}');
+                }
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c)) if !c.is_ascii_alphabetic() => text.push(c),
+                    Some((_, c)) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    None => return self.error("unexpected end of input"),
+                },
+                Some((_, c)) => text.push(c),
+                None => return self.error("unexpected end of input"),
+            }
+        }
+        Ok(text)
+    }
+
+    fn parse_command_name(&mut self) -> String {
+        let mut name = String::new();
+        match self.chars.next_if(|(_, c)| !c.is_ascii_alphabetic()) {
+            Some((_, c)) => name.push(c),
+            None => {
+                while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_alphabetic()) {
+                    name.push(c);
+                }
+            }
+        }
+        name
+    }
+
+    fn parse_atom(&mut self, terminator: Terminator) -> Result<Option<MathNode>, ParseError> {
+        let Some((_, c)) = self.chars.next() else {
+            return Ok(None);
+        };
+        let node = match c {
+            '{' => self.parse_row(Terminator::Brace)?,
+            '\\' => {
+                if self.chars.peek().is_none() {
+                    return self.error("expected a command name");
+                }
+                let name = self.parse_command_name();
+                return self.parse_command(&name, terminator).map(Some);
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::from(c);
+                while let Some((_, c)) =
+                    self.chars.next_if(|(_, c)| c.is_ascii_digit() || *c == '.')
+                {
+                    number.push(c);
+                }
+                MathNode::Number(number)
+            }
+            c if c.is_alphabetic() => MathNode::Identifier {
+                text: c.to_string(),
+                variant: MathVariant::Italic,
+            },
+            '+' | '*' => operator(c, OperatorClass::Binary),
+            '-' => operator('−', OperatorClass::Binary),
+            '=' | '<' | '>' | ':' => operator(c, OperatorClass::Relation),
+            ',' | ';' => operator(c, OperatorClass::Punctuation),
+            '(' | ')' | '[' | ']' | '|' => operator(c, OperatorClass::Fence),
+            '&' => return self.error("unexpected '&'"),
+            '~' => MathNode::Space(0.33),
+            c => operator(c, OperatorClass::Ordinary),
+        };
+        Ok(Some(node))
+    }
+
+    fn parse_command(
+        &mut self,
+        name: &str,
+        terminator: Terminator,
+    ) -> Result<MathNode, ParseError> {
+        let node = match name {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => MathNode::Fraction {
+                numerator: Box::new(self.parse_argument()?),
+                denominator: Box::new(self.parse_argument()?),
+                bar: true,
+            },
+            "binom" | "dbinom" | "tbinom" => MathNode::Delimited {
+                open: "(".into(),
+                close: ")".into(),
+                body: Box::new(MathNode::Fraction {
+                    numerator: Box::new(self.parse_argument()?),
+                    denominator: Box::new(self.parse_argument()?),
+                    bar: false,
+                }),
+            },
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.chars.next_if(|(_, c)| *c == '[').is_some() {
+                    Some(Box::new(self.parse_row(Terminator::Bracket)?))
+                } else {
+                    None
+                };
+                MathNode::Root {
+                    index,
+                    radicand: Box::new(self.parse_argument()?),
+                }
+            }
+            "left" => {
+                let open = self.parse_delimiter()?;
+                let body = self.parse_row(Terminator::Right)?;
+                if self.source[self.offset()..].starts_with("\\right") {
+                    for _ in 0.."\\right".len() {
+                        self.chars.next();
+                    }
+                } else {
+                    return self.error("expected '\\right'");
+                }
+                let close = self.parse_delimiter()?;
+                MathNode::Delimited {
+                    open,
+                    close,
+                    body: Box::new(body),
+                }
+            }
+            "right" if terminator != Terminator::Right => {
+                return self.error("'\\right' without '\\left'");
+            }
+            "begin" => self.parse_environment()?,
+            "text" | "textrm" | "textnormal" | "mbox" => {
+                MathNode::Text(self.parse_text_argument()?)
+            }
+            "operatorname" => MathNode::Function {
+                name: self.parse_text_argument()?,
+                limits: false,
+            },
+            "mathrm" | "mathit" | "mathbf" | "boldsymbol" | "bm" | "textbf" | "textit" => {
+                let variant = match name {
+                    "mathrm" => MathVariant::Upright,
+                    "mathit" | "textit" => MathVariant::Italic,
+                    _ => MathVariant::Bold,
+                };
+                with_variant(self.parse_argument()?, variant)
+            }
+            "mathbb" | "mathcal" | "mathfrak" | "mathscr" => {
+                let text = self.parse_text_argument()?;
+                let mapped = text
+                    .chars()
+                    .map(|c| symbols::map_alphabet(name, c))
+                    .collect();
+                MathNode::Identifier {
+                    text: mapped,
+                    variant: MathVariant::Upright,
+                }
+            }
+            "hat" | "widehat" => self.parse_accent(Accent::Hat)?,
+            "tilde" | "widetilde" => self.parse_accent(Accent::Tilde)?,
+            "bar" | "overline" => self.parse_accent(Accent::Bar)?,
+            "vec" | "overrightarrow" => self.parse_accent(Accent::Vector)?,
+            "dot" => self.parse_accent(Accent::Dot)?,
+            "ddot" => self.parse_accent(Accent::DoubleDot)?,
+            "underline" => self.parse_accent(Accent::Underline)?,
+            "\\" | "displaystyle" | "textstyle" | "limits" | "nolimits" | "big" | "Big"
+            | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr" => MathNode::Row(Vec::new()),
+            _ => match symbols::lookup(name) {
+                Some(Symbol::Identifier(text)) => MathNode::Identifier {
+                    text: text.into(),
+                    variant: if text.chars().all(|c| c.is_lowercase()) {
+                        MathVariant::Italic
+                    } else {
+                        MathVariant::Upright
+                    },
+                },
+                Some(Symbol::Operator(text, class)) => MathNode::Operator {
+                    text: text.into(),
+                    class,
+                },
+                Some(Symbol::LargeOperator(text, limits)) => MathNode::LargeOperator {
+                    text: text.into(),
+                    limits,
+                },
+                Some(Symbol::Function(name, limits)) => MathNode::Function {
+                    name: name.into(),
+                    limits,
+                },
+                Some(Symbol::Space(width)) => MathNode::Space(width),
+                None => return self.error(format!("unknown command '\\{name}'")),
+            },
+        };
+        Ok(node)
+    }
+
+    fn parse_accent(&mut self, accent: Accent) -> Result<MathNode, ParseError> {
+        Ok(MathNode::Accent {
+            accent,
+            body: Box::new(self.parse_argument()?),
+        })
+    }
+
+    fn parse_delimiter(&mut self) -> Result<String, ParseError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, '.')) => Ok(String::new()),
+            Some((_, '\\')) => {
+                let name = self.parse_command_name();
+                match symbols::delimiter(&name) {
+                    Some(delimiter) => Ok(delimiter.into()),
+                    None => self.error(format!("unknown delimiter '\\{name}'")),
+                }
+            }
+            Some((_, c)) if "()[]|/<>".contains(c) => Ok(match c {
+                '<' => "⟨".into(),
+                '>' => "⟩".into(),
+                c => c.to_string(),
+            }),
+            _ => self.error("expected a delimiter"),
+        }
+    }
+
+    fn parse_environment(&mut self) -> Result<MathNode, ParseError> {
+        let name = self.parse_text_argument()?;
+        let name = name.trim_end_matches('*');
+        let (open, close, alignment) = match name {
+            "matrix" | "smallmatrix" => ("", "", MatrixAlignment::Center),
+            "pmatrix" => ("(", ")", MatrixAlignment::Center),
+            "bmatrix" => ("[", "]", MatrixAlignment::Center),
+            "Bmatrix" => ("{", "}", MatrixAlignment::Center),
+            "vmatrix" => ("|", "|", MatrixAlignment::Center),
+            "Vmatrix" => ("‖", "‖", MatrixAlignment::Center),
+            "cases" => ("{", "", MatrixAlignment::Left),
+            "aligned" | "align" | "split" | "gathered" | "eqnarray" => {
+                ("", "", MatrixAlignment::RightLeft)
+            }
+            "array" => {
+                // Column specifications aren't supported, so skip them.
+                self.parse_text_argument()?;
+                ("", "", MatrixAlignment::Center)
+            }
+            _ => return self.error(format!("unknown environment '{name}'")),
+        };
+
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        loop {
+            row.push(self.parse_row(Terminator::Environment)?);
+            self.skip_whitespace();
+            let rest = &self.source[self.offset()..];
+            if rest.starts_with('&') {
+                self.chars.next();
+            } else if rest.starts_with("\\\\") {
+                self.chars.next();
+                self.chars.next();
+                rows.push(std::mem::take(&mut row));
+            } else if rest.starts_with("\\end") {
+                for _ in 0.."\\end".len() {
+                    self.chars.next();
+                }
+                let end_name = self.parse_text_argument()?;
+                if end_name.trim_end_matches('*') != name {
+                    return self.error(format!("expected '\\end{{{name}}}'"));
+                }
+                break;
+            } else {
+                return self.error(format!("expected '\\end{{{name}}}'"));
+            }
+        }
+        // A trailing `\\` leaves an empty row behind, which isn't displayed.
+        if !(row.len() == 1 && row[0] == MathNode::Row(Vec::new())) {
+            rows.push(row);
+        }
+
+        let matrix = MathNode::Matrix { rows, alignment };
+        Ok(if open.is_empty() && close.is_empty() {
+            matrix
+        } else {
+            MathNode::Delimited {
+                open: open.into(),
+                close: close.into(),
+                body: Box::new(matrix),
+            }
+        })
+    }
+}
+
+fn operator(c: char, class: OperatorClass) -> MathNode {
+    MathNode::Operator {
+        text: c.to_string(),
+        class,
+    }
+}
+
+fn is_command_prefix(text: &str, len: usize) -> bool {
+    text[len..]
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic())
+}
+
+fn attach_prime(base: MathNode) -> MathNode {
+    let prime = MathNode::Operator {
+        text: "′".into(),
+        class: OperatorClass::Ordinary,
+    };
+    match base {
+        MathNode::Scripts {
+            base,
+            subscript,
+            superscript: Some(superscript),
+        } => MathNode::Scripts {
+            base,
+            subscript,
+            superscript: Some(Box::new(MathNode::Row(vec![*superscript, prime]))),
+        },
+        MathNode::Scripts {
+            base,
+            subscript,
+            superscript: None,
+        } => MathNode::Scripts {
+            base,
+            subscript,
+            superscript: Some(Box::new(prime)),
+        },
+        base => MathNode::Scripts {
+            base: Box::new(base),
+            subscript: None,
+            superscript: Some(Box::new(prime)),
+        },
+    }
+}
+
+fn with_variant(node: MathNode, variant: MathVariant) -> MathNode {
+    match node {
+        MathNode::Identifier { text, .. } => MathNode::Identifier { text, variant },
+        MathNode::Number(text) if variant == MathVariant::Bold => {
+            MathNode::Identifier { text, variant }
+        }
+        MathNode::Row(nodes) => MathNode::Row(
+            nodes
+                .into_iter()
+                .map(|node| with_variant(node, variant))
+                .collect(),
+        ),
+        MathNode::Scripts {
+            base,
+            subscript,
+            superscript,
+        } => MathNode::Scripts {
+            base: Box::new(with_variant(*base, variant)),
+            subscript,
+            superscript,
+        },
+        MathNode::Accent { accent, body } => MathNode::Accent {
+            accent,
+            body: Box::new(with_variant(*body, variant)),
+        },
+        node => node,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ident(text: &str) -> MathNode {
+        MathNode::Identifier {
+            text: text.into(),
+            variant: MathVariant::Italic,
+        }
+    }
+
+    #[test]
+    fn test_parse_scripts_and_fractions() {
+        assert_eq!(
+            parse("x^2 + \\frac{a}{b_1}").unwrap(),
+            MathNode::Row(vec![
+                MathNode::Scripts {
+                    base: Box::new(ident("x")),
+                    subscript: None,
+                    superscript: Some(Box::new(MathNode::Number("2".into()))),
+                },
+                MathNode::Operator {
+                    text: "+".into(),
+                    class: OperatorClass::Binary,
+                },
+                MathNode::Fraction {
+                    numerator: Box::new(ident("a")),
+                    denominator: Box::new(MathNode::Scripts {
+                        base: Box::new(ident("b")),
+                        subscript: Some(Box::new(MathNode::Number("1".into()))),
+                        superscript: None,
+                    }),
+                    bar: true,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_delimiters_and_environments() {
+        assert_eq!(
+            parse("\\left( \\alpha \\right.").unwrap(),
+            MathNode::Delimited {
+                open: "(".into(),
+                close: "".into(),
+                body: Box::new(ident("α")),
+            }
+        );
+        assert_eq!(
+            parse("\\begin{pmatrix} 1 & 0 \\\\ 0 & 1 \\\\ \\end{pmatrix}").unwrap(),
+            MathNode::Delimited {
+                open: "(".into(),
+                close: ")".into(),
+                body: Box::new(MathNode::Matrix {
+                    rows: vec![
+                        vec![MathNode::Number("1".into()), MathNode::Number("0".into())],
+                        vec![MathNode::Number("0".into()), MathNode::Number("1".into())],
+                    ],
+                    alignment: MatrixAlignment::Center,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_text_and_variants() {
+        assert_eq!(
+            parse("\\mathbb{R} \\text{ if } \\mathbf{v}").unwrap(),
+            MathNode::Row(vec![
+                MathNode::Identifier {
+                    text: "ℝ".into(),
+                    variant: MathVariant::Upright,
+                },
+                MathNode::Text(" if ".into()),
+                MathNode::Identifier {
+                    text: "v".into(),
+                    variant: MathVariant::Bold,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("\\frac{a}").unwrap_err().message,
+            "expected an argument"
+        );
+        assert_eq!(
+            parse("\\foo").unwrap_err().message,
+            "unknown command '\\foo'"
+        );
+        assert_eq!(parse("x^1^2").unwrap_err().message, "double superscript");
+        assert_eq!(parse("{x").unwrap_err().message, "unexpected end of input");
+    }
+}
@@ -0,0 +1,284 @@
+use crate::parser::OperatorClass;
+
+pub(crate) enum Symbol {
+    Identifier(&'static str),
+    Operator(&'static str, OperatorClass),
+    /// A large operator, and whether its scripts are set as limits in display style.
+    LargeOperator(&'static str, bool),
+    /// A function name set in upright text, and whether its scripts are set as limits.
+    Function(&'static str, bool),
+    /// Horizontal space, measured in ems.
+    Space(f32),
+}
+
+pub(crate) fn lookup(name: &str) -> Option<Symbol> {
+    use OperatorClass::*;
+    use Symbol::*;
+
+    Some(match name {
+        "alpha" => Identifier("α"),
+        "beta" => Identifier("β"),
+        "gamma" => Identifier("γ"),
+        "delta" => Identifier("δ"),
+        "epsilon" => Identifier("ϵ"),
+        "varepsilon" => Identifier("ε"),
+        "zeta" => Identifier("ζ"),
+        "eta" => Identifier("η"),
+        "theta" => Identifier("θ"),
+        "vartheta" => Identifier("ϑ"),
+        "iota" => Identifier("ι"),
+        "kappa" => Identifier("κ"),
+        "lambda" => Identifier("λ"),
+        "mu" => Identifier("μ"),
+        "nu" => Identifier("ν"),
+        "xi" => Identifier("ξ"),
+        "pi" => Identifier("π"),
+        "varpi" => Identifier("ϖ"),
+        "rho" => Identifier("ρ"),
+        "varrho" => Identifier("ϱ"),
+        "sigma" => Identifier("σ"),
+        "varsigma" => Identifier("ς"),
+        "tau" => Identifier("τ"),
+        "upsilon" => Identifier("υ"),
+        "phi" => Identifier("ϕ"),
+        "varphi" => Identifier("φ"),
+        "chi" => Identifier("χ"),
+        "psi" => Identifier("ψ"),
+        "omega" => Identifier("ω"),
+        "Gamma" => Identifier("Γ"),
+        "Delta" => Identifier("Δ"),
+        "Theta" => Identifier("Θ"),
+        "Lambda" => Identifier("Λ"),
+        "Xi" => Identifier("Ξ"),
+        "Pi" => Identifier("Π"),
+        "Sigma" => Identifier("Σ"),
+        "Upsilon" => Identifier("Υ"),
+        "Phi" => Identifier("Φ"),
+        "Psi" => Identifier("Ψ"),
+        "Omega" => Identifier("Ω"),
+        "ell" => Identifier("ℓ"),
+        "hbar" => Identifier("ℏ"),
+        "imath" => Identifier("ı"),
+        "jmath" => Identifier("ȷ"),
+        "aleph" => Identifier("ℵ"),
+        "Re" => Identifier("ℜ"),
+        "Im" => Identifier("ℑ"),
+        "wp" => Identifier("℘"),
+
+        "infty" => Operator("∞", Ordinary),
+        "partial" => Operator("∂", Ordinary),
+        "nabla" => Operator("∇", Ordinary),
+        "forall" => Operator("∀", Ordinary),
+        "exists" => Operator("∃", Ordinary),
+        "nexists" => Operator("∄", Ordinary),
+        "emptyset" | "varnothing" => Operator("∅", Ordinary),
+        "neg" | "lnot" => Operator("¬", Ordinary),
+        "angle" => Operator("∠", Ordinary),
+        "triangle" => Operator("△", Ordinary),
+        "prime" => Operator("′", Ordinary),
+        "degree" => Operator("°", Ordinary),
+        "dots" | "ldots" => Operator("…", Ordinary),
+        "cdots" => Operator("⋯", Ordinary),
+        "vdots" => Operator("⋮", Ordinary),
+        "ddots" => Operator("⋱", Ordinary),
+        "top" => Operator("⊤", Ordinary),
+        "bot" => Operator("⊥", Ordinary),
+        "%" => Operator("%", Ordinary),
+        "#" => Operator("#", Ordinary),
+        "$" => Operator("$", Ordinary),
+        "_" => Operator("_", Ordinary),
+        "&" => Operator("&", Ordinary),
+
+        "pm" => Operator("±", Binary),
+        "mp" => Operator("∓", Binary),
+        "times" => Operator("×", Binary),
+        "div" => Operator("÷", Binary),
+        "cdot" => Operator("⋅", Binary),
+        "ast" => Operator("∗", Binary),
+        "star" => Operator("⋆", Binary),
+        "circ" => Operator("∘", Binary),
+        "bullet" => Operator("∙", Binary),
+        "cap" => Operator("∩", Binary),
+        "cup" => Operator("∪", Binary),
+        "wedge" | "land" => Operator("∧", Binary),
+        "vee" | "lor" => Operator("∨", Binary),
+        "oplus" => Operator("⊕", Binary),
+        "ominus" => Operator("⊖", Binary),
+        "otimes" => Operator("⊗", Binary),
+        "odot" => Operator("⊙", Binary),
+        "setminus" => Operator("∖", Binary),
+
+        "leq" | "le" => Operator("≤", Relation),
+        "geq" | "ge" => Operator("≥", Relation),
+        "neq" | "ne" => Operator("≠", Relation),
+        "approx" => Operator("≈", Relation),
+        "equiv" => Operator("≡", Relation),
+        "sim" => Operator("∼", Relation),
+        "simeq" => Operator("≃", Relation),
+        "cong" => Operator("≅", Relation),
+        "propto" => Operator("∝", Relation),
+        "ll" => Operator("≪", Relation),
+        "gg" => Operator("≫", Relation),
+        "in" => Operator("∈", Relation),
+        "notin" => Operator("∉", Relation),
+        "ni" => Operator("∋", Relation),
+        "subset" => Operator("⊂", Relation),
+        "subseteq" => Operator("⊆", Relation),
+        "supset" => Operator("⊃", Relation),
+        "supseteq" => Operator("⊇", Relation),
+        "perp" => Operator("⊥", Relation),
+        "mid" => Operator("∣", Relation),
+        "parallel" => Operator("∥", Relation),
+        "to" | "rightarrow" => Operator("→", Relation),
+        "leftarrow" | "gets" => Operator("←", Relation),
+        "leftrightarrow" => Operator("↔", Relation),
+        "Rightarrow" => Operator("⇒", Relation),
+        "Leftarrow" => Operator("⇐", Relation),
+        "Leftrightarrow" => Operator("⇔", Relation),
+        "implies" => Operator("⟹", Relation),
+        "impliedby" => Operator("⟸", Relation),
+        "iff" => Operator("⟺", Relation),
+        "mapsto" => Operator("↦", Relation),
+        "longrightarrow" => Operator("⟶", Relation),
+        "longleftarrow" => Operator("⟵", Relation),
+        "uparrow" => Operator("↑", Relation),
+        "downarrow" => Operator("↓", Relation),
+        "vdash" => Operator("⊢", Relation),
+        "models" => Operator("⊨", Relation),
+        "coloneqq" => Operator("≔", Relation),
+
+        "{" | "lbrace" => Operator("{", Fence),
+        "}" | "rbrace" => Operator("}", Fence),
+        "langle" => Operator("⟨", Fence),
+        "rangle" => Operator("⟩", Fence),
+        "lfloor" => Operator("⌊", Fence),
+        "rfloor" => Operator("⌋", Fence),
+        "lceil" => Operator("⌈", Fence),
+        "rceil" => Operator("⌉", Fence),
+        "|" | "Vert" | "lVert" | "rVert" => Operator("‖", Fence),
+        "vert" | "lvert" | "rvert" => Operator("|", Fence),
+
+        "sum" => LargeOperator("∑", true),
+        "prod" => LargeOperator("∏", true),
+        "coprod" => LargeOperator("∐", true),
+        "bigcup" => LargeOperator("⋃", true),
+        "bigcap" => LargeOperator("⋂", true),
+        "bigvee" => LargeOperator("⋁", true),
+        "bigwedge" => LargeOperator("⋀", true),
+        "bigoplus" => LargeOperator("⨁", true),
+        "bigotimes" => LargeOperator("⨂", true),
+        "int" => LargeOperator("∫", false),
+        "iint" => LargeOperator("∬", false),
+        "iiint" => LargeOperator("∭", false),
+        "oint" => LargeOperator("∮", false),
+
+        "lim" => Function("lim", true),
+        "limsup" => Function("lim sup", true),
+        "liminf" => Function("lim inf", true),
+        "max" => Function("max", true),
+        "min" => Function("min", true),
+        "sup" => Function("sup", true),
+        "inf" => Function("inf", true),
+        "det" => Function("det", true),
+        "gcd" => Function("gcd", true),
+        "Pr" => Function("Pr", true),
+        "argmax" => Function("arg max", true),
+        "argmin" => Function("arg min", true),
+        "sin" => Function("sin", false),
+        "cos" => Function("cos", false),
+        "tan" => Function("tan", false),
+        "sec" => Function("sec", false),
+        "csc" => Function("csc", false),
+        "cot" => Function("cot", false),
+        "arcsin" => Function("arcsin", false),
+        "arccos" => Function("arccos", false),
+        "arctan" => Function("arctan", false),
+        "sinh" => Function("sinh", false),
+        "cosh" => Function("cosh", false),
+        "tanh" => Function("tanh", false),
+        "log" => Function("log", false),
+        "lg" => Function("lg", false),
+        "ln" => Function("ln", false),
+        "exp" => Function("exp", false),
+        "deg" => Function("deg", false),
+        "dim" => Function("dim", false),
+        "ker" => Function("ker", false),
+        "arg" => Function("arg", false),
+        "hom" => Function("hom", false),
+        "mod" | "bmod" => Function("mod", false),
+
+        "," | "thinspace" => Space(3. / 18.),
+        ":" | ">" | "medspace" => Space(4. / 18.),
+        ";" | "thickspace" => Space(5. / 18.),
+        "!" | "negthinspace" => Space(-3. / 18.),
+        " " => Space(0.33),
+        "quad" => Space(1.),
+        "qquad" => Space(2.),
+        _ => return None,
+    })
+}
+
+/// Maps the name of a delimiter command, as used after `\left` or `\right`, to its text.
+pub(crate) fn delimiter(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "|" | "Vert" | "lVert" | "rVert" => "‖",
+        "vert" | "lvert" | "rvert" => "|",
+        _ => return None,
+    })
+}
+
+/// Maps a letter to the given alphabet, e.g. `R` to `ℝ` for `\mathbb`.
+pub(crate) fn map_alphabet(command: &str, c: char) -> char {
+    let exception = match (command, c) {
+        ("mathbb", 'C') => Some('ℂ'),
+        ("mathbb", 'H') => Some('ℍ'),
+        ("mathbb", 'N') => Some('ℕ'),
+        ("mathbb", 'P') => Some('ℙ'),
+        ("mathbb", 'Q') => Some('ℚ'),
+        ("mathbb", 'R') => Some('ℝ'),
+        ("mathbb", 'Z') => Some('ℤ'),
+        ("mathcal" | "mathscr", 'B') => Some('ℬ'),
+        ("mathcal" | "mathscr", 'E') => Some('ℰ'),
+        ("mathcal" | "mathscr", 'F') => Some('ℱ'),
+        ("mathcal" | "mathscr", 'H') => Some('ℋ'),
+        ("mathcal" | "mathscr", 'I') => Some('ℐ'),
+        ("mathcal" | "mathscr", 'L') => Some('ℒ'),
+        ("mathcal" | "mathscr", 'M') => Some('ℳ'),
+        ("mathcal" | "mathscr", 'R') => Some('ℛ'),
+        ("mathcal" | "mathscr", 'e') => Some('ℯ'),
+        ("mathcal" | "mathscr", 'g') => Some('ℊ'),
+        ("mathcal" | "mathscr", 'o') => Some('ℴ'),
+        ("mathfrak", 'C') => Some('ℭ'),
+        ("mathfrak", 'H') => Some('ℌ'),
+        ("mathfrak", 'I') => Some('ℑ'),
+        ("mathfrak", 'R') => Some('ℜ'),
+        ("mathfrak", 'Z') => Some('ℨ'),
+        _ => None,
+    };
+    if let Some(exception) = exception {
+        return exception;
+    }
+
+    let (upper, lower) = match command {
+        "mathbb" => (0x1D538, 0x1D552),
+        "mathcal" | "mathscr" => (0x1D49C, 0x1D4B6),
+        "mathfrak" => (0x1D504, 0x1D51E),
+        _ => return c,
+    };
+    let code_point = if c.is_ascii_uppercase() {
+        upper + (c as u32 - 'A' as u32)
+    } else if c.is_ascii_lowercase() {
+        lower + (c as u32 - 'a' as u32)
+    } else {
+        return c;
+    };
+    char::from_u32(code_point).unwrap_or(c)
+}
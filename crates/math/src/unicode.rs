@@ -0,0 +1,253 @@
+use crate::parser::{Accent, MathNode, OperatorClass};
+
+/// Converts a parsed math expression to a single line of plain text, using
+/// Unicode superscripts, subscripts and combining accents where possible.
+///
+/// This is used for inline math that is set within a line of text, such as
+/// `$x^2$`, which becomes `x²`.
+pub fn to_unicode(node: &MathNode) -> String {
+    let mut text = String::new();
+    write_node(node, &mut text);
+    text.trim().to_string()
+}
+
+fn write_node(node: &MathNode, text: &mut String) {
+    match node {
+        MathNode::Identifier { text: name, .. }
+        | MathNode::Number(name)
+        | MathNode::Text(name)
+        | MathNode::LargeOperator { text: name, .. } => text.push_str(name),
+        MathNode::Function { name, .. } => {
+            text.push_str(name);
+            text.push(' ');
+        }
+        MathNode::Operator {
+            text: operator,
+            class,
+        } => match class {
+            OperatorClass::Binary | OperatorClass::Relation => {
+                if !text.is_empty() && !text.ends_with(' ') {
+                    text.push(' ');
+                }
+                text.push_str(operator);
+                text.push(' ');
+            }
+            OperatorClass::Punctuation => {
+                text.push_str(operator);
+                text.push(' ');
+            }
+            OperatorClass::Fence | OperatorClass::Ordinary => text.push_str(operator),
+        },
+        MathNode::Row(nodes) => {
+            for (ix, node) in nodes.iter().enumerate() {
+                // A leading minus sign stays next to its operand.
+                if ix == 0
+                    && let MathNode::Operator {
+                        text: operator,
+                        class: OperatorClass::Binary,
+                    } = node
+                {
+                    text.push_str(operator);
+                    continue;
+                }
+                write_node(node, text);
+            }
+        }
+        MathNode::Fraction {
+            numerator,
+            denominator,
+            bar,
+        } => {
+            write_grouped(numerator, text);
+            text.push_str(if *bar { "/" } else { " " });
+            write_grouped(denominator, text);
+        }
+        MathNode::Root { index, radicand } => {
+            match index.as_deref().map(to_unicode).as_deref() {
+                None => text.push('√'),
+                Some("3") => text.push('∛'),
+                Some("4") => text.push('∜'),
+                Some(index) => {
+                    text.push_str(
+                        &to_script(index, superscript_char)
+                            .unwrap_or_else(|| format!("^({index})")),
+                    );
+                    text.push('√');
+                }
+            }
+            write_grouped(radicand, text);
+        }
+        MathNode::Scripts {
+            base,
+            subscript,
+            superscript,
+        } => {
+            write_node(base, text);
+            if let Some(subscript) = subscript {
+                let subscript = to_unicode(subscript);
+                match to_script(&subscript, subscript_char) {
+                    Some(script) => text.push_str(&script),
+                    None if subscript.chars().count() == 1 => {
+                        text.push('_');
+                        text.push_str(&subscript);
+                    }
+                    None => text.push_str(&format!("_({subscript})")),
+                }
+            }
+            if let Some(superscript) = superscript {
+                let superscript = to_unicode(superscript);
+                match to_script(&superscript, superscript_char) {
+                    Some(script) => text.push_str(&script),
+                    None if superscript.chars().count() == 1 => {
+                        text.push('^');
+                        text.push_str(&superscript);
+                    }
+                    None => text.push_str(&format!("^({superscript})")),
+                }
+            }
+        }
+        MathNode::Delimited { open, close, body } => {
+            text.push_str(open);
+            write_node(body, text);
+            text.push_str(close);
+        }
+        MathNode::Matrix { rows, .. } => {
+            let rows = rows
+                .iter()
+                .map(|row| row.iter().map(to_unicode).collect::<Vec<_>>().join(" "))
+                .collect::<Vec<_>>();
+            text.push_str(&rows.join("; "));
+        }
+        MathNode::Accent { accent, body } => {
+            let body = to_unicode(body);
+            let combining = match accent {
+                Accent::Hat => '\u{302}',
+                Accent::Tilde => '\u{303}',
+                Accent::Bar => '\u{305}',
+                Accent::Vector => '\u{20d7}',
+                Accent::Dot => '\u{307}',
+                Accent::DoubleDot => '\u{308}',
+                Accent::Underline => '\u{332}',
+            };
+            for c in body.chars() {
+                text.push(c);
+                text.push(combining);
+            }
+        }
+        MathNode::Space(ems) => {
+            if *ems > 0. && !text.ends_with(' ') {
+                text.push(' ');
+            }
+        }
+    }
+}
+
+/// Writes a node, wrapping it in parentheses unless it's a single symbol.
+fn write_grouped(node: &MathNode, text: &mut String) {
+    let inner = to_unicode(node);
+    let is_atomic = matches!(
+        node,
+        MathNode::Identifier { .. }
+            | MathNode::Number(_)
+            | MathNode::Delimited { .. }
+            | MathNode::Scripts { .. }
+    ) || inner.chars().count() == 1;
+    if is_atomic {
+        text.push_str(&inner);
+    } else {
+        text.push('(');
+        text.push_str(&inner);
+        text.push(')');
+    }
+}
+
+fn to_script(text: &str, map: fn(char) -> Option<char>) -> Option<String> {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(map)
+        .collect()
+}
+
+fn superscript_char(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '−' | '-' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'i' => 'ⁱ',
+        'n' => 'ⁿ',
+        'T' => 'ᵀ',
+        '′' => '′',
+        '∗' | '*' => '*',
+        _ => return None,
+    })
+}
+
+fn subscript_char(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '−' | '-' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn unicode(source: &str) -> String {
+        to_unicode(&parse(source).unwrap())
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        assert_eq!(unicode("x^2 + y_i^2 = r^2"), "x² + yᵢ² = r²");
+        assert_eq!(unicode("-\\frac{a+b}{2}"), "−(a + b)/2");
+        assert_eq!(unicode("\\sqrt{x} \\leq \\sqrt[3]{y}"), "√x ≤ ∛y");
+        assert_eq!(unicode("e^{i\\pi} + 1 = 0"), "e^(iπ) + 1 = 0");
+        assert_eq!(unicode("\\vec{v} \\in \\mathbb{R}^n"), "v⃗ ∈ ℝⁿ");
+        assert_eq!(unicode("\\sin x"), "sin x");
+    }
+}
@@ -0,0 +1,497 @@
+use anyhow::{Result, anyhow, bail};
+
+use crate::{
+    Theme,
+    graph::{Direction, clip_to_rect, layout_graph, midpoint},
+    svg::{Anchor, LineStyle, Marker, PADDING_X, PADDING_Y, SvgBuilder, line_height, text_width},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClassDiagram {
+    pub direction: Direction,
+    pub classes: Vec<Class>,
+    pub relations: Vec<Relation>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Class {
+    pub name: String,
+    /// An annotation such as `interface`, written as `<<interface>>`.
+    pub annotation: Option<String>,
+    pub attributes: Vec<String>,
+    pub methods: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relation {
+    pub from: usize,
+    pub to: usize,
+    pub from_end: RelationEnd,
+    pub to_end: RelationEnd,
+    pub line: RelationLine,
+    pub label: Option<String>,
+    pub from_cardinality: Option<String>,
+    pub to_cardinality: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelationEnd {
+    None,
+    /// `<|` or `|>`, for inheritance and realization.
+    Inheritance,
+    /// `*`
+    Composition,
+    /// `o`
+    Aggregation,
+    /// `<` or `>`, for associations and dependencies.
+    Arrow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelationLine {
+    Solid,
+    Dashed,
+}
+
+impl ClassDiagram {
+    pub(crate) fn parse(lines: &[&str]) -> Result<Self> {
+        let mut diagram = ClassDiagram::default();
+        let mut open_class = None;
+        for line in lines {
+            let line = *line;
+            if let Some(class) = open_class {
+                if line == "}" {
+                    open_class = None;
+                } else {
+                    diagram.add_member(class, line);
+                }
+                continue;
+            }
+
+            if let Some(direction) = line.strip_prefix("direction ") {
+                diagram.direction = Direction::parse(direction.trim())
+                    .ok_or_else(|| anyhow!("invalid direction '{direction}'"))?;
+            } else if let Some(rest) = line.strip_prefix("class ") {
+                let rest = rest.trim();
+                let (name, opens_block) = match rest.strip_suffix('{') {
+                    Some(name) => (name.trim(), true),
+                    None => (rest, false),
+                };
+                let class = diagram.class(name);
+                if opens_block {
+                    open_class = Some(class);
+                }
+            } else if let Some(rest) = line.strip_prefix("<<") {
+                let (annotation, name) = rest
+                    .split_once(">>")
+                    .ok_or_else(|| anyhow!("unterminated annotation in '{line}'"))?;
+                let class = diagram.class(name);
+                diagram.classes[class].annotation = Some(annotation.trim().to_string());
+            } else if let Some(relation) = diagram.parse_relation(line)? {
+                diagram.relations.push(relation);
+            } else if let Some((name, member)) = line.split_once(':') {
+                let class = diagram.class(name);
+                diagram.add_member(class, member);
+            } else if !line.starts_with("note") && !line.starts_with("style") {
+                bail!("unrecognized statement '{line}'");
+            }
+        }
+        if open_class.is_some() {
+            bail!("class body is missing a '}}'");
+        }
+        Ok(diagram)
+    }
+
+    fn class(&mut self, name: &str) -> usize {
+        let name = name.trim();
+        let name = name.split(":::").next().unwrap_or(name);
+        // Generic types are written as `List~int~`.
+        let name = match name.split_once('~') {
+            Some((base, generic)) => format!("{base}<{}>", generic.trim_end_matches('~')),
+            None => name.to_string(),
+        };
+        if let Some(index) = self.classes.iter().position(|class| class.name == name) {
+            return index;
+        }
+        self.classes.push(Class {
+            name,
+            ..Default::default()
+        });
+        self.classes.len() - 1
+    }
+
+    fn add_member(&mut self, class: usize, member: &str) {
+        let member = member.trim().replace('~', "<");
+        let class = &mut self.classes[class];
+        if let Some(annotation) = member
+            .strip_prefix("<<")
+            .and_then(|member| member.strip_suffix(">>"))
+        {
+            class.annotation = Some(annotation.to_string());
+        } else if member.contains('(') {
+            class.methods.push(member);
+        } else if !member.is_empty() {
+            class.attributes.push(member);
+        }
+    }
+
+    /// Parses a relationship such as `Animal <|-- Duck` or
+    /// `Customer "1" --> "*" Ticket : buys`.
+    fn parse_relation(&mut self, line: &str) -> Result<Option<Relation>> {
+        let (statement, label) = match line.split_once(':') {
+            Some((statement, label)) => (statement, Some(label.trim().to_string())),
+            None => (line, None),
+        };
+        let Some(line_ix) = [statement.find("--"), statement.find("..")]
+            .into_iter()
+            .flatten()
+            .min()
+        else {
+            return Ok(None);
+        };
+        let line_style = if statement[line_ix..].starts_with("..") {
+            RelationLine::Dashed
+        } else {
+            RelationLine::Solid
+        };
+        let line_end = line_ix
+            + statement[line_ix..]
+                .find(|c| c != '-' && c != '.')
+                .unwrap_or(statement.len() - line_ix);
+
+        let before = &statement[..line_ix];
+        let (before, from_end) = if let Some(before) = before.strip_suffix("<|") {
+            (before, RelationEnd::Inheritance)
+        } else if let Some(before) = before.strip_suffix('*') {
+            (before, RelationEnd::Composition)
+        } else if let Some(before) = before
+            .strip_suffix('o')
+            .filter(|before| before.ends_with([' ', '"']))
+        {
+            (before, RelationEnd::Aggregation)
+        } else if let Some(before) = before.strip_suffix('<') {
+            (before, RelationEnd::Arrow)
+        } else {
+            (before, RelationEnd::None)
+        };
+
+        let after = &statement[line_end..];
+        let (after, to_end) = if let Some(after) = after.strip_prefix("|>") {
+            (after, RelationEnd::Inheritance)
+        } else if let Some(after) = after.strip_prefix('*') {
+            (after, RelationEnd::Composition)
+        } else if let Some(after) = after
+            .strip_prefix('o')
+            .filter(|after| after.starts_with([' ', '"']))
+        {
+            (after, RelationEnd::Aggregation)
+        } else if let Some(after) = after.strip_prefix('>') {
+            (after, RelationEnd::Arrow)
+        } else {
+            (after, RelationEnd::None)
+        };
+
+        let (from, from_cardinality) = split_cardinality(before.trim(), false);
+        let (to, to_cardinality) = split_cardinality(after.trim(), true);
+        if from.is_empty() || to.is_empty() {
+            bail!("expected classes on both sides of '{line}'");
+        }
+        Ok(Some(Relation {
+            from: self.class(from),
+            to: self.class(to),
+            from_end,
+            to_end,
+            line: line_style,
+            label: label.filter(|label| !label.is_empty()),
+            from_cardinality,
+            to_cardinality,
+        }))
+    }
+
+    pub(crate) fn to_svg(&self, theme: &Theme) -> String {
+        let font_size = theme.font_size;
+        let line_height = line_height(font_size);
+        let header_lines = |class: &Class| 1 + class.annotation.is_some() as usize;
+        let section_height = |lines: usize| lines as f32 * line_height + PADDING_Y;
+        let sizes = self
+            .classes
+            .iter()
+            .map(|class| {
+                let width = class
+                    .attributes
+                    .iter()
+                    .chain(&class.methods)
+                    .chain([&class.name])
+                    .chain(&class.annotation)
+                    .map(|text| text_width(text, font_size))
+                    .fold(0., f32::max)
+                    + PADDING_X * 2.;
+                let height = section_height(header_lines(class))
+                    + section_height(class.attributes.len())
+                    + section_height(class.methods.len());
+                (width, height)
+            })
+            .collect::<Vec<_>>();
+
+        // Containers and parents are placed above the classes that refer to them.
+        let is_parent_end = |end: RelationEnd| {
+            matches!(
+                end,
+                RelationEnd::Inheritance | RelationEnd::Composition | RelationEnd::Aggregation
+            )
+        };
+        let edges = self
+            .relations
+            .iter()
+            .map(|relation| {
+                if is_parent_end(relation.to_end) && !is_parent_end(relation.from_end) {
+                    (relation.to, relation.from)
+                } else {
+                    (relation.from, relation.to)
+                }
+            })
+            .collect::<Vec<_>>();
+        let label_extent = self
+            .relations
+            .iter()
+            .filter_map(|relation| relation.label.as_deref())
+            .map(|label| match self.direction {
+                Direction::LeftRight | Direction::RightLeft => text_width(label, font_size),
+                Direction::TopDown | Direction::BottomUp => line_height,
+            })
+            .fold(0., f32::max);
+        let layout = layout_graph(&sizes, &edges, self.direction, 50. + label_extent, 40.);
+
+        let mut svg = SvgBuilder::new(theme);
+        for ((relation, edge), route) in self.relations.iter().zip(&edges).zip(&layout.edges) {
+            let mut points = route.clone();
+            if edge.0 != relation.from {
+                points.reverse();
+            }
+            if relation.from != relation.to && points.len() >= 2 {
+                points[0] = clip_to_rect(layout.nodes[relation.from], points[1]);
+                let last = points.len() - 1;
+                points[last] = clip_to_rect(layout.nodes[relation.to], points[last - 1]);
+            }
+            let style = match relation.line {
+                RelationLine::Solid => LineStyle::Solid,
+                RelationLine::Dashed => LineStyle::Dashed,
+            };
+            svg.polyline(&points, &theme.line, style);
+            if let [start, after, ..] = points.as_slice() {
+                svg.marker(marker(relation.from_end), *after, *start);
+                if let Some(cardinality) = &relation.from_cardinality {
+                    draw_cardinality(&mut svg, *start, *after, cardinality);
+                }
+            }
+            if let [.., before, end] = points.as_slice() {
+                svg.marker(marker(relation.to_end), *before, *end);
+                if let Some(cardinality) = &relation.to_cardinality {
+                    draw_cardinality(&mut svg, *end, *before, cardinality);
+                }
+            }
+            if let Some(label) = &relation.label {
+                let (x, y) = midpoint(&points);
+                svg.edge_label(x, y, std::slice::from_ref(label));
+            }
+        }
+
+        for (class, rect) in self.classes.iter().zip(&layout.nodes) {
+            svg.rect(
+                *rect,
+                0.,
+                &theme.node_fill,
+                &theme.node_border,
+                LineStyle::Solid,
+            );
+            let center_x = rect.center().0;
+            let mut y = rect.y + PADDING_Y / 2.;
+            if let Some(annotation) = &class.annotation {
+                svg.text(
+                    center_x,
+                    y + line_height / 2.,
+                    &format!("«{annotation}»"),
+                    Anchor::Middle,
+                    false,
+                );
+                y += line_height;
+            }
+            svg.text(
+                center_x,
+                y + line_height / 2.,
+                &class.name,
+                Anchor::Middle,
+                true,
+            );
+            y += line_height + PADDING_Y / 2.;
+
+            for members in [&class.attributes, &class.methods] {
+                svg.line(
+                    (rect.x, y),
+                    (rect.right(), y),
+                    &theme.node_border,
+                    LineStyle::Solid,
+                );
+                y += PADDING_Y / 2.;
+                for member in members {
+                    svg.text(
+                        rect.x + PADDING_X,
+                        y + line_height / 2.,
+                        member,
+                        Anchor::Start,
+                        false,
+                    );
+                    y += line_height;
+                }
+                y += PADDING_Y / 2.;
+            }
+        }
+        svg.finish(layout.width, layout.height)
+    }
+}
+
+/// Splits a quoted cardinality, such as `"1..*"`, from a class name.
+fn split_cardinality(text: &str, leading: bool) -> (&str, Option<String>) {
+    let quoted = if leading {
+        text.strip_prefix('"').and_then(|rest| {
+            let end = rest.find('"')?;
+            Some((rest[end + 1..].trim(), &rest[..end]))
+        })
+    } else {
+        text.strip_suffix('"').and_then(|rest| {
+            let start = rest.rfind('"')?;
+            Some((rest[..start].trim(), &rest[start + 1..]))
+        })
+    };
+    match quoted {
+        Some((name, cardinality)) => (name, Some(cardinality.to_string())),
+        None => (text, None),
+    }
+}
+
+fn marker(end: RelationEnd) -> Marker {
+    match end {
+        RelationEnd::None => Marker::None,
+        RelationEnd::Inheritance => Marker::Triangle,
+        RelationEnd::Composition => Marker::FilledDiamond,
+        RelationEnd::Aggregation => Marker::Diamond,
+        RelationEnd::Arrow => Marker::Arrow,
+    }
+}
+
+/// Draws a cardinality beside the end of a relation, clear of its marker.
+fn draw_cardinality(svg: &mut SvgBuilder, end: (f32, f32), toward: (f32, f32), text: &str) {
+    let (dx, dy) = (toward.0 - end.0, toward.1 - end.1);
+    let length = (dx * dx + dy * dy).sqrt().max(1.);
+    let (ux, uy) = (dx / length, dy / length);
+    let x = end.0 + ux * 24. - uy * 12.;
+    let y = end.1 + uy * 24. + ux * 12.;
+    svg.text(x, y, text, Anchor::Middle, false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Diagram;
+
+    fn parse(source: &str) -> ClassDiagram {
+        match Diagram::parse(source).unwrap() {
+            Diagram::Class(diagram) => diagram,
+            diagram => panic!("expected a class diagram, got {diagram:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_classes() {
+        let diagram = parse(
+            "classDiagram
+                class Animal {
+                    <<abstract>>
+                    +int age
+                    +isMammal() bool
+                }
+                Animal : +String gender
+                class List~T~
+                <<interface>> Shape",
+        );
+        assert_eq!(
+            diagram.classes,
+            [
+                Class {
+                    name: "Animal".into(),
+                    annotation: Some("abstract".into()),
+                    attributes: vec!["+int age".into(), "+String gender".into()],
+                    methods: vec!["+isMammal() bool".into()],
+                },
+                Class {
+                    name: "List<T>".into(),
+                    ..Default::default()
+                },
+                Class {
+                    name: "Shape".into(),
+                    annotation: Some("interface".into()),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_relations() {
+        let diagram = parse(
+            "classDiagram
+                Animal <|-- Duck
+                Car *-- Wheel
+                Pond o-- Duck
+                Customer \"1\" --> \"*\" Ticket : buys
+                Duck ..|> Swimmer
+                Duck .. Pond",
+        );
+        let relations = diagram
+            .relations
+            .iter()
+            .map(|relation| {
+                (
+                    diagram.classes[relation.from].name.as_str(),
+                    diagram.classes[relation.to].name.as_str(),
+                    relation.from_end,
+                    relation.to_end,
+                    relation.line,
+                )
+            })
+            .collect::<Vec<_>>();
+        use RelationEnd::*;
+        use RelationLine::*;
+        assert_eq!(
+            relations,
+            [
+                ("Animal", "Duck", Inheritance, None, Solid),
+                ("Car", "Wheel", Composition, None, Solid),
+                ("Pond", "Duck", Aggregation, None, Solid),
+                ("Customer", "Ticket", None, Arrow, Solid),
+                ("Duck", "Swimmer", None, Inheritance, Dashed),
+                ("Duck", "Pond", None, None, Dashed),
+            ]
+        );
+        let buys = &diagram.relations[3];
+        assert_eq!(buys.label.as_deref(), Some("buys"));
+        assert_eq!(buys.from_cardinality.as_deref(), Some("1"));
+        assert_eq!(buys.to_cardinality.as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Diagram::parse("classDiagram\n  class Animal {\n  +int age").is_err());
+        assert!(Diagram::parse("classDiagram\n  Animal <|--").is_err());
+        assert!(Diagram::parse("classDiagram\n  what is this").is_err());
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = parse("classDiagram\n  class Shape {\n  <<interface>>\n  +area() float\n  }\n  Shape <|.. Circle")
+            .to_svg(&Theme::default());
+        assert!(svg.contains(">«interface»</text>"));
+        assert!(svg.contains(r#"font-weight="bold">Shape</text>"#));
+        assert!(svg.contains(">+area() float</text>"));
+    }
+}
@@ -0,0 +1,662 @@
+use anyhow::{Result, anyhow, bail};
+use collections::HashMap;
+
+use crate::{
+    Theme,
+    graph::{Direction, clip_to_diamond, clip_to_ellipse, clip_to_rect, layout_graph, midpoint},
+    label_lines,
+    svg::{LineStyle, Marker, PADDING_X, PADDING_Y, Rect, SvgBuilder, label_size},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Flowchart {
+    pub direction: Direction,
+    pub nodes: Vec<FlowNode>,
+    pub edges: Vec<FlowEdge>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowNode {
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NodeShape {
+    #[default]
+    Rectangle,
+    Rounded,
+    Stadium,
+    Subroutine,
+    Cylinder,
+    Circle,
+    Asymmetric,
+    Diamond,
+    Hexagon,
+    Parallelogram,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: Option<String>,
+    pub stroke: EdgeStroke,
+    pub head: EdgeHead,
+    pub tail: EdgeHead,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EdgeStroke {
+    #[default]
+    Solid,
+    Dotted,
+    Thick,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EdgeHead {
+    #[default]
+    None,
+    Arrow,
+    Circle,
+    Cross,
+}
+
+/// The opening and closing delimiters of each node shape, longest first so
+/// that `((` is matched before `(`.
+const SHAPE_DELIMITERS: &[(&str, &str, NodeShape)] = &[
+    ("(((", ")))", NodeShape::Circle),
+    ("((", "))", NodeShape::Circle),
+    ("([", "])", NodeShape::Stadium),
+    ("[[", "]]", NodeShape::Subroutine),
+    ("[(", ")]", NodeShape::Cylinder),
+    ("{{", "}}", NodeShape::Hexagon),
+    ("[/", "/]", NodeShape::Parallelogram),
+    ("[\\", "\\]", NodeShape::Parallelogram),
+    ("[", "]", NodeShape::Rectangle),
+    ("(", ")", NodeShape::Rounded),
+    ("{", "}", NodeShape::Diamond),
+    (">", "]", NodeShape::Asymmetric),
+];
+
+/// Statements that only affect styling or interactivity, which are ignored.
+const IGNORED_KEYWORDS: &[&str] = &[
+    "classDef",
+    "class",
+    "style",
+    "linkStyle",
+    "click",
+    "subgraph",
+    "end",
+    "direction",
+];
+
+struct EdgeSpec {
+    label: Option<String>,
+    stroke: EdgeStroke,
+    head: EdgeHead,
+    tail: EdgeHead,
+}
+
+impl Flowchart {
+    pub(crate) fn parse(direction: &str, lines: &[&str]) -> Result<Self> {
+        let mut flowchart = Flowchart {
+            direction: if direction.is_empty() {
+                Direction::default()
+            } else {
+                Direction::parse(direction)
+                    .ok_or_else(|| anyhow!("invalid flowchart direction '{direction}'"))?
+            },
+            ..Default::default()
+        };
+        let mut ids = HashMap::default();
+        for line in lines {
+            for statement in split_statements(line) {
+                let keyword = statement.split_whitespace().next().unwrap_or_default();
+                if IGNORED_KEYWORDS.contains(&keyword) {
+                    continue;
+                }
+                StatementParser {
+                    text: statement,
+                    position: 0,
+                    flowchart: &mut flowchart,
+                    ids: &mut ids,
+                }
+                .parse()?;
+            }
+        }
+        Ok(flowchart)
+    }
+
+    pub(crate) fn to_svg(&self, theme: &Theme) -> String {
+        let font_size = theme.font_size;
+        let labels = self
+            .nodes
+            .iter()
+            .map(|node| label_lines(&node.label))
+            .collect::<Vec<_>>();
+        let sizes = self
+            .nodes
+            .iter()
+            .zip(&labels)
+            .map(|(node, lines)| node_size(node.shape, label_size(lines, font_size)))
+            .collect::<Vec<_>>();
+        let edge_labels = self
+            .edges
+            .iter()
+            .map(|edge| edge.label.as_deref().map(label_lines))
+            .collect::<Vec<_>>();
+        let label_extent = edge_labels
+            .iter()
+            .flatten()
+            .map(|lines| {
+                let (width, height) = label_size(lines, font_size);
+                match self.direction {
+                    Direction::LeftRight | Direction::RightLeft => width,
+                    Direction::TopDown | Direction::BottomUp => height,
+                }
+            })
+            .fold(0., f32::max);
+        let layout = layout_graph(
+            &sizes,
+            &self
+                .edges
+                .iter()
+                .map(|edge| (edge.from, edge.to))
+                .collect::<Vec<_>>(),
+            self.direction,
+            40. + label_extent,
+            30.,
+        );
+
+        let mut svg = SvgBuilder::new(theme);
+        for ((edge, route), label) in self.edges.iter().zip(&layout.edges).zip(&edge_labels) {
+            let mut points = route.clone();
+            if edge.from != edge.to && points.len() >= 2 {
+                let from = &self.nodes[edge.from];
+                let to = &self.nodes[edge.to];
+                points[0] = clip_to_shape(from.shape, layout.nodes[edge.from], points[1]);
+                let last = points.len() - 1;
+                points[last] = clip_to_shape(to.shape, layout.nodes[edge.to], points[last - 1]);
+            }
+            let style = match edge.stroke {
+                EdgeStroke::Solid => LineStyle::Solid,
+                EdgeStroke::Dotted => LineStyle::Dashed,
+                EdgeStroke::Thick => LineStyle::Thick,
+            };
+            let line = theme.line.clone();
+            svg.polyline(&points, &line, style);
+            if let [.., before, end] = points.as_slice() {
+                svg.marker(marker(edge.head), *before, *end);
+            }
+            if let [start, after, ..] = points.as_slice() {
+                svg.marker(marker(edge.tail), *after, *start);
+            }
+            if let Some(label) = label {
+                let (x, y) = midpoint(&points);
+                svg.edge_label(x, y, label);
+            }
+        }
+        for ((node, rect), lines) in self.nodes.iter().zip(&layout.nodes).zip(&labels) {
+            draw_node(&mut svg, node.shape, *rect);
+            let (x, y) = rect.center();
+            svg.label(x, y, lines);
+        }
+        svg.finish(layout.width, layout.height)
+    }
+}
+
+fn marker(head: EdgeHead) -> Marker {
+    match head {
+        EdgeHead::None => Marker::None,
+        EdgeHead::Arrow => Marker::Arrow,
+        EdgeHead::Circle => Marker::Circle,
+        EdgeHead::Cross => Marker::Cross,
+    }
+}
+
+/// Splits a line into `;`-separated statements, ignoring semicolons in quotes.
+fn split_statements(line: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (ix, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                statements.push(line[start..ix].trim());
+                start = ix + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(line[start..].trim());
+    statements.retain(|statement| !statement.is_empty());
+    statements
+}
+
+/// Parses a statement such as `A[Start] -->|yes| B & C --> D`.
+struct StatementParser<'a> {
+    text: &'a str,
+    position: usize,
+    flowchart: &'a mut Flowchart,
+    ids: &'a mut HashMap<String, usize>,
+}
+
+impl<'a> StatementParser<'a> {
+    fn parse(&mut self) -> Result<()> {
+        let mut previous: Option<(Vec<usize>, EdgeSpec)> = None;
+        loop {
+            let nodes = self.parse_node_group()?;
+            if let Some((sources, edge)) = previous.take() {
+                for &from in &sources {
+                    for &to in &nodes {
+                        self.flowchart.edges.push(FlowEdge {
+                            from,
+                            to,
+                            label: edge.label.clone(),
+                            stroke: edge.stroke,
+                            head: edge.head,
+                            tail: edge.tail,
+                        });
+                    }
+                }
+            }
+            self.skip_whitespace();
+            if self.rest().is_empty() {
+                return Ok(());
+            }
+            let Some(edge) = self.parse_edge()? else {
+                bail!("unexpected '{}' in '{}'", self.rest(), self.text);
+            };
+            previous = Some((nodes, edge));
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn parse_node_group(&mut self) -> Result<Vec<usize>> {
+        let mut nodes = vec![self.parse_node()?];
+        loop {
+            self.skip_whitespace();
+            if !self.rest().starts_with('&') {
+                return Ok(nodes);
+            }
+            self.position += 1;
+            nodes.push(self.parse_node()?);
+        }
+    }
+
+    fn parse_node(&mut self) -> Result<usize> {
+        self.skip_whitespace();
+        let id_len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest().len());
+        if id_len == 0 {
+            bail!("expected a node in '{}'", self.text);
+        }
+        let id = self.rest()[..id_len].to_string();
+        self.position += id_len;
+
+        let mut shape = None;
+        for (open, close, node_shape) in SHAPE_DELIMITERS {
+            if !self.rest().starts_with(open) {
+                continue;
+            }
+            self.position += open.len();
+            let rest = self.rest();
+            let (label, len) = if let Some(quoted) = rest.trim_start().strip_prefix('"') {
+                let end = quoted
+                    .find('"')
+                    .ok_or_else(|| anyhow!("unterminated string in '{}'", self.text))?;
+                let after = &quoted[end + 1..];
+                if !after.trim_start().starts_with(close) {
+                    bail!("expected '{close}' in '{}'", self.text);
+                }
+                let len = rest.len() - after.trim_start().len() + close.len();
+                (quoted[..end].to_string(), len)
+            } else {
+                let end = rest
+                    .find(close)
+                    .ok_or_else(|| anyhow!("expected '{close}' in '{}'", self.text))?;
+                (rest[..end].trim().to_string(), end + close.len())
+            };
+            self.position += len;
+            shape = Some((label, *node_shape));
+            break;
+        }
+        if let Some(class) = self.rest().strip_prefix(":::") {
+            let class_len = class
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(class.len());
+            self.position += 3 + class_len;
+        }
+
+        let index = *self.ids.entry(id.clone()).or_insert_with(|| {
+            self.flowchart.nodes.push(FlowNode {
+                label: id.clone(),
+                id,
+                shape: NodeShape::default(),
+            });
+            self.flowchart.nodes.len() - 1
+        });
+        if let Some((label, shape)) = shape {
+            let node = &mut self.flowchart.nodes[index];
+            node.label = label;
+            node.shape = shape;
+        }
+        Ok(index)
+    }
+
+    /// Parses an edge such as `-->`, `-.->`, `==>`, `--o`, `<-->`, with an
+    /// optional label written as `-->|label|` or `-- label -->`.
+    fn parse_edge(&mut self) -> Result<Option<EdgeSpec>> {
+        let start = self.position;
+        let tail = if self.rest().starts_with('<') {
+            self.position += 1;
+            EdgeHead::Arrow
+        } else {
+            EdgeHead::None
+        };
+        let Some((run, head)) = self.parse_edge_run() else {
+            self.position = start;
+            return Ok(None);
+        };
+        let stroke = edge_stroke(run);
+
+        let mut label = None;
+        let mut head = head;
+        if head == EdgeHead::None
+            && matches!(run, "--" | "==" | "-.")
+            && self.rest().starts_with(char::is_whitespace)
+        {
+            // An inline label, terminated by the rest of the edge.
+            let closers: &[&str] = match run {
+                "--" => &["-->", "---", "--o", "--x"],
+                "==" => &["==>", "==="],
+                _ => &[".->", ".-"],
+            };
+            let rest = self.rest();
+            let end = closers
+                .iter()
+                .filter_map(|closer| rest.find(closer))
+                .min()
+                .ok_or_else(|| anyhow!("unterminated edge label in '{}'", self.text))?;
+            label = Some(rest[..end].trim().to_string());
+            self.position += end;
+            let (_, closing_head) = self
+                .parse_edge_run()
+                .ok_or_else(|| anyhow!("invalid edge in '{}'", self.text))?;
+            head = closing_head;
+        }
+
+        self.skip_whitespace();
+        if let Some(rest) = self.rest().strip_prefix('|') {
+            let end = rest
+                .find('|')
+                .ok_or_else(|| anyhow!("unterminated edge label in '{}'", self.text))?;
+            label = Some(rest[..end].trim().trim_matches('"').to_string());
+            self.position += end + 2;
+        }
+
+        Ok(Some(EdgeSpec {
+            label,
+            stroke,
+            head,
+            tail,
+        }))
+    }
+
+    fn parse_edge_run(&mut self) -> Option<(&'a str, EdgeHead)> {
+        let text = self.text;
+        let rest = &text[self.position..];
+        let run_len = rest
+            .find(|c| !matches!(c, '-' | '=' | '.'))
+            .unwrap_or(rest.len());
+        let run = &rest[..run_len];
+        if run_len < 2 && !run.contains('.') {
+            return None;
+        }
+        let mut after = rest[run_len..].chars();
+        // `o` and `x` only end an edge when they aren't the start of a node id.
+        let ends_edge = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric() && c != '_');
+        let head = match (after.next(), after.next()) {
+            (Some('>'), _) => EdgeHead::Arrow,
+            (Some('o'), next) if ends_edge(next) => EdgeHead::Circle,
+            (Some('x'), next) if ends_edge(next) => EdgeHead::Cross,
+            _ => EdgeHead::None,
+        };
+        self.position += run_len + if head == EdgeHead::None { 0 } else { 1 };
+        Some((run, head))
+    }
+}
+
+fn edge_stroke(run: &str) -> EdgeStroke {
+    if run.contains('=') {
+        EdgeStroke::Thick
+    } else if run.contains('.') {
+        EdgeStroke::Dotted
+    } else {
+        EdgeStroke::Solid
+    }
+}
+
+/// The size of a node whose label has the given size, leaving room for the
+/// label within the node's shape.
+fn node_size(shape: NodeShape, (width, height): (f32, f32)) -> (f32, f32) {
+    let padded = (width + PADDING_X * 2., height + PADDING_Y * 2.);
+    match shape {
+        NodeShape::Rectangle | NodeShape::Rounded | NodeShape::Asymmetric => padded,
+        NodeShape::Stadium | NodeShape::Hexagon | NodeShape::Parallelogram => {
+            (padded.0 + padded.1, padded.1)
+        }
+        NodeShape::Subroutine => (padded.0 + 16., padded.1),
+        NodeShape::Cylinder => (padded.0, padded.1 + 16.),
+        NodeShape::Circle => {
+            let diameter = width.hypot(height) + PADDING_Y * 2.;
+            (diameter, diameter)
+        }
+        NodeShape::Diamond => (width * 2. + PADDING_X, height * 2. + PADDING_Y),
+    }
+}
+
+fn clip_to_shape(shape: NodeShape, rect: Rect, toward: (f32, f32)) -> (f32, f32) {
+    match shape {
+        NodeShape::Circle => clip_to_ellipse(rect, toward),
+        NodeShape::Diamond => clip_to_diamond(rect, toward),
+        _ => clip_to_rect(rect, toward),
+    }
+}
+
+fn draw_node(svg: &mut SvgBuilder, shape: NodeShape, rect: Rect) {
+    let theme = svg.theme();
+    let (fill, stroke) = (theme.node_fill.as_str(), theme.node_border.as_str());
+    let (x, y, right, bottom) = (rect.x, rect.y, rect.right(), rect.bottom());
+    let (cx, cy) = rect.center();
+    match shape {
+        NodeShape::Rectangle => svg.rect(rect, 0., fill, stroke, LineStyle::Solid),
+        NodeShape::Rounded => svg.rect(rect, 5., fill, stroke, LineStyle::Solid),
+        NodeShape::Stadium => svg.rect(rect, rect.height / 2., fill, stroke, LineStyle::Solid),
+        NodeShape::Subroutine => {
+            svg.rect(rect, 0., fill, stroke, LineStyle::Solid);
+            svg.line((x + 8., y), (x + 8., bottom), stroke, LineStyle::Solid);
+            svg.line(
+                (right - 8., y),
+                (right - 8., bottom),
+                stroke,
+                LineStyle::Solid,
+            );
+        }
+        NodeShape::Cylinder => {
+            let radius = 8.;
+            svg.ellipse(cx, bottom - radius, rect.width / 2., radius, fill, stroke);
+            svg.rect(
+                Rect {
+                    x,
+                    y: y + radius,
+                    width: rect.width,
+                    height: rect.height - radius * 2.,
+                },
+                0.,
+                fill,
+                "none",
+                LineStyle::Solid,
+            );
+            svg.line(
+                (x, y + radius),
+                (x, bottom - radius),
+                stroke,
+                LineStyle::Solid,
+            );
+            svg.line(
+                (right, y + radius),
+                (right, bottom - radius),
+                stroke,
+                LineStyle::Solid,
+            );
+            svg.ellipse(cx, y + radius, rect.width / 2., radius, fill, stroke);
+        }
+        NodeShape::Circle => svg.ellipse(cx, cy, rect.width / 2., rect.height / 2., fill, stroke),
+        NodeShape::Asymmetric => svg.polygon(
+            &[
+                (x, y),
+                (right, y),
+                (right, bottom),
+                (x, bottom),
+                (x + rect.height / 3., cy),
+            ],
+            fill,
+            stroke,
+        ),
+        NodeShape::Diamond => {
+            svg.polygon(&[(cx, y), (right, cy), (cx, bottom), (x, cy)], fill, stroke)
+        }
+        NodeShape::Hexagon => {
+            let inset = (rect.height / 2.).min(rect.width / 4.);
+            svg.polygon(
+                &[
+                    (x, cy),
+                    (x + inset, y),
+                    (right - inset, y),
+                    (right, cy),
+                    (right - inset, bottom),
+                    (x + inset, bottom),
+                ],
+                fill,
+                stroke,
+            )
+        }
+        NodeShape::Parallelogram => {
+            let slant = rect.height / 2.;
+            svg.polygon(
+                &[
+                    (x + slant, y),
+                    (right, y),
+                    (right - slant, bottom),
+                    (x, bottom),
+                ],
+                fill,
+                stroke,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Diagram;
+
+    fn parse(source: &str) -> Flowchart {
+        match Diagram::parse(source).unwrap() {
+            Diagram::Flowchart(flowchart) => flowchart,
+            diagram => panic!("expected a flowchart, got {diagram:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_nodes() {
+        let flowchart = parse(
+            "flowchart LR\n  A[Start] --> B{Is it?}\n  B --> C((Done)); D[(Database)]\n  E[\"Quoted [text]\"]",
+        );
+        assert_eq!(flowchart.direction, Direction::LeftRight);
+        let nodes = flowchart
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node.label.as_str(), node.shape))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            [
+                ("A", "Start", NodeShape::Rectangle),
+                ("B", "Is it?", NodeShape::Diamond),
+                ("C", "Done", NodeShape::Circle),
+                ("D", "Database", NodeShape::Cylinder),
+                ("E", "Quoted [text]", NodeShape::Rectangle),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_edges() {
+        let flowchart = parse(
+            "graph TD\n  A -->|yes| B -.-> C\n  A -- no --> D\n  B & C ==> D\n  D --- E\n  E <--> A\n  E --x F",
+        );
+        let edges = flowchart
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    flowchart.nodes[edge.from].id.as_str(),
+                    flowchart.nodes[edge.to].id.as_str(),
+                    edge.label.as_deref(),
+                    edge.stroke,
+                    edge.head,
+                    edge.tail,
+                )
+            })
+            .collect::<Vec<_>>();
+        use EdgeHead::*;
+        use EdgeStroke::*;
+        assert_eq!(
+            edges,
+            [
+                ("A", "B", Some("yes"), Solid, Arrow, None),
+                ("B", "C", Option::None, Dotted, Arrow, None),
+                ("A", "D", Some("no"), Solid, Arrow, None),
+                ("B", "D", Option::None, Thick, Arrow, None),
+                ("C", "D", Option::None, Thick, Arrow, None),
+                ("D", "E", Option::None, Solid, None, None),
+                ("E", "A", Option::None, Solid, Arrow, Arrow),
+                ("E", "F", Option::None, Solid, Cross, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Diagram::parse("graph XY\n  A --> B").is_err());
+        assert!(Diagram::parse("graph TD\n  A[Start --> B").is_err());
+        assert!(Diagram::parse("graph TD\n  A --> ").is_err());
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = parse("graph TD\n  A[Start] -->|go| B(End <br> here)").to_svg(&Theme::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">Start</text>"));
+        assert!(svg.contains(">go</text>"));
+        assert!(svg.contains(">End</text>"));
+        assert!(svg.contains(">here</text>"));
+    }
+}
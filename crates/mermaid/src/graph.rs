@@ -0,0 +1,539 @@
+//! A layered ("Sugiyama-style") layout for directed graphs, shared by the
+//! diagram types that are drawn as nodes connected by edges.
+
+use crate::svg::{MARGIN, Rect};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    TopDown,
+    BottomUp,
+    LeftRight,
+    RightLeft,
+}
+
+impl Direction {
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        match text {
+            "TD" | "TB" => Some(Direction::TopDown),
+            "BT" => Some(Direction::BottomUp),
+            "LR" => Some(Direction::LeftRight),
+            "RL" => Some(Direction::RightLeft),
+            _ => None,
+        }
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, Direction::LeftRight | Direction::RightLeft)
+    }
+}
+
+pub(crate) struct GraphLayout {
+    pub nodes: Vec<Rect>,
+    /// The route of each edge, from the center of its source node to the
+    /// center of its target node. Callers clip the ends to the node shapes.
+    pub edges: Vec<Vec<(f32, f32)>>,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A node in the layered graph. Edges spanning several ranks pass through a
+/// chain of virtual nodes, one per rank, so that they're routed around nodes.
+struct LayerNode {
+    /// The node's extent along the direction of the graph.
+    main: f32,
+    /// The node's extent across the direction of the graph.
+    cross: f32,
+    is_virtual: bool,
+    predecessors: Vec<usize>,
+    successors: Vec<usize>,
+}
+
+const LOOP_SIZE: f32 = 20.;
+
+pub(crate) fn layout_graph(
+    sizes: &[(f32, f32)],
+    edges: &[(usize, usize)],
+    direction: Direction,
+    rank_gap: f32,
+    node_gap: f32,
+) -> GraphLayout {
+    let node_count = sizes.len();
+    let reversed = find_back_edges(node_count, edges);
+    let oriented = edges
+        .iter()
+        .zip(&reversed)
+        .map(|(&(from, to), &reversed)| if reversed { (to, from) } else { (from, to) })
+        .collect::<Vec<_>>();
+    let ranks = assign_ranks(node_count, &oriented);
+
+    let mut layer_nodes = sizes
+        .iter()
+        .map(|&(width, height)| {
+            let (main, cross) = if direction.is_horizontal() {
+                (width, height)
+            } else {
+                (height, width)
+            };
+            LayerNode {
+                main,
+                cross,
+                is_virtual: false,
+                predecessors: Vec::new(),
+                successors: Vec::new(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut node_ranks = ranks.clone();
+    let mut chains = Vec::with_capacity(edges.len());
+    for &(from, to) in &oriented {
+        if from == to {
+            chains.push(Vec::new());
+            continue;
+        }
+        let mut chain = vec![from];
+        for rank in ranks[from] + 1..ranks[to] {
+            layer_nodes.push(LayerNode {
+                main: 0.,
+                cross: 0.,
+                is_virtual: true,
+                predecessors: Vec::new(),
+                successors: Vec::new(),
+            });
+            node_ranks.push(rank);
+            chain.push(layer_nodes.len() - 1);
+        }
+        chain.push(to);
+        for pair in chain.windows(2) {
+            layer_nodes[pair[0]].successors.push(pair[1]);
+            layer_nodes[pair[1]].predecessors.push(pair[0]);
+        }
+        chains.push(chain);
+    }
+
+    let rank_count = node_ranks.iter().copied().max().map_or(0, |rank| rank + 1);
+    let mut layers = vec![Vec::new(); rank_count];
+    for (node, &rank) in node_ranks.iter().enumerate() {
+        layers[rank].push(node);
+    }
+    order_layers(&mut layers, &layer_nodes);
+
+    // Position the ranks along the main axis.
+    let mut main_positions = vec![0.; layer_nodes.len()];
+    let mut offset = 0.;
+    for layer in &layers {
+        let thickness = layer
+            .iter()
+            .map(|&node| layer_nodes[node].main)
+            .fold(0., f32::max);
+        for &node in layer {
+            main_positions[node] = offset + thickness / 2.;
+        }
+        offset += thickness + rank_gap;
+    }
+    let main_extent = (offset - rank_gap).max(0.);
+
+    let cross_positions = position_across(&layers, &layer_nodes, node_gap);
+    let has_loop = edges.iter().any(|(from, to)| from == to);
+    let cross_start = layer_nodes
+        .iter()
+        .zip(&cross_positions)
+        .map(|(node, position)| position - node.cross / 2.)
+        .fold(f32::INFINITY, f32::min);
+    let cross_end = layer_nodes
+        .iter()
+        .zip(&cross_positions)
+        .map(|(node, position)| position + node.cross / 2.)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let cross_start = if cross_start.is_finite() {
+        cross_start
+    } else {
+        0.
+    };
+    let cross_extent = (cross_end - cross_start).max(0.);
+
+    let (content_width, content_height) = if direction.is_horizontal() {
+        (main_extent, cross_extent)
+    } else {
+        (cross_extent, main_extent)
+    };
+    let to_diagram = |main: f32, cross: f32| {
+        let cross = cross - cross_start;
+        let (x, y) = match direction {
+            Direction::TopDown => (cross, main),
+            Direction::BottomUp => (cross, main_extent - main),
+            Direction::LeftRight => (main, cross),
+            Direction::RightLeft => (main_extent - main, cross),
+        };
+        (x + MARGIN, y + MARGIN)
+    };
+
+    let nodes = sizes
+        .iter()
+        .enumerate()
+        .map(|(node, &(width, height))| {
+            Rect::centered(
+                to_diagram(main_positions[node], cross_positions[node]),
+                width,
+                height,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let edges = edges
+        .iter()
+        .zip(chains)
+        .zip(reversed)
+        .map(|((&(from, _), chain), reversed)| {
+            if chain.is_empty() {
+                let rect = nodes[from];
+                let (x, y) = (rect.right(), rect.center().1);
+                let offset = rect.height / 4.;
+                return vec![
+                    (x, y - offset),
+                    (x + LOOP_SIZE, y - offset),
+                    (x + LOOP_SIZE, y + offset),
+                    (x, y + offset),
+                ];
+            }
+            let mut points = chain
+                .iter()
+                .map(|&node| to_diagram(main_positions[node], cross_positions[node]))
+                .collect::<Vec<_>>();
+            if reversed {
+                points.reverse();
+            }
+            points
+        })
+        .collect();
+
+    let loop_width = if has_loop { LOOP_SIZE } else { 0. };
+    GraphLayout {
+        nodes,
+        edges,
+        width: content_width + loop_width + MARGIN * 2.,
+        height: content_height + MARGIN * 2.,
+    }
+}
+
+/// Finds the edges that close a cycle, which are laid out as if they were
+/// reversed so that the rest of the graph keeps flowing in one direction.
+fn find_back_edges(node_count: usize, edges: &[(usize, usize)]) -> Vec<bool> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        OnStack,
+        Done,
+    }
+
+    let mut outgoing = vec![Vec::new(); node_count];
+    for (ix, &(from, to)) in edges.iter().enumerate() {
+        outgoing[from].push((ix, to));
+    }
+    let mut reversed = vec![false; edges.len()];
+    let mut states = vec![State::Unvisited; node_count];
+    for root in 0..node_count {
+        if states[root] != State::Unvisited {
+            continue;
+        }
+        states[root] = State::OnStack;
+        let mut stack = vec![(root, 0)];
+        while let Some((node, next_edge)) = stack.last_mut() {
+            let node = *node;
+            if let Some(&(edge, to)) = outgoing[node].get(*next_edge) {
+                *next_edge += 1;
+                match states[to] {
+                    State::Unvisited => {
+                        states[to] = State::OnStack;
+                        stack.push((to, 0));
+                    }
+                    State::OnStack => reversed[edge] = to != node,
+                    State::Done => {}
+                }
+            } else {
+                states[node] = State::Done;
+                stack.pop();
+            }
+        }
+    }
+    reversed
+}
+
+/// Assigns each node the length of the longest path leading to it, then moves
+/// sources down so they sit just above the nodes they point to.
+fn assign_ranks(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut in_degree = vec![0; node_count];
+    let mut outgoing = vec![Vec::new(); node_count];
+    for &(from, to) in edges {
+        if from != to {
+            in_degree[to] += 1;
+            outgoing[from].push(to);
+        }
+    }
+    let sources = (0..node_count)
+        .filter(|&node| in_degree[node] == 0)
+        .collect::<Vec<_>>();
+
+    let mut ranks = vec![0usize; node_count];
+    let mut queue = sources.clone();
+    let mut remaining = in_degree;
+    while let Some(node) = queue.pop() {
+        for &to in &outgoing[node] {
+            ranks[to] = ranks[to].max(ranks[node] + 1);
+            remaining[to] -= 1;
+            if remaining[to] == 0 {
+                queue.push(to);
+            }
+        }
+    }
+
+    for source in sources {
+        if let Some(min_successor) = outgoing[source].iter().map(|&to| ranks[to]).min() {
+            ranks[source] = min_successor.saturating_sub(1);
+        }
+    }
+    ranks
+}
+
+/// Orders the nodes within each layer to reduce edge crossings, by repeatedly
+/// sorting them by the average position of their neighbors.
+fn order_layers(layers: &mut [Vec<usize>], nodes: &[LayerNode]) {
+    let mut positions = vec![0.; nodes.len()];
+    let update_positions = |layer: &[usize], positions: &mut [f32]| {
+        for (ix, &node) in layer.iter().enumerate() {
+            positions[node] = ix as f32;
+        }
+    };
+    for layer in layers.iter() {
+        update_positions(layer, &mut positions);
+    }
+
+    for iteration in 0..8 {
+        let downward = iteration % 2 == 0;
+        let layer_indices = if downward {
+            (1..layers.len()).collect::<Vec<_>>()
+        } else {
+            (0..layers.len().saturating_sub(1)).rev().collect()
+        };
+        for layer_ix in layer_indices {
+            let layer = &mut layers[layer_ix];
+            let mut keyed = layer
+                .iter()
+                .map(|&node| {
+                    let neighbors = if downward {
+                        &nodes[node].predecessors
+                    } else {
+                        &nodes[node].successors
+                    };
+                    let key = if neighbors.is_empty() {
+                        positions[node]
+                    } else {
+                        neighbors.iter().map(|&n| positions[n]).sum::<f32>()
+                            / neighbors.len() as f32
+                    };
+                    (key, node)
+                })
+                .collect::<Vec<_>>();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            *layer = keyed.into_iter().map(|(_, node)| node).collect();
+            update_positions(layer, &mut positions);
+        }
+    }
+}
+
+/// Positions the nodes of each layer across the main axis, aligning nodes
+/// with their neighbors where there's room to do so.
+fn position_across(layers: &[Vec<usize>], nodes: &[LayerNode], node_gap: f32) -> Vec<f32> {
+    let separation = |a: usize, b: usize| {
+        let gap = if nodes[a].is_virtual || nodes[b].is_virtual {
+            node_gap / 2.
+        } else {
+            node_gap
+        };
+        (nodes[a].cross + nodes[b].cross) / 2. + gap
+    };
+
+    let mut positions = vec![0.; nodes.len()];
+    for layer in layers {
+        let mut offset = 0.;
+        for (ix, &node) in layer.iter().enumerate() {
+            if ix > 0 {
+                offset += separation(layer[ix - 1], node);
+            }
+            positions[node] = offset;
+        }
+        let center = offset / 2.;
+        for &node in layer {
+            positions[node] -= center;
+        }
+    }
+
+    for iteration in 0..6 {
+        let downward = iteration % 2 == 0;
+        let layer_indices = if downward {
+            (1..layers.len()).collect::<Vec<_>>()
+        } else {
+            (0..layers.len().saturating_sub(1)).rev().collect()
+        };
+        for layer_ix in layer_indices {
+            let layer = &layers[layer_ix];
+            let desired = layer
+                .iter()
+                .map(|&node| {
+                    let neighbors = if downward {
+                        &nodes[node].predecessors
+                    } else {
+                        &nodes[node].successors
+                    };
+                    if neighbors.is_empty() {
+                        positions[node]
+                    } else {
+                        neighbors.iter().map(|&n| positions[n]).sum::<f32>()
+                            / neighbors.len() as f32
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Resolve overlaps by pushing nodes apart in both directions, and
+            // settle on the average so that neither side is favored.
+            let mut from_left = desired.clone();
+            for ix in 1..layer.len() {
+                from_left[ix] =
+                    from_left[ix].max(from_left[ix - 1] + separation(layer[ix - 1], layer[ix]));
+            }
+            let mut from_right = desired;
+            for ix in (0..layer.len().saturating_sub(1)).rev() {
+                from_right[ix] =
+                    from_right[ix].min(from_right[ix + 1] - separation(layer[ix], layer[ix + 1]));
+            }
+            for (ix, &node) in layer.iter().enumerate() {
+                positions[node] = (from_left[ix] + from_right[ix]) / 2.;
+            }
+        }
+    }
+    positions
+}
+
+/// Moves the end of a line from the center of a rectangle to its border.
+pub(crate) fn clip_to_rect(rect: Rect, toward: (f32, f32)) -> (f32, f32) {
+    let (cx, cy) = rect.center();
+    let (dx, dy) = (toward.0 - cx, toward.1 - cy);
+    let scale_x = if dx == 0. {
+        f32::INFINITY
+    } else {
+        rect.width / 2. / dx.abs()
+    };
+    let scale_y = if dy == 0. {
+        f32::INFINITY
+    } else {
+        rect.height / 2. / dy.abs()
+    };
+    let scale = scale_x.min(scale_y);
+    if !scale.is_finite() || scale >= 1. {
+        return (cx, cy);
+    }
+    (cx + dx * scale, cy + dy * scale)
+}
+
+/// Moves the end of a line from the center of a diamond to its border.
+pub(crate) fn clip_to_diamond(rect: Rect, toward: (f32, f32)) -> (f32, f32) {
+    let (cx, cy) = rect.center();
+    let (dx, dy) = (toward.0 - cx, toward.1 - cy);
+    let extent = dx.abs() / (rect.width / 2.) + dy.abs() / (rect.height / 2.);
+    if extent <= 1. {
+        return (cx, cy);
+    }
+    (cx + dx / extent, cy + dy / extent)
+}
+
+/// Moves the end of a line from the center of an ellipse to its border.
+pub(crate) fn clip_to_ellipse(rect: Rect, toward: (f32, f32)) -> (f32, f32) {
+    let (cx, cy) = rect.center();
+    let (dx, dy) = (toward.0 - cx, toward.1 - cy);
+    let (rx, ry) = (rect.width / 2., rect.height / 2.);
+    let extent = ((dx / rx).powi(2) + (dy / ry).powi(2)).sqrt();
+    if extent <= 1. {
+        return (cx, cy);
+    }
+    (cx + dx / extent, cy + dy / extent)
+}
+
+/// The point halfway along a polyline, where its label is drawn.
+pub(crate) fn midpoint(points: &[(f32, f32)]) -> (f32, f32) {
+    let length = |a: (f32, f32), b: (f32, f32)| ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+    let total = points
+        .windows(2)
+        .map(|pair| length(pair[0], pair[1]))
+        .sum::<f32>();
+    let mut remaining = total / 2.;
+    for pair in points.windows(2) {
+        let segment = length(pair[0], pair[1]);
+        if segment > 0. && remaining <= segment {
+            let t = remaining / segment;
+            return (
+                pair[0].0 + (pair[1].0 - pair[0].0) * t,
+                pair[0].1 + (pair[1].1 - pair[0].1) * t,
+            );
+        }
+        remaining -= segment;
+    }
+    points.first().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_chain_top_down() {
+        let layout = layout_graph(
+            &[(40., 20.), (40., 20.), (40., 20.)],
+            &[(0, 1), (1, 2)],
+            Direction::TopDown,
+            30.,
+            20.,
+        );
+        let centers = layout
+            .nodes
+            .iter()
+            .map(|rect| rect.center())
+            .collect::<Vec<_>>();
+        assert_eq!(centers, [(30., 20.), (30., 70.), (30., 120.)]);
+        assert_eq!((layout.width, layout.height), (60., 140.));
+        assert_eq!(layout.edges[0], [(30., 20.), (30., 70.)]);
+    }
+
+    #[test]
+    fn test_layout_cycle_and_long_edge() {
+        // 0 -> 1 -> 2 -> 0, plus a shortcut from 0 to 2 that spans two ranks.
+        let layout = layout_graph(
+            &[(40., 20.); 3],
+            &[(0, 1), (1, 2), (2, 0), (0, 2)],
+            Direction::LeftRight,
+            30.,
+            20.,
+        );
+        let xs = layout
+            .nodes
+            .iter()
+            .map(|rect| rect.center().0)
+            .collect::<Vec<_>>();
+        assert!(xs[0] < xs[1] && xs[1] < xs[2]);
+        // The back edge is routed from node 2 back to node 0.
+        assert_eq!(layout.edges[2].first(), Some(&layout.nodes[2].center()));
+        assert_eq!(layout.edges[2].last(), Some(&layout.nodes[0].center()));
+        // The long edges pass through a virtual node in the middle rank.
+        assert_eq!(layout.edges[3].len(), 3);
+        assert_eq!(layout.edges[3][1].0, xs[1]);
+    }
+
+    #[test]
+    fn test_clipping() {
+        let rect = Rect::centered((0., 0.), 20., 10.);
+        assert_eq!(clip_to_rect(rect, (100., 0.)), (10., 0.));
+        assert_eq!(clip_to_rect(rect, (0., -100.)), (0., -5.));
+        assert_eq!(clip_to_diamond(rect, (100., 0.)), (10., 0.));
+        assert_eq!(clip_to_ellipse(rect, (0., 100.)), (0., 5.));
+        assert_eq!(midpoint(&[(0., 0.), (10., 0.), (10., 10.)]), (10., 0.));
+    }
+}
@@ -0,0 +1,148 @@
+//! Renders [Mermaid](https://mermaid.js.org) diagrams to SVG, without a browser.
+//!
+//! Flowcharts, sequence diagrams and class diagrams are supported. Other diagram
+//! types fail to parse, so that callers can fall back to showing their source.
+
+mod class_diagram;
+mod flowchart;
+mod graph;
+mod sequence;
+mod svg;
+
+use anyhow::{Result, anyhow};
+
+pub use class_diagram::ClassDiagram;
+pub use flowchart::{EdgeHead, EdgeStroke, FlowEdge, FlowNode, Flowchart, NodeShape};
+pub use graph::Direction;
+pub use sequence::SequenceDiagram;
+
+/// The colors and font used to draw a diagram, as CSS colors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub font_family: String,
+    pub font_size: f32,
+    pub text: String,
+    pub line: String,
+    pub node_fill: String,
+    pub node_border: String,
+    pub note_fill: String,
+    pub note_border: String,
+    /// The background drawn behind edge labels, so lines don't cross them.
+    pub label_background: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            font_family: "sans-serif".into(),
+            font_size: 14.,
+            text: "#333333".into(),
+            line: "#555555".into(),
+            node_fill: "#ececff".into(),
+            node_border: "#9370db".into(),
+            note_fill: "#fff5ad".into(),
+            note_border: "#aaaa33".into(),
+            label_background: "#ffffff".into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diagram {
+    Flowchart(Flowchart),
+    Sequence(SequenceDiagram),
+    Class(ClassDiagram),
+}
+
+impl Diagram {
+    /// Parses the source of a diagram, such as the contents of a ```` ```mermaid ```` block.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut lines = source
+            .lines()
+            .map(strip_comment)
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("---"));
+        let header = lines.next().ok_or_else(|| anyhow!("empty diagram"))?;
+        let body = lines.collect::<Vec<_>>();
+        let (kind, argument) = header
+            .split_once(char::is_whitespace)
+            .map_or((header, ""), |(kind, argument)| (kind, argument.trim()));
+
+        match kind {
+            "graph" | "flowchart" => Ok(Diagram::Flowchart(Flowchart::parse(argument, &body)?)),
+            "sequenceDiagram" => Ok(Diagram::Sequence(SequenceDiagram::parse(&body)?)),
+            "classDiagram" | "classDiagram-v2" => Ok(Diagram::Class(ClassDiagram::parse(&body)?)),
+            _ => Err(anyhow!("unsupported diagram type '{kind}'")),
+        }
+    }
+
+    pub fn to_svg(&self, theme: &Theme) -> String {
+        match self {
+            Diagram::Flowchart(flowchart) => flowchart.to_svg(theme),
+            Diagram::Sequence(sequence) => sequence.to_svg(theme),
+            Diagram::Class(class_diagram) => class_diagram.to_svg(theme),
+        }
+    }
+}
+
+/// Parses and renders a diagram to SVG in one step.
+pub fn render_svg(source: &str, theme: &Theme) -> Result<String> {
+    Ok(Diagram::parse(source)?.to_svg(theme))
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find("%%") {
+        Some(ix) => &line[..ix],
+        None => line,
+    }
+}
+
+/// Splits a label into lines at `<br>` tags, as Mermaid does.
+fn label_lines(label: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut rest = label;
+    while let Some(start) = rest.find("<br") {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        lines.push(rest[..start].trim().to_string());
+        rest = &rest[start + end + 1..];
+    }
+    lines.push(rest.trim().to_string());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diagram_types() {
+        assert!(matches!(
+            Diagram::parse("%% comment\ngraph LR\n  A --> B").unwrap(),
+            Diagram::Flowchart(_)
+        ));
+        assert!(matches!(
+            Diagram::parse("sequenceDiagram\n  Alice->>Bob: Hi").unwrap(),
+            Diagram::Sequence(_)
+        ));
+        assert!(matches!(
+            Diagram::parse("classDiagram\n  Animal <|-- Duck").unwrap(),
+            Diagram::Class(_)
+        ));
+        assert_eq!(
+            Diagram::parse("pie\n  \"a\": 1").unwrap_err().to_string(),
+            "unsupported diagram type 'pie'"
+        );
+        assert_eq!(
+            Diagram::parse("  \n").unwrap_err().to_string(),
+            "empty diagram"
+        );
+    }
+
+    #[test]
+    fn test_label_lines() {
+        assert_eq!(label_lines("one<br>two<br/>three"), ["one", "two", "three"]);
+        assert_eq!(label_lines("single"), ["single"]);
+    }
+}
@@ -0,0 +1,676 @@
+use anyhow::{Result, anyhow, bail};
+
+use crate::{
+    Theme, label_lines,
+    svg::{
+        Anchor, LineStyle, MARGIN, Marker, PADDING_X, PADDING_Y, Rect, SvgBuilder, label_size,
+        line_height, text_width,
+    },
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SequenceDiagram {
+    pub participants: Vec<Participant>,
+    pub events: Vec<SequenceEvent>,
+    /// Whether messages are numbered, as enabled by `autonumber`.
+    pub autonumber: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Participant {
+    pub id: String,
+    pub label: String,
+    pub is_actor: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SequenceEvent {
+    Message {
+        from: usize,
+        to: usize,
+        text: String,
+        line: MessageLine,
+        head: MessageHead,
+    },
+    Note {
+        placement: NotePlacement,
+        participants: Vec<usize>,
+        text: String,
+    },
+    /// The start of a `loop`, `alt`, `opt`, `par`, `critical`, `break` or `rect` block.
+    BlockStart {
+        kind: String,
+        label: String,
+    },
+    /// An `else`, `and` or `option` section within a block.
+    BlockSection {
+        label: String,
+    },
+    BlockEnd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageLine {
+    Solid,
+    Dashed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageHead {
+    None,
+    Arrow,
+    Open,
+    Cross,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotePlacement {
+    LeftOf,
+    RightOf,
+    Over,
+}
+
+/// Message arrows, longest first so that `-->>` is matched before `-->`.
+const ARROWS: &[(&str, MessageLine, MessageHead)] = &[
+    ("-->>", MessageLine::Dashed, MessageHead::Arrow),
+    ("->>", MessageLine::Solid, MessageHead::Arrow),
+    ("--x", MessageLine::Dashed, MessageHead::Cross),
+    ("-x", MessageLine::Solid, MessageHead::Cross),
+    ("--)", MessageLine::Dashed, MessageHead::Open),
+    ("-)", MessageLine::Solid, MessageHead::Open),
+    ("-->", MessageLine::Dashed, MessageHead::None),
+    ("->", MessageLine::Solid, MessageHead::None),
+];
+
+const BLOCK_KINDS: &[&str] = &["loop", "alt", "opt", "par", "critical", "break", "rect"];
+const SECTION_KEYWORDS: &[&str] = &["else", "and", "option"];
+const IGNORED_KEYWORDS: &[&str] = &[
+    "title",
+    "activate",
+    "deactivate",
+    "create",
+    "destroy",
+    "link",
+    "links",
+];
+
+impl SequenceDiagram {
+    pub(crate) fn parse(lines: &[&str]) -> Result<Self> {
+        let mut diagram = SequenceDiagram::default();
+        let mut depth = 0;
+        for line in lines {
+            let (keyword, rest) = line
+                .split_once(char::is_whitespace)
+                .map_or((*line, ""), |(keyword, rest)| (keyword, rest.trim()));
+            match keyword {
+                "participant" | "actor" => {
+                    let (id, label) = rest
+                        .split_once(" as ")
+                        .map_or((rest, rest), |(id, label)| (id.trim(), label.trim()));
+                    let index = diagram.participant(id);
+                    let participant = &mut diagram.participants[index];
+                    participant.label = label.to_string();
+                    participant.is_actor = keyword == "actor";
+                }
+                "autonumber" => diagram.autonumber = true,
+                "end" => {
+                    if depth == 0 {
+                        bail!("'end' without a matching block");
+                    }
+                    depth -= 1;
+                    diagram.events.push(SequenceEvent::BlockEnd);
+                }
+                _ if BLOCK_KINDS.contains(&keyword) => {
+                    depth += 1;
+                    diagram.events.push(SequenceEvent::BlockStart {
+                        kind: keyword.to_string(),
+                        label: rest.to_string(),
+                    });
+                }
+                _ if SECTION_KEYWORDS.contains(&keyword) => {
+                    if depth == 0 {
+                        bail!("'{keyword}' outside of a block");
+                    }
+                    diagram.events.push(SequenceEvent::BlockSection {
+                        label: rest.to_string(),
+                    });
+                }
+                _ if IGNORED_KEYWORDS.contains(&keyword) => {}
+                _ if keyword.eq_ignore_ascii_case("note") => diagram.parse_note(rest)?,
+                _ => diagram.parse_message(line)?,
+            }
+        }
+        if depth > 0 {
+            bail!("block is missing an 'end'");
+        }
+        Ok(diagram)
+    }
+
+    fn participant(&mut self, id: &str) -> usize {
+        if let Some(index) = self.participants.iter().position(|p| p.id == id) {
+            return index;
+        }
+        self.participants.push(Participant {
+            id: id.to_string(),
+            label: id.to_string(),
+            is_actor: false,
+        });
+        self.participants.len() - 1
+    }
+
+    fn parse_note(&mut self, text: &str) -> Result<()> {
+        let (target, note) = text
+            .split_once(':')
+            .ok_or_else(|| anyhow!("expected ':' in note '{text}'"))?;
+        let target = target.trim();
+        let (placement, participants) = if let Some(rest) = target.strip_prefix("left of") {
+            (NotePlacement::LeftOf, rest)
+        } else if let Some(rest) = target.strip_prefix("right of") {
+            (NotePlacement::RightOf, rest)
+        } else if let Some(rest) = target.strip_prefix("over") {
+            (NotePlacement::Over, rest)
+        } else {
+            bail!("invalid note placement '{target}'");
+        };
+        let participants = participants
+            .split(',')
+            .map(|id| self.participant(id.trim()))
+            .collect();
+        self.events.push(SequenceEvent::Note {
+            placement,
+            participants,
+            text: note.trim().to_string(),
+        });
+        Ok(())
+    }
+
+    fn parse_message(&mut self, line: &str) -> Result<()> {
+        let (arrow_ix, (arrow, message_line, head)) = line
+            .match_indices('-')
+            .find_map(|(ix, _)| {
+                ARROWS
+                    .iter()
+                    .find(|(arrow, _, _)| line[ix..].starts_with(arrow))
+                    .map(|arrow| (ix, *arrow))
+            })
+            .ok_or_else(|| anyhow!("unrecognized statement '{line}'"))?;
+        let from = line[..arrow_ix].trim();
+        let rest = &line[arrow_ix + arrow.len()..];
+        let (to, text) = rest.split_once(':').unwrap_or((rest, ""));
+        let to = to.trim().trim_start_matches(['+', '-']).trim();
+        if from.is_empty() || to.is_empty() {
+            bail!("expected participants in '{line}'");
+        }
+        let from = self.participant(from);
+        let to = self.participant(to);
+        self.events.push(SequenceEvent::Message {
+            from,
+            to,
+            text: text.trim().to_string(),
+            line: message_line,
+            head,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn to_svg(&self, theme: &Theme) -> String {
+        let font_size = theme.font_size;
+        let line_height = line_height(font_size);
+        let box_widths = self
+            .participants
+            .iter()
+            .map(|participant| {
+                (text_width(&participant.label, font_size) + PADDING_X * 2.).max(80.)
+            })
+            .collect::<Vec<_>>();
+        let box_height = line_height + PADDING_Y * 2.;
+        let count = self.participants.len();
+
+        let mut message_number = 0;
+        let message_labels = self
+            .events
+            .iter()
+            .map(|event| match event {
+                SequenceEvent::Message { text, .. } if self.autonumber => {
+                    message_number += 1;
+                    label_lines(&format!("{message_number}. {text}"))
+                }
+                SequenceEvent::Message { text, .. } | SequenceEvent::Note { text, .. } => {
+                    label_lines(text)
+                }
+                _ => Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        // Space the participants so that the labels between them fit.
+        let mut gaps = (1..count)
+            .map(|ix| (box_widths[ix - 1] + box_widths[ix]) / 2. + 30.)
+            .collect::<Vec<_>>();
+        let mut left_extent = box_widths.first().map_or(0., |width| width / 2.);
+        let mut right_extent = box_widths.last().map_or(0., |width| width / 2.);
+        for (event, lines) in self.events.iter().zip(&message_labels) {
+            let label_width = label_size(lines, font_size).0;
+            match event {
+                SequenceEvent::Message { from, to, .. } if from == to => {
+                    let needed = label_width + 50.;
+                    match gaps.get_mut(*from) {
+                        Some(gap) => *gap = gap.max(needed),
+                        None => right_extent = right_extent.max(needed),
+                    }
+                }
+                SequenceEvent::Message { from, to, .. } => {
+                    let (start, end) = (*from.min(to), *from.max(to));
+                    let needed = label_width + 30.;
+                    let available = gaps[start..end].iter().sum::<f32>();
+                    if available < needed {
+                        gaps[end - 1] += needed - available;
+                    }
+                }
+                SequenceEvent::Note {
+                    placement,
+                    participants,
+                    ..
+                } => {
+                    let needed = label_width + PADDING_X * 2. + 20.;
+                    let participant = participants[0];
+                    match placement {
+                        NotePlacement::LeftOf if participant == 0 => {
+                            left_extent = left_extent.max(needed)
+                        }
+                        NotePlacement::LeftOf => {
+                            gaps[participant - 1] = gaps[participant - 1].max(needed)
+                        }
+                        NotePlacement::RightOf if participant + 1 == count => {
+                            right_extent = right_extent.max(needed)
+                        }
+                        NotePlacement::RightOf => gaps[participant] = gaps[participant].max(needed),
+                        NotePlacement::Over => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut centers = Vec::with_capacity(count);
+        let mut x = MARGIN + left_extent;
+        for ix in 0..count {
+            if ix > 0 {
+                x += gaps[ix - 1];
+            }
+            centers.push(x);
+        }
+        let width = x + right_extent + MARGIN;
+
+        let mut svg = SvgBuilder::new(theme);
+        let lifeline_top = MARGIN + box_height;
+        let mut y = lifeline_top + 20.;
+
+        // Lay out the events first, so that lifelines and blocks can be drawn
+        // beneath them.
+        struct Frame {
+            kind: String,
+            label: String,
+            top: f32,
+            sections: Vec<(f32, String)>,
+            participants: Option<(usize, usize)>,
+        }
+        let mut open_frames: Vec<Frame> = Vec::new();
+        let mut frames = Vec::new();
+        let mut event_positions = Vec::with_capacity(self.events.len());
+        for (event, lines) in self.events.iter().zip(&message_labels) {
+            event_positions.push(y);
+            let text_height = label_size(lines, font_size).1;
+            let involved = match event {
+                SequenceEvent::Message { from, to, .. } => Some((*from.min(to), *from.max(to))),
+                SequenceEvent::Note { participants, .. } => Some((
+                    *participants.iter().min().unwrap_or(&0),
+                    *participants.iter().max().unwrap_or(&0),
+                )),
+                _ => None,
+            };
+            if let Some((start, end)) = involved {
+                for frame in &mut open_frames {
+                    frame.participants = Some(match frame.participants {
+                        Some((frame_start, frame_end)) => {
+                            (frame_start.min(start), frame_end.max(end))
+                        }
+                        None => (start, end),
+                    });
+                }
+            }
+            match event {
+                SequenceEvent::Message { from, to, .. } => {
+                    y += text_height + 25.;
+                    if from == to {
+                        y += 20.;
+                    }
+                }
+                SequenceEvent::Note { .. } => y += text_height + PADDING_Y * 2. + 10.,
+                SequenceEvent::BlockStart { kind, label } => {
+                    open_frames.push(Frame {
+                        kind: kind.clone(),
+                        label: label.clone(),
+                        top: y,
+                        sections: Vec::new(),
+                        participants: None,
+                    });
+                    y += line_height + 10.;
+                }
+                SequenceEvent::BlockSection { label } => {
+                    if let Some(frame) = open_frames.last_mut() {
+                        frame.sections.push((y, label.clone()));
+                    }
+                    y += line_height + 10.;
+                }
+                SequenceEvent::BlockEnd => {
+                    if let Some(frame) = open_frames.pop() {
+                        let depth = open_frames.len() as f32;
+                        frames.push((frame, y, depth));
+                    }
+                    y += 10.;
+                }
+            }
+        }
+        let lifeline_bottom = y + 10.;
+        let height = lifeline_bottom + box_height + MARGIN;
+
+        for center in &centers {
+            svg.line(
+                (*center, lifeline_top),
+                (*center, lifeline_bottom),
+                &theme.line,
+                LineStyle::Dashed,
+            );
+        }
+
+        for (frame, bottom, depth) in &frames {
+            let (start, end) = frame.participants.unwrap_or((0, count.saturating_sub(1)));
+            let (Some(start_center), Some(end_center)) = (centers.get(start), centers.get(end))
+            else {
+                continue;
+            };
+            let inset = 10. - depth * 4.;
+            let left = start_center - box_widths[start] / 2. - inset;
+            let right = end_center + box_widths[end] / 2. + inset;
+            let rect = Rect {
+                x: left,
+                y: frame.top,
+                width: right - left,
+                height: bottom - frame.top,
+            };
+            svg.rect(rect, 0., "none", &theme.line, LineStyle::Solid);
+            let tag_width = text_width(&frame.kind, font_size) + 16.;
+            let tag_bottom = frame.top + line_height;
+            svg.polygon(
+                &[
+                    (left, frame.top),
+                    (left + tag_width, frame.top),
+                    (left + tag_width, tag_bottom - 6.),
+                    (left + tag_width - 6., tag_bottom),
+                    (left, tag_bottom),
+                ],
+                &theme.node_fill,
+                &theme.line,
+            );
+            svg.text(
+                left + 8.,
+                frame.top + line_height / 2.,
+                &frame.kind,
+                Anchor::Start,
+                true,
+            );
+            if !frame.label.is_empty() {
+                svg.text(
+                    left + tag_width + 8.,
+                    frame.top + line_height / 2.,
+                    &format!("[{}]", frame.label),
+                    Anchor::Start,
+                    false,
+                );
+            }
+            for (section_top, label) in &frame.sections {
+                svg.line(
+                    (left, *section_top),
+                    (right, *section_top),
+                    &theme.line,
+                    LineStyle::Dashed,
+                );
+                if !label.is_empty() {
+                    svg.text(
+                        (left + right) / 2.,
+                        section_top + line_height / 2.,
+                        &format!("[{label}]"),
+                        Anchor::Middle,
+                        false,
+                    );
+                }
+            }
+        }
+
+        for ((event, lines), top) in self.events.iter().zip(&message_labels).zip(event_positions) {
+            let (label_width, text_height) = label_size(lines, font_size);
+            match event {
+                SequenceEvent::Message {
+                    from,
+                    to,
+                    line,
+                    head,
+                    ..
+                } => {
+                    let style = match line {
+                        MessageLine::Solid => LineStyle::Solid,
+                        MessageLine::Dashed => LineStyle::Dashed,
+                    };
+                    let marker = match head {
+                        MessageHead::None => Marker::None,
+                        MessageHead::Arrow => Marker::Arrow,
+                        MessageHead::Open => Marker::OpenArrow,
+                        MessageHead::Cross => Marker::Cross,
+                    };
+                    let arrow_y = top + text_height + 5.;
+                    let start = centers[*from];
+                    if from == to {
+                        svg.label(
+                            start + 10. + label_width / 2.,
+                            top + text_height / 2.,
+                            lines,
+                        );
+                        let points = [
+                            (start, arrow_y),
+                            (start + 30., arrow_y),
+                            (start + 30., arrow_y + 20.),
+                            (start, arrow_y + 20.),
+                        ];
+                        svg.polyline(&points, &theme.line, style);
+                        svg.marker(marker, points[2], points[3]);
+                    } else {
+                        let end = centers[*to];
+                        svg.label((start + end) / 2., top + text_height / 2., lines);
+                        svg.line((start, arrow_y), (end, arrow_y), &theme.line, style);
+                        svg.marker(marker, (start, arrow_y), (end, arrow_y));
+                    }
+                }
+                SequenceEvent::Note {
+                    placement,
+                    participants,
+                    ..
+                } => {
+                    let note_width = label_width + PADDING_X * 2.;
+                    let note_height = text_height + PADDING_Y * 2.;
+                    let first = centers[participants[0]];
+                    let (left, right) = match placement {
+                        NotePlacement::LeftOf => (first - 10. - note_width, first - 10.),
+                        NotePlacement::RightOf => (first + 10., first + 10. + note_width),
+                        NotePlacement::Over => {
+                            let last = participants
+                                .last()
+                                .map_or(first, |participant| centers[*participant]);
+                            let (start, end) = (first.min(last), first.max(last));
+                            let center = (start + end) / 2.;
+                            let half_width = (note_width.max(end - start + 40.)) / 2.;
+                            (center - half_width, center + half_width)
+                        }
+                    };
+                    svg.rect(
+                        Rect {
+                            x: left,
+                            y: top,
+                            width: right - left,
+                            height: note_height,
+                        },
+                        0.,
+                        &theme.note_fill,
+                        &theme.note_border,
+                        LineStyle::Solid,
+                    );
+                    svg.label((left + right) / 2., top + note_height / 2., lines);
+                }
+                SequenceEvent::BlockStart { .. }
+                | SequenceEvent::BlockSection { .. }
+                | SequenceEvent::BlockEnd => {}
+            }
+        }
+
+        for ((participant, center), box_width) in
+            self.participants.iter().zip(&centers).zip(&box_widths)
+        {
+            for top in [MARGIN, lifeline_bottom] {
+                let rect = Rect {
+                    x: center - box_width / 2.,
+                    y: top,
+                    width: *box_width,
+                    height: box_height,
+                };
+                let radius = if participant.is_actor {
+                    box_height / 2.
+                } else {
+                    3.
+                };
+                svg.rect(
+                    rect,
+                    radius,
+                    &theme.node_fill,
+                    &theme.node_border,
+                    LineStyle::Solid,
+                );
+                svg.text(
+                    *center,
+                    top + box_height / 2.,
+                    &participant.label,
+                    Anchor::Middle,
+                    false,
+                );
+            }
+        }
+
+        svg.finish(width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Diagram;
+
+    fn parse(source: &str) -> SequenceDiagram {
+        match Diagram::parse(source).unwrap() {
+            Diagram::Sequence(diagram) => diagram,
+            diagram => panic!("expected a sequence diagram, got {diagram:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_sequence_diagram() {
+        let diagram = parse(
+            "sequenceDiagram
+                participant A as Alice
+                actor B
+                A->>+B: Hello
+                B-->>-A: Hi
+                loop Every minute
+                    A-xC: Ping
+                    alt is sick
+                        C--)A: Not well
+                    else
+                        C->A
+                    end
+                end
+                Note over A,B: A note",
+        );
+        let participants = diagram
+            .participants
+            .iter()
+            .map(|p| (p.id.as_str(), p.label.as_str(), p.is_actor))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            participants,
+            [("A", "Alice", false), ("B", "B", true), ("C", "C", false)]
+        );
+        assert_eq!(
+            diagram.events[..2],
+            [
+                SequenceEvent::Message {
+                    from: 0,
+                    to: 1,
+                    text: "Hello".into(),
+                    line: MessageLine::Solid,
+                    head: MessageHead::Arrow,
+                },
+                SequenceEvent::Message {
+                    from: 1,
+                    to: 0,
+                    text: "Hi".into(),
+                    line: MessageLine::Dashed,
+                    head: MessageHead::Arrow,
+                },
+            ]
+        );
+        assert!(matches!(
+            &diagram.events[3],
+            SequenceEvent::Message {
+                head: MessageHead::Cross,
+                line: MessageLine::Solid,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &diagram.events[5],
+            SequenceEvent::Message {
+                head: MessageHead::Open,
+                line: MessageLine::Dashed,
+                ..
+            }
+        ));
+        assert_eq!(
+            diagram.events[6],
+            SequenceEvent::BlockSection {
+                label: String::new()
+            }
+        );
+        assert_eq!(
+            diagram.events.last(),
+            Some(&SequenceEvent::Note {
+                placement: NotePlacement::Over,
+                participants: vec![0, 1],
+                text: "A note".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Diagram::parse("sequenceDiagram\n  loop forever\n  A->>B: hi").is_err());
+        assert!(Diagram::parse("sequenceDiagram\n  end").is_err());
+        assert!(Diagram::parse("sequenceDiagram\n  A says hi").is_err());
+        assert!(Diagram::parse("sequenceDiagram\n  Note above A: hi").is_err());
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = parse("sequenceDiagram\n  autonumber\n  Alice->>Bob: Hi\n  Bob->>Bob: Think")
+            .to_svg(&Theme::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">1. Hi</text>"));
+        assert!(svg.contains(">2. Think</text>"));
+        assert_eq!(svg.matches(">Alice</text>").count(), 2);
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt::Write as _;
+
+use crate::Theme;
+
+/// Padding between a shape's border and its label.
+pub(crate) const PADDING_X: f32 = 15.;
+pub(crate) const PADDING_Y: f32 = 10.;
+/// Margin around the whole diagram.
+pub(crate) const MARGIN: f32 = 10.;
+
+/// Estimates the width of a line of text.
+///
+/// Diagrams are laid out without access to a font, so this uses the average
+/// advance of a proportional font, counting wide (CJK) characters twice.
+pub(crate) fn text_width(text: &str, font_size: f32) -> f32 {
+    let columns = text
+        .chars()
+        .map(|c| if c >= '\u{2e80}' { 2. } else { 1. })
+        .sum::<f32>();
+    columns * font_size * 0.6
+}
+
+pub(crate) fn line_height(font_size: f32) -> f32 {
+    font_size * 1.4
+}
+
+/// The size of a multi-line label, without padding.
+pub(crate) fn label_size(lines: &[String], font_size: f32) -> (f32, f32) {
+    let width = lines
+        .iter()
+        .map(|line| text_width(line, font_size))
+        .fold(0., f32::max);
+    (width, lines.len() as f32 * line_height(font_size))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn centered(center: (f32, f32), width: f32, height: f32) -> Self {
+        Self {
+            x: center.0 - width / 2.,
+            y: center.1 - height / 2.,
+            width,
+            height,
+        }
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        (self.x + self.width / 2., self.y + self.height / 2.)
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Anchor {
+    Start,
+    Middle,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum LineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Thick,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum Marker {
+    #[default]
+    None,
+    Arrow,
+    OpenArrow,
+    Circle,
+    Cross,
+    Triangle,
+    FilledDiamond,
+    Diamond,
+}
+
+/// Accumulates SVG elements, and wraps them in a document once the size of the
+/// diagram is known.
+pub(crate) struct SvgBuilder<'a> {
+    theme: &'a Theme,
+    body: String,
+}
+
+impl<'a> SvgBuilder<'a> {
+    pub fn new(theme: &'a Theme) -> Self {
+        Self {
+            theme,
+            body: String::new(),
+        }
+    }
+
+    pub fn theme(&self) -> &'a Theme {
+        self.theme
+    }
+
+    pub fn rect(&mut self, rect: Rect, radius: f32, fill: &str, stroke: &str, style: LineStyle) {
+        write!(
+            self.body,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="{radius:.1}" fill="{}" stroke="{}"{}/>"#,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            escape(fill),
+            escape(stroke),
+            stroke_attributes(style),
+        )
+        .ok();
+    }
+
+    pub fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, fill: &str, stroke: &str) {
+        write!(
+            self.body,
+            r#"<ellipse cx="{cx:.1}" cy="{cy:.1}" rx="{rx:.1}" ry="{ry:.1}" fill="{}" stroke="{}"/>"#,
+            escape(fill),
+            escape(stroke),
+        )
+        .ok();
+    }
+
+    pub fn polygon(&mut self, points: &[(f32, f32)], fill: &str, stroke: &str) {
+        write!(
+            self.body,
+            r#"<polygon points="{}" fill="{}" stroke="{}"/>"#,
+            format_points(points),
+            escape(fill),
+            escape(stroke),
+        )
+        .ok();
+    }
+
+    pub fn polyline(&mut self, points: &[(f32, f32)], stroke: &str, style: LineStyle) {
+        write!(
+            self.body,
+            r#"<polyline points="{}" fill="none" stroke="{}"{}/>"#,
+            format_points(points),
+            escape(stroke),
+            stroke_attributes(style),
+        )
+        .ok();
+    }
+
+    pub fn line(&mut self, from: (f32, f32), to: (f32, f32), stroke: &str, style: LineStyle) {
+        self.polyline(&[from, to], stroke, style);
+    }
+
+    /// Draws a marker at the end of a line, pointing from `from` to `to`.
+    pub fn marker(&mut self, marker: Marker, from: (f32, f32), to: (f32, f32)) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0. {
+            return;
+        }
+        let (ux, uy) = (dx / length, dy / length);
+        // Maps a point in marker space, where x runs back along the line from
+        // its tip and y runs across it, to the diagram.
+        let at = |along: f32, across: f32| {
+            (
+                to.0 - ux * along - uy * across,
+                to.1 - uy * along + ux * across,
+            )
+        };
+        let line = self.theme.line.clone();
+        let background = self.theme.label_background.clone();
+        match marker {
+            Marker::None => {}
+            Marker::Arrow => self.polygon(&[at(0., 0.), at(9., 4.), at(9., -4.)], &line, &line),
+            Marker::OpenArrow => self.polyline(
+                &[at(9., 4.), at(0., 0.), at(9., -4.)],
+                &line,
+                LineStyle::Solid,
+            ),
+            Marker::Triangle => {
+                self.polygon(&[at(0., 0.), at(14., 7.), at(14., -7.)], &background, &line)
+            }
+            Marker::FilledDiamond | Marker::Diamond => {
+                let fill = if marker == Marker::FilledDiamond {
+                    &line
+                } else {
+                    &background
+                };
+                self.polygon(
+                    &[at(0., 0.), at(8., 5.), at(16., 0.), at(8., -5.)],
+                    fill,
+                    &line,
+                )
+            }
+            Marker::Circle => {
+                let (cx, cy) = at(4., 0.);
+                self.ellipse(cx, cy, 4., 4., &background, &line);
+            }
+            Marker::Cross => {
+                self.line(at(0., 4.), at(8., -4.), &line, LineStyle::Solid);
+                self.line(at(0., -4.), at(8., 4.), &line, LineStyle::Solid);
+            }
+        }
+    }
+
+    /// Draws a line of text, vertically centered on `y`.
+    pub fn text(&mut self, x: f32, y: f32, text: &str, anchor: Anchor, bold: bool) {
+        let font_size = self.theme.font_size;
+        // Approximates `dominant-baseline="central"`, which not every renderer supports.
+        let baseline = y + font_size * 0.35;
+        write!(
+            self.body,
+            r#"<text x="{x:.1}" y="{baseline:.1}" font-family="{}" font-size="{font_size:.1}" fill="{}" text-anchor="{}"{}>{}</text>"#,
+            escape(&self.theme.font_family),
+            escape(&self.theme.text),
+            match anchor {
+                Anchor::Start => "start",
+                Anchor::Middle => "middle",
+            },
+            if bold { r#" font-weight="bold""# } else { "" },
+            escape(text),
+        )
+        .ok();
+    }
+
+    /// Draws several lines of text, centered as a block on `(x, y)`.
+    pub fn label(&mut self, x: f32, y: f32, lines: &[String]) {
+        let line_height = line_height(self.theme.font_size);
+        let top = y - line_height * lines.len() as f32 / 2.;
+        for (ix, line) in lines.iter().enumerate() {
+            self.text(
+                x,
+                top + line_height * (ix as f32 + 0.5),
+                line,
+                Anchor::Middle,
+                false,
+            );
+        }
+    }
+
+    /// Draws a label on a background, so that it stays legible over lines.
+    pub fn edge_label(&mut self, x: f32, y: f32, lines: &[String]) {
+        let (width, height) = label_size(lines, self.theme.font_size);
+        let background = self.theme.label_background.clone();
+        self.rect(
+            Rect {
+                x: x - width / 2. - 2.,
+                y: y - height / 2.,
+                width: width + 4.,
+                height,
+            },
+            0.,
+            &background,
+            "none",
+            LineStyle::Solid,
+        );
+        self.label(x, y, lines);
+    }
+
+    pub fn finish(self, width: f32, height: f32) -> String {
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.1}" height="{height:.1}" viewBox="0 0 {width:.1} {height:.1}">{}</svg>"#,
+            self.body
+        )
+    }
+}
+
+fn stroke_attributes(style: LineStyle) -> &'static str {
+    match style {
+        LineStyle::Solid => r#" stroke-width="1.5""#,
+        LineStyle::Dashed => r#" stroke-width="1.5" stroke-dasharray="4 3""#,
+        LineStyle::Thick => r#" stroke-width="3""#,
+    }
+}
+
+fn format_points(points: &[(f32, f32)]) -> String {
+    let mut result = String::new();
+    for (ix, (x, y)) in points.iter().enumerate() {
+        if ix > 0 {
+            result.push(' ');
+        }
+        write!(result, "{x:.1},{y:.1}").ok();
+    }
+    result
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
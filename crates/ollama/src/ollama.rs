@@ -147,6 +147,28 @@ pub struct ChatOptions {
     pub top_p: Option<f32>,
 }
 
+#[derive(Serialize, Debug)]
+pub struct GenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    /// Text after the insertion point. Ollama formats `prompt` and `suffix`
+    /// with the model's fill-in-the-middle template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// Sends `prompt` to the model verbatim, without applying any template.
+    pub raw: bool,
+    pub stream: bool,
+    pub keep_alive: KeepAlive,
+    pub options: Option<ChatOptions>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GenerateResponse {
+    pub model: String,
+    pub response: String,
+    pub done: bool,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ChatResponseDelta {
     pub model: String,
@@ -312,6 +334,36 @@ pub async fn show_model(
     Ok(details)
 }
 
+/// Generates a single, non-streamed completion for a raw prompt.
+pub async fn generate(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    request: GenerateRequest,
+) -> Result<GenerateResponse> {
+    let uri = format!("{api_url}/api/generate");
+    let request = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .when_some(api_key, |builder, api_key| {
+            builder.header("Authorization", format!("Bearer {api_key}"))
+        })
+        .body(AsyncBody::from(serde_json::to_string(&request)?))?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to Ollama API: {} {}",
+        response.status(),
+        body,
+    );
+    serde_json::from_str(&body).context("Unable to parse generate response")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -515,6 +515,63 @@ pub async fn stream_completion(
     }
 }
 
+/// A request to the legacy `/completions` endpoint, which local servers such as
+/// llama.cpp and vLLM use for raw and fill-in-the-middle prompts.
+#[derive(Debug, Serialize)]
+pub struct TextCompletionRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    pub stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextCompletionResponse {
+    pub choices: Vec<TextCompletionChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextCompletionChoice {
+    pub text: String,
+    pub finish_reason: Option<String>,
+}
+
+pub async fn complete_text(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    request: TextCompletionRequest,
+) -> Result<TextCompletionResponse> {
+    let uri = format!("{api_url}/completions");
+    let mut request_builder = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(api_key) = api_key {
+        request_builder =
+            request_builder.header("Authorization", format!("Bearer {}", api_key.trim()));
+    }
+
+    let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "API request to {} failed with status {}: {}",
+        api_url,
+        response.status(),
+        body,
+    );
+    serde_json::from_str(&body).context("Unable to parse completion response")
+}
+
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum OpenAiEmbeddingModel {
     #[serde(rename = "text-embedding-3-small")]
@@ -247,6 +247,25 @@ pub fn debug_scenarios_file() -> &'static PathBuf {
     DEBUG_SCENARIOS_FILE.get_or_init(|| config_dir().join("debug.json"))
 }
 
+/// Returns the path to the `macros.json` file.
+pub fn keyboard_macros_file() -> &'static PathBuf {
+    static KEYBOARD_MACROS_FILE: OnceLock<PathBuf> = OnceLock::new();
+    KEYBOARD_MACROS_FILE.get_or_init(|| config_dir().join("macros.json"))
+}
+
+/// Returns the path to the `dictionary.txt` file, listing the words the user added to the
+/// spell checker.
+pub fn user_dictionary_file() -> &'static PathBuf {
+    static USER_DICTIONARY_FILE: OnceLock<PathBuf> = OnceLock::new();
+    USER_DICTIONARY_FILE.get_or_init(|| config_dir().join("dictionary.txt"))
+}
+
+/// Returns the path to the directory searched first for Hunspell dictionaries.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| config_dir().join("dictionaries"))
+}
+
 /// Returns the path to the extensions directory.
 ///
 /// This is where installed extensions are stored.
@@ -449,6 +468,14 @@ pub fn local_debug_file_relative_path() -> &'static RelPath {
     *CACHED
 }
 
+/// Returns the relative path to a `words.txt` file within a project, listing the words the
+/// spell checker accepts in that project.
+pub fn local_words_file_relative_path() -> &'static RelPath {
+    static CACHED: LazyLock<&'static RelPath> =
+        LazyLock::new(|| RelPath::unix(".zed/words.txt").unwrap());
+    *CACHED
+}
+
 /// Returns the relative path to a `.vscode/launch.json` file within a project.
 pub fn local_vscode_launch_file_relative_path() -> &'static RelPath {
     static CACHED: LazyLock<&'static RelPath> =
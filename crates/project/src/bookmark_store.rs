@@ -0,0 +1,404 @@
+//! Bookmarks on lines of files in the project.
+//!
+//! Bookmarks are anchored to buffer positions, so they follow the edits made in the editor. When a
+//! file changes on disk, its bookmarks are moved to the nearest line whose content matches the
+//! bookmarked line, as a reload can shift lines in ways anchors don't capture.
+use crate::{ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, SharedString, Subscription, Task};
+use language::{Buffer, BufferEvent, DiskState};
+use std::{path::Path, sync::Arc};
+use text::{BufferSnapshot, Point, ToPoint as _};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    pub position: text::Anchor,
+    pub label: Option<SharedString>,
+    /// The trimmed text of the bookmarked line, as of the last time the buffer was in sync with
+    /// the file on disk.
+    line_text: Arc<str>,
+}
+
+/// A bookmark as it is persisted between sessions.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub row: u32,
+    pub label: Option<Arc<str>>,
+    pub line_text: Arc<str>,
+}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    /// Sorted by position.
+    bookmarks: Vec<Bookmark>,
+    _subscription: Subscription,
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated,
+}
+
+pub struct BookmarkStore {
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+    worktree_store: Entity<WorktreeStore>,
+    buffer_store: Entity<BufferStore>,
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+impl BookmarkStore {
+    pub fn new(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
+        Self {
+            bookmarks: BTreeMap::default(),
+            worktree_store,
+            buffer_store,
+        }
+    }
+
+    pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .map(|file| file.worktree.read(cx).absolutize(&file.path))
+            .map(Arc::<Path>::from)
+    }
+
+    /// Removes the bookmark on the line of `position`, or adds one if there is none.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).text_snapshot();
+        let row = position.to_point(&snapshot).row;
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        let removed = self.bookmarks.get_mut(&abs_path).is_some_and(|file| {
+            let len_before = file.bookmarks.len();
+            file.bookmarks
+                .retain(|bookmark| bookmark.position.to_point(&snapshot).row != row);
+            file.bookmarks.len() != len_before
+        });
+        if !removed {
+            self.insert_bookmark(abs_path.clone(), buffer, row, None, cx);
+        }
+        self.bookmarks_changed(cx);
+    }
+
+    /// Sets the label of the bookmark on the line of `position`, adding the bookmark if needed.
+    pub fn set_label(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).text_snapshot();
+        let row = position.to_point(&snapshot).row;
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        let label = label.filter(|label| !label.trim().is_empty());
+        if let Some(bookmark) = self.bookmarks.get_mut(&abs_path).and_then(|file| {
+            file.bookmarks
+                .iter_mut()
+                .find(|bookmark| bookmark.position.to_point(&snapshot).row == row)
+        }) {
+            bookmark.label = label;
+        } else {
+            self.insert_bookmark(abs_path, buffer, row, label, cx);
+        }
+        self.bookmarks_changed(cx);
+    }
+
+    pub fn remove_bookmark(
+        &mut self,
+        abs_path: &Path,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(file) = self.bookmarks.get_mut(abs_path) {
+            file.bookmarks
+                .retain(|bookmark| bookmark.position != position);
+            self.bookmarks_changed(cx);
+        }
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        self.bookmarks.clear();
+        self.bookmarks_changed(cx);
+    }
+
+    /// Returns the bookmarks of the buffer, sorted by position.
+    pub fn bookmarks_for_buffer(&self, buffer: &Entity<Buffer>, cx: &App) -> &[Bookmark] {
+        Self::abs_path_from_buffer(buffer, cx)
+            .and_then(|abs_path| self.bookmarks.get(&abs_path))
+            .filter(|file| file.buffer == *buffer)
+            .map_or(&[], |file| file.bookmarks.as_slice())
+    }
+
+    /// Returns all bookmarks in the project, sorted by path and position.
+    pub fn all_bookmarks(&self) -> impl Iterator<Item = (&Arc<Path>, &Entity<Buffer>, &Bookmark)> {
+        self.bookmarks.iter().flat_map(|(abs_path, file)| {
+            file.bookmarks
+                .iter()
+                .map(move |bookmark| (abs_path, &file.buffer, bookmark))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    pub fn all_serialized_bookmarks(
+        &self,
+        cx: &App,
+    ) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        self.bookmarks
+            .iter()
+            .map(|(abs_path, file)| {
+                let snapshot = file.buffer.read(cx).text_snapshot();
+                let bookmarks = file
+                    .bookmarks
+                    .iter()
+                    .map(|bookmark| SerializedBookmark {
+                        row: bookmark.position.to_point(&snapshot).row,
+                        label: bookmark.label.as_ref().map(|label| label.as_ref().into()),
+                        line_text: bookmark.line_text.clone(),
+                    })
+                    .collect();
+                (abs_path.clone(), bookmarks)
+            })
+            .collect()
+    }
+
+    /// Replaces the bookmarks with the ones restored from a previous session, placing each one at
+    /// the line nearest to its row that still has the content it had back then.
+    pub fn with_serialized_bookmarks(
+        &self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let worktree_store = self.worktree_store.clone();
+        let buffer_store = self.buffer_store.clone();
+        cx.spawn(async move |this, cx| {
+            let mut new_bookmarks = BTreeMap::default();
+            for (abs_path, serialized_bookmarks) in bookmarks {
+                if serialized_bookmarks.is_empty() {
+                    continue;
+                }
+                let (worktree, path) = worktree_store
+                    .update(cx, |worktree_store, cx| {
+                        worktree_store.find_or_create_worktree(&abs_path, false, cx)
+                    })?
+                    .await?;
+                let buffer = buffer_store
+                    .update(cx, |buffer_store, cx| {
+                        let project_path = ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path,
+                        };
+                        buffer_store.open_buffer(project_path, cx)
+                    })?
+                    .await;
+                let Ok(buffer) = buffer else {
+                    log::error!("Could not open {abs_path:?} to restore its bookmarks");
+                    continue;
+                };
+                let file = this.update(cx, |_, cx| {
+                    let snapshot = buffer.read(cx).text_snapshot();
+                    let mut bookmarks = serialized_bookmarks
+                        .into_iter()
+                        .map(|serialized| {
+                            let row = find_line(&snapshot, serialized.row, &serialized.line_text);
+                            new_bookmark(&snapshot, row, serialized.label.map(Into::into))
+                        })
+                        .collect::<Vec<_>>();
+                    bookmarks.sort_by(|a, b| a.position.cmp(&b.position, &snapshot));
+                    bookmarks.dedup_by(|a, b| {
+                        a.position.to_point(&snapshot).row == b.position.to_point(&snapshot).row
+                    });
+                    BookmarksInFile::new(buffer, bookmarks, cx)
+                })?;
+                new_bookmarks.insert(abs_path, file);
+            }
+            this.update(cx, |this, cx| {
+                this.bookmarks = new_bookmarks;
+                cx.notify();
+            })
+        })
+    }
+
+    fn insert_bookmark(
+        &mut self,
+        abs_path: Arc<Path>,
+        buffer: Entity<Buffer>,
+        row: u32,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).text_snapshot();
+        let bookmark = new_bookmark(&snapshot, row, label);
+        let file = self
+            .bookmarks
+            .entry(abs_path)
+            .or_insert_with(|| BookmarksInFile::new(buffer, Vec::new(), cx));
+        let ix = file
+            .bookmarks
+            .partition_point(|other| other.position.cmp(&bookmark.position, &snapshot).is_lt());
+        file.bookmarks.insert(ix, bookmark);
+    }
+
+    fn bookmarks_changed(&mut self, cx: &mut Context<Self>) {
+        self.bookmarks.retain(|_, file| !file.bookmarks.is_empty());
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+
+    fn handle_buffer_event(
+        &mut self,
+        buffer: Entity<Buffer>,
+        event: &BufferEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let entity_id = buffer.entity_id();
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, file)| file.buffer.entity_id() == entity_id)
+            .map(|(abs_path, _)| abs_path.clone())
+        else {
+            return;
+        };
+
+        match event {
+            BufferEvent::Saved => {
+                let snapshot = buffer.read(cx).text_snapshot();
+                if let Some(file) = self.bookmarks.get_mut(&old_path) {
+                    for bookmark in &mut file.bookmarks {
+                        let row = bookmark.position.to_point(&snapshot).row;
+                        bookmark.line_text = line_text(&snapshot, row);
+                    }
+                }
+                self.bookmarks_changed(cx);
+            }
+            BufferEvent::Reloaded => {
+                let snapshot = buffer.read(cx).text_snapshot();
+                if let Some(file) = self.bookmarks.get_mut(&old_path) {
+                    for bookmark in &mut file.bookmarks {
+                        let row = bookmark.position.to_point(&snapshot).row;
+                        let row = find_line(&snapshot, row, &bookmark.line_text);
+                        *bookmark = new_bookmark(&snapshot, row, bookmark.label.take());
+                    }
+                    file.bookmarks
+                        .sort_by(|a, b| a.position.cmp(&b.position, &snapshot));
+                    file.bookmarks.dedup_by(|a, b| {
+                        a.position.to_point(&snapshot).row == b.position.to_point(&snapshot).row
+                    });
+                }
+                self.bookmarks_changed(cx);
+            }
+            BufferEvent::FileHandleChanged => {
+                if buffer
+                    .read(cx)
+                    .file()
+                    .is_none_or(|file| file.disk_state() == DiskState::Deleted)
+                {
+                    self.bookmarks.remove(&old_path);
+                    self.bookmarks_changed(cx);
+                } else if let Some(new_path) = Self::abs_path_from_buffer(&buffer, cx)
+                    && new_path != old_path
+                    && !self.bookmarks.contains_key(&new_path)
+                    && let Some(file) = self.bookmarks.remove(&old_path)
+                {
+                    self.bookmarks.insert(new_path, file);
+                    self.bookmarks_changed(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl BookmarksInFile {
+    fn new(
+        buffer: Entity<Buffer>,
+        bookmarks: Vec<Bookmark>,
+        cx: &mut Context<BookmarkStore>,
+    ) -> Self {
+        let subscription = cx.subscribe(&buffer, BookmarkStore::handle_buffer_event);
+        Self {
+            buffer,
+            bookmarks,
+            _subscription: subscription,
+        }
+    }
+}
+
+fn new_bookmark(snapshot: &BufferSnapshot, row: u32, label: Option<SharedString>) -> Bookmark {
+    Bookmark {
+        position: snapshot.anchor_after(Point::new(row, 0)),
+        label,
+        line_text: line_text(snapshot, row),
+    }
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32) -> Arc<str> {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>()
+        .trim()
+        .into()
+}
+
+/// Returns the row nearest to `row` whose trimmed text is `text`, or `row` clipped to the buffer
+/// if there is no such line. Blank lines are too ambiguous to be matched.
+fn find_line(snapshot: &BufferSnapshot, row: u32, text: &str) -> u32 {
+    let max_row = snapshot.max_point().row;
+    let row = row.min(max_row);
+    if text.is_empty() {
+        return row;
+    }
+    for distance in 0..=max_row {
+        let above = row.checked_sub(distance);
+        let below = row + distance;
+        if above.is_none() && below > max_row {
+            break;
+        }
+        if let Some(above) = above
+            && *line_text(snapshot, above) == *text
+        {
+            return above;
+        }
+        if below <= max_row && *line_text(snapshot, below) == *text {
+            return below;
+        }
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer as TextBuffer, BufferId};
+
+    #[test]
+    fn test_find_line() {
+        let buffer = TextBuffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "fn a() {}\n\nfn b() {}\n    fn c() {}\nfn b() {}\n",
+        );
+        let snapshot = &buffer.snapshot();
+
+        assert_eq!(find_line(snapshot, 2, "fn b() {}"), 2);
+        // The nearest matching line wins, preferring lines above on ties.
+        assert_eq!(find_line(snapshot, 3, "fn b() {}"), 2);
+        assert_eq!(find_line(snapshot, 0, "fn c() {}"), 3);
+        // Lines that no longer exist keep their row, clipped to the buffer.
+        assert_eq!(find_line(snapshot, 1, "fn d() {}"), 1);
+        assert_eq!(find_line(snapshot, 10, "fn d() {}"), 5);
+        assert_eq!(find_line(snapshot, 4, ""), 4);
+    }
+}
@@ -0,0 +1,548 @@
+//! Line coverage loaded from LCOV and Cobertura XML reports.
+//!
+//! The reports configured in the `coverage` settings are looked up relative to the root of each
+//! local worktree and reloaded whenever they change on disk, so that rerunning a coverage task
+//! refreshes what the editor and the project panel display.
+use crate::{
+    ProjectPath,
+    project_settings::ProjectSettings,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
+};
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, HashMap, HashSet};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Subscription, Task};
+use settings::{Settings as _, SettingsLocation, SettingsStore};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use util::{ResultExt as _, rel_path::RelPath};
+use worktree::Worktree;
+
+const REPORT_WATCH_LATENCY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCoverage {
+    Covered,
+    Uncovered,
+    /// The line was executed, but not all of its branches were taken.
+    Partial,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileCoverage {
+    /// The coverage of each line containing code, keyed by zero-based row.
+    pub lines: BTreeMap<u32, LineCoverage>,
+}
+
+impl FileCoverage {
+    /// Combines coverage of the same file from several reports: a line is covered if any report
+    /// covers it.
+    fn merge(&mut self, other: &FileCoverage) {
+        for (row, coverage) in &other.lines {
+            self.lines
+                .entry(*row)
+                .and_modify(|existing| {
+                    *existing = match (*existing, *coverage) {
+                        (LineCoverage::Covered, _) | (_, LineCoverage::Covered) => {
+                            LineCoverage::Covered
+                        }
+                        (LineCoverage::Partial, _) | (_, LineCoverage::Partial) => {
+                            LineCoverage::Partial
+                        }
+                        (LineCoverage::Uncovered, LineCoverage::Uncovered) => {
+                            LineCoverage::Uncovered
+                        }
+                    }
+                })
+                .or_insert(*coverage);
+        }
+    }
+
+    /// The percentage of lines that were executed at least once.
+    pub fn percentage(&self) -> Option<f32> {
+        if self.lines.is_empty() {
+            return None;
+        }
+        let executed = self
+            .lines
+            .values()
+            .filter(|coverage| **coverage != LineCoverage::Uncovered)
+            .count();
+        Some(executed as f32 * 100. / self.lines.len() as f32)
+    }
+}
+
+pub enum CoverageStoreEvent {
+    CoverageUpdated,
+}
+
+struct LoadedReport {
+    files: HashMap<ProjectPath, FileCoverage>,
+    _watch: Task<()>,
+}
+
+pub struct CoverageStore {
+    fs: Arc<dyn Fs>,
+    worktree_store: Entity<WorktreeStore>,
+    reports: HashMap<PathBuf, LoadedReport>,
+    coverage: HashMap<ProjectPath, FileCoverage>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl EventEmitter<CoverageStoreEvent> for CoverageStore {}
+
+impl CoverageStore {
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        worktree_store: Entity<WorktreeStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscriptions = [
+            cx.subscribe(&worktree_store, |this, _, event, cx| match event {
+                WorktreeStoreEvent::WorktreeAdded(_) | WorktreeStoreEvent::WorktreeRemoved(..) => {
+                    this.watch_reports(cx)
+                }
+                _ => {}
+            }),
+            cx.observe_global::<SettingsStore>(|this, cx| this.watch_reports(cx)),
+        ];
+        let mut this = Self {
+            fs,
+            worktree_store,
+            reports: HashMap::default(),
+            coverage: HashMap::default(),
+            _subscriptions: subscriptions,
+        };
+        this.watch_reports(cx);
+        this
+    }
+
+    pub fn coverage_for_path(&self, path: &ProjectPath) -> Option<&FileCoverage> {
+        self.coverage.get(path)
+    }
+
+    /// The path that coverage tasks should write their report to, so that it's picked up as
+    /// soon as it changes.
+    pub fn report_path_for_worktree(worktree: &Worktree, cx: &App) -> Option<PathBuf> {
+        let worktree = worktree.as_local()?;
+        let settings = ProjectSettings::get(
+            Some(SettingsLocation {
+                worktree_id: worktree.id(),
+                path: RelPath::empty(),
+            }),
+            cx,
+        );
+        let report = settings.coverage.reports.first()?;
+        Some(worktree.abs_path().join(report))
+    }
+
+    /// Starts watching the reports configured for each local worktree, and stops watching the
+    /// ones that are no longer configured.
+    fn watch_reports(&mut self, cx: &mut Context<Self>) {
+        let report_paths = self
+            .worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx).as_local()?;
+                let settings = ProjectSettings::get(
+                    Some(SettingsLocation {
+                        worktree_id: worktree.id(),
+                        path: RelPath::empty(),
+                    }),
+                    cx,
+                );
+                Some(
+                    settings
+                        .coverage
+                        .reports
+                        .iter()
+                        .map(|report| worktree.abs_path().join(report))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .flatten()
+            .collect::<HashSet<_>>();
+
+        let report_count = self.reports.len();
+        self.reports.retain(|path, _| report_paths.contains(path));
+        let reports_removed = self.reports.len() != report_count;
+        for path in report_paths {
+            if !self.reports.contains_key(&path) {
+                let watch = self.watch_report(path.clone(), cx);
+                self.reports.insert(
+                    path,
+                    LoadedReport {
+                        files: HashMap::default(),
+                        _watch: watch,
+                    },
+                );
+            }
+        }
+        if reports_removed {
+            self.update_coverage(cx);
+        }
+    }
+
+    fn watch_report(&self, path: PathBuf, cx: &mut Context<Self>) -> Task<()> {
+        let fs = self.fs.clone();
+        cx.spawn(async move |this, cx| {
+            let (mut events, _watcher) = fs.watch(&path, REPORT_WATCH_LATENCY).await;
+            loop {
+                // A missing report most likely hasn't been generated yet.
+                let report = match fs.load(&path).await {
+                    Ok(text) => cx
+                        .background_spawn(async move { parse_report(&text) })
+                        .await
+                        .with_context(|| format!("parsing coverage report {path:?}"))
+                        .log_err(),
+                    Err(_) => None,
+                };
+                if this
+                    .update(cx, |this, cx| this.set_report(&path, report, cx))
+                    .is_err()
+                {
+                    return;
+                }
+                // The watcher may also report changes to other files in the report's directory.
+                loop {
+                    let Some(events) = events.next().await else {
+                        return;
+                    };
+                    if events.iter().any(|event| event.path == path) {
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    fn set_report(&mut self, path: &Path, report: Option<Report>, cx: &mut Context<Self>) {
+        let mut files = HashMap::<ProjectPath, FileCoverage>::default();
+        if let Some(report) = report {
+            let report_dir = path.parent().unwrap_or(path);
+            for (file_path, coverage) in report.files {
+                let Some(project_path) =
+                    self.resolve_path(&file_path, report_dir, &report.sources, cx)
+                else {
+                    continue;
+                };
+                files.entry(project_path).or_default().merge(&coverage);
+            }
+        }
+        let Some(loaded_report) = self.reports.get_mut(path) else {
+            return;
+        };
+        if loaded_report.files.is_empty() && files.is_empty() {
+            return;
+        }
+        loaded_report.files = files;
+        self.update_coverage(cx);
+    }
+
+    /// Finds the project file a report refers to. Relative paths are resolved against the
+    /// report's source directories, then against the report's directory and its ancestors,
+    /// since tools write paths relative to where they were started.
+    fn resolve_path(
+        &self,
+        path: &Path,
+        report_dir: &Path,
+        sources: &[PathBuf],
+        cx: &App,
+    ) -> Option<ProjectPath> {
+        let worktree_store = self.worktree_store.read(cx);
+        if path.is_absolute() {
+            let (worktree, path) = worktree_store.find_worktree(path, cx)?;
+            return Some(ProjectPath {
+                worktree_id: worktree.read(cx).id(),
+                path,
+            });
+        }
+        sources
+            .iter()
+            .map(|source| report_dir.join(source))
+            .chain(report_dir.ancestors().map(Path::to_path_buf))
+            .find_map(|base| {
+                let (worktree, path) = worktree_store.find_worktree(base.join(path), cx)?;
+                let worktree = worktree.read(cx);
+                worktree
+                    .entry_for_path(&path)
+                    .is_some_and(|entry| entry.is_file())
+                    .then(|| ProjectPath {
+                        worktree_id: worktree.id(),
+                        path,
+                    })
+            })
+    }
+
+    fn update_coverage(&mut self, cx: &mut Context<Self>) {
+        self.coverage.clear();
+        for report in self.reports.values() {
+            for (path, coverage) in &report.files {
+                self.coverage
+                    .entry(path.clone())
+                    .or_default()
+                    .merge(coverage);
+            }
+        }
+        cx.emit(CoverageStoreEvent::CoverageUpdated);
+        cx.notify();
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Report {
+    /// Directories that relative file paths in the report may be relative to.
+    sources: Vec<PathBuf>,
+    files: Vec<(PathBuf, FileCoverage)>,
+}
+
+fn parse_report(text: &str) -> Result<Report> {
+    if text.trim_start().starts_with('<') {
+        parse_cobertura(text)
+    } else {
+        Ok(parse_lcov(text))
+    }
+}
+
+fn parse_lcov(text: &str) -> Report {
+    let mut report = Report::default();
+    let mut current_file = None;
+    let mut hits = BTreeMap::<u32, u64>::new();
+    let mut branches = HashMap::<u32, (usize, usize)>::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            current_file = Some(PathBuf::from(path));
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let mut fields = data.split(',');
+            if let (Some(Ok(line_number)), Some(Ok(count))) = (
+                fields.next().map(str::parse::<u32>),
+                fields.next().map(str::parse::<u64>),
+            ) {
+                *hits.entry(line_number).or_default() += count;
+            }
+        } else if let Some(data) = line.strip_prefix("BRDA:") {
+            let fields = data.split(',').collect::<Vec<_>>();
+            if let [line_number, _, _, taken] = fields.as_slice()
+                && let Ok(line_number) = line_number.parse::<u32>()
+            {
+                let (total, taken_count) = branches.entry(line_number).or_default();
+                *total += 1;
+                if taken.parse::<u64>().is_ok_and(|taken| taken > 0) {
+                    *taken_count += 1;
+                }
+            }
+        } else if line == "end_of_record" {
+            if let Some(path) = current_file.take() {
+                let lines = hits
+                    .iter()
+                    .filter(|(line_number, _)| **line_number > 0)
+                    .map(|(line_number, count)| {
+                        let coverage = if *count == 0 {
+                            LineCoverage::Uncovered
+                        } else if branches
+                            .get(line_number)
+                            .is_some_and(|(total, taken)| taken < total)
+                        {
+                            LineCoverage::Partial
+                        } else {
+                            LineCoverage::Covered
+                        };
+                        (line_number - 1, coverage)
+                    })
+                    .collect();
+                report.files.push((path, FileCoverage { lines }));
+            }
+            hits.clear();
+            branches.clear();
+        }
+    }
+    report
+}
+
+fn parse_cobertura(text: &str) -> Result<Report> {
+    let document = roxmltree::Document::parse(text)?;
+    let mut report = Report {
+        sources: document
+            .descendants()
+            .filter(|node| node.has_tag_name("source"))
+            .filter_map(|node| node.text())
+            .map(|source| PathBuf::from(source.trim()))
+            .collect(),
+        files: Vec::new(),
+    };
+    for class in document
+        .descendants()
+        .filter(|node| node.has_tag_name("class"))
+    {
+        let Some(filename) = class.attribute("filename") else {
+            continue;
+        };
+        let mut coverage = FileCoverage::default();
+        // Class elements also list the lines of their methods, which are duplicates.
+        let lines = class
+            .children()
+            .filter(|node| node.has_tag_name("lines"))
+            .flat_map(|lines| lines.children())
+            .filter(|node| node.has_tag_name("line"));
+        for line in lines {
+            let Some(line_number) = line
+                .attribute("number")
+                .and_then(|number| number.parse::<u32>().ok())
+                .filter(|number| *number > 0)
+            else {
+                continue;
+            };
+            let hits = line
+                .attribute("hits")
+                .and_then(|hits| hits.parse::<u64>().ok())
+                .unwrap_or(0);
+            let partial = line.attribute("branch") == Some("true")
+                && line
+                    .attribute("condition-coverage")
+                    .is_some_and(|condition| !condition.starts_with("100%"));
+            let line_coverage = if hits == 0 {
+                LineCoverage::Uncovered
+            } else if partial {
+                LineCoverage::Partial
+            } else {
+                LineCoverage::Covered
+            };
+            coverage.merge(&FileCoverage {
+                lines: BTreeMap::from_iter([(line_number - 1, line_coverage)]),
+            });
+        }
+        report.files.push((PathBuf::from(filename), coverage));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_lcov() {
+        let report = parse_report(
+            "TN:\n\
+             SF:/project/src/lib.rs\n\
+             FN:1,add\n\
+             DA:1,3\n\
+             DA:2,3\n\
+             DA:3,0\n\
+             BRDA:2,0,0,3\n\
+             BRDA:2,0,1,-\n\
+             LF:3\n\
+             LH:2\n\
+             end_of_record\n\
+             SF:src/main.rs\n\
+             DA:4,1\n\
+             end_of_record\n",
+        )
+        .unwrap();
+        assert_eq!(
+            report,
+            Report {
+                sources: Vec::new(),
+                files: vec![
+                    (
+                        PathBuf::from("/project/src/lib.rs"),
+                        FileCoverage {
+                            lines: BTreeMap::from_iter([
+                                (0, LineCoverage::Covered),
+                                (1, LineCoverage::Partial),
+                                (2, LineCoverage::Uncovered),
+                            ])
+                        }
+                    ),
+                    (
+                        PathBuf::from("src/main.rs"),
+                        FileCoverage {
+                            lines: BTreeMap::from_iter([(3, LineCoverage::Covered)])
+                        }
+                    ),
+                ]
+            }
+        );
+        assert_eq!(report.files[0].1.percentage(), Some(200. / 3.));
+    }
+
+    #[test]
+    fn test_parse_cobertura() {
+        let report = parse_report(
+            r#"<?xml version="1.0" ?>
+            <coverage version="7.4.0" line-rate="0.75" branch-rate="0.5">
+                <sources>
+                    <source>/project/src</source>
+                </sources>
+                <packages>
+                    <package name="app" line-rate="0.75">
+                        <classes>
+                            <class name="util.py" filename="app/util.py" line-rate="0.75">
+                                <methods/>
+                                <lines>
+                                    <line number="1" hits="1"/>
+                                    <line number="2" hits="1" branch="true" condition-coverage="50% (1/2)" missing-branches="4"/>
+                                    <line number="3" hits="1" branch="true" condition-coverage="100% (2/2)"/>
+                                    <line number="4" hits="0"/>
+                                </lines>
+                            </class>
+                        </classes>
+                    </package>
+                </packages>
+            </coverage>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            report,
+            Report {
+                sources: vec![PathBuf::from("/project/src")],
+                files: vec![(
+                    PathBuf::from("app/util.py"),
+                    FileCoverage {
+                        lines: BTreeMap::from_iter([
+                            (0, LineCoverage::Covered),
+                            (1, LineCoverage::Partial),
+                            (2, LineCoverage::Covered),
+                            (3, LineCoverage::Uncovered),
+                        ])
+                    }
+                )]
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_file_coverage() {
+        let mut coverage = FileCoverage {
+            lines: BTreeMap::from_iter([
+                (0, LineCoverage::Uncovered),
+                (1, LineCoverage::Uncovered),
+                (2, LineCoverage::Covered),
+            ]),
+        };
+        coverage.merge(&FileCoverage {
+            lines: BTreeMap::from_iter([
+                (0, LineCoverage::Partial),
+                (2, LineCoverage::Uncovered),
+                (3, LineCoverage::Uncovered),
+            ]),
+        });
+        assert_eq!(
+            coverage.lines,
+            BTreeMap::from_iter([
+                (0, LineCoverage::Partial),
+                (1, LineCoverage::Uncovered),
+                (2, LineCoverage::Covered),
+                (3, LineCoverage::Uncovered),
+            ])
+        );
+        assert_eq!(coverage.percentage(), Some(50.));
+    }
+}
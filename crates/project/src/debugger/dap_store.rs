@@ -5,7 +5,7 @@ use super::{
     session::{self, Session, SessionStateEvent},
 };
 use crate::{
-    InlayHint, InlayHintLabel, ProjectEnvironment, ResolveState,
+    InlayHint, InlayHintLabel, LspStore, ProjectEnvironment, ResolveState,
     debugger::session::SessionQuirks,
     project_settings::{DapBinary, ProjectSettings},
     worktree_store::WorktreeStore,
@@ -28,7 +28,9 @@ use futures::{
     channel::mpsc::{self, UnboundedSender},
     future::{Shared, join_all},
 };
-use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task};
+use gpui::{
+    App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task, WeakEntity,
+};
 use http_client::HttpClient;
 use language::{Buffer, LanguageToolchainStore};
 use node_runtime::NodeRuntime;
@@ -78,6 +80,7 @@ pub struct LocalDapStore {
     http_client: Arc<dyn HttpClient>,
     environment: Entity<ProjectEnvironment>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
+    lsp_store: WeakEntity<LspStore>,
 }
 
 pub struct RemoteDapStore {
@@ -119,6 +122,10 @@ impl DapStore {
             registry.add_locator(Arc::new(locators::go::GoLocator {}));
             registry.add_locator(Arc::new(locators::node::NodeLocator));
             registry.add_locator(Arc::new(locators::python::PythonLocator));
+            registry.add_locator(Arc::new(locators::ruby::RubyLocator));
+            registry.add_locator(Arc::new(locators::php::PhpLocator));
+            registry.add_locator(Arc::new(locators::java::JavaLocator));
+            registry.add_locator(Arc::new(locators::dotnet::DotNetLocator));
         });
         client.add_entity_request_handler(Self::handle_run_debug_locator);
         client.add_entity_request_handler(Self::handle_get_debug_adapter_binary);
@@ -142,11 +149,19 @@ impl DapStore {
             http_client,
             node_runtime,
             toolchain_store,
+            lsp_store: WeakEntity::new_invalid(),
         });
 
         Self::new(mode, breakpoint_store, worktree_store, cx)
     }
 
+    /// Gives debug adapters access to the project's language servers, for adapters hosted by one.
+    pub fn set_lsp_store(&mut self, lsp_store: WeakEntity<LspStore>) {
+        if let DapStoreMode::Local(local) = &mut self.mode {
+            local.lsp_store = lsp_store;
+        }
+    }
+
     pub fn new_remote(
         project_id: u64,
         remote_client: Entity<RemoteClient>,
@@ -538,6 +553,7 @@ impl DapStore {
             local_store.environment.update(cx, |env, cx| {
                 env.get_worktree_environment(worktree.clone(), cx)
             }),
+            local_store.lsp_store.clone(),
         ))
     }
 
@@ -870,6 +886,7 @@ pub struct DapAdapterDelegate {
     http_client: Arc<dyn HttpClient>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
     load_shell_env_task: Shared<Task<Option<HashMap<String, String>>>>,
+    lsp_store: WeakEntity<LspStore>,
 }
 
 impl DapAdapterDelegate {
@@ -881,6 +898,7 @@ impl DapAdapterDelegate {
         http_client: Arc<dyn HttpClient>,
         toolchain_store: Arc<dyn LanguageToolchainStore>,
         load_shell_env_task: Shared<Task<Option<HashMap<String, String>>>>,
+        lsp_store: WeakEntity<LspStore>,
     ) -> Self {
         Self {
             fs,
@@ -890,6 +908,7 @@ impl DapAdapterDelegate {
             node_runtime,
             toolchain_store,
             load_shell_env_task,
+            lsp_store,
         }
     }
 }
@@ -953,4 +972,44 @@ impl dap::adapters::DapDelegate for DapAdapterDelegate {
 
         self.fs.load(&abs_path).await
     }
+
+    fn execute_language_server_command(
+        &self,
+        server_name: &str,
+        command: String,
+        arguments: Vec<serde_json::Value>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<serde_json::Value>> {
+        let worktree_id = self.worktree.id();
+        let server = self.lsp_store.read_with(cx, |lsp_store, _| {
+            lsp_store
+                .language_server_statuses()
+                .find(|(_, status)| {
+                    status.name.0.as_ref() == server_name
+                        && status.worktree.is_none_or(|id| id == worktree_id)
+                })
+                .and_then(|(server_id, _)| lsp_store.language_server_for_id(server_id))
+        });
+        let server = match server {
+            Ok(Some(server)) => server,
+            Ok(None) => {
+                return Task::ready(Err(anyhow!(
+                    "language server {server_name} is not running for this worktree"
+                )));
+            }
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.background_spawn(async move {
+            server
+                .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                    command,
+                    arguments,
+                    ..lsp::ExecuteCommandParams::default()
+                })
+                .await
+                .into_response()
+                .map(Option::unwrap_or_default)
+        })
+    }
 }
@@ -1,4 +1,8 @@
 pub(crate) mod cargo;
+pub(crate) mod dotnet;
 pub(crate) mod go;
+pub(crate) mod java;
 pub(crate) mod node;
+pub(crate) mod php;
 pub(crate) mod python;
+pub(crate) mod ruby;
@@ -0,0 +1,162 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::SharedString;
+use smol::{io::AsyncReadExt, process::Stdio};
+use task::{BuildTaskDefinition, DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub(crate) struct DotNetLocator;
+
+/// Translates `dotnet build` arguments into the ones `dotnet msbuild` needs to
+/// evaluate the same project and configuration.
+fn msbuild_args(build_args: &[String]) -> Vec<String> {
+    let mut msbuild_args = Vec::new();
+    let mut args = build_args
+        .iter()
+        .skip(1)
+        .take_while(|arg| arg.as_str() != "--");
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--configuration" => {
+                if let Some(configuration) = args.next() {
+                    msbuild_args.push(format!("-property:Configuration={configuration}"));
+                }
+            }
+            "-f" | "--framework" => {
+                if let Some(framework) = args.next() {
+                    msbuild_args.push(format!("-property:TargetFramework={framework}"));
+                }
+            }
+            "--project" => msbuild_args.extend(args.next().cloned()),
+            _ if !arg.starts_with('-') => msbuild_args.push(arg.clone()),
+            _ => {}
+        }
+    }
+    msbuild_args
+}
+
+#[async_trait]
+impl DapLocator for DotNetLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("dotnet-locator")
+    }
+
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "netcoredbg"
+            || build_config.command != "dotnet"
+            || build_config
+                .args
+                .first()
+                .is_none_or(|action| action != "run")
+        {
+            return None;
+        }
+
+        // `dotnet run` builds and runs in one go; debugging needs the build on its own,
+        // with the program's arguments passed to the debugger instead.
+        let mut task_template = build_config.clone();
+        task_template.args[0] = "build".to_owned();
+        let program_args = match task_template.args.iter().position(|arg| arg == "--") {
+            Some(delimiter) => task_template.args.split_off(delimiter)[1..].to_vec(),
+            None => Vec::new(),
+        };
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: Some(BuildTaskDefinition::Template {
+                task_template,
+                locator_name: Some(self.name()),
+            }),
+            config: serde_json::json!({ "args": program_args }),
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, build_config: SpawnInTerminal) -> Result<DebugRequest> {
+        let cwd = build_config
+            .cwd
+            .clone()
+            .context("Couldn't get cwd from debug config which is needed for locators")?;
+
+        let mut child = util::command::new_smol_command("dotnet")
+            .arg("msbuild")
+            .args(msbuild_args(&build_config.args))
+            .arg("-getProperty:TargetPath")
+            .envs(build_config.env.iter().map(|(k, v)| (k.clone(), v.clone())))
+            .current_dir(&cwd)
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let mut output = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            stdout.read_to_string(&mut output).await?;
+        }
+        let status = child.status().await?;
+        anyhow::ensure!(status.success(), "dotnet msbuild command failed");
+
+        let program = output.trim();
+        anyhow::ensure!(
+            !program.is_empty(),
+            "Couldn't get the program path in dotnet locator"
+        );
+
+        Ok(DebugRequest::Launch(task::LaunchRequest {
+            program: program.to_owned(),
+            cwd: Some(cwd),
+            args: Vec::new(),
+            env: build_config.env.into_iter().collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_dotnet_locator() {
+        let adapter = DebugAdapterName("netcoredbg".into());
+        let build_task = TaskTemplate {
+            label: "dotnet run".into(),
+            command: "dotnet".into(),
+            args: vec![
+                "run".into(),
+                "--project".into(),
+                "src/App/App.csproj".into(),
+                "-c".into(),
+                "Release".into(),
+                "--".into(),
+                "--port".into(),
+                "8080".into(),
+            ],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..TaskTemplate::default()
+        };
+
+        let scenario = DotNetLocator
+            .create_scenario(&build_task, "dotnet run", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        let Some(BuildTaskDefinition::Template { task_template, .. }) = scenario.build else {
+            panic!("Expected a build task");
+        };
+        assert_eq!(
+            task_template.args,
+            ["build", "--project", "src/App/App.csproj", "-c", "Release"]
+        );
+        assert_eq!(
+            scenario.config,
+            serde_json::json!({ "args": ["--port", "8080"] })
+        );
+        assert_eq!(
+            msbuild_args(&task_template.args),
+            ["src/App/App.csproj", "-property:Configuration=Release"]
+        );
+    }
+}
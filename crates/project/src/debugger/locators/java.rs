@@ -0,0 +1,153 @@
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::SharedString;
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub(crate) struct JavaLocator;
+
+#[async_trait]
+impl DapLocator for JavaLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("Java")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "Java" || build_config.command != "java" {
+            return None;
+        }
+
+        let mut vm_args = Vec::new();
+        let mut class_paths = None;
+        let mut args = build_config.args.iter();
+        let main_class = loop {
+            let arg = args.next()?;
+            match arg.as_str() {
+                "-cp" | "-classpath" | "--class-path" => {
+                    class_paths = Some(args.next()?.clone());
+                }
+                // Executable jars and single-file programs have no main class for java-debug to launch.
+                "-jar" | "--source" => return None,
+                _ if arg.starts_with('-') => vm_args.push(arg.clone()),
+                _ => break arg.clone(),
+            }
+        };
+        if main_class.ends_with(".java") {
+            return None;
+        }
+
+        let mut config = serde_json::json!({
+            "request": "launch",
+            "mainClass": main_class,
+            "args": args.cloned().collect::<Vec<_>>(),
+            "vmArgs": vm_args,
+            "cwd": build_config.cwd.clone(),
+        });
+        if let Some(config_obj) = config.as_object_mut() {
+            if let Some(class_paths) = class_paths {
+                config_obj.insert(
+                    "classPaths".to_string(),
+                    std::env::split_paths(&class_paths)
+                        .map(|path| path.to_string_lossy().into_owned())
+                        .collect::<Vec<_>>()
+                        .into(),
+                );
+            }
+            if !build_config.env.is_empty() {
+                config_obj.insert(
+                    "env".to_string(),
+                    serde_json::to_value(&build_config.env).ok()?,
+                );
+            }
+        }
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal) -> Result<DebugRequest> {
+        bail!("Java locator should not require DapLocator::run to be ran");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_java_locator() {
+        let adapter = DebugAdapterName("Java".into());
+        let class_paths = std::env::join_paths(["target/classes", "lib/dep.jar"])
+            .unwrap()
+            .into_string()
+            .unwrap();
+        let task = TaskTemplate {
+            label: "run Main".into(),
+            command: "java".into(),
+            args: vec![
+                "-Xmx1g".into(),
+                "-cp".into(),
+                class_paths,
+                "com.example.Main".into(),
+                "--port".into(),
+                "8080".into(),
+            ],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..TaskTemplate::default()
+        };
+        let scenario = JavaLocator
+            .create_scenario(&task, "run Main", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "mainClass": "com.example.Main",
+                "args": ["--port", "8080"],
+                "vmArgs": ["-Xmx1g"],
+                "classPaths": ["target/classes", "lib/dep.jar"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+            })
+        );
+
+        for args in [
+            vec!["-jar".to_string(), "app.jar".to_string()],
+            vec!["Main.java".to_string()],
+        ] {
+            let task = TaskTemplate {
+                label: "run".into(),
+                command: "java".into(),
+                args,
+                ..TaskTemplate::default()
+            };
+            assert!(
+                JavaLocator
+                    .create_scenario(&task, "run", &adapter)
+                    .await
+                    .is_none()
+            );
+        }
+
+        assert!(
+            JavaLocator
+                .create_scenario(&task, "run Main", &DebugAdapterName("GDB".into()))
+                .await
+                .is_none()
+        );
+    }
+}
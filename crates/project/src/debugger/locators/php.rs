@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::SharedString;
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub(crate) struct PhpLocator;
+
+/// Options of the `php` executable whose value is the next argument when it isn't
+/// attached to the option, as in `-d memory_limit=1G` rather than `-dmemory_limit=1G`.
+const OPTIONS_WITH_VALUE: &[&str] = &["-c", "-d", "-r", "-z"];
+
+/// Finds the script in the arguments of a `php` command, skipping its options.
+fn program_position(args: &[String]) -> Option<usize> {
+    let mut args = args.iter().enumerate();
+    while let Some((ix, arg)) = args.next() {
+        if arg == "--" {
+            return args.next().map(|(ix, _)| ix);
+        } else if OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return Some(ix);
+        }
+    }
+    None
+}
+
+#[async_trait]
+impl DapLocator for PhpLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("PHP")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "Xdebug" {
+            return None;
+        }
+
+        let command_name = Path::new(&build_config.command).file_name()?.to_str()?;
+        let (runtime_args, program, args) = match command_name {
+            "php" => {
+                let program_position = program_position(&build_config.args)?;
+                // `php -r` runs a snippet rather than a file.
+                if build_config.args[..program_position]
+                    .iter()
+                    .any(|arg| arg == "-r")
+                {
+                    return None;
+                }
+                (
+                    build_config.args[..program_position].to_vec(),
+                    build_config.args[program_position].clone(),
+                    build_config.args[program_position + 1..].to_vec(),
+                )
+            }
+            // Test runners are PHP scripts, so they can be run by the PHP binary the adapter launches.
+            "phpunit" | "pest" | "artisan" => (
+                Vec::new(),
+                build_config.command.clone(),
+                build_config.args.clone(),
+            ),
+            _ => return None,
+        };
+
+        let mut config = serde_json::json!({
+            "request": "launch",
+            "program": program,
+            "args": args,
+            "cwd": build_config.cwd.clone(),
+        });
+        if let Some(config_obj) = config.as_object_mut() {
+            if !runtime_args.is_empty() {
+                config_obj.insert("runtimeArgs".to_string(), runtime_args.into());
+            }
+            if !build_config.env.is_empty() {
+                config_obj.insert(
+                    "env".to_string(),
+                    serde_json::to_value(&build_config.env).ok()?,
+                );
+            }
+        }
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal) -> Result<DebugRequest> {
+        bail!("PHP locator should not require DapLocator::run to be ran");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_php_locator() {
+        let adapter = DebugAdapterName("Xdebug".into());
+        let script_task = TaskTemplate {
+            label: "run $ZED_FILE".into(),
+            command: "php".into(),
+            args: vec![
+                "-d".into(),
+                "memory_limit=1G".into(),
+                "$ZED_FILE".into(),
+                "--verbose".into(),
+            ],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..TaskTemplate::default()
+        };
+        let scenario = PhpLocator
+            .create_scenario(&script_task, "run index.php", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "program": "$ZED_FILE",
+                "args": ["--verbose"],
+                "runtimeArgs": ["-d", "memory_limit=1G"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+            })
+        );
+
+        let test_task = TaskTemplate {
+            label: "test $ZED_SYMBOL".into(),
+            command: "vendor/bin/phpunit".into(),
+            args: vec!["--filter".into(), "$ZED_SYMBOL".into()],
+            ..TaskTemplate::default()
+        };
+        let scenario = PhpLocator
+            .create_scenario(&test_task, "test UserTest", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(scenario.config["program"], json!("vendor/bin/phpunit"));
+        assert_eq!(scenario.config["args"], json!(["--filter", "$ZED_SYMBOL"]));
+        assert!(scenario.config.get("runtimeArgs").is_none());
+
+        let snippet_task = TaskTemplate {
+            label: "run snippet".into(),
+            command: "php".into(),
+            args: vec!["-r".into(), "echo 1;".into(), "extra".into()],
+            ..TaskTemplate::default()
+        };
+        assert!(
+            PhpLocator
+                .create_scenario(&snippet_task, "run snippet", &adapter)
+                .await
+                .is_none()
+        );
+
+        assert!(
+            PhpLocator
+                .create_scenario(
+                    &script_task,
+                    "run index.php",
+                    &DebugAdapterName("GDB".into())
+                )
+                .await
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::SharedString;
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub(crate) struct RubyLocator;
+
+/// Options of the `ruby` executable whose value is the next argument when it isn't
+/// attached to the option, as in `-I lib` rather than `-Ilib`.
+const OPTIONS_WITH_VALUE: &[&str] = &["-C", "-E", "-I", "-e", "-r"];
+
+/// Finds the script in the arguments of a `ruby` command, skipping its options.
+fn script_position(args: &[String]) -> Option<usize> {
+    let mut args = args.iter().enumerate();
+    while let Some((ix, arg)) = args.next() {
+        if arg == "--" {
+            return args.next().map(|(ix, _)| ix);
+        } else if OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return Some(ix);
+        }
+    }
+    None
+}
+
+#[async_trait]
+impl DapLocator for RubyLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("Ruby")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "rdbg" {
+            return None;
+        }
+
+        let mut config = match build_config.command.as_str() {
+            "ruby" => {
+                let script_position = script_position(&build_config.args)?;
+                // `ruby -e` runs a snippet, which rdbg can't load as a script.
+                if build_config.args[..script_position]
+                    .iter()
+                    .any(|arg| arg == "-e")
+                {
+                    return None;
+                }
+                serde_json::json!({
+                    "request": "launch",
+                    "script": build_config.args[script_position].clone(),
+                    "args": build_config.args[script_position + 1..].to_vec(),
+                })
+            }
+            "bundle" | "rails" | "rake" | "rspec" => serde_json::json!({
+                "request": "launch",
+                "command": build_config.command.clone(),
+                "args": build_config.args.clone(),
+            }),
+            _ => return None,
+        };
+        if let Some(config_obj) = config.as_object_mut() {
+            config_obj.insert("cwd".to_string(), build_config.cwd.clone().into());
+            if !build_config.env.is_empty() {
+                config_obj.insert(
+                    "env".to_string(),
+                    serde_json::to_value(&build_config.env).ok()?,
+                );
+            }
+        }
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal) -> Result<DebugRequest> {
+        bail!("Ruby locator should not require DapLocator::run to be ran");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_ruby_locator() {
+        let adapter = DebugAdapterName("rdbg".into());
+        let build_task = TaskTemplate {
+            label: "test $ZED_SYMBOL".into(),
+            command: "bundle".into(),
+            args: vec![
+                "exec".into(),
+                "rspec".into(),
+                "$ZED_RELATIVE_FILE:$ZED_ROW".into(),
+            ],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..TaskTemplate::default()
+        };
+
+        let scenario = RubyLocator
+            .create_scenario(&build_task, "test User", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "command": "bundle",
+                "args": ["exec", "rspec", "$ZED_RELATIVE_FILE:$ZED_ROW"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+            })
+        );
+
+        let script_task = TaskTemplate {
+            label: "run $ZED_FILE".into(),
+            command: "ruby".into(),
+            args: vec!["-w".into(), "$ZED_FILE".into(), "--verbose".into()],
+            ..TaskTemplate::default()
+        };
+        let scenario = RubyLocator
+            .create_scenario(&script_task, "run main.rb", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(scenario.config["script"], json!("$ZED_FILE"));
+        assert_eq!(scenario.config["args"], json!(["--verbose"]));
+
+        let script_task_with_option_values = TaskTemplate {
+            label: "run $ZED_FILE".into(),
+            command: "ruby".into(),
+            args: vec![
+                "-I".into(),
+                "lib".into(),
+                "-r".into(),
+                "json".into(),
+                "$ZED_FILE".into(),
+                "input.json".into(),
+            ],
+            ..TaskTemplate::default()
+        };
+        let scenario = RubyLocator
+            .create_scenario(&script_task_with_option_values, "run main.rb", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(scenario.config["script"], json!("$ZED_FILE"));
+        assert_eq!(scenario.config["args"], json!(["input.json"]));
+
+        let snippet_task = TaskTemplate {
+            label: "run snippet".into(),
+            command: "ruby".into(),
+            args: vec!["-e".into(), "puts 1".into(), "extra".into()],
+            ..TaskTemplate::default()
+        };
+        assert!(
+            RubyLocator
+                .create_scenario(&snippet_task, "run snippet", &adapter)
+                .await
+                .is_none()
+        );
+
+        assert!(
+            RubyLocator
+                .create_scenario(&script_task, "run main.rb", &DebugAdapterName("GDB".into()))
+                .await
+                .is_none()
+        );
+    }
+}
@@ -6,12 +6,13 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, Initialize, Launch,
+    LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand, NextCommand,
+    PauseCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpointsCommand, SetExceptionBreakpoints, SetInstructionBreakpointsCommand,
+    SetVariableValueCommand, StackTraceCommand, StepBackCommand, StepCommand, StepInCommand,
+    StepOutCommand, TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use anyhow::{Context as _, Result, anyhow};
@@ -691,6 +692,8 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, dap::InstructionBreakpoint>,
+    disassembly: HashMap<DisassembleCommand, Arc<[dap::DisassembledInstruction]>>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: TaskContext,
@@ -784,6 +787,7 @@ pub enum SessionEvent {
         sender: mpsc::Sender<Result<u32>>,
     },
     DataBreakpointInfo,
+    Disassembly,
     ConsoleOutput,
 }
 
@@ -861,6 +865,8 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
+                disassembly: HashMap::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -1673,6 +1679,8 @@ impl Session {
         self.invalidate_command_type::<ThreadsCommand>();
         self.invalidate_command_type::<DataBreakpointInfoCommand>();
         self.invalidate_command_type::<ReadMemory>();
+        self.invalidate_command_type::<DisassembleCommand>();
+        self.disassembly.clear();
         let executor = self.as_running().map(|running| running.executor.clone());
         if let Some(executor) = executor {
             self.memory.clear(&executor);
@@ -1810,6 +1818,8 @@ impl Session {
             |this, response, cx| {
                 this.memory.clear(cx.background_executor());
                 this.invalidate_command_type::<ReadMemory>();
+                this.invalidate_command_type::<DisassembleCommand>();
+                this.disassembly.clear();
                 this.invalidate_command_type::<VariablesCommand>();
                 cx.emit(SessionEvent::Variables);
                 response.ok()
@@ -1986,6 +1996,50 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &dap::InstructionBreakpoint> {
+        self.instruction_breakpoints.values()
+    }
+
+    pub fn has_instruction_breakpoint(&self, instruction_reference: &str) -> bool {
+        self.instruction_breakpoints
+            .contains_key(instruction_reference)
+    }
+
+    /// Sets a breakpoint on the instruction at the given memory reference, or removes it
+    /// if there already is one.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                dap::InstructionBreakpoint {
+                    instruction_reference,
+                    offset: None,
+                    condition: None,
+                    hit_condition: None,
+                    mode: None,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let breakpoints = self.instruction_breakpoints.values().cloned().collect();
+            let command = SetInstructionBreakpointsCommand { breakpoints };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2547,6 +2601,40 @@ impl Session {
             .unwrap_or_default()
     }
 
+    /// Returns the disassembled instructions starting `instruction_offset` instructions
+    /// away from `memory_reference`, requesting them from the adapter if necessary.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Option<Arc<[dap::DisassembledInstruction]>> {
+        let command = DisassembleCommand {
+            memory_reference,
+            instruction_offset,
+            instruction_count,
+        };
+        if let Some(instructions) = self.disassembly.get(&command) {
+            return Some(instructions.clone());
+        }
+
+        self.fetch(
+            command.clone(),
+            move |this, instructions, cx| {
+                let Some(instructions) = instructions.log_err() else {
+                    return;
+                };
+
+                this.disassembly.insert(command, instructions.into());
+                cx.emit(SessionEvent::Disassembly);
+                cx.notify();
+            },
+            cx,
+        );
+        None
+    }
+
     pub fn data_breakpoint_info(
         &mut self,
         context: Arc<DataBreakpointContext>,
@@ -2678,6 +2766,22 @@ impl Session {
             return false;
         };
         local_mode.binary.request_args.request == StartDebuggingRequestArgumentsRequest::Attach
+            && !self.is_post_mortem()
+    }
+
+    /// Whether this session inspects a core dump rather than a live process.
+    ///
+    /// Post-mortem sessions are read-only: the program can't be resumed, stepped or modified.
+    pub fn is_post_mortem(&self) -> bool {
+        let SessionState::Running(local_mode) = &self.mode else {
+            return false;
+        };
+        local_mode
+            .binary
+            .request_args
+            .configuration
+            .get("coreFile")
+            .is_some_and(|core_file| !core_file.is_null())
     }
 
     pub fn disconnect_client(&mut self, cx: &mut Context<Self>) {
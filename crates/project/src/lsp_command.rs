@@ -2,7 +2,7 @@ mod signature_help;
 
 use crate::{
     CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
+    DocumentHighlight, DocumentLink, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
     InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
     LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
     ProjectTransaction, PulledDiagnostics, ResolveState,
@@ -19,9 +19,9 @@ use language::{
     Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind, CharScopeContext,
     OffsetRangeExt, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
     language_settings::{InlayHintKind, LanguageSettings, language_settings},
-    point_from_lsp, point_to_lsp,
+    point_from_lsp, point_from_lsp_with_encoding, point_to_lsp, point_to_lsp_with_encoding,
     proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
-    range_from_lsp, range_to_lsp,
+    range_from_lsp_with_encoding, range_to_lsp_with_encoding,
 };
 use lsp::{
     AdapterServerCapabilities, CodeActionKind, CodeActionOptions, CodeDescription,
@@ -66,13 +66,43 @@ pub(crate) fn make_text_document_identifier(path: &Path) -> Result<lsp::TextDocu
 pub(crate) fn make_lsp_text_document_position(
     path: &Path,
     position: PointUtf16,
+    buffer: &Buffer,
+    language_server: &LanguageServer,
 ) -> Result<lsp::TextDocumentPositionParams> {
     Ok(lsp::TextDocumentPositionParams {
         text_document: make_text_document_identifier(path)?,
-        position: point_to_lsp(position),
+        position: point_to_lsp_with_encoding(position, language_server.position_encoding(), buffer),
     })
 }
 
+/// Re-expresses a range from the server in UTF-16 columns, for responses that keep
+/// LSP ranges around rather than converting them to anchors.
+pub(crate) fn utf16_lsp_range(
+    range: lsp::Range,
+    encoding: lsp::PositionEncoding,
+    snapshot: &text::BufferSnapshot,
+) -> lsp::Range {
+    let range = range_from_lsp_with_encoding(range, encoding, snapshot);
+    lsp::Range::new(point_to_lsp(range.start.0), point_to_lsp(range.end.0))
+}
+
+/// Returns the position encoding negotiated with the server that produced a response.
+pub(crate) fn position_encoding_for_server(
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &AsyncApp,
+) -> lsp::PositionEncoding {
+    lsp_store
+        .read_with(cx, |lsp_store, _| {
+            lsp_store
+                .language_server_for_id(server_id)
+                .map(|server| server.position_encoding())
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
 #[async_trait(?Send)]
 pub trait LspCommand: 'static + Sized + Send + std::fmt::Debug {
     type Response: 'static + Default + Send + std::fmt::Debug;
@@ -245,6 +275,46 @@ pub(crate) struct GetCodeLens;
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentColor;
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentLinks;
+
+impl GetDocumentLinks {
+    pub(crate) fn can_resolve_links(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .document_link_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn serialize_link(link: &DocumentLink) -> proto::DocumentLink {
+        proto::DocumentLink {
+            server_id: link.server_id.0 as u64,
+            start: Some(serialize_anchor(&link.range.start)),
+            end: Some(serialize_anchor(&link.range.end)),
+            lsp_link: serde_json::to_vec(&link.lsp_link).unwrap(),
+            resolved: link.resolved,
+        }
+    }
+
+    pub(crate) fn deserialize_link(link: proto::DocumentLink) -> Result<DocumentLink> {
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid start")?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid end")?;
+        Ok(DocumentLink {
+            server_id: LanguageServerId(link.server_id as usize),
+            range: start..end,
+            lsp_link: serde_json::from_slice(&link.lsp_link)?,
+            resolved: link.resolved,
+        })
+    }
+}
+
 impl GetCodeLens {
     pub(crate) fn can_resolve_lens(capabilities: &ServerCapabilities) -> bool {
         capabilities
@@ -319,25 +389,26 @@ impl LspCommand for PrepareRename {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::TextDocumentPositionParams> {
-        make_lsp_text_document_position(path, self.position)
+        make_lsp_text_document_position(path, self.position, buffer, language_server)
     }
 
     async fn response_from_lsp(
         self,
         message: Option<lsp::PrepareRenameResponse>,
-        _: Entity<LspStore>,
+        lsp_store: Entity<LspStore>,
         buffer: Entity<Buffer>,
-        _: LanguageServerId,
+        server_id: LanguageServerId,
         cx: AsyncApp,
     ) -> Result<PrepareRenameResponse> {
+        let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
         buffer.read_with(&cx, |buffer, _| match message {
             Some(lsp::PrepareRenameResponse::Range(range))
             | Some(lsp::PrepareRenameResponse::RangeWithPlaceholder { range, .. }) => {
-                let Range { start, end } = range_from_lsp(range);
+                let Range { start, end } = range_from_lsp_with_encoding(range, encoding, buffer);
                 if buffer.clip_point_utf16(start, Bias::Left) == start.0
                     && buffer.clip_point_utf16(end, Bias::Left) == end.0
                 {
@@ -480,12 +551,17 @@ impl LspCommand for PerformRename {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::RenameParams> {
         Ok(lsp::RenameParams {
-            text_document_position: make_lsp_text_document_position(path, self.position)?,
+            text_document_position: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             new_name: self.new_name.clone(),
             work_done_progress_params: Default::default(),
         })
@@ -609,12 +685,17 @@ impl LspCommand for GetDefinitions {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::GotoDefinitionParams> {
         Ok(lsp::GotoDefinitionParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         })
@@ -712,12 +793,17 @@ impl LspCommand for GetDeclarations {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::GotoDeclarationParams> {
         Ok(lsp::GotoDeclarationParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         })
@@ -814,12 +900,17 @@ impl LspCommand for GetImplementations {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::GotoImplementationParams> {
         Ok(lsp::GotoImplementationParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         })
@@ -913,12 +1004,17 @@ impl LspCommand for GetTypeDefinitions {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::GotoTypeDefinitionParams> {
         Ok(lsp::GotoTypeDefinitionParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         })
@@ -1116,6 +1212,7 @@ pub async fn location_links_from_lsp(
     }
 
     let (_, language_server) = language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+    let encoding = language_server.position_encoding();
     let mut definitions = Vec::new();
     for (origin_range, target_uri, target_range) in unresolved_links {
         let target_buffer_handle = lsp_store
@@ -1127,10 +1224,14 @@ pub async fn location_links_from_lsp(
         cx.update(|cx| {
             let origin_location = origin_range.map(|origin_range| {
                 let origin_buffer = buffer.read(cx);
-                let origin_start =
-                    origin_buffer.clip_point_utf16(point_from_lsp(origin_range.start), Bias::Left);
-                let origin_end =
-                    origin_buffer.clip_point_utf16(point_from_lsp(origin_range.end), Bias::Left);
+                let origin_start = origin_buffer.clip_point_utf16(
+                    point_from_lsp_with_encoding(origin_range.start, encoding, origin_buffer),
+                    Bias::Left,
+                );
+                let origin_end = origin_buffer.clip_point_utf16(
+                    point_from_lsp_with_encoding(origin_range.end, encoding, origin_buffer),
+                    Bias::Left,
+                );
                 Location {
                     buffer: buffer.clone(),
                     range: origin_buffer.anchor_after(origin_start)
@@ -1139,10 +1240,14 @@ pub async fn location_links_from_lsp(
             });
 
             let target_buffer = target_buffer_handle.read(cx);
-            let target_start =
-                target_buffer.clip_point_utf16(point_from_lsp(target_range.start), Bias::Left);
-            let target_end =
-                target_buffer.clip_point_utf16(point_from_lsp(target_range.end), Bias::Left);
+            let target_start = target_buffer.clip_point_utf16(
+                point_from_lsp_with_encoding(target_range.start, encoding, target_buffer),
+                Bias::Left,
+            );
+            let target_end = target_buffer.clip_point_utf16(
+                point_from_lsp_with_encoding(target_range.end, encoding, target_buffer),
+                Bias::Left,
+            );
             let target_location = Location {
                 buffer: target_buffer_handle,
                 range: target_buffer.anchor_after(target_start)
@@ -1166,6 +1271,7 @@ pub async fn location_link_from_lsp(
     cx: &mut AsyncApp,
 ) -> Result<LocationLink> {
     let (_, language_server) = language_server_for_buffer(lsp_store, buffer, server_id, cx)?;
+    let encoding = language_server.position_encoding();
 
     let (origin_range, target_uri, target_range) = (
         link.origin_selection_range,
@@ -1182,10 +1288,14 @@ pub async fn location_link_from_lsp(
     cx.update(|cx| {
         let origin_location = origin_range.map(|origin_range| {
             let origin_buffer = buffer.read(cx);
-            let origin_start =
-                origin_buffer.clip_point_utf16(point_from_lsp(origin_range.start), Bias::Left);
-            let origin_end =
-                origin_buffer.clip_point_utf16(point_from_lsp(origin_range.end), Bias::Left);
+            let origin_start = origin_buffer.clip_point_utf16(
+                point_from_lsp_with_encoding(origin_range.start, encoding, origin_buffer),
+                Bias::Left,
+            );
+            let origin_end = origin_buffer.clip_point_utf16(
+                point_from_lsp_with_encoding(origin_range.end, encoding, origin_buffer),
+                Bias::Left,
+            );
             Location {
                 buffer: buffer.clone(),
                 range: origin_buffer.anchor_after(origin_start)
@@ -1194,10 +1304,14 @@ pub async fn location_link_from_lsp(
         });
 
         let target_buffer = target_buffer_handle.read(cx);
-        let target_start =
-            target_buffer.clip_point_utf16(point_from_lsp(target_range.start), Bias::Left);
-        let target_end =
-            target_buffer.clip_point_utf16(point_from_lsp(target_range.end), Bias::Left);
+        let target_start = target_buffer.clip_point_utf16(
+            point_from_lsp_with_encoding(target_range.start, encoding, target_buffer),
+            Bias::Left,
+        );
+        let target_end = target_buffer.clip_point_utf16(
+            point_from_lsp_with_encoding(target_range.end, encoding, target_buffer),
+            Bias::Left,
+        );
         let target_location = Location {
             buffer: target_buffer_handle,
             range: target_buffer.anchor_after(target_start)
@@ -1290,12 +1404,17 @@ impl LspCommand for GetReferences {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::ReferenceParams> {
         Ok(lsp::ReferenceParams {
-            text_document_position: make_lsp_text_document_position(path, self.position)?,
+            text_document_position: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
             context: lsp::ReferenceContext {
@@ -1315,6 +1434,7 @@ impl LspCommand for GetReferences {
         let mut references = Vec::new();
         let (_, language_server) =
             language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+        let encoding = language_server.position_encoding();
 
         if let Some(locations) = locations {
             for lsp_location in locations {
@@ -1331,10 +1451,22 @@ impl LspCommand for GetReferences {
                 target_buffer_handle
                     .clone()
                     .read_with(&cx, |target_buffer, _| {
-                        let target_start = target_buffer
-                            .clip_point_utf16(point_from_lsp(lsp_location.range.start), Bias::Left);
-                        let target_end = target_buffer
-                            .clip_point_utf16(point_from_lsp(lsp_location.range.end), Bias::Left);
+                        let target_start = target_buffer.clip_point_utf16(
+                            point_from_lsp_with_encoding(
+                                lsp_location.range.start,
+                                encoding,
+                                target_buffer,
+                            ),
+                            Bias::Left,
+                        );
+                        let target_end = target_buffer.clip_point_utf16(
+                            point_from_lsp_with_encoding(
+                                lsp_location.range.end,
+                                encoding,
+                                target_buffer,
+                            ),
+                            Bias::Left,
+                        );
                         references.push(Location {
                             buffer: target_buffer_handle,
                             range: target_buffer.anchor_after(target_start)
@@ -1467,12 +1599,17 @@ impl LspCommand for GetDocumentHighlights {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::DocumentHighlightParams> {
         Ok(lsp::DocumentHighlightParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         })
@@ -1481,21 +1618,26 @@ impl LspCommand for GetDocumentHighlights {
     async fn response_from_lsp(
         self,
         lsp_highlights: Option<Vec<lsp::DocumentHighlight>>,
-        _: Entity<LspStore>,
+        lsp_store: Entity<LspStore>,
         buffer: Entity<Buffer>,
-        _: LanguageServerId,
+        server_id: LanguageServerId,
         cx: AsyncApp,
     ) -> Result<Vec<DocumentHighlight>> {
+        let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
         buffer.read_with(&cx, |buffer, _| {
             let mut lsp_highlights = lsp_highlights.unwrap_or_default();
             lsp_highlights.sort_unstable_by_key(|h| (h.range.start, Reverse(h.range.end)));
             lsp_highlights
                 .into_iter()
                 .map(|lsp_highlight| {
-                    let start = buffer
-                        .clip_point_utf16(point_from_lsp(lsp_highlight.range.start), Bias::Left);
-                    let end = buffer
-                        .clip_point_utf16(point_from_lsp(lsp_highlight.range.end), Bias::Left);
+                    let start = buffer.clip_point_utf16(
+                        point_from_lsp_with_encoding(lsp_highlight.range.start, encoding, buffer),
+                        Bias::Left,
+                    );
+                    let end = buffer.clip_point_utf16(
+                        point_from_lsp_with_encoding(lsp_highlight.range.end, encoding, buffer),
+                        Bias::Left,
+                    );
                     DocumentHighlight {
                         range: buffer.anchor_after(start)..buffer.anchor_before(end),
                         kind: lsp_highlight
@@ -1637,14 +1779,16 @@ impl LspCommand for GetDocumentSymbols {
     async fn response_from_lsp(
         self,
         lsp_symbols: Option<lsp::DocumentSymbolResponse>,
-        _: Entity<LspStore>,
-        _: Entity<Buffer>,
-        _: LanguageServerId,
-        _: AsyncApp,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
     ) -> Result<Vec<DocumentSymbol>> {
         let Some(lsp_symbols) = lsp_symbols else {
             return Ok(Vec::new());
         };
+        let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
 
         let symbols: Vec<_> = match lsp_symbols {
             lsp::DocumentSymbolResponse::Flat(symbol_information) => symbol_information
@@ -1652,27 +1796,49 @@ impl LspCommand for GetDocumentSymbols {
                 .map(|lsp_symbol| DocumentSymbol {
                     name: lsp_symbol.name,
                     kind: lsp_symbol.kind,
-                    range: range_from_lsp(lsp_symbol.location.range),
-                    selection_range: range_from_lsp(lsp_symbol.location.range),
+                    range: range_from_lsp_with_encoding(
+                        lsp_symbol.location.range,
+                        encoding,
+                        &snapshot,
+                    ),
+                    selection_range: range_from_lsp_with_encoding(
+                        lsp_symbol.location.range,
+                        encoding,
+                        &snapshot,
+                    ),
                     children: Vec::new(),
                 })
                 .collect(),
             lsp::DocumentSymbolResponse::Nested(nested_responses) => {
-                fn convert_symbol(lsp_symbol: lsp::DocumentSymbol) -> DocumentSymbol {
+                fn convert_symbol(
+                    lsp_symbol: lsp::DocumentSymbol,
+                    encoding: lsp::PositionEncoding,
+                    snapshot: &BufferSnapshot,
+                ) -> DocumentSymbol {
                     DocumentSymbol {
                         name: lsp_symbol.name,
                         kind: lsp_symbol.kind,
-                        range: range_from_lsp(lsp_symbol.range),
-                        selection_range: range_from_lsp(lsp_symbol.selection_range),
+                        range: range_from_lsp_with_encoding(lsp_symbol.range, encoding, snapshot),
+                        selection_range: range_from_lsp_with_encoding(
+                            lsp_symbol.selection_range,
+                            encoding,
+                            snapshot,
+                        ),
                         children: lsp_symbol
                             .children
                             .map(|children| {
-                                children.into_iter().map(convert_symbol).collect::<Vec<_>>()
+                                children
+                                    .into_iter()
+                                    .map(|child| convert_symbol(child, encoding, snapshot))
+                                    .collect::<Vec<_>>()
                             })
                             .unwrap_or_default(),
                     }
                 }
-                nested_responses.into_iter().map(convert_symbol).collect()
+                nested_responses
+                    .into_iter()
+                    .map(|symbol| convert_symbol(symbol, encoding, &snapshot))
+                    .collect()
             }
         };
         Ok(symbols)
@@ -1818,12 +1984,17 @@ impl LspCommand for GetSignatureHelp {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _cx: &App,
     ) -> Result<lsp::SignatureHelpParams> {
         Ok(lsp::SignatureHelpParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             context: None,
             work_done_progress_params: Default::default(),
         })
@@ -1931,12 +2102,17 @@ impl LspCommand for GetHover {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::HoverParams> {
         Ok(lsp::HoverParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
         })
     }
@@ -1944,22 +2120,28 @@ impl LspCommand for GetHover {
     async fn response_from_lsp(
         self,
         message: Option<lsp::Hover>,
-        _: Entity<LspStore>,
+        lsp_store: Entity<LspStore>,
         buffer: Entity<Buffer>,
-        _: LanguageServerId,
+        server_id: LanguageServerId,
         cx: AsyncApp,
     ) -> Result<Self::Response> {
         let Some(hover) = message else {
             return Ok(None);
         };
+        let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
 
         let (language, range) = buffer.read_with(&cx, |buffer, _| {
             (
                 buffer.language().cloned(),
                 hover.range.map(|range| {
-                    let token_start =
-                        buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
-                    let token_end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    let token_start = buffer.clip_point_utf16(
+                        point_from_lsp_with_encoding(range.start, encoding, buffer),
+                        Bias::Left,
+                    );
+                    let token_end = buffer.clip_point_utf16(
+                        point_from_lsp_with_encoding(range.end, encoding, buffer),
+                        Bias::Left,
+                    );
                     buffer.anchor_after(token_start)..buffer.anchor_before(token_end)
                 }),
             )
@@ -2170,12 +2352,17 @@ impl LspCommand for GetCompletions {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::CompletionParams> {
         Ok(lsp::CompletionParams {
-            text_document_position: make_lsp_text_document_position(path, self.position)?,
+            text_document_position: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             context: Some(self.context.clone()),
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
@@ -2212,6 +2399,7 @@ impl LspCommand for GetCompletions {
                 lsp_store.language_server_adapter_for_id(server_id)
             })?
             .with_context(|| format!("no language server with id {server_id}"))?;
+        let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
 
         let lsp_defaults = response_list
             .as_ref()
@@ -2256,7 +2444,8 @@ impl LspCommand for GetCompletions {
                     // If the language server provides a range to overwrite, then
                     // check that the range is valid.
                     Some(completion_text_edit) => {
-                        match parse_completion_text_edit(&completion_text_edit, &snapshot) {
+                        match parse_completion_text_edit(&completion_text_edit, encoding, &snapshot)
+                        {
                             Some(edit) => edit,
                             None => return false,
                         }
@@ -2280,7 +2469,7 @@ impl LspCommand for GetCompletions {
                         });
 
                         let range = if let Some(range) = default_edit_range {
-                            let range = range_from_lsp(*range);
+                            let range = range_from_lsp_with_encoding(*range, encoding, &snapshot);
                             let start = snapshot.clip_point_utf16(range.start, Bias::Left);
                             let end = snapshot.clip_point_utf16(range.end, Bias::Left);
                             if start != range.start.0 || end != range.end.0 {
@@ -2467,6 +2656,7 @@ pub struct ParsedCompletionEdit {
 
 pub(crate) fn parse_completion_text_edit(
     edit: &lsp::CompletionTextEdit,
+    encoding: lsp::PositionEncoding,
     snapshot: &BufferSnapshot,
 ) -> Option<ParsedCompletionEdit> {
     let (replace_range, insert_range, new_text) = match edit {
@@ -2477,7 +2667,7 @@ pub(crate) fn parse_completion_text_edit(
     };
 
     let replace_range = {
-        let range = range_from_lsp(replace_range);
+        let range = range_from_lsp_with_encoding(replace_range, encoding, snapshot);
         let start = snapshot.clip_point_utf16(range.start, Bias::Left);
         let end = snapshot.clip_point_utf16(range.end, Bias::Left);
         if start != range.start.0 || end != range.end.0 {
@@ -2492,7 +2682,7 @@ pub(crate) fn parse_completion_text_edit(
     let insert_range = match insert_range {
         None => None,
         Some(insert_range) => {
-            let range = range_from_lsp(insert_range);
+            let range = range_from_lsp_with_encoding(insert_range, encoding, snapshot);
             let start = snapshot.clip_point_utf16(range.start, Bias::Left);
             let end = snapshot.clip_point_utf16(range.end, Bias::Left);
             if start != range.start.0 || end != range.end.0 {
@@ -2549,12 +2739,15 @@ impl LspCommand for GetCodeActions {
         language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::CodeActionParams> {
+        let encoding = language_server.position_encoding();
         let mut relevant_diagnostics = Vec::new();
         for entry in buffer
             .snapshot()
             .diagnostics_in_range::<_, language::PointUtf16>(self.range.clone(), false)
         {
-            relevant_diagnostics.push(entry.to_lsp_diagnostic_stub()?);
+            let mut diagnostic = entry.to_lsp_diagnostic_stub()?;
+            diagnostic.range = range_to_lsp_with_encoding(entry.range.clone(), encoding, buffer)?;
+            relevant_diagnostics.push(diagnostic);
         }
 
         let supported =
@@ -2579,7 +2772,7 @@ impl LspCommand for GetCodeActions {
 
         Ok(lsp::CodeActionParams {
             text_document: make_text_document_identifier(path)?,
-            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
+            range: range_to_lsp_with_encoding(self.range.to_point_utf16(buffer), encoding, buffer)?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
             context: lsp::CodeActionContext {
@@ -2791,12 +2984,17 @@ impl LspCommand for OnTypeFormatting {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::DocumentOnTypeFormattingParams> {
         Ok(lsp::DocumentOnTypeFormattingParams {
-            text_document_position: make_lsp_text_document_position(path, self.position)?,
+            text_document_position: make_lsp_text_document_position(
+                path,
+                self.position,
+                buffer,
+                language_server,
+            )?,
             ch: self.trigger.clone(),
             options: self.options.clone(),
         })
@@ -2906,6 +3104,7 @@ impl InlayHints {
         lsp_hint: lsp::InlayHint,
         buffer_handle: &Entity<Buffer>,
         server_id: LanguageServerId,
+        encoding: lsp::PositionEncoding,
         resolve_state: ResolveState,
         force_no_type_left_padding: bool,
         cx: &mut AsyncApp,
@@ -2917,7 +3116,10 @@ impl InlayHints {
         });
 
         let position = buffer_handle.read_with(cx, |buffer, _| {
-            let position = buffer.clip_point_utf16(point_from_lsp(lsp_hint.position), Bias::Left);
+            let position = buffer.clip_point_utf16(
+                point_from_lsp_with_encoding(lsp_hint.position, encoding, buffer),
+                Bias::Left,
+            );
             if kind == Some(InlayHintKind::Parameter) {
                 buffer.anchor_before(position)
             } else {
@@ -3020,8 +3222,10 @@ impl InlayHints {
                         proto::inlay_hint_label::Label::LabelParts(proto::InlayHintLabelParts {
                             parts: label_parts.into_iter().map(|label_part| {
                                 let location_url = label_part.location.as_ref().map(|(_, location)| location.uri.to_string());
-                                let location_range_start = label_part.location.as_ref().map(|(_, location)| point_from_lsp(location.range.start).0).map(|point| proto::PointUtf16 { row: point.row, column: point.column });
-                                let location_range_end = label_part.location.as_ref().map(|(_, location)| point_from_lsp(location.range.end).0).map(|point| proto::PointUtf16 { row: point.row, column: point.column });
+                                // The location is sent in the server's own encoding, which the guest converts
+                                // once it has opened the target buffer.
+                                let location_range_start = label_part.location.as_ref().map(|(_, location)| proto::PointUtf16 { row: location.range.start.line, column: location.range.start.character });
+                                let location_range_end = label_part.location.as_ref().map(|(_, location)| proto::PointUtf16 { row: location.range.end.line, column: location.range.end.character });
                                 proto::InlayHintLabelPart {
                                 value: label_part.value,
                                 tooltip: label_part.tooltip.map(|tooltip| {
@@ -3186,9 +3390,17 @@ impl InlayHints {
         })
     }
 
-    pub fn project_to_lsp_hint(hint: InlayHint, snapshot: &BufferSnapshot) -> lsp::InlayHint {
+    pub fn project_to_lsp_hint(
+        hint: InlayHint,
+        encoding: lsp::PositionEncoding,
+        snapshot: &BufferSnapshot,
+    ) -> lsp::InlayHint {
         lsp::InlayHint {
-            position: point_to_lsp(hint.position.to_point_utf16(snapshot)),
+            position: point_to_lsp_with_encoding(
+                hint.position.to_point_utf16(snapshot),
+                encoding,
+                snapshot,
+            ),
             kind: hint.kind.map(|kind| match kind {
                 InlayHintKind::Type => lsp::InlayHintKind::TYPE,
                 InlayHintKind::Parameter => lsp::InlayHintKind::PARAMETER,
@@ -3299,14 +3511,18 @@ impl LspCommand for InlayHints {
         &self,
         path: &Path,
         buffer: &Buffer,
-        _: &Arc<LanguageServer>,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::InlayHintParams> {
         Ok(lsp::InlayHintParams {
             text_document: lsp::TextDocumentIdentifier {
                 uri: file_path_to_lsp_url(path)?,
             },
-            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
+            range: range_to_lsp_with_encoding(
+                self.range.to_point_utf16(buffer),
+                language_server.position_encoding(),
+                buffer,
+            )?,
             work_done_progress_params: Default::default(),
         })
     }
@@ -3331,6 +3547,7 @@ impl LspCommand for InlayHints {
         let force_no_type_left_padding =
             lsp_adapter.name.0.as_ref() == "typescript-language-server";
 
+        let encoding = lsp_server.position_encoding();
         let hints = message.unwrap_or_default().into_iter().map(|lsp_hint| {
             let resolve_state = if InlayHints::can_resolve_inlays(&lsp_server.capabilities()) {
                 ResolveState::CanResolve(lsp_server.server_id(), lsp_hint.data.clone())
@@ -3344,6 +3561,7 @@ impl LspCommand for InlayHints {
                     lsp_hint,
                     &buffer,
                     server_id,
+                    encoding,
                     resolve_state,
                     force_no_type_left_padding,
                     cx,
@@ -3484,6 +3702,7 @@ impl LspCommand for GetCodeLens {
                 })
         })??;
         let server_capabilities = language_server.capabilities();
+        let encoding = language_server.position_encoding();
         let available_commands = server_capabilities
             .execute_command_provider
             .as_ref()
@@ -3499,7 +3718,8 @@ impl LspCommand for GetCodeLens {
                     .is_none_or(|command| available_commands.contains(&command.command))
             })
             .map(|code_lens| {
-                let code_lens_range = range_from_lsp(code_lens.range);
+                let code_lens_range =
+                    range_from_lsp_with_encoding(code_lens.range, encoding, &snapshot);
                 let start = snapshot.clip_point_utf16(code_lens_range.start, Bias::Left);
                 let end = snapshot.clip_point_utf16(code_lens_range.end, Bias::Right);
                 let range = snapshot.anchor_before(start)..snapshot.anchor_after(end);
@@ -3606,12 +3826,17 @@ impl LspCommand for LinkedEditingRange {
         &self,
         path: &Path,
         buffer: &Buffer,
-        _server: &Arc<LanguageServer>,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::LinkedEditingRangeParams> {
         let position = self.position.to_point_utf16(&buffer.snapshot());
         Ok(lsp::LinkedEditingRangeParams {
-            text_document_position_params: make_lsp_text_document_position(path, position)?,
+            text_document_position_params: make_lsp_text_document_position(
+                path,
+                position,
+                buffer,
+                language_server,
+            )?,
             work_done_progress_params: Default::default(),
         })
     }
@@ -3619,21 +3844,27 @@ impl LspCommand for LinkedEditingRange {
     async fn response_from_lsp(
         self,
         message: Option<lsp::LinkedEditingRanges>,
-        _: Entity<LspStore>,
+        lsp_store: Entity<LspStore>,
         buffer: Entity<Buffer>,
-        _server_id: LanguageServerId,
+        server_id: LanguageServerId,
         cx: AsyncApp,
     ) -> Result<Vec<Range<Anchor>>> {
         if let Some(lsp::LinkedEditingRanges { mut ranges, .. }) = message {
+            let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
             ranges.sort_by_key(|range| range.start);
 
             buffer.read_with(&cx, |buffer, _| {
                 ranges
                     .into_iter()
                     .map(|range| {
-                        let start =
-                            buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
-                        let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                        let start = buffer.clip_point_utf16(
+                            point_from_lsp_with_encoding(range.start, encoding, buffer),
+                            Bias::Left,
+                        );
+                        let end = buffer.clip_point_utf16(
+                            point_from_lsp_with_encoding(range.end, encoding, buffer),
+                            Bias::Left,
+                        );
                         buffer.anchor_before(start)..buffer.anchor_after(end)
                     })
                     .collect()
@@ -4241,15 +4472,17 @@ impl LspCommand for GetDocumentColor {
     async fn response_from_lsp(
         self,
         message: Vec<lsp::ColorInformation>,
-        _: Entity<LspStore>,
-        _: Entity<Buffer>,
-        _: LanguageServerId,
-        _: AsyncApp,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
     ) -> Result<Self::Response> {
+        let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
         Ok(message
             .into_iter()
             .map(|color| DocumentColor {
-                lsp_range: color.range,
+                lsp_range: utf16_lsp_range(color.range, encoding, &snapshot),
                 color: color.color,
                 resolved: false,
                 color_presentations: Vec::new(),
@@ -4345,6 +4578,124 @@ impl LspCommand for GetDocumentColor {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn display_name(&self) -> &str {
+        "Document links"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .document_link_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentLinkParams> {
+        Ok(lsp::DocumentLinkParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::DocumentLink>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        let encoding = position_encoding_for_server(&lsp_store, server_id, &cx);
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+        Ok(message
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lsp_link| {
+                let link_range = range_from_lsp_with_encoding(lsp_link.range, encoding, &snapshot);
+                let start = snapshot.clip_point_utf16(link_range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(link_range.end, Bias::Right);
+                DocumentLink {
+                    server_id,
+                    range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                    resolved: lsp_link.target.is_some(),
+                    lsp_link,
+                }
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentLinks {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentLinks,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentLink>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response.iter().map(Self::serialize_link).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .links
+            .into_iter()
+            .map(Self::deserialize_link)
+            .collect::<Result<Vec<_>>>()
+            .context("deserializing proto document links response")
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentLinks) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 fn process_related_documents(
     diagnostics: &mut HashMap<lsp::Uri, LspPullDiagnostics>,
     server_id: LanguageServerId,
@@ -17,8 +17,8 @@ pub mod rust_analyzer_ext;
 
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
-    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, LocationLink, LspAction,
-    LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
+    CompletionSource, CoreCompletion, DocumentColor, DocumentLink, Hover, InlayHint, LocationLink,
+    LspAction, LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
     ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
@@ -64,12 +64,13 @@ use language::{
     language_settings::{
         FormatOnSave, Formatter, LanguageSettings, SelectedFormatter, language_settings,
     },
-    point_to_lsp,
+    point_from_lsp, point_to_lsp, point_to_lsp_with_encoding,
     proto::{
         deserialize_anchor, deserialize_lsp_edit, deserialize_version, serialize_anchor,
         serialize_lsp_edit, serialize_version,
     },
-    range_from_lsp, range_to_lsp,
+    range_from_lsp, range_from_lsp_in_text, range_from_lsp_with_encoding,
+    range_to_lsp_with_encoding,
 };
 use lsp::{
     AdapterServerCapabilities, CodeActionKind, CompletionContext, DiagnosticSeverity,
@@ -111,7 +112,7 @@ use std::{
     time::{Duration, Instant},
 };
 use sum_tree::Dimensions;
-use text::{Anchor, BufferId, LineEnding, OffsetRangeExt, ToPoint as _};
+use text::{Anchor, BufferId, LineEnding, OffsetRangeExt, Rope, ToPoint as _};
 
 use util::{
     ConnectionResult, ResultExt as _, debug_panic, defer, maybe, merge_json_value_into,
@@ -181,6 +182,9 @@ pub struct DocumentDiagnostics {
     diagnostics: Vec<DiagnosticEntry<Unclipped<PointUtf16>>>,
     document_abs_path: PathBuf,
     version: Option<i32>,
+    /// The unit the diagnostics' columns are measured in. This is UTF-16 unless they were
+    /// reported in another encoding for a document that isn't open.
+    encoding: lsp::PositionEncoding,
 }
 
 pub struct LocalLspStore {
@@ -206,12 +210,14 @@ pub struct LocalLspStore {
         HashMap<LanguageServerId, (LanguageServerName, Arc<LanguageServer>)>,
     prettier_store: Entity<PrettierStore>,
     next_diagnostic_group_id: usize,
+    /// Diagnostics by path, along with the unit their columns are measured in.
     diagnostics: HashMap<
         WorktreeId,
         HashMap<
             Arc<RelPath>,
             Vec<(
                 LanguageServerId,
+                lsp::PositionEncoding,
                 Vec<DiagnosticEntry<Unclipped<PointUtf16>>>,
             )>,
         >,
@@ -1912,8 +1918,13 @@ impl LocalLspStore {
                 // TODO: Instead of using current snapshot, should use the latest snapshot sent to
                 // LSP.
                 let snapshot = buffer_handle.read(cx).snapshot();
+                let encoding = language_server.position_encoding();
                 for range in ranges {
-                    lsp_ranges.push(range_to_lsp(range.to_point_utf16(&snapshot))?);
+                    lsp_ranges.push(range_to_lsp_with_encoding(
+                        range.to_point_utf16(&snapshot),
+                        encoding,
+                        &snapshot,
+                    )?);
                 }
                 anyhow::Ok(())
             })??;
@@ -1983,7 +1994,13 @@ impl LocalLspStore {
         } else if matches!(range_formatting_provider, Some(p) if *p != OneOf::Left(false)) {
             let _timer = zlog::time!(logger => "format-range");
             let buffer_start = lsp::Position::new(0, 0);
-            let buffer_end = buffer.read_with(cx, |b, _| point_to_lsp(b.max_point_utf16()))?;
+            let buffer_end = buffer.read_with(cx, |b, _| {
+                point_to_lsp_with_encoding(
+                    b.max_point_utf16(),
+                    language_server.position_encoding(),
+                    b,
+                )
+            })?;
             language_server
                 .request::<lsp::request::RangeFormatting>(lsp::DocumentRangeFormattingParams {
                     text_document: text_document.clone(),
@@ -2132,9 +2149,24 @@ impl LocalLspStore {
         let language = buffer.language().cloned();
 
         if let Some(diagnostics) = self.diagnostics.get(&worktree_id) {
-            for (server_id, diagnostics) in
+            for (server_id, encoding, mut diagnostics) in
                 diagnostics.get(file.path()).cloned().unwrap_or_default()
             {
+                if encoding != lsp::PositionEncoding::Utf16 {
+                    // These were reported while the file was closed, so the server measured
+                    // their columns against the file's text on disk.
+                    let text = buffer_handle.read(cx).as_rope().clone();
+                    for entry in &mut diagnostics {
+                        entry.range = range_from_lsp_in_text(
+                            lsp::Range {
+                                start: point_to_lsp(entry.range.start.0),
+                                end: point_to_lsp(entry.range.end.0),
+                            },
+                            encoding,
+                            &text,
+                        );
+                    }
+                }
                 self.update_buffer_diagnostics(
                     buffer_handle,
                     server_id,
@@ -2781,11 +2813,17 @@ impl LocalLspStore {
         cx: &mut Context<LspStore>,
     ) -> Task<Result<Vec<(Range<Anchor>, Arc<str>)>>> {
         let snapshot = self.buffer_snapshot_for_lsp_version(buffer, server_id, version, cx);
+        let encoding = self.position_encoding(server_id);
         cx.background_spawn(async move {
             let snapshot = snapshot?;
             let mut lsp_edits = lsp_edits
                 .into_iter()
-                .map(|edit| (range_from_lsp(edit.range), edit.new_text))
+                .map(|edit| {
+                    (
+                        range_from_lsp_with_encoding(edit.range, encoding, &snapshot),
+                        edit.new_text,
+                    )
+                })
                 .collect::<Vec<_>>();
 
             lsp_edits.sort_by_key(|(range, _)| (range.start, range.end));
@@ -3030,9 +3068,19 @@ impl LocalLspStore {
                                 // Check if the edit that triggered that edit has been made by this participant.
 
                                 if let Some(most_recent_edit) = most_recent_edit {
+                                    let snapshot = buffer_to_edit.read(cx).text_snapshot();
+                                    let encoding = language_server.position_encoding();
                                     cx.emit(LspStoreEvent::SnippetEdit {
                                         buffer_id,
-                                        edits: snippet_edits,
+                                        edits: snippet_edits
+                                            .into_iter()
+                                            .map(|(range, snippet)| {
+                                                (
+                                                    utf16_lsp_range(range, encoding, &snapshot),
+                                                    snippet,
+                                                )
+                                            })
+                                            .collect(),
                                         most_recent_edit,
                                     });
                                 }
@@ -3434,6 +3482,13 @@ impl LocalLspStore {
         Ok(workspace_config)
     }
 
+    /// The position encoding the given server selected, or UTF-16 if it isn't running.
+    fn position_encoding(&self, id: LanguageServerId) -> lsp::PositionEncoding {
+        self.language_server_for_id(id)
+            .map(|server| server.position_encoding())
+            .unwrap_or_default()
+    }
+
     fn language_server_for_id(&self, id: LanguageServerId) -> Option<Arc<LanguageServer>> {
         if let Some(LanguageServerState::Running { server, .. }) = self.language_servers.get(&id) {
             Some(server.clone())
@@ -3500,6 +3555,7 @@ pub struct LspStore {
     pub lsp_server_capabilities: HashMap<LanguageServerId, lsp::ServerCapabilities>,
     lsp_document_colors: HashMap<BufferId, DocumentColorData>,
     lsp_code_lens: HashMap<BufferId, CodeLensData>,
+    lsp_document_links: HashMap<BufferId, DocumentLinksData>,
     running_lsp_requests: HashMap<TypeId, (Global, HashMap<LspRequestId, Task<()>>)>,
 }
 
@@ -3511,6 +3567,7 @@ pub struct DocumentColors {
 
 type DocumentColorTask = Shared<Task<std::result::Result<DocumentColors, Arc<anyhow::Error>>>>;
 type CodeLensTask = Shared<Task<std::result::Result<Option<Vec<CodeAction>>, Arc<anyhow::Error>>>>;
+type DocumentLinksTask = Shared<Task<std::result::Result<Vec<DocumentLink>, Arc<anyhow::Error>>>>;
 
 #[derive(Debug, Default)]
 struct DocumentColorData {
@@ -3527,6 +3584,13 @@ struct CodeLensData {
     update: Option<(Global, CodeLensTask)>,
 }
 
+#[derive(Debug, Default)]
+struct DocumentLinksData {
+    links_for_version: Global,
+    links: Option<Vec<DocumentLink>>,
+    update: Option<(Global, DocumentLinksTask)>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum LspFetchStrategy {
     IgnoreCache,
@@ -3563,6 +3627,7 @@ pub enum LspStoreEvent {
     },
     SnippetEdit {
         buffer_id: BufferId,
+        /// Ranges with UTF-16 columns, whatever the server's position encoding.
         edits: Vec<(lsp::Range, Snippet)>,
         most_recent_edit: clock::Lamport,
     },
@@ -3636,6 +3701,8 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -3773,6 +3840,7 @@ impl LspStore {
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
             _maintain_workspace_config,
@@ -3834,6 +3902,7 @@ impl LspStore {
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
 
@@ -4033,6 +4102,7 @@ impl LspStore {
                     if refcount == 0 {
                         lsp_store.lsp_document_colors.remove(&buffer_id);
                         lsp_store.lsp_code_lens.remove(&buffer_id);
+                        lsp_store.lsp_document_links.remove(&buffer_id);
                         let local = lsp_store.as_local_mut().unwrap();
                         local.registered_buffers.remove(&buffer_id);
                         local.buffers_opened_in_servers.remove(&buffer_id);
@@ -4747,6 +4817,104 @@ impl LspStore {
         }
     }
 
+    /// Resolves the code action's edit or command without applying it, so that the files it
+    /// edits can be checked first. Only local projects are supported.
+    pub fn resolve_code_action(
+        &self,
+        buffer_handle: &Entity<Buffer>,
+        mut action: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        if !self.mode.is_local() {
+            return Task::ready(Err(anyhow!(
+                "code actions can only be resolved in local projects"
+            )));
+        }
+        let Some(lang_server) = buffer_handle.update(cx, |buffer, cx| {
+            self.language_server_for_local_buffer(buffer, action.server_id, cx)
+                .map(|(_, server)| server.clone())
+        }) else {
+            return Task::ready(Err(anyhow!("no language server for the code action")));
+        };
+        cx.background_spawn(async move {
+            LocalLspStore::try_resolve_code_action(&lang_server, &mut action)
+                .await
+                .context("resolving a code action")?;
+            Ok(action)
+        })
+    }
+
+    /// Asks the buffer's language server how it would rename the symbol at `position`,
+    /// returning its edit without applying it. Only local projects are supported.
+    pub fn rename_edit(
+        &self,
+        buffer_handle: &Entity<Buffer>,
+        position: PointUtf16,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<(LanguageServerId, lsp::WorkspaceEdit)>>> {
+        if !self.mode.is_local() {
+            return Task::ready(Err(anyhow!(
+                "rename edits can only be previewed in local projects"
+            )));
+        }
+        let request = buffer_handle.update(cx, |buffer, cx| {
+            let abs_path = File::from_dyn(buffer.file())?.abs_path(cx);
+            let lang_server = self
+                .language_servers_for_local_buffer(buffer, cx)
+                .map(|(_, server)| server.clone())
+                .find(|server| {
+                    server
+                        .capabilities()
+                        .rename_provider
+                        .is_some_and(|capability| capability != OneOf::Left(false))
+                })?;
+            let params = make_lsp_text_document_position(&abs_path, position, buffer, &lang_server)
+                .map(|text_document_position| lsp::RenameParams {
+                    text_document_position,
+                    new_name,
+                    work_done_progress_params: Default::default(),
+                });
+            Some((lang_server, params))
+        });
+        let Some((lang_server, params)) = request else {
+            return Task::ready(Ok(None));
+        };
+        cx.background_spawn(async move {
+            let edit = lang_server
+                .request::<lsp::request::Rename>(params?)
+                .await
+                .into_response()
+                .context("requesting a rename")?;
+            Ok(edit.map(|edit| (lang_server.server_id(), edit)))
+        })
+    }
+
+    /// Applies a workspace edit that the given language server produced, such as one
+    /// returned by [`Self::rename_edit`] or a code action resolved by
+    /// [`Self::resolve_code_action`].
+    pub fn apply_workspace_edit(
+        &self,
+        edit: lsp::WorkspaceEdit,
+        server_id: LanguageServerId,
+        push_to_history: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(lang_server) = self.language_server_for_id(server_id) else {
+            return Task::ready(Err(anyhow!("language server {server_id} is not running")));
+        };
+        cx.spawn(async move |this, cx| {
+            LocalLspStore::deserialize_workspace_edit(
+                this.upgrade().context("no app present")?,
+                edit,
+                push_to_history,
+                lang_server,
+                cx,
+            )
+            .await
+        })
+    }
+
     pub fn apply_code_action_kind(
         &mut self,
         buffers: HashSet<Entity<Buffer>>,
@@ -4847,9 +5015,10 @@ impl LspStore {
                 return Task::ready(Ok(hint));
             }
             let buffer_snapshot = buffer.read(cx).snapshot();
+            let encoding = lang_server.position_encoding();
             cx.spawn(async move |_, cx| {
                 let resolve_task = lang_server.request::<lsp::request::InlayHintResolveRequest>(
-                    InlayHints::project_to_lsp_hint(hint, &buffer_snapshot),
+                    InlayHints::project_to_lsp_hint(hint, encoding, &buffer_snapshot),
                 );
                 let resolved_hint = resolve_task
                     .await
@@ -4859,6 +5028,7 @@ impl LspStore {
                     resolved_hint,
                     &buffer,
                     server_id,
+                    encoding,
                     ResolveState::Resolved,
                     false,
                     cx,
@@ -4869,6 +5039,122 @@ impl LspStore {
         }
     }
 
+    /// The document links of the buffer, which are only requested from the language
+    /// server again once the buffer has changed.
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> DocumentLinksTask {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        if let Some(cached_data) = self.lsp_document_links.get(&buffer_id) {
+            if let Some(links) = &cached_data.links
+                && !version_queried_for.changed_since(&cached_data.links_for_version)
+            {
+                return Task::ready(Ok(links.clone())).shared();
+            }
+            if let Some((updating_for, running_update)) = &cached_data.update
+                && !version_queried_for.changed_since(updating_for)
+            {
+                return running_update.clone();
+            }
+        }
+
+        let fetch_links = self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetDocumentLinks,
+            cx,
+        );
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                let fetched_links = fetch_links.await.context("fetching document links");
+                lsp_store
+                    .update(cx, |lsp_store, _| {
+                        let lsp_data = lsp_store.lsp_document_links.entry(buffer_id).or_default();
+                        lsp_data.update = None;
+                        let fetched_links = fetched_links?;
+                        if lsp_data.links.is_none()
+                            || !lsp_data
+                                .links_for_version
+                                .changed_since(&query_version_queried_for)
+                        {
+                            lsp_data.links_for_version = query_version_queried_for;
+                            lsp_data.links = Some(fetched_links.clone());
+                        }
+                        Ok(fetched_links)
+                    })
+                    .map_err(Arc::new)?
+                    .map_err(Arc::new)
+            })
+            .shared();
+        self.lsp_document_links.entry(buffer_id).or_default().update =
+            Some((version_queried_for, new_task.clone()));
+        new_task
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        if link.resolved {
+            return Task::ready(Ok(link));
+        }
+
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            if !self.check_if_capable_for_proto_request(
+                &buffer,
+                GetDocumentLinks::can_resolve_links,
+                cx,
+            ) {
+                return Task::ready(Ok(link));
+            }
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                link: Some(GetDocumentLinks::serialize_link(&link)),
+            };
+            cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                match response.link {
+                    Some(resolved_link) => GetDocumentLinks::deserialize_link(resolved_link)
+                        .context("document link proto resolve response conversion"),
+                    None => Ok(link),
+                }
+            })
+        } else {
+            let Some(lang_server) = buffer.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, link.server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(link));
+            };
+            if !GetDocumentLinks::can_resolve_links(&lang_server.capabilities()) {
+                return Task::ready(Ok(link));
+            }
+            cx.background_spawn(async move {
+                let resolved_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(link.lsp_link.clone())
+                    .await
+                    .into_response()
+                    .context("document link resolve LSP request")?;
+                Ok(DocumentLink {
+                    lsp_link: resolved_link,
+                    resolved: true,
+                    ..link
+                })
+            })
+        }
+    }
+
     pub fn resolve_color_presentation(
         &mut self,
         mut color: DocumentColor,
@@ -4939,12 +5225,17 @@ impl LspStore {
             }) else {
                 return Task::ready(Ok(color));
             };
+            let range = range_to_lsp_with_encoding(
+                point_from_lsp(color.lsp_range.start).0..point_from_lsp(color.lsp_range.end).0,
+                lang_server.position_encoding(),
+                &buffer.read(cx).snapshot(),
+            );
             cx.background_spawn(async move {
                 let resolve_task = lang_server.request::<lsp::request::ColorPresentationRequest>(
                     lsp::ColorPresentationParams {
                         text_document: make_text_document_identifier(&path)?,
                         color: color.color,
-                        range: color.lsp_range,
+                        range: range?,
                         work_done_progress_params: Default::default(),
                         partial_result_params: Default::default(),
                     },
@@ -6893,6 +7184,7 @@ impl LspStore {
                 server_id: LanguageServerId,
                 lsp_adapter: Arc<CachedLspAdapter>,
                 worktree: WeakEntity<Worktree>,
+                encoding: lsp::PositionEncoding,
                 lsp_symbols: Vec<(String, SymbolKind, lsp::Location)>,
             }
 
@@ -6933,6 +7225,7 @@ impl LspStore {
                 }
                 let worktree_handle = worktree_handle.clone();
                 let server_id = server.server_id();
+                let encoding = server.position_encoding();
                 requests.push(
                         server
                             .request::<lsp::request::WorkspaceSymbolRequest>(
@@ -6970,14 +7263,34 @@ impl LspStore {
                                     server_id,
                                     lsp_adapter,
                                     worktree: worktree_handle.downgrade(),
+                                    encoding,
                                     lsp_symbols,
                                 }
                             }),
                     );
             }
 
+            let fs = local.fs.clone();
             cx.spawn(async move |this, cx| {
                 let responses = futures::future::join_all(requests).await;
+
+                // Workspace symbols mostly point into files that aren't open, so columns in
+                // encodings other than UTF-16 are converted against the files on disk.
+                let mut texts_by_path = HashMap::<PathBuf, Rope>::default();
+                for result in &responses {
+                    if result.encoding == lsp::PositionEncoding::Utf16 {
+                        continue;
+                    }
+                    for (_, _, location) in &result.lsp_symbols {
+                        if let Ok(abs_path) = location.uri.to_file_path()
+                            && !texts_by_path.contains_key(&abs_path)
+                            && let Some(text) = fs.load(&abs_path).await.log_err()
+                        {
+                            texts_by_path.insert(abs_path, Rope::from(text));
+                        }
+                    }
+                }
+
                 let this = match this.upgrade() {
                     Some(this) => this,
                     None => return Ok(Vec::new()),
@@ -6991,6 +7304,7 @@ impl LspStore {
                             .into_iter()
                             .filter_map(|(symbol_name, symbol_kind, symbol_location)| {
                                 let abs_path = symbol_location.uri.to_file_path().ok()?;
+                                let text_on_disk = texts_by_path.get(&abs_path);
                                 let source_worktree = result.worktree.upgrade()?;
                                 let source_worktree_id = source_worktree.read(cx).id();
 
@@ -7009,6 +7323,25 @@ impl LspStore {
                                     }
                                 };
 
+                                let text = if result.encoding == lsp::PositionEncoding::Utf16 {
+                                    None
+                                } else if let SymbolLocation::InProject(project_path) = &path
+                                    && let Some(buffer) =
+                                        this.buffer_store.read(cx).get_by_path(project_path)
+                                {
+                                    Some(buffer.read(cx).as_rope().clone())
+                                } else {
+                                    text_on_disk.cloned()
+                                };
+                                let range = match &text {
+                                    Some(text) => range_from_lsp_in_text(
+                                        symbol_location.range,
+                                        result.encoding,
+                                        text,
+                                    ),
+                                    None => range_from_lsp(symbol_location.range),
+                                };
+
                                 Some(CoreSymbol {
                                     source_language_server_id: result.server_id,
                                     language_server_name: result.lsp_adapter.name.clone(),
@@ -7016,7 +7349,7 @@ impl LspStore {
                                     path,
                                     kind: symbol_kind,
                                     name: symbol_name,
-                                    range: range_from_lsp(symbol_location.range),
+                                    range,
                                 })
                             })
                             .collect()
@@ -7144,22 +7477,44 @@ impl LspStore {
                 .and_then(|m| m.get_mut(&language_server.server_id()))?;
             let previous_snapshot = buffer_snapshots.last()?;
 
+            let encoding = language_server.position_encoding();
             let build_incremental_change = || {
                 buffer
                     .edits_since::<Dimensions<PointUtf16, usize>>(
                         previous_snapshot.snapshot.version(),
                     )
                     .map(|edit| {
-                        let edit_start = edit.new.start.0;
-                        let edit_end = edit_start + (edit.old.end.0 - edit.old.start.0);
+                        // Each change applies to the document as left by the ones before it, so
+                        // the start is located in the new text and the end is offset from it by
+                        // the extent of the replaced text.
+                        let edit_start =
+                            point_to_lsp_with_encoding(edit.new.start.0, encoding, &next_snapshot);
+                        let old_start = point_to_lsp_with_encoding(
+                            edit.old.start.0,
+                            encoding,
+                            &previous_snapshot.snapshot,
+                        );
+                        let old_end = point_to_lsp_with_encoding(
+                            edit.old.end.0,
+                            encoding,
+                            &previous_snapshot.snapshot,
+                        );
+                        let edit_end = if old_start.line == old_end.line {
+                            lsp::Position::new(
+                                edit_start.line,
+                                edit_start.character + (old_end.character - old_start.character),
+                            )
+                        } else {
+                            lsp::Position::new(
+                                edit_start.line + (old_end.line - old_start.line),
+                                old_end.character,
+                            )
+                        };
                         let new_text = next_snapshot
                             .text_for_range(edit.new.start.1..edit.new.end.1)
                             .collect();
                         lsp::TextDocumentContentChangeEvent {
-                            range: Some(lsp::Range::new(
-                                point_to_lsp(edit_start),
-                                point_to_lsp(edit_end),
-                            )),
+                            range: Some(lsp::Range::new(edit_start, edit_end)),
                             range_length: None,
                             text: new_text,
                         }
@@ -7511,7 +7866,7 @@ impl LspStore {
             .collect();
     }
 
-    #[cfg(test)]
+    /// Replaces the diagnostics the given server reported for a document.
     pub fn update_diagnostic_entries(
         &mut self,
         server_id: LanguageServerId,
@@ -7527,6 +7882,7 @@ impl LspStore {
                     diagnostics,
                     document_abs_path: abs_path,
                     version,
+                    encoding: lsp::PositionEncoding::Utf16,
                 },
                 result_id,
                 server_id,
@@ -7599,6 +7955,7 @@ impl LspStore {
                     worktree.id(),
                     server_id,
                     project_path.path.clone(),
+                    update.diagnostics.encoding,
                     update.diagnostics.diagnostics,
                     cx,
                 )
@@ -7657,6 +8014,7 @@ impl LspStore {
         worktree_id: WorktreeId,
         server_id: LanguageServerId,
         path_in_worktree: Arc<RelPath>,
+        encoding: lsp::PositionEncoding,
         diagnostics: Vec<DiagnosticEntry<Unclipped<PointUtf16>>>,
         _: &mut Context<Worktree>,
     ) -> Result<ControlFlow<(), Option<(u64, proto::DiagnosticSummary)>>> {
@@ -7693,10 +8051,10 @@ impl LspStore {
                 .or_default();
             match diagnostics_by_server_id.binary_search_by_key(&server_id, |e| e.0) {
                 Ok(ix) => {
-                    diagnostics_by_server_id[ix] = (server_id, diagnostics);
+                    diagnostics_by_server_id[ix] = (server_id, encoding, diagnostics);
                 }
                 Err(ix) => {
-                    diagnostics_by_server_id.insert(ix, (server_id, diagnostics));
+                    diagnostics_by_server_id.insert(ix, (server_id, encoding, diagnostics));
                 }
             }
         }
@@ -8870,6 +9228,16 @@ impl LspStore {
         self.as_local()?.language_server_for_id(id)
     }
 
+    /// The position encoding the given server selected. Unlike [`Self::language_server_for_id`],
+    /// this also knows about the servers of a remote project.
+    pub fn language_server_position_encoding(&self, id: LanguageServerId) -> lsp::PositionEncoding {
+        lsp::PositionEncoding::from_kind(
+            self.lsp_server_capabilities
+                .get(&id)
+                .and_then(|capabilities| capabilities.position_encoding.as_ref()),
+        )
+    }
+
     fn on_lsp_progress(
         &mut self,
         progress: lsp::ProgressParams,
@@ -9133,13 +9501,19 @@ impl LspStore {
         let mut old_insert_end = None;
         let mut new_text = String::default();
         if let Ok(buffer_id) = BufferId::new(envelope.payload.buffer_id) {
-            let buffer_snapshot = this.update(&mut cx, |this, cx| {
+            let (buffer_snapshot, encoding) = this.update(&mut cx, |this, cx| {
                 let buffer = this.buffer_store.read(cx).get_existing(buffer_id)?;
-                anyhow::Ok(buffer.read(cx).snapshot())
+                let encoding = this
+                    .language_server_for_id(LanguageServerId(
+                        envelope.payload.language_server_id as usize,
+                    ))
+                    .map(|server| server.position_encoding())
+                    .unwrap_or_default();
+                anyhow::Ok((buffer.read(cx).snapshot(), encoding))
             })??;
 
             if let Some(text_edit) = completion.text_edit.as_ref() {
-                let edit = parse_completion_text_edit(text_edit, &buffer_snapshot);
+                let edit = parse_completion_text_edit(text_edit, encoding, &buffer_snapshot);
 
                 if let Some(mut edit) = edit {
                     LineEnding::normalize(&mut edit.new_text);
@@ -9358,6 +9732,32 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_document_link(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = envelope
+            .payload
+            .link
+            .context("incorrect protobuf resolve document link message: missing the link")?;
+        let link = GetDocumentLinks::deserialize_link(link)
+            .context("resolved proto document link conversion")?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let resolved_link = this
+            .update(&mut cx, |this, cx| {
+                this.resolve_document_link(link, buffer, cx)
+            })?
+            .await
+            .context("document link resolve")?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(GetDocumentLinks::serialize_link(&resolved_link)),
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -10231,6 +10631,7 @@ impl LspStore {
                         update.server_id,
                         update.diagnostics,
                         &update.disk_based_sources,
+                        cx,
                     ),
                     result_id: update.result_id,
                     server_id: update.server_id,
@@ -10249,12 +10650,55 @@ impl LspStore {
         server_id: LanguageServerId,
         mut lsp_diagnostics: lsp::PublishDiagnosticsParams,
         disk_based_sources: &[String],
+        cx: &App,
     ) -> DocumentDiagnostics {
         let mut diagnostics = Vec::default();
         let mut primary_diagnostic_group_ids = HashMap::default();
         let mut sources_by_group_id = HashMap::default();
         let mut supporting_diagnostics = HashMap::default();
 
+        // Columns in encodings other than UTF-16 can only be converted against the text the
+        // server saw, so diagnostics for files that aren't open keep the server's columns
+        // until the file is opened.
+        let encoding = self
+            .as_local()
+            .map(|local| local.position_encoding(server_id))
+            .unwrap_or_default();
+        let snapshot = if encoding == lsp::PositionEncoding::Utf16 {
+            None
+        } else {
+            self.worktree_store
+                .read(cx)
+                .find_worktree(&document_abs_path, cx)
+                .and_then(|(worktree, path)| {
+                    self.buffer_store.read(cx).get_by_path(&ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path,
+                    })
+                })
+                .and_then(|buffer| {
+                    let snapshot = self
+                        .as_local_mut()?
+                        .buffer_snapshot_for_lsp_version(
+                            &buffer,
+                            server_id,
+                            lsp_diagnostics.version,
+                            cx,
+                        )
+                        .unwrap_or_else(|_| buffer.read(cx).text_snapshot());
+                    Some(snapshot)
+                })
+        };
+        let to_range = |range: lsp::Range| match &snapshot {
+            Some(snapshot) => range_from_lsp_with_encoding(range, encoding, snapshot),
+            None => range_from_lsp(range),
+        };
+        let diagnostics_encoding = if snapshot.is_some() {
+            lsp::PositionEncoding::Utf16
+        } else {
+            encoding
+        };
+
         let adapter = self.language_server_adapter_for_id(server_id);
 
         // Ensure that primary diagnostics are always the most severe
@@ -10264,7 +10708,7 @@ impl LspStore {
 
         for diagnostic in &lsp_diagnostics.diagnostics {
             let source = diagnostic.source.as_ref();
-            let range = range_from_lsp(diagnostic.range);
+            let range = to_range(diagnostic.range);
             let is_supporting = diagnostic
                 .related_information
                 .as_ref()
@@ -10273,7 +10717,7 @@ impl LspStore {
                         primary_diagnostic_group_ids.contains_key(&(
                             source,
                             diagnostic.code.clone(),
-                            range_from_lsp(info.location.range),
+                            to_range(info.location.range),
                         ))
                     })
                 });
@@ -10327,7 +10771,7 @@ impl LspStore {
                 if let Some(infos) = &diagnostic.related_information {
                     for info in infos {
                         if info.location.uri == lsp_diagnostics.uri && !info.message.is_empty() {
-                            let range = range_from_lsp(info.location.range);
+                            let range = to_range(info.location.range);
                             diagnostics.push(DiagnosticEntry {
                                 range,
                                 diagnostic: Diagnostic {
@@ -10378,6 +10822,7 @@ impl LspStore {
             diagnostics,
             document_abs_path,
             version: lsp_diagnostics.version,
+            encoding: diagnostics_encoding,
         }
     }
 
@@ -11083,6 +11528,11 @@ impl LspStore {
         for buffer_lens in self.lsp_code_lens.values_mut() {
             buffer_lens.lens.remove(&for_server);
         }
+        for buffer_links in self.lsp_document_links.values_mut() {
+            if let Some(links) = &mut buffer_links.links {
+                links.retain(|link| link.server_id != for_server);
+            }
+        }
         if let Some(local) = self.as_local_mut() {
             local.buffer_pull_diagnostics_result_ids.remove(&for_server);
             for buffer_servers in local.buffers_opened_in_servers.values_mut() {
@@ -11474,6 +11924,18 @@ impl LspStore {
                         notify_server_capabilities_updated(&server, cx);
                     }
                 }
+                "textDocument/documentLink" => {
+                    if let Some(caps) = reg
+                        .register_options
+                        .map(serde_json::from_value)
+                        .transpose()?
+                    {
+                        server.update_capabilities(|capabilities| {
+                            capabilities.document_link_provider = Some(caps);
+                        });
+                        notify_server_capabilities_updated(&server, cx);
+                    }
+                }
                 "textDocument/diagnostic" => {
                     if let Some(caps) = reg
                         .register_options
@@ -11642,6 +12104,12 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/documentLink" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 "textDocument/diagnostic" => {
                     server.update_capabilities(|capabilities| {
                         capabilities.diagnostic_provider = None;
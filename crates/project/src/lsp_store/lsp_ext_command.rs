@@ -13,7 +13,7 @@ use async_trait::async_trait;
 use collections::HashMap;
 use gpui::{App, AsyncApp, Entity};
 use language::{
-    Buffer, point_to_lsp,
+    Buffer, point_to_lsp_with_encoding,
     proto::{deserialize_anchor, serialize_anchor},
 };
 use lsp::{AdapterServerCapabilities, LanguageServer, LanguageServerId};
@@ -75,13 +75,17 @@ impl LspCommand for ExpandMacro {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<ExpandMacroParams> {
         Ok(ExpandMacroParams {
             text_document: make_text_document_identifier(path)?,
-            position: point_to_lsp(self.position),
+            position: point_to_lsp_with_encoding(
+                self.position,
+                language_server.position_encoding(),
+                buffer,
+            ),
         })
     }
 
@@ -207,15 +211,19 @@ impl LspCommand for OpenDocs {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<OpenDocsParams> {
         Ok(OpenDocsParams {
             text_document: lsp::TextDocumentIdentifier {
                 uri: lsp::Uri::from_file_path(path).unwrap(),
             },
-            position: point_to_lsp(self.position),
+            position: point_to_lsp_with_encoding(
+                self.position,
+                language_server.position_encoding(),
+                buffer,
+            ),
         })
     }
 
@@ -423,11 +431,11 @@ impl LspCommand for GoToParentModule {
     fn to_lsp(
         &self,
         path: &Path,
-        _: &Buffer,
-        _: &Arc<LanguageServer>,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<lsp::TextDocumentPositionParams> {
-        make_lsp_text_document_position(path, self.position)
+        make_lsp_text_document_position(path, self.position, buffer, language_server)
     }
 
     async fn response_from_lsp(
@@ -602,15 +610,19 @@ impl LspCommand for GetLspRunnables {
         &self,
         path: &Path,
         buffer: &Buffer,
-        _: &Arc<LanguageServer>,
+        language_server: &Arc<LanguageServer>,
         _: &App,
     ) -> Result<RunnablesParams> {
         let url = file_path_to_lsp_url(path)?;
         Ok(RunnablesParams {
             text_document: lsp::TextDocumentIdentifier::new(url),
-            position: self
-                .position
-                .map(|anchor| point_to_lsp(anchor.to_point_utf16(&buffer.snapshot()))),
+            position: self.position.map(|anchor| {
+                point_to_lsp_with_encoding(
+                    anchor.to_point_utf16(buffer),
+                    language_server.position_encoding(),
+                    buffer,
+                )
+            }),
         })
     }
 
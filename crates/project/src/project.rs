@@ -1,8 +1,10 @@
 pub mod agent_server_store;
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
 pub mod context_server_store;
+pub mod coverage_store;
 pub mod debounced_delay;
 pub mod debugger;
 pub mod git_store;
@@ -13,9 +15,11 @@ mod manifest_tree;
 pub mod prettier_store;
 pub mod project_settings;
 pub mod search;
+pub mod spell_check_store;
 mod task_inventory;
 pub mod task_store;
 pub mod terminals;
+pub mod test_results;
 pub mod toolchain_store;
 pub mod worktree_store;
 
@@ -117,8 +121,12 @@ use std::{
     time::Duration,
 };
 
+use bookmark_store::BookmarkStore;
+use coverage_store::CoverageStore;
+use spell_check_store::SpellCheckStore;
 use task_store::TaskStore;
 use terminals::Terminals;
+use test_results::TestResultStore;
 use text::{Anchor, BufferId, OffsetRangeExt, Point, Rope};
 use toolchain_store::EmptyToolchainStore;
 use util::{
@@ -186,6 +194,10 @@ pub struct Project {
     agent_server_store: Entity<AgentServerStore>,
 
     breakpoint_store: Entity<BreakpointStore>,
+    bookmark_store: Entity<BookmarkStore>,
+    test_results: Entity<TestResultStore>,
+    coverage_store: Entity<CoverageStore>,
+    spell_check_store: Entity<SpellCheckStore>,
     collab_client: Arc<client::Client>,
     join_project_response_message_id: u32,
     task_store: Entity<TaskStore>,
@@ -666,7 +678,7 @@ impl LspAction {
         }
     }
 
-    fn edit(&self) -> Option<&lsp::WorkspaceEdit> {
+    pub fn edit(&self) -> Option<&lsp::WorkspaceEdit> {
         match self {
             Self::Action(action) => action.edit.as_ref(),
             Self::Command(_) => None,
@@ -735,6 +747,26 @@ pub struct LocationLink {
     pub target: Location,
 }
 
+/// A link in a buffer, as reported by a language server's `textDocument/documentLink`.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    /// The id of the language server that produced this link.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer covered by the link.
+    pub range: Range<language::Anchor>,
+    /// The raw link provided by the language server.
+    pub lsp_link: lsp::DocumentLink,
+    /// Whether the link was resolved using the language server.
+    pub resolved: bool,
+}
+
+impl DocumentLink {
+    /// The URI the link points to. Servers may leave it empty until the link is resolved.
+    pub fn target(&self) -> Option<&lsp::Uri> {
+        self.lsp_link.target.as_ref()
+    }
+}
+
 #[derive(Debug)]
 pub struct DocumentHighlight {
     pub range: Range<language::Anchor>,
@@ -806,6 +838,7 @@ pub struct DirectoryItem {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct DocumentColor {
+    /// The color's range, with UTF-16 columns whatever the server's position encoding.
     pub lsp_range: lsp::Range,
     pub color: lsp::Color,
     pub resolved: bool,
@@ -1142,6 +1175,9 @@ impl Project {
                     cx,
                 )
             });
+            dap_store.update(cx, |dap_store, _| {
+                dap_store.set_lsp_store(lsp_store.downgrade())
+            });
 
             let git_store = cx.new(|cx| {
                 GitStore::local(
@@ -1159,6 +1195,23 @@ impl Project {
 
             cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
 
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
+
+            let coverage_store =
+                cx.new(|cx| CoverageStore::new(fs.clone(), worktree_store.clone(), cx));
+
+            let spell_check_store = cx.new(|cx| {
+                SpellCheckStore::new(
+                    fs.clone(),
+                    worktree_store.clone(),
+                    buffer_store.clone(),
+                    lsp_store.clone(),
+                    false,
+                    cx,
+                )
+            });
+
             Self {
                 buffer_ordered_messages_tx: tx,
                 collaborators: Default::default(),
@@ -1182,6 +1235,10 @@ impl Project {
                 fs,
                 remote_client: None,
                 breakpoint_store,
+                bookmark_store,
+                test_results: cx.new(|_| TestResultStore::default()),
+                coverage_store,
+                spell_check_store,
                 dap_store,
                 agent_server_store,
 
@@ -1325,6 +1382,23 @@ impl Project {
 
             cx.subscribe(&remote, Self::on_remote_client_event).detach();
 
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
+
+            let coverage_store =
+                cx.new(|cx| CoverageStore::new(fs.clone(), worktree_store.clone(), cx));
+
+            let spell_check_store = cx.new(|cx| {
+                SpellCheckStore::new(
+                    fs.clone(),
+                    worktree_store.clone(),
+                    buffer_store.clone(),
+                    lsp_store.clone(),
+                    false,
+                    cx,
+                )
+            });
+
             let this = Self {
                 buffer_ordered_messages_tx: tx,
                 collaborators: Default::default(),
@@ -1334,6 +1408,10 @@ impl Project {
                 lsp_store,
                 context_server_store,
                 breakpoint_store,
+                bookmark_store,
+                test_results: cx.new(|_| TestResultStore::default()),
+                coverage_store,
+                spell_check_store,
                 dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
@@ -1592,6 +1670,23 @@ impl Project {
 
             cx.subscribe(&dap_store, Self::on_dap_store_event).detach();
 
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
+
+            let coverage_store =
+                cx.new(|cx| CoverageStore::new(fs.clone(), worktree_store.clone(), cx));
+
+            let spell_check_store = cx.new(|cx| {
+                SpellCheckStore::new(
+                    fs.clone(),
+                    worktree_store.clone(),
+                    buffer_store.clone(),
+                    lsp_store.clone(),
+                    true,
+                    cx,
+                )
+            });
+
             let mut project = Self {
                 buffer_ordered_messages_tx: tx,
                 buffer_store: buffer_store.clone(),
@@ -1619,6 +1714,10 @@ impl Project {
                     replica_id,
                 },
                 breakpoint_store,
+                bookmark_store,
+                test_results: cx.new(|_| TestResultStore::default()),
+                coverage_store,
+                spell_check_store,
                 dap_store: dap_store.clone(),
                 git_store: git_store.clone(),
                 agent_server_store,
@@ -1826,6 +1925,22 @@ impl Project {
         self.breakpoint_store.clone()
     }
 
+    pub fn bookmark_store(&self) -> Entity<BookmarkStore> {
+        self.bookmark_store.clone()
+    }
+
+    pub fn test_results(&self) -> Entity<TestResultStore> {
+        self.test_results.clone()
+    }
+
+    pub fn coverage_store(&self) -> Entity<CoverageStore> {
+        self.coverage_store.clone()
+    }
+
+    pub fn spell_check_store(&self) -> Entity<SpellCheckStore> {
+        self.spell_check_store.clone()
+    }
+
     pub fn active_debug_session(&self, cx: &App) -> Option<(Entity<Session>, ActiveStackFrame)> {
         let active_position = self.breakpoint_store.read(cx).active_position()?;
         let session = self
@@ -3648,6 +3763,32 @@ impl Project {
         )
     }
 
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        let document_links = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.document_links(buffer, cx));
+        cx.background_spawn(async move {
+            document_links
+                .await
+                .map_err(|e| anyhow!("document links fetch failed: {e:#}"))
+        })
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(link, buffer, cx)
+        })
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3805,6 +3946,29 @@ impl Project {
         })
     }
 
+    pub fn resolve_code_action(
+        &self,
+        buffer_handle: &Entity<Buffer>,
+        action: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_code_action(buffer_handle, action, cx)
+        })
+    }
+
+    pub fn apply_workspace_edit(
+        &self,
+        edit: lsp::WorkspaceEdit,
+        server_id: LanguageServerId,
+        push_to_history: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.apply_workspace_edit(edit, server_id, push_to_history, cx)
+        })
+    }
+
     pub fn apply_code_action_kind(
         &self,
         buffers: HashSet<Entity<Buffer>>,
@@ -3853,6 +4017,21 @@ impl Project {
         )
     }
 
+    /// Like [`Self::perform_rename`], but returns the language server's edit instead of
+    /// applying it. Only local projects are supported.
+    pub fn rename_edit<T: ToPointUtf16>(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<(LanguageServerId, lsp::WorkspaceEdit)>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.rename_edit(buffer, position, new_name, cx)
+        })
+    }
+
     pub fn on_type_format<T: ToPointUtf16>(
         &mut self,
         buffer: Entity<Buffer>,
@@ -61,6 +61,12 @@ pub struct ProjectSettings {
     /// Configuration for Diagnostics-related features.
     pub diagnostics: DiagnosticsSettings,
 
+    /// Configuration for displaying code coverage reports.
+    pub coverage: CoverageSettings,
+
+    /// Configuration for spell checking comments, strings and prose.
+    pub spell_check: SpellCheckSettings,
+
     /// Configuration for Git-related features
     pub git: GitSettings,
 
@@ -317,6 +323,10 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: settings::GitHunkStyleSetting,
+    /// How diff views render changes when they are opened.
+    ///
+    /// Default: unified
+    pub diff_view_style: settings::DiffViewStyle,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -382,6 +392,24 @@ impl Default for BranchPickerSettings {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct CoverageSettings {
+    /// Whether to highlight covered, uncovered and partially covered lines in the gutter.
+    pub gutter: bool,
+    /// Paths of coverage reports, relative to the root of each worktree.
+    pub reports: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SpellCheckSettings {
+    /// Whether to check the spelling of comments, strings and prose.
+    pub enabled: bool,
+    /// The names of the Hunspell dictionaries to check words against.
+    pub dictionaries: Vec<String>,
+    /// Additional directories to look for dictionaries in.
+    pub dictionary_directories: Vec<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct DiagnosticsSettings {
     /// Whether to show the project diagnostics button in the status bar.
@@ -470,6 +498,7 @@ impl Settings for ProjectSettings {
                 }
             },
             hunk_style: git.hunk_style.unwrap(),
+            diff_view_style: git.diff_view_style.unwrap(),
         };
         Self {
             context_servers: project
@@ -513,6 +542,21 @@ impl Settings for ProjectSettings {
                     max_severity: inline_diagnostics.max_severity.map(Into::into),
                 },
             },
+            coverage: {
+                let coverage = content.coverage.clone().unwrap();
+                CoverageSettings {
+                    gutter: coverage.gutter.unwrap(),
+                    reports: coverage.reports.unwrap(),
+                }
+            },
+            spell_check: {
+                let spell_check = content.spell_check.clone().unwrap();
+                SpellCheckSettings {
+                    enabled: spell_check.enabled.unwrap(),
+                    dictionaries: spell_check.dictionaries.unwrap(),
+                    dictionary_directories: spell_check.dictionary_directories.unwrap(),
+                }
+            },
             git: git_settings,
             node: content.node.clone().unwrap().into(),
             load_direnv: project.load_direnv.clone().unwrap(),
@@ -2,6 +2,7 @@
 
 use crate::{
     Event,
+    coverage_store::LineCoverage,
     git_store::{GitStoreEvent, RepositoryEvent, StatusEntry},
     task_inventory::TaskContexts,
     task_store::TaskSettingsLocation,
@@ -3275,6 +3276,191 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_utf8_position_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn 🦀() {}\nconst É: i32 = 🦀();",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                position_encoding: Some(lsp::PositionEncodingKind::UTF8),
+                text_document_sync: Some(lsp::TextDocumentSyncCapability::Kind(
+                    lsp::TextDocumentSyncKind::INCREMENTAL,
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await;
+
+    // Edits are reported in byte columns.
+    buffer.update(cx, |buffer, cx| {
+        let offset = buffer.text().find("();").unwrap();
+        buffer.edit([(offset..offset, "_x")], None, cx);
+    });
+    let change = fake_server
+        .receive_notification::<lsp::notification::DidChangeTextDocument>()
+        .await;
+    assert_eq!(
+        change.content_changes,
+        [lsp::TextDocumentContentChangeEvent {
+            range: Some(lsp::Range::new(
+                lsp::Position::new(1, 20),
+                lsp::Position::new(1, 20),
+            )),
+            range_length: None,
+            text: "_x".into(),
+        }]
+    );
+
+    // Request positions are sent in byte columns, and response ranges are read as such.
+    fake_server.set_request_handler::<lsp::request::GotoDefinition, _, _>(|params, _| async move {
+        assert_eq!(
+            params.text_document_position_params.position,
+            lsp::Position::new(1, 22)
+        );
+        Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+            lsp::Location::new(
+                lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+                lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+            ),
+        )))
+    });
+    let offset = buffer.read_with(cx, |buffer, _| buffer.text().find("();").unwrap());
+    let definitions = project
+        .update(cx, |project, cx| project.definitions(&buffer, offset, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(definitions.len(), 1);
+    cx.update(|cx| {
+        let target_buffer = definitions[0].target.buffer.read(cx);
+        assert_eq!(
+            target_buffer
+                .text_for_range(definitions[0].target.range.to_offset(target_buffer))
+                .collect::<String>(),
+            "🦀"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_utf8_position_encoding_in_closed_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "",
+            "b.rs": "const É: i32 = 1;\nfn 🦀() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                position_encoding: Some(lsp::PositionEncodingKind::UTF8),
+                workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let _buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    let crab_range = lsp::Range::new(lsp::Position::new(1, 3), lsp::Position::new(1, 7));
+
+    // Workspace symbols are converted against the file on disk.
+    #[allow(deprecated)]
+    let symbol = lsp::SymbolInformation {
+        name: "🦀".into(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        deprecated: None,
+        location: lsp::Location::new(
+            lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+            crab_range,
+        ),
+        container_name: None,
+    };
+    fake_server.set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(move |_, _| {
+        let symbol = symbol.clone();
+        async move { Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![symbol]))) }
+    });
+    let symbols = project
+        .update(cx, |project, cx| project.symbols("", cx))
+        .await
+        .unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(
+        symbols[0].range,
+        Unclipped(PointUtf16::new(1, 3))..Unclipped(PointUtf16::new(1, 5))
+    );
+
+    // Diagnostics for closed files are converted once the file is opened.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
+        uri: Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: crab_range,
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: "unnamed function".to_string(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| entry.range)
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics, [Point::new(1, 3)..Point::new(1, 7)]);
+    });
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -9585,6 +9771,182 @@ async fn search(
         .collect())
 }
 
+#[gpui::test]
+async fn test_coverage_reports(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "coverage": {
+                "lcov.info": "SF:src/lib.rs\nDA:1,1\nDA:2,0\nDA:3,1\nend_of_record\n",
+            },
+            "src": {
+                "lib.rs": "fn a() {\n    b();\n}\n",
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.executor().run_until_parked();
+
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let project_path = ProjectPath {
+        worktree_id,
+        path: rel_path("src/lib.rs").into(),
+    };
+    let coverage = |cx: &mut gpui::TestAppContext| {
+        project.read_with(cx, |project, cx| {
+            project
+                .coverage_store()
+                .read(cx)
+                .coverage_for_path(&project_path)
+                .map(|coverage| coverage.lines.clone().into_iter().collect::<Vec<_>>())
+        })
+    };
+    assert_eq!(
+        coverage(cx),
+        Some(vec![
+            (0, LineCoverage::Covered),
+            (1, LineCoverage::Uncovered),
+            (2, LineCoverage::Covered),
+        ])
+    );
+
+    // Regenerating the report refreshes the coverage.
+    fs.insert_file(
+        path!("/dir/coverage/lcov.info"),
+        "SF:src/lib.rs\nDA:1,1\nDA:2,4\nDA:3,1\nend_of_record\n"
+            .as_bytes()
+            .to_vec(),
+    )
+    .await;
+    cx.executor().run_until_parked();
+    assert_eq!(
+        coverage(cx),
+        Some(vec![
+            (0, LineCoverage::Covered),
+            (1, LineCoverage::Covered),
+            (2, LineCoverage::Covered),
+        ])
+    );
+
+    fs.remove_file(
+        path!("/dir/coverage/lcov.info").as_ref(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(coverage(cx), None);
+}
+
+#[gpui::test]
+async fn test_spell_check(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings(cx, |settings| {
+                settings.spell_check = Some(settings::SpellCheckSettingsContent {
+                    enabled: Some(true),
+                    dictionaries: Some(vec!["en_US".into()]),
+                    dictionary_directories: Some(vec![path!("/dictionaries").into()]),
+                });
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dictionaries"),
+        json!({
+            "en_US.aff": "SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n",
+            "en_US.dic": "3\nhello\nworld/S\nthe\n",
+        }),
+    )
+    .await;
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "notes.txt": "Hello wrold, hello the worlds.\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/notes.txt"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+
+    let misspellings = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            snapshot
+                .diagnostics_in_range::<_, usize>(0..snapshot.len(), false)
+                .map(|entry| {
+                    assert_eq!(entry.diagnostic.severity, DiagnosticSeverity::WARNING);
+                    snapshot.text_for_range(entry.range).collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    // Misspellings are summarized along with the project's other diagnostics.
+    let summaries = |cx: &mut gpui::TestAppContext| {
+        project.read_with(cx, |project, cx| {
+            let spell_check_server_id = project.spell_check_store().read(cx).server_id();
+            project
+                .diagnostic_summaries(false, cx)
+                .map(|(path, server_id, summary)| {
+                    assert_eq!(server_id, spell_check_server_id);
+                    (path.path.as_unix_str().to_string(), summary.warning_count)
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(misspellings(cx), ["wrold"]);
+    assert_eq!(summaries(cx), [("notes.txt".to_string(), 1)]);
+    project.read_with(cx, |project, cx| {
+        assert_eq!(
+            project.spell_check_store().read(cx).suggestions("wrold", 3),
+            ["world"]
+        );
+    });
+
+    // Words added to the project's word list are accepted from then on.
+    project
+        .update(cx, |project, cx| {
+            project.spell_check_store().update(cx, |store, cx| {
+                store.add_to_project_words("wrold".into(), &buffer, cx)
+            })
+        })
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert!(misspellings(cx).is_empty());
+    assert!(summaries(cx).is_empty());
+    assert_eq!(
+        fs.load(path!("/dir/.zed/words.txt").as_ref())
+            .await
+            .unwrap(),
+        "wrold\n"
+    );
+
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "Teh ")], None, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(misspellings(cx), ["Teh"]);
+    assert_eq!(summaries(cx), [("notes.txt".to_string(), 1)]);
+}
+
 pub fn init_test(cx: &mut gpui::TestAppContext) {
     zlog::init_test();
 
@@ -0,0 +1,615 @@
+//! Offline spell checking of comments, strings and prose.
+//!
+//! Misspelled words are stored as warnings through the [`LspStore`], under a language server id
+//! of their own, so that they're underlined, navigable and summarized like any other diagnostic.
+//! In code, only the text highlighted as a comment or a string is checked, while Markdown and
+//! plain text files are checked entirely.
+use crate::{
+    buffer_store::{BufferStore, BufferStoreEvent},
+    debounced_delay::DebouncedDelay,
+    lsp_store::LspStore,
+    project_settings::ProjectSettings,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
+};
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, Subscription, Task, WeakEntity};
+use language::{
+    Buffer, BufferEvent, BufferSnapshot, Diagnostic, DiagnosticEntry, DiagnosticSet,
+    DiagnosticSeverity, File as _, PointUtf16, Unclipped,
+};
+use lsp::LanguageServerId;
+use settings::{Settings as _, SettingsLocation, SettingsStore};
+use spellcheck::{Dictionary, SpellChecker};
+use std::{ops::Range, path::PathBuf, sync::Arc, time::Duration};
+use text::BufferId;
+use util::{ResultExt as _, rel_path::RelPath};
+
+/// The source of the diagnostics reporting misspelled words.
+pub const SPELL_CHECK_DIAGNOSTIC_SOURCE: &str = "spell check";
+
+const CHECK_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Languages whose text is checked entirely, rather than only in comments and strings.
+const PROSE_LANGUAGES: [&str; 2] = ["Markdown", "Plain Text"];
+
+struct CheckedBuffer {
+    buffer: WeakEntity<Buffer>,
+    has_diagnostics: bool,
+    /// Whether spell checking was enabled for the buffer when it was last checked.
+    is_enabled: bool,
+    check: DebouncedDelay<SpellCheckStore>,
+    _subscription: Subscription,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct DictionaryConfig {
+    dictionaries: Vec<String>,
+    dictionary_directories: Vec<String>,
+}
+
+pub struct SpellCheckStore {
+    fs: Arc<dyn Fs>,
+    worktree_store: Entity<WorktreeStore>,
+    lsp_store: Entity<LspStore>,
+    /// The id under which misspellings are stored among the project's diagnostics.
+    server_id: LanguageServerId,
+    /// Spell checking is off in projects joined via collab, whose host checks its buffers.
+    is_via_collab: bool,
+    dictionary_config: Option<DictionaryConfig>,
+    dictionaries: Vec<Arc<Dictionary>>,
+    accepted_words: HashSet<String>,
+    checker: Option<SpellChecker>,
+    buffers: HashMap<BufferId, CheckedBuffer>,
+    load_dictionaries: Task<()>,
+    load_accepted_words: Task<()>,
+    _subscriptions: [Subscription; 3],
+}
+
+impl SpellCheckStore {
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        worktree_store: Entity<WorktreeStore>,
+        buffer_store: Entity<BufferStore>,
+        lsp_store: Entity<LspStore>,
+        is_via_collab: bool,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscriptions = [
+            cx.subscribe(&buffer_store, |this, _, event, cx| match event {
+                BufferStoreEvent::BufferAdded(buffer) => this.register_buffer(buffer, cx),
+                BufferStoreEvent::BufferDropped(buffer_id) => {
+                    this.buffers.remove(buffer_id);
+                }
+                BufferStoreEvent::BufferChangedFilePath { buffer, old_file } => {
+                    this.clear_diagnostics_for_old_path(old_file.as_ref(), cx);
+                    this.schedule_check(buffer.read(cx).remote_id(), cx)
+                }
+                _ => {}
+            }),
+            cx.subscribe(&worktree_store, |this, _, event, cx| match event {
+                WorktreeStoreEvent::WorktreeAdded(_) | WorktreeStoreEvent::WorktreeRemoved(..) => {
+                    this.load_accepted_words(cx);
+                    this.settings_changed(cx);
+                }
+                _ => {}
+            }),
+            cx.observe_global::<SettingsStore>(|this, cx| this.settings_changed(cx)),
+        ];
+        let server_id = lsp_store.read(cx).languages.next_language_server_id();
+        let mut this = Self {
+            fs,
+            worktree_store,
+            lsp_store,
+            server_id,
+            is_via_collab,
+            dictionary_config: None,
+            dictionaries: Vec::new(),
+            accepted_words: HashSet::default(),
+            checker: None,
+            buffers: HashMap::default(),
+            load_dictionaries: Task::ready(()),
+            load_accepted_words: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        for buffer in buffer_store.read(cx).buffers().collect::<Vec<_>>() {
+            this.register_buffer(&buffer, cx);
+        }
+        this.load_accepted_words(cx);
+        this.settings_changed(cx);
+        this
+    }
+
+    /// Returns the id under which misspellings are stored among the project's diagnostics.
+    pub fn server_id(&self) -> LanguageServerId {
+        self.server_id
+    }
+
+    /// Returns replacements for a misspelled word, best first.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        self.checker
+            .as_ref()
+            .map_or_else(Vec::new, |checker| checker.suggest(word, limit))
+    }
+
+    /// Accepts the word in every project, by adding it to the user's dictionary.
+    pub fn add_to_user_dictionary(
+        &mut self,
+        word: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.add_accepted_word(word, paths::user_dictionary_file().clone(), cx)
+    }
+
+    /// Accepts the word in this project, by adding it to the word list of the worktree
+    /// containing the buffer.
+    pub fn add_to_project_words(
+        &mut self,
+        word: String,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| {
+                self.worktree_store
+                    .read(cx)
+                    .worktree_for_id(file.worktree_id(cx), cx)
+            })
+            .filter(|worktree| worktree.read(cx).is_local())
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "buffer does not belong to a local worktree"
+            )));
+        };
+        let path = worktree
+            .read(cx)
+            .abs_path()
+            .join(paths::local_words_file_relative_path().as_std_path());
+        self.add_accepted_word(word, path, cx)
+    }
+
+    fn add_accepted_word(
+        &mut self,
+        word: String,
+        path: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.accepted_words.insert(word.clone());
+        self.update_checker(cx);
+        let fs = self.fs.clone();
+        cx.background_spawn(async move {
+            let mut text = fs.load(&path).await.unwrap_or_default();
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&word);
+            text.push('\n');
+            if let Some(parent) = path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            fs.atomic_write(path.clone(), text)
+                .await
+                .with_context(|| format!("writing word list {path:?}"))
+        })
+    }
+
+    fn settings_changed(&mut self, cx: &mut Context<Self>) {
+        let settings = &ProjectSettings::get_global(cx).spell_check;
+        let config = DictionaryConfig {
+            dictionaries: settings.dictionaries.clone(),
+            dictionary_directories: settings.dictionary_directories.clone(),
+        };
+        // Dictionaries take a while to load, so they're only loaded once they're needed.
+        if self.is_enabled_anywhere(cx) && self.dictionary_config.as_ref() != Some(&config) {
+            self.load_dictionaries(config.clone(), cx);
+            self.dictionary_config = Some(config);
+        }
+        // Other settings changes don't affect spell checking, so only the buffers for which it
+        // was turned on or off are checked again.
+        let buffer_ids = self
+            .buffers
+            .iter()
+            .filter(|(_, checked_buffer)| {
+                checked_buffer.buffer.upgrade().is_some_and(|buffer| {
+                    is_enabled_for_buffer(buffer.read(cx), cx) != checked_buffer.is_enabled
+                })
+            })
+            .map(|(buffer_id, _)| *buffer_id)
+            .collect::<Vec<_>>();
+        for buffer_id in buffer_ids {
+            self.schedule_check(buffer_id, cx);
+        }
+    }
+
+    fn is_enabled_anywhere(&self, cx: &App) -> bool {
+        !self.is_via_collab
+            && (ProjectSettings::get_global(cx).spell_check.enabled
+                || self
+                    .worktree_store
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .any(|worktree| {
+                        let settings = ProjectSettings::get(
+                            Some(SettingsLocation {
+                                worktree_id: worktree.read(cx).id(),
+                                path: RelPath::empty(),
+                            }),
+                            cx,
+                        );
+                        settings.spell_check.enabled
+                    }))
+    }
+
+    fn load_dictionaries(&mut self, config: DictionaryConfig, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        let directories = config
+            .dictionary_directories
+            .iter()
+            .map(PathBuf::from)
+            .chain([paths::dictionaries_dir().clone()])
+            .chain(system_dictionary_dirs())
+            .collect::<Vec<_>>();
+        self.load_dictionaries = cx.spawn(async move |this, cx| {
+            let mut dictionaries = Vec::new();
+            for name in &config.dictionaries {
+                match load_dictionary(fs.as_ref(), name, &directories, cx).await {
+                    Ok(Some(dictionary)) => dictionaries.push(Arc::new(dictionary)),
+                    Ok(None) => log::warn!("spell check dictionary {name:?} not found"),
+                    Err(error) => log::error!("failed to load dictionary {name:?}: {error:#}"),
+                }
+            }
+            this.update(cx, |this, cx| {
+                this.dictionaries = dictionaries;
+                this.update_checker(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn load_accepted_words(&mut self, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        let paths = self
+            .worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter(|worktree| worktree.read(cx).is_local())
+            .map(|worktree| {
+                worktree
+                    .read(cx)
+                    .abs_path()
+                    .join(paths::local_words_file_relative_path().as_std_path())
+            })
+            .chain([paths::user_dictionary_file().clone()])
+            .collect::<Vec<_>>();
+        self.load_accepted_words = cx.spawn(async move |this, cx| {
+            let mut accepted_words = HashSet::default();
+            for path in paths {
+                // Word lists are optional, and don't exist until a word is added.
+                if let Ok(text) = fs.load(&path).await {
+                    accepted_words.extend(
+                        text.lines()
+                            .map(str::trim)
+                            .filter(|word| !word.is_empty() && !word.starts_with('#'))
+                            .map(str::to_string),
+                    );
+                }
+            }
+            this.update(cx, |this, cx| {
+                this.accepted_words = accepted_words;
+                this.update_checker(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn update_checker(&mut self, cx: &mut Context<Self>) {
+        self.checker = (!self.dictionaries.is_empty())
+            .then(|| SpellChecker::new(self.dictionaries.clone(), self.accepted_words.clone()));
+        let buffer_ids = self.buffers.keys().copied().collect::<Vec<_>>();
+        for buffer_id in buffer_ids {
+            self.schedule_check(buffer_id, cx);
+        }
+    }
+
+    fn register_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        if self.is_via_collab {
+            return;
+        }
+        let buffer_id = buffer.read(cx).remote_id();
+        let subscription = cx.subscribe(buffer, move |this, _, event, cx| match event {
+            BufferEvent::Edited | BufferEvent::Reparsed | BufferEvent::LanguageChanged => {
+                this.schedule_check(buffer_id, cx)
+            }
+            _ => {}
+        });
+        self.buffers.insert(
+            buffer_id,
+            CheckedBuffer {
+                buffer: buffer.downgrade(),
+                has_diagnostics: false,
+                is_enabled: false,
+                check: DebouncedDelay::new(),
+                _subscription: subscription,
+            },
+        );
+        self.schedule_check(buffer_id, cx);
+    }
+
+    fn schedule_check(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        let Some(checked_buffer) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        checked_buffer
+            .check
+            .fire_new(CHECK_DEBOUNCE, cx, move |this, cx| {
+                this.check_buffer(buffer_id, cx)
+            });
+    }
+
+    fn check_buffer(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) -> Task<()> {
+        let Some(checked_buffer) = self.buffers.get_mut(&buffer_id) else {
+            return Task::ready(());
+        };
+        let Some(buffer) = checked_buffer.buffer.upgrade() else {
+            self.buffers.remove(&buffer_id);
+            return Task::ready(());
+        };
+        checked_buffer.is_enabled = is_enabled_for_buffer(buffer.read(cx), cx);
+        let checker = self.checker.clone().filter(|_| checked_buffer.is_enabled);
+        let Some(checker) = checker else {
+            if checked_buffer.has_diagnostics {
+                checked_buffer.has_diagnostics = false;
+                self.update_diagnostics(&buffer, Vec::new(), cx);
+            }
+            return Task::ready(());
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let version = snapshot.version().clone();
+        cx.spawn(async move |this, cx| {
+            let diagnostics = cx
+                .background_spawn(async move { misspelling_diagnostics(&checker, &snapshot) })
+                .await;
+            this.update(cx, |this, cx| {
+                // The buffer was edited while it was checked, so it's about to be checked again.
+                if buffer.read(cx).version() != version {
+                    return;
+                }
+                let Some(checked_buffer) = this.buffers.get_mut(&buffer_id) else {
+                    return;
+                };
+                if diagnostics.is_empty() && !checked_buffer.has_diagnostics {
+                    return;
+                }
+                checked_buffer.has_diagnostics = !diagnostics.is_empty();
+                this.update_diagnostics(&buffer, diagnostics, cx);
+            })
+            .ok();
+        })
+    }
+
+    /// Stores the buffer's misspellings through the LSP store, which keeps the project's
+    /// diagnostic summaries up to date. Buffers it doesn't store diagnostics for, such as those
+    /// of remote projects or without a file, are updated directly.
+    fn update_diagnostics(
+        &self,
+        buffer: &Entity<Buffer>,
+        diagnostics: Vec<DiagnosticEntry<Unclipped<PointUtf16>>>,
+        cx: &mut Context<Self>,
+    ) {
+        let abs_path = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx));
+        if let Some(abs_path) = abs_path
+            && self.lsp_store.read(cx).as_local().is_some()
+        {
+            self.lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store
+                    .update_diagnostic_entries(
+                        self.server_id,
+                        abs_path,
+                        None,
+                        None,
+                        diagnostics,
+                        cx,
+                    )
+                    .log_err();
+            });
+        } else {
+            buffer.update(cx, |buffer, cx| {
+                let diagnostics = DiagnosticSet::new(
+                    diagnostics.into_iter().map(|entry| DiagnosticEntry {
+                        range: entry.range.start.0..entry.range.end.0,
+                        diagnostic: entry.diagnostic,
+                    }),
+                    buffer,
+                );
+                buffer.update_diagnostics(self.server_id, diagnostics, cx)
+            });
+        }
+    }
+
+    /// Removes the misspellings of a buffer whose file was renamed from the summary of its
+    /// previous path.
+    fn clear_diagnostics_for_old_path(
+        &self,
+        old_file: Option<&Arc<dyn language::File>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = old_file
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return;
+        };
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            if lsp_store.as_local().is_some() {
+                lsp_store
+                    .update_diagnostic_entries(self.server_id, abs_path, None, None, Vec::new(), cx)
+                    .log_err();
+            }
+        });
+    }
+}
+
+fn is_enabled_for_buffer(buffer: &Buffer, cx: &App) -> bool {
+    let location = buffer.file().map(|file| SettingsLocation {
+        worktree_id: file.worktree_id(cx),
+        path: file.path().as_ref(),
+    });
+    ProjectSettings::get(location, cx).spell_check.enabled
+}
+
+/// The directories Hunspell dictionaries are installed in by the system's package manager.
+fn system_dictionary_dirs() -> Vec<PathBuf> {
+    if cfg!(target_os = "macos") {
+        vec![
+            paths::home_dir().join("Library/Spelling"),
+            PathBuf::from("/Library/Spelling"),
+        ]
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        [
+            "/usr/share/hunspell",
+            "/usr/share/myspell",
+            "/usr/share/myspell/dicts",
+            "/usr/local/share/hunspell",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Loads the dictionary with the given name from the first directory containing both its
+/// `.aff` and `.dic` files.
+async fn load_dictionary(
+    fs: &dyn Fs,
+    name: &str,
+    directories: &[PathBuf],
+    cx: &gpui::AsyncApp,
+) -> Result<Option<Dictionary>> {
+    for directory in directories {
+        let aff_path = directory.join(format!("{name}.aff"));
+        let dic_path = directory.join(format!("{name}.dic"));
+        let (Ok(aff), Ok(dic)) = (
+            fs.load_bytes(&aff_path).await,
+            fs.load_bytes(&dic_path).await,
+        ) else {
+            continue;
+        };
+        let dictionary = cx
+            .background_spawn(async move { Dictionary::new(&aff, &dic) })
+            .await
+            .with_context(|| format!("parsing {dic_path:?}"))?;
+        return Ok(Some(dictionary));
+    }
+    Ok(None)
+}
+
+fn misspelling_diagnostics(
+    checker: &SpellChecker,
+    snapshot: &BufferSnapshot,
+) -> Vec<DiagnosticEntry<Unclipped<PointUtf16>>> {
+    let text = snapshot.text();
+    let (ranges, excluded_ranges) = checked_ranges(snapshot);
+    let mut misspellings = checker.misspellings(&text, ranges);
+    misspellings.sort_by_key(|range| (range.start, range.end));
+    misspellings.dedup();
+    misspellings.retain(|range| {
+        !excluded_ranges
+            .iter()
+            .any(|excluded| excluded.start < range.end && range.start < excluded.end)
+    });
+
+    misspellings
+        .into_iter()
+        .enumerate()
+        .map(|(group_id, range)| DiagnosticEntry {
+            range: Unclipped(snapshot.offset_to_point_utf16(range.start))
+                ..Unclipped(snapshot.offset_to_point_utf16(range.end)),
+            diagnostic: Diagnostic {
+                source: Some(SPELL_CHECK_DIAGNOSTIC_SOURCE.to_string()),
+                severity: DiagnosticSeverity::WARNING,
+                message: format!("Unknown word `{}`", &text[range]),
+                group_id,
+                is_primary: true,
+                ..Diagnostic::default()
+            },
+        })
+        .collect()
+}
+
+/// Returns the byte ranges of the buffer that should be checked, and the ones within them that
+/// shouldn't, such as escape sequences, inline code and links.
+fn checked_ranges(snapshot: &BufferSnapshot) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let mut ranges = Vec::new();
+    let mut excluded_ranges = Vec::new();
+    let language = snapshot.language();
+    let is_prose =
+        language.is_none_or(|language| PROSE_LANGUAGES.contains(&language.name().as_ref()));
+    if is_prose {
+        ranges.push(0..snapshot.len());
+        // Code blocks are checked like the rest of their language's code.
+        if let Some(language) = language {
+            excluded_ranges.extend(
+                snapshot
+                    .syntax_layers()
+                    .filter(|layer| !Arc::ptr_eq(layer.language, language))
+                    .map(|layer| layer.node().byte_range()),
+            );
+        }
+    }
+
+    let mut matches = snapshot.matches(0..snapshot.len(), |grammar| {
+        grammar
+            .highlights_config
+            .as_ref()
+            .map(|config| &config.query)
+    });
+    let capture_names = matches
+        .grammars()
+        .iter()
+        .map(|grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map_or(&[][..], |config| config.query.capture_names())
+        })
+        .collect::<Vec<_>>();
+    while let Some(mat) = matches.peek() {
+        for capture in mat.captures {
+            let name = capture_names[mat.grammar_index][capture.index as usize];
+            let range = capture.node.byte_range();
+            if is_excluded_capture(name) {
+                excluded_ranges.push(range);
+            } else if !is_prose && is_checked_capture(name) {
+                ranges.push(range);
+            }
+        }
+        matches.advance();
+    }
+    (ranges, excluded_ranges)
+}
+
+fn is_checked_capture(name: &str) -> bool {
+    ["comment", "string"].iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+fn is_excluded_capture(name: &str) -> bool {
+    name.split('.').any(|part| {
+        matches!(
+            part,
+            "escape" | "regex" | "special" | "literal" | "link_uri" | "embedded"
+        )
+    })
+}
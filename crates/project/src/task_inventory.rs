@@ -28,7 +28,9 @@ use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, post_inc, rel_path::RelPath};
 use worktree::WorktreeId;
 
-use crate::{task_store::TaskSettingsLocation, worktree_store::WorktreeStore};
+use crate::{
+    coverage_store::CoverageStore, task_store::TaskSettingsLocation, worktree_store::WorktreeStore,
+};
 
 #[derive(Clone, Debug, Default)]
 pub struct DebugScenarioContext {
@@ -1029,6 +1031,12 @@ impl ContextProvider for BasicContextProvider {
                 VariableName::WorktreeRoot,
                 worktree.abs_path().to_string_lossy().into_owned(),
             );
+            if let Some(report_path) = CoverageStore::report_path_for_worktree(worktree, cx) {
+                task_variables.insert(
+                    VariableName::CoverageReport,
+                    report_path.to_string_lossy().into_owned(),
+                );
+            }
             if let Some(current_file) = current_file.as_ref() {
                 let relative_path = current_file.path();
                 task_variables.insert(
@@ -0,0 +1,83 @@
+//! Statuses of the tests found in project buffers, as last reported by a test run.
+//!
+//! The store only holds what should be displayed; discovering and running tests is up to its
+//! clients, which publish statuses for the tests they know about in each buffer.
+use collections::HashMap;
+use gpui::{Context, EventEmitter};
+use text::{Anchor, BufferId, BufferSnapshot, ToPoint as _};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TestStatus {
+    Running,
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl TestStatus {
+    /// Combines the statuses of several runs of the same test, or of the tests within a group,
+    /// into the one that needs the most attention.
+    pub fn merge(self, other: Self) -> Self {
+        fn priority(status: TestStatus) -> u8 {
+            match status {
+                TestStatus::Skipped => 0,
+                TestStatus::Passed => 1,
+                TestStatus::Running => 2,
+                TestStatus::Failed => 3,
+            }
+        }
+        if priority(other) > priority(self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+pub enum TestResultsEvent {
+    StatusesChanged(BufferId),
+}
+
+#[derive(Default)]
+pub struct TestResultStore {
+    statuses: HashMap<BufferId, Vec<(Anchor, TestStatus)>>,
+}
+
+impl EventEmitter<TestResultsEvent> for TestResultStore {}
+
+impl TestResultStore {
+    /// Replaces the statuses of the tests in the given buffer, each anchored at the
+    /// start of the test's runnable range.
+    pub fn set_statuses(
+        &mut self,
+        buffer_id: BufferId,
+        statuses: Vec<(Anchor, TestStatus)>,
+        cx: &mut Context<Self>,
+    ) {
+        if statuses.is_empty() {
+            if self.statuses.remove(&buffer_id).is_none() {
+                return;
+            }
+        } else {
+            self.statuses.insert(buffer_id, statuses);
+        }
+        cx.emit(TestResultsEvent::StatusesChanged(buffer_id));
+        cx.notify();
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        for (buffer_id, _) in self.statuses.drain() {
+            cx.emit(TestResultsEvent::StatusesChanged(buffer_id));
+        }
+        cx.notify();
+    }
+
+    pub fn status_for_row(&self, buffer: &BufferSnapshot, row: u32) -> Option<TestStatus> {
+        self.statuses
+            .get(&buffer.remote_id())?
+            .iter()
+            .filter(|(anchor, _)| anchor.to_point(buffer).row == row)
+            .map(|(_, status)| *status)
+            .reduce(TestStatus::merge)
+    }
+}
@@ -38,8 +38,8 @@ use rayon::slice::ParallelSliceMut;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
-    DockSide, ProjectPanelEntrySpacing, Settings, SettingsStore, ShowDiagnostics, ShowIndentGuides,
-    update_settings_file,
+    DockSide, ProjectPanelEntrySpacing, ProjectPanelSortMode, Settings, SettingsStore,
+    ShowDiagnostics, ShowIndentGuides, update_settings_file,
 };
 use smallvec::SmallVec;
 use std::{any::TypeId, time::Instant};
@@ -50,7 +50,7 @@ use std::{
     ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 use theme::ThemeSettings;
 use ui::{
@@ -59,7 +59,7 @@ use ui::{
     ScrollAxes, ScrollableHandle, Scrollbars, StickyCandidate, Tooltip, WithScrollbar, prelude::*,
     v_flex,
 };
-use util::{ResultExt, TakeUntilExt, TryFutureExt, maybe, paths::compare_paths, rel_path::RelPath};
+use util::{ResultExt, TakeUntilExt, TryFutureExt, maybe, rel_path::RelPath};
 use workspace::{
     DraggedSelection, OpenInTerminal, OpenOptions, OpenVisible, PreviewTabsSettings, SelectedEntry,
     SplitDirection, Workspace,
@@ -208,6 +208,7 @@ struct EntryDetails {
     sticky: Option<StickyDetails>,
     filename_text_color: Color,
     diagnostic_severity: Option<DiagnosticSeverity>,
+    coverage_percentage: Option<u8>,
     git_status: GitSummary,
     is_private: bool,
     worktree_id: WorktreeId,
@@ -658,6 +659,11 @@ impl ProjectPanel {
             })
             .detach();
 
+            cx.observe(&project.read(cx).coverage_store(), |_, _, cx| {
+                cx.notify();
+            })
+            .detach();
+
             let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
             cx.observe_global_in::<SettingsStore>(window, move |this, window, cx| {
                 let new_settings = *ProjectPanelSettings::get_global(cx);
@@ -668,6 +674,11 @@ impl ProjectPanel {
                     if project_panel_settings.hide_root != new_settings.hide_root {
                         this.update_visible_entries(None, false, false, window, cx);
                     }
+                    if project_panel_settings.sort_mode != new_settings.sort_mode
+                        || project_panel_settings.natural_sort != new_settings.natural_sort
+                    {
+                        this.update_visible_entries(None, false, false, window, cx);
+                    }
                     if project_panel_settings.sticky_scroll && !new_settings.sticky_scroll {
                         this.sticky_items_count = 0;
                     }
@@ -2031,6 +2042,9 @@ impl ProjectPanel {
             .iter()
             .filter(|e| e.worktree_id == worktree_id)
             .collect::<HashSet<_>>();
+        let settings = ProjectPanelSettings::get_global(cx);
+        let worktree_snapshot = worktree.snapshot();
+        let dir_mtime = worktree_dir_mtime(&worktree_snapshot);
         let latest_entry = marked_entries_in_worktree
             .iter()
             .max_by(|a, b| {
@@ -2038,9 +2052,12 @@ impl ProjectPanel {
                     worktree.entry_for_id(a.entry_id),
                     worktree.entry_for_id(b.entry_id),
                 ) {
-                    (Some(a), Some(b)) => compare_paths(
-                        (a.path.as_std_path(), a.is_file()),
-                        (b.path.as_std_path(), b.is_file()),
+                    (Some(a), Some(b)) => cmp_with_sort_mode(
+                        a,
+                        b,
+                        settings.sort_mode,
+                        settings.natural_sort,
+                        &dir_mtime,
                     ),
                     _ => cmp::Ordering::Equal,
                 }
@@ -2052,8 +2069,7 @@ impl ProjectPanel {
 
         // Remove all siblings that are being deleted except the last marked entry
         let repo_snapshots = git_store.repo_snapshots(cx);
-        let worktree_snapshot = worktree.snapshot();
-        let hide_gitignore = ProjectPanelSettings::get_global(cx).hide_gitignore;
+        let hide_gitignore = settings.hide_gitignore;
         let mut siblings: Vec<_> =
             ChildEntriesGitIter::new(&repo_snapshots, &worktree_snapshot, parent_path)
                 .filter(|sibling| {
@@ -2066,7 +2082,12 @@ impl ProjectPanel {
                 .map(|entry| entry.to_owned())
                 .collect();
 
-        sort_worktree_entries(&mut siblings);
+        sort_worktree_entries_with_mode(
+            &mut siblings,
+            settings.sort_mode,
+            settings.natural_sort,
+            &worktree_snapshot,
+        );
         let sibling_entry_index = siblings
             .iter()
             .position(|sibling| sibling.id == latest_entry.id)?;
@@ -3187,6 +3208,8 @@ impl ProjectPanel {
         let settings = ProjectPanelSettings::get_global(cx);
         let auto_collapse_dirs = settings.auto_fold_dirs;
         let hide_gitignore = settings.hide_gitignore;
+        let sort_mode = settings.sort_mode;
+        let natural_sort = settings.natural_sort;
         let project = self.project.read(cx);
         let repo_snapshots = project.git_store().read(cx).repo_snapshots(cx);
 
@@ -3391,7 +3414,12 @@ impl ProjectPanel {
                             entry_iter.advance();
                         }
 
-                        par_sort_worktree_entries(&mut visible_worktree_entries);
+                        par_sort_worktree_entries_with_mode(
+                            &mut visible_worktree_entries,
+                            sort_mode,
+                            natural_sort,
+                            &worktree_snapshot,
+                        );
                         new_state.visible_entries.push(VisibleEntriesForWorktree {
                             worktree_id,
                             entries: visible_worktree_entries,
@@ -4265,6 +4293,7 @@ impl ProjectPanel {
 
         let filename_text_color = details.filename_text_color;
         let diagnostic_severity = details.diagnostic_severity;
+        let coverage_percentage = details.coverage_percentage;
         let item_colors = get_item_color(is_sticky, cx);
 
         let canonical_path = details
@@ -4652,28 +4681,44 @@ impl ProjectPanel {
                         }
                     })
                     .selectable(false)
-                    .when_some(canonical_path, |this, path| {
-                        this.end_slot::<AnyElement>(
-                            div()
-                                .id("symlink_icon")
-                                .pr_3()
-                                .tooltip(move |window, cx| {
-                                    Tooltip::with_meta(
-                                        path.to_string(),
-                                        None,
-                                        "Symbolic Link",
-                                        window,
-                                        cx,
-                                    )
-                                })
-                                .child(
-                                    Icon::new(IconName::ArrowUpRight)
-                                        .size(IconSize::Indicator)
-                                        .color(filename_text_color),
-                                )
-                                .into_any_element(),
-                        )
-                    })
+                    .when(
+                        coverage_percentage.is_some() || canonical_path.is_some(),
+                        |this| {
+                            this.end_slot::<AnyElement>(
+                                h_flex()
+                                    .pr_3()
+                                    .gap_1()
+                                    .when_some(coverage_percentage, |this, percentage| {
+                                        this.child(
+                                            Label::new(format!("{percentage}%"))
+                                                .size(LabelSize::XSmall)
+                                                .color(coverage_color(percentage)),
+                                        )
+                                    })
+                                    .when_some(canonical_path, |this, path| {
+                                        this.child(
+                                            div()
+                                                .id("symlink_icon")
+                                                .tooltip(move |window, cx| {
+                                                    Tooltip::with_meta(
+                                                        path.to_string(),
+                                                        None,
+                                                        "Symbolic Link",
+                                                        window,
+                                                        cx,
+                                                    )
+                                                })
+                                                .child(
+                                                    Icon::new(IconName::ArrowUpRight)
+                                                        .size(IconSize::Indicator)
+                                                        .color(filename_text_color),
+                                                ),
+                                        )
+                                    })
+                                    .into_any_element(),
+                            )
+                        },
+                    )
                     .child(if let Some(icon) = &icon {
                         if let Some((_, decoration_color)) =
                             entry_diagnostic_aware_icon_decoration_and_color(diagnostic_severity)
@@ -4994,6 +5039,22 @@ impl ProjectPanel {
             .get(&(worktree_id, entry.path.clone()))
             .cloned();
 
+        let coverage_percentage =
+            if ProjectPanelSettings::get_global(cx).show_coverage && entry.is_file() {
+                self.project
+                    .read(cx)
+                    .coverage_store()
+                    .read(cx)
+                    .coverage_for_path(&ProjectPath {
+                        worktree_id,
+                        path: entry.path.clone(),
+                    })
+                    .and_then(|coverage| coverage.percentage())
+                    .map(|percentage| percentage.round() as u8)
+            } else {
+                None
+            };
+
         let filename_text_color =
             entry_git_aware_label_color(git_status, entry.is_ignored, is_marked);
 
@@ -5018,6 +5079,7 @@ impl ProjectPanel {
             sticky,
             filename_text_color,
             diagnostic_severity,
+            coverage_percentage,
             git_status,
             is_private: entry.is_private,
             worktree_id,
@@ -5278,6 +5340,14 @@ impl StickyCandidate for StickyProjectPanelCandidate {
     }
 }
 
+fn coverage_color(percentage: u8) -> Color {
+    match percentage {
+        80.. => Color::Success,
+        50..80 => Color::Warning,
+        _ => Color::Error,
+    }
+}
+
 fn item_width_estimate(depth: usize, item_text_chars: usize, is_symlink: bool) -> usize {
     const ICON_SIZE_FACTOR: usize = 2;
     let mut item_width = depth * ICON_SIZE_FACTOR + item_text_chars;
@@ -6038,6 +6108,128 @@ fn cmp<T: AsRef<Entry>>(lhs: T, rhs: T) -> cmp::Ordering {
     )
 }
 
+/// Compares two entries according to the project panel's sort settings.
+///
+/// Like [`util::paths::compare_rel_paths`], entries are compared component by component,
+/// so that every entry stays grouped under its parent directory. `dir_mtime` is used to
+/// look up the modification time of ancestor directories in [`ProjectPanelSortMode::Modified`].
+fn cmp_with_sort_mode(
+    entry_a: &Entry,
+    entry_b: &Entry,
+    sort_mode: ProjectPanelSortMode,
+    natural_sort: bool,
+    dir_mtime: &impl Fn(&RelPath) -> Option<SystemTime>,
+) -> cmp::Ordering {
+    if sort_mode == ProjectPanelSortMode::DirectoriesFirst && natural_sort {
+        return util::paths::compare_rel_paths(
+            (&entry_a.path, entry_a.is_file()),
+            (&entry_b.path, entry_b.is_file()),
+        );
+    }
+
+    let mut components_a = entry_a.path.components();
+    let mut components_b = entry_b.path.components();
+    let mut depth = 0;
+    loop {
+        match (components_a.next(), components_b.next()) {
+            (Some(component_a), Some(component_b)) => {
+                depth += 1;
+                let a_is_last = components_a.rest().is_empty();
+                let b_is_last = components_b.rest().is_empty();
+                let a_is_file = entry_a.is_file() && a_is_last;
+                let b_is_file = entry_b.is_file() && b_is_last;
+                if component_a == component_b && a_is_file == b_is_file {
+                    continue;
+                }
+
+                let (a_stem, a_extension) = split_file_name(component_a, a_is_file);
+                let (b_stem, b_extension) = split_file_name(component_b, b_is_file);
+                let ordering = match sort_mode {
+                    ProjectPanelSortMode::DirectoriesFirst => a_is_file.cmp(&b_is_file),
+                    ProjectPanelSortMode::Mixed => cmp::Ordering::Equal,
+                    ProjectPanelSortMode::FilesFirst => b_is_file.cmp(&a_is_file),
+                    ProjectPanelSortMode::Modified => a_is_file.cmp(&b_is_file).then_with(|| {
+                        let mtime_a = component_mtime(entry_a, depth, a_is_last, dir_mtime);
+                        let mtime_b = component_mtime(entry_b, depth, b_is_last, dir_mtime);
+                        mtime_b.cmp(&mtime_a)
+                    }),
+                    ProjectPanelSortMode::FileType => a_is_file.cmp(&b_is_file).then_with(|| {
+                        compare_names(
+                            a_extension.unwrap_or_default(),
+                            b_extension.unwrap_or_default(),
+                            natural_sort,
+                        )
+                    }),
+                }
+                .then_with(|| compare_names(a_stem, b_stem, natural_sort))
+                .then_with(|| a_extension.cmp(&b_extension))
+                .then_with(|| a_is_file.cmp(&b_is_file));
+
+                if !ordering.is_eq() {
+                    return ordering;
+                }
+            }
+            (Some(_), None) => break cmp::Ordering::Greater,
+            (None, Some(_)) => break cmp::Ordering::Less,
+            (None, None) => break cmp::Ordering::Equal,
+        }
+    }
+}
+
+/// Splits a file name into its stem and extension, treating dotfiles such as `.bashrc`
+/// as having no extension. Directory names are never split.
+fn split_file_name(name: &str, is_file: bool) -> (&str, Option<&str>) {
+    if is_file
+        && let Some((stem, extension)) = name.rsplit_once('.')
+        && !stem.is_empty()
+    {
+        (stem, Some(extension))
+    } else {
+        (name, None)
+    }
+}
+
+fn compare_names(a: &str, b: &str, natural_sort: bool) -> cmp::Ordering {
+    if natural_sort {
+        util::paths::natural_sort(a, b)
+    } else {
+        a.chars()
+            .map(|c| c.to_ascii_lowercase())
+            .cmp(b.chars().map(|c| c.to_ascii_lowercase()))
+            .then_with(|| b.cmp(a))
+    }
+}
+
+/// Returns the modification time of the path component at `depth` (1-based) of the entry's path.
+fn component_mtime(
+    entry: &Entry,
+    depth: usize,
+    is_last: bool,
+    dir_mtime: &impl Fn(&RelPath) -> Option<SystemTime>,
+) -> Option<SystemTime> {
+    if is_last {
+        entry.mtime.map(|mtime| mtime.timestamp_for_user())
+    } else {
+        let component_count = entry.path.components().count();
+        entry
+            .path
+            .ancestors()
+            .nth(component_count - depth)
+            .and_then(dir_mtime)
+    }
+}
+
+fn worktree_dir_mtime(
+    worktree: &worktree::Snapshot,
+) -> impl Fn(&RelPath) -> Option<SystemTime> + '_ {
+    move |path| {
+        worktree
+            .entry_for_path(path)
+            .and_then(|entry| entry.mtime)
+            .map(|mtime| mtime.timestamp_for_user())
+    }
+}
+
 pub fn sort_worktree_entries(entries: &mut [impl AsRef<Entry>]) {
     entries.sort_by(|lhs, rhs| cmp(lhs, rhs));
 }
@@ -6046,5 +6238,43 @@ pub fn par_sort_worktree_entries(entries: &mut Vec<GitEntry>) {
     entries.par_sort_by(|lhs, rhs| cmp(lhs, rhs));
 }
 
+/// Sorts entries of a single worktree in the order configured for the project panel.
+pub fn sort_worktree_entries_with_mode(
+    entries: &mut [impl AsRef<Entry>],
+    sort_mode: ProjectPanelSortMode,
+    natural_sort: bool,
+    worktree: &worktree::Snapshot,
+) {
+    let dir_mtime = worktree_dir_mtime(worktree);
+    entries.sort_by(|lhs, rhs| {
+        cmp_with_sort_mode(
+            lhs.as_ref(),
+            rhs.as_ref(),
+            sort_mode,
+            natural_sort,
+            &dir_mtime,
+        )
+    });
+}
+
+/// Parallel version of [`sort_worktree_entries_with_mode`], used for the panel's visible entries.
+pub fn par_sort_worktree_entries_with_mode(
+    entries: &mut Vec<GitEntry>,
+    sort_mode: ProjectPanelSortMode,
+    natural_sort: bool,
+    worktree: &worktree::Snapshot,
+) {
+    let dir_mtime = worktree_dir_mtime(worktree);
+    entries.par_sort_by(|lhs, rhs| {
+        cmp_with_sort_mode(
+            lhs.as_ref(),
+            rhs.as_ref(),
+            sort_mode,
+            natural_sort,
+            &dir_mtime,
+        )
+    });
+}
+
 #[cfg(test)]
 mod project_panel_tests;
@@ -3,8 +3,8 @@ use gpui::Pixels;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
-    DockSide, ProjectPanelEntrySpacing, Settings, SettingsContent, ShowDiagnostics,
-    ShowIndentGuides,
+    DockSide, ProjectPanelEntrySpacing, ProjectPanelSortMode, Settings, SettingsContent,
+    ShowDiagnostics, ShowIndentGuides,
 };
 use ui::{
     px,
@@ -29,8 +29,11 @@ pub struct ProjectPanelSettings {
     pub starts_open: bool,
     pub scrollbar: ScrollbarSettings,
     pub show_diagnostics: ShowDiagnostics,
+    pub show_coverage: bool,
     pub hide_root: bool,
     pub drag_and_drop: bool,
+    pub sort_mode: ProjectPanelSortMode,
+    pub natural_sort: bool,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -78,8 +81,11 @@ impl Settings for ProjectPanelSettings {
                 show: project_panel.scrollbar.unwrap().show.map(Into::into),
             },
             show_diagnostics: project_panel.show_diagnostics.unwrap(),
+            show_coverage: project_panel.show_coverage.unwrap(),
             hide_root: project_panel.hide_root.unwrap(),
             drag_and_drop: project_panel.drag_and_drop.unwrap(),
+            sort_mode: project_panel.sort_mode.unwrap(),
+            natural_sort: project_panel.natural_sort.unwrap(),
         }
     }
 
@@ -96,6 +102,16 @@ impl Settings for ProjectPanelSettings {
         if let Some(compact_folders) = vscode.read_bool("explorer.compactFolders") {
             current.project_panel.get_or_insert_default().auto_fold_dirs = Some(compact_folders);
         }
+        if let Some(sort_mode) = vscode.read_enum("explorer.sortOrder", |s| match s {
+            "default" => Some(ProjectPanelSortMode::DirectoriesFirst),
+            "mixed" => Some(ProjectPanelSortMode::Mixed),
+            "filesFirst" => Some(ProjectPanelSortMode::FilesFirst),
+            "type" => Some(ProjectPanelSortMode::FileType),
+            "modified" => Some(ProjectPanelSortMode::Modified),
+            _ => None,
+        }) {
+            current.project_panel.get_or_insert_default().sort_mode = Some(sort_mode);
+        }
 
         if Some(false) == vscode.read_bool("git.decorations.enabled") {
             current.project_panel.get_or_insert_default().git_status = Some(false);
@@ -6492,6 +6492,130 @@ async fn test_hide_root(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_sort_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root1",
+        json!({
+            "b_dir": { "x.txt": "" },
+            "a_dir": { "y.txt": "" },
+            "a.rs": "",
+            "c.md": "",
+            "file10.txt": "",
+            "file2.txt": "",
+        }),
+    )
+    .await;
+
+    let cases = [
+        (
+            ProjectPanelSortMode::DirectoriesFirst,
+            true,
+            [
+                "v root1",
+                "    > a_dir",
+                "    > b_dir",
+                "      a.rs",
+                "      c.md",
+                "      file2.txt",
+                "      file10.txt",
+            ],
+        ),
+        (
+            ProjectPanelSortMode::DirectoriesFirst,
+            false,
+            [
+                "v root1",
+                "    > a_dir",
+                "    > b_dir",
+                "      a.rs",
+                "      c.md",
+                "      file10.txt",
+                "      file2.txt",
+            ],
+        ),
+        (
+            ProjectPanelSortMode::Mixed,
+            true,
+            [
+                "v root1",
+                "      a.rs",
+                "    > a_dir",
+                "    > b_dir",
+                "      c.md",
+                "      file2.txt",
+                "      file10.txt",
+            ],
+        ),
+        (
+            ProjectPanelSortMode::FilesFirst,
+            true,
+            [
+                "v root1",
+                "      a.rs",
+                "      c.md",
+                "      file2.txt",
+                "      file10.txt",
+                "    > a_dir",
+                "    > b_dir",
+            ],
+        ),
+        (
+            ProjectPanelSortMode::FileType,
+            true,
+            [
+                "v root1",
+                "    > a_dir",
+                "    > b_dir",
+                "      c.md",
+                "      a.rs",
+                "      file2.txt",
+                "      file10.txt",
+            ],
+        ),
+    ];
+
+    for (sort_mode, natural_sort, expected) in cases {
+        let project = Project::test(fs.clone(), ["/root1".as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+        cx.update(|_, cx| {
+            let settings = *ProjectPanelSettings::get_global(cx);
+            ProjectPanelSettings::override_global(
+                ProjectPanelSettings {
+                    sort_mode,
+                    natural_sort,
+                    ..settings
+                },
+                cx,
+            );
+        });
+
+        let panel = workspace.update(cx, ProjectPanel::new).unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &expected,
+            "Unexpected order for sort mode {sort_mode:?} with natural_sort={natural_sort}"
+        );
+
+        // Expanded directories keep their children grouped underneath them.
+        toggle_expand_dir(&panel, "root1/b_dir", cx);
+        let entries = visible_entries_as_strings(&panel, 0..10, cx);
+        let dir_ix = entries
+            .iter()
+            .position(|entry| entry.starts_with("    v b_dir"))
+            .unwrap();
+        assert_eq!(entries[dir_ix + 1], "          x.txt");
+    }
+}
+
 #[gpui::test]
 async fn test_compare_selected_files(cx: &mut gpui::TestAppContext) {
     init_test_with_editor(cx);
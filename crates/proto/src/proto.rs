@@ -93,6 +93,8 @@ messages!(
     (GetDefinitionResponse, Background),
     (GetDocumentHighlights, Background),
     (GetDocumentHighlightsResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
     (GetDocumentSymbols, Background),
     (GetDocumentSymbolsResponse, Background),
     (GetHover, Background),
@@ -216,6 +218,8 @@ messages!(
     (ResolveCompletionDocumentationResponse, Background),
     (ResolveInlayHint, Background),
     (ResolveInlayHintResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
     (GetDocumentColor, Background),
     (GetDocumentColorResponse, Background),
     (GetColorPresentation, Background),
@@ -358,6 +362,7 @@ request_messages!(
     (GetDeclaration, GetDeclarationResponse),
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
     (GetHover, GetHoverResponse),
     (GetNotifications, GetNotificationsResponse),
@@ -412,6 +417,7 @@ request_messages!(
         ResolveCompletionDocumentationResponse
     ),
     (ResolveInlayHint, ResolveInlayHintResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetColorPresentation, GetColorPresentationResponse),
     (RespondToChannelInvite, Ack),
@@ -545,6 +551,7 @@ entity_messages!(
     GetDeclaration,
     GetImplementation,
     GetDocumentHighlights,
+    GetDocumentLinks,
     GetDocumentSymbols,
     GetHover,
     GetProjectSymbols,
@@ -577,6 +584,7 @@ entity_messages!(
     RenameProjectEntry,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
+    ResolveDocumentLink,
     SaveBuffer,
     Stage,
     StartLanguageServer,
@@ -193,6 +193,9 @@ impl HeadlessProject {
             lsp_store.shared(REMOTE_SERVER_PROJECT_ID, session.clone(), cx);
             lsp_store
         });
+        dap_store.update(cx, |dap_store, _| {
+            dap_store.set_lsp_store(lsp_store.downgrade())
+        });
 
         let agent_server_store = cx.new(|cx| {
             let mut agent_server_store =
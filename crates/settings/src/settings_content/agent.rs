@@ -6,7 +6,7 @@ use serde_with::skip_serializing_none;
 use settings_macros::MergeFrom;
 use std::{borrow::Cow, path::PathBuf, sync::Arc};
 
-use crate::DockPosition;
+use crate::{DockPosition, ExtendingVec};
 
 #[skip_serializing_none]
 #[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, Default)]
@@ -178,6 +178,9 @@ pub struct AgentProfileContent {
     pub enable_all_context_servers: Option<bool>,
     #[serde(default)]
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
+    /// Permission rules for tool calls made while this profile is active, keyed by tool name.
+    #[serde(default)]
+    pub tool_permissions: IndexMap<Arc<str>, ToolPermissionRulesContent>,
 }
 
 #[skip_serializing_none]
@@ -186,6 +189,50 @@ pub struct ContextServerPresetContent {
     pub tools: IndexMap<Arc<str>, bool>,
 }
 
+/// Whether the agent may run a tool call.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermission {
+    /// Run the tool call without asking for confirmation.
+    Allow,
+    /// Ask for confirmation before running the tool call.
+    Ask,
+    /// Refuse to run the tool call.
+    Deny,
+}
+
+/// Rules deciding whether calls to a single tool need confirmation.
+///
+/// Patterns are globs matched against the tool's input: each command run by
+/// the `terminal` tool (e.g. `"cargo test*"`), each path touched by the file
+/// tools (e.g. `"**/.env"`), or the URL requested by `fetch`. Deny patterns
+/// take precedence over ask patterns, which take precedence over allow patterns.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct ToolPermissionRulesContent {
+    /// What to do with calls that match none of the patterns.
+    ///
+    /// Default: the tool's built-in behavior
+    pub default: Option<ToolPermission>,
+    /// Patterns for calls that run without asking for confirmation.
+    pub allow: Option<ExtendingVec<String>>,
+    /// Patterns for calls that always ask for confirmation.
+    pub ask: Option<ExtendingVec<String>>,
+    /// Patterns for calls that are always refused.
+    pub deny: Option<ExtendingVec<String>>,
+}
+
+impl ToolPermissionRulesContent {
+    /// Drops the allow patterns and an `allow` default, leaving the rules that
+    /// only make tool calls ask for confirmation or be refused.
+    pub fn remove_allow_rules(&mut self) {
+        self.allow = None;
+        if self.default == Some(ToolPermission::Allow) {
+            self.default = None;
+        }
+    }
+}
+
 #[derive(Copy, Clone, Default, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
 #[serde(rename_all = "snake_case")]
 pub enum DefaultAgentView {
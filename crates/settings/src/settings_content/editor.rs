@@ -185,6 +185,9 @@ pub struct EditorSettingsContent {
     /// Drag and drop related settings
     pub drag_and_drop_selection: Option<DragAndDropSelectionContent>,
 
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
+
     /// How to render LSP `textDocument/documentColor` colors in the editor.
     ///
     /// Default: [`DocumentColorsRenderMode::Inlay`]
@@ -678,6 +681,22 @@ pub struct DragAndDropSelectionContent {
     pub delay: Option<u64>,
 }
 
+/// Pin the scopes enclosing the top of the viewport while scrolling.
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to show the enclosing scopes (impls, functions, classes, ...)
+    /// at the top of the editor while scrolling.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+
+    /// The maximum number of nested scopes to pin at once.
+    ///
+    /// Default: 5
+    pub max_depth: Option<u32>,
+}
+
 /// When to show the minimap in the editor.
 ///
 /// Default: never
@@ -82,6 +82,9 @@ pub enum EditPredictionProvider {
     Copilot,
     Supermaven,
     Zed,
+    /// Fill-in-the-middle completions from a self-hosted model, such as one served by
+    /// Ollama or llama.cpp.
+    Local,
 }
 
 impl EditPredictionProvider {
@@ -90,7 +93,8 @@ impl EditPredictionProvider {
             EditPredictionProvider::Zed => true,
             EditPredictionProvider::None
             | EditPredictionProvider::Copilot
-            | EditPredictionProvider::Supermaven => false,
+            | EditPredictionProvider::Supermaven
+            | EditPredictionProvider::Local => false,
         }
     }
 }
@@ -108,6 +112,8 @@ pub struct EditPredictionSettingsContent {
     pub mode: Option<EditPredictionsMode>,
     /// Settings specific to GitHub Copilot.
     pub copilot: Option<CopilotSettingsContent>,
+    /// Settings for the `local` edit prediction provider.
+    pub local: Option<LocalEditPredictionSettingsContent>,
     /// Whether edit predictions are enabled in the assistant prompt editor.
     /// This has no effect if globally disabled.
     pub enabled_in_text_threads: Option<bool>,
@@ -130,6 +136,59 @@ pub struct CopilotSettingsContent {
     pub enterprise_uri: Option<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LocalEditPredictionSettingsContent {
+    /// The API used to request completions.
+    ///
+    /// Default: ollama
+    pub api: Option<LocalEditPredictionApi>,
+    /// The URL of the completion server. For OpenAI-compatible servers this
+    /// is the base URL that `/completions` is appended to.
+    ///
+    /// Default: "http://localhost:11434"
+    pub api_url: Option<String>,
+    /// The model to request completions from.
+    ///
+    /// Default: "qwen2.5-coder:1.5b-base"
+    pub model: Option<String>,
+    /// A fill-in-the-middle prompt template with `{prefix}` and `{suffix}`
+    /// placeholders, e.g. `<|fim_prefix|>{prefix}<|fim_suffix|>{suffix}<|fim_middle|>`.
+    /// When unset, the prefix and suffix are sent separately and the server
+    /// applies the model's own template.
+    ///
+    /// Default: null
+    pub fim_template: Option<String>,
+    /// The maximum number of tokens to generate for a prediction.
+    ///
+    /// Default: 128
+    pub max_output_tokens: Option<u32>,
+    /// The maximum number of bytes of the buffer around the cursor to include
+    /// in the prompt.
+    ///
+    /// Default: 4096
+    pub max_context_bytes: Option<usize>,
+    /// How long to wait after typing stops before requesting a prediction, in
+    /// milliseconds.
+    ///
+    /// Default: 150
+    pub debounce_ms: Option<u64>,
+}
+
+/// The API a `local` edit prediction server speaks.
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalEditPredictionApi {
+    /// Ollama's `/api/generate` endpoint.
+    #[default]
+    Ollama,
+    /// The `/completions` endpoint of an OpenAI-compatible server, such as
+    /// llama.cpp's `llama-server` or vLLM.
+    OpenAiCompatible,
+}
+
 /// The mode in which edit predictions should be displayed.
 #[derive(
     Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom,
@@ -211,6 +270,16 @@ pub struct LanguageSettingsContent {
     pub wrap_guides: Option<Vec<usize>>,
     /// Indent guide related settings.
     pub indent_guides: Option<IndentGuideSettingsContent>,
+    /// Whether to color brackets by their nesting depth, using the
+    /// `editor.rainbow_bracket.*` theme colors.
+    ///
+    /// Default: false
+    pub colorize_brackets: Option<bool>,
+    /// Whether to draw a vertical guide between the brackets of the scope
+    /// enclosing the cursor.
+    ///
+    /// Default: false
+    pub show_bracket_guides: Option<bool>,
     /// Whether or not to perform a buffer format before saving.
     ///
     /// Default: on
@@ -1,13 +1,15 @@
 use std::{path::PathBuf, sync::Arc};
 
-use collections::{BTreeMap, HashMap};
+use collections::{BTreeMap, HashMap, IndexMap};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use settings_macros::MergeFrom;
 use util::serde::default_true;
 
-use crate::{AllLanguageSettingsContent, ExtendingVec, SlashCommandSettings};
+use crate::{
+    AllLanguageSettingsContent, ExtendingVec, SlashCommandSettings, ToolPermissionRulesContent,
+};
 
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
@@ -45,6 +47,13 @@ pub struct ProjectSettingsContent {
 
     /// The list of custom Git hosting providers.
     pub git_hosting_providers: Option<ExtendingVec<GitHostingProviderConfig>>,
+
+    /// Rules deciding which agent tool calls run without asking for confirmation,
+    /// keyed by tool name. Patterns from user and project settings accumulate, but
+    /// project settings can't allow tool calls.
+    ///
+    /// Default: {}
+    pub agent_tool_permissions: Option<IndexMap<Arc<str>, ToolPermissionRulesContent>>,
 }
 
 #[skip_serializing_none]
@@ -266,6 +275,10 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: Option<GitHunkStyleSetting>,
+    /// How diff views render changes when they are opened.
+    ///
+    /// Default: unified
+    pub diff_view_style: Option<DiffViewStyle>,
 }
 
 #[derive(
@@ -361,6 +374,64 @@ pub enum GitHunkStyleSetting {
     UnstagedHollow,
 }
 
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffViewStyle {
+    /// Show deleted and added lines interleaved in a single editor.
+    #[default]
+    Unified,
+    /// Show the old text on the left and the new text on the right, side by side.
+    Split,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct CoverageSettingsContent {
+    /// Whether to highlight covered, uncovered and partially covered lines in the gutter.
+    ///
+    /// Default: true
+    pub gutter: Option<bool>,
+    /// Paths of LCOV or Cobertura XML coverage reports, relative to the root of each worktree.
+    /// Reports are reloaded whenever they change on disk. The first path is also exposed to
+    /// tasks as `ZED_COVERAGE_REPORT`.
+    ///
+    /// Default: ["lcov.info", "coverage/lcov.info", "coverage.xml", "coverage/cobertura-coverage.xml"]
+    pub reports: Option<Vec<String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to check the spelling of comments and strings in code, and of all the text in
+    /// Markdown and plain text files.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The Hunspell dictionaries to check words against, by the name of their `.aff` and `.dic`
+    /// files. A word is accepted if any of the dictionaries accepts it. Compound words are only
+    /// accepted if the dictionary lists them, since compounding rules aren't supported.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Additional directories to look for dictionaries in, before the `dictionaries` directory
+    /// of the Zed config directory and the system's Hunspell directories.
+    ///
+    /// Default: []
+    pub dictionary_directories: Option<Vec<String>>,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct DiagnosticsSettingsContent {
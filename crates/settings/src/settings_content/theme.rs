@@ -658,6 +658,24 @@ pub struct ThemeColorsContent {
     #[serde(rename = "editor.indent_guide_active")]
     pub editor_indent_guide_active: Option<String>,
 
+    #[serde(rename = "editor.rainbow_bracket.1")]
+    pub editor_rainbow_bracket_1: Option<String>,
+
+    #[serde(rename = "editor.rainbow_bracket.2")]
+    pub editor_rainbow_bracket_2: Option<String>,
+
+    #[serde(rename = "editor.rainbow_bracket.3")]
+    pub editor_rainbow_bracket_3: Option<String>,
+
+    #[serde(rename = "editor.rainbow_bracket.4")]
+    pub editor_rainbow_bracket_4: Option<String>,
+
+    #[serde(rename = "editor.rainbow_bracket.5")]
+    pub editor_rainbow_bracket_5: Option<String>,
+
+    #[serde(rename = "editor.rainbow_bracket.6")]
+    pub editor_rainbow_bracket_6: Option<String>,
+
     /// Read-access of a symbol, like reading a variable.
     ///
     /// A document highlight is a range inside a text document which deserves
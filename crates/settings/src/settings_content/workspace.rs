@@ -106,6 +106,11 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: true
     pub zoomed_padding: Option<bool>,
+    /// Named arrangements of panes and docks that can be loaded with
+    /// `workspace: load layout`, in addition to the layouts saved per workspace.
+    ///
+    /// Default: {}
+    pub layouts: Option<HashMap<String, WorkspaceLayoutContent>>,
 }
 
 #[skip_serializing_none]
@@ -433,6 +438,89 @@ pub struct CenteredLayoutSettings {
     pub right_padding: Option<f32>,
 }
 
+/// A named arrangement of the panes in the center of a workspace and of its docks.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct WorkspaceLayoutContent {
+    /// The panes in the center of the workspace.
+    pub center: Option<PaneLayoutContent>,
+    /// The state of the left dock.
+    pub left_dock: Option<DockLayoutContent>,
+    /// The state of the right dock.
+    pub right_dock: Option<DockLayoutContent>,
+    /// The state of the bottom dock.
+    pub bottom_dock: Option<DockLayoutContent>,
+}
+
+/// A node in the tree of panes in the center of a workspace.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaneLayoutContent {
+    /// Panes laid out next to each other along an axis.
+    Split {
+        /// The axis along which the members are laid out.
+        axis: PaneLayoutAxis,
+        /// The panes or splits that make up this split.
+        members: Vec<PaneLayoutContent>,
+        /// The relative sizes of the members, defaulting to equal sizes.
+        #[serde(default)]
+        flexes: Option<Vec<f32>>,
+    },
+    /// A single pane.
+    Pane {
+        /// The items open in the pane.
+        #[serde(default)]
+        items: Vec<PaneLayoutItem>,
+        /// The index of the active item.
+        #[serde(default)]
+        active_item: Option<usize>,
+        /// Whether the pane is zoomed.
+        #[serde(default)]
+        zoomed: bool,
+    },
+}
+
+/// An item open in a pane of a workspace layout.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+#[serde(untagged)]
+pub enum PaneLayoutItem {
+    /// A file, either as an absolute path or as a path relative to a worktree
+    /// of the project, optionally prefixed by the worktree's root name.
+    Path(String),
+    /// An item that isn't a file, such as a terminal. It is matched against the
+    /// open items of the same kind, or restored from the state the workspace
+    /// saved for it while that state is still around.
+    Serialized {
+        /// The kind the item is serialized as, such as "Terminal".
+        kind: String,
+        /// The id the workspace saved the item's state under.
+        item_id: u64,
+    },
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum PaneLayoutAxis {
+    /// Members are laid out from left to right.
+    Horizontal,
+    /// Members are laid out from top to bottom.
+    Vertical,
+}
+
+/// The state of a dock in a workspace layout.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct DockLayoutContent {
+    /// Whether the dock is open.
+    pub visible: Option<bool>,
+    /// The panel to activate in the dock, such as "Project Panel" or "TerminalPanel".
+    pub active_panel: Option<String>,
+    /// The size of the active panel, in pixels.
+    pub size: Option<f32>,
+    /// Whether the active panel is zoomed.
+    pub zoomed: Option<bool>,
+}
+
 #[derive(
     Copy,
     Clone,
@@ -524,6 +612,10 @@ pub struct ProjectPanelSettingsContent {
     ///
     /// Default: all
     pub show_diagnostics: Option<ShowDiagnostics>,
+    /// Whether to show the line coverage percentage of files that appear in coverage reports.
+    ///
+    /// Default: true
+    pub show_coverage: Option<bool>,
     /// Settings related to indent guides in the project panel.
     pub indent_guides: Option<ProjectPanelIndentGuidesSettings>,
     /// Whether to hide the root entry when only one folder is open in the window.
@@ -538,6 +630,15 @@ pub struct ProjectPanelSettingsContent {
     ///
     /// Default: true
     pub drag_and_drop: Option<bool>,
+    /// How to order entries in the project panel.
+    ///
+    /// Default: directories_first
+    pub sort_mode: Option<ProjectPanelSortMode>,
+    /// Whether to compare numbers in entry names by their value ("file2" before "file10"),
+    /// rather than character by character.
+    ///
+    /// Default: true
+    pub natural_sort: Option<bool>,
 }
 
 #[derive(
@@ -563,6 +664,36 @@ pub enum ProjectPanelEntrySpacing {
     Standard,
 }
 
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectPanelSortMode {
+    /// Directories are listed before files.
+    #[default]
+    DirectoriesFirst,
+    /// Directories and files are interleaved.
+    Mixed,
+    /// Files are listed before directories.
+    FilesFirst,
+    /// Directories are listed before files, and both are ordered by
+    /// modification time, most recent first.
+    Modified,
+    /// Directories are listed before files, and files are grouped by extension.
+    FileType,
+}
+
 #[skip_serializing_none]
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
 pub struct ProjectPanelIndentGuidesSettings {
@@ -733,13 +733,22 @@ impl SettingsStore {
                     .remove(&(root_id, directory_path.clone()));
             }
             (LocalSettingsKind::Settings, Some(settings_contents)) => {
-                let new_settings = parse_json_with_comments::<ProjectSettingsContent>(
+                let mut new_settings = parse_json_with_comments::<ProjectSettingsContent>(
                     settings_contents,
                 )
                 .map_err(|e| InvalidSettingsError::LocalSettings {
                     path: directory_path.join(local_settings_file_relative_path()),
                     message: e.to_string(),
                 })?;
+                // Project settings come with the repository, so they may make the agent ask
+                // before running its tools, but not run them without asking.
+                for rules in new_settings
+                    .agent_tool_permissions
+                    .iter_mut()
+                    .flat_map(|permissions| permissions.values_mut())
+                {
+                    rules.remove_allow_rules();
+                }
                 match self.local_settings.entry((root_id, directory_path.clone())) {
                     btree_map::Entry::Vacant(v) => {
                         v.insert(SettingsContent {
@@ -1302,6 +1311,66 @@ mod tests {
         );
     }
 
+    #[derive(Debug, PartialEq)]
+    struct TerminalPermissionsSetting(Option<crate::ToolPermissionRulesContent>);
+
+    impl Settings for TerminalPermissionsSetting {
+        fn from_settings(content: &SettingsContent) -> Self {
+            TerminalPermissionsSetting(
+                content
+                    .project
+                    .agent_tool_permissions
+                    .as_ref()
+                    .and_then(|permissions| permissions.get("terminal").cloned()),
+            )
+        }
+    }
+
+    #[gpui::test]
+    fn test_local_settings_cannot_allow_tool_calls(cx: &mut App) {
+        let mut store = SettingsStore::new(cx, &test_settings());
+        store.register_setting::<TerminalPermissionsSetting>();
+
+        store
+            .set_user_settings(
+                r#"{ "agent_tool_permissions": { "terminal": { "allow": ["cargo test*"] } } }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_local_settings(
+                WorktreeId::from_usize(1),
+                rel_path("root").into(),
+                LocalSettingsKind::Settings,
+                Some(
+                    r#"{
+                        "agent_tool_permissions": {
+                            "terminal": {
+                                "default": "allow",
+                                "allow": ["rm *"],
+                                "deny": ["git push*"]
+                            }
+                        }
+                    }"#,
+                ),
+                cx,
+            )
+            .unwrap();
+
+        assert_eq!(
+            store.get::<TerminalPermissionsSetting>(Some(SettingsLocation {
+                worktree_id: WorktreeId::from_usize(1),
+                path: rel_path("root/src/main.rs"),
+            })),
+            &TerminalPermissionsSetting(Some(crate::ToolPermissionRulesContent {
+                default: None,
+                allow: Some(crate::ExtendingVec(vec!["cargo test*".into()])),
+                ask: None,
+                deny: Some(crate::ExtendingVec(vec!["git push*".into()])),
+            }))
+        );
+    }
+
     #[gpui::test]
     fn test_setting_store_assign_json_before_register(cx: &mut App) {
         let mut store = SettingsStore::new(cx, &test_settings());
@@ -779,6 +779,48 @@ pub(crate) fn user_settings_data() -> Vec<SettingsPage> {
                     }),
                     metadata: None,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Sticky Scroll",
+                    description: "Whether to pin the enclosing scopes at the top of the editor while scrolling",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(sticky_scroll) = &settings_content.editor.sticky_scroll {
+                                &sticky_scroll.enabled
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .editor
+                                .sticky_scroll
+                                .get_or_insert_default()
+                                .enabled
+                        },
+                    }),
+                    metadata: None,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Sticky Scroll Max Depth",
+                    description: "The maximum number of nested scopes to pin at once",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(sticky_scroll) = &settings_content.editor.sticky_scroll {
+                                &sticky_scroll.max_depth
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .editor
+                                .sticky_scroll
+                                .get_or_insert_default()
+                                .max_depth
+                        },
+                    }),
+                    metadata: None,
+                }),
                 SettingsPageItem::SectionHeader("Auto Actions"),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Use Autoclose",
@@ -1959,6 +2001,46 @@ pub(crate) fn user_settings_data() -> Vec<SettingsPage> {
                     }),
                     metadata: None,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Sort Mode",
+                    description: "How to order entries in the project panel",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(project_panel) = &settings_content.project_panel {
+                                &project_panel.sort_mode
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .project_panel
+                                .get_or_insert_default()
+                                .sort_mode
+                        },
+                    }),
+                    metadata: None,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Natural Sort",
+                    description: "Whether to compare numbers in entry names by their value rather than character by character",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(project_panel) = &settings_content.project_panel {
+                                &project_panel.natural_sort
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .project_panel
+                                .get_or_insert_default()
+                                .natural_sort
+                        },
+                    }),
+                    metadata: None,
+                }),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "File Icons",
                     description: "Whether to show folder icons or chevrons for directories in the project panel",
@@ -2143,6 +2225,26 @@ pub(crate) fn user_settings_data() -> Vec<SettingsPage> {
                     }),
                     metadata: None,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Show Coverage",
+                    description: "Whether to show the line coverage percentage of files that appear in coverage reports",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(project_panel) = &settings_content.project_panel {
+                                &project_panel.show_coverage
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .project_panel
+                                .get_or_insert_default()
+                                .show_coverage
+                        },
+                    }),
+                    metadata: None,
+                }),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Sticky Scroll",
                     description: "Whether to stick parent directories at top of the project panel",
@@ -2938,6 +3040,23 @@ pub(crate) fn user_settings_data() -> Vec<SettingsPage> {
                     }),
                     metadata: None,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Diff View Style",
+                    description: "Whether diff views open in unified or side-by-side mode",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(git) = &settings_content.git {
+                                &git.diff_view_style
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content.git.get_or_insert_default().diff_view_style
+                        },
+                    }),
+                    metadata: None,
+                }),
             ],
         },
         SettingsPage {
@@ -3943,6 +4062,41 @@ fn language_settings_data() -> Vec<SettingsPageItem> {
             }),
             metadata: None,
         }),
+        SettingsPageItem::SectionHeader("Brackets"),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Colorize Brackets",
+            description: "Whether to color brackets by their nesting depth",
+            field: Box::new(SettingField {
+                pick: |settings_content| {
+                    language_settings_field(settings_content, |language| {
+                        &language.colorize_brackets
+                    })
+                },
+                pick_mut: |settings_content| {
+                    language_settings_field_mut(settings_content, |language| {
+                        &mut language.colorize_brackets
+                    })
+                },
+            }),
+            metadata: None,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Show Bracket Guides",
+            description: "Whether to draw a guide between the brackets of the scope enclosing the cursor",
+            field: Box::new(SettingField {
+                pick: |settings_content| {
+                    language_settings_field(settings_content, |language| {
+                        &language.show_bracket_guides
+                    })
+                },
+                pick_mut: |settings_content| {
+                    language_settings_field_mut(settings_content, |language| {
+                        &mut language.show_bracket_guides
+                    })
+                },
+            }),
+            metadata: None,
+        }),
         SettingsPageItem::SectionHeader("Formatting"),
         SettingsPageItem::SettingItem(SettingItem {
             title: "Format On Save",
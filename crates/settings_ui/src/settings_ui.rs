@@ -287,6 +287,9 @@ fn init_renderers(cx: &mut App) {
         .add_renderer::<settings::GitHunkStyleSetting>(|settings_field, file, _, window, cx| {
             render_dropdown(*settings_field, file, window, cx)
         })
+        .add_renderer::<settings::DiffViewStyle>(|settings_field, file, _, window, cx| {
+            render_dropdown(*settings_field, file, window, cx)
+        })
         .add_renderer::<settings::DiagnosticSeverityContent>(
             |settings_field, file, _, window, cx| {
                 render_dropdown(*settings_field, file, window, cx)
@@ -317,6 +320,9 @@ fn init_renderers(cx: &mut App) {
                 render_dropdown(*settings_field, file, window, cx)
             },
         )
+        .add_renderer::<settings::ProjectPanelSortMode>(|settings_field, file, _, window, cx| {
+            render_dropdown(*settings_field, file, window, cx)
+        })
         .add_renderer::<settings::RewrapBehavior>(|settings_field, file, _, window, cx| {
             render_dropdown(*settings_field, file, window, cx)
         })
@@ -0,0 +1,604 @@
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use encoding_rs::{Encoding, UTF_8};
+
+/// A flag attached to dictionary words and affix rules. Flags of every format supported by the
+/// `FLAG` option are normalized to a number.
+type Flag = u32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FlagFormat {
+    /// One character per flag.
+    #[default]
+    Short,
+    /// Two characters per flag.
+    Long,
+    /// Comma-separated decimal numbers.
+    Numeric,
+}
+
+#[derive(Debug)]
+enum CharMatcher {
+    Any,
+    Char(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl CharMatcher {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharMatcher::Any => true,
+            CharMatcher::Char(expected) => *expected == c,
+            CharMatcher::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+/// The condition an affix rule places on the stem it applies to, a tiny subset of regular
+/// expressions made of literal characters, `.` and bracketed character sets.
+#[derive(Debug, Default)]
+struct Condition(Vec<CharMatcher>);
+
+impl Condition {
+    fn parse(source: &str) -> Self {
+        let mut matchers = Vec::new();
+        let mut chars = source.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => matchers.push(CharMatcher::Any),
+                '[' => {
+                    let mut set = Vec::new();
+                    let mut negated = false;
+                    for c in chars.by_ref() {
+                        match c {
+                            ']' => break,
+                            '^' if set.is_empty() && !negated => negated = true,
+                            c => set.push(c),
+                        }
+                    }
+                    matchers.push(CharMatcher::Set {
+                        chars: set,
+                        negated,
+                    });
+                }
+                c => matchers.push(CharMatcher::Char(c)),
+            }
+        }
+        Self(matchers)
+    }
+
+    fn matches_start(&self, word: &str) -> bool {
+        let mut chars = word.chars();
+        self.0
+            .iter()
+            .all(|matcher| chars.next().is_some_and(|c| matcher.matches(c)))
+    }
+
+    fn matches_end(&self, word: &str) -> bool {
+        let mut chars = word.chars().rev();
+        self.0
+            .iter()
+            .rev()
+            .all(|matcher| chars.next().is_some_and(|c| matcher.matches(c)))
+    }
+}
+
+#[derive(Debug)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Condition,
+}
+
+/// A Hunspell dictionary, made of an affix file (`.aff`) and a word list (`.dic`).
+///
+/// Words are checked against their stems and the prefix and suffix rules of the affix file.
+/// Compounding, morphological analysis and input/output conversion tables aren't supported.
+#[derive(Default)]
+pub struct Dictionary {
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+    forbidden_flag: Option<Flag>,
+    need_affix_flag: Option<Flag>,
+    no_suggest_flag: Option<Flag>,
+    keep_case_flag: Option<Flag>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Title,
+    Upper,
+    Mixed,
+}
+
+fn casing(word: &str) -> Casing {
+    let mut letters = word.chars().filter(|c| c.is_alphabetic());
+    let Some(first) = letters.next() else {
+        return Casing::Lower;
+    };
+    let (mut upper, mut lower) = (0, 0);
+    for c in letters {
+        if c.is_uppercase() {
+            upper += 1;
+        } else if c.is_lowercase() {
+            lower += 1;
+        }
+    }
+    match (first.is_uppercase(), upper, lower) {
+        (false, 0, _) => Casing::Lower,
+        (true, 0, _) => Casing::Title,
+        (true, _, 0) => Casing::Upper,
+        _ => Casing::Mixed,
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Decodes the contents of a dictionary file in the encoding named by the `SET` option,
+/// falling back to UTF-8 for encodings that aren't known.
+fn decode(bytes: &[u8], encoding: &str) -> String {
+    // Hunspell names the Windows code pages `microsoft-cp125x`.
+    let label = encoding.strip_prefix("microsoft-").unwrap_or(encoding);
+    Encoding::for_label(label.as_bytes())
+        .unwrap_or(UTF_8)
+        .decode(bytes)
+        .0
+        .into_owned()
+}
+
+impl Dictionary {
+    /// Parses a dictionary from the contents of its `.aff` and `.dic` files.
+    pub fn new(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        let encoding = String::from_utf8_lossy(aff)
+            .lines()
+            .find_map(|line| {
+                let mut parts = line.split_whitespace();
+                (parts.next() == Some("SET")).then(|| parts.next().map(str::to_string))?
+            })
+            .unwrap_or_else(|| "UTF-8".to_string());
+
+        let mut dictionary = Self::default();
+        let mut flag_format = FlagFormat::default();
+        let mut flag_aliases = Vec::new();
+        // The number of rules left to read for each affix class, by its kind and flag.
+        let mut pending_affix_rules = HashMap::<(bool, Flag), (bool, usize)>::default();
+
+        for (ix, line) in decode(aff, &encoding).lines().enumerate() {
+            let mut parts = line.split_whitespace();
+            let Some(keyword) = parts.next() else {
+                continue;
+            };
+            let parts = parts.collect::<Vec<_>>();
+            let argument = parts.first().copied().unwrap_or_default();
+            match keyword {
+                "FLAG" => {
+                    flag_format = match argument {
+                        "long" => FlagFormat::Long,
+                        "num" => FlagFormat::Numeric,
+                        _ => FlagFormat::Short,
+                    }
+                }
+                "AF" if parts.len() == 1 && argument.parse::<usize>().is_ok() => {}
+                "AF" => flag_aliases.push(parse_flags(argument, flag_format)),
+                "TRY" => dictionary.try_chars = argument.chars().collect(),
+                "REP" if parts.len() >= 2 => dictionary
+                    .replacements
+                    .push((parts[0].replace('_', " "), parts[1].replace('_', " "))),
+                "FORBIDDENWORD" => {
+                    dictionary.forbidden_flag = parse_flags(argument, flag_format).first().copied()
+                }
+                "NEEDAFFIX" | "PSEUDOROOT" => {
+                    dictionary.need_affix_flag = parse_flags(argument, flag_format).first().copied()
+                }
+                "NOSUGGEST" => {
+                    dictionary.no_suggest_flag = parse_flags(argument, flag_format).first().copied()
+                }
+                "KEEPCASE" => {
+                    dictionary.keep_case_flag = parse_flags(argument, flag_format).first().copied()
+                }
+                "PFX" | "SFX" => {
+                    let is_prefix = keyword == "PFX";
+                    let flag = parse_flags(argument, flag_format)
+                        .first()
+                        .copied()
+                        .with_context(|| format!("missing affix flag on line {}", ix + 1))?;
+                    match pending_affix_rules.get_mut(&(is_prefix, flag)) {
+                        Some((cross_product, remaining)) if *remaining > 0 => {
+                            *remaining -= 1;
+                            let strip = parts.get(1).copied().unwrap_or("0");
+                            let add = parts.get(2).copied().unwrap_or("0");
+                            // Continuation flags after the added text aren't supported.
+                            let add = add.split('/').next().unwrap_or_default();
+                            let affix = Affix {
+                                flag,
+                                cross_product: *cross_product,
+                                strip: if strip == "0" { "" } else { strip }.to_string(),
+                                add: if add == "0" { "" } else { add }.to_string(),
+                                condition: Condition::parse(parts.get(3).copied().unwrap_or(".")),
+                            };
+                            if is_prefix {
+                                dictionary.prefixes.push(affix);
+                            } else {
+                                dictionary.suffixes.push(affix);
+                            }
+                        }
+                        _ => {
+                            let cross_product = parts.get(1) == Some(&"Y");
+                            let count = parts
+                                .get(2)
+                                .and_then(|count| count.parse().ok())
+                                .with_context(|| {
+                                    format!("invalid affix header on line {}", ix + 1)
+                                })?;
+                            pending_affix_rules.insert((is_prefix, flag), (cross_product, count));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (ix, line) in decode(dic, &encoding).lines().enumerate() {
+            let line = line.trim();
+            // The first line is the approximate number of words.
+            if ix == 0 && line.parse::<usize>().is_ok() || line.is_empty() {
+                continue;
+            }
+            let entry = line.split_whitespace().next().unwrap_or_default();
+            let (word, flags) = split_dic_entry(entry);
+            let flags = match flags {
+                Some(flags) if !flag_aliases.is_empty() => flags
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|alias| flag_aliases.get(alias.wrapping_sub(1)))
+                    .cloned()
+                    .unwrap_or_default(),
+                Some(flags) => parse_flags(flags, flag_format),
+                None => Vec::new(),
+            };
+            dictionary.words.entry(word).or_default().push(flags);
+        }
+
+        Ok(dictionary)
+    }
+
+    /// The number of stems in the dictionary.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns whether the word is spelled correctly. Lowercase words are accepted in title case
+    /// or uppercase, as they appear at the start of sentences or in headings.
+    pub fn check(&self, word: &str) -> bool {
+        if self.is_forbidden(word) {
+            return false;
+        }
+        if self.check_form(word, true) {
+            return true;
+        }
+        match casing(word) {
+            Casing::Upper => {
+                let lower = word.to_lowercase();
+                self.check_form(&capitalize(&lower), false) || self.check_form(&lower, false)
+            }
+            Casing::Title => self.check_form(&word.to_lowercase(), false),
+            Casing::Lower | Casing::Mixed => false,
+        }
+    }
+
+    /// Returns up to `limit` correctly spelled words close to the given one, the most likely
+    /// first.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let original_casing = casing(word);
+        let mut seen = HashSet::default();
+        let mut suggestions = Vec::new();
+        let mut push = |candidate: String| {
+            if suggestions.len() >= limit || candidate == word || !seen.insert(candidate.clone()) {
+                return;
+            }
+            let valid = candidate
+                .split(' ')
+                .all(|part| self.check(part) && !self.is_no_suggest(part));
+            if valid {
+                suggestions.push(match original_casing {
+                    Casing::Title => capitalize(&candidate),
+                    Casing::Upper => candidate.to_uppercase(),
+                    Casing::Lower | Casing::Mixed => candidate,
+                });
+            }
+        };
+
+        push(word.to_lowercase());
+        push(capitalize(&word.to_lowercase()));
+
+        let chars = word.chars().collect::<Vec<_>>();
+        let try_chars = if self.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.try_chars.clone()
+        };
+
+        for (from, to) in &self.replacements {
+            for (ix, _) in word.match_indices(from.as_str()) {
+                push(format!("{}{}{}", &word[..ix], to, &word[ix + from.len()..]));
+            }
+        }
+        for ix in 1..chars.len() {
+            let mut swapped = chars.clone();
+            swapped.swap(ix - 1, ix);
+            push(swapped.into_iter().collect());
+        }
+        for ix in 0..chars.len() {
+            for c in &try_chars {
+                if *c != chars[ix] {
+                    let mut replaced = chars.clone();
+                    replaced[ix] = *c;
+                    push(replaced.into_iter().collect());
+                }
+            }
+        }
+        for ix in 0..chars.len() {
+            let mut deleted = chars.clone();
+            deleted.remove(ix);
+            push(deleted.into_iter().collect());
+        }
+        for ix in 0..=chars.len() {
+            for c in &try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(ix, *c);
+                push(inserted.into_iter().collect());
+            }
+        }
+        for ix in 1..chars.len() {
+            let (left, right) = chars.split_at(ix);
+            push(format!(
+                "{} {}",
+                left.iter().collect::<String>(),
+                right.iter().collect::<String>()
+            ));
+        }
+
+        suggestions
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.forbidden_flag
+            .is_some_and(|flag| self.stem_has_flag(word, flag))
+    }
+
+    fn is_no_suggest(&self, word: &str) -> bool {
+        self.no_suggest_flag
+            .is_some_and(|flag| self.stem_has_flag(word, flag))
+    }
+
+    fn stem_has_flag(&self, stem: &str, flag: Flag) -> bool {
+        self.words
+            .get(stem)
+            .is_some_and(|homonyms| homonyms.iter().any(|flags| flags.contains(&flag)))
+    }
+
+    /// Returns whether one of the homonyms of the stem can be used, optionally with the given
+    /// affix flags.
+    fn stem_allows(&self, stem: &str, required_flags: &[Flag], exact_case: bool) -> bool {
+        let Some(homonyms) = self.words.get(stem) else {
+            return false;
+        };
+        homonyms.iter().any(|flags| {
+            let has = |flag: Option<Flag>| flag.is_some_and(|flag| flags.contains(&flag));
+            !has(self.forbidden_flag)
+                && (exact_case || !has(self.keep_case_flag))
+                && (!required_flags.is_empty() || !has(self.need_affix_flag))
+                && required_flags.iter().all(|flag| flags.contains(flag))
+        })
+    }
+
+    fn check_form(&self, word: &str, exact_case: bool) -> bool {
+        if self.stem_allows(word, &[], exact_case) {
+            return true;
+        }
+        for suffix in &self.suffixes {
+            if let Some(stem) = strip_suffix(word, suffix)
+                && suffix.condition.matches_end(&stem)
+                && self.stem_allows(&stem, &[suffix.flag], exact_case)
+            {
+                return true;
+            }
+        }
+        for prefix in &self.prefixes {
+            let Some(stem) = strip_prefix(word, prefix) else {
+                continue;
+            };
+            if prefix.condition.matches_start(&stem)
+                && self.stem_allows(&stem, &[prefix.flag], exact_case)
+            {
+                return true;
+            }
+            if !prefix.cross_product {
+                continue;
+            }
+            for suffix in self.suffixes.iter().filter(|suffix| suffix.cross_product) {
+                if let Some(stem) = strip_suffix(&stem, suffix)
+                    && suffix.condition.matches_end(&stem)
+                    && prefix.condition.matches_start(&stem)
+                    && self.stem_allows(&stem, &[prefix.flag, suffix.flag], exact_case)
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+fn strip_suffix(word: &str, suffix: &Affix) -> Option<String> {
+    let rest = word.strip_suffix(suffix.add.as_str())?;
+    (!rest.is_empty()).then(|| format!("{rest}{}", suffix.strip))
+}
+
+fn strip_prefix(word: &str, prefix: &Affix) -> Option<String> {
+    let rest = word.strip_prefix(prefix.add.as_str())?;
+    (!rest.is_empty()).then(|| format!("{}{rest}", prefix.strip))
+}
+
+/// Splits a `.dic` entry into its word and flags, at the first slash that isn't escaped.
+fn split_dic_entry(entry: &str) -> (String, Option<&str>) {
+    let mut escaped = false;
+    for (ix, c) in entry.char_indices() {
+        match c {
+            '\\' => escaped = !escaped,
+            '/' if !escaped && ix > 0 => {
+                return (entry[..ix].replace("\\/", "/"), Some(&entry[ix + 1..]));
+            }
+            _ => escaped = false,
+        }
+    }
+    (entry.replace("\\/", "/"), None)
+}
+
+fn parse_flags(flags: &str, format: FlagFormat) -> Vec<Flag> {
+    match format {
+        FlagFormat::Short => flags.chars().map(|c| c as Flag).collect(),
+        FlagFormat::Long => {
+            let chars = flags.chars().collect::<Vec<_>>();
+            chars
+                .chunks(2)
+                .map(|pair| pair.iter().fold(0, |flag, c| (flag << 16) | *c as Flag))
+                .collect()
+        }
+        FlagFormat::Numeric => flags
+            .split(',')
+            .filter_map(|flag| flag.trim().parse().ok())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn english() -> Dictionary {
+        let aff = indoc! {"
+            SET UTF-8
+            TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+            FORBIDDENWORD !
+            REP 2
+            REP f ph
+            REP ph f
+
+            PFX U Y 1
+            PFX U   0     un         .
+
+            SFX S Y 4
+            SFX S   y     ies        [^aeiou]y
+            SFX S   0     s          [aeiou]y
+            SFX S   0     es         [sxzh]
+            SFX S   0     s          [^sxzhy]
+
+            SFX D Y 2
+            SFX D   0     d          e
+            SFX D   0     ed         [^ey]
+        "};
+        let dic = indoc! {"
+            8
+            word/S
+            city/S
+            box/S
+            lock/USD
+            tie/D
+            Paris
+            phone/S
+            foo/!
+        "};
+        Dictionary::new(aff.as_bytes(), dic.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        let dictionary = english();
+        assert_eq!(dictionary.len(), 8);
+
+        for word in [
+            "word", "words", "city", "cities", "boxes", "lock", "locks", "unlock", "unlocks",
+            "unlocked", "tied", "Paris", "Word", "WORDS", "PARIS",
+        ] {
+            assert!(dictionary.check(word), "{word} should be spelled correctly");
+        }
+        for word in [
+            "wrod", "citys", "boxs", "untie", "tieed", "paris", "wOrd", "foo", "unword",
+        ] {
+            assert!(!dictionary.check(word), "{word} should be misspelled");
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = english();
+        assert_eq!(dictionary.suggest("wrod", 3), ["word"]);
+        assert_eq!(dictionary.suggest("Citys", 3), ["City"]);
+        assert_eq!(dictionary.suggest("fone", 3), ["phone"]);
+        assert_eq!(dictionary.suggest("paris", 3), ["Paris"]);
+        assert_eq!(dictionary.suggest("wordbox", 3), ["word box"]);
+        assert!(dictionary.suggest("foo", 3).is_empty());
+    }
+
+    #[test]
+    fn test_flag_formats() {
+        let aff = indoc! {"
+            FLAG long
+            SFX Aa Y 1
+            SFX Aa 0 s .
+        "};
+        let dictionary = Dictionary::new(aff.as_bytes(), b"1\ncat/AaBb\n").unwrap();
+        assert!(dictionary.check("cats"));
+
+        let aff = indoc! {"
+            FLAG num
+            SFX 101 Y 1
+            SFX 101 0 s .
+        "};
+        let dictionary = Dictionary::new(aff.as_bytes(), b"1\ncat/7,101\n").unwrap();
+        assert!(dictionary.check("cats"));
+
+        let aff = indoc! {"
+            AF 1
+            AF S
+            SFX S Y 1
+            SFX S 0 s .
+        "};
+        let dictionary = Dictionary::new(aff.as_bytes(), b"1\ncat/1\n").unwrap();
+        assert!(dictionary.check("cats"));
+    }
+
+    #[test]
+    fn test_latin1_encoding() {
+        let aff = b"SET ISO8859-1\n";
+        let dic = b"1\ncaf\xe9\n";
+        let dictionary = Dictionary::new(aff, dic).unwrap();
+        assert!(dictionary.check("café"));
+    }
+
+    #[test]
+    fn test_windows_code_page_encoding() {
+        let aff = b"SET microsoft-cp1251\n";
+        let dic = b"1\n\xec\xe8\xf0\n";
+        let dictionary = Dictionary::new(aff, dic).unwrap();
+        assert!(dictionary.check("мир"));
+    }
+}
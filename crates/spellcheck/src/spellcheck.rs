@@ -0,0 +1,74 @@
+//! Offline spell checking with Hunspell dictionaries.
+mod dictionary;
+mod words;
+
+use std::{ops::Range, sync::Arc};
+
+use collections::{HashMap, HashSet};
+
+pub use dictionary::Dictionary;
+pub use words::{Word, words};
+
+/// Checks words against a set of dictionaries, accepting a word if any of them does, and against
+/// a list of words added by the user.
+#[derive(Clone, Default)]
+pub struct SpellChecker {
+    dictionaries: Vec<Arc<Dictionary>>,
+    accepted_words: Arc<HashSet<String>>,
+}
+
+impl SpellChecker {
+    pub fn new(dictionaries: Vec<Arc<Dictionary>>, accepted_words: HashSet<String>) -> Self {
+        Self {
+            dictionaries,
+            accepted_words: Arc::new(accepted_words),
+        }
+    }
+
+    /// Whether there is anything to check words against.
+    pub fn is_empty(&self) -> bool {
+        self.dictionaries.is_empty()
+    }
+
+    pub fn check(&self, word: &str) -> bool {
+        self.accepted_words.contains(word)
+            || self.accepted_words.contains(&word.to_lowercase())
+            || self
+                .dictionaries
+                .iter()
+                .any(|dictionary| dictionary.check(word))
+    }
+
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        for dictionary in &self.dictionaries {
+            for suggestion in dictionary.suggest(word, limit) {
+                if suggestions.len() < limit && !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions
+    }
+
+    /// Returns the byte ranges of the misspelled words in the given ranges of `text`.
+    pub fn misspellings(
+        &self,
+        text: &str,
+        ranges: impl IntoIterator<Item = Range<usize>>,
+    ) -> Vec<Range<usize>> {
+        let mut checked_words = HashMap::<&str, bool>::default();
+        let mut misspellings = Vec::new();
+        for range in ranges {
+            for word in words(&text[range.clone()]) {
+                let correct = *checked_words
+                    .entry(word.text)
+                    .or_insert_with(|| self.check(word.text));
+                if !correct {
+                    misspellings.push(range.start + word.range.start..range.start + word.range.end);
+                }
+            }
+        }
+        misspellings
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+/// A word to spell check, found in a larger piece of text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Word<'a> {
+    /// The byte range of the word in the text.
+    pub range: Range<usize>,
+    pub text: &'a str,
+}
+
+/// Returns the words of `text` that should be spell checked.
+///
+/// Identifiers are split into the words they're made of, so `parseHTTPResponse` and
+/// `parse_response` are checked as `parse`, `HTTP` and `Response`. Single letters, words
+/// containing digits, acronyms written in uppercase, URLs and email addresses are skipped.
+pub fn words(text: &str) -> Vec<Word<'_>> {
+    let mut words = Vec::new();
+    let mut offset = 0;
+    for chunk in text.split_whitespace() {
+        let chunk_start = offset + text[offset..].find(chunk).unwrap_or(0);
+        offset = chunk_start + chunk.len();
+        if chunk.contains("://") || chunk.starts_with("www.") || chunk.contains('@') {
+            continue;
+        }
+
+        let mut token_start = None;
+        for (ix, c) in chunk.char_indices().chain([(chunk.len(), ' ')]) {
+            let is_token_char = c.is_alphanumeric() || c == '_' || c == '\'' || c == '’';
+            match (token_start, is_token_char) {
+                (None, true) => token_start = Some(ix),
+                (Some(start), false) => {
+                    push_token_words(&chunk[start..ix], chunk_start + start, &mut words);
+                    token_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    words
+}
+
+fn push_token_words<'a>(token: &'a str, token_start: usize, words: &mut Vec<Word<'a>>) {
+    if token.chars().any(|c| c.is_numeric()) {
+        return;
+    }
+    let mut part_start = 0;
+    for part in token.split('_') {
+        for range in camel_case_ranges(part) {
+            let word = &part[range.clone()];
+            let trimmed = word.trim_matches(['\'', '’']);
+            let start = range.start + (word.len() - word.trim_start_matches(['\'', '’']).len());
+            let is_acronym = !trimmed.chars().any(|c| c.is_lowercase());
+            if trimmed.chars().count() > 1 && !is_acronym {
+                let start = token_start + part_start + start;
+                words.push(Word {
+                    range: start..start + trimmed.len(),
+                    text: trimmed,
+                });
+            }
+        }
+        part_start += part.len() + 1;
+    }
+}
+
+/// Splits a camel case identifier into the byte ranges of its words. A run of uppercase
+/// letters is an acronym, whose last letter starts the next word if a lowercase letter follows.
+fn camel_case_ranges(identifier: &str) -> Vec<Range<usize>> {
+    let chars = identifier.char_indices().collect::<Vec<_>>();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (ix, (offset, c)) in chars.iter().enumerate().skip(1) {
+        let previous = chars[ix - 1].1;
+        let next = chars.get(ix + 1).map(|(_, c)| *c);
+        let starts_word = c.is_uppercase()
+            && (previous.is_lowercase()
+                || previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()));
+        if starts_word {
+            ranges.push(start..*offset);
+            start = *offset;
+        }
+    }
+    if start < identifier.len() {
+        ranges.push(start..identifier.len());
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_texts(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|word| word.text).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            word_texts("// Parse the response, don't retry."),
+            ["Parse", "the", "response", "don't", "retry"]
+        );
+        assert_eq!(
+            word_texts("parseHTTPResponse parse_response_body XMLHttpRequest"),
+            [
+                "parse", "Response", "parse", "response", "body", "Http", "Request"
+            ]
+        );
+        assert_eq!(
+            word_texts("see https://example.com/page or mail me@example.com, 'quoted' utf8 a"),
+            ["see", "or", "mail", "quoted"]
+        );
+    }
+
+    #[test]
+    fn test_word_ranges() {
+        let text = "let fooBar = 'élan_vital';";
+        for word in words(text) {
+            assert_eq!(&text[word.range.clone()], word.text);
+        }
+        assert_eq!(
+            words(text)
+                .into_iter()
+                .map(|word| word.range)
+                .collect::<Vec<_>>(),
+            [0..3, 4..7, 7..10, 14..19, 20..25]
+        );
+    }
+}
@@ -4,17 +4,20 @@ mod assets;
 mod stories;
 mod story_selector;
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use clap::Parser;
 use dialoguer::FuzzySelect;
 use gpui::{
-    AnyView, App, Bounds, Context, Render, Window, WindowBounds, WindowOptions,
+    AnyView, App, Bounds, Context, Render, TestAppContext, TestDispatcher, Window, WindowBounds,
+    WindowOptions,
     colors::{Colors, GlobalColors},
     div, px, size,
 };
 use log::LevelFilter;
 use project::Project;
+use rand::{SeedableRng, rngs::StdRng};
 use reqwest_client::ReqwestClient;
 use settings::{KeymapFile, Settings};
 use simplelog::SimpleLogger;
@@ -40,6 +43,11 @@ struct Args {
     /// If not provided, the default theme will be used.
     #[arg(long)]
     theme: Option<String>,
+
+    /// Render every story without opening a window, and save each one as a PNG in
+    /// the given directory.
+    #[arg(long, value_name = "DIR")]
+    dump_pngs: Option<PathBuf>,
 }
 
 fn main() {
@@ -48,6 +56,15 @@ fn main() {
     menu::init();
     let args = Args::parse();
 
+    if let Some(directory) = args.dump_pngs {
+        let theme_name = args.theme.unwrap_or("One Dark".to_string());
+        if let Err(error) = dump_pngs(&directory, theme_name) {
+            log::error!("failed to dump stories: {error:?}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let story_selector = args.story.unwrap_or_else(|| {
         let stories = ComponentStory::iter().collect::<Vec<_>>();
 
@@ -75,42 +92,77 @@ fn main() {
         let http_client = ReqwestClient::user_agent("zed_storybook").unwrap();
         cx.set_http_client(Arc::new(http_client));
 
-        settings::init(cx);
-        theme::init(theme::LoadThemes::All(Box::new(Assets)), cx);
-
         let selector = story_selector;
 
-        let mut theme_settings = ThemeSettings::get_global(cx).clone();
-        theme_settings.theme =
-            theme::ThemeSelection::Static(settings::ThemeName(theme_name.into()));
-        ThemeSettings::override_global(theme_settings, cx);
-
-        language::init(cx);
-        editor::init(cx);
-        Project::init_settings(cx);
-        workspace::init_settings(cx);
+        init_story_settings(theme_name, cx);
         init(cx);
         load_storybook_keymap(cx);
         cx.set_menus(app_menus());
 
-        let size = size(px(1500.), px(780.));
-        let bounds = Bounds::centered(None, size, cx);
-        let _window = cx.open_window(
-            WindowOptions {
-                window_bounds: Some(WindowBounds::Windowed(bounds)),
-                ..Default::default()
-            },
-            move |window, cx| {
-                theme::setup_ui_font(window, cx);
-
-                cx.new(|cx| StoryWrapper::new(selector.story(window, cx)))
-            },
-        );
+        let _window = open_story_window(selector, cx);
 
         cx.activate(true);
     });
 }
 
+fn init_story_settings(theme_name: String, cx: &mut App) {
+    settings::init(cx);
+    theme::init(theme::LoadThemes::All(Box::new(Assets)), cx);
+
+    let mut theme_settings = ThemeSettings::get_global(cx).clone();
+    theme_settings.theme = theme::ThemeSelection::Static(settings::ThemeName(theme_name.into()));
+    ThemeSettings::override_global(theme_settings, cx);
+
+    language::init(cx);
+    editor::init(cx);
+    Project::init_settings(cx);
+    workspace::init_settings(cx);
+}
+
+fn open_story_window(
+    selector: StorySelector,
+    cx: &mut App,
+) -> anyhow::Result<gpui::WindowHandle<StoryWrapper>> {
+    let size = size(px(1500.), px(780.));
+    let bounds = Bounds::centered(None, size, cx);
+    cx.open_window(
+        WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(bounds)),
+            ..Default::default()
+        },
+        move |window, cx| {
+            theme::setup_ui_font(window, cx);
+
+            cx.new(|cx| StoryWrapper::new(selector.story(window, cx)))
+        },
+    )
+}
+
+/// Renders each component story on the CPU, using the test platform, so that the
+/// stories can be compared across changes on machines without a GPU.
+fn dump_pngs(directory: &Path, theme_name: String) -> anyhow::Result<()> {
+    std::fs::create_dir_all(directory)?;
+
+    let dispatcher = TestDispatcher::new(StdRng::seed_from_u64(0));
+    let mut cx = TestAppContext::build_with_platform_text_system(dispatcher, None);
+    cx.set_asset_source(Assets);
+    cx.update(|cx| {
+        load_embedded_fonts(cx)?;
+        cx.set_global(GlobalColors(Arc::new(Colors::default())));
+        init_story_settings(theme_name, cx);
+        anyhow::Ok(())
+    })?;
+
+    for story in ComponentStory::iter() {
+        let window = cx.update(|cx| open_story_window(StorySelector::Component(story), cx))?;
+        let path = directory.join(format!("{story}.png"));
+        cx.render_window(window.into()).save(&path)?;
+        log::info!("rendered {story} to {path:?}");
+        cx.update_window(window.into(), |_, window, _| window.remove_window())?;
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct StoryWrapper {
     story: AnyView,
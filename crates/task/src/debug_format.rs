@@ -106,6 +106,22 @@ impl LaunchRequest {
     }
 }
 
+/// Represents a request to load a core dump or minidump, to inspect the state of a
+/// program at the moment it crashed
+#[derive(Deserialize, Serialize, Default, PartialEq, Eq, JsonSchema, Clone, Debug)]
+pub struct CoreDumpRequest {
+    /// The program that produced the dump
+    pub program: String,
+    /// The core dump or minidump to load
+    pub core_file: String,
+    /// The directory to look up the program's shared libraries in, for dumps taken on another machine
+    #[serde(default)]
+    pub sysroot: Option<PathBuf>,
+    /// Additional directories to search for shared libraries
+    #[serde(default)]
+    pub solib_search_paths: Vec<PathBuf>,
+}
+
 /// Represents the type that will determine which request to call on the debug adapter
 #[derive(Deserialize, Serialize, PartialEq, Eq, JsonSchema, Clone, Debug)]
 #[serde(rename_all = "lowercase", tag = "request")]
@@ -114,6 +130,9 @@ pub enum DebugRequest {
     Launch(LaunchRequest),
     /// Call the `attach` request on the debug adapter
     Attach(AttachRequest),
+    /// Load a core dump, without running the program
+    #[serde(rename = "core_dump")]
+    CoreDump(CoreDumpRequest),
 }
 
 impl DebugRequest {
@@ -145,6 +164,23 @@ impl DebugRequest {
                     },
                 )),
             },
+            DebugRequest::CoreDump(core_dump_request) => proto::DebugRequest {
+                request: Some(proto::debug_request::Request::DebugCoreDumpRequest(
+                    proto::DebugCoreDumpRequest {
+                        program: core_dump_request.program.clone(),
+                        core_file: core_dump_request.core_file.clone(),
+                        sysroot: core_dump_request
+                            .sysroot
+                            .as_ref()
+                            .map(|sysroot| sysroot.to_string_lossy().into_owned()),
+                        solib_search_paths: core_dump_request
+                            .solib_search_paths
+                            .iter()
+                            .map(|path| path.to_string_lossy().into_owned())
+                            .collect(),
+                    },
+                )),
+            },
         }
     }
 
@@ -168,6 +204,18 @@ impl DebugRequest {
             }) => Ok(DebugRequest::Attach(AttachRequest {
                 process_id: Some(process_id),
             })),
+
+            proto::debug_request::Request::DebugCoreDumpRequest(proto::DebugCoreDumpRequest {
+                program,
+                core_file,
+                sysroot,
+                solib_search_paths,
+            }) => Ok(DebugRequest::CoreDump(CoreDumpRequest {
+                program,
+                core_file,
+                sysroot: sysroot.map(From::from),
+                solib_search_paths: solib_search_paths.into_iter().map(From::from).collect(),
+            })),
         }
     }
 }
@@ -184,6 +232,12 @@ impl From<AttachRequest> for DebugRequest {
     }
 }
 
+impl From<CoreDumpRequest> for DebugRequest {
+    fn from(core_dump_config: CoreDumpRequest) -> Self {
+        DebugRequest::CoreDump(core_dump_config)
+    }
+}
+
 #[derive(Serialize, PartialEq, Eq, JsonSchema, Clone, Debug)]
 #[serde(untagged)]
 pub enum BuildTaskDefinition {
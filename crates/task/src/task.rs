@@ -20,8 +20,8 @@ use util::get_system_shell;
 
 pub use adapter_schema::{AdapterSchema, AdapterSchemas};
 pub use debug_format::{
-    AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
-    Request, TcpArgumentsTemplate, ZedDebugConfig,
+    AttachRequest, BuildTaskDefinition, CoreDumpRequest, DebugRequest, DebugScenario,
+    DebugTaskFile, LaunchRequest, Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
 pub use shell_builder::{ShellBuilder, ShellKind};
 pub use task_template::{
@@ -174,6 +174,9 @@ pub enum VariableName {
     SelectedText,
     /// The symbol selected by the symbol tagging system, specifically the @run capture in a runnables.scm
     RunnableSymbol,
+    /// An absolute path of the coverage report of the current worktree: coverage shown in the
+    /// editor is reloaded whenever a task writes to it.
+    CoverageReport,
     /// Custom variable, provided by the plugin or other external source.
     /// Will be printed with `CUSTOM_` prefix to avoid potential conflicts with other variables.
     Custom(Cow<'static, str>),
@@ -208,6 +211,7 @@ impl FromStr for VariableName {
             "SELECTED_TEXT" => Self::SelectedText,
             "ROW" => Self::Row,
             "COLUMN" => Self::Column,
+            "COVERAGE_REPORT" => Self::CoverageReport,
             _ => {
                 if let Some(custom_name) =
                     without_prefix.strip_prefix(ZED_CUSTOM_VARIABLE_NAME_PREFIX)
@@ -241,6 +245,7 @@ impl std::fmt::Display for VariableName {
             Self::Column => write!(f, "{ZED_VARIABLE_NAME_PREFIX}COLUMN"),
             Self::SelectedText => write!(f, "{ZED_VARIABLE_NAME_PREFIX}SELECTED_TEXT"),
             Self::RunnableSymbol => write!(f, "{ZED_VARIABLE_NAME_PREFIX}RUNNABLE_SYMBOL"),
+            Self::CoverageReport => write!(f, "{ZED_VARIABLE_NAME_PREFIX}COVERAGE_REPORT"),
             Self::Custom(s) => write!(
                 f,
                 "{ZED_VARIABLE_NAME_PREFIX}{ZED_CUSTOM_VARIABLE_NAME_PREFIX}{s}"
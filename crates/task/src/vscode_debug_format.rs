@@ -87,6 +87,8 @@ fn task_type_to_adapter_name(task_type: &str) -> String {
         "cppdbg" | "lldb" => "CodeLLDB",
         "debugpy" => "Debugpy",
         "rdbg" => "rdbg",
+        "java" => "Java",
+        "coreclr" => "netcoredbg",
         _ => task_type,
     }
     .to_owned()
@@ -529,6 +529,7 @@ mod tests {
                     (VariableName::Dirname, path!("/dir/rust").into()),
                     (VariableName::Stem, "b".into()),
                     (VariableName::WorktreeRoot, path!("/dir").into()),
+                    (VariableName::CoverageReport, path!("/dir/lcov.info").into()),
                     (VariableName::Row, "1".into()),
                     (VariableName::Column, "1".into()),
                 ]),
@@ -561,6 +562,7 @@ mod tests {
                     (VariableName::Dirname, path!("/dir/rust").into()),
                     (VariableName::Stem, "b".into()),
                     (VariableName::WorktreeRoot, path!("/dir").into()),
+                    (VariableName::CoverageReport, path!("/dir/lcov.info").into()),
                     (VariableName::Row, "1".into()),
                     (VariableName::Column, "15".into()),
                     (VariableName::SelectedText, "is_i".into()),
@@ -590,6 +592,7 @@ mod tests {
                     (VariableName::Dirname, path!("/dir").into()),
                     (VariableName::Stem, "a".into()),
                     (VariableName::WorktreeRoot, path!("/dir").into()),
+                    (VariableName::CoverageReport, path!("/dir/lcov.info").into()),
                     (VariableName::Row, "1".into()),
                     (VariableName::Column, "1".into()),
                     (VariableName::Symbol, "this_is_a_test".into()),
@@ -0,0 +1,658 @@
+//! Machine-readable test reporters: how to ask each supported test runner for one, and how to
+//! read what it reports.
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Duration,
+};
+use util::paths::PathExt as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFramework {
+    /// `cargo test`, reporting through libtest's default output.
+    Cargo,
+    /// `pytest`, reporting through a JUnit XML file.
+    Pytest,
+    /// `go test -json`.
+    GoTest,
+    /// Jest, reporting through a JSON file.
+    Jest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCaseResult {
+    /// Names identifying the test, outermost first: the module path, test class or `describe`
+    /// blocks, then the name of the test itself.
+    pub segments: Vec<String>,
+    /// The file the test is defined in, if the reporter says.
+    pub file: Option<PathBuf>,
+    pub outcome: TestOutcome,
+    pub duration: Option<Duration>,
+    /// The failure message, or the output the test produced before failing.
+    pub message: Option<String>,
+    /// Where the test failed, as a path printed by the test runner and a zero-based row.
+    pub failure_location: Option<(PathBuf, u32)>,
+}
+
+impl TestFramework {
+    /// Recognizes the test runner invoked by a resolved task command.
+    pub fn detect(command: &str, args: &[String]) -> Option<Self> {
+        let program = Path::new(command).file_stem()?.to_str()?;
+        // Skip toolchain overrides, as in `cargo +nightly test`.
+        let subcommand = args.iter().find(|arg| !arg.starts_with('+'));
+        match program {
+            "cargo" if subcommand.is_some_and(|arg| arg == "test") => Some(Self::Cargo),
+            "go" if subcommand.is_some_and(|arg| arg == "test") => Some(Self::GoTest),
+            "pytest" => Some(Self::Pytest),
+            "jest" => Some(Self::Jest),
+            _ if args.iter().any(|arg| arg == "pytest") => Some(Self::Pytest),
+            _ if args.iter().any(|arg| arg == "jest") => Some(Self::Jest),
+            _ => None,
+        }
+    }
+
+    /// Whether the reporter writes to a file rather than to stdout.
+    pub fn reports_to_file(self) -> bool {
+        matches!(self, Self::Pytest | Self::Jest)
+    }
+
+    /// Adjusts the arguments of a test command so that it reports its results in the format
+    /// [`Self::parse`] reads, writing them to `report_path` if [`Self::reports_to_file`].
+    ///
+    /// The command line is run by a shell, so the report path is quoted for it where needed.
+    pub fn prepare_command(self, args: &mut Vec<String>, report_path: &Path) -> Result<()> {
+        match self {
+            Self::Cargo => {
+                // libtest's JSON output needs a nightly toolchain, so read the default output,
+                // which is stable. Captured output is printed along with each failure, and
+                // that's where the panic location is found.
+                args.retain(|arg| arg != "--nocapture");
+                let separator = match args.iter().position(|arg| arg == "--") {
+                    Some(ix) => ix,
+                    None => {
+                        args.push("--".to_string());
+                        args.len() - 1
+                    }
+                };
+                let mut test_args = args.split_off(separator + 1);
+                test_args.retain(|arg| !matches!(arg.as_str(), "-q" | "--quiet"));
+                args.extend(["--format".to_string(), "pretty".to_string()]);
+                args.extend(test_args);
+            }
+            Self::GoTest => {
+                let ix = args
+                    .iter()
+                    .position(|arg| arg == "test")
+                    .map_or(0, |ix| ix + 1);
+                args.insert(ix, "-json".to_string());
+            }
+            Self::Pytest => {
+                args.push(format!("--junitxml={}", report_path.try_shell_safe()?));
+            }
+            Self::Jest => {
+                args.extend([
+                    "--json".to_string(),
+                    "--testLocationInResults".to_string(),
+                    format!("--outputFile={}", report_path.try_shell_safe()?),
+                ]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the results out of the report: the command's stdout, or the contents of the
+    /// report file.
+    pub fn parse(self, report: &str) -> Result<Vec<TestCaseResult>> {
+        match self {
+            Self::Cargo => Ok(parse_libtest_output(report)),
+            Self::GoTest => Ok(parse_go_test_json(report)),
+            Self::Pytest => parse_junit_xml(report),
+            Self::Jest => parse_jest_json(report),
+        }
+    }
+
+    /// Turns a test name as it appears in the source into the name the runner reports.
+    pub fn normalize_name(self, name: &str) -> String {
+        match self {
+            // `go test` replaces spaces in subtest names with underscores.
+            Self::GoTest => name.replace(' ', "_"),
+            Self::Cargo | Self::Pytest | Self::Jest => name.to_string(),
+        }
+    }
+}
+
+fn parse_libtest_output(output: &str) -> Vec<TestCaseResult> {
+    // Several test binaries may report into the same output, interleaved with whatever else
+    // cargo prints. Each test gets a `test <name> ... <outcome>` line, and the captured output
+    // of the failed ones follows in `---- <name> stdout ----` sections.
+    let mut results = Vec::new();
+    let mut failure_outputs = HashMap::<String, String>::default();
+    let mut failure_output: Option<(&str, String)> = None;
+    for line in output.lines() {
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|line| line.strip_suffix(" stdout ----"))
+        {
+            if let Some((name, output)) = failure_output.replace((name, String::new())) {
+                failure_outputs.insert(name.to_string(), output);
+            }
+            continue;
+        }
+        if let Some((_, output)) = &mut failure_output {
+            if line != "failures:" {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            }
+            if let Some((name, output)) = failure_output.take() {
+                failure_outputs.insert(name.to_string(), output);
+            }
+        }
+
+        let Some((name, outcome)) = line
+            .strip_prefix("test ")
+            .and_then(|line| line.split_once(" ... "))
+        else {
+            continue;
+        };
+        let outcome = match outcome {
+            "ok" => TestOutcome::Passed,
+            "FAILED" => TestOutcome::Failed,
+            _ if outcome.starts_with("ignored") => TestOutcome::Skipped,
+            _ => continue,
+        };
+        results.push(TestCaseResult {
+            segments: name.split("::").map(str::to_string).collect(),
+            file: None,
+            outcome,
+            duration: None,
+            message: None,
+            failure_location: None,
+        });
+    }
+    if let Some((name, output)) = failure_output {
+        failure_outputs.insert(name.to_string(), output);
+    }
+
+    for result in &mut results {
+        if result.outcome != TestOutcome::Failed {
+            continue;
+        }
+        let Some(message) = failure_outputs.remove(&result.segments.join("::")) else {
+            continue;
+        };
+        let message = message.trim().to_string();
+        result.failure_location = message
+            .find("panicked at")
+            .and_then(|ix| locations(&message[ix..]).into_iter().next());
+        result.message = Some(message);
+    }
+    results
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GoTestEvent {
+    action: String,
+    test: Option<String>,
+    output: Option<String>,
+    elapsed: Option<f64>,
+}
+
+fn parse_go_test_json(output: &str) -> Vec<TestCaseResult> {
+    let mut outputs = HashMap::<String, String>::default();
+    let mut results = Vec::new();
+    for event in output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<GoTestEvent>(line).ok())
+    {
+        let Some(test) = event.test else {
+            continue;
+        };
+        let outcome = match event.action.as_str() {
+            "output" => {
+                let output = event.output.unwrap_or_default();
+                // Skip the runner's own progress lines.
+                if !output.starts_with("=== ") && !output.trim_start().starts_with("--- ") {
+                    outputs.entry(test).or_default().push_str(&output);
+                }
+                continue;
+            }
+            "pass" => TestOutcome::Passed,
+            "fail" => TestOutcome::Failed,
+            "skip" => TestOutcome::Skipped,
+            _ => continue,
+        };
+        let message = outputs.remove(&test).filter(|output| !output.is_empty());
+        let failure_location = message
+            .as_deref()
+            .filter(|_| outcome == TestOutcome::Failed)
+            .and_then(|message| locations(message).into_iter().next());
+        results.push(TestCaseResult {
+            segments: test.split('/').map(str::to_string).collect(),
+            file: None,
+            outcome,
+            duration: event.elapsed.map(Duration::from_secs_f64),
+            message,
+            failure_location,
+        });
+    }
+    results
+}
+
+fn parse_junit_xml(report: &str) -> Result<Vec<TestCaseResult>> {
+    let document = roxmltree::Document::parse(report).context("parsing JUnit report")?;
+    Ok(document
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+        .filter_map(|testcase| {
+            // Parametrized cases are reported under the function that defines them.
+            let name = testcase.attribute("name")?;
+            let name = name.split_once('[').map_or(name, |(name, _)| name);
+            let mut segments = testcase
+                .attribute("classname")
+                .map(|classname| classname.split('.').map(str::to_string).collect::<Vec<_>>())
+                .unwrap_or_default();
+            segments.push(name.to_string());
+            let file = testcase.attribute("file").map(PathBuf::from);
+
+            let mut outcome = TestOutcome::Passed;
+            let mut message = None;
+            for child in testcase.children().filter(|child| child.is_element()) {
+                match child.tag_name().name() {
+                    "failure" | "error" => {
+                        outcome = TestOutcome::Failed;
+                        message = child
+                            .text()
+                            .filter(|text| !text.trim().is_empty())
+                            .or_else(|| child.attribute("message"))
+                            .map(str::to_string);
+                    }
+                    "skipped" => {
+                        outcome = TestOutcome::Skipped;
+                        message = child.attribute("message").map(str::to_string);
+                    }
+                    _ => {}
+                }
+            }
+            // The traceback ends with the innermost frame; prefer the last one in the test's
+            // own file, which is where the assertion is.
+            let failure_location = message
+                .as_deref()
+                .filter(|_| outcome == TestOutcome::Failed)
+                .and_then(|message| {
+                    let locations = locations(message);
+                    let in_test_file = file.as_deref().and_then(|file| {
+                        locations
+                            .iter()
+                            .rfind(|(path, _)| path.ends_with(file) || file.ends_with(path))
+                    });
+                    in_test_file.or(locations.last()).cloned()
+                });
+            Some(TestCaseResult {
+                segments,
+                file,
+                outcome,
+                duration: testcase
+                    .attribute("time")
+                    .and_then(|time| time.parse().ok())
+                    .map(Duration::from_secs_f64),
+                message,
+                failure_location,
+            })
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestReport {
+    test_results: Vec<JestFileResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestFileResult {
+    name: PathBuf,
+    assertion_results: Vec<JestAssertionResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestAssertionResult {
+    #[serde(default)]
+    ancestor_titles: Vec<String>,
+    title: String,
+    status: String,
+    duration: Option<f64>,
+    #[serde(default)]
+    failure_messages: Vec<String>,
+}
+
+fn parse_jest_json(report: &str) -> Result<Vec<TestCaseResult>> {
+    let report: JestReport = serde_json::from_str(report).context("parsing Jest report")?;
+    Ok(report
+        .test_results
+        .into_iter()
+        .flat_map(|file_result| {
+            let file = file_result.name;
+            file_result
+                .assertion_results
+                .into_iter()
+                .map(move |assertion| {
+                    let outcome = match assertion.status.as_str() {
+                        "passed" => TestOutcome::Passed,
+                        "failed" => TestOutcome::Failed,
+                        _ => TestOutcome::Skipped,
+                    };
+                    let message = (!assertion.failure_messages.is_empty())
+                        .then(|| assertion.failure_messages.join("\n"));
+                    // The first stack frame in the test file is the failed expectation.
+                    let failure_location = message.as_deref().and_then(|message| {
+                        locations(message)
+                            .into_iter()
+                            .find(|(path, _)| *path == file)
+                    });
+                    let mut segments = assertion.ancestor_titles;
+                    segments.push(assertion.title);
+                    TestCaseResult {
+                        segments,
+                        file: Some(file.clone()),
+                        outcome,
+                        duration: assertion.duration.map(Duration::from_secs_f64),
+                        message,
+                        failure_location,
+                    }
+                })
+        })
+        .collect())
+}
+
+/// Finds the `path:line` and `path:line:column` references in test output, in order.
+fn locations(text: &str) -> Vec<(PathBuf, u32)> {
+    static LOCATION: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"([^\s()'"`,]+\.[A-Za-z0-9]+):(\d+)(?::\d+)?"#).unwrap());
+    LOCATION
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let line = captures[2].parse::<u32>().ok()?;
+            Some((PathBuf::from(&captures[1]), line.saturating_sub(1)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_detect_framework() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            TestFramework::detect("cargo", &args(&["test", "-p", "foo"])),
+            Some(TestFramework::Cargo)
+        );
+        assert_eq!(
+            TestFramework::detect("cargo", &args(&["+nightly", "test"])),
+            Some(TestFramework::Cargo)
+        );
+        assert_eq!(TestFramework::detect("cargo", &args(&["run"])), None);
+        assert_eq!(
+            TestFramework::detect("/usr/bin/python3", &args(&["-m", "pytest", "a.py"])),
+            Some(TestFramework::Pytest)
+        );
+        assert_eq!(
+            TestFramework::detect("npm", &args(&["exec", "--", "jest", "a.test.js"])),
+            Some(TestFramework::Jest)
+        );
+        assert_eq!(
+            TestFramework::detect("go", &args(&["test", "-run", "^TestFoo$"])),
+            Some(TestFramework::GoTest)
+        );
+        assert_eq!(
+            TestFramework::detect("/usr/bin/python3", &args(&["-m", "unittest"])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prepare_command() {
+        let report_path = Path::new("/tmp/report");
+        let mut args = ["test", "-p", "foo", "--", "--nocapture", "-q", "tests::bar"]
+            .map(String::from)
+            .to_vec();
+        TestFramework::Cargo
+            .prepare_command(&mut args, report_path)
+            .unwrap();
+        assert_eq!(
+            args,
+            [
+                "test",
+                "-p",
+                "foo",
+                "--",
+                "--format",
+                "pretty",
+                "tests::bar"
+            ]
+        );
+
+        let mut args = ["test", "-p", "foo"].map(String::from).to_vec();
+        TestFramework::Cargo
+            .prepare_command(&mut args, report_path)
+            .unwrap();
+        assert_eq!(args, ["test", "-p", "foo", "--", "--format", "pretty"]);
+
+        let mut args = ["test", "-run", "^TestFoo$"].map(String::from).to_vec();
+        TestFramework::GoTest
+            .prepare_command(&mut args, report_path)
+            .unwrap();
+        assert_eq!(args, ["test", "-json", "-run", "^TestFoo$"]);
+
+        let mut args = ["-m", "pytest", "a.py"].map(String::from).to_vec();
+        TestFramework::Pytest
+            .prepare_command(&mut args, report_path)
+            .unwrap();
+        assert_eq!(args, ["-m", "pytest", "a.py", "--junitxml=/tmp/report"]);
+
+        let mut args = ["jest"].map(String::from).to_vec();
+        TestFramework::Jest
+            .prepare_command(&mut args, report_path)
+            .unwrap();
+        assert_eq!(
+            args,
+            [
+                "jest",
+                "--json",
+                "--testLocationInResults",
+                "--outputFile=/tmp/report"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_libtest_output() {
+        let output = indoc! {"
+               Compiling foo v0.1.0
+                Finished `test` profile [unoptimized + debuginfo] target(s) in 0.50s
+                 Running unittests src/lib.rs (target/debug/deps/foo-0123456789abcdef)
+
+            running 4 tests
+            test tests::ignored ... ignored
+            test tests::later ... ignored, not yet
+            test tests::passes ... ok
+            test tests::fails ... FAILED
+
+            failures:
+
+            ---- tests::fails stdout ----
+
+            thread 'tests::fails' panicked at src/lib.rs:12:9:
+            assertion `left == right` failed
+              left: 1
+             right: 2
+            note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+            failures:
+                tests::fails
+
+            test result: FAILED. 1 passed; 1 failed; 2 ignored; 0 measured; 0 filtered out; finished in 0.00s
+        "};
+        let results = TestFramework::Cargo.parse(output).unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].segments, ["tests", "ignored"]);
+        assert_eq!(results[0].outcome, TestOutcome::Skipped);
+        assert_eq!(results[1].outcome, TestOutcome::Skipped);
+        assert_eq!(results[2].segments, ["tests", "passes"]);
+        assert_eq!(results[2].outcome, TestOutcome::Passed);
+        assert_eq!(results[2].message, None);
+        assert_eq!(results[3].outcome, TestOutcome::Failed);
+        assert_eq!(
+            results[3].failure_location,
+            Some((PathBuf::from("src/lib.rs"), 11))
+        );
+        assert!(
+            results[3]
+                .message
+                .as_deref()
+                .unwrap()
+                .starts_with("thread 'tests::fails' panicked at src/lib.rs:12:9:")
+        );
+    }
+
+    #[test]
+    fn test_parse_go_test_json() {
+        let output = r#"{"Action":"run","Package":"example.com/foo","Test":"TestSum"}
+{"Action":"output","Package":"example.com/foo","Test":"TestSum","Output":"=== RUN   TestSum\n"}
+{"Action":"run","Package":"example.com/foo","Test":"TestSum/adds_negatives"}
+{"Action":"output","Package":"example.com/foo","Test":"TestSum/adds_negatives","Output":"    sum_test.go:21: got 1, want -1\n"}
+{"Action":"output","Package":"example.com/foo","Test":"TestSum/adds_negatives","Output":"    --- FAIL: TestSum/adds_negatives (0.00s)\n"}
+{"Action":"fail","Package":"example.com/foo","Test":"TestSum/adds_negatives","Elapsed":0}
+{"Action":"fail","Package":"example.com/foo","Test":"TestSum","Elapsed":0.01}
+{"Action":"fail","Package":"example.com/foo","Elapsed":0.02}
+"#;
+        let results = TestFramework::GoTest.parse(output).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].segments, ["TestSum", "adds_negatives"]);
+        assert_eq!(results[0].outcome, TestOutcome::Failed);
+        assert_eq!(
+            results[0].message.as_deref(),
+            Some("    sum_test.go:21: got 1, want -1\n")
+        );
+        assert_eq!(
+            results[0].failure_location,
+            Some((PathBuf::from("sum_test.go"), 20))
+        );
+        assert_eq!(results[1].segments, ["TestSum"]);
+        assert_eq!(
+            TestFramework::GoTest.normalize_name("adds negatives"),
+            "adds_negatives"
+        );
+    }
+
+    #[test]
+    fn test_parse_junit_xml() {
+        let report = r#"<?xml version="1.0" encoding="utf-8"?>
+<testsuites>
+  <testsuite name="pytest" errors="0" failures="1" skipped="1" tests="4">
+    <testcase classname="tests.test_math" name="test_add" file="tests/test_math.py" line="3" time="0.001" />
+    <testcase classname="tests.test_math.TestDivide" name="test_by_zero[1]" file="tests/test_math.py" line="10" time="0.002">
+      <failure message="assert 1 == 2">def test_by_zero(n):
+&gt;       assert helper(n) == 2
+
+tests/test_math.py:12:
+_ _ _ _
+
+    def helper(n):
+&gt;       raise ValueError()
+
+tests/helpers.py:4: ValueError</failure>
+    </testcase>
+    <testcase classname="tests.test_math.TestDivide" name="test_by_zero[2]" time="0.001" />
+    <testcase classname="tests.test_math" name="test_later" time="0">
+      <skipped message="not yet" />
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+        let results = TestFramework::Pytest.parse(report).unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].segments, ["tests", "test_math", "test_add"]);
+        assert_eq!(results[0].outcome, TestOutcome::Passed);
+        assert_eq!(
+            results[1].segments,
+            ["tests", "test_math", "TestDivide", "test_by_zero"]
+        );
+        assert_eq!(results[1].outcome, TestOutcome::Failed);
+        assert_eq!(
+            results[1].failure_location,
+            Some((PathBuf::from("tests/test_math.py"), 11))
+        );
+        assert_eq!(results[3].outcome, TestOutcome::Skipped);
+        assert_eq!(results[3].message.as_deref(), Some("not yet"));
+    }
+
+    #[test]
+    fn test_parse_jest_json() {
+        let report = r#"{
+  "numFailedTests": 1,
+  "testResults": [
+    {
+      "name": "/project/src/sum.test.js",
+      "status": "failed",
+      "assertionResults": [
+        {
+          "ancestorTitles": ["sum"],
+          "fullName": "sum adds numbers",
+          "status": "passed",
+          "title": "adds numbers",
+          "duration": 2,
+          "failureMessages": [],
+          "location": { "line": 4, "column": 3 }
+        },
+        {
+          "ancestorTitles": ["sum"],
+          "fullName": "sum handles strings",
+          "status": "failed",
+          "title": "handles strings",
+          "duration": 3,
+          "failureMessages": [
+            "Error: expect(received).toBe(expected)\n    at Object.toBe (/project/src/sum.test.js:9:25)\n    at Promise.then.completed (/project/node_modules/jest-circus/build/utils.js:298:28)"
+          ]
+        },
+        {
+          "ancestorTitles": [],
+          "title": "todo",
+          "status": "todo",
+          "failureMessages": []
+        }
+      ]
+    }
+  ]
+}"#;
+        let results = TestFramework::Jest.parse(report).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].segments, ["sum", "adds numbers"]);
+        assert_eq!(results[0].outcome, TestOutcome::Passed);
+        assert_eq!(results[1].outcome, TestOutcome::Failed);
+        assert_eq!(
+            results[1].failure_location,
+            Some((PathBuf::from("/project/src/sum.test.js"), 8))
+        );
+        assert_eq!(results[2].segments, ["todo"]);
+        assert_eq!(results[2].outcome, TestOutcome::Skipped);
+    }
+}
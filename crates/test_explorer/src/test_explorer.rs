@@ -0,0 +1,1591 @@
+mod reporters;
+mod test_explorer_settings;
+
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    Action, AnyElement, App, AsyncWindowContext, ClickEvent, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, InteractiveElement, IntoElement, KeyContext, ParentElement, Pixels,
+    Render, ScrollStrategy, SharedString, Styled, Subscription, Task, UniformListScrollHandle,
+    WeakEntity, Window, actions, div, px, uniform_list,
+};
+use language::{
+    Buffer, BufferEvent, BufferId, BufferSnapshot, Language, Location, OffsetRangeExt as _, Point,
+    ToPoint as _, language_settings::language_settings,
+};
+use project::{
+    Fs, Project, ProjectPath, TaskSourceKind,
+    buffer_store::BufferStoreEvent,
+    test_results::{TestResultStore, TestStatus},
+};
+use reporters::{TestCaseResult, TestFramework, TestOutcome};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use smallvec::SmallVec;
+use std::{cmp::Reverse, ops::Range, path::PathBuf, sync::Arc, time::Duration};
+use task::{ResolvedTask, RunnableTag, ShellBuilder, SpawnInTerminal, TaskContext, TaskVariables};
+use test_explorer_settings::TestExplorerSettings;
+use ui::{
+    Icon, IconButton, IconButtonShape, IconName, IconSize, Label, LabelSize, ListItem, Tab,
+    Tooltip, WithScrollbar, h_flex, prelude::*, v_flex,
+};
+use util::ResultExt;
+use workspace::{
+    Toast, Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::NotificationId,
+};
+
+const TEST_EXPLORER_KEY: &str = "TestExplorer";
+
+actions!(
+    test_explorer,
+    [
+        /// Toggles focus on the test explorer panel.
+        ToggleFocus,
+        /// Looks for tests in all files of the project.
+        DiscoverTests,
+        /// Runs all discovered tests.
+        RunAllTests,
+        /// Runs the tests that failed in their last run again.
+        RerunFailedTests,
+        /// Runs the selected test, or all tests in the selected file.
+        RunSelectedTest,
+        /// Starts a debug session for the selected test.
+        DebugSelectedTest,
+        /// Collapses the selected entry.
+        CollapseSelectedEntry,
+        /// Expands the selected entry.
+        ExpandSelectedEntry,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    TestExplorerSettings::register(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<TestExplorer>(window, cx);
+            })
+            .register_action(|workspace, _: &RunAllTests, window, cx| {
+                if let Some(panel) = workspace.panel::<TestExplorer>(cx) {
+                    panel.update(cx, |panel, cx| panel.run_all(window, cx));
+                }
+            })
+            .register_action(|workspace, _: &RerunFailedTests, window, cx| {
+                if let Some(panel) = workspace.panel::<TestExplorer>(cx) {
+                    panel.update(cx, |panel, cx| panel.rerun_failed(window, cx));
+                }
+            })
+            .register_action(|workspace, _: &DiscoverTests, _, cx| {
+                if let Some(panel) = workspace.panel::<TestExplorer>(cx) {
+                    panel.update(cx, |panel, cx| panel.discover_tests(cx));
+                }
+            });
+    })
+    .detach();
+}
+
+/// Identifies a test by the file it's in and the names of the tests enclosing it, outermost
+/// first.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestId {
+    path: ProjectPath,
+    segments: Arc<[SharedString]>,
+}
+
+impl TestId {
+    fn name(&self) -> &SharedString {
+        self.segments.last().expect("tests have a name")
+    }
+
+    fn contains(&self, other: &TestId) -> bool {
+        self.path == other.path && other.segments.starts_with(&self.segments)
+    }
+}
+
+struct TestFile {
+    buffer: Entity<Buffer>,
+    tests: Vec<DiscoveredTest>,
+}
+
+/// A runnable found by the language's runnable query whose tags mark it as a test.
+struct DiscoveredTest {
+    id: TestId,
+    depth: usize,
+    run_start: language::Anchor,
+    tags: SmallVec<[RunnableTag; 1]>,
+    language: Arc<Language>,
+    extra_captures: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug)]
+struct TestResult {
+    status: TestStatus,
+    duration: Option<Duration>,
+    message: Option<SharedString>,
+    failure_location: Option<(PathBuf, u32)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ExplorerEntry {
+    File(ProjectPath),
+    Test { path: ProjectPath, ix: usize },
+}
+
+/// Everything needed to resolve and run the task for one discovered test.
+struct TestJob {
+    id: TestId,
+    buffer: Entity<Buffer>,
+    run_start: language::Anchor,
+    tags: SmallVec<[RunnableTag; 1]>,
+    language: Arc<Language>,
+    extra_captures: HashMap<String, String>,
+}
+
+pub struct TestExplorer {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    test_results: Entity<TestResultStore>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    files: BTreeMap<ProjectPath, TestFile>,
+    results: HashMap<TestId, TestResult>,
+    collapsed_files: HashSet<ProjectPath>,
+    collapsed_tests: HashSet<TestId>,
+    entries: Vec<ExplorerEntry>,
+    selected_ix: Option<usize>,
+    buffer_subscriptions: HashMap<BufferId, Subscription>,
+    scans: HashMap<BufferId, Task<()>>,
+    /// Buffers opened by test discovery, kept alive until they are scanned.
+    discovered_buffers: HashMap<BufferId, Entity<Buffer>>,
+    discovery: Option<Task<()>>,
+    run: Option<Task<()>>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedTestExplorer {
+    width: Option<Pixels>,
+}
+
+impl TestExplorer {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(TEST_EXPLORER_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedTestExplorer>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let panel = Self::new(workspace, window, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|width| width.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new(|cx| {
+            let buffer_store = project.read(cx).buffer_store().clone();
+            let mut old_dock_position = TestExplorerSettings::get_global(cx).dock;
+            let subscriptions = vec![
+                cx.subscribe(&buffer_store, |this, _, event, cx| match event {
+                    BufferStoreEvent::BufferAdded(buffer) => this.track_buffer(buffer.clone(), cx),
+                    BufferStoreEvent::BufferChangedFilePath { buffer, .. } => {
+                        this.scan_buffer(buffer.clone(), cx)
+                    }
+                    BufferStoreEvent::BufferDropped(buffer_id) => {
+                        this.buffer_subscriptions.remove(buffer_id);
+                        this.scans.remove(buffer_id);
+                    }
+                    _ => {}
+                }),
+                cx.observe_global_in::<SettingsStore>(window, move |_, _, cx| {
+                    let new_dock_position = TestExplorerSettings::get_global(cx).dock;
+                    if new_dock_position != old_dock_position {
+                        old_dock_position = new_dock_position;
+                        cx.emit(PanelEvent::ZoomOut);
+                    }
+                    cx.notify();
+                }),
+            ];
+
+            let mut this = Self {
+                test_results: project.read(cx).test_results(),
+                project: project.clone(),
+                workspace: workspace_handle,
+                fs,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                width: None,
+                files: BTreeMap::default(),
+                results: HashMap::default(),
+                collapsed_files: HashSet::default(),
+                collapsed_tests: HashSet::default(),
+                entries: Vec::new(),
+                selected_ix: None,
+                buffer_subscriptions: HashMap::default(),
+                scans: HashMap::default(),
+                discovered_buffers: HashMap::default(),
+                discovery: None,
+                run: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            };
+            for buffer in project.read(cx).opened_buffers(cx) {
+                this.track_buffer(buffer, cx);
+            }
+            this
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        TEST_EXPLORER_KEY.into(),
+                        serde_json::to_string(&SerializedTestExplorer { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn track_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let subscription = cx.subscribe(&buffer, |this, buffer, event, cx| {
+            if let BufferEvent::Reparsed = event {
+                this.scan_buffer(buffer, cx);
+            }
+        });
+        self.buffer_subscriptions.insert(buffer_id, subscription);
+        if !buffer.read(cx).is_parsing() {
+            self.scan_buffer(buffer, cx);
+        }
+    }
+
+    /// Finds the tests in the buffer, replacing those previously found in its file.
+    fn scan_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(path) = buffer
+            .read(cx)
+            .file()
+            .map(|file| ProjectPath::from_file(file.as_ref(), cx))
+        else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let scan = cx.spawn(async move |this, cx| {
+            let tests = cx
+                .background_spawn({
+                    let path = path.clone();
+                    async move { discover_tests(&snapshot, &path) }
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.scans.remove(&buffer_id);
+                this.discovered_buffers.remove(&buffer_id);
+                if tests.is_empty() {
+                    if this.files.remove(&path).is_none() {
+                        return;
+                    }
+                    this.test_results.update(cx, |test_results, cx| {
+                        test_results.set_statuses(buffer_id, Vec::new(), cx)
+                    });
+                } else {
+                    this.files.insert(path.clone(), TestFile { buffer, tests });
+                }
+                this.publish_statuses(&path, cx);
+                this.update_entries(cx);
+            })
+            .ok();
+        });
+        self.scans.insert(buffer_id, scan);
+    }
+
+    /// Opens the project's files in languages with runnables, so that the tests in files that
+    /// haven't been opened show up too.
+    fn discover_tests(&mut self, cx: &mut Context<Self>) {
+        if self.discovery.is_some() {
+            return;
+        }
+        let project = self.project.clone();
+        let languages = project.read(cx).languages().clone();
+        let candidates = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .flat_map(|worktree| {
+                let snapshot = worktree.read(cx).snapshot();
+                let worktree_id = snapshot.id();
+                snapshot
+                    .files(false, 0)
+                    .filter(|entry| {
+                        languages
+                            .language_for_file_path(entry.path.as_std_path())
+                            .is_some()
+                    })
+                    .map(|entry| ProjectPath {
+                        worktree_id,
+                        path: entry.path.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|path| !self.files.contains_key(path))
+            .collect::<Vec<_>>();
+
+        self.discovery = Some(cx.spawn(async move |this, cx| {
+            for path in candidates {
+                let Ok(open_buffer) =
+                    project.update(cx, |project, cx| project.open_buffer(path, cx))
+                else {
+                    break;
+                };
+                let Some(buffer) = open_buffer.await.log_err() else {
+                    continue;
+                };
+                let updated = this.update(cx, |this, cx| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    if !this.buffer_subscriptions.contains_key(&buffer_id) {
+                        this.track_buffer(buffer.clone(), cx);
+                    }
+                    if this.scans.contains_key(&buffer_id) || buffer.read(cx).is_parsing() {
+                        this.discovered_buffers.insert(buffer_id, buffer);
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+            this.update(cx, |this, cx| {
+                this.discovery = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        let selected = self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .cloned();
+        self.entries.clear();
+        for (path, file) in &self.files {
+            self.entries.push(ExplorerEntry::File(path.clone()));
+            if self.collapsed_files.contains(path) {
+                continue;
+            }
+            let mut collapsed_depth = None;
+            for (ix, test) in file.tests.iter().enumerate() {
+                if let Some(depth) = collapsed_depth {
+                    if test.depth > depth {
+                        continue;
+                    }
+                    collapsed_depth = None;
+                }
+                if self.collapsed_tests.contains(&test.id) {
+                    collapsed_depth = Some(test.depth);
+                }
+                self.entries.push(ExplorerEntry::Test {
+                    path: path.clone(),
+                    ix,
+                });
+            }
+        }
+        self.selected_ix = selected
+            .and_then(|selected| self.entries.iter().position(|entry| *entry == selected))
+            .or_else(|| {
+                self.selected_ix
+                    .map(|ix| ix.min(self.entries.len().saturating_sub(1)))
+                    .filter(|_| !self.entries.is_empty())
+            });
+        cx.notify();
+    }
+
+    fn test(&self, path: &ProjectPath, ix: usize) -> Option<&DiscoveredTest> {
+        self.files.get(path)?.tests.get(ix)
+    }
+
+    /// Indices of the tests nested in the test at `ix`.
+    fn descendants(file: &TestFile, ix: usize) -> Range<usize> {
+        let depth = file.tests[ix].depth;
+        let end = file.tests[ix + 1..]
+            .iter()
+            .position(|test| test.depth <= depth)
+            .map_or(file.tests.len(), |offset| ix + 1 + offset);
+        ix + 1..end
+    }
+
+    /// The status of a test, taking into account the tests nested in it.
+    fn test_status(&self, path: &ProjectPath, ix: usize) -> Option<TestStatus> {
+        let file = self.files.get(path)?;
+        let own_status = self
+            .results
+            .get(&file.tests[ix].id)
+            .map(|result| result.status);
+        Self::descendants(file, ix)
+            .filter_map(|ix| self.results.get(&file.tests[ix].id))
+            .map(|result| result.status)
+            .chain(own_status)
+            .reduce(TestStatus::merge)
+    }
+
+    fn file_status(&self, path: &ProjectPath) -> Option<TestStatus> {
+        self.files
+            .get(path)?
+            .tests
+            .iter()
+            .filter_map(|test| self.results.get(&test.id))
+            .map(|result| result.status)
+            .reduce(TestStatus::merge)
+    }
+
+    /// Shows the statuses of the file's tests in the gutter of its editors.
+    fn publish_statuses(&self, path: &ProjectPath, cx: &mut Context<Self>) {
+        let Some(file) = self.files.get(path) else {
+            return;
+        };
+        let statuses = (0..file.tests.len())
+            .filter_map(|ix| Some((file.tests[ix].run_start, self.test_status(path, ix)?)))
+            .collect();
+        let buffer_id = file.buffer.read(cx).remote_id();
+        self.test_results.update(cx, |test_results, cx| {
+            test_results.set_statuses(buffer_id, statuses, cx)
+        });
+    }
+
+    fn job(&self, id: &TestId) -> Option<TestJob> {
+        let file = self.files.get(&id.path)?;
+        let test = file.tests.iter().find(|test| test.id == *id)?;
+        Some(TestJob {
+            id: id.clone(),
+            buffer: file.buffer.clone(),
+            run_start: test.run_start,
+            tags: test.tags.clone(),
+            language: test.language.clone(),
+            extra_captures: test.extra_captures.clone(),
+        })
+    }
+
+    fn run_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let ids = self
+            .files
+            .values()
+            .flat_map(|file| file.tests.iter().filter(|test| test.depth == 0))
+            .map(|test| test.id.clone())
+            .collect();
+        self.run_tests(ids, window, cx);
+    }
+
+    fn rerun_failed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let failed = self
+            .results
+            .iter()
+            .filter(|(id, result)| result.status == TestStatus::Failed && self.job(id).is_some())
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        // Running a test also runs the tests nested in it.
+        let ids = failed
+            .iter()
+            .filter(|id| {
+                !failed
+                    .iter()
+                    .any(|other| other != *id && other.contains(id))
+            })
+            .cloned()
+            .collect();
+        self.run_tests(ids, window, cx);
+    }
+
+    fn run_selected(&mut self, _: &RunSelectedTest, window: &mut Window, cx: &mut Context<Self>) {
+        match self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .cloned()
+        {
+            Some(ExplorerEntry::File(path)) => self.run_file(&path, window, cx),
+            Some(ExplorerEntry::Test { path, ix }) => {
+                if let Some(test) = self.test(&path, ix) {
+                    self.run_tests(vec![test.id.clone()], window, cx);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn run_file(&mut self, path: &ProjectPath, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(file) = self.files.get(path) else {
+            return;
+        };
+        let ids = file
+            .tests
+            .iter()
+            .filter(|test| test.depth == 0)
+            .map(|test| test.id.clone())
+            .collect();
+        self.run_tests(ids, window, cx);
+    }
+
+    fn run_tests(&mut self, ids: Vec<TestId>, window: &mut Window, cx: &mut Context<Self>) {
+        if self.run.is_some() || ids.is_empty() {
+            return;
+        }
+        if !self.project.read(cx).is_local() {
+            self.show_toast(
+                "Running tests with results is only supported in local projects",
+                cx,
+            );
+            return;
+        }
+        let jobs = ids.iter().filter_map(|id| self.job(id)).collect::<Vec<_>>();
+        for job in &jobs {
+            self.set_running(&job.id, cx);
+        }
+        self.update_entries(cx);
+
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        let fs = self.fs.clone();
+        self.run = Some(cx.spawn_in(window, async move |this, cx| {
+            for job in jobs {
+                let Some((kind, resolved_task, _)) = resolve_test_task(&project, &job, cx).await
+                else {
+                    this.update(cx, |this, cx| {
+                        this.finish_job(&job.id, Err(anyhow::anyhow!("No task runs this test")), cx)
+                    })
+                    .ok();
+                    continue;
+                };
+                let spawn = resolved_task.resolved.clone();
+                let framework = spawn
+                    .command
+                    .as_deref()
+                    .and_then(|command| TestFramework::detect(command, &spawn.args));
+                let Some(framework) = framework else {
+                    // Without a reporter we can't tell how the test went, but can still run it.
+                    workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.schedule_resolved_task(kind, resolved_task, false, window, cx)
+                        })
+                        .ok();
+                    this.update(cx, |this, cx| this.clear_running(&job.id, cx))
+                        .ok();
+                    continue;
+                };
+                let results = cx
+                    .background_spawn(run_with_reporter(fs.clone(), spawn, framework))
+                    .await;
+                let updated = this.update(cx, |this, cx| {
+                    let results = results.map(|results| (framework, results));
+                    this.finish_job(&job.id, results, cx)
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            this.update(cx, |this, cx| {
+                this.run = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn set_running(&mut self, id: &TestId, cx: &mut Context<Self>) {
+        let Some(file) = self.files.get(&id.path) else {
+            return;
+        };
+        for test in file.tests.iter().filter(|test| id.contains(&test.id)) {
+            self.results.insert(
+                test.id.clone(),
+                TestResult {
+                    status: TestStatus::Running,
+                    duration: None,
+                    message: None,
+                    failure_location: None,
+                },
+            );
+        }
+        self.publish_statuses(&id.path, cx);
+    }
+
+    fn clear_running(&mut self, id: &TestId, cx: &mut Context<Self>) {
+        self.results.retain(|test_id, result| {
+            !(id.contains(test_id) && result.status == TestStatus::Running)
+        });
+        self.publish_statuses(&id.path, cx);
+        self.update_entries(cx);
+    }
+
+    /// Records the results of running the test `id`, matching the reported test cases with the
+    /// tests nested in it.
+    fn finish_job(
+        &mut self,
+        id: &TestId,
+        results: Result<(TestFramework, Vec<TestCaseResult>)>,
+        cx: &mut Context<Self>,
+    ) {
+        let (framework, results) = match results {
+            Ok(results) => results,
+            Err(error) => {
+                self.clear_running(id, cx);
+                self.results.insert(
+                    id.clone(),
+                    TestResult {
+                        status: TestStatus::Failed,
+                        duration: None,
+                        message: Some(format!("{error:#}").into()),
+                        failure_location: None,
+                    },
+                );
+                self.publish_statuses(&id.path, cx);
+                self.update_entries(cx);
+                return;
+            }
+        };
+        let Some(file) = self.files.get(&id.path) else {
+            return;
+        };
+        let candidates = file
+            .tests
+            .iter()
+            .filter(|test| id.contains(&test.id))
+            .map(|test| test.id.clone())
+            .collect::<Vec<_>>();
+        let mut new_results = HashMap::<TestId, TestResult>::default();
+        for result in results {
+            let Some(test_id) = candidates
+                .iter()
+                .filter(|test_id| result_matches(framework, test_id, &result))
+                .max_by_key(|test_id| test_id.segments.len())
+            else {
+                continue;
+            };
+            let status = match result.outcome {
+                TestOutcome::Passed => TestStatus::Passed,
+                TestOutcome::Failed => TestStatus::Failed,
+                TestOutcome::Skipped => TestStatus::Skipped,
+            };
+            let new_result = TestResult {
+                status,
+                duration: result.duration,
+                message: result.message.map(SharedString::from),
+                failure_location: result.failure_location,
+            };
+            // Parametrized tests report one result per case.
+            match new_results.get_mut(test_id) {
+                Some(existing) if existing.status.merge(status) == existing.status => {}
+                Some(existing) => *existing = new_result,
+                None => {
+                    new_results.insert(test_id.clone(), new_result);
+                }
+            }
+        }
+        for test_id in &candidates {
+            match new_results.remove(test_id) {
+                Some(result) => {
+                    self.results.insert(test_id.clone(), result);
+                }
+                // Groups that aren't reported on their own take the status of their tests.
+                None => {
+                    self.results.remove(test_id);
+                }
+            }
+        }
+        self.publish_statuses(&id.path, cx);
+        self.update_entries(cx);
+    }
+
+    fn debug_selected(
+        &mut self,
+        _: &DebugSelectedTest,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ExplorerEntry::Test { path, ix }) = self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .cloned()
+            && let Some(test) = self.test(&path, ix)
+        {
+            let id = test.id.clone();
+            self.debug_test(&id, window, cx);
+        }
+    }
+
+    fn debug_test(&mut self, id: &TestId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(job) = self.job(id) else {
+            return;
+        };
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let (_, resolved_task, task_context) = resolve_test_task(&project, &job, cx)
+                .await
+                .context("no task runs this test")?;
+            let scenario = project.update(cx, |project, cx| {
+                let buffer = job.buffer.read(cx);
+                let adapter = language_settings(Some(job.language.name()), buffer.file(), cx)
+                    .debuggers
+                    .first()
+                    .map(|debugger| SharedString::from(debugger.clone()))
+                    .or_else(|| job.language.config().debuggers.first().cloned())?;
+                Some(project.dap_store().update(cx, |dap_store, cx| {
+                    dap_store.debug_scenario_for_build_task(
+                        resolved_task.original_task().clone(),
+                        adapter.into(),
+                        resolved_task.resolved_label.clone().into(),
+                        cx,
+                    )
+                }))
+            })?;
+            let scenario = match scenario {
+                Some(scenario) => scenario.await,
+                None => None,
+            };
+            let Some(scenario) = scenario else {
+                this.update(cx, |this, cx| {
+                    this.show_toast(format!("Don't know how to debug {}", job.id.name()), cx)
+                })?;
+                return anyhow::Ok(());
+            };
+            let worktree_id = job.id.path.worktree_id;
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.start_debug_session(
+                    scenario,
+                    task_context,
+                    Some(job.buffer.clone()),
+                    Some(worktree_id),
+                    window,
+                    cx,
+                )
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn show_toast(&self, message: impl Into<SharedString>, cx: &mut Context<Self>) {
+        let message = message.into();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<TestExplorer>(), message),
+                    cx,
+                )
+            })
+            .ok();
+    }
+
+    /// Opens the location the selected test failed at, or where it is defined.
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix {
+            self.open_entry(ix, window, cx);
+        }
+    }
+
+    fn open_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix).cloned() else {
+            return;
+        };
+        let (path, ix) = match entry {
+            ExplorerEntry::File(path) => {
+                self.workspace
+                    .update(cx, |workspace, cx| {
+                        workspace
+                            .open_path(path, None, true, window, cx)
+                            .detach_and_log_err(cx)
+                    })
+                    .ok();
+                return;
+            }
+            ExplorerEntry::Test { path, ix } => (path, ix),
+        };
+        let Some(test) = self.test(&path, ix) else {
+            return;
+        };
+        let failure_location = self
+            .results
+            .get(&test.id)
+            .filter(|result| result.status == TestStatus::Failed)
+            .and_then(|result| result.failure_location.clone());
+        let open_item = if let Some((abs_path, row)) = failure_location {
+            self.workspace.update(cx, |workspace, cx| {
+                let open_item = workspace.open_abs_path(abs_path, Default::default(), window, cx);
+                (open_item, Point::new(row, 0))
+            })
+        } else {
+            let point = test
+                .run_start
+                .to_point(&self.files[&path].buffer.read(cx).snapshot());
+            self.workspace.update(cx, |workspace, cx| {
+                let open_item = workspace.open_path(path, None, true, window, cx);
+                (open_item, point)
+            })
+        };
+        let Ok((open_item, point)) = open_item else {
+            return;
+        };
+        cx.spawn_in(window, async move |_, cx| {
+            let item = open_item.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update_in(cx, |editor, window, cx| {
+                    editor.change_selections(
+                        SelectionEffects::scroll(Autoscroll::center()),
+                        window,
+                        cx,
+                        |selections| selections.select_ranges([point..point]),
+                    );
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1) % self.entries.len()),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None | Some(0) => Some(self.entries.len() - 1),
+            Some(ix) => Some(ix - 1),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_ix {
+            self.set_collapsed(ix, true, cx);
+        }
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_ix {
+            self.set_collapsed(ix, false, cx);
+        }
+    }
+
+    fn set_collapsed(&mut self, ix: usize, collapsed: bool, cx: &mut Context<Self>) {
+        match self.entries.get(ix).cloned() {
+            Some(ExplorerEntry::File(path)) => {
+                if collapsed {
+                    self.collapsed_files.insert(path);
+                } else {
+                    self.collapsed_files.remove(&path);
+                }
+            }
+            Some(ExplorerEntry::Test { path, ix }) => {
+                let Some(test) = self.test(&path, ix) else {
+                    return;
+                };
+                let id = test.id.clone();
+                if collapsed {
+                    self.collapsed_tests.insert(id);
+                } else {
+                    self.collapsed_tests.remove(&id);
+                }
+            }
+            None => return,
+        }
+        self.update_entries(cx);
+    }
+
+    fn toggle_collapsed(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let collapsed = match self.entries.get(ix) {
+            Some(ExplorerEntry::File(path)) => self.collapsed_files.contains(path),
+            Some(ExplorerEntry::Test { path, ix }) => self
+                .test(path, *ix)
+                .is_some_and(|test| self.collapsed_tests.contains(&test.id)),
+            None => return,
+        };
+        self.set_collapsed(ix, !collapsed, cx);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let selected = self.selected_ix == Some(ix);
+        let (label, depth, status, has_children, collapsed, result) = match entry {
+            ExplorerEntry::File(path) => {
+                let label = path.path.display(self.project.read(cx).path_style(cx));
+                (
+                    SharedString::from(label.into_owned()),
+                    0,
+                    self.file_status(path),
+                    true,
+                    self.collapsed_files.contains(path),
+                    None,
+                )
+            }
+            ExplorerEntry::Test { path, ix } => {
+                let file = &self.files[path];
+                let test = &file.tests[*ix];
+                (
+                    test.id.name().clone(),
+                    test.depth + 1,
+                    self.test_status(path, *ix),
+                    !Self::descendants(file, *ix).is_empty(),
+                    self.collapsed_tests.contains(&test.id),
+                    self.results.get(&test.id),
+                )
+            }
+        };
+        let is_test = matches!(entry, ExplorerEntry::Test { .. });
+        let message = result.and_then(|result| result.message.clone());
+        let duration = result
+            .and_then(|result| result.duration)
+            .map(|duration| format!("{}ms", duration.as_millis()));
+
+        let run_button = IconButton::new(("run-test", ix), IconName::PlayOutlined)
+            .shape(IconButtonShape::Square)
+            .icon_size(IconSize::XSmall)
+            .tooltip(Tooltip::text(if is_test { "Run Test" } else { "Run File" }))
+            .disabled(self.run.is_some())
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.select_ix(Some(ix), cx);
+                this.run_selected(&RunSelectedTest, window, cx);
+            }));
+        let debug_button = is_test.then(|| {
+            IconButton::new(("debug-test", ix), IconName::Debug)
+                .shape(IconButtonShape::Square)
+                .icon_size(IconSize::XSmall)
+                .tooltip(Tooltip::text("Debug Test"))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.select_ix(Some(ix), cx);
+                    this.debug_selected(&DebugSelectedTest, window, cx);
+                }))
+        });
+
+        ListItem::new(("test-explorer-entry", ix))
+            .indent_level(depth)
+            .indent_step_size(px(12.))
+            .toggle_state(selected)
+            .toggle(has_children.then_some(!collapsed))
+            .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_collapsed(ix, cx)))
+            .start_slot(status_icon(status))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(label).size(LabelSize::Small).truncate())
+                    .when_some(duration, |this, duration| {
+                        this.child(
+                            Label::new(duration)
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .end_hover_slot(h_flex().gap_0p5().child(run_button).children(debug_button))
+            .when_some(message, |this, message| {
+                this.tooltip(Tooltip::text(message))
+            })
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.select_ix(Some(ix), cx);
+                if event.click_count() > 1 || is_test {
+                    this.open_entry(ix, window, cx);
+                }
+            }))
+            .into_any_element()
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (mut passed, mut failed) = (0, 0);
+        for result in self.results.values() {
+            match result.status {
+                TestStatus::Passed => passed += 1,
+                TestStatus::Failed => failed += 1,
+                TestStatus::Running | TestStatus::Skipped => {}
+            }
+        }
+        let summary = if self.run.is_some() {
+            "Running…".to_string()
+        } else if self.discovery.is_some() {
+            "Discovering tests…".to_string()
+        } else if passed + failed > 0 {
+            format!("{passed} passed, {failed} failed")
+        } else {
+            String::new()
+        };
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .h(Tab::container_height(cx))
+            .px_2()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(summary)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        IconButton::new("discover-tests", IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.discovery.is_some())
+                            .tooltip(Tooltip::text("Discover Tests"))
+                            .on_click(cx.listener(|this, _, _, cx| this.discover_tests(cx))),
+                    )
+                    .child(
+                        IconButton::new("rerun-failed-tests", IconName::Rerun)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.run.is_some() || failed == 0)
+                            .tooltip({
+                                let focus_handle = focus_handle.clone();
+                                move |window, cx| {
+                                    Tooltip::for_action_in(
+                                        "Rerun Failed Tests",
+                                        &RerunFailedTests,
+                                        &focus_handle,
+                                        window,
+                                        cx,
+                                    )
+                                }
+                            })
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.rerun_failed(window, cx)),
+                            ),
+                    )
+                    .child(
+                        IconButton::new("run-all-tests", IconName::PlayOutlined)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.run.is_some() || self.files.is_empty())
+                            .tooltip(move |window, cx| {
+                                Tooltip::for_action_in(
+                                    "Run All Tests",
+                                    &RunAllTests,
+                                    &focus_handle,
+                                    window,
+                                    cx,
+                                )
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| this.run_all(window, cx))),
+                    ),
+            )
+    }
+}
+
+fn status_icon(status: Option<TestStatus>) -> Icon {
+    let (icon, color) = match status {
+        None => (IconName::Circle, Color::Muted),
+        Some(TestStatus::Running) => (IconName::LoadCircle, Color::Accent),
+        Some(TestStatus::Passed) => (IconName::Check, Color::Success),
+        Some(TestStatus::Failed) => (IconName::XCircle, Color::Error),
+        Some(TestStatus::Skipped) => (IconName::Dash, Color::Muted),
+    };
+    Icon::new(icon).size(IconSize::Small).color(color)
+}
+
+fn is_test_tag(tag: &str) -> bool {
+    // Doc tests are tagged on the comment containing them, so they have no name of their own.
+    tag.contains("test") && !tag.ends_with("doc-test")
+}
+
+/// Finds the tests in a buffer, in order, each nested under the tests whose scope contains it.
+fn discover_tests(snapshot: &BufferSnapshot, path: &ProjectPath) -> Vec<DiscoveredTest> {
+    let mut runnables = snapshot
+        .runnable_ranges(0..snapshot.len())
+        .filter(|runnable| {
+            runnable
+                .runnable
+                .tags
+                .iter()
+                .any(|RunnableTag(tag)| is_test_tag(tag))
+        })
+        .map(|runnable| {
+            let name = snapshot
+                .text_for_range(runnable.run_range.clone())
+                .collect::<String>();
+            let name = name.trim_matches(['"', '\'', '`']).to_string();
+            // Runnable queries rarely capture the body of a test module or class, so use the
+            // outline item the runnable names when there is one.
+            let item_range = snapshot
+                .symbols_containing(runnable.run_range.end, None)
+                .into_iter()
+                .rev()
+                .find(|item| {
+                    item.name_ranges
+                        .iter()
+                        .any(|range| item.text.get(range.clone()) == Some(name.as_str()))
+                })
+                .map(|item| item.range.to_offset(snapshot));
+            let scope = match item_range {
+                Some(item_range) => {
+                    item_range.start.min(runnable.full_range.start)
+                        ..item_range.end.max(runnable.full_range.end)
+                }
+                None => runnable.full_range.clone(),
+            };
+            (scope, name, runnable)
+        })
+        .collect::<Vec<_>>();
+    runnables.sort_by_key(|(scope, _, _)| (scope.start, Reverse(scope.end)));
+
+    let mut tests = Vec::<DiscoveredTest>::new();
+    let mut ancestors = Vec::<(Range<usize>, Arc<[SharedString]>)>::new();
+    let mut last_run_range = None;
+    for (scope, name, runnable) in runnables {
+        // Several patterns of a query may match the same runnable.
+        if last_run_range.as_ref() == Some(&runnable.run_range) {
+            continue;
+        }
+        last_run_range = Some(runnable.run_range.clone());
+        while ancestors
+            .last()
+            .is_some_and(|(parent, _)| parent.end < scope.end)
+        {
+            ancestors.pop();
+        }
+        let mut segments = ancestors
+            .last()
+            .map(|(_, segments)| segments.to_vec())
+            .unwrap_or_default();
+        segments.push(name.into());
+        let segments = Arc::<[SharedString]>::from(segments);
+        tests.push(DiscoveredTest {
+            id: TestId {
+                path: path.clone(),
+                segments: segments.clone(),
+            },
+            depth: ancestors.len(),
+            run_start: snapshot.anchor_before(runnable.run_range.start),
+            tags: runnable.runnable.tags,
+            language: runnable.runnable.language,
+            extra_captures: runnable.extra_captures,
+        });
+        ancestors.push((scope, segments));
+    }
+    tests
+}
+
+/// Whether a reported test case is the test `id` or one of the cases of a parametrized test:
+/// reporters qualify names with module paths and the like, so only the innermost segments
+/// need to match.
+fn result_matches(framework: TestFramework, id: &TestId, result: &TestCaseResult) -> bool {
+    result.segments.len() >= id.segments.len()
+        && result
+            .segments
+            .iter()
+            .rev()
+            .zip(id.segments.iter().rev())
+            .all(|(reported, name)| *reported == framework.normalize_name(name))
+}
+
+/// Resolves the task that runs a test, the same way the editor does for its runnable
+/// indicators: the task must be tagged with one of the runnable's tags, and tasks defined
+/// closer to the project take precedence.
+async fn resolve_test_task(
+    project: &Entity<Project>,
+    job: &TestJob,
+    cx: &mut AsyncWindowContext,
+) -> Option<(TaskSourceKind, ResolvedTask, TaskContext)> {
+    let (inventory, file, worktree_id) = project
+        .read_with(cx, |project, cx| {
+            let file = job.buffer.read(cx).file().cloned();
+            let worktree_id = file.as_ref().map(|file| file.worktree_id(cx));
+            (
+                project.task_store().read(cx).task_inventory().cloned(),
+                file,
+                worktree_id,
+            )
+        })
+        .ok()?;
+    let templates = inventory?
+        .read_with(cx, |inventory, cx| {
+            inventory.list_tasks(file, Some(job.language.clone()), worktree_id, cx)
+        })
+        .ok()?
+        .await;
+    let (kind, template) = templates
+        .into_iter()
+        .filter(|(_, template)| {
+            template.tags.iter().any(|tag| {
+                job.tags
+                    .iter()
+                    .any(|RunnableTag(runnable_tag)| runnable_tag == tag)
+            })
+        })
+        .min_by(|(a, _), (b, _)| a.cmp(b))?;
+
+    let mut variables = TaskVariables::default();
+    for (name, value) in &job.extra_captures {
+        variables.insert(
+            task::VariableName::Custom(name.clone().into()),
+            value.clone(),
+        );
+    }
+    let location = Location {
+        buffer: job.buffer.clone(),
+        range: job.run_start..job.run_start,
+    };
+    let task_context = project
+        .update(cx, |project, cx| {
+            project.task_store().update(cx, |task_store, cx| {
+                task_store.task_context_for_location(variables, location, cx)
+            })
+        })
+        .ok()?
+        .await?;
+    let resolved_task = template.resolve_task(&kind.to_id_base(), &task_context)?;
+    Some((kind, resolved_task, task_context))
+}
+
+/// Runs a resolved test task with the framework's reporter enabled and reads its results.
+async fn run_with_reporter(
+    fs: Arc<dyn Fs>,
+    spawn: SpawnInTerminal,
+    framework: TestFramework,
+) -> Result<Vec<TestCaseResult>> {
+    let command = spawn.command.context("test task has no command")?;
+    let report_dir = tempfile::tempdir()?;
+    let report_path = report_dir.path().join("report");
+    let mut args = spawn.args;
+    framework.prepare_command(&mut args, &report_path)?;
+
+    let (program, args) = ShellBuilder::new(&spawn.shell)
+        .non_interactive()
+        .redirect_stdin_to_dev_null()
+        .build(Some(command), &args);
+    let mut command = util::command::new_smol_command(program);
+    // Dropping the run, e.g. by closing the project, stops the tests.
+    command.args(args).envs(spawn.env).kill_on_drop(true);
+    if let Some(cwd) = &spawn.cwd {
+        command.current_dir(cwd);
+    }
+    let output = command.output().await.context("running tests")?;
+
+    let report = if framework.reports_to_file() {
+        fs.load(&report_path).await.ok()
+    } else {
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let mut results = match report {
+        Some(report) => framework.parse(&report)?,
+        None => Vec::new(),
+    };
+    if results.is_empty() && !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lines = stderr.lines().collect::<Vec<_>>();
+        anyhow::bail!("{}", lines[lines.len().saturating_sub(20)..].join("\n"));
+    }
+
+    // Test runners print paths relative to where they were started, or to the root of the
+    // package being tested, which may be above it.
+    if let Some(cwd) = &spawn.cwd {
+        for result in &mut results {
+            if let Some((path, _)) = &mut result.failure_location
+                && path.is_relative()
+            {
+                for dir in cwd.ancestors() {
+                    let candidate = dir.join(&*path);
+                    if fs.is_file(&candidate).await {
+                        *path = candidate;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Ok(results)
+}
+
+impl Render for TestExplorer {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::new_with_defaults();
+        key_context.add("TestExplorer");
+
+        v_flex()
+            .id("test-explorer")
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::run_selected))
+            .on_action(cx.listener(Self::debug_selected))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(|this, _: &RunAllTests, window, cx| this.run_all(window, cx)))
+            .on_action(cx.listener(|this, _: &RerunFailedTests, window, cx| {
+                this.rerun_failed(window, cx)
+            }))
+            .child(self.render_header(cx))
+            .map(|this| {
+                if self.entries.is_empty() {
+                    this.child(
+                        v_flex().p_4().gap_2().child(
+                            Label::new(
+                                "No tests found. Open a file with tests, or discover the tests in the project.",
+                            )
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        div()
+                            .flex_1()
+                            .size_full()
+                            .child(
+                                uniform_list(
+                                    "test-explorer-entries",
+                                    self.entries.len(),
+                                    cx.processor(|this, range: Range<usize>, _, cx| {
+                                        range.map(|ix| this.render_entry(ix, cx)).collect()
+                                    }),
+                                )
+                                .track_scroll(self.scroll_handle.clone())
+                                .size_full(),
+                            )
+                            .vertical_scrollbar_for(self.scroll_handle.clone(), window, cx),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for TestExplorer {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for TestExplorer {}
+
+impl Panel for TestExplorer {
+    fn persistent_name() -> &'static str {
+        "TestExplorer"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        TestExplorerSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.test_explorer.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| TestExplorerSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        TestExplorerSettings::get_global(cx)
+            .button
+            .then_some(IconName::ListTodo)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Test Explorer")
+    }
+
+    fn icon_label(&self, _: &Window, _: &App) -> Option<String> {
+        let failed = self
+            .results
+            .values()
+            .filter(|result| result.status == TestStatus::Failed)
+            .count();
+        (failed > 0).then(|| failed.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use pretty_assertions::assert_eq;
+    use unindent::Unindent as _;
+    use util::rel_path::rel_path;
+
+    #[gpui::test]
+    fn test_discover_nested_tests(cx: &mut TestAppContext) {
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["rs".to_string()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap()
+            .with_runnable_query(
+                r#"
+                (
+                    (attribute_item (attribute ((identifier) @_attribute) arguments: ((token_tree (identifier) @_test) (#eq? @_test "test")))) (#eq? @_attribute "cfg")
+                    .
+                    (mod_item name: (_) @run)
+                    (#set! tag rust-mod-test)
+                )
+                (
+                    ((attribute_item (attribute ((identifier) @_attribute) (#eq? @_attribute "test"))) @_start
+                    .
+                    (function_item name: (_) @run body: _) @_end)
+                    (#set! tag rust-test)
+                )
+                "#,
+            )
+            .unwrap(),
+        );
+        let text = r#"
+            fn main() {}
+
+            #[test]
+            fn top_level() {}
+
+            #[cfg(test)]
+            mod tests {
+                fn helper() {}
+
+                #[test]
+                fn first() {}
+
+                #[test]
+                fn second() {}
+            }
+        "#
+        .unindent();
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+        cx.executor().run_until_parked();
+
+        let path = ProjectPath {
+            worktree_id: settings::WorktreeId::from_usize(0),
+            path: rel_path("src/lib.rs").into(),
+        };
+        let tests = buffer.read_with(cx, |buffer, _| discover_tests(&buffer.snapshot(), &path));
+        assert_eq!(
+            tests
+                .iter()
+                .map(|test| (test.depth, test.id.segments.join("::")))
+                .collect::<Vec<_>>(),
+            [
+                (0, "top_level".to_string()),
+                (0, "tests".to_string()),
+                (1, "tests::first".to_string()),
+                (1, "tests::second".to_string()),
+            ]
+        );
+
+        let result = |segments: &[&str]| TestCaseResult {
+            segments: segments.iter().map(|segment| segment.to_string()).collect(),
+            file: None,
+            outcome: TestOutcome::Passed,
+            duration: None,
+            message: None,
+            failure_location: None,
+        };
+        assert!(result_matches(
+            TestFramework::Cargo,
+            &tests[2].id,
+            &result(&["my_crate", "tests", "first"])
+        ));
+        assert!(!result_matches(
+            TestFramework::Cargo,
+            &tests[2].id,
+            &result(&["other", "first"])
+        ));
+        assert!(tests[1].id.contains(&tests[3].id));
+        assert!(!tests[0].id.contains(&tests[3].id));
+    }
+}
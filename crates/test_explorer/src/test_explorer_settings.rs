@@ -0,0 +1,22 @@
+use gpui::Pixels;
+use settings::Settings;
+use ui::px;
+use workspace::dock::DockPosition;
+
+#[derive(Debug)]
+pub struct TestExplorerSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for TestExplorerSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.test_explorer.as_ref().unwrap();
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+        }
+    }
+}
@@ -113,6 +113,12 @@ impl ThemeColors {
             editor_active_wrap_guide: neutral().light_alpha().step_8(),
             editor_indent_guide: neutral().light_alpha().step_5(),
             editor_indent_guide_active: neutral().light_alpha().step_6(),
+            editor_rainbow_bracket_1: yellow().light().step_10(),
+            editor_rainbow_bracket_2: purple().light().step_10(),
+            editor_rainbow_bracket_3: blue().light().step_10(),
+            editor_rainbow_bracket_4: green().light().step_10(),
+            editor_rainbow_bracket_5: orange().light().step_10(),
+            editor_rainbow_bracket_6: pink().light().step_10(),
             editor_document_highlight_read_background: neutral().light_alpha().step_3(),
             editor_document_highlight_write_background: neutral().light_alpha().step_4(),
             editor_document_highlight_bracket_background: green().light_alpha().step_5(),
@@ -239,6 +245,12 @@ impl ThemeColors {
             editor_active_wrap_guide: neutral().dark_alpha().step_4(),
             editor_indent_guide: neutral().dark_alpha().step_4(),
             editor_indent_guide_active: neutral().dark_alpha().step_6(),
+            editor_rainbow_bracket_1: yellow().dark().step_11(),
+            editor_rainbow_bracket_2: purple().dark().step_11(),
+            editor_rainbow_bracket_3: blue().dark().step_11(),
+            editor_rainbow_bracket_4: green().dark().step_11(),
+            editor_rainbow_bracket_5: orange().dark().step_11(),
+            editor_rainbow_bracket_6: pink().dark().step_11(),
             editor_document_highlight_read_background: neutral().dark_alpha().step_4(),
             editor_document_highlight_write_background: neutral().dark_alpha().step_4(),
             editor_document_highlight_bracket_background: green().dark_alpha().step_6(),
@@ -160,6 +160,12 @@ pub(crate) fn zed_default_dark() -> Theme {
                 editor_active_wrap_guide: hsla(228. / 360., 8. / 100., 25. / 100., 1.),
                 editor_indent_guide: hsla(228. / 360., 8. / 100., 25. / 100., 1.),
                 editor_indent_guide_active: hsla(225. / 360., 13. / 100., 12. / 100., 1.),
+                editor_rainbow_bracket_1: hsla(45. / 360., 90. / 100., 60. / 100., 1.),
+                editor_rainbow_bracket_2: hsla(286. / 360., 60. / 100., 70. / 100., 1.),
+                editor_rainbow_bracket_3: hsla(207. / 360., 80. / 100., 66. / 100., 1.),
+                editor_rainbow_bracket_4: hsla(95. / 360., 40. / 100., 60. / 100., 1.),
+                editor_rainbow_bracket_5: hsla(29. / 360., 70. / 100., 60. / 100., 1.),
+                editor_rainbow_bracket_6: hsla(330. / 360., 65. / 100., 70. / 100., 1.),
                 editor_document_highlight_read_background: hsla(
                     207.8 / 360.,
                     81. / 100.,
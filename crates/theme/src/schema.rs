@@ -577,6 +577,30 @@ pub fn theme_colors_refinement(
             .editor_indent_guide_active
             .as_ref()
             .and_then(|color| try_parse_color(color).ok()),
+        editor_rainbow_bracket_1: this
+            .editor_rainbow_bracket_1
+            .as_ref()
+            .and_then(|color| try_parse_color(color).ok()),
+        editor_rainbow_bracket_2: this
+            .editor_rainbow_bracket_2
+            .as_ref()
+            .and_then(|color| try_parse_color(color).ok()),
+        editor_rainbow_bracket_3: this
+            .editor_rainbow_bracket_3
+            .as_ref()
+            .and_then(|color| try_parse_color(color).ok()),
+        editor_rainbow_bracket_4: this
+            .editor_rainbow_bracket_4
+            .as_ref()
+            .and_then(|color| try_parse_color(color).ok()),
+        editor_rainbow_bracket_5: this
+            .editor_rainbow_bracket_5
+            .as_ref()
+            .and_then(|color| try_parse_color(color).ok()),
+        editor_rainbow_bracket_6: this
+            .editor_rainbow_bracket_6
+            .as_ref()
+            .and_then(|color| try_parse_color(color).ok()),
         editor_document_highlight_read_background,
         editor_document_highlight_write_background: this
             .editor_document_highlight_write_background
@@ -187,6 +187,14 @@ pub struct ThemeColors {
     pub editor_active_wrap_guide: Hsla,
     pub editor_indent_guide: Hsla,
     pub editor_indent_guide_active: Hsla,
+    /// Text Color. Used to color brackets by their nesting depth, cycling through
+    /// the colors for deeper levels.
+    pub editor_rainbow_bracket_1: Hsla,
+    pub editor_rainbow_bracket_2: Hsla,
+    pub editor_rainbow_bracket_3: Hsla,
+    pub editor_rainbow_bracket_4: Hsla,
+    pub editor_rainbow_bracket_5: Hsla,
+    pub editor_rainbow_bracket_6: Hsla,
     /// Read-access of a symbol, like reading a variable.
     ///
     /// A document highlight is a range inside a text document which deserves
@@ -362,6 +370,12 @@ pub enum ThemeColorField {
     EditorActiveWrapGuide,
     EditorIndentGuide,
     EditorIndentGuideActive,
+    EditorRainbowBracket1,
+    EditorRainbowBracket2,
+    EditorRainbowBracket3,
+    EditorRainbowBracket4,
+    EditorRainbowBracket5,
+    EditorRainbowBracket6,
     EditorDocumentHighlightReadBackground,
     EditorDocumentHighlightWriteBackground,
     EditorDocumentHighlightBracketBackground,
@@ -481,6 +495,12 @@ impl ThemeColors {
             ThemeColorField::EditorActiveWrapGuide => self.editor_active_wrap_guide,
             ThemeColorField::EditorIndentGuide => self.editor_indent_guide,
             ThemeColorField::EditorIndentGuideActive => self.editor_indent_guide_active,
+            ThemeColorField::EditorRainbowBracket1 => self.editor_rainbow_bracket_1,
+            ThemeColorField::EditorRainbowBracket2 => self.editor_rainbow_bracket_2,
+            ThemeColorField::EditorRainbowBracket3 => self.editor_rainbow_bracket_3,
+            ThemeColorField::EditorRainbowBracket4 => self.editor_rainbow_bracket_4,
+            ThemeColorField::EditorRainbowBracket5 => self.editor_rainbow_bracket_5,
+            ThemeColorField::EditorRainbowBracket6 => self.editor_rainbow_bracket_6,
             ThemeColorField::EditorDocumentHighlightReadBackground => {
                 self.editor_document_highlight_read_background
             }
@@ -402,6 +402,12 @@ impl ButtonCommon for Button {
         self.base = self.base.layer(elevation);
         self
     }
+
+    /// Sets the name screen readers announce for the button, instead of its label.
+    fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.base = self.base.accessibility_label(label);
+        self
+    }
 }
 
 impl RenderOnce for Button {
@@ -423,7 +429,13 @@ impl RenderOnce for Button {
             self.label_color.unwrap_or_default()
         };
 
-        self.base.child(
+        let accessibility_label = self
+            .base
+            .accessibility_label
+            .clone()
+            .unwrap_or_else(|| label.clone());
+
+        self.base.accessibility_label(accessibility_label).child(
             h_flex()
                 .gap(DynamicSpacing::Base04.rems(cx))
                 .when(self.icon_position == Some(IconPosition::Start), |this| {
@@ -602,3 +614,78 @@ impl Component for Button {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{Hsla, TestAppContext, VisualTestContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    struct Buttons;
+
+    impl Render for Buttons {
+        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            let button = |name: &'static str, button: Button| {
+                div().debug_selector(move || name.into()).child(button)
+            };
+            v_flex()
+                .size_full()
+                .p_2()
+                .gap_2()
+                .bg(cx.theme().colors().background)
+                .child(button(
+                    "filled",
+                    Button::new("filled", "Filled").style(ButtonStyle::Filled),
+                ))
+                .child(button(
+                    "subtle",
+                    Button::new("subtle", "Subtle").style(ButtonStyle::Subtle),
+                ))
+                .child(button(
+                    "outlined",
+                    Button::new("outlined", "Outlined").style(ButtonStyle::Outlined),
+                ))
+                .child(button(
+                    "selected",
+                    Button::new("selected", "Selected")
+                        .style(ButtonStyle::Subtle)
+                        .selected_style(ButtonStyle::Filled)
+                        .toggle_state(true),
+                ))
+        }
+    }
+
+    #[gpui::test]
+    fn test_render_button_styles(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        let (_, cx) = cx.add_window_view(|_, _| Buttons);
+        let image = cx.render_window();
+        let colors = cx.update(|_, cx| cx.theme().colors().clone());
+
+        // Samples the pixel at `offset` pixels from the left edge of a button, halfway down.
+        let pixel = |name: &'static str, offset: f32, cx: &mut VisualTestContext| {
+            let bounds = cx.debug_bounds(name).unwrap();
+            let scale_factor = cx.update(|window, _| window.scale_factor());
+            let x = (f32::from(bounds.left()) + offset) * scale_factor;
+            let y = f32::from(bounds.center().y) * scale_factor;
+            image.get_pixel(x as u32, y as u32).0
+        };
+
+        assert_eq!(pixel("filled", 4., cx), bytes(colors.element_background));
+        assert_eq!(pixel("subtle", 4., cx), bytes(colors.background));
+        assert_eq!(pixel("outlined", 0.5, cx), bytes(colors.border_variant));
+        assert_eq!(pixel("outlined", 4., cx), bytes(colors.element_background));
+        assert_eq!(pixel("selected", 4., cx), bytes(colors.element_background));
+    }
+
+    /// Converts an opaque color to the bytes of a rendered pixel.
+    fn bytes(color: Hsla) -> [u8; 4] {
+        let color = color.to_rgb();
+        [color.r, color.g, color.b, color.a].map(|channel| (channel * 255.).round() as u8)
+    }
+}
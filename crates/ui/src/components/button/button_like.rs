@@ -1,7 +1,7 @@
 use documented::Documented;
 use gpui::{
-    AnyElement, AnyView, ClickEvent, CursorStyle, DefiniteLength, Hsla, MouseButton,
-    MouseClickEvent, MouseDownEvent, MouseUpEvent, Rems, StyleRefinement, relative,
+    AccessibilityRole, AnyElement, AnyView, ClickEvent, CursorStyle, DefiniteLength, Hsla,
+    MouseButton, MouseClickEvent, MouseDownEvent, MouseUpEvent, Rems, StyleRefinement, relative,
     transparent_black,
 };
 use smallvec::SmallVec;
@@ -41,6 +41,11 @@ pub trait ButtonCommon: Clickable + Disableable {
     fn tab_index(self, tab_index: impl Into<isize>) -> Self;
 
     fn layer(self, elevation: ElevationIndex) -> Self;
+
+    /// The name that screen readers announce for the button.
+    ///
+    /// Buttons with a text label use it by default, so this is mostly needed for icon buttons.
+    fn accessibility_label(self, label: impl Into<SharedString>) -> Self;
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
@@ -397,6 +402,7 @@ pub struct ButtonLike {
     pub(super) height: Option<DefiniteLength>,
     pub(super) layer: Option<ElevationIndex>,
     tab_index: Option<isize>,
+    pub(super) accessibility_label: Option<SharedString>,
     size: ButtonSize,
     rounding: Option<ButtonLikeRounding>,
     tooltip: Option<Box<dyn Fn(&mut Window, &mut App) -> AnyView>>,
@@ -428,6 +434,7 @@ impl ButtonLike {
             on_right_click: None,
             layer: None,
             tab_index: None,
+            accessibility_label: None,
         }
     }
 
@@ -549,6 +556,11 @@ impl ButtonCommon for ButtonLike {
         self.layer = Some(elevation);
         self
     }
+
+    fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.accessibility_label = Some(label.into());
+        self
+    }
 }
 
 impl VisibleOnHover for ButtonLike {
@@ -575,6 +587,12 @@ impl RenderOnce for ButtonLike {
             .h_flex()
             .id(self.id.clone())
             .when_some(self.tab_index, |this, tab_index| this.tab_index(tab_index))
+            .role(AccessibilityRole::Button)
+            .when_some(self.accessibility_label, |this, label| {
+                this.accessibility_label(label)
+            })
+            .when(self.selected, |this| this.accessibility_selected(true))
+            .accessibility_disabled(self.disabled)
             .font_ui(cx)
             .group("")
             .flex_none()
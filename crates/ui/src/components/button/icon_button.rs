@@ -173,6 +173,11 @@ impl ButtonCommon for IconButton {
         self.base = self.base.layer(elevation);
         self
     }
+
+    fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.base = self.base.accessibility_label(label);
+        self
+    }
 }
 
 impl VisibleOnHover for IconButton {
@@ -190,7 +195,12 @@ impl RenderOnce for IconButton {
         let selected_style = self.base.selected_style;
 
         let color = self.icon_color.color(cx).opacity(self.alpha.unwrap_or(1.0));
+        let accessibility_label = self.base.accessibility_label.clone().unwrap_or_else(|| {
+            let icon_name: &'static str = self.icon.into();
+            icon_name.replace('_', " ").into()
+        });
         self.base
+            .accessibility_label(accessibility_label)
             .map(|this| match self.shape {
                 IconButtonShape::Square => {
                     let size = self.icon_size.square(window, cx);
@@ -132,6 +132,11 @@ impl ButtonCommon for ToggleButton {
         self.base = self.base.layer(elevation);
         self
     }
+
+    fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.base = self.base.accessibility_label(label);
+        self
+    }
 }
 
 impl RenderOnce for ToggleButton {
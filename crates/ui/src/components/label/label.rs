@@ -1,5 +1,5 @@
 use crate::{LabelLike, prelude::*};
-use gpui::StyleRefinement;
+use gpui::{AccessibilityRole, StyleRefinement};
 
 /// A struct representing a label element in the UI.
 ///
@@ -200,7 +200,12 @@ impl LabelCommon for Label {
 
 impl RenderOnce for Label {
     fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
-        self.base.child(self.label)
+        let mut base = self.base;
+        base.base = base
+            .base
+            .role(AccessibilityRole::Label)
+            .accessibility_label(self.label.clone());
+        base.child(self.label)
     }
 }
 
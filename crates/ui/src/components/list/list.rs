@@ -1,4 +1,4 @@
-use gpui::AnyElement;
+use gpui::{AccessibilityRole, AnyElement};
 use smallvec::SmallVec;
 
 use crate::{Label, ListHeader, prelude::*, v_flex};
@@ -77,6 +77,7 @@ impl From<AnyElement> for EmptyMessage {
 impl RenderOnce for List {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         v_flex()
+            .role(AccessibilityRole::List)
             .w_full()
             .py(DynamicSpacing::Base04.rems(cx))
             .children(self.header)
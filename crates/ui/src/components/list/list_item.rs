@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use gpui::{AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels, px};
+use gpui::{
+    AccessibilityAction, AccessibilityRole, AnyElement, AnyView, ClickEvent, MouseButton,
+    MouseDownEvent, Pixels, px,
+};
 use smallvec::SmallVec;
 
 use crate::{Disclosure, prelude::*};
@@ -210,6 +213,25 @@ impl RenderOnce for ListItem {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         h_flex()
             .id(self.id)
+            .role(AccessibilityRole::ListItem)
+            .accessibility_selected(self.selected)
+            .accessibility_disabled(self.disabled)
+            .when_some(self.toggle, |this, is_open| {
+                this.accessibility_expanded(is_open)
+            })
+            .when_some(
+                self.on_toggle.clone().zip(self.toggle),
+                |this, (on_toggle, is_open)| {
+                    let action = if is_open {
+                        AccessibilityAction::Collapse
+                    } else {
+                        AccessibilityAction::Expand
+                    };
+                    this.on_accessibility_action(action, move |_, window, cx| {
+                        on_toggle(&ClickEvent::default(), window, cx)
+                    })
+                },
+            )
             .when_some(self.group_name, |this, group| this.group(group))
             .w_full()
             .relative()
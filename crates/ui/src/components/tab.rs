@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use gpui::{AnyElement, IntoElement, Stateful};
+use gpui::{AccessibilityRole, AnyElement, IntoElement, Stateful};
 use smallvec::SmallVec;
 
 use crate::prelude::*;
@@ -142,6 +142,8 @@ impl RenderOnce for Tab {
         };
 
         self.div
+            .role(AccessibilityRole::Tab)
+            .accessibility_selected(self.selected)
             .h(Tab::container_height(cx))
             .bg(tab_bg)
             .border_color(cx.theme().colors().border)
@@ -1,4 +1,4 @@
-use gpui::{AnyElement, ScrollHandle};
+use gpui::{AccessibilityRole, AnyElement, ScrollHandle};
 use smallvec::SmallVec;
 
 use crate::Tab;
@@ -129,6 +129,7 @@ impl RenderOnce for TabBar {
                     .child(
                         h_flex()
                             .id("tabs")
+                            .role(AccessibilityRole::TabList)
                             .flex_grow()
                             .overflow_x_scroll()
                             .when_some(self.scroll_handle, |cx, scroll_handle| {
@@ -1,6 +1,6 @@
 use gpui::{
-    AnyElement, AnyView, ClickEvent, ElementId, Hsla, IntoElement, Styled, Window, div, hsla,
-    prelude::*,
+    AccessibilityRole, AnyElement, AnyView, ClickEvent, ElementId, Hsla, IntoElement, Styled,
+    Window, div, hsla, prelude::*,
 };
 use std::{rc::Rc, sync::Arc};
 
@@ -232,6 +232,14 @@ impl RenderOnce for Checkbox {
 
         h_flex()
             .id(self.id)
+            .role(AccessibilityRole::CheckBox)
+            .when_some(self.label.clone(), |this, label| {
+                this.accessibility_label(label)
+            })
+            .when(self.toggle_state != ToggleState::Indeterminate, |this| {
+                this.accessibility_checked(self.toggle_state == ToggleState::Selected)
+            })
+            .accessibility_disabled(self.disabled)
             .gap(DynamicSpacing::Base06.rems(cx))
             .child(checkbox)
             .when_some(
@@ -506,6 +514,12 @@ impl RenderOnce for Switch {
 
         let switch = div()
             .id((self.id.clone(), "switch"))
+            .role(AccessibilityRole::Switch)
+            .when_some(self.label.clone(), |this, label| {
+                this.accessibility_label(label)
+            })
+            .accessibility_checked(is_on)
+            .accessibility_disabled(self.disabled)
             .p(px(1.0))
             .border_2()
             .border_color(cx.theme().colors().border_transparent)
@@ -1,12 +1,12 @@
 use std::{path::Path, sync::Arc};
 
-use gpui::{EventEmitter, FocusHandle, Focusable};
+use gpui::{Action as _, EventEmitter, FocusHandle, Focusable};
 use ui::{
     App, Button, ButtonCommon, ButtonStyle, Clickable, Context, FluentBuilder, InteractiveElement,
     KeyBinding, Label, LabelCommon, LabelSize, ParentElement, Render, SharedString, Styled as _,
     Window, h_flex, v_flex,
 };
-use zed_actions::workspace::OpenWithSystem;
+use zed_actions::{hex_editor::ReopenAsHex, workspace::OpenWithSystem};
 
 use crate::Item;
 
@@ -97,18 +97,34 @@ impl Render for InvalidBufferView {
                         )
                         .when(self.is_local, |contents| {
                             contents.child(
-                                h_flex().justify_center().child(
-                                    Button::new("open-with-system", "Open in Default App")
-                                        .on_click(move |_, _, cx| {
-                                            cx.open_with_system(&abs_path);
-                                        })
-                                        .style(ButtonStyle::Outlined)
-                                        .key_binding(KeyBinding::for_action(
-                                            &OpenWithSystem,
-                                            window,
-                                            cx,
-                                        )),
-                                ),
+                                h_flex()
+                                    .justify_center()
+                                    .gap_2()
+                                    .child(
+                                        Button::new("open-with-system", "Open in Default App")
+                                            .on_click(move |_, _, cx| {
+                                                cx.open_with_system(&abs_path);
+                                            })
+                                            .style(ButtonStyle::Outlined)
+                                            .key_binding(KeyBinding::for_action(
+                                                &OpenWithSystem,
+                                                window,
+                                                cx,
+                                            )),
+                                    )
+                                    .child(
+                                        Button::new("reopen-as-hex", "Open as Hex")
+                                            .on_click(|_, window, cx| {
+                                                window
+                                                    .dispatch_action(ReopenAsHex.boxed_clone(), cx);
+                                            })
+                                            .style(ButtonStyle::Outlined)
+                                            .key_binding(KeyBinding::for_action(
+                                                &ReopenAsHex,
+                                                window,
+                                                cx,
+                                            )),
+                                    ),
                             )
                         }),
                 ),
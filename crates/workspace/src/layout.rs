@@ -0,0 +1,800 @@
+use std::path::Path;
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::{HashMap, HashSet};
+use gpui::{
+    Action, App, AsyncWindowContext, Axis, Context, Entity, EntityId, Task, WeakEntity, px,
+};
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use settings::{
+    DockLayoutContent, PaneLayoutAxis, PaneLayoutContent, PaneLayoutItem, Settings as _,
+    WorkspaceLayoutContent,
+};
+use ui::Window;
+use util::ResultExt as _;
+
+use crate::{
+    ItemHandle, ItemId, Member, Pane, PaneAxis, PaneGroup, SerializableItemRegistry, Workspace,
+    WorkspaceId, WorkspaceSettings, dock::Dock, move_all_items, move_item,
+    notifications::NotifyTaskExt as _, persistence::DB,
+};
+
+/// Saves the current arrangement of panes and docks as a named layout of the workspace.
+#[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = workspace)]
+#[serde(deny_unknown_fields)]
+pub struct SaveLayout {
+    pub name: String,
+}
+
+/// Loads a named layout, either saved in the workspace or declared in the `layouts` setting.
+#[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = workspace)]
+#[serde(deny_unknown_fields)]
+pub struct LoadLayout {
+    pub name: String,
+}
+
+/// Deletes a named layout saved in the workspace.
+#[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = workspace)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteLayout {
+    pub name: String,
+}
+
+/// A pane created for a layout, along with the items it should show.
+struct PaneLayout {
+    pane: Entity<Pane>,
+    items: Vec<PaneLayoutItem>,
+    active_item: Option<usize>,
+    zoomed: bool,
+}
+
+impl Workspace {
+    /// Returns the names of the layouts saved in this workspace.
+    pub fn saved_layout_names(&self) -> Vec<String> {
+        let Some(database_id) = self.database_id else {
+            return Vec::new();
+        };
+        DB.layouts(database_id)
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Captures the current arrangement of the center panes and of the docks.
+    pub fn capture_layout(&self, window: &Window, cx: &App) -> WorkspaceLayoutContent {
+        WorkspaceLayoutContent {
+            center: Some(self.capture_member(&self.center.root, cx)),
+            left_dock: Some(capture_dock(&self.left_dock, window, cx)),
+            right_dock: Some(capture_dock(&self.right_dock, window, cx)),
+            bottom_dock: Some(capture_dock(&self.bottom_dock, window, cx)),
+        }
+    }
+
+    pub fn save_layout(
+        &mut self,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(database_id) = self.database_id else {
+            return Task::ready(Err(anyhow!("layouts can't be saved in this workspace")));
+        };
+        let layout = match serde_json::to_string(&self.capture_layout(window, cx)) {
+            Ok(layout) => layout,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        cx.background_spawn(async move { DB.save_layout(database_id, name, layout).await })
+    }
+
+    /// Loads the layout with the given name, preferring layouts saved in this workspace
+    /// over the ones declared in settings.
+    pub fn load_layout(
+        &mut self,
+        name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let saved_layout = self
+            .database_id
+            .and_then(|database_id| DB.layout(database_id, name.to_string()).log_err())
+            .flatten();
+        let layout = if let Some(saved_layout) = saved_layout {
+            match serde_json::from_str(&saved_layout)
+                .with_context(|| format!("deserializing layout {name:?}"))
+            {
+                Ok(layout) => layout,
+                Err(error) => return Task::ready(Err(error)),
+            }
+        } else if let Some(layout) = WorkspaceSettings::get_global(cx).layouts.get(name) {
+            layout.clone()
+        } else {
+            return Task::ready(Err(anyhow!("no layout named {name:?}")));
+        };
+        self.apply_layout(layout, window, cx)
+    }
+
+    pub fn delete_layout(&mut self, name: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some(database_id) = self.database_id else {
+            return Task::ready(Ok(()));
+        };
+        cx.background_spawn(async move { DB.delete_layout(database_id, name).await })
+    }
+
+    /// Rearranges the panes and docks of this workspace according to `layout`.
+    ///
+    /// Items that are already open are moved into the panes listing them, and the
+    /// ones the layout doesn't mention are kept at the end of its first pane.
+    pub fn apply_layout(
+        &mut self,
+        layout: WorkspaceLayoutContent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_zoomed(false, cx));
+        }
+        for dock in self.all_docks() {
+            dock.update(cx, |dock, cx| dock.zoom_out(window, cx));
+        }
+        self.zoomed = None;
+        self.zoomed_position = None;
+
+        for (dock, dock_layout) in [
+            (self.left_dock.clone(), layout.left_dock),
+            (self.right_dock.clone(), layout.right_dock),
+            (self.bottom_dock.clone(), layout.bottom_dock),
+        ] {
+            if let Some(dock_layout) = dock_layout {
+                self.apply_dock_layout(&dock, dock_layout, window, cx);
+            }
+        }
+        cx.emit(crate::Event::ZoomChanged);
+
+        let Some(center) = layout.center else {
+            cx.notify();
+            self.serialize_workspace(window, cx);
+            return Task::ready(Ok(()));
+        };
+
+        let mut pane_layouts = Vec::new();
+        let root = self.build_layout_member(center, &mut pane_layouts, window, cx);
+        let first_pane = pane_layouts[0].pane.clone();
+        for pane in self.center.panes() {
+            move_all_items(pane, &first_pane, window, cx);
+        }
+        self.remove_panes(self.center.root.clone(), window, cx);
+        self.center = PaneGroup::with_root(root);
+        self.set_active_pane(&first_pane, window, cx);
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let mut placed_item_ids = HashSet::default();
+            for pane_layout in pane_layouts {
+                let pane = pane_layout.pane;
+                let mut position = 0;
+                for layout_item in pane_layout.items {
+                    let item = match layout_item {
+                        PaneLayoutItem::Path(path) => {
+                            Self::open_layout_path(&this, &path, &pane, cx).await
+                        }
+                        PaneLayoutItem::Serialized { kind, item_id } => {
+                            Self::open_layout_serialized_item(
+                                &this,
+                                &kind,
+                                item_id,
+                                &pane,
+                                &placed_item_ids,
+                                cx,
+                            )
+                            .await
+                        }
+                    };
+                    let Some((source, item_id)) = item.log_err().flatten() else {
+                        continue;
+                    };
+                    cx.update(|window, cx| {
+                        move_item(&source, &pane, item_id, position, false, window, cx)
+                    })?;
+                    placed_item_ids.insert(item_id);
+                    position += 1;
+                }
+
+                this.update_in(cx, |this, window, cx| {
+                    if let Some(active_item) = pane_layout.active_item.filter(|ix| *ix < position) {
+                        pane.update(cx, |pane, cx| {
+                            pane.activate_item(active_item, false, false, window, cx)
+                        });
+                    }
+                    if pane_layout.zoomed {
+                        pane.update(cx, |pane, cx| pane.set_zoomed(true, cx));
+                        this.zoomed = Some(pane.downgrade().into());
+                        this.zoomed_position = None;
+                        this.set_active_pane(&pane, window, cx);
+                        cx.emit(crate::Event::ZoomChanged);
+                    }
+                })?;
+            }
+
+            this.update_in(cx, |this, window, cx| {
+                window.focus(&this.active_pane.focus_handle(cx));
+                this.serialize_workspace(window, cx);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Opens the file at `path` in `pane` unless it's already open, returning the pane
+    /// containing it and its id.
+    async fn open_layout_path(
+        this: &WeakEntity<Self>,
+        path: &str,
+        pane: &Entity<Pane>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Option<(Entity<Pane>, EntityId)>> {
+        let project_path = this
+            .update(cx, |this, cx| this.resolve_layout_path(path, cx))?
+            .await?;
+        let open_item = this.update(cx, |this, cx| {
+            this.center.panes().into_iter().find_map(|pane| {
+                let item = pane
+                    .read(cx)
+                    .items()
+                    .find(|item| item.project_path(cx).as_ref() == Some(&project_path))?;
+                Some((pane.clone(), item.item_id()))
+            })
+        })?;
+        if open_item.is_some() {
+            return Ok(open_item);
+        }
+        let item = this
+            .update_in(cx, |this, window, cx| {
+                this.open_path(project_path, Some(pane.downgrade()), false, window, cx)
+            })?
+            .await?;
+        Ok(Some((pane.clone(), item.item_id())))
+    }
+
+    /// Finds the open item that a layout saved under `item_id`, or else an open item of
+    /// the same kind that the layout hasn't placed yet. When there is none, the item is
+    /// restored into `pane` from the state saved for it in this workspace.
+    async fn open_layout_serialized_item(
+        this: &WeakEntity<Self>,
+        kind: &str,
+        item_id: u64,
+        pane: &Entity<Pane>,
+        placed_item_ids: &HashSet<EntityId>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Option<(Entity<Pane>, EntityId)>> {
+        let (open_item, project, database_id) = this.update(cx, |this, cx| {
+            let open_items = this
+                .center
+                .panes()
+                .into_iter()
+                .flat_map(|pane| {
+                    pane.read(cx)
+                        .items()
+                        .filter(|item| {
+                            !placed_item_ids.contains(&item.item_id())
+                                && item
+                                    .to_serializable_item_handle(cx)
+                                    .is_some_and(|item| item.serialized_item_kind() == kind)
+                        })
+                        .map(|item| (pane.clone(), item.item_id()))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let open_item = open_items
+                .iter()
+                .find(|(_, id)| id.as_u64() == item_id)
+                .or_else(|| open_items.first())
+                .cloned();
+            (open_item, this.project.clone(), this.database_id)
+        })?;
+        if open_item.is_some() {
+            return Ok(open_item);
+        }
+        let Some(database_id) = database_id else {
+            return Ok(None);
+        };
+        let item = pane
+            .update_in(cx, |_, window, cx| {
+                SerializableItemRegistry::deserialize(
+                    kind,
+                    project,
+                    this.clone(),
+                    database_id,
+                    item_id,
+                    window,
+                    cx,
+                )
+            })?
+            .await?;
+        pane.update_in(cx, |pane, window, cx| {
+            pane.add_item(item.boxed_clone(), false, false, None, window, cx)
+        })?;
+        Ok(Some((pane.clone(), item.item_id())))
+    }
+
+    pub(crate) fn handle_save_layout(
+        &mut self,
+        action: &SaveLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.save_layout(action.name.clone(), window, cx)
+            .detach_and_notify_err(window, cx);
+    }
+
+    pub(crate) fn handle_load_layout(
+        &mut self,
+        action: &LoadLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.load_layout(&action.name, window, cx)
+            .detach_and_notify_err(window, cx);
+    }
+
+    pub(crate) fn handle_delete_layout(
+        &mut self,
+        action: &DeleteLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.delete_layout(action.name.clone(), cx)
+            .detach_and_notify_err(window, cx);
+    }
+
+    fn capture_member(&self, member: &Member, cx: &App) -> PaneLayoutContent {
+        match member {
+            Member::Axis(PaneAxis {
+                axis,
+                members,
+                flexes,
+                ..
+            }) => PaneLayoutContent::Split {
+                axis: match axis {
+                    Axis::Horizontal => PaneLayoutAxis::Horizontal,
+                    Axis::Vertical => PaneLayoutAxis::Vertical,
+                },
+                members: members
+                    .iter()
+                    .map(|member| self.capture_member(member, cx))
+                    .collect(),
+                flexes: Some(flexes.lock().clone()),
+            },
+            Member::Pane(pane) => {
+                let pane = pane.read(cx);
+                let project = self.project.read(cx);
+                let mut items = Vec::new();
+                let mut active_item = None;
+                for (ix, item) in pane.items().enumerate() {
+                    let Some(layout_item) = layout_item(item.as_ref(), project, cx) else {
+                        continue;
+                    };
+                    if ix == pane.active_item_index() {
+                        active_item = Some(items.len());
+                    }
+                    items.push(layout_item);
+                }
+                PaneLayoutContent::Pane {
+                    items,
+                    active_item,
+                    zoomed: pane.is_zoomed(),
+                }
+            }
+        }
+    }
+
+    fn build_layout_member(
+        &mut self,
+        layout: PaneLayoutContent,
+        pane_layouts: &mut Vec<PaneLayout>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Member {
+        match layout {
+            PaneLayoutContent::Split {
+                axis,
+                members,
+                flexes,
+            } => {
+                let mut members = members
+                    .into_iter()
+                    .map(|member| self.build_layout_member(member, pane_layouts, window, cx))
+                    .collect::<Vec<_>>();
+                match members.len() {
+                    0 => self.build_layout_member(
+                        PaneLayoutContent::Pane {
+                            items: Vec::new(),
+                            active_item: None,
+                            zoomed: false,
+                        },
+                        pane_layouts,
+                        window,
+                        cx,
+                    ),
+                    1 => members.remove(0),
+                    _ => {
+                        let axis = match axis {
+                            PaneLayoutAxis::Horizontal => Axis::Horizontal,
+                            PaneLayoutAxis::Vertical => Axis::Vertical,
+                        };
+                        let flexes = flexes.filter(|flexes| flexes.len() == members.len());
+                        Member::Axis(PaneAxis::load(axis, members, flexes))
+                    }
+                }
+            }
+            PaneLayoutContent::Pane {
+                items,
+                active_item,
+                zoomed,
+            } => {
+                let pane = self.add_pane(window, cx);
+                pane_layouts.push(PaneLayout {
+                    pane: pane.clone(),
+                    items,
+                    active_item,
+                    zoomed,
+                });
+                Member::Pane(pane)
+            }
+        }
+    }
+
+    fn apply_dock_layout(
+        &mut self,
+        dock: &Entity<Dock>,
+        layout: DockLayoutContent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let zoomed_panel = dock.update(cx, |dock, cx| {
+            if let Some(active_panel) = layout.active_panel.as_deref()
+                && let Some(panel_ix) = dock.panel_index_for_persistent_name(active_panel, cx)
+            {
+                dock.activate_panel(panel_ix, window, cx);
+            }
+            if let Some(visible) = layout.visible {
+                dock.set_open(visible, window, cx);
+            }
+            if let Some(size) = layout.size {
+                dock.resize_active_panel(Some(px(size)), window, cx);
+            }
+            let panel = dock.visible_panel()?.clone();
+            layout.zoomed.unwrap_or(false).then(|| {
+                panel.set_zoomed(true, window, cx);
+                (panel, dock.position())
+            })
+        });
+        if let Some((panel, position)) = zoomed_panel {
+            self.zoomed = Some(panel.to_any().downgrade());
+            self.zoomed_position = Some(position);
+        }
+    }
+
+    fn resolve_layout_path(&self, path: &str, cx: &mut App) -> Task<Result<ProjectPath>> {
+        if let Some(project_path) = self.project.read(cx).find_project_path(path, cx) {
+            return Task::ready(Ok(project_path));
+        }
+        if Path::new(path).is_absolute() {
+            let task =
+                Workspace::project_path_for_path(self.project.clone(), path.as_ref(), false, cx);
+            return cx.spawn(async move |_| Ok(task.await?.1));
+        }
+        Task::ready(Err(anyhow!("{path:?} is not part of the project")))
+    }
+}
+
+/// Updates the layouts saved in a workspace after its items were restored. Items are saved
+/// under their id in the session that saved them, so the layouts' items are renamed from
+/// the ids the items were restored from to the ones they are now saved under.
+pub(crate) async fn remap_saved_layouts(
+    workspace_id: WorkspaceId,
+    restored_item_ids: &HashMap<ItemId, ItemId>,
+) -> Result<()> {
+    for (name, layout) in DB.layouts(workspace_id)? {
+        let mut layout: WorkspaceLayoutContent = serde_json::from_str(&layout)
+            .with_context(|| format!("deserializing layout {name:?}"))?;
+        let Some(center) = layout.center.as_mut() else {
+            continue;
+        };
+        if remap_layout_items(center, restored_item_ids) {
+            DB.save_layout(workspace_id, name, serde_json::to_string(&layout)?)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Renames the serialized items of a layout, returning whether any of them changed.
+fn remap_layout_items(
+    layout: &mut PaneLayoutContent,
+    restored_item_ids: &HashMap<ItemId, ItemId>,
+) -> bool {
+    match layout {
+        PaneLayoutContent::Split { members, .. } => {
+            members.iter_mut().fold(false, |changed, member| {
+                remap_layout_items(member, restored_item_ids) | changed
+            })
+        }
+        PaneLayoutContent::Pane { items, .. } => {
+            let mut changed = false;
+            for item in items {
+                if let PaneLayoutItem::Serialized { item_id, .. } = item
+                    && let Some(restored_item_id) = restored_item_ids.get(item_id)
+                    && restored_item_id != item_id
+                {
+                    *item_id = *restored_item_id;
+                    changed = true;
+                }
+            }
+            changed
+        }
+    }
+}
+
+fn capture_dock(dock: &Entity<Dock>, window: &Window, cx: &App) -> DockLayoutContent {
+    let dock = dock.read(cx);
+    let active_panel = dock.active_panel();
+    DockLayoutContent {
+        visible: Some(dock.is_open()),
+        active_panel: active_panel.map(|panel| panel.persistent_name().to_string()),
+        size: active_panel.map(|panel| panel.size(window, cx).into()),
+        zoomed: Some(dock.zoomed_panel(window, cx).is_some()),
+    }
+}
+
+/// Describes an item for a layout, by its path if it's a file in the project and otherwise
+/// by the state the workspace serializes for it. Other items are left out of layouts.
+fn layout_item(item: &dyn ItemHandle, project: &Project, cx: &App) -> Option<PaneLayoutItem> {
+    if let Some(project_path) = item.project_path(cx) {
+        return layout_path(&project_path, project, cx).map(PaneLayoutItem::Path);
+    }
+    let item = item.to_serializable_item_handle(cx)?;
+    Some(PaneLayoutItem::Serialized {
+        kind: item.serialized_item_kind().to_string(),
+        item_id: item.item_id().as_u64(),
+    })
+}
+
+/// Formats a project path for a layout, keeping it valid for other checkouts of the project
+/// unless the item lives outside of the project's visible worktrees.
+fn layout_path(project_path: &ProjectPath, project: &Project, cx: &App) -> Option<String> {
+    let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+    let worktree = worktree.read(cx);
+    let path = if worktree.is_visible() {
+        worktree
+            .root_name()
+            .join(&project_path.path)
+            .as_unix_str()
+            .to_string()
+    } else {
+        worktree
+            .absolutize(&project_path.path)
+            .to_string_lossy()
+            .into_owned()
+    };
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use fs::FakeFs;
+    use gpui::{AppContext as _, TestAppContext};
+
+    use super::*;
+    use crate::{
+        SplitDirection,
+        item::test::TestItem,
+        persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup},
+        register_serializable_item,
+        tests::init_test,
+    };
+
+    #[gpui::test]
+    async fn test_restore_layout(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_serializable_item::<TestItem>);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let item1 = cx.new(TestItem::new);
+        let item2 = cx.new(TestItem::new);
+        let right_pane = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_item_to_active_pane(Box::new(item1.clone()), None, true, window, cx);
+            let right_pane = workspace.split_pane(
+                workspace.active_pane().clone(),
+                SplitDirection::Right,
+                window,
+                cx,
+            );
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(item2.clone()), true, true, None, window, cx)
+            });
+            right_pane
+        });
+        right_pane.update(cx, |pane, cx| pane.set_zoomed(true, cx));
+
+        let layout = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.capture_layout(window, cx)
+        });
+        let serialized = |item_id: EntityId| PaneLayoutItem::Serialized {
+            kind: "TestItem".to_string(),
+            item_id: item_id.as_u64(),
+        };
+        assert_eq!(
+            layout.center,
+            Some(PaneLayoutContent::Split {
+                axis: PaneLayoutAxis::Horizontal,
+                members: vec![
+                    PaneLayoutContent::Pane {
+                        items: vec![serialized(item1.item_id())],
+                        active_item: Some(0),
+                        zoomed: false,
+                    },
+                    PaneLayoutContent::Pane {
+                        items: vec![serialized(item2.item_id())],
+                        active_item: Some(0),
+                        zoomed: true,
+                    },
+                ],
+                flexes: Some(vec![1., 1.]),
+            })
+        );
+
+        // Collapse everything into a single pane, then restore the captured layout.
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.apply_layout(
+                    WorkspaceLayoutContent {
+                        center: Some(PaneLayoutContent::Pane {
+                            items: Vec::new(),
+                            active_item: None,
+                            zoomed: false,
+                        }),
+                        ..Default::default()
+                    },
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        workspace.read_with(cx, |workspace, cx| {
+            assert_eq!(workspace.panes().len(), 1);
+            assert_eq!(workspace.active_pane().read(cx).items_len(), 2);
+        });
+
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.apply_layout(layout.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        workspace.update_in(cx, |workspace, window, cx| {
+            let panes = workspace.center.panes();
+            let items = panes
+                .iter()
+                .map(|pane| {
+                    pane.read(cx)
+                        .items()
+                        .map(|item| item.item_id())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(items, vec![vec![item1.item_id()], vec![item2.item_id()]]);
+            assert!(panes[1].read(cx).is_zoomed());
+            assert_eq!(workspace.capture_layout(window, cx), layout);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restore_layout_after_reopening(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_serializable_item::<TestItem>);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let database_id = DB.next_id().await.unwrap();
+        let (workspace, cx) = cx.add_window_view(|window, cx| {
+            let mut workspace = Workspace::test_new(project.clone(), window, cx);
+            workspace.database_id = Some(database_id);
+            workspace
+        });
+
+        // A layout saved in an earlier session, where the items were saved as 1 and 2.
+        let serialized = |item_id| PaneLayoutItem::Serialized {
+            kind: "TestItem".to_string(),
+            item_id,
+        };
+        let layout = WorkspaceLayoutContent {
+            center: Some(PaneLayoutContent::Split {
+                axis: PaneLayoutAxis::Horizontal,
+                members: vec![
+                    PaneLayoutContent::Pane {
+                        items: vec![serialized(1)],
+                        active_item: Some(0),
+                        zoomed: false,
+                    },
+                    PaneLayoutContent::Pane {
+                        items: vec![serialized(2)],
+                        active_item: Some(0),
+                        zoomed: false,
+                    },
+                ],
+                flexes: None,
+            }),
+            ..Default::default()
+        };
+        DB.save_layout(
+            database_id,
+            "saved".to_string(),
+            serde_json::to_string(&layout).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        // Reopening the workspace restores both items into a single pane, under new ids.
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.load_detached_window(
+                    SerializedPaneGroup::Pane(SerializedPane::new(
+                        vec![
+                            SerializedItem::new("TestItem", 2, true, false),
+                            SerializedItem::new("TestItem", 1, false, false),
+                        ],
+                        true,
+                        0,
+                    )),
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let restored_items = workspace.read_with(cx, |workspace, cx| {
+            workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .map(|item| item.item_id())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(restored_items.len(), 2);
+
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.load_layout("saved", window, cx)
+            })
+            .await
+            .unwrap();
+        workspace.read_with(cx, |workspace, cx| {
+            let items = workspace
+                .center
+                .panes()
+                .iter()
+                .map(|pane| {
+                    pane.read(cx)
+                        .items()
+                        .map(|item| item.item_id())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                items,
+                vec![vec![restored_items[1]], vec![restored_items[0]]]
+            );
+        });
+    }
+}
@@ -17,11 +17,11 @@ use anyhow::Result;
 use collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use futures::{StreamExt, stream::FuturesUnordered};
 use gpui::{
-    Action, AnyElement, App, AsyncWindowContext, ClickEvent, ClipboardItem, Context, Corner, Div,
-    DragMoveEvent, Entity, EntityId, EventEmitter, ExternalPaths, FocusHandle, FocusOutEvent,
-    Focusable, IsZero, KeyContext, MouseButton, MouseDownEvent, NavigationDirection, Pixels, Point,
-    PromptLevel, Render, ScrollHandle, Subscription, Task, WeakEntity, WeakFocusHandle, Window,
-    actions, anchored, deferred, prelude::*,
+    Action, AnyElement, App, AsyncWindowContext, Bounds, ClickEvent, ClipboardItem, Context,
+    Corner, Div, DragMoveEvent, Entity, EntityId, EventEmitter, ExternalPaths, FocusHandle,
+    FocusOutEvent, Focusable, IsZero, KeyContext, MouseButton, MouseDownEvent, MouseUpEvent,
+    NavigationDirection, Pixels, Point, PromptLevel, Render, ScrollHandle, Subscription, Task,
+    WeakEntity, WeakFocusHandle, Window, actions, anchored, deferred, prelude::*,
 };
 use itertools::Itertools;
 use language::DiagnosticSeverity;
@@ -233,6 +233,8 @@ actions!(
         SplitAndMoveRight,
         /// Splits the pane downward, moving the current item.
         SplitAndMoveDown,
+        /// Moves the current item into a new window that shares this project.
+        MoveItemToNewWindow,
         /// Splits the pane horizontally.
         SplitHorizontal,
         /// Splits the pane vertically.
@@ -284,6 +286,13 @@ pub enum Event {
     ItemUnpinned,
     JoinAll,
     JoinIntoNext,
+    MoveItemToNewWindow {
+        item_id: EntityId,
+    },
+    TabDroppedOutside {
+        item_id: EntityId,
+        screen_position: Point<Pixels>,
+    },
     ChangeItemTitle,
     Focus,
     ZoomIn,
@@ -320,6 +329,18 @@ impl fmt::Debug for Event {
                 .finish(),
             Event::JoinAll => f.write_str("JoinAll"),
             Event::JoinIntoNext => f.write_str("JoinIntoNext"),
+            Event::MoveItemToNewWindow { item_id } => f
+                .debug_struct("MoveItemToNewWindow")
+                .field("item_id", item_id)
+                .finish(),
+            Event::TabDroppedOutside {
+                item_id,
+                screen_position,
+            } => f
+                .debug_struct("TabDroppedOutside")
+                .field("item_id", item_id)
+                .field("screen_position", screen_position)
+                .finish(),
             Event::ChangeItemTitle => f.write_str("ChangeItemTitle"),
             Event::Focus => f.write_str("Focus"),
             Event::ZoomIn => f.write_str("ZoomIn"),
@@ -393,6 +414,9 @@ pub struct Pane {
     diagnostics: HashMap<ProjectPath, DiagnosticSeverity>,
     zoom_out_on_close: bool,
     diagnostic_summary_update: Task<()>,
+    /// A tab of this pane that is being dragged outside of the window, with the window
+    /// position of the cursor.
+    torn_out_tab: Option<(EntityId, Point<Pixels>)>,
     /// If a certain project item wants to get recreated with specific data, it can persist its data before the recreation here.
     pub project_item_restoration_data: HashMap<ProjectItemKind, Box<dyn Any + Send>>,
 }
@@ -536,6 +560,7 @@ impl Pane {
             diagnostics: Default::default(),
             zoom_out_on_close: true,
             diagnostic_summary_update: Task::ready(()),
+            torn_out_tab: None,
             project_item_restoration_data: HashMap::default(),
         }
     }
@@ -2310,6 +2335,12 @@ impl Pane {
         }
     }
 
+    pub fn move_item_to_new_window(&mut self, item_id: EntityId, cx: &mut Context<Self>) {
+        if self.index_for_item_id(item_id).is_some() {
+            cx.emit(Event::MoveItemToNewWindow { item_id });
+        }
+    }
+
     pub fn toolbar(&self) -> &Entity<Toolbar> {
         &self.toolbar
     }
@@ -2881,6 +2912,14 @@ impl Pane {
                                     pane.close_all_items(&close_all_items_action, window, cx)
                                         .detach_and_log_err(cx)
                                 }),
+                            )
+                            .separator()
+                            .entry(
+                                "Move to New Window",
+                                Some(MoveItemToNewWindow.boxed_clone()),
+                                window.handler_for(&pane, move |pane, _, cx| {
+                                    pane.move_item_to_new_window(item_id, cx);
+                                }),
                             );
 
                         let pin_tab_entries = |menu: ContextMenu| {
@@ -3231,6 +3270,42 @@ impl Pane {
         }
     }
 
+    fn handle_tab_drag_out(
+        &mut self,
+        event: &DragMoveEvent<DraggedTab>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let position = event.event.position;
+        let viewport = Bounds::new(Point::default(), window.viewport_size());
+        let dragged_tab = event.drag(cx);
+        self.torn_out_tab = (!viewport.contains(&position) && dragged_tab.pane == cx.entity())
+            .then(|| (dragged_tab.item.item_id(), position));
+    }
+
+    /// Hands a tab that was dropped outside of this window over to the workspace, which
+    /// moves it into another window.
+    fn handle_tab_drop_outside(
+        &mut self,
+        _: &MouseUpEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let torn_out_tab = self.torn_out_tab.take();
+        if !cx.has_active_drag() {
+            return;
+        }
+        let Some((item_id, position)) = torn_out_tab else {
+            return;
+        };
+        if self.index_for_item_id(item_id).is_some() {
+            cx.emit(Event::TabDroppedOutside {
+                item_id,
+                screen_position: window.bounds().origin + position,
+            });
+        }
+    }
+
     pub fn handle_tab_drop(
         &mut self,
         dragged_tab: &DraggedTab,
@@ -3705,6 +3780,11 @@ impl Render for Pane {
             .on_action(cx.listener(|pane, _: &SplitAndMoveRight, _, cx| {
                 pane.split_and_move(SplitDirection::Right, cx)
             }))
+            .on_action(cx.listener(|pane, _: &MoveItemToNewWindow, _, cx| {
+                if let Some(item_id) = pane.active_item().map(|item| item.item_id()) {
+                    pane.move_item_to_new_window(item_id, cx);
+                }
+            }))
             .on_action(cx.listener(|_, _: &JoinIntoNext, _, cx| {
                 cx.emit(Event::JoinIntoNext);
             }))
@@ -3801,6 +3881,11 @@ impl Render for Pane {
                     }
                 }),
             )
+            .on_drag_move::<DraggedTab>(cx.listener(Self::handle_tab_drag_out))
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(Self::handle_tab_drop_outside),
+            )
             .on_action(cx.listener(|_, _: &menu::Cancel, window, cx| {
                 if cx.stop_active_drag(window) {
                 } else {
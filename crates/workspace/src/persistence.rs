@@ -16,7 +16,10 @@ use db::{
     sqlez_macros::sql,
 };
 use gpui::{Axis, Bounds, Task, WindowBounds, WindowId, point, size};
-use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+use project::{
+    bookmark_store::SerializedBookmark,
+    debugger::breakpoint_store::{BreakpointState, SourceBreakpoint},
+};
 
 use language::{LanguageName, Toolchain, ToolchainScope};
 use project::WorktreeId;
@@ -38,8 +41,8 @@ use crate::{
 };
 
 use model::{
-    GroupId, ItemId, PaneId, RemoteConnectionId, SerializedItem, SerializedPane,
-    SerializedPaneGroup, SerializedWorkspace,
+    GroupId, ItemId, PaneId, RemoteConnectionId, SerializedDetachedWindow, SerializedItem,
+    SerializedPane, SerializedPaneGroup, SerializedWorkspace,
 };
 
 use self::model::{DockStructure, SerializedWorkspaceLocation};
@@ -702,6 +705,38 @@ impl Domain for WorkspaceDb {
         sql!(
             DROP TABLE ssh_connections;
         ),
+        sql!(
+            CREATE TABLE detached_windows(
+                workspace_id INTEGER PRIMARY KEY,
+                parent_workspace_id INTEGER NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE,
+                FOREIGN KEY(parent_workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            CREATE TABLE workspace_layouts(
+                workspace_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                layout TEXT NOT NULL,
+                PRIMARY KEY(workspace_id, name),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            CREATE TABLE bookmarks(
+                workspace_id INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                row INTEGER NOT NULL,
+                label TEXT,
+                line_text TEXT NOT NULL,
+                PRIMARY KEY(workspace_id, path, row),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -821,6 +856,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            bookmarks: self.bookmarks(workspace_id),
             window_id,
             user_toolchains: self.user_toolchains(workspace_id, remote_connection_id),
         })
@@ -872,6 +908,37 @@ impl WorkspaceDb {
         }
     }
 
+    fn bookmarks(&self, workspace_id: WorkspaceId) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        let bookmarks: Result<Vec<(PathBuf, u32, Option<Arc<str>>, Arc<str>)>> = self
+            .select_bound(sql! {
+                SELECT path, row, label, line_text
+                FROM bookmarks
+                WHERE workspace_id = ?
+                ORDER BY path, row
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match bookmarks {
+            Ok(bookmarks) => {
+                let mut map: BTreeMap<Arc<Path>, Vec<SerializedBookmark>> = Default::default();
+                for (path, row, label, line_text) in bookmarks {
+                    map.entry(path.into())
+                        .or_default()
+                        .push(SerializedBookmark {
+                            row,
+                            label,
+                            line_text,
+                        });
+                }
+                map
+            }
+            Err(msg) => {
+                log::error!("Bookmarks query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
     fn user_toolchains(
         &self,
         workspace_id: WorkspaceId,
@@ -998,6 +1065,28 @@ impl WorkspaceDb {
                     }
                 }
 
+                conn.exec_bound(
+                    sql!(
+                        DELETE FROM bookmarks WHERE workspace_id = ?1;
+                    )
+                )?(workspace.id).context("Clearing old bookmarks")?;
+
+                for (path, bookmarks) in workspace.bookmarks {
+                    for bookmark in bookmarks {
+                        conn.exec_bound(sql!(
+                            INSERT OR REPLACE INTO bookmarks (workspace_id, path, row, label, line_text)
+                            VALUES (?1, ?2, ?3, ?4, ?5);
+                        ))?((
+                            workspace.id,
+                            path.as_ref(),
+                            bookmark.row,
+                            bookmark.label,
+                            bookmark.line_text,
+                        ))
+                        .log_err();
+                    }
+                }
+
                 conn.exec_bound(
                     sql!(
                         DELETE FROM user_toolchains WHERE workspace_id = ?1;
@@ -1098,6 +1187,105 @@ impl WorkspaceDb {
         .await;
     }
 
+    /// Saves a window that was detached from the workspace `detached_window.parent_id`.
+    ///
+    /// Detached windows share their parent's project, so they are stored without paths and
+    /// are only restored together with their parent workspace.
+    pub(crate) async fn save_detached_window(&self, detached_window: SerializedDetachedWindow) {
+        log::debug!(
+            "Saving detached window {:?} of workspace {:?}",
+            detached_window.id,
+            detached_window.parent_id
+        );
+        self.write(move |conn| {
+            conn.with_savepoint("update_detached_window", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM pane_groups WHERE workspace_id = ?1;
+                    DELETE FROM panes WHERE workspace_id = ?1;))?(
+                    detached_window.id
+                )
+                .context("Clearing old panes")?;
+
+                conn.exec_bound(sql!(
+                    UPDATE workspaces
+                    SET
+                        window_state = ?2,
+                        window_x = ?3,
+                        window_y = ?4,
+                        window_width = ?5,
+                        window_height = ?6,
+                        display = ?7,
+                        timestamp = CURRENT_TIMESTAMP
+                    WHERE workspace_id = ?1
+                ))?((
+                    detached_window.id,
+                    detached_window.window_bounds,
+                    detached_window.display,
+                ))
+                .context("Updating detached window bounds")?;
+
+                conn.exec_bound(sql!(
+                    INSERT INTO detached_windows(workspace_id, parent_workspace_id)
+                    VALUES (?1, ?2)
+                    ON CONFLICT DO
+                    UPDATE SET parent_workspace_id = ?2
+                ))?((detached_window.id, detached_window.parent_id))
+                .context("Updating detached window")?;
+
+                Self::save_pane_group(
+                    conn,
+                    detached_window.id,
+                    &detached_window.center_group,
+                    None,
+                )
+                .context("save pane group in save detached window")?;
+
+                Ok(())
+            })
+            .log_err();
+        })
+        .await;
+    }
+
+    /// Returns the windows that were detached from the given workspace when it was last open.
+    pub(crate) fn detached_windows(&self, parent_id: WorkspaceId) -> Vec<SerializedDetachedWindow> {
+        let detached_windows: Vec<(WorkspaceId, Option<SerializedWindowBounds>, Option<Uuid>)> =
+            self.select_bound(sql!(
+                SELECT
+                    workspaces.workspace_id,
+                    window_state,
+                    window_x,
+                    window_y,
+                    window_width,
+                    window_height,
+                    display
+                FROM detached_windows
+                JOIN workspaces ON workspaces.workspace_id = detached_windows.workspace_id
+                WHERE detached_windows.parent_workspace_id = ?
+                ORDER BY workspaces.workspace_id
+            ))
+            .and_then(|mut select| select(parent_id))
+            .context("Getting detached windows")
+            .log_err()
+            .unwrap_or_default();
+
+        detached_windows
+            .into_iter()
+            .filter_map(|(id, window_bounds, display)| {
+                Some(SerializedDetachedWindow {
+                    id,
+                    parent_id,
+                    center_group: self
+                        .get_center_pane_group(id)
+                        .context("Getting detached window center group")
+                        .log_err()?,
+                    window_bounds,
+                    display,
+                })
+            })
+            .collect()
+    }
+
     pub(crate) async fn get_or_create_remote_connection(
         &self,
         options: RemoteConnectionOptions,
@@ -1317,6 +1505,38 @@ impl WorkspaceDb {
         Ok(result.into_iter().next().unwrap_or((None, None)))
     }
 
+    query! {
+        pub(crate) fn layouts(workspace_id: WorkspaceId) -> Result<Vec<(String, String)>> {
+            SELECT name, layout
+            FROM workspace_layouts
+            WHERE workspace_id = ?
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub(crate) fn layout(workspace_id: WorkspaceId, name: String) -> Result<Option<String>> {
+            SELECT layout
+            FROM workspace_layouts
+            WHERE workspace_id = ?1 AND name = ?2
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_layout(workspace_id: WorkspaceId, name: String, layout: String) -> Result<()> {
+            INSERT INTO workspace_layouts(workspace_id, name, layout)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET layout = ?3
+        }
+    }
+
+    query! {
+        pub(crate) async fn delete_layout(workspace_id: WorkspaceId, name: String) -> Result<()> {
+            DELETE FROM workspace_layouts
+            WHERE workspace_id = ?1 AND name = ?2
+        }
+    }
+
     query! {
         pub async fn delete_workspace_by_id(id: WorkspaceId) -> Result<()> {
             DELETE FROM workspaces
@@ -1911,6 +2131,7 @@ mod tests {
                 );
                 map
             },
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2032,6 +2253,7 @@ mod tests {
                 );
                 map
             },
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2066,6 +2288,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2082,6 +2305,53 @@ mod tests {
         assert!(empty_breakpoints.is_none());
     }
 
+    #[gpui::test]
+    async fn test_bookmarks() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_bookmarks").await;
+        let id = db.next_id().await.unwrap();
+
+        let path: Arc<Path> = Path::new("/tmp/test_bookmarks.rs").into();
+        let bookmarks = vec![
+            SerializedBookmark {
+                row: 3,
+                label: None,
+                line_text: "fn main() {".into(),
+            },
+            SerializedBookmark {
+                row: 10,
+                label: Some("cleanup".into()),
+                line_text: "drop(state);".into(),
+            },
+        ];
+
+        let mut workspace = SerializedWorkspace {
+            id,
+            paths: PathList::new(&["/tmp"]),
+            location: SerializedWorkspaceLocation::Local,
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            bookmarks: collections::BTreeMap::from_iter([(path.clone(), bookmarks.clone())]),
+            session_id: None,
+            window_id: None,
+            user_toolchains: Default::default(),
+        };
+
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.bookmarks.get(&path), Some(&bookmarks));
+
+        workspace.bookmarks = Default::default();
+        db.save_workspace(workspace).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert!(loaded.bookmarks.is_empty());
+    }
+
     #[gpui::test]
     async fn test_next_id_stability() {
         zlog::init_test();
@@ -2164,6 +2434,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2179,6 +2450,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2283,6 +2555,7 @@ mod tests {
             center_group,
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2304,6 +2577,110 @@ mod tests {
         assert_eq!(workspace, round_trip_workspace.unwrap());
     }
 
+    #[gpui::test]
+    async fn test_detached_windows() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_detached_windows").await;
+
+        let parent = default_workspace(&["/tmp"], &Default::default());
+        db.save_workspace(parent.clone()).await;
+
+        let detached_window = SerializedDetachedWindow {
+            id: db.next_id().await.unwrap(),
+            parent_id: parent.id,
+            center_group: group(
+                Axis::Horizontal,
+                vec![
+                    SerializedPaneGroup::Pane(SerializedPane::new(
+                        vec![SerializedItem::new("Terminal", 1, true, false)],
+                        false,
+                        0,
+                    )),
+                    SerializedPaneGroup::Pane(SerializedPane::new(
+                        vec![
+                            SerializedItem::new("Terminal", 2, false, false),
+                            SerializedItem::new("Terminal", 3, true, false),
+                        ],
+                        true,
+                        0,
+                    )),
+                ],
+            ),
+            window_bounds: Some(SerializedWindowBounds(WindowBounds::Windowed(Bounds {
+                origin: point(px(10.0), px(20.0)),
+                size: size(px(800.0), px(600.0)),
+            }))),
+            display: Some(Uuid::from_u128(1)),
+        };
+        db.save_detached_window(detached_window.clone()).await;
+        assert_eq!(
+            db.detached_windows(parent.id),
+            vec![detached_window.clone()]
+        );
+
+        // Detached windows have no paths of their own, so they are not recent workspaces.
+        let recent_workspace_ids = db
+            .recent_workspaces()
+            .unwrap()
+            .into_iter()
+            .map(|(id, ..)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(recent_workspace_ids, vec![parent.id]);
+
+        // Saving the parent again keeps its detached windows around.
+        db.save_workspace(parent.clone()).await;
+        assert_eq!(
+            db.detached_windows(parent.id),
+            vec![detached_window.clone()]
+        );
+
+        db.delete_workspace_by_id(detached_window.id).await.unwrap();
+        assert_eq!(db.detached_windows(parent.id), Vec::new());
+    }
+
+    #[gpui::test]
+    async fn test_workspace_layouts() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_workspace_layouts").await;
+
+        let workspace = default_workspace(&["/tmp"], &Default::default());
+        db.save_workspace(workspace.clone()).await;
+
+        db.save_layout(workspace.id, "review".into(), "{}".into())
+            .await
+            .unwrap();
+        db.save_layout(workspace.id, "debug".into(), "{\"center\":null}".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.layouts(workspace.id).unwrap(),
+            vec![
+                ("debug".to_string(), "{\"center\":null}".to_string()),
+                ("review".to_string(), "{}".to_string()),
+            ]
+        );
+
+        // Saving a layout with an existing name replaces it.
+        db.save_layout(workspace.id, "review".into(), "{\"left_dock\":null}".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.layout(workspace.id, "review".into()).unwrap(),
+            Some("{\"left_dock\":null}".to_string())
+        );
+
+        db.delete_layout(workspace.id, "review".into())
+            .await
+            .unwrap();
+        assert_eq!(db.layout(workspace.id, "review".into()).unwrap(), None);
+
+        // Layouts are removed along with their workspace.
+        db.delete_workspace_by_id(workspace.id).await.unwrap();
+        assert_eq!(db.layouts(workspace.id).unwrap(), Vec::new());
+    }
+
     #[gpui::test]
     async fn test_workspace_assignment() {
         zlog::init_test();
@@ -2317,6 +2694,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2335,6 +2713,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: Some(2),
             user_toolchains: Default::default(),
@@ -2374,6 +2753,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2415,6 +2795,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
             user_toolchains: Default::default(),
@@ -2430,6 +2811,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
             user_toolchains: Default::default(),
@@ -2445,6 +2827,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
             user_toolchains: Default::default(),
@@ -2460,6 +2843,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2486,6 +2870,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
             user_toolchains: Default::default(),
@@ -2498,6 +2883,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2552,6 +2938,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -2589,6 +2976,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
             user_toolchains: Default::default(),
         })
@@ -2687,6 +3075,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
             user_toolchains: Default::default(),
         })
@@ -13,7 +13,9 @@ use db::sqlez::{
 use gpui::{AsyncWindowContext, Entity, WeakEntity};
 
 use language::{Toolchain, ToolchainScope};
-use project::{Project, debugger::breakpoint_store::SourceBreakpoint};
+use project::{
+    Project, bookmark_store::SerializedBookmark, debugger::breakpoint_store::SourceBreakpoint,
+};
 use remote::RemoteConnectionOptions;
 use std::{
     collections::BTreeMap,
@@ -59,10 +61,21 @@ pub(crate) struct SerializedWorkspace {
     pub(crate) docks: DockStructure,
     pub(crate) session_id: Option<String>,
     pub(crate) breakpoints: BTreeMap<Arc<Path>, Vec<SourceBreakpoint>>,
+    pub(crate) bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
     pub(crate) user_toolchains: BTreeMap<ToolchainScope, IndexSet<Toolchain>>,
     pub(crate) window_id: Option<u64>,
 }
 
+/// A window that was detached from a workspace window, showing more items of its project.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct SerializedDetachedWindow {
+    pub(crate) id: WorkspaceId,
+    pub(crate) parent_id: WorkspaceId,
+    pub(crate) center_group: SerializedPaneGroup,
+    pub(crate) window_bounds: Option<SerializedWindowBounds>,
+    pub(crate) display: Option<Uuid>,
+}
+
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct DockStructure {
     pub(crate) left: DockData,
@@ -174,7 +187,7 @@ impl SerializedPaneGroup {
     ) -> Option<(
         Member,
         Option<Entity<Pane>>,
-        Vec<Option<Box<dyn ItemHandle>>>,
+        Vec<(ItemId, Option<Box<dyn ItemHandle>>)>,
     )> {
         match self {
             SerializedPaneGroup::Group {
@@ -269,7 +282,7 @@ impl SerializedPane {
         workspace_id: WorkspaceId,
         workspace: WeakEntity<Workspace>,
         cx: &mut AsyncWindowContext,
-    ) -> Result<Vec<Option<Box<dyn ItemHandle>>>> {
+    ) -> Result<Vec<(ItemId, Option<Box<dyn ItemHandle>>)>> {
         let mut item_tasks = Vec::new();
         let mut active_item_index = None;
         let mut preview_item_index = None;
@@ -295,9 +308,13 @@ impl SerializedPane {
         }
 
         let mut items = Vec::new();
-        for item_handle in futures::future::join_all(item_tasks).await {
+        for (item, item_handle) in self
+            .children
+            .iter()
+            .zip(futures::future::join_all(item_tasks).await)
+        {
             let item_handle = item_handle.log_err();
-            items.push(item_handle.clone());
+            items.push((item.item_id, item_handle.clone()));
 
             if let Some(item_handle) = item_handle {
                 pane.update_in(cx, |pane, window, cx| {
@@ -2,6 +2,7 @@ pub mod dock;
 pub mod history_manager;
 pub mod invalid_buffer_view;
 pub mod item;
+mod layout;
 mod modal_layer;
 pub mod notifications;
 pub mod pane;
@@ -53,6 +54,7 @@ pub use item::{
 };
 use itertools::Itertools;
 use language::{Buffer, LanguageRegistry, Rope, language_settings::all_language_settings};
+pub use layout::{DeleteLayout, LoadLayout, SaveLayout};
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
 use notifications::{
@@ -64,7 +66,10 @@ pub use pane_group::{
     ActivePaneDecorator, HANDLE_HITBOX_SIZE, Member, PaneAxis, PaneGroup, PaneRenderContext,
     SplitDirection,
 };
-use persistence::{DB, SerializedWindowBounds, model::SerializedWorkspace};
+use persistence::{
+    DB, SerializedWindowBounds,
+    model::{SerializedDetachedWindow, SerializedWorkspace},
+};
 pub use persistence::{
     DB as WORKSPACE_DB, WorkspaceDb, delete_unloaded_items,
     model::{ItemId, SerializedWorkspaceLocation},
@@ -73,6 +78,7 @@ use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
     WorktreeSettings,
+    bookmark_store::BookmarkStoreEvent,
     debugger::{breakpoint_store::BreakpointStoreEvent, session::ThreadStatus},
     toolchain_store::ToolchainStoreEvent,
 };
@@ -203,6 +209,10 @@ actions!(
         Feedback,
         /// Follows the next collaborator in the session.
         FollowNextCollaborator,
+        /// Moves the items of every window split off from this project back into the current window.
+        MergeAllWindows,
+        /// Moves the items of the current window back into the window it was split off from.
+        MergeWindow,
         /// Moves the focused panel to the next position.
         MoveFocusedPanelToNextPosition,
         /// Opens a new terminal in the center.
@@ -1137,6 +1147,9 @@ pub struct Workspace {
     active_call: Option<(Entity<ActiveCall>, Vec<Subscription>)>,
     leader_updates_tx: mpsc::UnboundedSender<(PeerId, proto::UpdateFollowers)>,
     database_id: Option<WorkspaceId>,
+    /// Set for windows torn out of another window of the same project,
+    /// which are persisted and restored alongside that window's workspace.
+    parent_workspace_id: Option<WorkspaceId>,
     app_state: Arc<AppState>,
     dispatching_keystrokes: Rc<RefCell<DispatchingKeystrokes>>,
     _subscriptions: Vec<Subscription>,
@@ -1284,6 +1297,14 @@ impl Workspace {
             },
         )
         .detach();
+        cx.subscribe_in(
+            &project.read(cx).bookmark_store(),
+            window,
+            |workspace, _, event, window, cx| match event {
+                BookmarkStoreEvent::BookmarksUpdated => workspace.serialize_workspace(window, cx),
+            },
+        )
+        .detach();
         if let Some(toolchain_store) = project.read(cx).toolchain_store() {
             cx.subscribe_in(
                 &toolchain_store,
@@ -1478,6 +1499,7 @@ impl Workspace {
             dirty_items: Default::default(),
             active_call,
             database_id: workspace_id,
+            parent_workspace_id: None,
             app_state,
             _observe_current_user,
             _apply_leader_updates,
@@ -1666,6 +1688,7 @@ impl Workspace {
             };
 
             notify_if_database_failed(window, cx);
+            let was_serialized = serialized_workspace.is_some();
             let opened_items = window
                 .update(cx, |_workspace, window, cx| {
                     open_items(serialized_workspace, project_paths, window, cx)
@@ -1673,6 +1696,10 @@ impl Workspace {
                 .await
                 .unwrap_or_default();
 
+            if was_serialized {
+                restore_detached_windows(workspace_id, project_handle, app_state, cx).await;
+            }
+
             window
                 .update(cx, |workspace, window, cx| {
                     window.activate_window();
@@ -3967,6 +3994,16 @@ impl Workspace {
             pane::Event::JoinAll => {
                 self.join_all_panes(window, cx);
             }
+            pane::Event::MoveItemToNewWindow { item_id } => {
+                self.move_item_to_new_window(pane.clone(), *item_id, None, window, cx)
+                    .detach_and_log_err(cx);
+            }
+            pane::Event::TabDroppedOutside {
+                item_id,
+                screen_position,
+            } => {
+                self.move_torn_out_tab(pane.clone(), *item_id, *screen_position, window, cx);
+            }
             pane::Event::Remove { focus_on_pane } => {
                 self.remove_pane(pane.clone(), focus_on_pane.clone(), window, cx);
             }
@@ -4143,6 +4180,224 @@ impl Workspace {
         cx.notify();
     }
 
+    /// Moves the given item into a new window that shares this workspace's project.
+    pub fn move_item_to_new_window(
+        &mut self,
+        pane: Entity<Pane>,
+        item_id: EntityId,
+        window_origin: Option<Point<Pixels>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<WindowHandle<Workspace>>> {
+        if !pane.read(cx).items().any(|item| item.item_id() == item_id) {
+            return Task::ready(Err(anyhow!("no item with id {item_id:?} in pane")));
+        }
+        let project = self.project.clone();
+        let app_state = self.app_state.clone();
+        let parent_workspace_id = self.parent_workspace_id.or(self.database_id);
+        let display = window.display(cx).and_then(|display| display.uuid().ok());
+        let current_bounds = window.bounds();
+        let window_bounds = Bounds::new(
+            window_origin.unwrap_or(current_bounds.origin + point(px(32.), px(32.))),
+            current_bounds.size,
+        );
+
+        cx.spawn_in(window, async move |this, cx| {
+            let database_id = if parent_workspace_id.is_some() {
+                DB.next_id().await.log_err()
+            } else {
+                None
+            };
+            let mut options = cx.update(|_, cx| (app_state.build_window_options)(display, cx))?;
+            options.window_bounds = Some(WindowBounds::Windowed(window_bounds));
+            let new_window = cx.update(|_, cx| {
+                cx.open_window(options, move |window, cx| {
+                    cx.new(|cx| {
+                        let mut workspace =
+                            Workspace::new(database_id, project, app_state, window, cx);
+                        workspace.parent_workspace_id = parent_workspace_id;
+                        workspace
+                    })
+                })
+            })??;
+
+            this.update_in(cx, |this, window, cx| {
+                this.move_items_to_window(vec![(pane, item_id)], new_window, window, cx)
+            })?
+            .await?;
+            Ok(new_window)
+        })
+    }
+
+    /// Moves the given items out of their panes and into the active pane of another
+    /// window's workspace.
+    fn move_items_to_window(
+        &mut self,
+        items: Vec<(Entity<Pane>, EntityId)>,
+        target: WindowHandle<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let active_item_id = self.active_item(cx).map(|item| item.item_id());
+        let items = items
+            .into_iter()
+            .filter_map(|(pane, item_id)| {
+                pane.update(cx, |pane, cx| {
+                    let item = pane
+                        .items()
+                        .find(|item| item.item_id() == item_id)?
+                        .boxed_clone();
+                    pane.remove_item(item_id, false, true, window, cx);
+                    Some(item)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn_in(window, async move |this, cx| {
+            target.update(cx, |workspace, window, cx| {
+                for item in items {
+                    let focus_item = Some(item.item_id()) == active_item_id;
+                    workspace.add_item_to_active_pane(item, None, focus_item, window, cx);
+                }
+                window.activate_window();
+            })?;
+            this.update_in(cx, |this, window, cx| {
+                this.close_if_detached_and_empty(window, cx)
+            })
+        })
+    }
+
+    /// Closes this window if it was detached from another one and has no items left.
+    fn close_if_detached_and_empty(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.parent_workspace_id.is_none()
+            || self.panes.iter().any(|pane| pane.read(cx).items_len() > 0)
+        {
+            return;
+        }
+        self.remove_from_session(window, cx).detach();
+        window.remove_window();
+    }
+
+    /// Returns the other windows showing this workspace's project, along with their
+    /// workspace ids and the ids of the workspaces they were detached from.
+    fn other_project_windows(
+        &self,
+        window: &Window,
+        cx: &App,
+    ) -> Vec<(
+        WindowHandle<Workspace>,
+        Option<WorkspaceId>,
+        Option<WorkspaceId>,
+    )> {
+        let current_window_id = window.window_handle().window_id();
+        cx.windows()
+            .into_iter()
+            .filter(|handle| handle.window_id() != current_window_id)
+            .filter_map(|handle| handle.downcast::<Workspace>())
+            .filter_map(|handle| {
+                let workspace = handle.read(cx).ok()?;
+                (workspace.project == self.project).then_some((
+                    handle,
+                    workspace.database_id,
+                    workspace.parent_workspace_id,
+                ))
+            })
+            .collect()
+    }
+
+    fn center_items(&self, cx: &App) -> Vec<(Entity<Pane>, EntityId)> {
+        self.center
+            .panes()
+            .into_iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .map(|item| (pane.clone(), item.item_id()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn merge_window(&mut self, _: &MergeWindow, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(parent_workspace_id) = self.parent_workspace_id else {
+            return;
+        };
+        let other_windows = self.other_project_windows(window, cx);
+        let target = other_windows
+            .iter()
+            .find(|(_, database_id, _)| *database_id == Some(parent_workspace_id))
+            .or_else(|| other_windows.iter().find(|(_, _, parent)| parent.is_none()))
+            .or_else(|| other_windows.first())
+            .map(|(handle, _, _)| *handle);
+        let Some(target) = target else {
+            return;
+        };
+        let items = self.center_items(cx);
+        self.move_items_to_window(items, target, window, cx)
+            .detach_and_log_err(cx);
+    }
+
+    fn merge_all_windows(
+        &mut self,
+        _: &MergeAllWindows,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(target) = window.window_handle().downcast::<Workspace>() else {
+            return;
+        };
+        let detached_windows = self
+            .other_project_windows(window, cx)
+            .into_iter()
+            .filter(|(_, _, parent)| parent.is_some())
+            .map(|(handle, _, _)| handle)
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |_, cx| {
+            for detached_window in detached_windows {
+                detached_window
+                    .update(cx, |workspace, window, cx| {
+                        let items = workspace.center_items(cx);
+                        workspace.move_items_to_window(items, target, window, cx)
+                    })?
+                    .await
+                    .log_err();
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Moves a tab that was dropped outside of this window into the project window under
+    /// the cursor, or into a new window if there is none.
+    fn move_torn_out_tab(
+        &mut self,
+        pane: Entity<Pane>,
+        item_id: EntityId,
+        screen_position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let target = self
+            .other_project_windows(window, cx)
+            .into_iter()
+            .map(|(handle, _, _)| handle)
+            .find(|handle| {
+                handle
+                    .update(cx, |_, window, _| {
+                        window.bounds().contains(&screen_position)
+                    })
+                    .unwrap_or(false)
+            });
+
+        if let Some(target) = target {
+            self.move_items_to_window(vec![(pane, item_id)], target, window, cx)
+                .detach_and_log_err(cx);
+        } else if self.parent_workspace_id.is_none() || self.center_items(cx).len() > 1 {
+            self.move_item_to_new_window(pane, item_id, Some(screen_position), window, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
     fn remove_pane(
         &mut self,
         pane: Entity<Pane>,
@@ -5165,6 +5420,14 @@ impl Workspace {
 
     fn remove_from_session(&mut self, window: &mut Window, cx: &mut App) -> Task<()> {
         self.session_id.take();
+        if self.parent_workspace_id.is_some()
+            && let Some(database_id) = self.database_id
+        {
+            // Detached windows are only restored alongside their parent, so a closed one is forgotten.
+            return window.spawn(cx, async move |_| {
+                DB.delete_workspace_by_id(database_id).await.log_err();
+            });
+        }
         self.serialize_workspace_internal(window, cx)
     }
 
@@ -5319,6 +5582,19 @@ impl Workspace {
             }
         }
 
+        if let Some(parent_id) = self.parent_workspace_id {
+            let detached_window = SerializedDetachedWindow {
+                id: database_id,
+                parent_id,
+                center_group: build_serialized_pane_group(&self.center.root, window, cx),
+                window_bounds: Some(SerializedWindowBounds(window.window_bounds())),
+                display: window.display(cx).and_then(|display| display.uuid().ok()),
+            };
+            return window.spawn(cx, async move |_| {
+                persistence::DB.save_detached_window(detached_window).await;
+            });
+        }
+
         match self.serialize_workspace_location(cx) {
             WorkspaceLocation::Location(location, paths) => {
                 let breakpoints = self.project.update(cx, |project, cx| {
@@ -5327,6 +5603,12 @@ impl Workspace {
                         .read(cx)
                         .all_source_breakpoints(cx)
                 });
+                let bookmarks = self
+                    .project
+                    .read(cx)
+                    .bookmark_store()
+                    .read(cx)
+                    .all_serialized_bookmarks(cx);
                 let user_toolchains = self
                     .project
                     .read(cx)
@@ -5348,6 +5630,7 @@ impl Workspace {
                     centered_layout: self.centered_layout,
                     session_id: self.session_id.clone(),
                     breakpoints,
+                    bookmarks,
                     window_id: Some(window.window_handle().window_id().as_u64()),
                     user_toolchains,
                 };
@@ -5466,9 +5749,14 @@ impl Workspace {
 
             let mut items_by_project_path = HashMap::default();
             let mut item_ids_by_kind = HashMap::default();
+            let mut restored_item_ids = HashMap::default();
             let mut all_deserialized_items = Vec::default();
             cx.update(|_, cx| {
-                for item in center_items.unwrap_or_default().into_iter().flatten() {
+                for (saved_item_id, item) in center_items.unwrap_or_default() {
+                    let Some(item) = item else {
+                        continue;
+                    };
+                    restored_item_ids.insert(saved_item_id, item.item_id().as_u64() as ItemId);
                     if let Some(serializable_item_handle) = item.to_serializable_item_handle(cx) {
                         item_ids_by_kind
                             .entry(serializable_item_handle.serialized_item_kind())
@@ -5535,6 +5823,14 @@ impl Workspace {
                 })?
                 .await;
 
+            let _ = project
+                .update(cx, |project, cx| {
+                    project.bookmark_store().update(cx, |bookmark_store, cx| {
+                        bookmark_store.with_serialized_bookmarks(serialized_workspace.bookmarks, cx)
+                    })
+                })?
+                .await;
+
             // Clean up all the items that have _not_ been loaded. Our ItemIds aren't stable. That means
             // after loading the items, we might have different items and in order to avoid
             // the database filling up, we delete items that haven't been loaded now.
@@ -5557,6 +5853,9 @@ impl Workspace {
             })?;
 
             futures::future::join_all(clean_up_tasks).await;
+            layout::remap_saved_layouts(serialized_workspace.id, &restored_item_ids)
+                .await
+                .log_err();
 
             workspace
                 .update_in(cx, |workspace, window, cx| {
@@ -5572,6 +5871,71 @@ impl Workspace {
         })
     }
 
+    fn load_detached_window(
+        &mut self,
+        center_group: SerializedPaneGroup,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(database_id) = self.database_id else {
+            return Task::ready(Ok(()));
+        };
+        cx.spawn_in(window, async move |workspace, cx| {
+            let project = workspace.read_with(cx, |workspace, _| workspace.project().clone())?;
+            let Some((group, active_pane, items)) = center_group
+                .deserialize(&project, database_id, workspace.clone(), cx)
+                .await
+            else {
+                return Ok(());
+            };
+
+            let mut restored_item_ids = HashMap::default();
+            let clean_up_tasks = workspace.update_in(cx, |workspace, window, cx| {
+                workspace.remove_panes(workspace.center.root.clone(), window, cx);
+                workspace.center = PaneGroup::with_root(group);
+                let active_pane = active_pane.unwrap_or_else(|| workspace.center.first_pane());
+                workspace.set_active_pane(&active_pane, window, cx);
+                cx.notify();
+
+                let mut item_ids_by_kind = HashMap::default();
+                for (saved_item_id, item) in items {
+                    let Some(item) = item else {
+                        continue;
+                    };
+                    restored_item_ids.insert(saved_item_id, item.item_id().as_u64() as ItemId);
+                    if let Some(item) = item.to_serializable_item_handle(cx) {
+                        item_ids_by_kind
+                            .entry(item.serialized_item_kind())
+                            .or_insert(Vec::new())
+                            .push(item.item_id().as_u64() as ItemId);
+                    }
+                }
+                item_ids_by_kind
+                    .into_iter()
+                    .map(|(item_kind, loaded_items)| {
+                        SerializableItemRegistry::cleanup(
+                            item_kind,
+                            database_id,
+                            loaded_items,
+                            window,
+                            cx,
+                        )
+                        .log_err()
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            futures::future::join_all(clean_up_tasks).await;
+            layout::remap_saved_layouts(database_id, &restored_item_ids)
+                .await
+                .log_err();
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.serialize_workspace_internal(window, cx).detach();
+                workspace.update_window_edited(window, cx);
+            })
+        })
+    }
+
     fn actions(&self, div: Div, window: &mut Window, cx: &mut Context<Self>) -> Div {
         self.add_workspace_actions_listeners(div, window, cx)
             .on_action(cx.listener(
@@ -5588,6 +5952,11 @@ impl Workspace {
             .on_action(cx.listener(Self::add_folder_to_project))
             .on_action(cx.listener(Self::follow_next_collaborator))
             .on_action(cx.listener(Self::close_window))
+            .on_action(cx.listener(Self::merge_window))
+            .on_action(cx.listener(Self::merge_all_windows))
+            .on_action(cx.listener(Self::handle_save_layout))
+            .on_action(cx.listener(Self::handle_load_layout))
+            .on_action(cx.listener(Self::handle_delete_layout))
             .on_action(cx.listener(Self::activate_pane_at_index))
             .on_action(cx.listener(Self::move_item_to_pane_at_index))
             .on_action(cx.listener(Self::move_focused_panel_to_next_position))
@@ -6094,6 +6463,47 @@ fn window_bounds_env_override() -> Option<Bounds<Pixels>> {
         })
 }
 
+/// Reopens the windows that were detached from the given workspace when it was last open.
+async fn restore_detached_windows(
+    parent_workspace_id: WorkspaceId,
+    project: Entity<Project>,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncApp,
+) {
+    for detached_window in DB.detached_windows(parent_workspace_id) {
+        let SerializedDetachedWindow {
+            id,
+            center_group,
+            window_bounds,
+            display,
+            ..
+        } = detached_window;
+        let restored = async {
+            let mut options = cx.update(|cx| (app_state.build_window_options)(display, cx))?;
+            options.window_bounds = window_bounds.map(|bounds| bounds.0);
+            let window = cx.open_window(options, {
+                let project = project.clone();
+                let app_state = app_state.clone();
+                move |window, cx| {
+                    cx.new(|cx| {
+                        let mut workspace =
+                            Workspace::new(Some(id), project, app_state, window, cx);
+                        workspace.parent_workspace_id = Some(parent_workspace_id);
+                        workspace
+                    })
+                }
+            })?;
+            window
+                .update(cx, |workspace, window, cx| {
+                    workspace.load_detached_window(center_group, window, cx)
+                })?
+                .await
+        }
+        .await;
+        restored.log_err();
+    }
+}
+
 fn open_items(
     serialized_workspace: Option<SerializedWorkspace>,
     mut project_paths_to_open: Vec<(PathBuf, Option<ProjectPath>)>,
@@ -8399,6 +8809,85 @@ mod tests {
         assert!(!task.await.unwrap());
     }
 
+    #[gpui::test]
+    async fn test_detach_and_reattach_tab(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let database_id = DB.next_id().await.unwrap();
+        let (workspace, cx) = cx.add_window_view(|window, cx| {
+            let mut workspace = Workspace::test_new(project.clone(), window, cx);
+            workspace.database_id = Some(database_id);
+            workspace
+        });
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+        let item1 = cx.new(TestItem::new);
+        let item2 = cx.new(TestItem::new);
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_item_to_active_pane(Box::new(item1.clone()), None, true, window, cx);
+            workspace.add_item_to_active_pane(Box::new(item2.clone()), None, true, window, cx);
+        });
+
+        // Dropping a tab outside of the window moves the item itself into a new window.
+        pane.update(cx, |_, cx| {
+            cx.emit(pane::Event::TabDroppedOutside {
+                item_id: item2.item_id(),
+                screen_position: point(px(2000.), px(2000.)),
+            })
+        });
+        cx.run_until_parked();
+
+        let detached_window = cx.update(|_, cx| {
+            cx.windows()
+                .into_iter()
+                .filter_map(|window| window.downcast::<Workspace>())
+                .find(|window| window.entity(cx).unwrap() != workspace)
+                .unwrap()
+        });
+        let detached_items = |cx: &mut VisualTestContext| {
+            detached_window
+                .read_with(cx, |workspace, cx| {
+                    workspace
+                        .active_pane()
+                        .read(cx)
+                        .items()
+                        .map(|item| item.item_id())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+        assert_eq!(detached_items(cx), vec![item2.item_id()]);
+        pane.read_with(cx, |pane, _| {
+            assert_eq!(
+                pane.items().map(|item| item.item_id()).collect::<Vec<_>>(),
+                vec![item1.item_id()]
+            );
+        });
+        detached_window
+            .read_with(cx, |workspace, _| {
+                assert_eq!(workspace.parent_workspace_id, Some(database_id));
+            })
+            .unwrap();
+
+        // Merging the detached window moves the item back and closes that window.
+        detached_window
+            .update(cx, |workspace, window, cx| {
+                workspace.merge_window(&MergeWindow, window, cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        pane.read_with(cx, |pane, _| {
+            assert_eq!(
+                pane.items().map(|item| item.item_id()).collect::<Vec<_>>(),
+                vec![item1.item_id(), item2.item_id()]
+            );
+        });
+        assert!(detached_window.read_with(cx, |_, _| ()).is_err());
+    }
+
     #[gpui::test]
     async fn test_close_window_with_serializable_items(cx: &mut TestAppContext) {
         init_test(cx);
@@ -592,6 +592,7 @@ pub fn main() {
         app_state.languages.set_theme(cx.theme().clone());
         editor::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
 
@@ -606,6 +607,8 @@ pub fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        test_explorer::init(cx);
+        bookmarks::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -614,6 +617,7 @@ pub fn main() {
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        layout_selector::init(cx);
         line_ending_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
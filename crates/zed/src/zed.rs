@@ -574,6 +574,8 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let test_explorer = test_explorer::TestExplorer::load(workspace_handle.clone(), cx.clone());
+        let bookmarks_panel = bookmarks::BookmarksPanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         let (
@@ -583,6 +585,8 @@ fn initialize_panels(
             git_panel,
             channels_panel,
             notification_panel,
+            test_explorer,
+            bookmarks_panel,
             debug_panel,
         ) = futures::try_join!(
             project_panel,
@@ -591,6 +595,8 @@ fn initialize_panels(
             terminal_panel,
             channels_panel,
             notification_panel,
+            test_explorer,
+            bookmarks_panel,
             debug_panel,
         )?;
 
@@ -601,6 +607,8 @@ fn initialize_panels(
             workspace.add_panel(git_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
+            workspace.add_panel(test_explorer, window, cx);
+            workspace.add_panel(bookmarks_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
         })?;
 
@@ -4498,6 +4506,7 @@ mod tests {
                 "assistant",
                 "assistant2",
                 "auto_update",
+                "bookmarks",
                 "branches",
                 "buffer_search",
                 "channel_modal",
@@ -4521,11 +4530,13 @@ mod tests {
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hex_editor",
                 "icon_theme_selector",
                 "journal",
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
+                "layout_selector",
                 "line_ending",
                 "lsp_tool",
                 "markdown",
@@ -4555,6 +4566,7 @@ mod tests {
                 "task",
                 "terminal",
                 "terminal_panel",
+                "test_explorer",
                 "theme_selector",
                 "toast",
                 "toolchain",
@@ -4698,6 +4710,8 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            test_explorer::init(cx);
+            bookmarks::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
@@ -4706,6 +4720,7 @@ mod tests {
                 cx,
             );
             image_viewer::init(cx);
+            hex_editor::init(cx);
             language_model::init(app_state.client.clone(), cx);
             language_models::init(app_state.user_store.clone(), app_state.client.clone(), cx);
             web_search::init(cx);
@@ -168,6 +168,18 @@ pub mod workspace {
     );
 }
 
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Reopens the active file in the hex editor.
+            ReopenAsHex,
+        ]
+    );
+}
+
 pub mod git {
     use gpui::actions;
 
@@ -257,6 +269,17 @@ pub mod icon_theme_selector {
     }
 }
 
+pub mod layout_selector {
+    use gpui::Action;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    /// Opens the layout selector to load, save or delete named pane and dock layouts.
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+    #[action(namespace = layout_selector)]
+    pub struct Toggle;
+}
+
 pub mod settings_profile_selector {
     use gpui::Action;
     use schemars::JsonSchema;